@@ -38,26 +38,37 @@
 //! copy of each amplitude will ever hold any data).
 use dyn_clone::DynClone;
 use itertools::Itertools;
-use nalgebra::Complex;
+use nalgebra::{Complex, DMatrix};
 use parking_lot::RwLock;
 use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashSet,
+    any::Any,
+    collections::{HashMap, HashSet},
     fmt::{Debug, Display},
     ops::{Add, Mul},
     sync::Arc,
 };
-use tracing::{debug, info};
+use tracing::{debug, debug_span, info, trace_span};
 
 use crate::{
+    cache::PrecalculationCache,
     convert,
     dataset::{Dataset, Event},
     errors::RustitudeError,
+    four_momentum::FourMomentum,
+    frames::{self, Frame},
+    kde::{Bandwidth, Kde},
+    math,
+    profiling::{self, Stage, Timer},
+    serialization::{AmpTree, NodeSpec},
     Field,
 };
 
 /// A single parameter within an [`Amplitude`].
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct Parameter<F: Field> {
     /// Name of the parent [`Amplitude`] containing this parameter.
     pub amplitude: String,
@@ -74,8 +85,72 @@ pub struct Parameter<F: Field> {
     pub initial: F,
     /// Bounds for the given parameter (defaults to +/- infinity). This is mostly optional and
     /// isn't used in any Rust code asside from being able to get and set it.
+    #[serde(with = "crate::serialization::bounds")]
     pub bounds: (F, F),
+    /// An optional [`Transform`] between the value seen by a minimizer and the physical value
+    /// seen by [`Node::calculate`] (see [`Model::set_transform`]).
+    #[serde(default)]
+    pub transform: Option<Transform<F>>,
+}
+/// A parsed pattern over `amplitude::parameter` names (see [`Parameter::matches`]).
+enum NamePattern {
+    Glob(Vec<char>),
+    Regex(Regex),
+}
+
+impl NamePattern {
+    /// Parses a pattern, which is a regular expression if it is wrapped in slashes and a glob
+    /// pattern otherwise.
+    fn parse(pattern: &str) -> Result<Self, RustitudeError> {
+        pattern
+            .strip_prefix('/')
+            .and_then(|inner| inner.strip_suffix('/'))
+            .map_or_else(
+                || Ok(Self::Glob(pattern.chars().collect())),
+                |inner| {
+                    Regex::new(inner)
+                        .map(Self::Regex)
+                        .map_err(|err| RustitudeError::ParseError(err.to_string()))
+                },
+            )
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Glob(pattern) => glob_match(pattern, &name.chars().collect::<Vec<_>>()),
+            Self::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+/// Matches `text` against a glob `pattern` with `*` and `?` wildcards, backtracking to the most
+/// recent `*` on a mismatch.
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
 }
+
 impl<F: Field> Parameter<F> {
     /// Creates a new [`Parameter`] within an [`Amplitude`] using the name of the [`Amplitude`],
     /// the name of the [`Parameter`], and the index of the parameter within the [`Model`].
@@ -90,6 +165,7 @@ impl<F: Field> Parameter<F> {
             fixed_index: None,
             initial: F::one(),
             bounds: (F::neg_infinity(), F::infinity()),
+            transform: None,
         }
     }
 
@@ -102,6 +178,130 @@ impl<F: Field> Parameter<F> {
     pub const fn is_fixed(&self) -> bool {
         self.index.is_none()
     }
+
+    /// Returns `true` if the full name of the [`Parameter`], `amplitude::parameter`, matches a
+    /// pattern.
+    ///
+    /// A pattern wrapped in slashes, such as `/^a2_1700::bin [0-9]+ re$/`, is a regular
+    /// expression (see [`regex`]) which matches if it is found anywhere in the name, and an
+    /// invalid regular expression matches nothing. Any other pattern is a glob pattern which
+    /// must match the whole name, in which `*` matches any sequence of characters (including
+    /// none) and `?` matches any single character.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use rustitude_core::prelude::*;
+    /// let parameter: Parameter<f64> = Parameter::new("a2_1700", "bin 3 re", 0);
+    /// assert!(parameter.matches("a2_1700::*"));
+    /// assert!(parameter.matches("a2_*::bin ? re"));
+    /// assert!(!parameter.matches("a2_1700::*im"));
+    /// assert!(parameter.matches("/^a2_1700::bin [0-9]+ (re|im)$/"));
+    /// assert!(!parameter.matches("/bin [4-9]/"));
+    /// ```
+    pub fn matches(&self, pattern: &str) -> bool {
+        NamePattern::parse(pattern).is_ok_and(|pattern| self.matches_pattern(&pattern))
+    }
+
+    fn matches_pattern(&self, pattern: &NamePattern) -> bool {
+        pattern.matches(&format!("{}::{}", self.amplitude, self.name))
+    }
+
+    /// Returns the physical value of the [`Parameter`] given a list of free parameters in the
+    /// internal space of any [`Transform`]. Fixed parameters return their fixed value.
+    pub fn value(&self, parameters: &[F]) -> F {
+        self.index
+            .map_or(self.initial, |i| self.physical(parameters[i]))
+    }
+
+    /// Maps an internal value to the physical value of the [`Parameter`].
+    pub fn physical(&self, internal: F) -> F {
+        self.transform
+            .map_or(internal, |transform| transform.physical(internal))
+    }
+
+    /// Maps a physical value of the [`Parameter`] to its internal value.
+    pub fn internal(&self, physical: F) -> F {
+        self.transform
+            .map_or(physical, |transform| transform.internal(physical))
+    }
+
+    /// Returns the derivative of the physical value with respect to the internal value.
+    pub fn derivative(&self, internal: F) -> F {
+        self.transform
+            .map_or_else(F::one, |transform| transform.derivative(internal))
+    }
+}
+
+/// A reparameterization of a [`Parameter`] (see [`Model::set_transform`]).
+///
+/// A minimizer works with an unconstrained internal value $`u`$ while [`Node::calculate`] sees
+/// the physical value $`x(u)`$.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum Transform<F: Field> {
+    /// $`x = e^u`$, which keeps the physical value positive.
+    Log,
+    /// $`x = u^2`$, which keeps the physical value non-negative. Physical values map to the
+    /// non-negative root.
+    Sqrt,
+    /// $`x = a + (b - a) / (1 + e^{-u})`$, which keeps the physical value within $`(a, b)`$.
+    Bounded(F, F),
+}
+
+impl<F: Field> Transform<F> {
+    /// Maps an internal value to a physical value.
+    pub fn physical(&self, internal: F) -> F {
+        match *self {
+            Self::Log => internal.exp(),
+            Self::Sqrt => internal.powi(2),
+            Self::Bounded(a, b) => a + (b - a) / (F::one() + (-internal).exp()),
+        }
+    }
+
+    /// Maps a physical value to an internal value. Values outside of the domain of the
+    /// [`Transform`] give non-finite results.
+    pub fn internal(&self, physical: F) -> F {
+        match *self {
+            Self::Log => physical.ln(),
+            Self::Sqrt => physical.sqrt(),
+            Self::Bounded(a, b) => ((physical - a) / (b - physical)).ln(),
+        }
+    }
+
+    /// Returns the derivative $`dx/du`$ of the physical value with respect to the internal
+    /// value.
+    pub fn derivative(&self, internal: F) -> F {
+        match *self {
+            Self::Log => internal.exp(),
+            Self::Sqrt => convert!(2, F) * internal,
+            Self::Bounded(a, b) => {
+                let x = self.physical(internal);
+                (x - a) * (b - x) / (b - a)
+            }
+        }
+    }
+
+    /// Maps physical bounds to bounds on the internal value. [`Transform::Log`] maps each bound
+    /// through the logarithm, [`Transform::Sqrt`] keeps $`|u| \le \sqrt{b}`$, and
+    /// [`Transform::Bounded`] is unbounded.
+    pub fn bounds(&self, bounds: (F, F)) -> (F, F) {
+        let ln = |bound: F| {
+            if bound > F::zero() {
+                bound.ln()
+            } else {
+                F::neg_infinity()
+            }
+        };
+        match *self {
+            Self::Log => (ln(bounds.0), ln(bounds.1)),
+            Self::Sqrt => {
+                let upper = bounds.1.max(F::zero()).sqrt();
+                (-upper, upper)
+            }
+            Self::Bounded(_, _) => (F::neg_infinity(), F::infinity()),
+        }
+    }
 }
 
 impl<F: Field> Debug for Parameter<F> {
@@ -260,6 +460,35 @@ pub trait Node<F: Field>: Sync + Send + DynClone {
         Ok(())
     }
 
+    /// A method which lists the [`SharedQuantity`]s this [`Node`] reads during precalculation.
+    ///
+    /// [`Model::load`] computes every requested [`SharedQuantity`] once per [`Dataset`], even if
+    /// several [`Node`]s request the same key, and passes the results to
+    /// [`Node::precalculate_shared`]. The default requests nothing.
+    fn requirements(&self) -> Vec<SharedQuantity<F>> {
+        vec![]
+    }
+
+    /// A version of [`Node::precalculate`] which is given the [`SharedData`] computed from the
+    /// [`Node::requirements`] of every [`Node`] in the [`Model`].
+    ///
+    /// [`Node`]s which declare [`Node::requirements`] should override this method and usually
+    /// implement [`Node::precalculate`] by computing their own requirements with
+    /// [`SharedData::compute`]. The default ignores the [`SharedData`] and calls
+    /// [`Node::precalculate`].
+    ///
+    /// # Errors
+    ///
+    /// This function should be written to return a [`RustitudeError`] if any part of the
+    /// calculation fails or if a required [`SharedQuantity`] is missing.
+    fn precalculate_shared(
+        &mut self,
+        dataset: &Dataset<F>,
+        _shared: &SharedData,
+    ) -> Result<(), RustitudeError> {
+        self.precalculate(dataset)
+    }
+
     /// A method which runs every time the amplitude is evaluated and produces a [`Complex`].
     ///
     /// Because this method is run on every evaluation, it should be as lean as possible.
@@ -275,6 +504,61 @@ pub trait Node<F: Field>: Sync + Send + DynClone {
     /// calculation fails.
     fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError>;
 
+    /// A method which runs [`Node::calculate`] over a contiguous batch of [`Event`]s, writing one
+    /// value per [`Event`] into `out` (which has the same length as `events`).
+    ///
+    /// The default implementation calls [`Node::calculate`] for each [`Event`]. [`Node`]s which
+    /// can be evaluated in a tight loop, such as lookups of precalculated values (see
+    /// [`gather_batch`]), should override it so the loop can be vectorized. Batches are only
+    /// used by the [`Manager`](crate::manager::Manager) when the `simd` feature is enabled.
+    ///
+    /// # Errors
+    ///
+    /// This function should be written to return a [`RustitudeError`] if any part of the
+    /// calculation fails.
+    fn calculate_batch(
+        &self,
+        parameters: &[F],
+        events: &[Event<F>],
+        out: &mut [Complex<F>],
+    ) -> Result<(), RustitudeError> {
+        for (value, event) in out.iter_mut().zip(events) {
+            *value = self.calculate(parameters, event)?;
+        }
+        Ok(())
+    }
+
+    /// A method which computes the derivative of [`Node::calculate`] with respect to each of the
+    /// [`Node`]'s parameters.
+    ///
+    /// The returned [`Vec`] must have the same length and order as [`Node::parameters`]. The
+    /// default implementation uses central finite differences, which costs two calls to
+    /// [`Node::calculate`] per parameter, so [`Node`]s with simple analytic derivatives should
+    /// override it.
+    ///
+    /// # Errors
+    ///
+    /// This function should be written to return a [`RustitudeError`] if any part of the
+    /// calculation fails.
+    fn calculate_gradient(
+        &self,
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        let mut shifted = parameters.to_vec();
+        let mut gradient = Vec::with_capacity(parameters.len());
+        for (i, &value) in parameters.iter().enumerate() {
+            let h = F::cbrt(F::epsilon()) * (F::abs(value) + F::one());
+            shifted[i] = value + h;
+            let plus = self.calculate(&shifted, event)?;
+            shifted[i] = value - h;
+            let minus = self.calculate(&shifted, event)?;
+            shifted[i] = value;
+            gradient.push((plus - minus) / (convert!(2, F) * h));
+        }
+        Ok(gradient)
+    }
+
     /// A method which specifies the number and order of parameters used by the [`Node`].
     ///
     /// This method tells the [`crate::manager::Manager`] how to assign its input [`Vec`] of parameter values to
@@ -285,6 +569,26 @@ pub trait Node<F: Field>: Sync + Send + DynClone {
         vec![]
     }
 
+    /// A method which gives the default initial value of each parameter, in the same order as
+    /// [`Node::parameters`].
+    ///
+    /// [`Model::new`] uses these as the [`Parameter::initial`] values, which can still be changed
+    /// with [`Model::set_initial`]. Parameters without an entry (including every parameter if
+    /// this method returns an empty [`Vec`], the default) start at one.
+    fn initial_values(&self) -> Vec<F> {
+        vec![]
+    }
+
+    /// A method which gives the default bounds of each parameter, in the same order as
+    /// [`Node::parameters`].
+    ///
+    /// [`Model::new`] uses these as the [`Parameter::bounds`], which can still be changed with
+    /// [`Model::set_bounds`]. Parameters without an entry (including every parameter if this
+    /// method returns an empty [`Vec`], the default) are unbounded.
+    fn parameter_bounds(&self) -> Vec<(F, F)> {
+        vec![]
+    }
+
     /// A convenience method for turning [`Node`]s into [`Amplitude`]s.
     fn into_amplitude(self, name: &str) -> Amplitude<F>
     where
@@ -309,12 +613,229 @@ pub trait Node<F: Field>: Sync + Send + DynClone {
     fn is_python_node(&self) -> bool {
         false
     }
+
+    /// A flag which says if the result of [`Node::calculate`] (and [`Node::calculate_gradient`])
+    /// depends only on the parameters and not on the [`Event`].
+    ///
+    /// [`Node`]s which return `true` are calculated once per evaluation of a
+    /// [`Manager`](crate::manager::Manager) rather than once per [`Event`] (see
+    /// [`Model::precompute`]), and their [`Node::calculate`] method may be given any [`Event`] of
+    /// the [`Dataset`]. The default is `false`, which is always safe.
+    fn is_event_independent(&self) -> bool {
+        false
+    }
+
+    /// A method which describes how to rebuild the [`Node`] through a
+    /// [`NodeRegistry`](crate::serialization::NodeRegistry). The returned [`NodeSpec`] should
+    /// contain a registered kind and all of the arguments needed to construct the [`Node`] again
+    /// (but not any precalculated data). [`Node`]s which return [`None`] (the default) cannot be
+    /// serialized as part of a [`Model`].
+    fn spec(&self) -> Option<NodeSpec> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(<F> Node<F>);
 
+/// Copies the value of `data` at the index of each [`Event`] into `out`.
+///
+/// This is intended for [`Node::calculate_batch`] implementations of [`Node`]s which store one
+/// precalculated value per [`Event`]. If the [`Event`]s have consecutive indices, as they do in a
+/// chunk of a [`Dataset`], this is a single contiguous copy.
+///
+/// # Panics
+///
+/// This function panics if `out` and `events` have different lengths or if any [`Event`] index
+/// is out of range for `data`.
+pub fn gather_batch<F: Field, T: Copy>(data: &[T], events: &[Event<F>], out: &mut [T]) {
+    let start = events.first().map_or(0, |event| event.index);
+    if events
+        .iter()
+        .enumerate()
+        .all(|(i, event)| event.index == start + i)
+    {
+        out.copy_from_slice(&data[start..start + events.len()]);
+    } else {
+        for (value, event) in out.iter_mut().zip(events) {
+            *value = data[event.index];
+        }
+    }
+}
+
+/// The type-erased function which computes a [`SharedQuantity`].
+type SharedCompute<F> = dyn Fn(&Dataset<F>, &SharedData) -> Result<Arc<dyn Any + Send + Sync>, RustitudeError>
+    + Send
+    + Sync;
+
+/// A named quantity which is computed once from a [`Dataset`] and shared by every [`Node`] which
+/// requests it through [`Node::requirements`].
+///
+/// The key identifies the quantity, so two [`Node`]s which request the same key (for instance,
+/// the decay angles of the same particle in the same frame) share a single computation. A
+/// [`SharedQuantity`] may depend on other [`SharedQuantity`]s, which are computed first and can
+/// be read from the [`SharedData`] passed to its function.
+///
+/// # Example:
+///
+/// ```
+/// use rustitude_core::prelude::*;
+/// use rustitude_core::utils::generate_test_dataset_f64;
+/// # fn main() -> Result<(), RustitudeError> {
+/// let masses = SharedQuantity::new("resonance_mass", |dataset: &Dataset<f64>, _| {
+///     Ok(dataset
+///         .events
+///         .iter()
+///         .map(|event| (event.daughter_p4s[0] + event.daughter_p4s[1]).m())
+///         .collect::<Vec<f64>>())
+/// });
+/// let squares = SharedQuantity::new("resonance_mass2", |_, shared| {
+///     let masses = shared.require::<Vec<f64>>("resonance_mass")?;
+///     Ok(masses.iter().map(|m| m * m).collect::<Vec<f64>>())
+/// })
+/// .with_dependencies(vec![masses]);
+/// let dataset = generate_test_dataset_f64();
+/// let shared = SharedData::compute(&[squares], &dataset)?;
+/// assert_eq!(shared.len(), 2);
+/// assert_eq!(shared.require::<Vec<f64>>("resonance_mass2")?.len(), dataset.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SharedQuantity<F: Field + 'static> {
+    key: String,
+    dependencies: Vec<Self>,
+    compute: Arc<SharedCompute<F>>,
+}
+
+impl<F: Field> Debug for SharedQuantity<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedQuantity")
+            .field("key", &self.key)
+            .field("dependencies", &self.dependencies)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Field> SharedQuantity<F> {
+    /// Creates a new [`SharedQuantity`] from a key and a function which computes its value.
+    pub fn new<T: Send + Sync + 'static>(
+        key: &str,
+        compute: impl Fn(&Dataset<F>, &SharedData) -> Result<T, RustitudeError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            key: key.to_string(),
+            dependencies: Vec::new(),
+            compute: Arc::new(move |dataset, shared| {
+                Ok(Arc::new(compute(dataset, shared)?) as Arc<dyn Any + Send + Sync>)
+            }),
+        }
+    }
+
+    /// Sets the [`SharedQuantity`]s which must be computed before this one.
+    #[must_use]
+    pub fn with_dependencies(mut self, dependencies: Vec<Self>) -> Self {
+        self.dependencies = dependencies;
+        self
+    }
+
+    /// Returns the key of the [`SharedQuantity`].
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+/// The computed values of a set of [`SharedQuantity`]s, indexed by key.
+#[derive(Clone, Default)]
+pub struct SharedData {
+    values: HashMap<String, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Debug for SharedData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.values.keys()).finish()
+    }
+}
+
+impl SharedData {
+    /// Computes each of the given [`SharedQuantity`]s (and their dependencies) over a
+    /// [`Dataset`]. Quantities with the same key are only computed once.
+    ///
+    /// # Errors
+    ///
+    /// This method will return any error produced while computing a [`SharedQuantity`].
+    pub fn compute<F: Field>(
+        quantities: &[SharedQuantity<F>],
+        dataset: &Dataset<F>,
+    ) -> Result<Self, RustitudeError> {
+        let mut shared = Self::default();
+        shared.extend(quantities, dataset)?;
+        Ok(shared)
+    }
+
+    /// Computes any of the given [`SharedQuantity`]s (and their dependencies) whose keys are not
+    /// already stored.
+    ///
+    /// # Errors
+    ///
+    /// This method will return any error produced while computing a [`SharedQuantity`].
+    pub fn extend<F: Field>(
+        &mut self,
+        quantities: &[SharedQuantity<F>],
+        dataset: &Dataset<F>,
+    ) -> Result<(), RustitudeError> {
+        for quantity in quantities {
+            if self.values.contains_key(&quantity.key) {
+                continue;
+            }
+            self.extend(&quantity.dependencies, dataset)?;
+            let _span = debug_span!("shared", key = %quantity.key).entered();
+            let value = (quantity.compute)(dataset, self)?;
+            debug!("Computed shared quantity {}", quantity.key);
+            self.values.insert(quantity.key.clone(), value);
+        }
+        Ok(())
+    }
+
+    /// Returns the value stored under `key`, or [`None`] if there is no such value or it has a
+    /// different type.
+    pub fn get<T: 'static>(&self, key: &str) -> Option<&T> {
+        self.values.get(key).and_then(|value| value.downcast_ref())
+    }
+
+    /// Returns the value stored under `key`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::EvaluationError`] if there is no value stored
+    /// under `key` or if it has a different type.
+    pub fn require<T: 'static>(&self, key: &str) -> Result<&T, RustitudeError> {
+        self.get(key).ok_or_else(|| {
+            RustitudeError::EvaluationError(format!(
+                "shared quantity \"{key}\" is missing or has an unexpected type"
+            ))
+        })
+    }
+
+    /// Checks if a value is stored under `key`.
+    pub fn contains(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// Returns the number of stored values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Checks if there are no stored values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
 /// This trait is used to implement operations which can be performed on [`Amplitude`]s (and other
-/// operations themselves). Currently, there are only a limited number of defined operations,
+/// operations themselves).
+///
+/// Currently, there are only a limited number of defined operations,
 /// namely [`Real`], [`Imag`], and [`Product`]. Others may be added in the future, but they
 /// should probably only be added through this crate and not externally, since they require several
 /// operator overloads to be implemented for nice syntax.
@@ -330,6 +851,23 @@ pub trait AmpLike<F: Field>: Send + Sync + Debug + Display + AsTree + DynClone {
     /// calculate the desired mathematical structure given by the [`AmpLike`] and any
     /// [`AmpLike`]s it contains.
     fn compute(&self, cache: &[Option<Complex<F>>]) -> Option<Complex<F>>;
+    /// Computes the same value as [`AmpLike::compute`] along with its gradient with respect to
+    /// every parameter in the [`Model`].
+    ///
+    /// The `gradient_cache` holds the result of [`Node::calculate_gradient`] for each active
+    /// [`Amplitude`]. Returned gradients are dense but may be shorter than the number of
+    /// parameters, in which case the missing trailing entries are zero.
+    fn compute_gradient(
+        &self,
+        cache: &[Option<Complex<F>>],
+        gradient_cache: &[Option<Vec<Complex<F>>>],
+    ) -> Option<(Complex<F>, Vec<Complex<F>>)>;
+    /// This method returns a serializable [`AmpTree`] which mirrors the structure of the
+    /// [`AmpLike`], referring to each contained [`Amplitude`] by name.
+    fn to_amp_tree(&self) -> AmpTree;
+    /// This method passes the [`AmpLike`] and (recursively) every [`AmpLike`] it contains to a
+    /// [`Visitor`] in depth-first order. See [`Model::visit`].
+    fn accept(&self, visitor: &mut dyn Visitor<F>);
     /// This method returns clones of any [`AmpLike`]s wrapped by the given [`AmpLike`].
     fn get_cloned_terms(&self) -> Option<Vec<Box<dyn AmpLike<F>>>> {
         None
@@ -365,6 +903,221 @@ pub trait AmpLike<F: Field>: Send + Sync + Debug + Display + AsTree + DynClone {
         Sum(*dyn_clone::clone_box(als))
     }
 }
+
+/// A node of an [`AmpLike`] expression tree, as seen by a [`Visitor`].
+#[derive(Debug)]
+pub enum AmpNode<'a, F: Field> {
+    /// The absolute square of a coherent sum, which is the root of each tree. This holds the
+    /// position of the coherent sum in [`Model::cohsums`].
+    NormSqr(usize),
+    /// An [`Amplitude`], which is always a leaf of the tree.
+    Amplitude(&'a Amplitude<F>),
+    /// The real part of the single child node (see [`Real`]).
+    Real,
+    /// The imaginary part of the single child node (see [`Imag`]).
+    Imag,
+    /// The product of the given number of child nodes (see [`Product`]).
+    Product(usize),
+    /// The sum of the given number of child nodes (see [`Sum`]).
+    Sum(usize),
+}
+
+/// A trait for inspecting the expression tree of a [`Model`] (see [`Model::visit`]).
+///
+/// Nodes are visited in depth-first order. [`Visitor::enter`] is called before the children of a
+/// node are visited, and [`Visitor::exit`] is called after, so a [`Visitor`] can keep track of
+/// its depth in the tree or build a nested representation such as a formula.
+///
+/// # Example:
+///
+/// ```
+/// use rustitude_core::prelude::*;
+///
+/// struct Names(Vec<String>);
+///
+/// impl Visitor<f64> for Names {
+///     fn enter(&mut self, node: &AmpNode<'_, f64>) -> bool {
+///         if let AmpNode::Amplitude(amp) = node {
+///             self.0.push(amp.name.clone());
+///         }
+///         true
+///     }
+/// }
+///
+/// let model = model!((scalar("a") * cscalar("b")).real(), scalar("c"));
+/// let mut names = Names(Vec::new());
+/// model.visit(&mut names);
+/// assert_eq!(names.0, vec!["a", "b", "c"]);
+/// ```
+pub trait Visitor<F: Field> {
+    /// Called when a node is entered, before any of its children. If this returns `false`, the
+    /// children of the node are skipped (but [`Visitor::exit`] is still called).
+    fn enter(&mut self, _node: &AmpNode<'_, F>) -> bool {
+        true
+    }
+    /// Called when a node is exited, after all of its children.
+    fn exit(&mut self, _node: &AmpNode<'_, F>) {}
+}
+
+/// Builds the formula of [`Model::to_latex`]. Each level of the stack holds the rendered children
+/// of the node being visited, along with a flag which is `true` for sums (which need parentheses
+/// inside of products).
+struct LatexVisitor<'a, F: Field> {
+    parameters: &'a [Parameter<F>],
+    stack: Vec<Vec<(String, bool)>>,
+}
+
+/// Escapes characters with special meaning in LaTeX text.
+fn latex_escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' => r"\textbackslash{}".to_string(),
+            '~' => r"\textasciitilde{}".to_string(),
+            '^' => r"\textasciicircum{}".to_string(),
+            '_' | '&' | '%' | '$' | '#' | '{' | '}' => format!("\\{c}"),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+impl<F: Field> Visitor<F> for LatexVisitor<'_, F> {
+    fn enter(&mut self, _node: &AmpNode<'_, F>) -> bool {
+        self.stack.push(Vec::new());
+        true
+    }
+
+    fn exit(&mut self, node: &AmpNode<'_, F>) {
+        let children = self.stack.pop().unwrap_or_default();
+        let first = || children.first().map_or("", |(term, _)| term.as_str());
+        let term = match node {
+            AmpNode::NormSqr(_) => (format!(r"\left|{}\right|^2", first()), false),
+            AmpNode::Amplitude(amp) => {
+                let parameters: Vec<String> = self
+                    .parameters
+                    .iter()
+                    .filter(|par| par.amplitude == amp.name)
+                    .map(|par| {
+                        let name = format!(r"\text{{{}}}", latex_escape(&par.name));
+                        if par.index.is_some() {
+                            name
+                        } else {
+                            format!("{name} = {}", par.initial)
+                        }
+                    })
+                    .collect();
+                let name = format!(r"\text{{{}}}", latex_escape(&amp.name));
+                if parameters.is_empty() {
+                    (name, false)
+                } else {
+                    (
+                        format!(r"{name}\left({}\right)", parameters.join(", ")),
+                        false,
+                    )
+                }
+            }
+            AmpNode::Real => (format!(r"\Re\left[{}\right]", first()), false),
+            AmpNode::Imag => (format!(r"\Im\left[{}\right]", first()), false),
+            AmpNode::Product(_) => (
+                children
+                    .iter()
+                    .map(|(term, is_sum)| {
+                        if *is_sum {
+                            format!(r"\left({term}\right)")
+                        } else {
+                            term.clone()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                false,
+            ),
+            AmpNode::Sum(_) => (
+                children
+                    .iter()
+                    .map(|(term, _)| term.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" + "),
+                children.len() > 1,
+            ),
+        };
+        if let Some(siblings) = self.stack.last_mut() {
+            siblings.push(term);
+        }
+    }
+}
+
+/// Collects the nodes and edges of the graph drawn by [`Model::to_dot`] and
+/// [`Model::to_mermaid`]. Each node is stored as its label lines and a flag which is `false` if it
+/// is inactive, and the stack holds the indices of the nodes being visited.
+struct GraphVisitor<'a, F: Field> {
+    model: &'a Model<F>,
+    nodes: Vec<(Vec<String>, bool)>,
+    edges: Vec<(usize, usize)>,
+    stack: Vec<usize>,
+}
+
+impl<'a, F: Field> GraphVisitor<'a, F> {
+    fn new(model: &'a Model<F>) -> Self {
+        let mut visitor = Self {
+            model,
+            nodes: vec![(vec!["+".to_string()], true)],
+            edges: Vec::new(),
+            stack: vec![0],
+        };
+        model.visit(&mut visitor);
+        visitor
+    }
+}
+
+impl<F: Field> Visitor<F> for GraphVisitor<'_, F> {
+    fn enter(&mut self, node: &AmpNode<'_, F>) -> bool {
+        let (label, active) = match node {
+            AmpNode::NormSqr(index) => {
+                let cohsum = &self.model.cohsums[*index];
+                let mut label = vec!["|_|^2".to_string()];
+                label.extend(cohsum.name.clone());
+                (label, cohsum.active)
+            }
+            AmpNode::Amplitude(amp) => {
+                let parameters: Vec<String> = self
+                    .model
+                    .parameters
+                    .iter()
+                    .filter(|par| par.amplitude == amp.name)
+                    .map(|par| {
+                        if par.index.is_some() {
+                            par.name.clone()
+                        } else {
+                            format!("{} = {}", par.name, par.initial)
+                        }
+                    })
+                    .collect();
+                let mut label = vec![amp.name.clone()];
+                if parameters.len() > 7 {
+                    label.push(format!("{}, ...", parameters[0..7].join(", ")));
+                } else if !parameters.is_empty() {
+                    label.push(parameters.join(", "));
+                }
+                (label, amp.active)
+            }
+            AmpNode::Real => (vec!["real".to_string()], true),
+            AmpNode::Imag => (vec!["imag".to_string()], true),
+            AmpNode::Product(_) => (vec!["*".to_string()], true),
+            AmpNode::Sum(_) => (vec!["+".to_string()], true),
+        };
+        let id = self.nodes.len();
+        self.nodes.push((label, active));
+        if let Some(parent) = self.stack.last() {
+            self.edges.push((*parent, id));
+        }
+        self.stack.push(id);
+        true
+    }
+
+    fn exit(&mut self, _node: &AmpNode<'_, F>) {
+        self.stack.pop();
+    }
+}
 dyn_clone::clone_trait_object!(<F> AmpLike<F>);
 
 /// This trait defines some simple methods for pretty-printing tree-like structures.
@@ -419,6 +1172,10 @@ pub struct Amplitude<F: Field> {
     /// Indicates the position in the final parameter vector that coincides with the starting index
     /// for parameters in this [`Amplitude`]
     pub parameter_index_start: usize,
+    /// A fixed factor which multiplies every value of the [`Node`] (see [`Amplitude::scaled`]).
+    pub scale: F,
+    /// Records the time spent in [`Node::calculate`] (see [`crate::profiling`]).
+    calculate_timer: Arc<Timer>,
 }
 
 impl<F: Field> Debug for Amplitude<F> {
@@ -432,6 +1189,7 @@ impl<F: Field> Display for Amplitude<F> {
         writeln!(f, "Amplitude")?;
         writeln!(f, "  Name:                     {}", self.name)?;
         writeln!(f, "  Active:                   {}", self.active)?;
+        writeln!(f, "  Scale:                    {}", self.scale)?;
         writeln!(f, "  Cache Position:           {}", self.cache_position)?;
         writeln!(f, "  Index of First Parameter: {}", self.parameter_index_start)
     }
@@ -462,8 +1220,49 @@ impl<F: Field> Amplitude<F> {
             active: true,
             cache_position: 0,
             parameter_index_start: 0,
+            scale: F::one(),
+            calculate_timer: profiling::timer(Stage::Calculate, name),
+        }
+    }
+    /// Creates a new [`Amplitude`] from a name and an already boxed [`Node`].
+    pub fn from_boxed(name: &str, node: Box<dyn Node<F>>) -> Self {
+        info!("Created new amplitude named {name}");
+        let parameters = node.parameters();
+        Self {
+            name: name.to_string(),
+            node,
+            parameters,
+            active: true,
+            cache_position: 0,
+            parameter_index_start: 0,
+            scale: F::one(),
+            calculate_timer: profiling::timer(Stage::Calculate, name),
         }
     }
+    /// Multiplies every value of the [`Amplitude`] by a fixed `scale` without introducing a
+    /// [`Parameter`].
+    ///
+    /// This is intended for known constants such as isospin Clebsch-Gordan coefficients, which
+    /// would otherwise need a fixed [`scalar`] [`Amplitude`]. Scales compound, so scaling an
+    /// [`Amplitude`] twice multiplies it by both factors.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use rustitude_core::prelude::*;
+    /// use rustitude_core::utils::generate_test_event_f64;
+    /// # fn main() -> Result<(), RustitudeError> {
+    /// let a = cscalar("a").scaled(-f64::sqrt(2.0 / 3.0));
+    /// let value = a.calculate(&[0.5, 1.0], &generate_test_event_f64())?;
+    /// assert!((value.re + 0.5 * f64::sqrt(2.0 / 3.0)).abs() < 1e-12);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn scaled(mut self, scale: F) -> Self {
+        self.scale *= scale;
+        self
+    }
     /// Set the [`Amplitude::cache_position`] and [`Amplitude::parameter_index_start`] and runs
     /// [`Amplitude::precalculate`] over the given [`Dataset`].
     ///
@@ -479,19 +1278,70 @@ impl<F: Field> Amplitude<F> {
         self.parameter_index_start = parameter_index_start;
         self.precalculate(dataset)
     }
-}
-impl<F: Field> Node<F> for Amplitude<F> {
-    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
-        self.node.precalculate(dataset)?;
-        debug!("Precalculated amplitude {}", self.name);
-        Ok(())
-    }
-    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        let res = self.node.calculate(
-            &parameters
-                [self.parameter_index_start..self.parameter_index_start + self.parameters.len()],
-            event,
-        );
+    /// Computes any [`Node::requirements`] which are missing from `shared` and runs
+    /// [`Node::precalculate_shared`] over the given [`Dataset`].
+    ///
+    /// # Errors
+    /// This function will raise a [`RustitudeError`] if a [`SharedQuantity`] or the
+    /// precalculation step fails.
+    pub fn precalculate_with(
+        &mut self,
+        dataset: &Dataset<F>,
+        shared: &mut SharedData,
+    ) -> Result<(), RustitudeError> {
+        shared.extend(&self.requirements(), dataset)?;
+        self.precalculate_shared(dataset, shared)
+    }
+    /// Creates a free [`Parameter`] for each of the [`Node::parameters`] with consecutive
+    /// indices starting at `first_index`, using the [`Node::initial_values`] and
+    /// [`Node::parameter_bounds`] where they are given.
+    fn new_parameters(&self, first_index: usize) -> Vec<Parameter<F>> {
+        let initial = self.initial_values();
+        let bounds = self.parameter_bounds();
+        self.parameters()
+            .iter()
+            .enumerate()
+            .map(|(j, name)| {
+                let mut parameter = Parameter::new(&self.name, name, first_index + j);
+                if let Some(value) = initial.get(j) {
+                    parameter.initial = *value;
+                }
+                if let Some(bounds) = bounds.get(j) {
+                    parameter.bounds = *bounds;
+                }
+                parameter
+            })
+            .collect()
+    }
+}
+impl<F: Field> Node<F> for Amplitude<F> {
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        let shared = SharedData::compute(&self.requirements(), dataset)?;
+        self.precalculate_shared(dataset, &shared)
+    }
+    fn requirements(&self) -> Vec<SharedQuantity<F>> {
+        self.node.requirements()
+    }
+    fn precalculate_shared(
+        &mut self,
+        dataset: &Dataset<F>,
+        shared: &SharedData,
+    ) -> Result<(), RustitudeError> {
+        let _span = debug_span!("precalculate", amplitude = %self.name).entered();
+        profiling::timer(Stage::Precalculate, &self.name)
+            .time(1, || self.node.precalculate_shared(dataset, shared))?;
+        debug!("Precalculated amplitude {}", self.name);
+        Ok(())
+    }
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let _span = trace_span!("calculate", amplitude = %self.name).entered();
+        let res = self.calculate_timer.time(1, || {
+            self.node.calculate(
+                &parameters[self.parameter_index_start
+                    ..self.parameter_index_start + self.parameters.len()],
+                event,
+            )
+        });
         debug!(
             "{}({:?}, event #{}) = {}",
             self.name,
@@ -502,11 +1352,59 @@ impl<F: Field> Node<F> for Amplitude<F> {
                 .map(|c| c.to_string())
                 .unwrap_or_else(|e| e.to_string())
         );
-        res
+        res.map(|value| value * self.scale)
+    }
+    fn calculate_batch(
+        &self,
+        parameters: &[F],
+        events: &[Event<F>],
+        out: &mut [Complex<F>],
+    ) -> Result<(), RustitudeError> {
+        let _span = trace_span!("calculate", amplitude = %self.name).entered();
+        self.calculate_timer.time(events.len(), || {
+            self.node.calculate_batch(
+                &parameters[self.parameter_index_start
+                    ..self.parameter_index_start + self.parameters.len()],
+                events,
+                out,
+            )
+        })?;
+        if self.scale != F::one() {
+            out.iter_mut().for_each(|value| *value *= self.scale);
+        }
+        Ok(())
+    }
+    fn calculate_gradient(
+        &self,
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        Ok(self
+            .node
+            .calculate_gradient(
+                &parameters[self.parameter_index_start
+                    ..self.parameter_index_start + self.parameters.len()],
+                event,
+            )?
+            .into_iter()
+            .map(|value| value * self.scale)
+            .collect())
     }
     fn parameters(&self) -> Vec<String> {
         self.node.parameters()
     }
+    fn initial_values(&self) -> Vec<F> {
+        self.node.initial_values()
+    }
+    fn parameter_bounds(&self) -> Vec<(F, F)> {
+        self.node.parameter_bounds()
+    }
+    fn is_event_independent(&self) -> bool {
+        self.node.is_event_independent()
+    }
+    fn spec(&self) -> Option<NodeSpec> {
+        self.node.spec()
+    }
 }
 impl<F: Field> AmpLike<F> for Amplitude<F> {
     fn walk(&self) -> Vec<Self> {
@@ -517,6 +1415,16 @@ impl<F: Field> AmpLike<F> for Amplitude<F> {
         vec![self]
     }
 
+    fn to_amp_tree(&self) -> AmpTree {
+        AmpTree::Amplitude(self.name.clone())
+    }
+
+    fn accept(&self, visitor: &mut dyn Visitor<F>) {
+        let node = AmpNode::Amplitude(self);
+        visitor.enter(&node);
+        visitor.exit(&node);
+    }
+
     fn compute(&self, cache: &[Option<Complex<F>>]) -> Option<Complex<F>> {
         let res = cache[self.cache_position];
         debug!(
@@ -526,11 +1434,23 @@ impl<F: Field> AmpLike<F> for Amplitude<F> {
         );
         res
     }
+
+    fn compute_gradient(
+        &self,
+        cache: &[Option<Complex<F>>],
+        gradient_cache: &[Option<Vec<Complex<F>>>],
+    ) -> Option<(Complex<F>, Vec<Complex<F>>)> {
+        let value = cache[self.cache_position]?;
+        let local = gradient_cache[self.cache_position].as_ref()?;
+        let mut gradient = vec![Complex::default(); self.parameter_index_start];
+        gradient.extend_from_slice(local);
+        Some((value, gradient))
+    }
 }
 
 /// An [`AmpLike`] representing the real part of the [`AmpLike`] it contains.
 #[derive(Clone)]
-pub struct Real<F: Field>(Box<dyn AmpLike<F>>);
+pub struct Real<F: Field>(pub Box<dyn AmpLike<F>>);
 impl<F: Field> Debug for Real<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Real [ {:?} ]", self.0)
@@ -550,6 +1470,32 @@ impl<F: Field> AmpLike<F> for Real<F> {
         self.0.walk_mut()
     }
 
+    fn to_amp_tree(&self) -> AmpTree {
+        AmpTree::Real(Box::new(self.0.to_amp_tree()))
+    }
+
+    fn accept(&self, visitor: &mut dyn Visitor<F>) {
+        if visitor.enter(&AmpNode::Real) {
+            self.0.accept(visitor);
+        }
+        visitor.exit(&AmpNode::Real);
+    }
+
+    fn compute_gradient(
+        &self,
+        cache: &[Option<Complex<F>>],
+        gradient_cache: &[Option<Vec<Complex<F>>>],
+    ) -> Option<(Complex<F>, Vec<Complex<F>>)> {
+        self.0
+            .compute_gradient(cache, gradient_cache)
+            .map(|(value, gradient)| {
+                (
+                    value.re.into(),
+                    gradient.iter().map(|g| g.re.into()).collect(),
+                )
+            })
+    }
+
     fn compute(&self, cache: &[Option<Complex<F>>]) -> Option<Complex<F>> {
         let res: Option<Complex<F>> = self.0.compute(cache).map(|r| r.re.into());
         debug!(
@@ -574,7 +1520,7 @@ impl<F: Field> AsTree for Real<F> {
 
 /// An [`AmpLike`] representing the imaginary part of the [`AmpLike`] it contains.
 #[derive(Clone)]
-pub struct Imag<F: Field>(Box<dyn AmpLike<F>>);
+pub struct Imag<F: Field>(pub Box<dyn AmpLike<F>>);
 impl<F: Field> Debug for Imag<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Imag [ {:?} ]", self.0)
@@ -594,6 +1540,32 @@ impl<F: Field> AmpLike<F> for Imag<F> {
         self.0.walk_mut()
     }
 
+    fn to_amp_tree(&self) -> AmpTree {
+        AmpTree::Imag(Box::new(self.0.to_amp_tree()))
+    }
+
+    fn accept(&self, visitor: &mut dyn Visitor<F>) {
+        if visitor.enter(&AmpNode::Imag) {
+            self.0.accept(visitor);
+        }
+        visitor.exit(&AmpNode::Imag);
+    }
+
+    fn compute_gradient(
+        &self,
+        cache: &[Option<Complex<F>>],
+        gradient_cache: &[Option<Vec<Complex<F>>>],
+    ) -> Option<(Complex<F>, Vec<Complex<F>>)> {
+        self.0
+            .compute_gradient(cache, gradient_cache)
+            .map(|(value, gradient)| {
+                (
+                    value.im.into(),
+                    gradient.iter().map(|g| g.im.into()).collect(),
+                )
+            })
+    }
+
     fn compute(&self, cache: &[Option<Complex<F>>]) -> Option<Complex<F>> {
         let res: Option<Complex<F>> = self.0.compute(cache).map(|r| r.im.into());
         debug!(
@@ -618,7 +1590,7 @@ impl<F: Field> AsTree for Imag<F> {
 
 /// An [`AmpLike`] representing the product of the [`AmpLike`]s it contains.
 #[derive(Clone)]
-pub struct Product<F: Field>(Vec<Box<dyn AmpLike<F>>>);
+pub struct Product<F: Field>(pub Vec<Box<dyn AmpLike<F>>>);
 impl<F: Field> Debug for Product<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Product [ ")?;
@@ -663,6 +1635,18 @@ impl<F: Field> AmpLike<F> for Product<F> {
         self.0.iter_mut().flat_map(|op| op.walk_mut()).collect()
     }
 
+    fn to_amp_tree(&self) -> AmpTree {
+        AmpTree::Product(self.0.iter().map(|op| op.to_amp_tree()).collect())
+    }
+
+    fn accept(&self, visitor: &mut dyn Visitor<F>) {
+        let node = AmpNode::Product(self.0.len());
+        if visitor.enter(&node) {
+            self.0.iter().for_each(|op| op.accept(visitor));
+        }
+        visitor.exit(&node);
+    }
+
     fn compute(&self, cache: &[Option<Complex<F>>]) -> Option<Complex<F>> {
         let mut values = self.0.iter().filter_map(|op| op.compute(cache)).peekable();
         let res: Option<Complex<F>> = if values.peek().is_none() {
@@ -677,6 +1661,33 @@ impl<F: Field> AmpLike<F> for Product<F> {
         );
         res
     }
+
+    fn compute_gradient(
+        &self,
+        cache: &[Option<Complex<F>>],
+        gradient_cache: &[Option<Vec<Complex<F>>>],
+    ) -> Option<(Complex<F>, Vec<Complex<F>>)> {
+        let terms: Vec<(Complex<F>, Vec<Complex<F>>)> = self
+            .0
+            .iter()
+            .filter_map(|op| op.compute_gradient(cache, gradient_cache))
+            .collect();
+        if terms.is_empty() {
+            return Some((Complex::default(), Vec::default()));
+        }
+        let value = terms.iter().map(|(v, _)| *v).product();
+        let mut gradient = Vec::default();
+        for (i, (_, term_gradient)) in terms.iter().enumerate() {
+            let others: Complex<F> = terms
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, (v, _))| *v)
+                .product();
+            accumulate_gradient(&mut gradient, others, term_gradient);
+        }
+        Some((value, gradient))
+    }
 }
 
 /// An [`AmpLike`] representing the sum of the [`AmpLike`]s it contains.
@@ -726,6 +1737,18 @@ impl<F: Field> AmpLike<F> for Sum<F> {
         self.0.iter_mut().flat_map(|op| op.walk_mut()).collect()
     }
 
+    fn to_amp_tree(&self) -> AmpTree {
+        AmpTree::Sum(self.0.iter().map(|op| op.to_amp_tree()).collect())
+    }
+
+    fn accept(&self, visitor: &mut dyn Visitor<F>) {
+        let node = AmpNode::Sum(self.0.len());
+        if visitor.enter(&node) {
+            self.0.iter().for_each(|op| op.accept(visitor));
+        }
+        visitor.exit(&node);
+    }
+
     fn compute(&self, cache: &[Option<Complex<F>>]) -> Option<Complex<F>> {
         let res = Some(
             self.0
@@ -740,15 +1763,60 @@ impl<F: Field> AmpLike<F> for Sum<F> {
         );
         res
     }
+
+    fn compute_gradient(
+        &self,
+        cache: &[Option<Complex<F>>],
+        gradient_cache: &[Option<Vec<Complex<F>>>],
+    ) -> Option<(Complex<F>, Vec<Complex<F>>)> {
+        let mut value = Complex::default();
+        let mut gradient = Vec::default();
+        for (term_value, term_gradient) in self
+            .0
+            .iter()
+            .filter_map(|al| al.compute_gradient(cache, gradient_cache))
+        {
+            value += term_value;
+            accumulate_gradient(&mut gradient, Complex::from(F::one()), &term_gradient);
+        }
+        Some((value, gradient))
+    }
+}
+
+/// Adds `scale * term` to `gradient`, extending `gradient` with zeros if it is shorter than
+/// `term`.
+fn accumulate_gradient<F: Field>(
+    gradient: &mut Vec<Complex<F>>,
+    scale: Complex<F>,
+    term: &[Complex<F>],
+) {
+    if gradient.len() < term.len() {
+        gradient.resize(term.len(), Complex::default());
+    }
+    gradient
+        .iter_mut()
+        .zip(term)
+        .for_each(|(g, t)| *g += scale * t);
 }
 
 /// Struct to hold a coherent sum of [`AmpLike`]s
 #[derive(Clone)]
-pub struct NormSqr<F: Field>(pub Box<dyn AmpLike<F>>);
+pub struct NormSqr<F: Field> {
+    /// The term whose absolute square is taken.
+    pub term: Box<dyn AmpLike<F>>,
+    /// An optional name for the coherent sum (see [`Model::new_named`]).
+    pub name: Option<String>,
+    /// A flag which is `false` if the coherent sum is excluded from the [`Model`] (see
+    /// [`Model::deactivate_sum`]).
+    pub active: bool,
+}
 
 impl<F: Field> Debug for NormSqr<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "NormSqr[ {:?} ]", self.0)
+        match &self.name {
+            Some(name) => write!(f, "NormSqr({name})[ {:?} ]", self.term),
+            None => write!(f, "NormSqr[ {:?} ]", self.term),
+        }
     }
 }
 impl<F: Field> Display for NormSqr<F> {
@@ -762,30 +1830,504 @@ impl<F: Field> AsTree for NormSqr<F> {
         res.push_str(&self._get_indent(bits.to_vec()));
         res.push_str(&self._get_end());
         bits.push(false);
-        res.push_str(&self.0._get_tree(&mut bits.clone()));
+        res.push_str(&self.term._get_tree(&mut bits.clone()));
         bits.pop();
         res
     }
 }
 impl<F: Field> NormSqr<F> {
+    /// Creates a new, unnamed, and active [`NormSqr`] from a term.
+    pub fn new(term: Box<dyn AmpLike<F>>) -> Self {
+        Self {
+            term,
+            name: None,
+            active: true,
+        }
+    }
+
     /// Shortcut for computation using a cache of precomputed values. This method will return
-    /// [`None`] if the cache value at the corresponding [`Amplitude`]'s
-    /// [`Amplitude::cache_position`] is also [`None`], otherwise it just returns the corresponding
-    /// cached value. The computation is run across the [`NormSqr`]'s contained term, and the absolute
-    /// square of the result is returned.
+    /// [`None`] if the [`NormSqr`] is inactive or if the cache value at the corresponding
+    /// [`Amplitude`]'s [`Amplitude::cache_position`] is also [`None`], otherwise it just returns
+    /// the corresponding cached value. The computation is run across the [`NormSqr`]'s contained
+    /// term, and the absolute square of the result is returned.
     pub fn compute(&self, cache: &[Option<Complex<F>>]) -> Option<F> {
-        self.0.compute(cache).map(|res| res.norm_sqr())
+        if !self.active {
+            return None;
+        }
+        self.term.compute(cache).map(|res| res.norm_sqr())
+    }
+
+    /// Computes the same value as [`NormSqr::compute`] along with its gradient with respect to
+    /// every parameter in the [`Model`] (see [`AmpLike::compute_gradient`]). Since the parameters
+    /// are real, the derivative of $`|z|^2`$ is $`2\,\text{Re}(z^* \partial z)`$.
+    pub fn compute_gradient(
+        &self,
+        cache: &[Option<Complex<F>>],
+        gradient_cache: &[Option<Vec<Complex<F>>>],
+    ) -> Option<(F, Vec<F>)> {
+        if !self.active {
+            return None;
+        }
+        self.term
+            .compute_gradient(cache, gradient_cache)
+            .map(|(value, gradient)| {
+                let two = convert!(2, F);
+                (
+                    value.norm_sqr(),
+                    gradient
+                        .iter()
+                        .map(|g| two * (value.conj() * g).re)
+                        .collect(),
+                )
+            })
     }
 
     /// Walks through a [`NormSqr`] and collects all the contained [`Amplitude`]s recursively.
     pub fn walk(&self) -> Vec<Amplitude<F>> {
-        self.0.walk()
+        self.term.walk()
     }
 
     /// Walks through an [`NormSqr`] and collects all the contained [`Amplitude`]s recursively. This
     /// method gives mutable access to said [`Amplitude`]s.
     pub fn walk_mut(&mut self) -> Vec<&mut Amplitude<F>> {
-        self.0.walk_mut()
+        self.term.walk_mut()
+    }
+
+    /// Returns a serializable [`AmpTree`] representing the term inside the [`NormSqr`].
+    pub fn to_amp_tree(&self) -> AmpTree {
+        self.term.to_amp_tree()
+    }
+
+    /// Passes the [`NormSqr`] (as the `index`-th coherent sum of a [`Model`]) and every
+    /// [`AmpLike`] it contains to a [`Visitor`]. See [`Model::visit`].
+    pub fn accept(&self, visitor: &mut dyn Visitor<F>, index: usize) {
+        let node = AmpNode::NormSqr(index);
+        if visitor.enter(&node) {
+            self.term.accept(visitor);
+        }
+        visitor.exit(&node);
+    }
+}
+
+/// A Gaussian constraint on a single [`Parameter`] in a [`Model`].
+///
+/// Each constraint contributes a term of $`\left(\frac{x - \mu}{\sigma}\right)^2`$ to the
+/// negative log-likelihood (in the same $`-2\ln\mathcal{L}`$ convention used by
+/// [`ExtendedLogLikelihood`](crate::manager::ExtendedLogLikelihood)), where $`x`$ is the value of
+/// the parameter. This is typically used to constrain nuisance parameters to an external
+/// measurement.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct GaussianConstraint<F: Field> {
+    /// Name of the parent [`Amplitude`] containing the constrained parameter.
+    pub amplitude: String,
+    /// Name of the constrained parameter.
+    pub parameter: String,
+    /// Central value of the constraint.
+    pub mean: F,
+    /// Width of the constraint.
+    pub sigma: F,
+}
+
+/// The signature of the function of a [`Penalty`], which takes the values of the penalized
+/// [`Parameter`]s in order.
+pub type PenaltyFunction<F> = dyn Fn(&[F]) -> Result<F, RustitudeError> + Send + Sync;
+
+/// A custom penalty term on [`Parameter`]s in a [`Model`] (see [`Model::add_penalty`]).
+///
+/// Like a [`GaussianConstraint`], the value of the function is added to $`-2\ln\mathcal{L}`$.
+/// Penalties are arbitrary closures, so they are not recorded in [`Model::history`] or stored
+/// when the [`Model`] is serialized.
+#[derive(Clone)]
+pub struct Penalty<F: Field> {
+    /// The `(amplitude, parameter)` names of the penalized [`Parameter`]s.
+    pub parameters: Vec<(String, String)>,
+    function: Arc<PenaltyFunction<F>>,
+}
+impl<F: Field> Debug for Penalty<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Penalty")
+            .field("parameters", &self.parameters)
+            .finish_non_exhaustive()
+    }
+}
+impl<F: Field> Penalty<F> {
+    /// Evaluates the penalty for the given values of its [`Parameter`]s.
+    ///
+    /// # Errors
+    ///
+    /// This method yields any error produced by the penalty function.
+    pub fn evaluate(&self, values: &[F]) -> Result<F, RustitudeError> {
+        (self.function)(values)
+    }
+
+    /// Evaluates the gradient of the penalty with respect to its [`Parameter`]s by central
+    /// finite differences.
+    ///
+    /// # Errors
+    ///
+    /// This method yields any error produced by the penalty function.
+    pub fn gradient(&self, values: &[F]) -> Result<Vec<F>, RustitudeError> {
+        let mut x = values.to_vec();
+        (0..x.len())
+            .map(|i| {
+                let h = F::epsilon().cbrt() * F::max(F::one(), x[i].abs());
+                let x_i = x[i];
+                x[i] = x_i + h;
+                let f_plus = self.evaluate(&x)?;
+                x[i] = x_i - h;
+                let f_minus = self.evaluate(&x)?;
+                x[i] = x_i;
+                Ok((f_plus - f_minus) / (convert!(2, F) * h))
+            })
+            .collect()
+    }
+}
+
+/// A parameter operation on a [`Model`], recorded in [`Model::history`].
+///
+/// Recorded operations can be undone with [`Model::undo`] or applied to another [`Model`] with
+/// [`Model::replay`], which makes it easy to configure several [`Model`]s (for instance, one
+/// for each bin of a binned fit) in exactly the same way.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum ModelOperation<F: Field> {
+    /// See [`Model::fix`].
+    Fix {
+        /// Name of the parent [`Amplitude`].
+        amplitude: String,
+        /// Name of the parameter.
+        parameter: String,
+        /// The fixed value.
+        value: F,
+    },
+    /// See [`Model::free`].
+    Free {
+        /// Name of the parent [`Amplitude`].
+        amplitude: String,
+        /// Name of the parameter.
+        parameter: String,
+    },
+    /// See [`Model::constrain`].
+    Constrain {
+        /// Name of the parent [`Amplitude`] of the first parameter.
+        amplitude_1: String,
+        /// Name of the first parameter.
+        parameter_1: String,
+        /// Name of the parent [`Amplitude`] of the second parameter.
+        amplitude_2: String,
+        /// Name of the second parameter.
+        parameter_2: String,
+    },
+    /// See [`Model::set_initial`].
+    SetInitial {
+        /// Name of the parent [`Amplitude`].
+        amplitude: String,
+        /// Name of the parameter.
+        parameter: String,
+        /// The initial value.
+        initial: F,
+    },
+    /// See [`Model::set_bounds`].
+    SetBounds {
+        /// Name of the parent [`Amplitude`].
+        amplitude: String,
+        /// Name of the parameter.
+        parameter: String,
+        /// The bounds.
+        bounds: (F, F),
+    },
+    /// See [`Model::set_transform`].
+    SetTransform {
+        /// Name of the parent [`Amplitude`].
+        amplitude: String,
+        /// Name of the parameter.
+        parameter: String,
+        /// The transform.
+        transform: Option<Transform<F>>,
+    },
+    /// See [`Model::add_gaussian_constraint`].
+    AddGaussianConstraint(GaussianConstraint<F>),
+}
+
+impl<F: Field> Display for ModelOperation<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fix {
+                amplitude,
+                parameter,
+                value,
+            } => write!(f, "fix({amplitude}::{parameter}, {value})"),
+            Self::Free {
+                amplitude,
+                parameter,
+            } => write!(f, "free({amplitude}::{parameter})"),
+            Self::Constrain {
+                amplitude_1,
+                parameter_1,
+                amplitude_2,
+                parameter_2,
+            } => write!(
+                f,
+                "constrain({amplitude_1}::{parameter_1}, {amplitude_2}::{parameter_2})"
+            ),
+            Self::SetInitial {
+                amplitude,
+                parameter,
+                initial,
+            } => write!(f, "set_initial({amplitude}::{parameter}, {initial})"),
+            Self::SetBounds {
+                amplitude,
+                parameter,
+                bounds,
+            } => write!(
+                f,
+                "set_bounds({amplitude}::{parameter}, ({}, {}))",
+                bounds.0, bounds.1
+            ),
+            Self::SetTransform {
+                amplitude,
+                parameter,
+                transform,
+            } => match transform {
+                Some(transform) => {
+                    write!(f, "set_transform({amplitude}::{parameter}, {transform:?})")
+                }
+                None => write!(f, "set_transform({amplitude}::{parameter}, None)"),
+            },
+            Self::AddGaussianConstraint(constraint) => write!(
+                f,
+                "add_gaussian_constraint({}::{}, {}, {})",
+                constraint.amplitude, constraint.parameter, constraint.mean, constraint.sigma
+            ),
+        }
+    }
+}
+
+impl<F: Field> ModelOperation<F> {
+    /// Applies the operation to a [`Model`] (which records it in the [`Model::history`]).
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if the operation fails on the given [`Model`],
+    /// for instance if a parameter is not found by name.
+    pub fn apply(&self, model: &mut Model<F>) -> Result<(), RustitudeError> {
+        match self {
+            Self::Fix {
+                amplitude,
+                parameter,
+                value,
+            } => model.fix(amplitude, parameter, *value),
+            Self::Free {
+                amplitude,
+                parameter,
+            } => model.free(amplitude, parameter),
+            Self::Constrain {
+                amplitude_1,
+                parameter_1,
+                amplitude_2,
+                parameter_2,
+            } => model.constrain(amplitude_1, parameter_1, amplitude_2, parameter_2),
+            Self::SetInitial {
+                amplitude,
+                parameter,
+                initial,
+            } => model.set_initial(amplitude, parameter, *initial),
+            Self::SetBounds {
+                amplitude,
+                parameter,
+                bounds,
+            } => model.set_bounds(amplitude, parameter, *bounds),
+            Self::SetTransform {
+                amplitude,
+                parameter,
+                transform,
+            } => model.set_transform(amplitude, parameter, *transform),
+            Self::AddGaussianConstraint(constraint) => model.add_gaussian_constraint(
+                &constraint.amplitude,
+                &constraint.parameter,
+                constraint.mean,
+                constraint.sigma,
+            ),
+        }
+    }
+    /// Replaces the name of the parent [`Amplitude`] of every parameter in the operation which
+    /// is named `old` (see [`Model::rename_amplitude`]).
+    fn rename_amplitude(&mut self, old: &str, new: &str) {
+        let amplitudes = match self {
+            Self::Fix { amplitude, .. }
+            | Self::Free { amplitude, .. }
+            | Self::SetInitial { amplitude, .. }
+            | Self::SetBounds { amplitude, .. }
+            | Self::SetTransform { amplitude, .. }
+            | Self::AddGaussianConstraint(GaussianConstraint { amplitude, .. }) => {
+                vec![amplitude]
+            }
+            Self::Constrain {
+                amplitude_1,
+                amplitude_2,
+                ..
+            } => vec![amplitude_1, amplitude_2],
+        };
+        for amplitude in amplitudes {
+            if amplitude == old {
+                *amplitude = new.to_string();
+            }
+        }
+    }
+}
+
+/// A [`ModelOperation`] together with the state of the [`Model`] before it was applied.
+#[derive(Clone)]
+struct HistoryEntry<F: Field> {
+    operation: ModelOperation<F>,
+    parameters: Vec<Parameter<F>>,
+    gaussian_constraints: Vec<GaussianConstraint<F>>,
+}
+
+/// The activation state of every [`Amplitude`] and coherent sum in a [`Model`], returned by
+/// [`Model::activation_state`] and applied with [`Model::restore`].
+///
+/// This is useful for code which temporarily isolates some [`Amplitude`]s (to plot a single
+/// wave, for instance) and must leave the [`Model`] as it found it afterwards.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActivationSnapshot {
+    /// The name of each [`Amplitude`] and whether it is active.
+    pub amplitudes: Vec<(String, bool)>,
+    /// Whether each coherent sum is active, in the order of [`Model::cohsums`].
+    pub sums: Vec<bool>,
+}
+
+/// An operation of an [`EvaluationPlan`]. Operands are slots of the evaluation buffer, where the
+/// first slots hold the value of each [`Amplitude`] and the rest hold the result of each
+/// operation in order.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum PlanOp {
+    Real(usize),
+    Imag(usize),
+    Product(Vec<usize>),
+    Sum(Vec<usize>),
+}
+
+/// The coherent sums of a [`Model`] compiled into a list of operations in which identical
+/// subtrees are evaluated once (see [`Model::optimize`]).
+#[derive(Clone, Debug)]
+struct EvaluationPlan {
+    /// The number of [`Amplitude`]s the plan was built for.
+    n_amplitudes: usize,
+    /// The operations in evaluation order.
+    ops: Vec<PlanOp>,
+    /// The slot holding the term of each coherent sum.
+    roots: Vec<usize>,
+    /// The coherent sums which use each [`Amplitude`].
+    users: Vec<Vec<usize>>,
+}
+
+impl EvaluationPlan {
+    fn new(cohsums: &[AmpTree], names: &[String]) -> Result<Self, RustitudeError> {
+        let mut plan = Self {
+            n_amplitudes: names.len(),
+            ops: Vec::new(),
+            roots: Vec::new(),
+            users: vec![Vec::new(); names.len()],
+        };
+        let mut slots = HashMap::new();
+        for (i, tree) in cohsums.iter().enumerate() {
+            let root = plan.insert(tree, names, &mut slots, i)?;
+            plan.roots.push(root);
+        }
+        Ok(plan)
+    }
+
+    /// Adds the operations of a tree (used by the coherent sum `cohsum`), reusing the slot of
+    /// any identical operation which was already added.
+    fn insert(
+        &mut self,
+        tree: &AmpTree,
+        names: &[String],
+        slots: &mut HashMap<PlanOp, usize>,
+        cohsum: usize,
+    ) -> Result<usize, RustitudeError> {
+        let op = match tree {
+            AmpTree::Amplitude(name) => {
+                let slot = names
+                    .iter()
+                    .position(|n| n == name)
+                    .ok_or_else(|| RustitudeError::AmplitudeNotFoundError(name.clone()))?;
+                if !self.users[slot].contains(&cohsum) {
+                    self.users[slot].push(cohsum);
+                }
+                return Ok(slot);
+            }
+            AmpTree::Real(inner) => PlanOp::Real(self.insert(inner, names, slots, cohsum)?),
+            AmpTree::Imag(inner) => PlanOp::Imag(self.insert(inner, names, slots, cohsum)?),
+            AmpTree::Product(terms) => PlanOp::Product(
+                terms
+                    .iter()
+                    .map(|term| self.insert(term, names, slots, cohsum))
+                    .collect::<Result<_, _>>()?,
+            ),
+            AmpTree::Sum(terms) => PlanOp::Sum(
+                terms
+                    .iter()
+                    .map(|term| self.insert(term, names, slots, cohsum))
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+        let next = self.n_amplitudes + self.ops.len();
+        Ok(*slots.entry(op.clone()).or_insert_with(|| {
+            self.ops.push(op);
+            next
+        }))
+    }
+
+    /// Evaluates each operation, appending its result to `values`, which must hold the value of
+    /// each [`Amplitude`]. This follows the same rules as [`AmpLike::compute`].
+    fn evaluate<F: Field>(&self, values: &mut Vec<Option<Complex<F>>>) {
+        for op in &self.ops {
+            let value = match op {
+                PlanOp::Real(slot) => values[*slot].map(|v| v.re.into()),
+                PlanOp::Imag(slot) => values[*slot].map(|v| v.im.into()),
+                PlanOp::Product(slots) => {
+                    let mut factors = slots.iter().filter_map(|slot| values[*slot]).peekable();
+                    if factors.peek().is_none() {
+                        Some(Complex::default())
+                    } else {
+                        Some(factors.product())
+                    }
+                }
+                PlanOp::Sum(slots) => Some(slots.iter().filter_map(|slot| values[*slot]).sum()),
+            };
+            values.push(value);
+        }
+    }
+}
+
+/// Flattens directly nested [`Sum`]s and [`Product`]s of an [`AmpTree`], as the `+` and `*`
+/// operators do for [`Product`]s.
+fn flatten(tree: AmpTree) -> AmpTree {
+    match tree {
+        AmpTree::Amplitude(_) => tree,
+        AmpTree::Real(inner) => AmpTree::Real(Box::new(flatten(*inner))),
+        AmpTree::Imag(inner) => AmpTree::Imag(Box::new(flatten(*inner))),
+        AmpTree::Product(terms) => AmpTree::Product(
+            terms
+                .into_iter()
+                .flat_map(|term| match flatten(term) {
+                    AmpTree::Product(inner) => inner,
+                    term => vec![term],
+                })
+                .collect(),
+        ),
+        AmpTree::Sum(terms) => AmpTree::Sum(
+            terms
+                .into_iter()
+                .flat_map(|term| match flatten(term) {
+                    AmpTree::Sum(inner) => inner,
+                    term => vec![term],
+                })
+                .collect(),
+        ),
     }
 }
 
@@ -800,9 +2342,19 @@ pub struct Model<F: Field> {
     pub amplitudes: Arc<RwLock<Vec<Amplitude<F>>>>,
     /// The unique parameters located within all coherent sums.
     pub parameters: Vec<Parameter<F>>,
+    /// Gaussian constraints on [`Parameter`]s which are added to likelihoods built from this
+    /// [`Model`].
+    pub gaussian_constraints: Vec<GaussianConstraint<F>>,
+    /// Custom [`Penalty`] terms which are added to likelihoods built from this [`Model`].
+    pub penalties: Vec<Penalty<F>>,
     /// Flag which is `True` iff at least one [`Amplitude`] is written in Python and has a [`Node`]
     /// for which [`Node::is_python_node`] returns `True`.
     pub contains_python_amplitudes: bool,
+    history: Vec<HistoryEntry<F>>,
+    /// The `(alias, amplitude, parameter)` names registered with [`Model::alias`].
+    aliases: Vec<(String, String, String)>,
+    /// The [`EvaluationPlan`] built by [`Model::optimize`], if any.
+    plan: Option<Arc<EvaluationPlan>>,
 }
 impl<F: Field> Debug for Model<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -851,652 +2403,4095 @@ impl<F: Field> Model<F> {
                 }
             })
             .collect();
-        let parameter_tags: Vec<(String, String)> = amplitudes
+        let mut next_index = 0;
+        let parameters = amplitudes
             .iter()
             .flat_map(|amp| {
-                amp.parameters()
-                    .iter()
-                    .map(|p| (amp.name.clone(), p.clone()))
-                    .collect::<Vec<_>>()
+                let parameters = amp.new_parameters(next_index);
+                next_index += parameters.len();
+                parameters
             })
             .collect();
-        let parameters = parameter_tags
-            .iter()
-            .enumerate()
-            .map(|(i, (amp_name, par_name))| Parameter::new(amp_name, par_name, i))
-            .collect();
         let contains_python_amplitudes = amplitudes.iter().any(|amp| amp.node.is_python_node());
         Self {
-            cohsums: amps.iter().map(|inner| NormSqr(inner.clone())).collect(),
+            cohsums: amps
+                .iter()
+                .map(|inner| NormSqr::new(inner.clone()))
+                .collect(),
             amplitudes: Arc::new(RwLock::new(amplitudes)),
             parameters,
+            gaussian_constraints: Vec::default(),
+            penalties: Vec::default(),
             contains_python_amplitudes,
+            history: Vec::default(),
+            aliases: Vec::default(),
+            plan: None,
         }
     }
-    /// Creates a true clone (deep copy) of the [`Model`] where the `amplitudes` field is
-    /// duplicated rather than having its reference count increased.
-    pub fn deep_clone(&self) -> Self {
-        Self {
-            cohsums: self.cohsums.clone(),
-            amplitudes: Arc::new(RwLock::new(self.amplitudes.read().clone())),
-            parameters: self.parameters.clone(),
-            contains_python_amplitudes: self.contains_python_amplitudes,
+    /// Creates a new [`Model`] from a list of named [`Box<AmpLike>`]s, one per coherent sum.
+    ///
+    /// Several coherent sums may share a name, in which case [`Model::activate_sum`] and
+    /// [`Model::deactivate_sum`] apply to all of them.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use rustitude_core::prelude::*;
+    ///
+    /// let model: Model<f64> = model!(
+    ///     "PosRe" => (scalar("a") * cscalar("b")).real(),
+    ///     "PosIm" => (scalar("a") * cscalar("b")).imag(),
+    /// );
+    /// assert_eq!(model.sum_names(), vec![Some("PosRe"), Some("PosIm")]);
+    /// ```
+    pub fn new_named(sums: &[(&str, Box<dyn AmpLike<F>>)]) -> Self {
+        let terms: Vec<Box<dyn AmpLike<F>>> = sums.iter().map(|(_, term)| term.clone()).collect();
+        let mut model = Self::new(&terms);
+        for (cohsum, (name, _)) in model.cohsums.iter_mut().zip(sums) {
+            cohsum.name = Some(name.to_string());
         }
+        model
     }
-    /// Computes the result of evaluating the terms in the model with the given [`Parameter`]s for
-    /// the given [`Event`] by summing the result of [`NormSqr::compute`] for each [`NormSqr`]
-    /// contained in the [`Model`] (see the `cohsum` field of [`Model`]).
+    /// Adds an incoherent sum $`\sum_i f_i |A_i|^2`$ of the given terms to the [`Model`], where
+    /// the fractions $`f_i`$ are free but always sum to one.
+    ///
+    /// Each term becomes a coherent sum named `name` which is multiplied by a [`Fraction`]
+    /// [`Amplitude`] named `"{name} fraction {i}"`. All of these [`Amplitude`]s share the same
+    /// logit [`Parameter`]s (found under `"{name} fraction 0"`), which start at zero so that
+    /// every term has an equal fraction. The sum can be switched on and off with
+    /// [`Model::activate_sum`] and [`Model::deactivate_sum`].
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use rustitude_core::prelude::*;
+    ///
+    /// let mut model: Model<f64> = model!(cscalar("signal"));
+    /// model
+    ///     .add_incoherent_sum("bkg", &[Box::new(scalar("flat")), Box::new(scalar("slope"))])
+    ///     .unwrap();
+    /// assert_eq!(model.sum_names(), vec![None, Some("bkg"), Some("bkg")]);
+    /// assert_eq!(model.get_n_free(), 5);
+    /// ```
     ///
     /// # Errors
     ///
-    /// This method yields a [`RustitudeError`] if any of the [`Amplitude::calculate`] steps fail.
-    pub fn compute(
-        &self,
-        amplitudes: &[Amplitude<F>],
-        parameters: &[F],
-        event: &Event<F>,
-    ) -> Result<F, RustitudeError> {
-        // TODO: Stop reallocating?
-
-        // NOTE: This seems to be just as fast as using a Vec<ComplexField> and replacing active
-        // amplitudes by multiplying their cached values by 0.0. Branch prediction doesn't get us
-        // any performance here I guess.
-        let cache: Vec<Option<Complex<F>>> = amplitudes
-            .iter()
-            .map(|amp| {
-                if amp.active {
-                    amp.calculate(parameters, event).map(Some)
-                } else {
-                    Ok(None)
-                }
-            })
-            .collect::<Result<Vec<Option<Complex<F>>>, RustitudeError>>()?;
-        Ok(self
-            .cohsums
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if no terms are given,
+    /// if a [`Fraction`] [`Amplitude`] would share its name with an existing [`Amplitude`], or if
+    /// any [`ModelOperation`] has already been applied to the [`Model`] (since
+    /// [`Model::undo`] cannot remove the new [`Parameter`]s).
+    pub fn add_incoherent_sum(
+        &mut self,
+        name: &str,
+        terms: &[Box<dyn AmpLike<F>>],
+    ) -> Result<(), RustitudeError>
+    where
+        F: 'static,
+    {
+        if terms.is_empty() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "the incoherent sum \"{name}\" has no terms"
+            )));
+        }
+        if !self.history.is_empty() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "the incoherent sum \"{name}\" must be added before any model operations"
+            )));
+        }
+        let n = terms.len();
+        let fractions: Vec<Amplitude<F>> = (0..n)
+            .map(|i| Amplitude::new(&format!("{name} fraction {i}"), Fraction::new(i, n)))
+            .collect();
+        let mut known: HashSet<String> = self
+            .amplitudes
+            .read()
             .iter()
-            .filter_map(|cohsum| cohsum.compute(&cache))
-            .sum::<F>())
+            .map(|amp| amp.name.clone())
+            .collect();
+        if let Some(fraction) = fractions
+            .iter()
+            .find(|fraction| known.contains(&fraction.name))
+        {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "an amplitude named \"{}\" already exists",
+                fraction.name
+            )));
+        }
+        let first_index = self.get_min_free_index().unwrap_or(0);
+        let mut new_amplitudes = Vec::new();
+        for (fraction, term) in fractions.into_iter().zip(terms) {
+            let fraction_name = fraction.name.clone();
+            let cohsum: Box<dyn AmpLike<F>> =
+                Box::new(Product(vec![Box::new(fraction), term.clone()]));
+            for amp in cohsum.walk() {
+                if !known.insert(amp.name.clone()) {
+                    continue;
+                }
+                // Every fraction shares the logits of the first one
+                let index = if amp.name == fraction_name {
+                    first_index
+                } else {
+                    self.get_min_free_index().unwrap_or(0)
+                };
+                self.parameters.extend(amp.new_parameters(index));
+                self.contains_python_amplitudes |= amp.node.is_python_node();
+                new_amplitudes.push(amp);
+            }
+            let mut cohsum = NormSqr::new(cohsum);
+            cohsum.name = Some(name.to_string());
+            self.cohsums.push(cohsum);
+        }
+        self.amplitudes.write().extend(new_amplitudes);
+        self.plan = None;
+        Ok(())
     }
-    /// Registers the [`Model`] with the [`Dataset`] by [`Amplitude::register`]ing each
-    /// [`Amplitude`] and setting the proper cache position and parameter starting index.
+    /// Adds a coherent sum $`|A|^2`$ of the given term to the end of [`Model::cohsums`], with an
+    /// optional name.
     ///
-    /// # Errors
+    /// [`Amplitude`]s which share a name with an [`Amplitude`] already in the [`Model`] refer to
+    /// it (and its [`Parameter`]s), while the rest are added with new free [`Parameter`]s.
     ///
-    /// This method will yield a [`RustitudeError`] if any [`Amplitude::precalculate`] steps fail.
-    pub fn load(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
-        let mut next_cache_pos = 0;
-        let mut parameter_index = 0;
-        self.amplitudes.write().iter_mut().try_for_each(|amp| {
-            amp.register(next_cache_pos, parameter_index, dataset)?;
-            self.cohsums.iter_mut().for_each(|cohsum| {
-                cohsum.walk_mut().iter_mut().for_each(|r_amp| {
-                    if r_amp.name == amp.name {
-                        r_amp.cache_position = next_cache_pos;
-                        r_amp.parameter_index_start = parameter_index;
-                    }
-                })
-            });
-            next_cache_pos += 1;
-            parameter_index += amp.parameters().len();
-            Ok(())
-        })
-    }
-
-    /// Retrieves a copy of an [`Amplitude`] in the [`Model`] by name.
+    /// # Example:
+    ///
+    /// ```
+    /// use rustitude_core::prelude::*;
+    ///
+    /// let mut model: Model<f64> = model!(cscalar("a"));
+    /// model
+    ///     .add_sum(Some("negative"), Box::new(cscalar("a") * scalar("b")))
+    ///     .unwrap();
+    /// assert_eq!(model.sum_names(), vec![None, Some("negative")]);
+    /// assert_eq!(model.get_n_free(), 3);
+    /// ```
     ///
     /// # Errors
-    /// This will throw a [`RustitudeError`] if the amplitude name is not located within the model.
-    pub fn get_amplitude(&self, amplitude_name: &str) -> Result<Amplitude<F>, RustitudeError> {
-        self.amplitudes
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if any
+    /// [`ModelOperation`] has already been applied to the [`Model`] (since [`Model::undo`]
+    /// cannot remove the new [`Parameter`]s).
+    pub fn add_sum(
+        &mut self,
+        name: Option<&str>,
+        term: Box<dyn AmpLike<F>>,
+    ) -> Result<(), RustitudeError> {
+        if !self.history.is_empty() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "coherent sums must be added before any model operations".to_string(),
+            ));
+        }
+        let mut known: HashSet<String> = self
+            .amplitudes
             .read()
             .iter()
-            .find(|a: &&Amplitude<F>| a.name == amplitude_name)
-            .ok_or_else(|| RustitudeError::AmplitudeNotFoundError(amplitude_name.to_string()))
-            .cloned()
+            .map(|amp| amp.name.clone())
+            .collect();
+        let mut new_amplitudes = Vec::new();
+        for amp in term.walk() {
+            if !known.insert(amp.name.clone()) {
+                continue;
+            }
+            let index = self.get_min_free_index().unwrap_or(0);
+            self.parameters.extend(amp.new_parameters(index));
+            self.contains_python_amplitudes |= amp.node.is_python_node();
+            new_amplitudes.push(amp);
+        }
+        let mut cohsum = NormSqr::new(term);
+        cohsum.name = name.map(str::to_string);
+        self.cohsums.push(cohsum);
+        self.amplitudes.write().extend(new_amplitudes);
+        self.plan = None;
+        Ok(())
     }
-    /// Retrieves a copy of a [`Parameter`] in the [`Model`] by name.
+    /// Removes the `index`-th coherent sum from [`Model::cohsums`] and returns it.
+    ///
+    /// [`Amplitude`]s which are not used by any of the remaining coherent sums are removed
+    /// along with their [`Parameter`]s (so the indices of later free [`Parameter`]s shift down)
+    /// and any [`GaussianConstraint`]s, [`Penalty`] terms, and aliases which refer to them. The
+    /// [`Model`] stops sharing its [`Amplitude`]s with any clones.
     ///
     /// # Errors
-    /// This will throw a [`RustitudeError`] if the parameter name is not located within the model
-    /// or if the amplitude name is not located within the model (this is checked first).
-    pub fn get_parameter(
-        &self,
-        amplitude_name: &str,
-        parameter_name: &str,
-    ) -> Result<Parameter<F>, RustitudeError> {
-        self.get_amplitude(amplitude_name)?;
-        self.parameters
-            .iter()
-            .find(|p: &&Parameter<F>| p.amplitude == amplitude_name && p.name == parameter_name)
-            .ok_or_else(|| RustitudeError::ParameterNotFoundError(parameter_name.to_string()))
-            .cloned()
-    }
-    /// Pretty-prints all parameters in the model
-    pub fn print_parameters(&self) {
-        let any_fixed = if self.any_fixed() { 1 } else { 0 };
-        if self.any_fixed() {
-            println!(
-                "Fixed: {}",
-                self.group_by_index()[0]
-                    .iter()
-                    .map(|p| format!("{:?}", p))
-                    .join(", ")
-            );
+    ///
+    /// This method will return a [`RustitudeError::SumNotFoundError`] if `index` is out of range,
+    /// or a [`RustitudeError::InvalidParameterValue`] if any [`ModelOperation`] has already been
+    /// applied to the [`Model`] (since [`Model::undo`] cannot restore the removed
+    /// [`Parameter`]s).
+    pub fn remove_sum(&mut self, index: usize) -> Result<NormSqr<F>, RustitudeError> {
+        if index >= self.cohsums.len() {
+            return Err(RustitudeError::SumNotFoundError(index.to_string()));
         }
-        for (i, group) in self.group_by_index().iter().skip(any_fixed).enumerate() {
-            println!(
-                "{}: {}",
-                i,
-                group.iter().map(|p| format!("{:?}", p)).join(", ")
-            );
+        if !self.history.is_empty() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "coherent sums must be removed before any model operations".to_string(),
+            ));
         }
-    }
-
-    /// Returns a [`Vec<Parameter<F>>`] containing the free parameters in the [`Model`].
-    pub fn free_parameters(&self) -> Vec<Parameter<F>> {
-        self.parameters
+        let cohsum = self.cohsums.remove(index);
+        let used: HashSet<String> = self
+            .cohsums
             .iter()
-            .filter(|p| p.is_free())
-            .cloned()
-            .collect()
-    }
-
-    /// Returns a [`Vec<Parameter<F>>`] containing the fixed parameters in the [`Model`].
-    pub fn fixed_parameters(&self) -> Vec<Parameter<F>> {
-        self.parameters
+            .flat_map(NormSqr::walk)
+            .map(|amp| amp.name)
+            .collect();
+        let removed: HashSet<String> = cohsum
+            .walk()
+            .into_iter()
+            .map(|amp| amp.name)
+            .filter(|name| !used.contains(name))
+            .collect();
+        let amplitudes: Vec<Amplitude<F>> = self
+            .amplitudes
+            .read()
             .iter()
-            .filter(|p| p.is_fixed())
+            .filter(|amp| !removed.contains(&amp.name))
             .cloned()
-            .collect()
+            .collect();
+        self.contains_python_amplitudes = amplitudes.iter().any(|amp| amp.node.is_python_node());
+        self.amplitudes = Arc::new(RwLock::new(amplitudes));
+        self.parameters
+            .retain(|par| !removed.contains(&par.amplitude));
+        self.gaussian_constraints
+            .retain(|constraint| !removed.contains(&constraint.amplitude));
+        self.penalties.retain(|penalty| {
+            penalty
+                .parameters
+                .iter()
+                .all(|(amplitude, _)| !removed.contains(amplitude))
+        });
+        self.aliases
+            .retain(|(_, amplitude, _)| !removed.contains(amplitude));
+        self.reindex_parameters();
+        self.plan = None;
+        Ok(cohsum)
     }
-
-    /// Constrains two [`Parameter`]s in the [`Model`] to be equal to each other when evaluated.
+    /// Simplifies the coherent sums of the [`Model`] and compiles them into an evaluation plan
+    /// which [`Model::compute_into`] (and therefore [`Manager::evaluate`]) uses from then on.
+    ///
+    /// Directly nested [`Sum`]s and [`Product`]s are flattened, and subtrees which appear more
+    /// than once (within a coherent sum or across coherent sums, such as a common production
+    /// factor shared by several waves) are evaluated once per event. [`Amplitude`]s which are
+    /// only used by deactivated coherent sums are not calculated. The plan does not depend on
+    /// which [`Amplitude`]s or coherent sums are active, but this method should be called again
+    /// after any coherent sums are added or modified, since the plan is otherwise ignored.
+    ///
+    /// [`Manager::evaluate`]: crate::manager::Manager::evaluate
     ///
     /// # Errors
     ///
-    /// This method will yield a [`RustitudeError`] if either of the parameters is not found by
-    /// name.
-    pub fn constrain(
-        &mut self,
-        amplitude_1: &str,
-        parameter_1: &str,
-        amplitude_2: &str,
-        parameter_2: &str,
-    ) -> Result<(), RustitudeError> {
-        let p1 = self.get_parameter(amplitude_1, parameter_1)?;
-        let p2 = self.get_parameter(amplitude_2, parameter_2)?;
-        for par in self.parameters.iter_mut() {
-            // None < Some(0)
-            match p1.index.cmp(&p2.index) {
-                // p1 < p2
-                std::cmp::Ordering::Less => {
-                    if par.index == p2.index {
-                        par.index = p1.index;
-                        par.initial = p1.initial;
-                        par.fixed_index = p1.fixed_index;
-                    }
-                }
-                std::cmp::Ordering::Equal => unimplemented!(),
-                // p2 < p1
-                std::cmp::Ordering::Greater => {
-                    if par.index == p1.index {
-                        par.index = p2.index;
-                        par.initial = p2.initial;
-                        par.fixed_index = p2.fixed_index;
-                    }
-                }
-            }
+    /// This method will return a [`RustitudeError::AmplitudeNotFoundError`] if a coherent sum
+    /// refers to an [`Amplitude`] which is not in the [`Model`].
+    pub fn optimize(&mut self) -> Result<(), RustitudeError>
+    where
+        F: 'static,
+    {
+        let amplitudes: HashMap<String, Amplitude<F>> = self
+            .amplitudes
+            .read()
+            .iter()
+            .map(|amp| (amp.name.clone(), amp.clone()))
+            .collect();
+        let names: Vec<String> = self
+            .amplitudes
+            .read()
+            .iter()
+            .map(|amp| amp.name.clone())
+            .collect();
+        let trees: Vec<AmpTree> = self
+            .cohsums
+            .iter()
+            .map(|cohsum| flatten(cohsum.to_amp_tree()))
+            .collect();
+        for (cohsum, tree) in self.cohsums.iter_mut().zip(&trees) {
+            cohsum.term = tree.build(&amplitudes)?;
         }
-        self.reindex_parameters();
+        self.plan = Some(Arc::new(EvaluationPlan::new(&trees, &names)?));
         Ok(())
     }
-
-    /// Fixes a [`Parameter`] in the [`Model`] to a given value.
+    /// Returns `true` if the [`Model`] has an evaluation plan built by [`Model::optimize`].
+    pub const fn is_optimized(&self) -> bool {
+        self.plan.is_some()
+    }
+    /// Creates a true clone (deep copy) of the [`Model`] where the `amplitudes` field is
+    /// duplicated rather than having its reference count increased.
+    pub fn deep_clone(&self) -> Self {
+        Self {
+            cohsums: self.cohsums.clone(),
+            amplitudes: Arc::new(RwLock::new(self.amplitudes.read().clone())),
+            parameters: self.parameters.clone(),
+            gaussian_constraints: self.gaussian_constraints.clone(),
+            penalties: self.penalties.clone(),
+            contains_python_amplitudes: self.contains_python_amplitudes,
+            history: self.history.clone(),
+            aliases: self.aliases.clone(),
+            plan: self.plan.clone(),
+        }
+    }
+    /// Passes the expression tree of each coherent sum in the [`Model`] to a [`Visitor`] in
+    /// depth-first order. The root of each tree is an [`AmpNode::NormSqr`].
+    pub fn visit(&self, visitor: &mut impl Visitor<F>) {
+        for (index, cohsum) in self.cohsums.iter().enumerate() {
+            cohsum.accept(visitor, index);
+        }
+    }
+    /// Returns a LaTeX formula for the intensity of the [`Model`], the sum of the absolute
+    /// squares of its coherent sums.
     ///
-    /// This method technically sets the [`Parameter`] to be fixed and gives it an initial value of
-    /// the given value. This method also handles groups of constrained parameters.
+    /// Each [`Amplitude`] is written as its name followed by the names of its parameters, where
+    /// fixed parameters are shown with their values. Inactive [`Amplitude`]s are included, since
+    /// the formula describes the structure of the [`Model`] rather than a particular evaluation.
     ///
-    /// # Errors
+    /// # Example:
     ///
-    /// This method yields a [`RustitudeError`] if the parameter is not found by name.
-    pub fn fix(
-        &mut self,
-        amplitude: &str,
-        parameter: &str,
-        value: F,
-    ) -> Result<(), RustitudeError> {
-        let search_par = self.get_parameter(amplitude, parameter)?;
-        let fixed_index = self.get_min_fixed_index();
-        for par in self.parameters.iter_mut() {
-            if par.index == search_par.index {
-                par.index = None;
-                par.initial = value;
-                par.fixed_index = fixed_index;
-            }
-        }
-        self.reindex_parameters();
-        Ok(())
+    /// ```
+    /// use rustitude_core::prelude::*;
+    ///
+    /// let mut model = model!((scalar("a") * cscalar("b_1")).real());
+    /// model.fix("b_1", "imag", 0.0).unwrap();
+    /// assert_eq!(
+    ///     model.to_latex(),
+    ///     r"\left|\Re\left[\text{a}\left(\text{value}\right) \text{b\_1}\left(\text{real}, \text{imag} = 0\right)\right]\right|^2"
+    /// );
+    /// ```
+    pub fn to_latex(&self) -> String {
+        let mut visitor = LatexVisitor {
+            parameters: &self.parameters,
+            stack: vec![Vec::new()],
+        };
+        self.visit(&mut visitor);
+        visitor
+            .stack
+            .pop()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(term, _)| term)
+            .collect::<Vec<_>>()
+            .join(" + ")
     }
-    /// Frees a [`Parameter`] in the [`Model`].
+    /// Returns the expression tree of the [`Model`] (see [`AsTree::get_tree`]) as a Graphviz
+    /// `digraph`.
     ///
-    /// This method does not modify the initial value of the parameter. This method
-    /// also handles groups of constrained parameters.
+    /// Each [`Amplitude`] is labeled with its name and the names of its parameters, where fixed
+    /// parameters are shown with their values. Inactive [`Amplitude`]s and coherent sums are drawn
+    /// with dashed outlines.
     ///
-    /// # Errors
+    /// # Example:
     ///
-    /// This method yields a [`RustitudeError`] if the parameter is not found by name.
-    pub fn free(&mut self, amplitude: &str, parameter: &str) -> Result<(), RustitudeError> {
-        let search_par = self.get_parameter(amplitude, parameter)?;
-        let index = self.get_min_free_index();
-        for par in self.parameters.iter_mut() {
-            if par.fixed_index == search_par.fixed_index {
-                par.index = index;
-                par.fixed_index = None;
+    /// ```
+    /// use rustitude_core::prelude::*;
+    ///
+    /// let mut model: Model<f64> = model!(scalar("a"));
+    /// model.deactivate("a").unwrap();
+    /// assert_eq!(
+    ///     model.to_dot(),
+    ///     concat!(
+    ///         "digraph {\n",
+    ///         "    node [shape=box];\n",
+    ///         "    n0 [label=\"+\"];\n",
+    ///         "    n1 [label=\"|_|^2\"];\n",
+    ///         "    n2 [label=\"a\\nvalue\", style=dashed];\n",
+    ///         "    n0 -> n1;\n",
+    ///         "    n1 -> n2;\n",
+    ///         "}\n"
+    ///     )
+    /// );
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let graph = GraphVisitor::new(self);
+        let mut res = String::from("digraph {\n    node [shape=box];\n");
+        for (id, (label, active)) in graph.nodes.iter().enumerate() {
+            let label = label
+                .iter()
+                .map(|line| line.replace('\\', r"\\").replace('"', "\\\""))
+                .join(r"\n");
+            if *active {
+                res.push_str(&format!("    n{id} [label=\"{label}\"];\n"));
+            } else {
+                res.push_str(&format!("    n{id} [label=\"{label}\", style=dashed];\n"));
             }
         }
-        self.reindex_parameters();
-        Ok(())
+        for (parent, child) in &graph.edges {
+            res.push_str(&format!("    n{parent} -> n{child};\n"));
+        }
+        res.push_str("}\n");
+        res
     }
-    /// Sets the bounds on a [`Parameter`] in the [`Model`].
+    /// Returns the expression tree of the [`Model`] as a Mermaid flowchart, labeled in the same
+    /// way as [`Model::to_dot`]. Inactive [`Amplitude`]s and coherent sums are given the
+    /// `inactive` class, which draws them with dashed outlines.
+    pub fn to_mermaid(&self) -> String {
+        let graph = GraphVisitor::new(self);
+        let mut res = String::from("flowchart TD\n");
+        for (id, (label, _)) in graph.nodes.iter().enumerate() {
+            let label = label
+                .iter()
+                .map(|line| line.replace('"', "#quot;"))
+                .join("<br/>");
+            res.push_str(&format!("    n{id}[\"{label}\"]\n"));
+        }
+        for (parent, child) in &graph.edges {
+            res.push_str(&format!("    n{parent} --> n{child}\n"));
+        }
+        let inactive: Vec<String> = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, active))| !active)
+            .map(|(id, _)| format!("n{id}"))
+            .collect();
+        if !inactive.is_empty() {
+            res.push_str("    classDef inactive stroke-dasharray: 5 5\n");
+            res.push_str(&format!("    class {} inactive\n", inactive.join(",")));
+        }
+        res
+    }
+    /// Computes the result of evaluating the terms in the model with the given [`Parameter`]s for
+    /// the given [`Event`] by summing the result of [`NormSqr::compute`] for each [`NormSqr`]
+    /// contained in the [`Model`] (see the `cohsum` field of [`Model`]).
     ///
     /// # Errors
     ///
-    /// This method yields a [`RustitudeError`] if the parameter is not found by name.
-    pub fn set_bounds(
-        &mut self,
-        amplitude: &str,
-        parameter: &str,
-        bounds: (F, F),
-    ) -> Result<(), RustitudeError> {
-        let search_par = self.get_parameter(amplitude, parameter)?;
-        if search_par.index.is_some() {
-            for par in self.parameters.iter_mut() {
-                if par.index == search_par.index {
-                    par.bounds = bounds;
-                }
-            }
-        } else {
-            for par in self.parameters.iter_mut() {
-                if par.fixed_index == search_par.fixed_index {
-                    par.bounds = bounds;
+    /// This method yields a [`RustitudeError`] if any of the [`Amplitude::calculate`] steps fail.
+    pub fn compute(
+        &self,
+        amplitudes: &[Amplitude<F>],
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<F, RustitudeError> {
+        self.compute_precomputed(amplitudes, &[], parameters, event)
+    }
+    /// Calculates every active [`Amplitude`] whose [`Node::is_event_independent`] returns `true`
+    /// with the given [`Parameter`]s. The [`Event`] is passed along to [`Amplitude::calculate`]
+    /// and is usually the first [`Event`] of a [`Dataset`].
+    ///
+    /// The result has an entry for each [`Amplitude`], which is [`None`] for those which must be
+    /// calculated for each [`Event`], and can be passed to [`Model::compute_precomputed`] to
+    /// avoid recalculating the rest for every [`Event`] in a [`Dataset`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if any of the [`Amplitude::calculate`] steps fail.
+    pub fn precompute(
+        &self,
+        amplitudes: &[Amplitude<F>],
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<Vec<Option<Complex<F>>>, RustitudeError> {
+        amplitudes
+            .iter()
+            .map(|amp| {
+                if amp.active && amp.node.is_event_independent() {
+                    amp.calculate(parameters, event).map(Some)
+                } else {
+                    Ok(None)
                 }
+            })
+            .collect()
+    }
+    /// Computes the same value as [`Model::compute`], using the values of any [`Amplitude`]s
+    /// which were already calculated by [`Model::precompute`]. An empty `precomputed` slice
+    /// calculates every [`Amplitude`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if any of the [`Amplitude::calculate`] steps fail.
+    pub fn compute_precomputed(
+        &self,
+        amplitudes: &[Amplitude<F>],
+        precomputed: &[Option<Complex<F>>],
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<F, RustitudeError> {
+        let mut scratch = Vec::with_capacity(amplitudes.len());
+        self.compute_into(amplitudes, precomputed, parameters, event, &mut scratch)
+    }
+    /// Computes the same value as [`Model::compute_precomputed`], storing the value of each
+    /// [`Amplitude`] in a reusable `scratch` buffer rather than allocating a new one. Loops over
+    /// [`Event`]s should keep one buffer per thread, since its previous contents are discarded.
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if any of the [`Amplitude::calculate`] steps fail.
+    pub fn compute_into(
+        &self,
+        amplitudes: &[Amplitude<F>],
+        precomputed: &[Option<Complex<F>>],
+        parameters: &[F],
+        event: &Event<F>,
+        scratch: &mut Vec<Option<Complex<F>>>,
+    ) -> Result<F, RustitudeError> {
+        // NOTE: This seems to be just as fast as using a Vec<ComplexField> and replacing active
+        // amplitudes by multiplying their cached values by 0.0. Branch prediction doesn't get us
+        // any performance here I guess.
+        scratch.clear();
+        if let Some(plan) = self.plan.as_ref().filter(|plan| {
+            plan.n_amplitudes == amplitudes.len() && plan.roots.len() == self.cohsums.len()
+        }) {
+            // Amplitudes which are only used by inactive coherent sums are not calculated
+            for (i, amp) in amplitudes.iter().enumerate() {
+                scratch.push(if let Some(Some(value)) = precomputed.get(i) {
+                    Some(*value)
+                } else if amp.active && plan.users[i].iter().any(|&c| self.cohsums[c].active) {
+                    Some(amp.calculate(parameters, event)?)
+                } else {
+                    None
+                });
             }
+            plan.evaluate(scratch);
+            return Ok(self
+                .cohsums
+                .iter()
+                .zip(&plan.roots)
+                .filter(|(cohsum, _)| cohsum.active)
+                .filter_map(|(_, root)| scratch[*root].map(|value| value.norm_sqr()))
+                .sum::<F>());
         }
-        Ok(())
+        for (i, amp) in amplitudes.iter().enumerate() {
+            scratch.push(if let Some(Some(value)) = precomputed.get(i) {
+                Some(*value)
+            } else if amp.active {
+                Some(amp.calculate(parameters, event)?)
+            } else {
+                None
+            });
+        }
+        Ok(self
+            .cohsums
+            .iter()
+            .filter_map(|cohsum| cohsum.compute(scratch))
+            .sum::<F>())
     }
-    /// Sets the initial value of a [`Parameter`] in the [`Model`].
+    /// Computes the [`Model`] over a contiguous batch of [`Event`]s, writing one value per
+    /// [`Event`] into `out`.
+    ///
+    /// Each active [`Amplitude`] which was not precomputed is evaluated over the whole batch with
+    /// [`Node::calculate_batch`] before the coherent sums are combined, so [`Node`]s which
+    /// override that method are evaluated in vectorizable loops rather than one [`Event`] at a
+    /// time.
     ///
     /// # Errors
     ///
-    /// This method yields a [`RustitudeError`] if the parameter is not found by name.
-    pub fn set_initial(
-        &mut self,
-        amplitude: &str,
-        parameter: &str,
-        initial: F,
+    /// This method yields a [`RustitudeError`] if any of the [`Node::calculate_batch`] steps fail.
+    pub fn compute_batch(
+        &self,
+        amplitudes: &[Amplitude<F>],
+        precomputed: &[Option<Complex<F>>],
+        parameters: &[F],
+        events: &[Event<F>],
+        out: &mut [F],
     ) -> Result<(), RustitudeError> {
-        let search_par = self.get_parameter(amplitude, parameter)?;
-        if search_par.index.is_some() {
-            for par in self.parameters.iter_mut() {
-                if par.index == search_par.index {
-                    par.initial = initial;
-                }
-            }
-        } else {
-            for par in self.parameters.iter_mut() {
-                if par.fixed_index == search_par.fixed_index {
-                    par.initial = initial;
+        let columns = amplitudes
+            .iter()
+            .enumerate()
+            .map(|(i, amp)| {
+                if matches!(precomputed.get(i), Some(Some(_))) || !amp.active {
+                    return Ok(None);
                 }
-            }
+                let mut column = vec![Complex::default(); events.len()];
+                amp.calculate_batch(parameters, events, &mut column)?;
+                Ok(Some(column))
+            })
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        let mut scratch = Vec::with_capacity(amplitudes.len());
+        for (j, value) in out.iter_mut().enumerate() {
+            scratch.clear();
+            scratch.extend(columns.iter().enumerate().map(|(i, column)| {
+                precomputed
+                    .get(i)
+                    .copied()
+                    .flatten()
+                    .or_else(|| column.as_ref().map(|column| column[j]))
+            }));
+            *value = self
+                .cohsums
+                .iter()
+                .filter_map(|cohsum| cohsum.compute(&scratch))
+                .sum::<F>();
         }
         Ok(())
     }
-    /// Returns a list of bounds of free [`Parameter`]s in the [`Model`].
-    pub fn get_bounds(&self) -> Vec<(F, F)> {
-        let any_fixed = if self.any_fixed() { 1 } else { 0 };
-        self.group_by_index()
+    /// Computes the interference $`2\text{Re}(A B^*)`$ between two groups of [`Amplitude`]s for
+    /// the given [`Event`], summed over each [`NormSqr`] in the [`Model`].
+    ///
+    /// Here $`A`$ ($`B`$) is the value of the coherent sum when every [`Amplitude`] outside of
+    /// the first (second) group is set to zero. Unlike deactivating an [`Amplitude`] (which drops
+    /// it from any [`Product`]), setting it to zero removes every term which contains it, so a
+    /// group should list all of the factors of its terms. [`Amplitude`]s may appear in both
+    /// groups, such as a shared angular distribution. Inactive [`Amplitude`]s are ignored as in
+    /// [`Model::compute`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if any of the [`Amplitude::calculate`] steps fail.
+    pub fn compute_interference(
+        &self,
+        amplitudes: &[Amplitude<F>],
+        parameters: &[F],
+        event: &Event<F>,
+        amplitudes_a: &[&str],
+        amplitudes_b: &[&str],
+    ) -> Result<F, RustitudeError> {
+        let cache: Vec<Option<Complex<F>>> = amplitudes
             .iter()
-            .skip(any_fixed)
-            .filter_map(|group| group.first().map(|par| par.bounds))
+            .map(|amp| {
+                if amp.active {
+                    amp.calculate(parameters, event).map(Some)
+                } else {
+                    Ok(None)
+                }
+            })
+            .collect::<Result<Vec<Option<Complex<F>>>, RustitudeError>>()?;
+        let masked = |group: &[&str]| -> Vec<Option<Complex<F>>> {
+            cache
+                .iter()
+                .zip(amplitudes)
+                .map(|(value, amp)| {
+                    value.map(|v| {
+                        if group.contains(&amp.name.as_str()) {
+                            v
+                        } else {
+                            Complex::default()
+                        }
+                    })
+                })
+                .collect()
+        };
+        let (cache_a, cache_b) = (masked(amplitudes_a), masked(amplitudes_b));
+        Ok(self
+            .cohsums
+            .iter()
+            .filter(|cohsum| cohsum.active)
+            .filter_map(|cohsum| {
+                let a = cohsum.term.compute(&cache_a)?;
+                let b = cohsum.term.compute(&cache_b)?;
+                Some(convert!(2, F) * (a * b.conj()).re)
+            })
+            .sum::<F>())
+    }
+    /// Computes the same value as [`Model::compute`] along with its gradient with respect to
+    /// every [`Parameter`] in the [`Model`] (in the order of [`Model::parameters`]).
+    ///
+    /// Each [`Amplitude`]'s derivatives come from [`Node::calculate_gradient`] and are propagated
+    /// analytically through the [`AmpLike`] operations.
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if any of the [`Amplitude::calculate`] or
+    /// [`Amplitude::calculate_gradient`] steps fail.
+    pub fn compute_gradient(
+        &self,
+        amplitudes: &[Amplitude<F>],
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<(F, Vec<F>), RustitudeError> {
+        self.compute_gradient_precomputed(amplitudes, &[], parameters, event)
+    }
+    /// Gradient version of [`Model::precompute`], which calculates both the value and the
+    /// [`Node::calculate_gradient`] of every active event-independent [`Amplitude`] for the given
+    /// [`Event`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if any of the [`Amplitude::calculate`] or
+    /// [`Amplitude::calculate_gradient`] steps fail.
+    #[allow(clippy::type_complexity)]
+    pub fn precompute_gradient(
+        &self,
+        amplitudes: &[Amplitude<F>],
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<Vec<Option<(Complex<F>, Vec<Complex<F>>)>>, RustitudeError> {
+        amplitudes
+            .iter()
+            .map(|amp| {
+                if amp.active && amp.node.is_event_independent() {
+                    Ok(Some((
+                        amp.calculate(parameters, event)?,
+                        amp.calculate_gradient(parameters, event)?,
+                    )))
+                } else {
+                    Ok(None)
+                }
+            })
             .collect()
     }
-    /// Returns a list of initial values of free [`Parameter`]s in the [`Model`].
-    pub fn get_initial(&self) -> Vec<F> {
-        let any_fixed = if self.any_fixed() { 1 } else { 0 };
-        self.group_by_index()
+    /// Computes the same value and gradient as [`Model::compute_gradient`], using the results of
+    /// [`Model::precompute_gradient`]. An empty `precomputed` slice calculates every
+    /// [`Amplitude`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if any of the [`Amplitude::calculate`] or
+    /// [`Amplitude::calculate_gradient`] steps fail.
+    #[allow(clippy::type_complexity)]
+    pub fn compute_gradient_precomputed(
+        &self,
+        amplitudes: &[Amplitude<F>],
+        precomputed: &[Option<(Complex<F>, Vec<Complex<F>>)>],
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<(F, Vec<F>), RustitudeError> {
+        let mut cache: Vec<Option<Complex<F>>> = Vec::with_capacity(amplitudes.len());
+        let mut gradient_cache: Vec<Option<Vec<Complex<F>>>> = Vec::with_capacity(amplitudes.len());
+        for (i, amp) in amplitudes.iter().enumerate() {
+            if let Some(Some((value, gradient))) = precomputed.get(i) {
+                cache.push(Some(*value));
+                gradient_cache.push(Some(gradient.clone()));
+            } else if amp.active {
+                cache.push(Some(amp.calculate(parameters, event)?));
+                gradient_cache.push(Some(amp.calculate_gradient(parameters, event)?));
+            } else {
+                cache.push(None);
+                gradient_cache.push(None);
+            }
+        }
+        let mut value = F::zero();
+        let mut gradient = vec![F::zero(); parameters.len()];
+        for (cohsum_value, cohsum_gradient) in self
+            .cohsums
             .iter()
-            .skip(any_fixed)
-            .filter_map(|group| group.first().map(|par| par.initial))
+            .filter_map(|cohsum| cohsum.compute_gradient(&cache, &gradient_cache))
+        {
+            value += cohsum_value;
+            gradient
+                .iter_mut()
+                .zip(cohsum_gradient)
+                .for_each(|(g, c)| *g += c);
+        }
+        Ok((value, gradient))
+    }
+    /// Projects a gradient with respect to every [`Parameter`] in the [`Model`] (see
+    /// [`Model::compute_gradient`]) onto the free parameters.
+    ///
+    /// Fixed parameters are dropped and the derivatives of constrained parameters, which share a
+    /// free index, are summed. Derivatives of parameters with a [`Transform`] are multiplied by
+    /// $`dx/du`$ at the given free parameters.
+    pub fn free_gradient(&self, parameters: &[F], gradient: &[F]) -> Vec<F> {
+        let mut free = vec![F::zero(); self.get_n_free()];
+        for (parameter, g) in self.parameters.iter().zip(gradient) {
+            if let Some(i) = parameter.index {
+                free[i] += *g * parameter.derivative(parameters[i]);
+            }
+        }
+        free
+    }
+    /// Returns the physical value of every [`Parameter`] in the [`Model`] (in the order of
+    /// [`Model::parameters`]) given the free parameters. Fixed parameters take their fixed value
+    /// and any [`Transform`]s are applied.
+    pub fn physical_parameters(&self, parameters: &[F]) -> Vec<F> {
+        self.parameters
+            .iter()
+            .map(|p| p.value(parameters))
             .collect()
     }
-    /// Returns the number of free [`Parameter`]s in the [`Model`].
-    pub fn get_n_free(&self) -> usize {
-        self.get_min_free_index().unwrap_or(0)
+    /// Registers the [`Model`] with the [`Dataset`] by [`Amplitude::register`]ing each
+    /// [`Amplitude`] and setting the proper cache position and parameter starting index.
+    ///
+    /// Each [`SharedQuantity`] requested by the [`Node::requirements`] of the [`Amplitude`]s is
+    /// computed once and passed to every [`Node`] which requested it. If the global
+    /// [`PrecalculationCache`] is enabled, precalculated [`Node`]s are shared with any other
+    /// [`Model`] loaded with the same [`Dataset`].
+    ///
+    /// # Errors
+    ///
+    /// This method will yield a [`RustitudeError`] if any [`Amplitude::precalculate`] steps fail.
+    pub fn load(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        let mut next_cache_pos = 0;
+        let mut parameter_index = 0;
+        let cache = PrecalculationCache::global();
+        let fingerprint = cache.is_enabled().then(|| dataset.fingerprint());
+        let mut shared = SharedData::default();
+        self.amplitudes.write().iter_mut().try_for_each(|amp| {
+            amp.cache_position = next_cache_pos;
+            amp.parameter_index_start = parameter_index;
+            if let Some(fingerprint) = fingerprint {
+                cache.precalculate_shared(amp, fingerprint, dataset, &mut shared)?;
+            } else {
+                amp.precalculate_with(dataset, &mut shared)?;
+            }
+            self.cohsums.iter_mut().for_each(|cohsum| {
+                cohsum.walk_mut().iter_mut().for_each(|r_amp| {
+                    if r_amp.name == amp.name {
+                        r_amp.cache_position = next_cache_pos;
+                        r_amp.parameter_index_start = parameter_index;
+                    }
+                })
+            });
+            next_cache_pos += 1;
+            parameter_index += amp.parameters().len();
+            Ok(())
+        })
     }
-    /// Activates an [`Amplitude`] in the [`Model`] by name.
+
+    /// Retrieves a copy of an [`Amplitude`] in the [`Model`] by name.
     ///
     /// # Errors
+    /// This will throw a [`RustitudeError`] if the amplitude name is not located within the model.
+    pub fn get_amplitude(&self, amplitude_name: &str) -> Result<Amplitude<F>, RustitudeError> {
+        self.amplitudes
+            .read()
+            .iter()
+            .find(|a: &&Amplitude<F>| a.name == amplitude_name)
+            .ok_or_else(|| RustitudeError::AmplitudeNotFoundError(amplitude_name.to_string()))
+            .cloned()
+    }
+    /// Renames an [`Amplitude`] in the [`Model`].
     ///
-    /// This function will return a [`RustitudeError::AmplitudeNotFoundError`] if the given
-    /// amplitude is not present in the [`Model`].
-    pub fn activate(&mut self, amplitude: &str) -> Result<(), RustitudeError> {
-        if !self.amplitudes.read().iter().any(|a| a.name == amplitude) {
-            return Err(RustitudeError::AmplitudeNotFoundError(
-                amplitude.to_string(),
-            ));
+    /// The new name is used everywhere the old one was, including by the [`Parameter`]s,
+    /// [`GaussianConstraint`]s, [`Penalty`] terms, and aliases (see [`Model::alias`]) of the
+    /// [`Amplitude`] and by the operations in the [`Model::history`], so they can still be undone
+    /// or replayed onto [`Model`]s which were renamed in the same way.
+    ///
+    /// Like [`Model::activate`], this changes the [`Amplitude`]s shared by any clones of the
+    /// [`Model`] (but not their [`Parameter`]s), so it should be done before the [`Model`] is
+    /// used to build a [`Manager`](crate::manager::Manager).
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::AmplitudeNotFoundError`] if no amplitude is
+    /// named `old`, or a [`RustitudeError::InvalidParameterValue`] if another amplitude is
+    /// already named `new`.
+    pub fn rename_amplitude(&mut self, old: &str, new: &str) -> Result<(), RustitudeError> {
+        self.get_amplitude(old)?;
+        if old == new {
+            return Ok(());
         }
-        self.amplitudes.write().iter_mut().for_each(|amp| {
-            if amp.name == amplitude {
-                amp.active = true
+        if self.get_amplitude(new).is_ok() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "an amplitude named \"{new}\" already exists"
+            )));
+        }
+        let rename = |amplitude: &mut String| {
+            if amplitude == old {
+                *amplitude = new.to_string();
             }
-        });
-        self.cohsums.iter_mut().for_each(|cohsum| {
-            cohsum.walk_mut().iter_mut().for_each(|amp| {
-                if amp.name == amplitude {
-                    amp.active = true
-                }
-            })
-        });
-        Ok(())
-    }
-    /// Activates all [`Amplitude`]s in the [`Model`].
-    pub fn activate_all(&mut self) {
+        };
         self.amplitudes
             .write()
             .iter_mut()
-            .for_each(|amp| amp.active = true);
+            .for_each(|amp| rename(&mut amp.name));
         self.cohsums.iter_mut().for_each(|cohsum| {
             cohsum
                 .walk_mut()
+                .into_iter()
+                .for_each(|amp| rename(&mut amp.name))
+        });
+        self.parameters
+            .iter_mut()
+            .for_each(|par| rename(&mut par.amplitude));
+        self.gaussian_constraints
+            .iter_mut()
+            .for_each(|constraint| rename(&mut constraint.amplitude));
+        self.penalties.iter_mut().for_each(|penalty| {
+            penalty
+                .parameters
                 .iter_mut()
-                .for_each(|amp| amp.active = true)
+                .for_each(|(amplitude, _)| rename(amplitude))
         });
-    }
-    /// Activate only the specified [`Amplitude`]s while deactivating the rest.
-    ///
-    /// # Errors
-    ///
-    /// This function will return a [`RustitudeError::AmplitudeNotFoundError`] if a given
-    /// amplitude is not present in the [`Model`].
-    pub fn isolate(&mut self, amplitudes: Vec<&str>) -> Result<(), RustitudeError> {
-        self.deactivate_all();
-        for amplitude in amplitudes {
-            self.activate(amplitude)?;
+        self.aliases
+            .iter_mut()
+            .for_each(|(_, amplitude, _)| rename(amplitude));
+        for entry in &mut self.history {
+            entry.operation.rename_amplitude(old, new);
+            entry
+                .parameters
+                .iter_mut()
+                .for_each(|par| rename(&mut par.amplitude));
+            entry
+                .gaussian_constraints
+                .iter_mut()
+                .for_each(|constraint| rename(&mut constraint.amplitude));
         }
         Ok(())
     }
-    /// Deactivates an [`Amplitude`] in the [`Model`] by name.
+    /// Registers `alias` as a friendly name for a [`Parameter`] in the [`Model`].
+    ///
+    /// Several [`Parameter`]s may share an alias. Wherever a pattern is accepted (see
+    /// [`Model::matching_parameters`]), a pattern which is exactly equal to an alias matches the
+    /// aliased [`Parameter`]s (in the order they were aliased) instead, so for example
+    /// [`Model::constrain_matching`] constrains every [`Parameter`] with a given alias and
+    /// [`Model::fix_matching`] fixes them.
     ///
     /// # Errors
     ///
-    /// This function will return a [`RustitudeError::AmplitudeNotFoundError`] if the given
-    /// amplitude is not present in the [`Model`].
-    pub fn deactivate(&mut self, amplitude: &str) -> Result<(), RustitudeError> {
-        if !self.amplitudes.read().iter().any(|a| a.name == amplitude) {
-            return Err(RustitudeError::AmplitudeNotFoundError(
-                amplitude.to_string(),
-            ));
+    /// This method will return a [`RustitudeError`] if the parameter is not found by name.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use rustitude_core::prelude::*;
+    ///
+    /// let mut model: Model<f64> = model!(scalar("wave 0 (+)"), scalar("wave 0 (-)"));
+    /// model.alias("f0 yield", "wave 0 (+)", "value").unwrap();
+    /// model.alias("f0 yield", "wave 0 (-)", "value").unwrap();
+    /// model.constrain_matching("f0 yield").unwrap();
+    /// assert_eq!(model.get_n_free(), 1);
+    /// ```
+    pub fn alias(
+        &mut self,
+        alias: &str,
+        amplitude: &str,
+        parameter: &str,
+    ) -> Result<(), RustitudeError> {
+        self.get_parameter(amplitude, parameter)?;
+        let entry = (
+            alias.to_string(),
+            amplitude.to_string(),
+            parameter.to_string(),
+        );
+        if !self.aliases.contains(&entry) {
+            self.aliases.push(entry);
         }
-        self.amplitudes.write().iter_mut().for_each(|amp| {
-            if amp.name == amplitude {
-                amp.active = false
-            }
-        });
-        self.cohsums.iter_mut().for_each(|cohsum| {
-            cohsum.walk_mut().iter_mut().for_each(|amp| {
-                if amp.name == amplitude {
-                    amp.active = false
-                }
-            })
-        });
         Ok(())
     }
-    /// Deactivates all [`Amplitude`]s in the [`Model`].
-    pub fn deactivate_all(&mut self) {
-        self.amplitudes
-            .write()
-            .iter_mut()
-            .for_each(|amp| amp.active = false);
-        self.cohsums.iter_mut().for_each(|cohsum| {
-            cohsum
-                .walk_mut()
-                .iter_mut()
-                .for_each(|amp| amp.active = false)
-        });
+    /// Removes an alias registered with [`Model::alias`] from every [`Parameter`] which has it.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if no parameter has
+    /// the given alias.
+    pub fn remove_alias(&mut self, alias: &str) -> Result<(), RustitudeError> {
+        let n_aliases = self.aliases.len();
+        self.aliases.retain(|(name, _, _)| name != alias);
+        if self.aliases.len() == n_aliases {
+            return Err(RustitudeError::ParameterNotFoundError(alias.to_string()));
+        }
+        Ok(())
     }
-    fn group_by_index(&self) -> Vec<Vec<&Parameter<F>>> {
-        self.parameters
-            .iter()
-            .sorted_by_key(|par| par.index)
-            .chunk_by(|par| par.index)
-            .into_iter()
-            .map(|(_, group)| group.collect::<Vec<_>>())
-            .collect()
+    /// Returns the `(alias, amplitude, parameter)` names of every alias registered with
+    /// [`Model::alias`].
+    pub fn aliases(&self) -> &[(String, String, String)] {
+        &self.aliases
     }
-    fn group_by_index_mut(&mut self) -> Vec<Vec<&mut Parameter<F>>> {
+    /// Retrieves a copy of a [`Parameter`] in the [`Model`] by name.
+    ///
+    /// # Errors
+    /// This will throw a [`RustitudeError`] if the parameter name is not located within the model
+    /// or if the amplitude name is not located within the model (this is checked first).
+    pub fn get_parameter(
+        &self,
+        amplitude_name: &str,
+        parameter_name: &str,
+    ) -> Result<Parameter<F>, RustitudeError> {
+        self.get_amplitude(amplitude_name)?;
         self.parameters
-            .iter_mut()
-            .sorted_by_key(|par| par.index)
-            .chunk_by(|par| par.index)
-            .into_iter()
-            .map(|(_, group)| group.collect())
-            .collect()
-    }
-    fn any_fixed(&self) -> bool {
-        self.parameters.iter().any(|p| p.index.is_none())
+            .iter()
+            .find(|p: &&Parameter<F>| p.amplitude == amplitude_name && p.name == parameter_name)
+            .ok_or_else(|| RustitudeError::ParameterNotFoundError(parameter_name.to_string()))
+            .cloned()
     }
-    fn reindex_parameters(&mut self) {
+    /// Pretty-prints all parameters in the model
+    pub fn print_parameters(&self) {
         let any_fixed = if self.any_fixed() { 1 } else { 0 };
-        self.group_by_index_mut()
-            .iter_mut()
-            .skip(any_fixed) // first element could be index = None
-            .enumerate()
-            .for_each(|(ind, par_group)| par_group.iter_mut().for_each(|par| par.index = Some(ind)))
+        if self.any_fixed() {
+            println!(
+                "Fixed: {}",
+                self.group_by_index()[0]
+                    .iter()
+                    .map(|p| format!("{:?}", p))
+                    .join(", ")
+            );
+        }
+        for (i, group) in self.group_by_index().iter().skip(any_fixed).enumerate() {
+            println!(
+                "{}: {}",
+                i,
+                group.iter().map(|p| format!("{:?}", p)).join(", ")
+            );
+        }
     }
-    fn get_min_free_index(&self) -> Option<usize> {
+
+    /// Returns a [`Vec<Parameter<F>>`] containing the free parameters in the [`Model`].
+    pub fn free_parameters(&self) -> Vec<Parameter<F>> {
         self.parameters
             .iter()
-            .filter_map(|p| p.index)
-            .max()
-            .map_or(Some(0), |max| Some(max + 1))
+            .filter(|p| p.is_free())
+            .cloned()
+            .collect()
     }
-    fn get_min_fixed_index(&self) -> Option<usize> {
+
+    /// Returns a [`Vec<Parameter<F>>`] containing the fixed parameters in the [`Model`].
+    pub fn fixed_parameters(&self) -> Vec<Parameter<F>> {
         self.parameters
             .iter()
-            .filter_map(|p| p.fixed_index)
-            .max()
-            .map_or(Some(0), |max| Some(max + 1))
+            .filter(|p| p.is_fixed())
+            .cloned()
+            .collect()
+    }
+
+    /// Returns a [`Vec<Parameter<F>>`] containing the (free and fixed) parameters in the
+    /// [`Model`] whose names match a glob pattern or regular expression (see
+    /// [`Parameter::matches`]), or the parameters with the given alias if the pattern is an
+    /// alias (see [`Model::alias`]).
+    pub fn matching_parameters(&self, pattern: &str) -> Vec<Parameter<F>> {
+        if self.aliases.iter().any(|(alias, _, _)| alias == pattern) {
+            return self
+                .aliases
+                .iter()
+                .filter(|(alias, _, _)| alias == pattern)
+                .filter_map(|(_, amplitude, parameter)| {
+                    self.get_parameter(amplitude, parameter).ok()
+                })
+                .collect();
+        }
+        NamePattern::parse(pattern).map_or_else(
+            |_| Vec::new(),
+            |pattern| {
+                self.parameters
+                    .iter()
+                    .filter(|p| p.matches_pattern(&pattern))
+                    .cloned()
+                    .collect()
+            },
+        )
+    }
+
+    /// Returns the `(amplitude, parameter)` names of the [`Parameter`]s which match a pattern
+    /// (see [`Model::matching_parameters`]), a [`RustitudeError::ParseError`] if the pattern is
+    /// an invalid regular expression, or a [`RustitudeError::ParameterNotFoundError`] if there
+    /// are no matches.
+    fn matching_names(&self, pattern: &str) -> Result<Vec<(String, String)>, RustitudeError> {
+        if !self.aliases.iter().any(|(alias, _, _)| alias == pattern) {
+            NamePattern::parse(pattern)?;
+        }
+        let names: Vec<(String, String)> = self
+            .matching_parameters(pattern)
+            .into_iter()
+            .map(|p| (p.amplitude, p.name))
+            .collect();
+        if names.is_empty() {
+            return Err(RustitudeError::ParameterNotFoundError(pattern.to_string()));
+        }
+        Ok(names)
+    }
+
+    /// Constrains two [`Parameter`]s in the [`Model`] to be equal to each other when evaluated.
+    ///
+    /// # Errors
+    ///
+    /// This method will yield a [`RustitudeError`] if either of the parameters is not found by
+    /// name.
+    pub fn constrain(
+        &mut self,
+        amplitude_1: &str,
+        parameter_1: &str,
+        amplitude_2: &str,
+        parameter_2: &str,
+    ) -> Result<(), RustitudeError> {
+        let p1 = self.get_parameter(amplitude_1, parameter_1)?;
+        let p2 = self.get_parameter(amplitude_2, parameter_2)?;
+        self.record(ModelOperation::Constrain {
+            amplitude_1: amplitude_1.to_string(),
+            parameter_1: parameter_1.to_string(),
+            amplitude_2: amplitude_2.to_string(),
+            parameter_2: parameter_2.to_string(),
+        });
+        for par in self.parameters.iter_mut() {
+            // None < Some(0)
+            match p1.index.cmp(&p2.index) {
+                // p1 < p2
+                std::cmp::Ordering::Less => {
+                    if par.index == p2.index {
+                        par.index = p1.index;
+                        par.initial = p1.initial;
+                        par.fixed_index = p1.fixed_index;
+                    }
+                }
+                std::cmp::Ordering::Equal => unimplemented!(),
+                // p2 < p1
+                std::cmp::Ordering::Greater => {
+                    if par.index == p1.index {
+                        par.index = p2.index;
+                        par.initial = p2.initial;
+                        par.fixed_index = p2.fixed_index;
+                    }
+                }
+            }
+        }
+        self.reindex_parameters();
+        Ok(())
+    }
+
+    /// Fixes a [`Parameter`] in the [`Model`] to a given value.
+    ///
+    /// This method technically sets the [`Parameter`] to be fixed and gives it an initial value of
+    /// the given value. This method also handles groups of constrained parameters.
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if the parameter is not found by name.
+    pub fn fix(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        value: F,
+    ) -> Result<(), RustitudeError> {
+        let search_par = self.get_parameter(amplitude, parameter)?;
+        self.record(ModelOperation::Fix {
+            amplitude: amplitude.to_string(),
+            parameter: parameter.to_string(),
+            value,
+        });
+        let fixed_index = self.get_min_fixed_index();
+        for par in self.parameters.iter_mut() {
+            if par.index == search_par.index {
+                par.index = None;
+                par.initial = value;
+                par.fixed_index = fixed_index;
+            }
+        }
+        self.reindex_parameters();
+        Ok(())
+    }
+    /// Frees a [`Parameter`] in the [`Model`].
+    ///
+    /// This method does not modify the initial value of the parameter. This method
+    /// also handles groups of constrained parameters.
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if the parameter is not found by name.
+    pub fn free(&mut self, amplitude: &str, parameter: &str) -> Result<(), RustitudeError> {
+        let search_par = self.get_parameter(amplitude, parameter)?;
+        self.record(ModelOperation::Free {
+            amplitude: amplitude.to_string(),
+            parameter: parameter.to_string(),
+        });
+        let index = self.get_min_free_index();
+        for par in self.parameters.iter_mut() {
+            if par.fixed_index == search_par.fixed_index {
+                par.index = index;
+                par.fixed_index = None;
+            }
+        }
+        self.reindex_parameters();
+        Ok(())
+    }
+    /// Sets the bounds on a [`Parameter`] in the [`Model`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if the parameter is not found by name.
+    pub fn set_bounds(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        bounds: (F, F),
+    ) -> Result<(), RustitudeError> {
+        let search_par = self.get_parameter(amplitude, parameter)?;
+        self.record(ModelOperation::SetBounds {
+            amplitude: amplitude.to_string(),
+            parameter: parameter.to_string(),
+            bounds,
+        });
+        if search_par.index.is_some() {
+            for par in self.parameters.iter_mut() {
+                if par.index == search_par.index {
+                    par.bounds = bounds;
+                }
+            }
+        } else {
+            for par in self.parameters.iter_mut() {
+                if par.fixed_index == search_par.fixed_index {
+                    par.bounds = bounds;
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Sets (or removes) the [`Transform`] of a [`Parameter`] in the [`Model`].
+    ///
+    /// With a [`Transform`], every list of free parameters (the input to
+    /// [`Model::physical_parameters`] and to the likelihoods built on the [`Model`], as well as
+    /// the output of [`Model::get_initial`] and [`Model::get_bounds`]) holds the internal value
+    /// $`u`$, while [`Node::calculate`], constraints, and penalties see the physical value
+    /// $`x(u)`$. Initial values, bounds, and fixed values are always given as physical values.
+    /// Constrained parameters share the [`Transform`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError::ParameterNotFoundError`] if the parameter is not
+    /// found by name or a [`RustitudeError::InvalidParameterValue`] if a
+    /// [`Transform::Bounded`] has a lower bound which is not below its upper bound.
+    pub fn set_transform(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        transform: Option<Transform<F>>,
+    ) -> Result<(), RustitudeError> {
+        let search_par = self.get_parameter(amplitude, parameter)?;
+        if let Some(Transform::Bounded(a, b)) = transform {
+            if a.partial_cmp(&b) != Some(std::cmp::Ordering::Less) {
+                return Err(RustitudeError::InvalidParameterValue(format!(
+                    "the bounds of a transform must be increasing, got ({a}, {b})"
+                )));
+            }
+        }
+        self.record(ModelOperation::SetTransform {
+            amplitude: amplitude.to_string(),
+            parameter: parameter.to_string(),
+            transform,
+        });
+        if search_par.index.is_some() {
+            for par in self.parameters.iter_mut() {
+                if par.index == search_par.index {
+                    par.transform = transform;
+                }
+            }
+        } else {
+            for par in self.parameters.iter_mut() {
+                if par.fixed_index == search_par.fixed_index {
+                    par.transform = transform;
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Sets the initial value of a [`Parameter`] in the [`Model`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if the parameter is not found by name.
+    pub fn set_initial(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        initial: F,
+    ) -> Result<(), RustitudeError> {
+        let search_par = self.get_parameter(amplitude, parameter)?;
+        self.record(ModelOperation::SetInitial {
+            amplitude: amplitude.to_string(),
+            parameter: parameter.to_string(),
+            initial,
+        });
+        if search_par.index.is_some() {
+            for par in self.parameters.iter_mut() {
+                if par.index == search_par.index {
+                    par.initial = initial;
+                }
+            }
+        } else {
+            for par in self.parameters.iter_mut() {
+                if par.fixed_index == search_par.fixed_index {
+                    par.initial = initial;
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Fixes every [`Parameter`] whose name matches a pattern (see [`Parameter::matches`]) to a
+    /// given value.
+    ///
+    /// Each match is fixed with [`Model::fix`] and recorded separately in the
+    /// [`Model::history`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError::ParseError`] if the pattern is an invalid regular
+    /// expression, or a [`RustitudeError::ParameterNotFoundError`] if no parameter matches it.
+    pub fn fix_matching(&mut self, pattern: &str, value: F) -> Result<(), RustitudeError> {
+        for (amplitude, parameter) in self.matching_names(pattern)? {
+            self.fix(&amplitude, &parameter, value)?;
+        }
+        Ok(())
+    }
+    /// Frees every fixed [`Parameter`] whose name matches a pattern (see
+    /// [`Parameter::matches`]).
+    ///
+    /// Each fixed match is freed with [`Model::free`] and recorded separately in the
+    /// [`Model::history`]. Matches which are already free are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError::ParseError`] if the pattern is an invalid regular
+    /// expression, or a [`RustitudeError::ParameterNotFoundError`] if no parameter matches it.
+    pub fn free_matching(&mut self, pattern: &str) -> Result<(), RustitudeError> {
+        for (amplitude, parameter) in self.matching_names(pattern)? {
+            if self.get_parameter(&amplitude, &parameter)?.is_fixed() {
+                self.free(&amplitude, &parameter)?;
+            }
+        }
+        Ok(())
+    }
+    /// Sets the bounds on every [`Parameter`] whose name matches a pattern (see
+    /// [`Parameter::matches`]).
+    ///
+    /// Each match is recorded separately in the [`Model::history`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError::ParseError`] if the pattern is an invalid regular
+    /// expression, or a [`RustitudeError::ParameterNotFoundError`] if no parameter matches it.
+    pub fn set_bounds_matching(
+        &mut self,
+        pattern: &str,
+        bounds: (F, F),
+    ) -> Result<(), RustitudeError> {
+        for (amplitude, parameter) in self.matching_names(pattern)? {
+            self.set_bounds(&amplitude, &parameter, bounds)?;
+        }
+        Ok(())
+    }
+    /// Sets the initial value of every [`Parameter`] whose name matches a pattern (see
+    /// [`Parameter::matches`]).
+    ///
+    /// Each match is recorded separately in the [`Model::history`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError::ParseError`] if the pattern is an invalid regular
+    /// expression, or a [`RustitudeError::ParameterNotFoundError`] if no parameter matches it.
+    pub fn set_initial_matching(
+        &mut self,
+        pattern: &str,
+        initial: F,
+    ) -> Result<(), RustitudeError> {
+        for (amplitude, parameter) in self.matching_names(pattern)? {
+            self.set_initial(&amplitude, &parameter, initial)?;
+        }
+        Ok(())
+    }
+    /// Constrains every [`Parameter`] whose name matches a pattern (see
+    /// [`Parameter::matches`]) to be equal to the first match in the [`Model`].
+    ///
+    /// Each constraint is applied with [`Model::constrain`] and recorded separately in the
+    /// [`Model::history`]. Matches which already share the index of the first match (including
+    /// fixed matches when the first match is fixed) are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError::ParseError`] if the pattern is an invalid regular
+    /// expression, or a [`RustitudeError::ParameterNotFoundError`] if no parameter matches it.
+    pub fn constrain_matching(&mut self, pattern: &str) -> Result<(), RustitudeError> {
+        let names = self.matching_names(pattern)?;
+        let (first_amplitude, first_parameter) = &names[0];
+        for (amplitude, parameter) in &names[1..] {
+            let first = self.get_parameter(first_amplitude, first_parameter)?;
+            if self.get_parameter(amplitude, parameter)?.index != first.index {
+                self.constrain(first_amplitude, first_parameter, amplitude, parameter)?;
+            }
+        }
+        Ok(())
+    }
+    /// Records a [`ModelOperation`] in the [`Model::history`] along with the current state of
+    /// the [`Parameter`]s and [`GaussianConstraint`]s, which is restored by [`Model::undo`].
+    fn record(&mut self, operation: ModelOperation<F>) {
+        self.history.push(HistoryEntry {
+            operation,
+            parameters: self.parameters.clone(),
+            gaussian_constraints: self.gaussian_constraints.clone(),
+        });
+    }
+    /// Returns the [`ModelOperation`]s which have been applied to the [`Model`] (and not undone),
+    /// in order.
+    pub fn history(&self) -> Vec<&ModelOperation<F>> {
+        self.history.iter().map(|entry| &entry.operation).collect()
+    }
+    /// Undoes the most recent [`ModelOperation`] in the [`Model::history`], restoring the
+    /// [`Parameter`]s and [`GaussianConstraint`]s to their previous state, and returns it.
+    ///
+    /// Returns [`None`] if the history is empty.
+    pub fn undo(&mut self) -> Option<ModelOperation<F>> {
+        let entry = self.history.pop()?;
+        self.parameters = entry.parameters;
+        self.gaussian_constraints = entry.gaussian_constraints;
+        Some(entry.operation)
+    }
+    /// Clears the [`Model::history`] without changing the [`Model`].
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+    /// Applies every [`ModelOperation`] in the [`Model::history`] to another [`Model`], in order.
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if any operation fails on the other [`Model`],
+    /// in which case the operations before it remain applied.
+    pub fn replay(&self, model: &mut Self) -> Result<(), RustitudeError> {
+        self.history
+            .iter()
+            .try_for_each(|entry| entry.operation.apply(model))
+    }
+    /// Returns a list of bounds of free [`Parameter`]s in the [`Model`], mapped to the internal
+    /// space of any [`Transform`] (see [`Transform::bounds`]).
+    pub fn get_bounds(&self) -> Vec<(F, F)> {
+        let any_fixed = if self.any_fixed() { 1 } else { 0 };
+        self.group_by_index()
+            .iter()
+            .skip(any_fixed)
+            .filter_map(|group| {
+                group.first().map(|par| {
+                    par.transform
+                        .map_or(par.bounds, |transform| transform.bounds(par.bounds))
+                })
+            })
+            .collect()
+    }
+    /// Returns a list of initial values of free [`Parameter`]s in the [`Model`], mapped to the
+    /// internal space of any [`Transform`].
+    pub fn get_initial(&self) -> Vec<F> {
+        let any_fixed = if self.any_fixed() { 1 } else { 0 };
+        self.group_by_index()
+            .iter()
+            .skip(any_fixed)
+            .filter_map(|group| group.first().map(|par| par.internal(par.initial)))
+            .collect()
+    }
+    /// Returns the number of free [`Parameter`]s in the [`Model`].
+    pub fn get_n_free(&self) -> usize {
+        self.get_min_free_index().unwrap_or(0)
+    }
+    /// Activates an [`Amplitude`] in the [`Model`] by name.
+    ///
+    /// Activating or deactivating [`Amplitude`]s never changes the index of any [`Parameter`],
+    /// so the same free parameter values can be used before and after.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::AmplitudeNotFoundError`] if the given
+    /// amplitude is not present in the [`Model`].
+    pub fn activate(&mut self, amplitude: &str) -> Result<(), RustitudeError> {
+        if !self.amplitudes.read().iter().any(|a| a.name == amplitude) {
+            return Err(RustitudeError::AmplitudeNotFoundError(
+                amplitude.to_string(),
+            ));
+        }
+        self.amplitudes.write().iter_mut().for_each(|amp| {
+            if amp.name == amplitude {
+                amp.active = true
+            }
+        });
+        self.cohsums.iter_mut().for_each(|cohsum| {
+            cohsum.walk_mut().iter_mut().for_each(|amp| {
+                if amp.name == amplitude {
+                    amp.active = true
+                }
+            })
+        });
+        Ok(())
+    }
+    /// Activates all [`Amplitude`]s in the [`Model`].
+    pub fn activate_all(&mut self) {
+        self.amplitudes
+            .write()
+            .iter_mut()
+            .for_each(|amp| amp.active = true);
+        self.cohsums.iter_mut().for_each(|cohsum| {
+            cohsum
+                .walk_mut()
+                .iter_mut()
+                .for_each(|amp| amp.active = true)
+        });
+    }
+    /// Activate only the specified [`Amplitude`]s while deactivating the rest.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::AmplitudeNotFoundError`] if a given
+    /// amplitude is not present in the [`Model`].
+    pub fn isolate(&mut self, amplitudes: Vec<&str>) -> Result<(), RustitudeError> {
+        self.deactivate_all();
+        for amplitude in amplitudes {
+            self.activate(amplitude)?;
+        }
+        Ok(())
+    }
+    /// Deactivates an [`Amplitude`] in the [`Model`] by name.
+    ///
+    /// The [`Parameter`]s of a deactivated [`Amplitude`] keep their indices (see
+    /// [`Model::activate`]).
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::AmplitudeNotFoundError`] if the given
+    /// amplitude is not present in the [`Model`].
+    pub fn deactivate(&mut self, amplitude: &str) -> Result<(), RustitudeError> {
+        if !self.amplitudes.read().iter().any(|a| a.name == amplitude) {
+            return Err(RustitudeError::AmplitudeNotFoundError(
+                amplitude.to_string(),
+            ));
+        }
+        self.amplitudes.write().iter_mut().for_each(|amp| {
+            if amp.name == amplitude {
+                amp.active = false
+            }
+        });
+        self.cohsums.iter_mut().for_each(|cohsum| {
+            cohsum.walk_mut().iter_mut().for_each(|amp| {
+                if amp.name == amplitude {
+                    amp.active = false
+                }
+            })
+        });
+        Ok(())
+    }
+    /// Adds a [`GaussianConstraint`] to a [`Parameter`] in the [`Model`].
+    ///
+    /// If the [`Parameter`] is constrained to other [`Parameter`]s, the constraint applies to the
+    /// whole group, since they share a value.
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if the parameter is not found by name or if `sigma`
+    /// is not positive.
+    pub fn add_gaussian_constraint(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: F,
+        sigma: F,
+    ) -> Result<(), RustitudeError> {
+        self.get_parameter(amplitude, parameter)?;
+        if sigma <= F::zero() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "Gaussian constraint on {amplitude}::{parameter} must have a positive width (got {sigma})"
+            )));
+        }
+        let constraint = GaussianConstraint {
+            amplitude: amplitude.to_string(),
+            parameter: parameter.to_string(),
+            mean,
+            sigma,
+        };
+        self.record(ModelOperation::AddGaussianConstraint(constraint.clone()));
+        self.gaussian_constraints.push(constraint);
+        Ok(())
+    }
+    /// Adds a Gaussian prior to a [`Parameter`] in the [`Model`], such as a resonance mass or
+    /// width constrained to an external measurement. This is equivalent to
+    /// [`Model::add_gaussian_constraint`].
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if the parameter is not found by name or if `sigma`
+    /// is not positive.
+    pub fn add_prior(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: F,
+        sigma: F,
+    ) -> Result<(), RustitudeError> {
+        self.add_gaussian_constraint(amplitude, parameter, mean, sigma)
+    }
+    /// Adds a custom [`Penalty`] on a set of [`Parameter`]s, given by `(amplitude, parameter)`
+    /// names, to the [`Model`].
+    ///
+    /// The function receives the values of the [`Parameter`]s in the given order (fixed
+    /// parameters use their fixed value), and its result is added to $`-2\ln\mathcal{L}`$ along
+    /// with any [`GaussianConstraint`]s. Its gradient is computed by finite differences (see
+    /// [`Penalty::gradient`]).
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError::ParameterNotFoundError`] if any parameter is not
+    /// found by name.
+    pub fn add_penalty(
+        &mut self,
+        parameters: &[(&str, &str)],
+        function: impl Fn(&[F]) -> Result<F, RustitudeError> + Send + Sync + 'static,
+    ) -> Result<(), RustitudeError> {
+        for (amplitude, parameter) in parameters {
+            self.get_parameter(amplitude, parameter)?;
+        }
+        self.penalties.push(Penalty {
+            parameters: parameters
+                .iter()
+                .map(|(amplitude, parameter)| (amplitude.to_string(), parameter.to_string()))
+                .collect(),
+            function: Arc::new(function),
+        });
+        Ok(())
+    }
+    /// Returns the physical values of the [`Parameter`]s of a [`Penalty`] and their free
+    /// parameter indices.
+    fn penalty_values(
+        &self,
+        penalty: &Penalty<F>,
+        parameters: &[F],
+    ) -> Result<(Vec<F>, Vec<Option<usize>>), RustitudeError> {
+        penalty
+            .parameters
+            .iter()
+            .map(|(amplitude, parameter)| {
+                let par = self.get_parameter(amplitude, parameter)?;
+                Ok((par.value(parameters), par.index))
+            })
+            .collect()
+    }
+    /// Computes the total contribution of all [`GaussianConstraint`]s and [`Penalty`] terms in
+    /// the [`Model`] for the given free parameter values. Fixed parameters use their fixed value.
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if a constrained parameter is no longer in the
+    /// [`Model`] or if a [`Penalty`] fails.
+    pub fn constraint_penalty(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        let gaussian = self
+            .gaussian_constraints
+            .iter()
+            .map(|constraint| {
+                let par = self.get_parameter(&constraint.amplitude, &constraint.parameter)?;
+                Ok(((par.value(parameters) - constraint.mean) / constraint.sigma).powi(2))
+            })
+            .sum::<Result<F, RustitudeError>>()?;
+        self.penalties.iter().try_fold(gaussian, |total, penalty| {
+            let (values, _) = self.penalty_values(penalty, parameters)?;
+            Ok(total + penalty.evaluate(&values)?)
+        })
+    }
+    /// Computes the gradient of [`Model::constraint_penalty`] with respect to the free
+    /// parameters.
+    ///
+    /// # Errors
+    ///
+    /// This method yields a [`RustitudeError`] if a constrained parameter is no longer in the
+    /// [`Model`] or if a [`Penalty`] fails.
+    pub fn constraint_penalty_gradient(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        let mut gradient = vec![F::zero(); self.get_n_free()];
+        for constraint in &self.gaussian_constraints {
+            let par = self.get_parameter(&constraint.amplitude, &constraint.parameter)?;
+            if let Some(i) = par.index {
+                gradient[i] += convert!(2, F) * (par.value(parameters) - constraint.mean)
+                    / constraint.sigma.powi(2)
+                    * par.derivative(parameters[i]);
+            }
+        }
+        for penalty in &self.penalties {
+            let (values, indices) = self.penalty_values(penalty, parameters)?;
+            for ((g, index), (amplitude, parameter)) in penalty
+                .gradient(&values)?
+                .into_iter()
+                .zip(indices)
+                .zip(&penalty.parameters)
+            {
+                if let Some(i) = index {
+                    let par = self.get_parameter(amplitude, parameter)?;
+                    gradient[i] += g * par.derivative(parameters[i]);
+                }
+            }
+        }
+        Ok(gradient)
+    }
+    /// Deactivates all [`Amplitude`]s in the [`Model`].
+    pub fn deactivate_all(&mut self) {
+        self.amplitudes
+            .write()
+            .iter_mut()
+            .for_each(|amp| amp.active = false);
+        self.cohsums.iter_mut().for_each(|cohsum| {
+            cohsum
+                .walk_mut()
+                .iter_mut()
+                .for_each(|amp| amp.active = false)
+        });
+    }
+    /// Returns the current [`ActivationSnapshot`] of the [`Model`].
+    pub fn activation_state(&self) -> ActivationSnapshot {
+        ActivationSnapshot {
+            amplitudes: self
+                .amplitudes
+                .read()
+                .iter()
+                .map(|amp| (amp.name.clone(), amp.active))
+                .collect(),
+            sums: self.cohsums.iter().map(|cohsum| cohsum.active).collect(),
+        }
+    }
+    /// Restores the activation state of every [`Amplitude`] and coherent sum from an
+    /// [`ActivationSnapshot`] taken with [`Model::activation_state`].
+    ///
+    /// The snapshot is checked against the [`Model`] before anything is changed, so a failed
+    /// restore leaves the [`Model`] untouched.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::AmplitudeNotFoundError`] if an amplitude in
+    /// the snapshot is not present in the [`Model`], or a
+    /// [`RustitudeError::InvalidParameterValue`] if the snapshot does not cover the same number of
+    /// [`Amplitude`]s and coherent sums as the [`Model`].
+    pub fn restore(&mut self, snapshot: &ActivationSnapshot) -> Result<(), RustitudeError> {
+        let states: HashMap<&str, bool> = snapshot
+            .amplitudes
+            .iter()
+            .map(|(name, active)| (name.as_str(), *active))
+            .collect();
+        if let Some((name, _)) = snapshot
+            .amplitudes
+            .iter()
+            .find(|(name, _)| !self.amplitudes.read().iter().any(|amp| &amp.name == name))
+        {
+            return Err(RustitudeError::AmplitudeNotFoundError(name.clone()));
+        }
+        let n_amplitudes = self.amplitudes.read().len();
+        let n_sums = self.cohsums.len();
+        if states.len() != n_amplitudes || snapshot.sums.len() != n_sums {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "the snapshot has {} amplitudes and {} coherent sums, but the model has {} amplitudes and {} coherent sums",
+                states.len(),
+                snapshot.sums.len(),
+                n_amplitudes,
+                n_sums
+            )));
+        }
+        self.amplitudes.write().iter_mut().for_each(|amp| {
+            amp.active = states[amp.name.as_str()];
+        });
+        for (cohsum, active) in self.cohsums.iter_mut().zip(&snapshot.sums) {
+            cohsum.active = *active;
+            cohsum.walk_mut().iter_mut().for_each(|amp| {
+                if let Some(active) = states.get(amp.name.as_str()) {
+                    amp.active = *active;
+                }
+            });
+        }
+        Ok(())
+    }
+    /// Returns the name of each coherent sum in the [`Model`] (or [`None`] for unnamed sums).
+    pub fn sum_names(&self) -> Vec<Option<&str>> {
+        self.cohsums
+            .iter()
+            .map(|cohsum| cohsum.name.as_deref())
+            .collect()
+    }
+    /// Retrieves a copy of the first coherent sum in the [`Model`] with the given name.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::SumNotFoundError`] if no coherent sum has
+    /// the given name.
+    pub fn get_sum(&self, name: &str) -> Result<NormSqr<F>, RustitudeError> {
+        self.cohsums
+            .iter()
+            .find(|cohsum| cohsum.name.as_deref() == Some(name))
+            .cloned()
+            .ok_or_else(|| RustitudeError::SumNotFoundError(name.to_string()))
+    }
+    /// Activates every coherent sum in the [`Model`] with the given name.
+    ///
+    /// Like [`Model::activate`], this never changes the index of any [`Parameter`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::SumNotFoundError`] if no coherent sum has
+    /// the given name.
+    pub fn activate_sum(&mut self, name: &str) -> Result<(), RustitudeError> {
+        self.set_sum_active(name, true)
+    }
+    /// Deactivates every coherent sum in the [`Model`] with the given name, removing it from
+    /// the intensity without changing the [`Amplitude`]s it contains (which may also appear in
+    /// other coherent sums).
+    ///
+    /// Like [`Model::deactivate`], this never changes the index of any [`Parameter`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::SumNotFoundError`] if no coherent sum has
+    /// the given name.
+    pub fn deactivate_sum(&mut self, name: &str) -> Result<(), RustitudeError> {
+        self.set_sum_active(name, false)
+    }
+    fn set_sum_active(&mut self, name: &str, active: bool) -> Result<(), RustitudeError> {
+        let mut found = false;
+        for cohsum in self
+            .cohsums
+            .iter_mut()
+            .filter(|cohsum| cohsum.name.as_deref() == Some(name))
+        {
+            cohsum.active = active;
+            found = true;
+        }
+        if !found {
+            return Err(RustitudeError::SumNotFoundError(name.to_string()));
+        }
+        Ok(())
+    }
+    fn group_by_index(&self) -> Vec<Vec<&Parameter<F>>> {
+        self.parameters
+            .iter()
+            .sorted_by_key(|par| par.index)
+            .chunk_by(|par| par.index)
+            .into_iter()
+            .map(|(_, group)| group.collect::<Vec<_>>())
+            .collect()
+    }
+    fn group_by_index_mut(&mut self) -> Vec<Vec<&mut Parameter<F>>> {
+        self.parameters
+            .iter_mut()
+            .sorted_by_key(|par| par.index)
+            .chunk_by(|par| par.index)
+            .into_iter()
+            .map(|(_, group)| group.collect())
+            .collect()
+    }
+    fn any_fixed(&self) -> bool {
+        self.parameters.iter().any(|p| p.index.is_none())
+    }
+    fn reindex_parameters(&mut self) {
+        let any_fixed = if self.any_fixed() { 1 } else { 0 };
+        self.group_by_index_mut()
+            .iter_mut()
+            .skip(any_fixed) // first element could be index = None
+            .enumerate()
+            .for_each(|(ind, par_group)| par_group.iter_mut().for_each(|par| par.index = Some(ind)))
+    }
+    fn get_min_free_index(&self) -> Option<usize> {
+        self.parameters
+            .iter()
+            .filter_map(|p| p.index)
+            .max()
+            .map_or(Some(0), |max| Some(max + 1))
+    }
+    fn get_min_fixed_index(&self) -> Option<usize> {
+        self.parameters
+            .iter()
+            .filter_map(|p| p.fixed_index)
+            .max()
+            .map_or(Some(0), |max| Some(max + 1))
+    }
+}
+
+/// A [`Node`] for computing a single scalar value from an input parameter.
+///
+/// This struct implements [`Node`] to generate a single new parameter called `value`.
+///
+/// # Parameters:
+///
+/// - `value`: The value of the scalar.
+#[derive(Clone)]
+pub struct Scalar;
+impl<F: Field> Node<F> for Scalar {
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new("Scalar", serde_json::Value::Null))
+    }
+    fn is_event_independent(&self) -> bool {
+        true
+    }
+    fn parameters(&self) -> Vec<String> {
+        vec!["value".to_string()]
+    }
+    fn calculate(&self, parameters: &[F], _event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        Ok(Complex::new(parameters[0], F::zero()))
+    }
+    fn calculate_gradient(
+        &self,
+        _parameters: &[F],
+        _event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        Ok(vec![Complex::new(F::one(), F::zero())])
+    }
+}
+
+/// Creates a named [`Scalar`].
+///
+/// This is a convenience method to generate an [`Amplitude`] which is just a single free
+/// parameter called `value`.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use rustitude_core::prelude::*;
+/// let my_scalar: Amplitude<f64> = scalar("MyScalar");
+/// assert_eq!(my_scalar.parameters, vec!["value".to_string()]);
+/// ```
+pub fn scalar<F: Field>(name: &str) -> Amplitude<F> {
+    Amplitude::new(name, Scalar)
+}
+/// A [`Node`] for computing a single complex value from two input parameters.
+///
+/// This struct implements [`Node`] to generate a complex value from two input parameters called
+/// `real` and `imag`.
+///
+/// # Parameters:
+///
+/// - `real`: The real part of the complex scalar.
+/// - `imag`: The imaginary part of the complex scalar.
+#[derive(Clone)]
+pub struct ComplexScalar;
+impl<F: Field> Node<F> for ComplexScalar {
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new("ComplexScalar", serde_json::Value::Null))
+    }
+    fn is_event_independent(&self) -> bool {
+        true
+    }
+    fn calculate(&self, parameters: &[F], _event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        Ok(Complex::new(parameters[0], parameters[1]))
+    }
+    fn calculate_gradient(
+        &self,
+        _parameters: &[F],
+        _event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        Ok(vec![
+            Complex::new(F::one(), F::zero()),
+            Complex::new(F::zero(), F::one()),
+        ])
+    }
+
+    fn parameters(&self) -> Vec<String> {
+        vec!["real".to_string(), "imag".to_string()]
+    }
+}
+/// Creates a named [`ComplexScalar`].
+///
+/// This is a convenience method to generate an [`Amplitude`] which represents a complex
+/// value determined by two parameters, `real` and `imag`.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use rustitude_core::prelude::*;
+/// let my_cscalar: Amplitude<f64> = cscalar("MyComplexScalar");
+/// assert_eq!(my_cscalar.parameters, vec!["real".to_string(), "imag".to_string()]);
+/// ```
+pub fn cscalar<F: Field>(name: &str) -> Amplitude<F> {
+    Amplitude::new(name, ComplexScalar)
+}
+
+/// A [`Node`] for computing a single complex value from two input parameters in polar form.
+///
+/// This struct implements [`Node`] to generate a complex value from two input parameters called
+/// `mag` and `phi`.
+///
+/// # Parameters:
+///
+/// - `mag`: The magnitude of the complex scalar.
+/// - `phi`: The phase of the complex scalar.
+#[derive(Clone)]
+pub struct PolarComplexScalar;
+impl<F: Field> Node<F> for PolarComplexScalar {
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new("PolarComplexScalar", serde_json::Value::Null))
+    }
+    fn is_event_independent(&self) -> bool {
+        true
+    }
+    fn calculate(&self, parameters: &[F], _event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        Ok(Complex::cis(parameters[1]).mul(parameters[0]))
+    }
+    fn calculate_gradient(
+        &self,
+        parameters: &[F],
+        _event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        let phase = Complex::cis(parameters[1]);
+        Ok(vec![phase, phase * Complex::i() * parameters[0]])
+    }
+
+    fn parameters(&self) -> Vec<String> {
+        vec!["mag".to_string(), "phi".to_string()]
+    }
+
+    fn parameter_bounds(&self) -> Vec<(F, F)> {
+        vec![(F::zero(), F::infinity()), (-F::PI(), F::PI())]
+    }
+}
+
+/// Creates a named [`PolarComplexScalar`].
+///
+/// This is a convenience method to generate an [`Amplitude `] which represents a complex
+/// value determined by two parameters, `real` and `imag`.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use rustitude_core::prelude::*;
+/// let my_pcscalar: Amplitude<f64> = pcscalar("MyPolarComplexScalar");
+/// assert_eq!(my_pcscalar.parameters, vec!["mag".to_string(), "phi".to_string()]);
+/// ```
+pub fn pcscalar<F: Field>(name: &str) -> Amplitude<F> {
+    Amplitude::new(name, PolarComplexScalar)
+}
+
+/// A [`Node`] for the square root of one fraction in an incoherent sum (see
+/// [`Model::add_incoherent_sum`]).
+///
+/// The fractions $`f_i`$ of the `n` terms are given by a softmax over logits $`x_i`$, where the
+/// logit of the first term is fixed to zero so that the fractions always sum to one:
+///
+/// ```math
+/// f_i = \frac{e^{x_i}}{\sum_{j=0}^{n-1} e^{x_j}}
+/// ```
+///
+/// This struct implements [`Node`] to generate the real value $`\sqrt{f_i}`$, so that the squared
+/// amplitude of a term it multiplies is weighted by $`f_i`$.
+///
+/// # Parameters:
+///
+/// - `logit 1`, ..., `logit {n-1}`: The logits of every term after the first.
+#[derive(Clone)]
+pub struct Fraction {
+    index: usize,
+    n: usize,
+}
+impl Fraction {
+    /// Creates a new [`Fraction`] for the term at `index` in an incoherent sum of `n` terms.
+    pub const fn new(index: usize, n: usize) -> Self {
+        Self { index, n }
+    }
+    /// Computes every fraction from the logits of the terms after the first.
+    fn fractions<F: Field>(parameters: &[F]) -> Vec<F> {
+        let max = parameters.iter().copied().fold(F::zero(), F::max);
+        let exps: Vec<F> = std::iter::once(F::zero())
+            .chain(parameters.iter().copied())
+            .map(|x| F::exp(x - max))
+            .collect();
+        let total: F = exps.iter().copied().sum();
+        exps.into_iter().map(|e| e / total).collect()
+    }
+}
+impl<F: Field> Node<F> for Fraction {
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "Fraction",
+            serde_json::json!({ "index": self.index, "n": self.n }),
+        ))
+    }
+    fn is_event_independent(&self) -> bool {
+        true
+    }
+    fn calculate(&self, parameters: &[F], _event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let fractions = Self::fractions(parameters);
+        Ok(Complex::new(F::sqrt(fractions[self.index]), F::zero()))
+    }
+    fn calculate_gradient(
+        &self,
+        parameters: &[F],
+        _event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        let fractions = Self::fractions(parameters);
+        let root = F::sqrt(fractions[self.index]);
+        Ok((1..self.n)
+            .map(|j| {
+                let delta = if j == self.index { F::one() } else { F::zero() };
+                Complex::new(convert!(0.5, F) * root * (delta - fractions[j]), F::zero())
+            })
+            .collect())
+    }
+    fn parameters(&self) -> Vec<String> {
+        (1..self.n).map(|j| format!("logit {j}")).collect()
+    }
+
+    fn initial_values(&self) -> Vec<F> {
+        vec![F::zero(); self.n - 1]
+    }
+}
+
+/// The scale $`q_R = 0.1973\,\text{GeV}`$ ($`1\,\text{fm}^{-1}`$) used in the Blatt-Weisskopf barrier
+/// factors.
+const BARRIER_SCALE: f64 = 0.1973;
+
+/// Computes the breakup momentum of a particle with mass `m0` decaying into two particles with
+/// masses `m1` and `m2`, taking the magnitude below threshold.
+fn breakup_momentum<F: Field>(m0: F, m1: F, m2: F) -> F {
+    let lambda = (m0.powi(2) - (m1 + m2).powi(2)) * (m0.powi(2) - (m1 - m2).powi(2));
+    F::sqrt(F::abs(lambda)) / (convert!(2, F) * m0)
+}
+
+/// Computes the phase space factor $`\rho = 2q/m`$ of a particle with mass `m0` decaying into two
+/// particles with masses `m1` and `m2`, which is imaginary below threshold.
+fn phase_space_factor<F: Field>(m0: F, m1: F, m2: F) -> Complex<F> {
+    let lambda = (m0.powi(2) - (m1 + m2).powi(2)) * (m0.powi(2) - (m1 - m2).powi(2));
+    Complex::new(lambda, F::zero()).sqrt() / m0.powi(2)
+}
+
+/// Computes the Blatt-Weisskopf barrier factor for a particle with mass `m0` decaying into two
+/// particles with masses `m1` and `m2` with orbital angular momentum `l` (at most 4).
+fn blatt_weisskopf<F: Field>(m0: F, m1: F, m2: F, l: usize) -> F {
+    let z = (breakup_momentum(m0, m1, m2) / convert!(BARRIER_SCALE, F)).powi(2);
+    match l {
+        0 => F::one(),
+        1 => F::sqrt(convert!(2, F) * z / (z + F::one())),
+        2 => F::sqrt(
+            convert!(13, F) * z.powi(2) / ((z - convert!(3, F)).powi(2) + convert!(9, F) * z),
+        ),
+        3 => F::sqrt(
+            convert!(277, F) * z.powi(3)
+                / (z * (z - convert!(15, F)).powi(2)
+                    + convert!(9, F) * (convert!(2, F) * z - convert!(5, F)).powi(2)),
+        ),
+        _ => F::sqrt(
+            convert!(12746, F) * z.powi(4)
+                / ((z.powi(2) - convert!(45, F) * z + convert!(105, F)).powi(2)
+                    + convert!(25, F) * z * (convert!(2, F) * z - convert!(21, F)).powi(2)),
+        ),
+    }
+}
+
+/// Sums the four-momenta of the final state particles at the given indices of
+/// [`Event::daughter_p4s`].
+fn daughters_p4<F: Field>(
+    event: &Event<F>,
+    indices: &[usize],
+) -> Result<FourMomentum<F>, RustitudeError> {
+    indices
+        .iter()
+        .map(|&i| {
+            event.daughter_p4s.get(i).copied().ok_or_else(|| {
+                RustitudeError::EvaluationError(format!(
+                    "event #{} has no daughter at index {i}",
+                    event.index
+                ))
+            })
+        })
+        .sum()
+}
+
+/// Checks that both groups of daughter indices of a lineshape are non-empty.
+fn check_daughters(daughters_1: &[usize], daughters_2: &[usize]) -> Result<(), RustitudeError> {
+    if daughters_1.is_empty() || daughters_2.is_empty() {
+        return Err(RustitudeError::InvalidParameterValue(
+            "both decay products must contain at least one daughter".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// A [`Node`] for a relativistic Breit-Wigner lineshape with a mass-dependent width.
+///
+/// The resonance decays into two products whose four-momenta are the sums of the
+/// [`Event::daughter_p4s`] at the given indices. With invariant masses $`m`$, $`m_1`$, and
+/// $`m_2`$, breakup momentum $`q`$, and Blatt-Weisskopf barrier factor $`B_\ell`$ (a subscript of
+/// zero denotes the value at $`m = m_0`$), this struct implements [`Node`] to calculate
+///
+/// ```math
+/// \frac{B_\ell(q)}{B_\ell(q_0)} \frac{m_0\Gamma_0}{m_0^2 - m^2 - i m_0 \Gamma(m)}, \quad
+/// \Gamma(m) = \Gamma_0 \frac{m_0}{m} \frac{q}{q_0} \left(\frac{B_\ell(q)}{B_\ell(q_0)}\right)^2
+/// ```
+///
+/// # Parameters:
+///
+/// - `mass`: The mass $`m_0`$ of the resonance.
+/// - `width`: The width $`\Gamma_0`$ of the resonance.
+#[derive(Clone)]
+pub struct BreitWigner<F: Field> {
+    daughters_1: Vec<usize>,
+    daughters_2: Vec<usize>,
+    l: usize,
+    data: Vec<(F, F, F, F, F)>,
+}
+impl<F: Field> BreitWigner<F> {
+    /// Creates a new [`BreitWigner`] for a resonance which decays into the sums of the daughters
+    /// at `daughters_1` and `daughters_2` with orbital angular momentum `l`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if either group of
+    /// daughters is empty or if `l` is larger than 4.
+    pub fn new(
+        daughters_1: &[usize],
+        daughters_2: &[usize],
+        l: usize,
+    ) -> Result<Self, RustitudeError> {
+        check_daughters(daughters_1, daughters_2)?;
+        if l > 4 {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "Blatt-Weisskopf factors are only implemented up to L = 4, got L = {l}"
+            )));
+        }
+        Ok(Self {
+            daughters_1: daughters_1.to_vec(),
+            daughters_2: daughters_2.to_vec(),
+            l,
+            data: Vec::default(),
+        })
+    }
+}
+impl<F: Field> Node<F> for BreitWigner<F> {
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        self.data = dataset
+            .events
+            .par_iter()
+            .map(|event| {
+                let p1 = daughters_p4(event, &self.daughters_1)?;
+                let p2 = daughters_p4(event, &self.daughters_2)?;
+                let (m, m1, m2) = ((p1 + p2).m(), p1.m(), p2.m());
+                Ok((
+                    m,
+                    m1,
+                    m2,
+                    breakup_momentum(m, m1, m2),
+                    blatt_weisskopf(m, m1, m2, self.l),
+                ))
+            })
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        Ok(())
+    }
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let (m, m1, m2, q, b) = self.data[event.index];
+        let (m0, g0) = (parameters[0], parameters[1]);
+        let q0 = breakup_momentum(m0, m1, m2);
+        let ratio = b / blatt_weisskopf(m0, m1, m2, self.l);
+        let g = g0 * (m0 / m) * (q / q0) * ratio.powi(2);
+        Ok(Complex::new(ratio * m0 * g0, F::zero())
+            / Complex::new(m0.powi(2) - m.powi(2), -(m0 * g)))
+    }
+    fn parameters(&self) -> Vec<String> {
+        vec!["mass".to_string(), "width".to_string()]
+    }
+    fn parameter_bounds(&self) -> Vec<(F, F)> {
+        vec![(F::zero(), F::infinity()); 2]
+    }
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "RelativisticBreitWigner",
+            serde_json::json!({
+                "daughters_1": self.daughters_1,
+                "daughters_2": self.daughters_2,
+                "l": self.l,
+            }),
+        ))
+    }
+}
+
+/// A [`Node`] for a Flatté lineshape of a resonance near the threshold of a second decay channel.
+///
+/// The invariant mass $`m`$ is that of the sum of the [`Event::daughter_p4s`] at the given
+/// indices. With the phase space factor $`\rho_j = 2q_j/m`$ of each channel (which is imaginary
+/// below its threshold), this struct implements [`Node`] to calculate
+///
+/// ```math
+/// \frac{g_c}{m_0^2 - m^2 - i\left(g_1^2\rho_1(m) + g_2^2\rho_2(m)\right)}
+/// ```
+///
+/// where $`c`$ is the channel of the observed decay.
+///
+/// # Parameters:
+///
+/// - `mass`: The mass $`m_0`$ of the resonance.
+/// - `g1`: The coupling $`g_1`$ to the first channel.
+/// - `g2`: The coupling $`g_2`$ to the second channel.
+#[derive(Clone)]
+pub struct Flatte<F: Field> {
+    daughters_1: Vec<usize>,
+    daughters_2: Vec<usize>,
+    channel: usize,
+    m1s: [F; 2],
+    m2s: [F; 2],
+    data: Vec<(F, [Complex<F>; 2])>,
+}
+impl<F: Field> Flatte<F> {
+    /// Creates a new [`Flatte`] for a resonance which decays into the sums of the daughters at
+    /// `daughters_1` and `daughters_2` through the given `channel` (`0` or `1`), where `m1s` and
+    /// `m2s` hold the masses of the decay products of the first and second channels.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if either group of
+    /// daughters is empty or if the channel is not `0` or `1`.
+    pub fn new(
+        daughters_1: &[usize],
+        daughters_2: &[usize],
+        channel: usize,
+        m1s: [F; 2],
+        m2s: [F; 2],
+    ) -> Result<Self, RustitudeError> {
+        check_daughters(daughters_1, daughters_2)?;
+        if channel > 1 {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "the channel of a Flatte lineshape must be 0 or 1, got {channel}"
+            )));
+        }
+        Ok(Self {
+            daughters_1: daughters_1.to_vec(),
+            daughters_2: daughters_2.to_vec(),
+            channel,
+            m1s,
+            m2s,
+            data: Vec::default(),
+        })
+    }
+}
+impl<F: Field> Node<F> for Flatte<F> {
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        self.data = dataset
+            .events
+            .par_iter()
+            .map(|event| {
+                let p1 = daughters_p4(event, &self.daughters_1)?;
+                let p2 = daughters_p4(event, &self.daughters_2)?;
+                let m = (p1 + p2).m();
+                Ok((
+                    m,
+                    [
+                        phase_space_factor(m, self.m1s[0], self.m1s[1]),
+                        phase_space_factor(m, self.m2s[0], self.m2s[1]),
+                    ],
+                ))
+            })
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        Ok(())
+    }
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let (m, rho) = self.data[event.index];
+        let (m0, couplings) = (parameters[0], [parameters[1], parameters[2]]);
+        let width = rho[0] * couplings[0].powi(2) + rho[1] * couplings[1].powi(2);
+        Ok(Complex::new(couplings[self.channel], F::zero())
+            / (Complex::new(m0.powi(2) - m.powi(2), F::zero()) - Complex::<F>::i() * width))
+    }
+    fn parameters(&self) -> Vec<String> {
+        vec!["mass".to_string(), "g1".to_string(), "g2".to_string()]
+    }
+    fn parameter_bounds(&self) -> Vec<(F, F)> {
+        vec![(F::zero(), F::infinity()); 3]
+    }
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "FlatteLineshape",
+            serde_json::json!({
+                "daughters_1": self.daughters_1,
+                "daughters_2": self.daughters_2,
+                "channel": self.channel,
+                "m1s": self.m1s,
+                "m2s": self.m2s,
+            }),
+        ))
+    }
+}
+
+/// A [`Node`] for a Gaussian lineshape, which describes a narrow peak whose shape is dominated by
+/// the detector resolution.
+///
+/// The invariant mass $`m`$ is that of the sum of the [`Event::daughter_p4s`] at the given
+/// indices. This struct implements [`Node`] to calculate the real amplitude
+///
+/// ```math
+/// \left(2\pi\sigma^2\right)^{-1/4} \exp\left(-\frac{(m - m_0)^2}{4\sigma^2}\right)
+/// ```
+///
+/// whose square is a normalized Gaussian distribution in $`m`$.
+///
+/// # Parameters:
+///
+/// - `mass`: The mass $`m_0`$ of the peak.
+/// - `sigma`: The standard deviation $`\sigma`$ of the peak.
+#[derive(Clone)]
+pub struct Gaussian<F: Field> {
+    daughters: Vec<usize>,
+    data: Vec<F>,
+}
+impl<F: Field> Gaussian<F> {
+    /// Creates a new [`Gaussian`] in the invariant mass of the sum of the daughters at
+    /// `daughters`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if `daughters` is
+    /// empty.
+    pub fn new(daughters: &[usize]) -> Result<Self, RustitudeError> {
+        check_daughters(daughters, daughters)?;
+        Ok(Self {
+            daughters: daughters.to_vec(),
+            data: Vec::default(),
+        })
+    }
+}
+impl<F: Field> Node<F> for Gaussian<F> {
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        self.data = dataset
+            .kinematics(&format!("resonance_mass{:?}", self.daughters), |event| {
+                Ok(daughters_p4(event, &self.daughters)?.m())
+            })?
+            .to_vec();
+        Ok(())
+    }
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let m = self.data[event.index];
+        let (m0, sigma) = (parameters[0], parameters[1]);
+        let norm = F::powf(F::TAU() * sigma.powi(2), convert!(-0.25, F));
+        Ok(Complex::from(
+            norm * F::exp(-(m - m0).powi(2) / (convert!(4, F) * sigma.powi(2))),
+        ))
+    }
+    fn parameters(&self) -> Vec<String> {
+        vec!["mass".to_string(), "sigma".to_string()]
+    }
+    fn parameter_bounds(&self) -> Vec<(F, F)> {
+        vec![(F::zero(), F::infinity()); 2]
+    }
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "GaussianLineshape",
+            serde_json::json!({ "daughters": self.daughters }),
+        ))
+    }
+}
+
+/// A [`Node`] for a Voigtian lineshape, a non-relativistic Breit-Wigner convolved with a
+/// Gaussian resolution.
+///
+/// This describes a peak whose natural width is comparable to the detector resolution.
+/// The invariant mass $`m`$ is that of the sum of the [`Event::daughter_p4s`] at the given
+/// indices. This struct implements [`Node`] to calculate the real amplitude
+/// $`\sqrt{V(m)}`$, where
+///
+/// ```math
+/// V(m) = \frac{\text{Re}\,w(z)}{\sigma\sqrt{2\pi}}, \quad z = \frac{m - m_0 + i\Gamma/2}{\sigma\sqrt{2}}
+/// ```
+///
+/// is the normalized Voigt profile and $`w`$ is the [`faddeeva`](math::faddeeva) function. It
+/// reduces to a [`Gaussian`] as $`\Gamma \to 0`$ and to a Cauchy distribution as $`\sigma \to 0`$.
+///
+/// # Parameters:
+///
+/// - `mass`: The mass $`m_0`$ of the peak.
+/// - `width`: The natural width $`\Gamma`$ (full width at half maximum) of the peak.
+/// - `sigma`: The standard deviation $`\sigma`$ of the resolution.
+#[derive(Clone)]
+pub struct Voigtian<F: Field> {
+    daughters: Vec<usize>,
+    data: Vec<F>,
+}
+impl<F: Field> Voigtian<F> {
+    /// Creates a new [`Voigtian`] in the invariant mass of the sum of the daughters at
+    /// `daughters`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if `daughters` is
+    /// empty.
+    pub fn new(daughters: &[usize]) -> Result<Self, RustitudeError> {
+        check_daughters(daughters, daughters)?;
+        Ok(Self {
+            daughters: daughters.to_vec(),
+            data: Vec::default(),
+        })
+    }
+}
+impl<F: Field> Node<F> for Voigtian<F> {
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        self.data = dataset
+            .kinematics(&format!("resonance_mass{:?}", self.daughters), |event| {
+                Ok(daughters_p4(event, &self.daughters)?.m())
+            })?
+            .to_vec();
+        Ok(())
+    }
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let m = self.data[event.index];
+        let (m0, width, sigma) = (parameters[0], parameters[1], parameters[2]);
+        let scale = sigma * F::SQRT_2();
+        let z = Complex::new((m - m0) / scale, width / (convert!(2, F) * scale));
+        let profile = math::faddeeva(z).re / (scale * F::sqrt(F::PI()));
+        // The profile is positive, but may be rounded below zero far from the peak
+        Ok(Complex::from(F::sqrt(F::max(profile, F::zero()))))
+    }
+    fn parameters(&self) -> Vec<String> {
+        vec!["mass".to_string(), "width".to_string(), "sigma".to_string()]
+    }
+    fn parameter_bounds(&self) -> Vec<(F, F)> {
+        vec![(F::zero(), F::infinity()); 3]
+    }
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "VoigtianLineshape",
+            serde_json::json!({ "daughters": self.daughters }),
+        ))
+    }
+}
+
+/// A decay channel of a [`KMatrix`], which consists of two particles with masses `m1` and `m2`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct KMatrixChannel<F: Field> {
+    /// The name of the channel, used to label its non-resonant production parameters.
+    pub name: String,
+    /// The mass of the first particle in the channel.
+    pub m1: F,
+    /// The mass of the second particle in the channel.
+    pub m2: F,
+}
+
+/// A pole of a [`KMatrix`] with its mass and its coupling to each [`KMatrixChannel`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct KMatrixPole<F: Field> {
+    /// The name of the pole, used to label its production parameters.
+    pub name: String,
+    /// The (bare) mass $`m_\alpha`$ of the pole.
+    pub mass: F,
+    /// The couplings $`g_{i\alpha}`$ of the pole to each channel, in the order of
+    /// [`KMatrixConfig::channels`].
+    pub couplings: Vec<F>,
+}
+
+/// An Adler zero which suppresses the [`KMatrix`] near $`s = s_0`$ by a factor of
+/// $`(s - s_0) / s_{\text{norm}}`$.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct AdlerZero<F: Field> {
+    /// The location $`s_0`$ of the zero.
+    pub s_0: F,
+    /// The normalization $`s_{\text{norm}}`$.
+    pub s_norm: F,
+}
+
+/// The fixed constants which define the coupled-channel system of a [`KMatrix`].
+///
+/// The light-meson systems [`KMatrixConfig::f0`], [`KMatrixConfig::f2`], [`KMatrixConfig::a0`],
+/// [`KMatrixConfig::a2`], [`KMatrixConfig::rho`], and [`KMatrixConfig::pi1`] are provided as
+/// presets. Other systems can be built in code or deserialized from JSON or YAML, for example:
+///
+/// ```yaml
+/// channels:
+///   - { name: pipi, m1: 0.13498, m2: 0.13498 }
+///   - { name: kkbar, m1: 0.49368, m2: 0.49761 }
+/// poles:
+///   - { name: f0_980, mass: 0.9063, couplings: [0.064, 0.774] }
+///   - { name: f0_1500, mass: 1.461, couplings: [0.013, 0.092] }
+/// background: [[0.037, -0.014], [-0.014, 0.023]]
+/// l: 0
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct KMatrixConfig<F: Field> {
+    /// The decay channels.
+    pub channels: Vec<KMatrixChannel<F>>,
+    /// The poles.
+    pub poles: Vec<KMatrixPole<F>>,
+    /// The symmetric matrix of non-pole terms $`c_{ij}`$ (zero if omitted).
+    #[serde(default)]
+    pub background: Option<Vec<Vec<F>>>,
+    /// An optional [`AdlerZero`].
+    #[serde(default)]
+    pub adler_zero: Option<AdlerZero<F>>,
+    /// The orbital angular momentum $`\ell`$ of every channel.
+    #[serde(default)]
+    pub l: usize,
+}
+
+impl<F: Field + 'static> KMatrixConfig<F> {
+    /// Checks that the couplings and background terms match the number of channels.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are no
+    /// channels or poles, or if the couplings of a pole or the background matrix do not have one
+    /// entry per channel.
+    pub fn validate(&self) -> Result<(), RustitudeError> {
+        let n = self.channels.len();
+        if n == 0 || self.poles.is_empty() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "a K-matrix must have at least one channel and one pole".to_string(),
+            ));
+        }
+        if let Some(pole) = self.poles.iter().find(|pole| pole.couplings.len() != n) {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "pole \"{}\" has {} couplings for a K-matrix with {n} channels",
+                pole.name,
+                pole.couplings.len()
+            )));
+        }
+        if let Some(background) = &self.background {
+            if background.len() != n || background.iter().any(|row| row.len() != n) {
+                return Err(RustitudeError::InvalidParameterValue(format!(
+                    "the background of a K-matrix with {n} channels must be a {n}x{n} matrix"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds a [`KMatrixConfig`] from tables of constants, where each pole is given by its name,
+    /// mass, and couplings to each channel.
+    fn from_table(
+        channels: &[(&str, f64, f64)],
+        poles: &[(&str, f64, &[f64])],
+        background: Option<&[&[f64]]>,
+        adler_zero: Option<(f64, f64)>,
+        l: usize,
+    ) -> Self {
+        Self {
+            channels: channels
+                .iter()
+                .map(|(name, m1, m2)| KMatrixChannel {
+                    name: name.to_string(),
+                    m1: convert!(*m1, F),
+                    m2: convert!(*m2, F),
+                })
+                .collect(),
+            poles: poles
+                .iter()
+                .map(|(name, mass, couplings)| KMatrixPole {
+                    name: name.to_string(),
+                    mass: convert!(*mass, F),
+                    couplings: couplings.iter().map(|g| convert!(*g, F)).collect(),
+                })
+                .collect(),
+            background: background.map(|rows| {
+                rows.iter()
+                    .map(|row| row.iter().map(|c| convert!(*c, F)).collect())
+                    .collect()
+            }),
+            adler_zero: adler_zero.map(|(s_0, s_norm)| AdlerZero {
+                s_0: convert!(s_0, F),
+                s_norm: convert!(s_norm, F),
+            }),
+            l,
+        }
+    }
+
+    /// The $`f_0`$ ($`J^{PC} = 0^{++}`$, $`I = 0`$) system with channels $`\pi\pi`$,
+    /// $`2\pi 2\pi`$, $`K\bar{K}`$, $`\eta\eta`$, and $`\eta\eta'`$ and poles
+    /// $`f_0(500)`$, $`f_0(980)`$, $`f_0(1370)`$, $`f_0(1500)`$, and $`f_0(1710)`$, with the
+    /// constants of the coupled-channel fit of Kopf et al. (Eur. Phys. J. C 81, 1056 (2021)).
+    #[rustfmt::skip]
+    pub fn f0() -> Self {
+        Self::from_table(
+            &[
+                ("pipi", 0.1349768, 0.1349768),
+                ("2pi2pi", 2.0 * 0.1349768, 2.0 * 0.1349768),
+                ("kkbar", 0.493677, 0.497611),
+                ("etaeta", 0.547862, 0.547862),
+                ("etaetap", 0.547862, 0.95778),
+            ],
+            &[
+                ("f0_500", 0.51461, &[0.74987, -0.01257, 0.27536, -0.15102, 0.36103]),
+                ("f0_980", 0.90630, &[0.06401, 0.00204, 0.77413, 0.50999, 0.13112]),
+                ("f0_1370", 1.23089, &[-0.23417, -0.01032, 0.72283, 0.11934, 0.36792]),
+                ("f0_1500", 1.46104, &[0.01270, 0.26700, 0.09214, 0.02742, -0.04025]),
+                ("f0_1710", 1.69611, &[-0.14242, 0.22780, 0.15981, 0.16272, -0.17397]),
+            ],
+            Some(&[
+                &[ 0.03728, 0.00000, -0.01398, -0.02203,  0.01397],
+                &[ 0.00000, 0.00000,  0.00000,  0.00000,  0.00000],
+                &[-0.01398, 0.00000,  0.02349,  0.03101, -0.04003],
+                &[-0.02203, 0.00000,  0.03101, -0.13769, -0.06722],
+                &[ 0.01397, 0.00000, -0.04003, -0.06722, -0.28401],
+            ]),
+            Some((0.0091125, 1.0)),
+            0,
+        )
+    }
+
+    /// The $`f_2`$ ($`J^{PC} = 2^{++}`$, $`I = 0`$) system with channels $`\pi\pi`$,
+    /// $`2\pi 2\pi`$, $`K\bar{K}`$, and $`\eta\eta`$ and poles $`f_2(1270)`$, $`f_2'(1525)`$,
+    /// $`f_2(1810)`$, and $`f_2(1950)`$ (see [`KMatrixConfig::f0`]).
+    #[rustfmt::skip]
+    pub fn f2() -> Self {
+        Self::from_table(
+            &[
+                ("pipi", 0.1349768, 0.1349768),
+                ("2pi2pi", 2.0 * 0.1349768, 2.0 * 0.1349768),
+                ("kkbar", 0.493677, 0.497611),
+                ("etaeta", 0.547862, 0.547862),
+            ],
+            &[
+                ("f2_1270", 1.15299, &[0.40033, 0.15479, -0.08900, -0.00113]),
+                ("f2_1525", 1.48359, &[0.01820, 0.17300, 0.32393, 0.15256]),
+                ("f2_1810", 1.72923, &[-0.06709, 0.22941, -0.43133, 0.23721]),
+                ("f2_1950", 1.96700, &[-0.49924, 0.19295, 0.27975, -0.03987]),
+            ],
+            Some(&[
+                &[-0.04319, 0.00000,  0.00984,  0.01028],
+                &[ 0.00000, 0.00000,  0.00000,  0.00000],
+                &[ 0.00984, 0.00000, -0.07344,  0.05533],
+                &[ 0.01028, 0.00000,  0.05533, -0.05183],
+            ]),
+            None,
+            2,
+        )
+    }
+
+    /// The $`a_0`$ ($`J^{PC} = 0^{++}`$, $`I = 1`$) system with channels $`\pi\eta`$ and
+    /// $`K\bar{K}`$ and poles $`a_0(980)`$ and $`a_0(1450)`$ (see [`KMatrixConfig::f0`]).
+    #[rustfmt::skip]
+    pub fn a0() -> Self {
+        Self::from_table(
+            &[
+                ("pieta", 0.1349768, 0.547862),
+                ("kkbar", 0.493677, 0.497611),
+            ],
+            &[
+                ("a0_980", 0.95395, &[0.43215, -0.28825]),
+                ("a0_1450", 1.26767, &[0.19000, 0.43372]),
+            ],
+            None,
+            None,
+            0,
+        )
+    }
+
+    /// The $`a_2`$ ($`J^{PC} = 2^{++}`$, $`I = 1`$) system with channels $`\pi\eta`$,
+    /// $`K\bar{K}`$, and $`\pi\eta'`$ and poles $`a_2(1320)`$ and $`a_2(1700)`$ (see
+    /// [`KMatrixConfig::f0`]).
+    #[rustfmt::skip]
+    pub fn a2() -> Self {
+        Self::from_table(
+            &[
+                ("pieta", 0.1349768, 0.547862),
+                ("kkbar", 0.493677, 0.497611),
+                ("pietap", 0.1349768, 0.95778),
+            ],
+            &[
+                ("a2_1320", 1.30080, &[0.30073, 0.21426, -0.09162]),
+                ("a2_1700", 1.75351, &[0.68567, 0.12543, 0.00184]),
+            ],
+            Some(&[
+                &[-0.40184,  0.00033, -0.08707],
+                &[ 0.00033, -0.21416, -0.06193],
+                &[-0.08707, -0.06193, -0.17435],
+            ]),
+            None,
+            2,
+        )
+    }
+
+    /// The $`\rho`$ ($`J^{PC} = 1^{--}`$, $`I = 1`$) system with channels $`\pi\pi`$,
+    /// $`2\pi 2\pi`$, and $`K\bar{K}`$ and poles $`\rho(770)`$ and $`\rho(1700)`$ (see
+    /// [`KMatrixConfig::f0`]).
+    #[rustfmt::skip]
+    pub fn rho() -> Self {
+        Self::from_table(
+            &[
+                ("pipi", 0.1349768, 0.1349768),
+                ("2pi2pi", 2.0 * 0.1349768, 2.0 * 0.1349768),
+                ("kkbar", 0.493677, 0.497611),
+            ],
+            &[
+                ("rho_770", 0.71093, &[0.28023, 0.01806, 0.06501]),
+                ("rho_1700", 1.58660, &[0.16318, 0.53879, 0.00495]),
+            ],
+            Some(&[
+                &[-0.06948, 0.00000,  0.07958],
+                &[ 0.00000, 0.00000,  0.00000],
+                &[ 0.07958, 0.00000, -0.60000],
+            ]),
+            None,
+            1,
+        )
+    }
+
+    /// The $`\pi_1`$ ($`J^{PC} = 1^{-+}`$, $`I = 1`$) system with channels $`\pi\eta`$ and
+    /// $`\pi\eta'`$ and the pole $`\pi_1(1600)`$ (see [`KMatrixConfig::f0`]).
+    #[rustfmt::skip]
+    pub fn pi1() -> Self {
+        Self::from_table(
+            &[
+                ("pieta", 0.1349768, 0.547862),
+                ("pietap", 0.1349768, 0.95778),
+            ],
+            &[("pi1_1600", 1.38552, &[0.80564, 1.04595])],
+            Some(&[
+                &[1.05000,  0.15163],
+                &[0.15163, -0.24611],
+            ]),
+            None,
+            1,
+        )
+    }
+
+    /// Computes the ratio $`B_\ell(q_i(\sqrt{s})) / B_\ell(q_i(m_\alpha))`$ of Blatt-Weisskopf
+    /// barrier factors for channel `i` and pole `a`.
+    fn barrier_factor(&self, s: F, i: usize, a: usize) -> F {
+        let (m1, m2) = (self.channels[i].m1, self.channels[i].m2);
+        math::blatt_weisskopf(math::breakup_momentum(F::sqrt(s), m1, m2), self.l)
+            / math::blatt_weisskopf(math::breakup_momentum(self.poles[a].mass, m1, m2), self.l)
+    }
+
+    /// Computes $`\prod_{\beta \neq \alpha} (m_\beta^2 - s)`$, or the product over every pole
+    /// if `a` is [`None`].
+    fn pole_product(&self, s: F, a: Option<usize>) -> F {
+        self.poles
+            .iter()
+            .enumerate()
+            .filter(|(b, _)| Some(*b) != a)
+            .map(|(_, pole)| pole.mass.powi(2) - s)
+            .product()
+    }
+
+    /// Computes the row of $`(P(s) I + \hat{K}(s) C(s))^{-1}`$ for the given channel, where
+    /// $`\hat{K} = P(s) K`$ is the [`KMatrix`] multiplied by the pole product $`P(s)`$ and $`C`$
+    /// is the diagonal matrix of Chew-Mandelstam functions.
+    fn ikc_inv_row(&self, s: F, channel: usize) -> Option<Vec<Complex<F>>> {
+        let n = self.channels.len();
+        let adler = self
+            .adler_zero
+            .map_or_else(F::one, |az| (s - az.s_0) / az.s_norm);
+        let pole_product = self.pole_product(s, None);
+        let ikc = DMatrix::from_fn(n, n, |i, j| {
+            let c_ij = self
+                .background
+                .as_ref()
+                .map_or_else(F::zero, |background| background[i][j]);
+            let k_ij = self
+                .poles
+                .iter()
+                .enumerate()
+                .map(|(a, pole)| {
+                    self.barrier_factor(s, i, a)
+                        * self.barrier_factor(s, j, a)
+                        * (pole.couplings[i] * pole.couplings[j] + c_ij * (pole.mass.powi(2) - s))
+                        * self.pole_product(s, Some(a))
+                })
+                .sum::<F>()
+                * adler;
+            let identity = if i == j { pole_product } else { F::zero() };
+            Complex::from(identity)
+                + Complex::from(k_ij)
+                    * math::chew_mandelstam(s, self.channels[j].m1, self.channels[j].m2)
+        });
+        let ikc_inv = F::try_inverse(ikc)?;
+        Some(ikc_inv.row(channel).iter().copied().collect())
+    }
+
+    /// Computes the constants $`g_{j\alpha} B_{j\alpha}(s) \prod_{\beta \neq \alpha} (m_\beta^2 -
+    /// s)`$ which multiply each production coupling $`\beta_\alpha`$ in the $`P`$-vector, indexed
+    /// by channel and then by pole.
+    fn p_vector_constants(&self, s: F) -> Vec<Vec<Complex<F>>> {
+        (0..self.channels.len())
+            .map(|j| {
+                self.poles
+                    .iter()
+                    .enumerate()
+                    .map(|(a, pole)| {
+                        Complex::from(self.barrier_factor(s, j, a))
+                            * pole.couplings[j]
+                            * self.pole_product(s, Some(a))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Computes $`\sum_j a_j b_j`$, adding the terms of four-element vectors in pairs like the
+/// fixed-size dot product in [`nalgebra`] does.
+fn dot<F: Field>(a: &[Complex<F>], b: &[Complex<F>]) -> Complex<F> {
+    if a.len() == 4 {
+        (a[0] * b[0] + a[2] * b[2]) + (a[1] * b[1] + a[3] * b[3])
+    } else {
+        a.iter().zip(b).map(|(a_j, b_j)| *a_j * *b_j).sum()
+    }
+}
+
+/// The values precalculated by a [`KMatrix`] for each [`Event`]: the row of
+/// $`(P(s) I + \hat{K}(s) C(s))^{-1}`$ (see [`KMatrixConfig::ikc_inv_row`]), the
+/// [`KMatrixConfig::p_vector_constants`], and the pole product $`P(s)`$.
+#[derive(Clone)]
+struct KMatrixData<F: Field> {
+    ikc_inv: Vec<Complex<F>>,
+    p_vector_constants: Vec<Vec<Complex<F>>>,
+    pole_product: F,
+}
+
+/// A [`Node`] for a user-configurable $`K`$-matrix describing a set of poles coupled to several
+/// decay channels, with production described by a $`P`$-vector.
+///
+/// The invariant mass squared $`s`$ is that of the sum of the [`Event::daughter_p4s`] at the
+/// given indices. With the couplings $`g_{i\alpha}`$ and masses $`m_\alpha`$ of each
+/// [`KMatrixPole`], the background terms $`c_{ij}`$, and Blatt-Weisskopf barrier factors
+/// $`B_{i\alpha}(s) = B_\ell(q_i(\sqrt{s})) / B_\ell(q_i(m_\alpha))`$ (see [`KMatrixConfig`]),
+///
+/// ```math
+/// K_{ij}(s) = \sum_\alpha B_{i\alpha}(s) B_{j\alpha}(s) \left(\frac{g_{i\alpha} g_{j\alpha}}{m_\alpha^2 - s} + c_{ij}\right),
+/// \quad P_j(s) = \sum_\alpha \frac{\beta_\alpha g_{j\alpha} B_{j\alpha}(s)}{m_\alpha^2 - s},
+/// ```
+///
+/// (where $`K`$ is multiplied by the [`AdlerZero`] factor if there is one), and this struct
+/// implements [`Node`] to calculate
+///
+/// ```math
+/// \sum_j \left[(I + K(s) C(s))^{-1}\right]_{cj} P_j(s)
+/// ```
+///
+/// where $`c`$ is the channel of the observed decay and $`C(s)`$ is the diagonal matrix of
+/// Chew-Mandelstam functions (see [`math::chew_mandelstam`]) of each channel. Non-resonant
+/// production in a set of channels can be added with [`KMatrix::with_non_resonant`], which adds a
+/// constant to the $`P`$-vector in each of those channels.
+///
+/// # Parameters:
+///
+/// - `{pole} re`, `{pole} im`: The complex production coupling $`\beta_\alpha`$ of each pole.
+/// - `{channel} non-resonant re`, `{channel} non-resonant im`: The non-resonant production in
+///   each channel given to [`KMatrix::with_non_resonant`].
+#[derive(Clone)]
+pub struct KMatrix<F: Field> {
+    daughters_1: Vec<usize>,
+    daughters_2: Vec<usize>,
+    channel: usize,
+    config: KMatrixConfig<F>,
+    non_resonant: Vec<usize>,
+    data: Vec<KMatrixData<F>>,
+}
+impl<F: Field + 'static> KMatrix<F> {
+    /// Creates a new [`KMatrix`] for a system which decays into the sums of the daughters at
+    /// `daughters_1` and `daughters_2` through the given `channel` (an index into
+    /// [`KMatrixConfig::channels`]).
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if either group of
+    /// daughters is empty, if the [`KMatrixConfig`] is invalid (see
+    /// [`KMatrixConfig::validate`]), or if the channel is out of range.
+    pub fn new(
+        daughters_1: &[usize],
+        daughters_2: &[usize],
+        channel: usize,
+        config: KMatrixConfig<F>,
+    ) -> Result<Self, RustitudeError> {
+        check_daughters(daughters_1, daughters_2)?;
+        config.validate()?;
+        if channel >= config.channels.len() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "invalid channel {channel} for a K-matrix with {} channels",
+                config.channels.len()
+            )));
+        }
+        Ok(Self {
+            daughters_1: daughters_1.to_vec(),
+            daughters_2: daughters_2.to_vec(),
+            channel,
+            config,
+            non_resonant: Vec::new(),
+            data: Vec::default(),
+        })
+    }
+
+    /// Adds non-resonant production in each of the given channels.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if a channel is out
+    /// of range or repeated.
+    pub fn with_non_resonant(mut self, channels: &[usize]) -> Result<Self, RustitudeError> {
+        for (i, channel) in channels.iter().enumerate() {
+            if *channel >= self.config.channels.len() || channels[..i].contains(channel) {
+                return Err(RustitudeError::InvalidParameterValue(format!(
+                    "invalid non-resonant production channels {channels:?} for a K-matrix with {} channels",
+                    self.config.channels.len()
+                )));
+            }
+        }
+        self.non_resonant = channels.to_vec();
+        Ok(self)
+    }
+
+    /// Returns the [`KMatrixConfig`] of the [`KMatrix`].
+    pub const fn config(&self) -> &KMatrixConfig<F> {
+        &self.config
+    }
+}
+impl<F: Field + 'static> Node<F> for KMatrix<F> {
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        self.data = dataset
+            .events
+            .par_iter()
+            .map(|event| {
+                let p1 = daughters_p4(event, &self.daughters_1)?;
+                let p2 = daughters_p4(event, &self.daughters_2)?;
+                let s = (p1 + p2).m2();
+                let ikc_inv = self.config.ikc_inv_row(s, self.channel).ok_or_else(|| {
+                    RustitudeError::EvaluationError(format!(
+                        "the K-matrix is singular for event #{}",
+                        event.index
+                    ))
+                })?;
+                Ok(KMatrixData {
+                    ikc_inv,
+                    p_vector_constants: self.config.p_vector_constants(s),
+                    pole_product: self.config.pole_product(s, None),
+                })
+            })
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        Ok(())
+    }
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let data = &self.data[event.index];
+        let (betas, non_resonant) = parameters.split_at(2 * self.config.poles.len());
+        let p_vector: Vec<Complex<F>> = data
+            .p_vector_constants
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(betas.chunks_exact(2))
+                    .map(|(constant, beta)| Complex::new(beta[0], beta[1]) * *constant)
+                    .sum()
+            })
+            .collect();
+        let non_resonant_term = self
+            .non_resonant
+            .iter()
+            .zip(non_resonant.chunks_exact(2))
+            .map(|(&j, beta)| data.ikc_inv[j] * Complex::new(beta[0], beta[1]))
+            .sum::<Complex<F>>()
+            * data.pole_product;
+        Ok(dot(&data.ikc_inv, &p_vector) + non_resonant_term)
+    }
+    fn calculate_gradient(
+        &self,
+        _parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        let data = &self.data[event.index];
+        let poles = (0..self.config.poles.len()).map(|a| {
+            let column: Vec<Complex<F>> =
+                data.p_vector_constants.iter().map(|row| row[a]).collect();
+            dot(&data.ikc_inv, &column)
+        });
+        let non_resonant = self
+            .non_resonant
+            .iter()
+            .map(|&j| data.ikc_inv[j] * data.pole_product);
+        Ok(poles
+            .chain(non_resonant)
+            .flat_map(|coefficient| [coefficient, coefficient * Complex::<F>::i()])
+            .collect())
+    }
+    fn parameters(&self) -> Vec<String> {
+        let poles = self.config.poles.iter().map(|pole| pole.name.as_str());
+        let non_resonant = self
+            .non_resonant
+            .iter()
+            .map(|&j| format!("{} non-resonant", self.config.channels[j].name));
+        poles
+            .map(str::to_string)
+            .chain(non_resonant)
+            .flat_map(|label| [format!("{label} re"), format!("{label} im")])
+            .collect()
+    }
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "KMatrix",
+            serde_json::json!({
+                "daughters_1": self.daughters_1,
+                "daughters_2": self.daughters_2,
+                "channel": self.channel,
+                "config": self.config,
+                "non_resonant": self.non_resonant,
+            }),
+        ))
+    }
+}
+
+/// A generic struct which can be used to create any kind of piecewise function.
+#[derive(Clone)]
+pub struct Piecewise<V, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    edges: Vec<(F, F)>,
+    variable: V,
+    calculated_variable: Vec<F>,
+    spec: Option<NodeSpec>,
+}
+
+impl<V, F> Piecewise<V, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    /// Create a new [`Piecewise`] struct from a number of bins, a range of values, and a callable
+    /// which defines a variable over the [`Event`]s in a [`Dataset`].
+    pub fn new(bins: usize, range: (F, F), variable: V) -> Self {
+        let diff = (range.1 - range.0) / convert!(bins, F);
+        let edges = (0..bins)
+            .map(|i| {
+                (
+                    F::mul_add(convert!(i, F), diff, range.0),
+                    F::mul_add(convert!(i + 1, F), diff, range.0),
+                )
+            })
+            .collect();
+        Self {
+            edges,
+            variable,
+            calculated_variable: Vec::default(),
+            spec: None,
+        }
+    }
+
+    /// Attach a [`NodeSpec`] to the [`Piecewise`] so that it can be serialized. Since the binning
+    /// variable is an arbitrary callable, the [`NodeSpec`] must name a builder in the
+    /// [`NodeRegistry`](crate::serialization::NodeRegistry) which reconstructs the same variable.
+    pub fn with_spec(mut self, spec: NodeSpec) -> Self {
+        self.spec = Some(spec);
+        self
+    }
+}
+
+impl<V, F> Node<F> for Piecewise<V, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        self.calculated_variable = dataset.events.par_iter().map(self.variable).collect();
+        Ok(())
+    }
+
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let val = self.calculated_variable[event.index];
+        let opt_i_bin = self.edges.iter().position(|&(l, r)| val >= l && val <= r);
+        opt_i_bin.map_or_else(
+            || Ok(Complex::default()),
+            |i_bin| {
+                Ok(Complex::new(
+                    parameters[i_bin * 2],
+                    parameters[(i_bin * 2) + 1],
+                ))
+            },
+        )
+    }
+
+    fn calculate_batch(
+        &self,
+        parameters: &[F],
+        events: &[Event<F>],
+        out: &mut [Complex<F>],
+    ) -> Result<(), RustitudeError> {
+        let mut values = vec![F::zero(); events.len()];
+        gather_batch(&self.calculated_variable, events, &mut values);
+        for (value, val) in out.iter_mut().zip(values) {
+            *value = self
+                .edges
+                .iter()
+                .position(|&(l, r)| val >= l && val <= r)
+                .map_or_else(Complex::default, |i_bin| {
+                    Complex::new(parameters[i_bin * 2], parameters[(i_bin * 2) + 1])
+                });
+        }
+        Ok(())
+    }
+    fn calculate_gradient(
+        &self,
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        let val = self.calculated_variable[event.index];
+        let mut gradient = vec![Complex::default(); parameters.len()];
+        if let Some(i_bin) = self.edges.iter().position(|&(l, r)| val >= l && val <= r) {
+            gradient[i_bin * 2] = Complex::new(F::one(), F::zero());
+            gradient[(i_bin * 2) + 1] = Complex::new(F::zero(), F::one());
+        }
+        Ok(gradient)
+    }
+
+    fn parameters(&self) -> Vec<String> {
+        (0..self.edges.len())
+            .flat_map(|i| vec![format!("bin {} re", i), format!("bin {} im", i)])
+            .collect()
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        self.spec.clone()
+    }
+}
+
+/// A generic struct which can be used to create a piecewise function of two variables, such as
+/// a function binned in both mass and $`t`$.
+///
+/// Each of the `bins.0 * bins.1` bins has a complex value with real and imaginary parts named
+/// `{x} bin {i}, {y} bin {j} re` and `{x} bin {i}, {y} bin {j} im`, where `x` and `y` are the
+/// labels of the two variables (`"x"` and `"y"` by default, see [`Piecewise2D::with_labels`]).
+/// The value is zero for any [`Event`] outside of the binned region.
+#[derive(Clone)]
+pub struct Piecewise2D<V, W, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    W: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    x_edges: Vec<(F, F)>,
+    y_edges: Vec<(F, F)>,
+    variables: (V, W),
+    labels: (String, String),
+    calculated_bin: Vec<Option<usize>>,
+    spec: Option<NodeSpec>,
+}
+
+impl<V, W, F> Piecewise2D<V, W, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    W: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    /// Create a new [`Piecewise2D`] struct from the number of bins and the range of values of each
+    /// of two callables which define variables over the [`Event`]s in a [`Dataset`].
+    pub fn new(bins: (usize, usize), ranges: ((F, F), (F, F)), variables: (V, W)) -> Self {
+        let x = Piecewise::new(bins.0, ranges.0, variables.0);
+        let y = Piecewise::new(bins.1, ranges.1, variables.1);
+        Self {
+            x_edges: x.edges,
+            y_edges: y.edges,
+            variables,
+            labels: ("x".to_string(), "y".to_string()),
+            calculated_bin: Vec::default(),
+            spec: None,
+        }
+    }
+
+    /// Set the labels of the two variables used in the names of the parameters.
+    pub fn with_labels(mut self, x: &str, y: &str) -> Self {
+        self.labels = (x.to_string(), y.to_string());
+        self
+    }
+
+    /// Attach a [`NodeSpec`] to the [`Piecewise2D`] so that it can be serialized (see
+    /// [`Piecewise::with_spec`]).
+    pub fn with_spec(mut self, spec: NodeSpec) -> Self {
+        self.spec = Some(spec);
+        self
+    }
+
+    fn bin(&self, event: &Event<F>) -> Option<usize> {
+        let (x, y) = ((self.variables.0)(event), (self.variables.1)(event));
+        let i = self.x_edges.iter().position(|&(l, r)| x >= l && x <= r)?;
+        let j = self.y_edges.iter().position(|&(l, r)| y >= l && y <= r)?;
+        Some(i * self.y_edges.len() + j)
+    }
+}
+
+impl<V, W, F> Node<F> for Piecewise2D<V, W, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    W: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        self.calculated_bin = dataset
+            .events
+            .par_iter()
+            .map(|event| self.bin(event))
+            .collect();
+        Ok(())
+    }
+
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        Ok(
+            self.calculated_bin[event.index].map_or_else(Complex::default, |i_bin| {
+                Complex::new(parameters[i_bin * 2], parameters[(i_bin * 2) + 1])
+            }),
+        )
+    }
+
+    fn calculate_gradient(
+        &self,
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        let mut gradient = vec![Complex::default(); parameters.len()];
+        if let Some(i_bin) = self.calculated_bin[event.index] {
+            gradient[i_bin * 2] = Complex::new(F::one(), F::zero());
+            gradient[(i_bin * 2) + 1] = Complex::new(F::zero(), F::one());
+        }
+        Ok(gradient)
+    }
+
+    fn parameters(&self) -> Vec<String> {
+        let (x, y) = &self.labels;
+        (0..self.x_edges.len())
+            .cartesian_product(0..self.y_edges.len())
+            .flat_map(|(i, j)| {
+                [
+                    format!("{x} bin {i}, {y} bin {j} re"),
+                    format!("{x} bin {i}, {y} bin {j} im"),
+                ]
+            })
+            .collect()
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        self.spec.clone()
+    }
+}
+
+pub fn piecewise_m<F: Field + 'static>(name: &str, bins: usize, range: (F, F)) -> Amplitude<F> {
+    //! Creates a named [`Piecewise`] amplitude with the resonance mass as the binning variable.
+    Amplitude::new(
+        name,
+        Piecewise::new(bins, range, |e: &Event<F>| {
+            (e.daughter_p4s[0] + e.daughter_p4s[1]).m()
+        })
+        .with_spec(NodeSpec::new(
+            "PiecewiseM",
+            serde_json::json!({ "bins": bins, "range": range }),
+        )),
+    )
+}
+
+pub fn piecewise<F, V>(name: &str, bins: usize, range: (F, F), variable: V) -> Amplitude<F>
+where
+    F: Field + 'static,
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy + 'static,
+{
+    //! Creates a named [`Piecewise`] amplitude binned in a user-supplied variable. Since the
+    //! variable is an arbitrary callable, the resulting [`Amplitude`] cannot be serialized.
+    Amplitude::new(name, Piecewise::new(bins, range, variable))
+}
+
+pub fn piecewise_t<F: Field + 'static>(name: &str, bins: usize, range: (F, F)) -> Amplitude<F> {
+    //! Creates a named [`Piecewise`] amplitude with the Mandelstam $`t`$ as the binning variable.
+    //!
+    //! Here $`t = (p_{\text{beam}} - p_{X})^2`$, where $`X`$ is the system of the first two
+    //! daughters, so $`t`$ is negative in the physical region.
+    Amplitude::new(
+        name,
+        Piecewise::new(bins, range, |e: &Event<F>| {
+            (e.beam_p4 - e.daughter_p4s[0] - e.daughter_p4s[1]).m2()
+        })
+        .with_spec(NodeSpec::new(
+            "PiecewiseT",
+            serde_json::json!({ "bins": bins, "range": range }),
+        )),
+    )
+}
+
+pub fn piecewise_2d<F, V, W>(
+    name: &str,
+    bins: (usize, usize),
+    ranges: ((F, F), (F, F)),
+    variables: (V, W),
+) -> Amplitude<F>
+where
+    F: Field + 'static,
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy + 'static,
+    W: Fn(&Event<F>) -> F + Send + Sync + Copy + 'static,
+{
+    //! Creates a named [`Piecewise2D`] amplitude binned in two user-supplied variables. Since the
+    //! variables are arbitrary callables, the resulting [`Amplitude`] cannot be serialized.
+    Amplitude::new(name, Piecewise2D::new(bins, ranges, variables))
+}
+
+pub fn piecewise_m_t<F: Field + 'static>(
+    name: &str,
+    bins: (usize, usize),
+    ranges: ((F, F), (F, F)),
+) -> Amplitude<F> {
+    //! Creates a named [`Piecewise2D`] amplitude binned in the resonance mass and the Mandelstam
+    //! $`t`$ (as defined in [`piecewise_m`] and [`piecewise_t`]).
+    //!
+    //! The parameters are named `m bin {i}, t bin {j} re` and `m bin {i}, t bin {j} im`.
+    Amplitude::new(
+        name,
+        Piecewise2D::new(
+            bins,
+            ranges,
+            (
+                |e: &Event<F>| (e.daughter_p4s[0] + e.daughter_p4s[1]).m(),
+                |e: &Event<F>| (e.beam_p4 - e.daughter_p4s[0] - e.daughter_p4s[1]).m2(),
+            ),
+        )
+        .with_labels("m", "t")
+        .with_spec(NodeSpec::new(
+            "PiecewiseMT",
+            serde_json::json!({ "bins": bins, "ranges": ranges }),
+        )),
+    )
+}
+
+pub fn piecewise_costheta<F: Field + 'static>(
+    name: &str,
+    bins: usize,
+    range: (F, F),
+) -> Amplitude<F> {
+    //! Creates a named [`Piecewise`] amplitude with $`\cos\theta`$ as the binning variable.
+    //!
+    //! Here $`\theta`$ is the polar angle of the first daughter in the rest frame of the first two
+    //! daughters, measured from the beam direction (the Gottfried-Jackson frame).
+    Amplitude::new(
+        name,
+        Piecewise::new(bins, range, |e: &Event<F>| {
+            let resonance = e.daughter_p4s[0] + e.daughter_p4s[1];
+            frames::decay_angles(Frame::GottfriedJackson, e, &resonance, &e.daughter_p4s[0]).0
+        })
+        .with_spec(NodeSpec::new(
+            "PiecewiseCosTheta",
+            serde_json::json!({ "bins": bins, "range": range }),
+        )),
+    )
+}
+
+pub fn piecewise_beam_energy<F: Field + 'static>(
+    name: &str,
+    bins: usize,
+    range: (F, F),
+) -> Amplitude<F> {
+    //! Creates a named [`Piecewise`] amplitude with the beam energy as the binning variable.
+    Amplitude::new(
+        name,
+        Piecewise::new(bins, range, |e: &Event<F>| e.beam_p4.e()).with_spec(NodeSpec::new(
+            "PiecewiseBeamEnergy",
+            serde_json::json!({ "bins": bins, "range": range }),
+        )),
+    )
+}
+
+/// The basis in which the coefficients of a [`Polynomial`] are defined.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum PolynomialBasis<F: Field> {
+    /// The monomials $`x^k`$ of the variable itself.
+    Power,
+    /// The Chebyshev polynomials $`T_k(u)`$ of the first kind, where $`u \in [-1, 1]`$ is the
+    /// variable mapped linearly from the range $`[a, b]`$. The [`Polynomial`] is zero outside of
+    /// this range.
+    Chebyshev(F, F),
+}
+
+/// A generic struct which can be used to create a smooth real-valued polynomial over any variable,
+/// such as a non-resonant background.
+///
+/// The coefficients of each basis polynomial up to the given order are free parameters named
+/// `c {k}`.
+#[derive(Clone)]
+pub struct Polynomial<V, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    order: usize,
+    basis: PolynomialBasis<F>,
+    variable: V,
+    terms: Vec<Option<Vec<F>>>,
+    spec: Option<NodeSpec>,
+}
+
+impl<V, F> Polynomial<V, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    /// Create a new [`Polynomial`] struct from its order, the basis of its coefficients, and a
+    /// callable which defines a variable over the [`Event`]s in a [`Dataset`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if a
+    /// [`PolynomialBasis::Chebyshev`] range is empty or reversed.
+    pub fn new(
+        order: usize,
+        basis: PolynomialBasis<F>,
+        variable: V,
+    ) -> Result<Self, RustitudeError> {
+        if let PolynomialBasis::Chebyshev(a, b) = basis {
+            if a.partial_cmp(&b) != Some(std::cmp::Ordering::Less) {
+                return Err(RustitudeError::InvalidParameterValue(
+                    "a Chebyshev range must be non-empty and increasing".to_string(),
+                ));
+            }
+        }
+        Ok(Self {
+            order,
+            basis,
+            variable,
+            terms: Vec::default(),
+            spec: None,
+        })
+    }
+
+    /// Attach a [`NodeSpec`] to the [`Polynomial`] so that it can be serialized (see
+    /// [`Piecewise::with_spec`]).
+    pub fn with_spec(mut self, spec: NodeSpec) -> Self {
+        self.spec = Some(spec);
+        self
+    }
+
+    /// Evaluates each basis polynomial up to the order of the [`Polynomial`] at `x`, or returns
+    /// [`None`] if `x` is outside of the range of a [`PolynomialBasis::Chebyshev`] basis.
+    fn basis_values(&self, x: F) -> Option<Vec<F>> {
+        let mut values = Vec::with_capacity(self.order + 1);
+        match self.basis {
+            PolynomialBasis::Power => {
+                let mut term = F::one();
+                for _ in 0..=self.order {
+                    values.push(term);
+                    term *= x;
+                }
+            }
+            PolynomialBasis::Chebyshev(a, b) => {
+                if x < a || x > b {
+                    return None;
+                }
+                let u = (convert!(2, F) * x - a - b) / (b - a);
+                values.push(F::one());
+                if self.order > 0 {
+                    values.push(u);
+                }
+                for k in 2..=self.order {
+                    values.push(convert!(2, F) * u * values[k - 1] - values[k - 2]);
+                }
+            }
+        }
+        Some(values)
+    }
+}
+
+impl<V, F> Node<F> for Polynomial<V, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        self.terms = dataset
+            .events
+            .par_iter()
+            .map(|event| self.basis_values((self.variable)(event)))
+            .collect();
+        Ok(())
+    }
+
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        Ok(self.terms[event.index]
+            .as_ref()
+            .map_or_else(Complex::default, |terms| {
+                Complex::new(
+                    terms
+                        .iter()
+                        .zip(parameters)
+                        .map(|(&term, &coefficient)| term * coefficient)
+                        .sum(),
+                    F::zero(),
+                )
+            }))
+    }
+
+    fn calculate_gradient(
+        &self,
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        Ok(self.terms[event.index].as_ref().map_or_else(
+            || vec![Complex::default(); parameters.len()],
+            |terms| {
+                terms
+                    .iter()
+                    .map(|&term| Complex::new(term, F::zero()))
+                    .collect()
+            },
+        ))
     }
-}
 
-/// A [`Node`] for computing a single scalar value from an input parameter.
-///
-/// This struct implements [`Node`] to generate a single new parameter called `value`.
-///
-/// # Parameters:
-///
-/// - `value`: The value of the scalar.
-#[derive(Clone)]
-pub struct Scalar;
-impl<F: Field> Node<F> for Scalar {
     fn parameters(&self) -> Vec<String> {
-        vec!["value".to_string()]
+        (0..=self.order).map(|k| format!("c {k}")).collect()
     }
-    fn calculate(&self, parameters: &[F], _event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        Ok(Complex::new(parameters[0], F::zero()))
+
+    fn spec(&self) -> Option<NodeSpec> {
+        self.spec.clone()
     }
 }
 
-/// Creates a named [`Scalar`].
-///
-/// This is a convenience method to generate an [`Amplitude`] which is just a single free
-/// parameter called `value`.
-///
-/// # Examples
+/// Creates a named [`Polynomial`] amplitude with the resonance mass as the variable.
 ///
-/// Basic usage:
+/// # Errors
 ///
-/// ```
-/// use rustitude_core::prelude::*;
-/// let my_scalar: Amplitude<f64> = scalar("MyScalar");
-/// assert_eq!(my_scalar.parameters, vec!["value".to_string()]);
-/// ```
-pub fn scalar<F: Field>(name: &str) -> Amplitude<F> {
-    Amplitude::new(name, Scalar)
+/// This function will return a [`RustitudeError::InvalidParameterValue`] if the basis is invalid
+/// (see [`Polynomial::new`]).
+pub fn polynomial_m<F: Field + 'static>(
+    name: &str,
+    order: usize,
+    basis: PolynomialBasis<F>,
+) -> Result<Amplitude<F>, RustitudeError> {
+    let spec = NodeSpec::new(
+        "PolynomialM",
+        serde_json::json!({ "order": order, "basis": basis }),
+    );
+    Ok(Amplitude::new(
+        name,
+        Polynomial::new(order, basis, |e: &Event<F>| {
+            (e.daughter_p4s[0] + e.daughter_p4s[1]).m()
+        })?
+        .with_spec(spec),
+    ))
 }
-/// A [`Node`] for computing a single complex value from two input parameters.
-///
-/// This struct implements [`Node`] to generate a complex value from two input parameters called
-/// `real` and `imag`.
-///
-/// # Parameters:
+
+/// The interpolation used by a [`Spline`] between its knots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SplineKind {
+    /// A natural cubic spline, which has continuous first and second derivatives.
+    Cubic,
+    /// A monotone (Fritsch-Carlson) cubic Hermite spline, which has a continuous first
+    /// derivative and never overshoots the knot values.
+    Monotone,
+}
+
+/// The pair of free parameters which determine the complex value at each knot of a [`Spline`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KnotForm {
+    /// The real and imaginary parts are interpolated (parameters `knot {i} re` and
+    /// `knot {i} im`).
+    Cartesian,
+    /// The magnitude and phase are interpolated (parameters `knot {i} mag` and `knot {i} phi`).
+    Polar,
+}
+
+/// A generic struct which can be used to create a smooth lineshape over any variable by
+/// interpolating free complex values at a set of knots.
 ///
-/// - `real`: The real part of the complex scalar.
-/// - `imag`: The imaginary part of the complex scalar.
+/// Unlike a [`Piecewise`] function, a [`Spline`] is continuous at its knots. Like a
+/// [`Piecewise`] function, it is zero outside of the range of the knots.
 #[derive(Clone)]
-pub struct ComplexScalar;
-impl<F: Field> Node<F> for ComplexScalar {
-    fn calculate(&self, parameters: &[F], _event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        Ok(Complex::new(parameters[0], parameters[1]))
+pub struct Spline<V, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    knots: Vec<F>,
+    kind: SplineKind,
+    form: KnotForm,
+    /// The second derivatives at each knot of a natural cubic spline through a unit value at
+    /// each knot, so `curvature[j][i]` is the derivative of the curvature at knot `i` with
+    /// respect to the value at knot `j`.
+    curvature: Vec<Vec<F>>,
+    variable: V,
+    positions: Vec<Option<(usize, F)>>,
+    spec: Option<NodeSpec>,
+}
+
+impl<V, F> Spline<V, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    /// Create a new [`Spline`] struct from a list of knots, the kind of interpolation, the form
+    /// of the knot parameters, and a callable which defines a variable over the [`Event`]s in a
+    /// [`Dataset`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are fewer
+    /// than two knots or if the knots are not strictly increasing.
+    pub fn new(
+        knots: Vec<F>,
+        kind: SplineKind,
+        form: KnotForm,
+        variable: V,
+    ) -> Result<Self, RustitudeError> {
+        if knots.len() < 2 {
+            return Err(RustitudeError::InvalidParameterValue(
+                "a spline requires at least two knots".to_string(),
+            ));
+        }
+        if knots
+            .windows(2)
+            .any(|w| w[1].partial_cmp(&w[0]) != Some(std::cmp::Ordering::Greater))
+        {
+            return Err(RustitudeError::InvalidParameterValue(
+                "spline knots must be strictly increasing".to_string(),
+            ));
+        }
+        let curvature = Self::natural_curvature(&knots);
+        Ok(Self {
+            knots,
+            kind,
+            form,
+            curvature,
+            variable,
+            positions: Vec::default(),
+            spec: None,
+        })
+    }
+
+    /// Attach a [`NodeSpec`] to the [`Spline`] so that it can be serialized (see
+    /// [`Piecewise::with_spec`]).
+    pub fn with_spec(mut self, spec: NodeSpec) -> Self {
+        self.spec = Some(spec);
+        self
+    }
+
+    /// Solves for the second derivatives of a natural cubic spline through a unit value at each
+    /// knot in turn.
+    fn natural_curvature(knots: &[F]) -> Vec<Vec<F>> {
+        let n = knots.len();
+        let h: Vec<F> = knots.windows(2).map(|w| w[1] - w[0]).collect();
+        (0..n)
+            .map(|j| {
+                let y = |i: usize| if i == j { F::one() } else { F::zero() };
+                // Thomas algorithm over the interior knots, with zero curvature at both ends
+                let mut c_prime = vec![F::zero(); n];
+                let mut d_prime = vec![F::zero(); n];
+                for i in 1..n - 1 {
+                    let lower = h[i - 1];
+                    let diag = convert!(2, F) * (h[i - 1] + h[i]);
+                    let upper = h[i];
+                    let rhs =
+                        convert!(6, F) * ((y(i + 1) - y(i)) / h[i] - (y(i) - y(i - 1)) / h[i - 1]);
+                    let denom = diag - lower * c_prime[i - 1];
+                    c_prime[i] = upper / denom;
+                    d_prime[i] = (rhs - lower * d_prime[i - 1]) / denom;
+                }
+                let mut column = vec![F::zero(); n];
+                for i in (1..n - 1).rev() {
+                    column[i] = d_prime[i] - c_prime[i] * column[i + 1];
+                }
+                column
+            })
+            .collect()
+    }
+
+    /// Evaluates the interpolation of the values `y(i)` at the knots, at a position `x` in the
+    /// segment starting at knot `k`.
+    fn interpolate(&self, y: impl Fn(usize) -> F, k: usize, x: F) -> F {
+        let h = self.knots[k + 1] - self.knots[k];
+        match self.kind {
+            SplineKind::Cubic => {
+                let a = (self.knots[k + 1] - x) / h;
+                let b = F::one() - a;
+                let (mut m_k, mut m_k1) = (F::zero(), F::zero());
+                for (j, column) in self.curvature.iter().enumerate() {
+                    m_k += column[k] * y(j);
+                    m_k1 += column[k + 1] * y(j);
+                }
+                a * y(k)
+                    + b * y(k + 1)
+                    + ((a.powi(3) - a) * m_k + (b.powi(3) - b) * m_k1) * h.powi(2) / convert!(6, F)
+            }
+            SplineKind::Monotone => {
+                let t = (x - self.knots[k]) / h;
+                let (t2, t3) = (t.powi(2), t.powi(3));
+                let two = convert!(2, F);
+                let three = convert!(3, F);
+                (two * t3 - three * t2 + F::one()) * y(k)
+                    + (t3 - two * t2 + t) * h * self.monotone_slope(&y, k)
+                    + (three * t2 - two * t3) * y(k + 1)
+                    + (t3 - t2) * h * self.monotone_slope(&y, k + 1)
+            }
+        }
+    }
+
+    /// Computes the Fritsch-Carlson slope at knot `i`, using one-sided differences at the ends.
+    fn monotone_slope(&self, y: &impl Fn(usize) -> F, i: usize) -> F {
+        let secant = |k: usize| (y(k + 1) - y(k)) / (self.knots[k + 1] - self.knots[k]);
+        let n = self.knots.len();
+        if i == 0 {
+            return secant(0);
+        }
+        if i == n - 1 {
+            return secant(n - 2);
+        }
+        let (left, right) = (secant(i - 1), secant(i));
+        if left * right <= F::zero() {
+            return F::zero();
+        }
+        let (h_left, h_right) = (
+            self.knots[i] - self.knots[i - 1],
+            self.knots[i + 1] - self.knots[i],
+        );
+        let w_left = convert!(2, F) * h_right + h_left;
+        let w_right = h_right + convert!(2, F) * h_left;
+        (w_left + w_right) / (w_left / left + w_right / right)
+    }
+}
+
+impl<V, F> Node<F> for Spline<V, F>
+where
+    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    F: Field,
+{
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        let last = self.knots.len() - 1;
+        self.positions = dataset
+            .events
+            .par_iter()
+            .map(|event| {
+                let x = (self.variable)(event);
+                if x < self.knots[0] || x > self.knots[last] {
+                    return None;
+                }
+                let k = self.knots.partition_point(|&knot| knot <= x).clamp(1, last) - 1;
+                Some((k, x))
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let Some((k, x)) = self.positions[event.index] else {
+            return Ok(Complex::default());
+        };
+        let first = self.interpolate(|i| parameters[2 * i], k, x);
+        let second = self.interpolate(|i| parameters[2 * i + 1], k, x);
+        Ok(match self.form {
+            KnotForm::Cartesian => Complex::new(first, second),
+            KnotForm::Polar => Complex::from_polar(first, second),
+        })
     }
 
     fn parameters(&self) -> Vec<String> {
-        vec!["real".to_string(), "imag".to_string()]
+        let (first, second) = match self.form {
+            KnotForm::Cartesian => ("re", "im"),
+            KnotForm::Polar => ("mag", "phi"),
+        };
+        (0..self.knots.len())
+            .flat_map(|i| vec![format!("knot {i} {first}"), format!("knot {i} {second}")])
+            .collect()
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        self.spec.clone()
     }
 }
-/// Creates a named [`ComplexScalar`].
-///
-/// This is a convenience method to generate an [`Amplitude`] which represents a complex
-/// value determined by two parameters, `real` and `imag`.
-///
-/// # Examples
+
+/// Creates a named [`Spline`] amplitude with the resonance mass as the variable.
 ///
-/// Basic usage:
+/// # Errors
 ///
-/// ```
-/// use rustitude_core::prelude::*;
-/// let my_cscalar: Amplitude<f64> = cscalar("MyComplexScalar");
-/// assert_eq!(my_cscalar.parameters, vec!["real".to_string(), "imag".to_string()]);
-/// ```
-pub fn cscalar<F: Field>(name: &str) -> Amplitude<F> {
-    Amplitude::new(name, ComplexScalar)
+/// This function will return a [`RustitudeError::InvalidParameterValue`] if the knots are
+/// invalid (see [`Spline::new`]).
+pub fn spline_m<F: Field + 'static>(
+    name: &str,
+    knots: Vec<F>,
+    kind: SplineKind,
+    form: KnotForm,
+) -> Result<Amplitude<F>, RustitudeError> {
+    let spec = NodeSpec::new(
+        "SplineM",
+        serde_json::json!({ "knots": knots, "kind": kind, "form": form }),
+    );
+    Ok(Amplitude::new(
+        name,
+        Spline::new(knots, kind, form, |e: &Event<F>| {
+            (e.daughter_p4s[0] + e.daughter_p4s[1]).m()
+        })?
+        .with_spec(spec),
+    ))
 }
 
-/// A [`Node`] for computing a single complex value from two input parameters in polar form.
-///
-/// This struct implements [`Node`] to generate a complex value from two input parameters called
-/// `mag` and `phi`.
-///
-/// # Parameters:
-///
-/// - `mag`: The magnitude of the complex scalar.
-/// - `phi`: The phase of the complex scalar.
+/// The shape of a [`Template`], either a histogram or a kernel density estimate.
 #[derive(Clone)]
-pub struct PolarComplexScalar;
-impl<F: Field> Node<F> for PolarComplexScalar {
-    fn calculate(&self, parameters: &[F], _event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        Ok(Complex::cis(parameters[1]).mul(parameters[0]))
+enum TemplateShape<F: Field> {
+    Histogram {
+        edges: Vec<Vec<F>>,
+        contents: Vec<F>,
+    },
+    Kde(Kde<F>),
+}
+
+impl<F: Field> TemplateShape<F> {
+    const fn dimension(&self) -> usize {
+        match self {
+            Self::Histogram { edges, .. } => edges.len(),
+            Self::Kde(kde) => kde.dimension(),
+        }
     }
 
-    fn parameters(&self) -> Vec<String> {
-        vec!["mag".to_string(), "phi".to_string()]
+    fn evaluate(&self, x: &[F]) -> Result<F, RustitudeError> {
+        match self {
+            Self::Histogram { edges, contents } => {
+                let mut index = 0;
+                for (axis, value) in edges.iter().zip(x) {
+                    if axis.len() < 2 || *value < axis[0] || *value > axis[axis.len() - 1] {
+                        return Ok(F::zero());
+                    }
+                    let bin = axis
+                        .partition_point(|edge| edge <= value)
+                        .saturating_sub(1)
+                        .min(axis.len() - 2);
+                    index = index * (axis.len() - 1) + bin;
+                }
+                Ok(contents[index])
+            }
+            Self::Kde(kde) => kde.evaluate(x),
+        }
     }
 }
 
-/// Creates a named [`PolarComplexScalar`].
-///
-/// This is a convenience method to generate an [`Amplitude `] which represents a complex
-/// value determined by two parameters, `real` and `imag`.
-///
-/// # Examples
-///
-/// Basic usage:
+/// A [`Node`] whose value is looked up from a fixed histogram or kernel density estimate (KDE)
+/// in some variables of each [`Event`].
 ///
-/// ```
-/// use rustitude_core::prelude::*;
-/// let my_pcscalar: Amplitude<f64> = pcscalar("MyPolarComplexScalar");
-/// assert_eq!(my_pcscalar.parameters, vec!["mag".to_string(), "phi".to_string()]);
-/// ```
-pub fn pcscalar<F: Field>(name: &str) -> Amplitude<F> {
-    Amplitude::new(name, PolarComplexScalar)
-}
-
-/// A generic struct which can be used to create any kind of piecewise function.
+/// The template is evaluated once per [`Event`] in [`Node::precalculate`], and the [`Node`]
+/// returns the square root of the template value so that the [`NormSqr`] of a [`Template`] is the
+/// template itself. This allows data-driven shapes (such as a background estimated from
+/// sidebands) to be added to the intensity as an incoherent term, usually multiplied by a
+/// [`scalar`] to give it a free normalization. A [`Template`] has no free parameters.
 #[derive(Clone)]
-pub struct Piecewise<V, F>
+pub struct Template<V, F>
 where
-    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    V: Fn(&Event<F>) -> Vec<F> + Send + Sync + Copy,
     F: Field,
 {
-    edges: Vec<(F, F)>,
-    variable: V,
-    calculated_variable: Vec<F>,
+    shape: TemplateShape<F>,
+    variables: V,
+    calculated_values: Vec<F>,
+    spec: Option<NodeSpec>,
 }
 
-impl<V, F> Piecewise<V, F>
+impl<V, F> Template<V, F>
 where
-    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    V: Fn(&Event<F>) -> Vec<F> + Send + Sync + Copy,
     F: Field,
 {
-    /// Create a new [`Piecewise`] struct from a number of bins, a range of values, and a callable
-    /// which defines a variable over the [`Event`]s in a [`Dataset`].
-    pub fn new(bins: usize, range: (F, F), variable: V) -> Self {
-        let diff = (range.1 - range.0) / convert!(bins, F);
-        let edges = (0..bins)
-            .map(|i| {
-                (
-                    F::mul_add(convert!(i, F), diff, range.0),
-                    F::mul_add(convert!(i + 1, F), diff, range.0),
-                )
-            })
-            .collect();
+    /// Create a new [`Template`] from a histogram and a callable which returns the variables
+    /// of an [`Event`] in which the histogram is binned.
+    ///
+    /// `edges` contains the (increasing) bin edges along each axis, and `contents` contains the
+    /// value in each bin, with the last axis varying fastest. [`Event`]s outside of the
+    /// histogram have a value of zero.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if any axis has fewer
+    /// than two edges or the number of bins does not match the length of `contents`.
+    pub fn histogram(
+        edges: Vec<Vec<F>>,
+        contents: Vec<F>,
+        variables: V,
+    ) -> Result<Self, RustitudeError> {
+        if edges.is_empty() || edges.iter().any(|axis| axis.len() < 2) {
+            return Err(RustitudeError::InvalidParameterValue(
+                "each histogram axis must have at least two edges".to_string(),
+            ));
+        }
+        let n_bins: usize = edges.iter().map(|axis| axis.len() - 1).product();
+        if n_bins != contents.len() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "histogram has {} bins but {} values",
+                n_bins,
+                contents.len()
+            )));
+        }
+        Ok(Self {
+            shape: TemplateShape::Histogram { edges, contents },
+            variables,
+            calculated_values: Vec::default(),
+            spec: None,
+        })
+    }
+
+    /// Create a new [`Template`] from a Gaussian kernel density estimate and a callable which
+    /// returns the variables of an [`Event`] in which the KDE is defined.
+    ///
+    /// Each of the `points` is a sample in those variables with an optional weight (the default
+    /// weight is one), and the kernel has the given bandwidth along each axis. The resulting
+    /// density is normalized to unity. See [`Template::from_kde`] to use a [`Kde`] built with
+    /// another [`Bandwidth`] rule.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are no
+    /// points, the dimensions of the points and bandwidths do not match, or the number of
+    /// weights does not match the number of points. See [`Bandwidth::select`].
+    pub fn kde(
+        points: Vec<Vec<F>>,
+        weights: Option<Vec<F>>,
+        bandwidths: Vec<F>,
+        variables: V,
+    ) -> Result<Self, RustitudeError> {
+        Ok(Self::from_kde(
+            Kde::new(points, weights, &Bandwidth::Fixed(bandwidths))?,
+            variables,
+        ))
+    }
+
+    /// Create a new [`Template`] from a [`Kde`] and a callable which returns the variables of an
+    /// [`Event`] in which the [`Kde`] is defined.
+    pub const fn from_kde(kde: Kde<F>, variables: V) -> Self {
         Self {
-            edges,
-            variable,
-            calculated_variable: Vec::default(),
+            shape: TemplateShape::Kde(kde),
+            variables,
+            calculated_values: Vec::new(),
+            spec: None,
         }
     }
+
+    /// Attach a [`NodeSpec`] to the [`Template`] so that it can be serialized (see
+    /// [`Piecewise::with_spec`]).
+    pub fn with_spec(mut self, spec: NodeSpec) -> Self {
+        self.spec = Some(spec);
+        self
+    }
 }
 
-impl<V, F> Node<F> for Piecewise<V, F>
+impl<V, F> Node<F> for Template<V, F>
 where
-    V: Fn(&Event<F>) -> F + Send + Sync + Copy,
+    V: Fn(&Event<F>) -> Vec<F> + Send + Sync + Copy,
     F: Field,
 {
     fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
-        self.calculated_variable = dataset.events.par_iter().map(self.variable).collect();
+        let dimension = self.shape.dimension();
+        self.calculated_values = dataset
+            .events
+            .par_iter()
+            .map(|event| {
+                let x = (self.variables)(event);
+                if x.len() != dimension {
+                    return Err(RustitudeError::EvaluationError(format!(
+                        "template expects {} variables but got {}",
+                        dimension,
+                        x.len()
+                    )));
+                }
+                Ok(self.shape.evaluate(&x)?.max(F::zero()).sqrt())
+            })
+            .collect::<Result<Vec<F>, RustitudeError>>()?;
         Ok(())
     }
 
-    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        let val = self.calculated_variable[event.index];
-        let opt_i_bin = self.edges.iter().position(|&(l, r)| val >= l && val <= r);
-        opt_i_bin.map_or_else(
-            || Ok(Complex::default()),
-            |i_bin| {
-                Ok(Complex::new(
-                    parameters[i_bin * 2],
-                    parameters[(i_bin * 2) + 1],
-                ))
-            },
-        )
+    fn calculate(&self, _parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        Ok(Complex::new(self.calculated_values[event.index], F::zero()))
+    }
+
+    fn calculate_gradient(
+        &self,
+        _parameters: &[F],
+        _event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        Ok(Vec::new())
     }
 
     fn parameters(&self) -> Vec<String> {
-        (0..self.edges.len())
-            .flat_map(|i| vec![format!("bin {} re", i), format!("bin {} im", i)])
-            .collect()
+        Vec::new()
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        self.spec.clone()
     }
 }
 
-pub fn piecewise_m<F: Field + 'static>(name: &str, bins: usize, range: (F, F)) -> Amplitude<F> {
-    //! Creates a named [`Piecewise`] amplitude with the resonance mass as the binning variable.
-    Amplitude::new(
+/// Creates a named [`Template`] amplitude from a histogram in the resonance mass.
+///
+/// # Errors
+///
+/// See [`Template::histogram`].
+pub fn template_hist_m<F: Field + 'static>(
+    name: &str,
+    edges: Vec<F>,
+    contents: Vec<F>,
+) -> Result<Amplitude<F>, RustitudeError> {
+    let spec = NodeSpec::new(
+        "TemplateHistM",
+        serde_json::json!({ "edges": edges, "contents": contents }),
+    );
+    Ok(Amplitude::new(
         name,
-        Piecewise::new(bins, range, |e: &Event<F>| {
-            (e.daughter_p4s[0] + e.daughter_p4s[1]).m()
-        }),
-    )
+        Template::histogram(vec![edges], contents, |e: &Event<F>| {
+            vec![(e.daughter_p4s[0] + e.daughter_p4s[1]).m()]
+        })?
+        .with_spec(spec),
+    ))
+}
+
+/// Creates a named [`Template`] amplitude from a Gaussian kernel density estimate in the
+/// resonance mass.
+///
+/// # Errors
+///
+/// See [`Template::kde`].
+pub fn template_kde_m<F: Field + 'static>(
+    name: &str,
+    points: Vec<F>,
+    weights: Option<Vec<F>>,
+    bandwidth: F,
+) -> Result<Amplitude<F>, RustitudeError> {
+    let spec = NodeSpec::new(
+        "TemplateKDEM",
+        serde_json::json!({ "points": points, "weights": weights, "bandwidth": bandwidth }),
+    );
+    Ok(Amplitude::new(
+        name,
+        Template::kde(
+            points.into_iter().map(|p| vec![p]).collect(),
+            weights,
+            vec![bandwidth],
+            |e: &Event<F>| vec![(e.daughter_p4s[0] + e.daughter_p4s[1]).m()],
+        )?
+        .with_spec(spec),
+    ))
 }
 
 macro_rules! impl_sum {
@@ -0,0 +1,339 @@
+//! This module contains a [`BinnedChi2`] objective which compares histograms of the data and
+//! the weighted Monte-Carlo in one or more variables.
+//!
+//! The data and Monte-Carlo are binned once when the [`BinnedChi2`] is created. Each evaluation
+//! then computes the predicted yield in each bin,
+//! $`\mu_b = \frac{N_{\text{data}}}{N_{\text{MC}}}\sum_{j \in b} w_j I_j`$, and returns
+//!
+//! ```math
+//! \chi^2 = \sum_b \frac{(n_b - \mu_b)^2}{\sigma_b^2}
+//! ```
+//!
+//! where $`n_b = \sum_{i \in b} w_i`$ is the data yield in the bin and
+//! $`\sigma_b^2 = \sum_{i \in b} w_i^2`$ is its variance. Bins without data have no variance and
+//! are skipped, as are events which fall outside of the bins. Since the histograms only depend
+//! on a few variables, a [`BinnedChi2`] is much cheaper to minimize than an
+//! [`ExtendedLogLikelihood`](crate::manager::ExtendedLogLikelihood) when the [`Model`] is
+//! evaluated on a fraction of the Monte-Carlo, and the result is a good starting point for the
+//! full unbinned fit. To compare a finished fit to the data in bins of a variable, see
+//! [`binned_chi2`](crate::diagnostics::binned_chi2) instead.
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! use rustitude_core::utils::generate_test_dataset_f64;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let dataset = generate_test_dataset_f64();
+//! let model = model!(scalar("a"));
+//! let manager = Manager::new(&model, &dataset)?;
+//! let edges: Vec<f64> = (0..=10).map(|i| 0.5 + 0.15 * i as f64).collect();
+//! let chi2 = BinnedChi2::new(manager.clone(), manager, vec![edges], |event| {
+//!     vec![(event.daughter_p4s[0] + event.daughter_p4s[1]).m()]
+//! })?;
+//! // The Monte-Carlo matches the data exactly when the intensity is constant
+//! assert!(chi2.evaluate(&[1.0])? < 1e-8);
+//! assert!(chi2.evaluate(&[2.0])? > 0.0);
+//! # Ok(())
+//! # }
+//! ```
+#[cfg(feature = "ganesh")]
+use ganesh::prelude::{DVector, Function};
+use rayon::prelude::*;
+
+use crate::{
+    amplitude::Model,
+    convert,
+    dataset::Event,
+    errors::RustitudeError,
+    manager::{Binning, Manager},
+    Field,
+};
+
+/// A $`\chi^2`$ between histograms of the data and the weighted Monte-Carlo. See the
+/// [module-level documentation](crate::binned) for more information.
+#[derive(Clone)]
+pub struct BinnedChi2<F: Field + 'static> {
+    /// A [`Manager`] for the data [`Dataset`](crate::dataset::Dataset).
+    pub data_manager: Manager<F>,
+    /// A [`Manager`] for the Monte-Carlo [`Dataset`](crate::dataset::Dataset).
+    pub mc_manager: Manager<F>,
+    edges: Vec<Vec<F>>,
+    mc_bins: Vec<Option<usize>>,
+    counts: Vec<F>,
+    variances: Vec<F>,
+    scale: F,
+}
+
+impl<F: Field> BinnedChi2<F> {
+    /// Create a new [`BinnedChi2`], binning each data and Monte-Carlo [`Event`] in the values
+    /// returned by `variables`, which must return one value for each set of `edges`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are no
+    /// `edges`, if any set of `edges` has fewer than two values or is not strictly increasing, or
+    /// if `variables` does not return one value for each set of `edges`.
+    pub fn new(
+        data_manager: Manager<F>,
+        mc_manager: Manager<F>,
+        edges: Vec<Vec<F>>,
+        variables: impl Fn(&Event<F>) -> Vec<F> + Sync,
+    ) -> Result<Self, RustitudeError> {
+        let data_values: Vec<Vec<F>> = data_manager
+            .dataset
+            .events
+            .par_iter()
+            .map(&variables)
+            .collect();
+        let mc_values: Vec<Vec<F>> = mc_manager
+            .dataset
+            .events
+            .par_iter()
+            .map(&variables)
+            .collect();
+        Self::from_values(data_manager, mc_manager, edges, &data_values, &mc_values)
+    }
+
+    /// Create a new [`BinnedChi2`] from precomputed values of the binning variables for each
+    /// data and Monte-Carlo [`Event`], with one value for each set of `edges`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are no
+    /// `edges`, if any set of `edges` has fewer than two values or is not strictly increasing, or
+    /// if the values do not match the [`Event`]s and `edges`.
+    pub fn from_values(
+        data_manager: Manager<F>,
+        mc_manager: Manager<F>,
+        edges: Vec<Vec<F>>,
+        data_values: &[Vec<F>],
+        mc_values: &[Vec<F>],
+    ) -> Result<Self, RustitudeError> {
+        if edges.is_empty() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "a BinnedChi2 needs at least one binning variable".to_string(),
+            ));
+        }
+        if data_values.len() != data_manager.dataset.len()
+            || mc_values.len() != mc_manager.dataset.len()
+            || data_values
+                .iter()
+                .chain(mc_values)
+                .any(|values| values.len() != edges.len())
+        {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "expected {} binning values for each data and Monte-Carlo event",
+                edges.len()
+            )));
+        }
+        // Bin each variable separately, then flatten the bins in row-major order
+        let mut data_bins = vec![Some(0); data_values.len()];
+        let mut mc_bins = vec![Some(0); mc_values.len()];
+        for (axis, axis_edges) in edges.iter().enumerate() {
+            let column = |values: &[Vec<F>]| values.iter().map(|v| v[axis]).collect::<Vec<F>>();
+            let binning =
+                Binning::from_values(axis_edges.clone(), &column(data_values), &column(mc_values))?;
+            let n_bins = binning.n_bins();
+            let flatten = |bins: &mut [Option<usize>], axis_bins: &[Option<usize>]| {
+                for (bin, axis_bin) in bins.iter_mut().zip(axis_bins) {
+                    *bin = bin.zip(*axis_bin).map(|(bin, b)| bin * n_bins + b);
+                }
+            };
+            flatten(&mut data_bins, &binning.data_bins);
+            flatten(&mut mc_bins, &binning.mc_bins);
+        }
+        let n_bins = edges.iter().map(|e| e.len() - 1).product();
+        let mut counts = vec![F::zero(); n_bins];
+        let mut variances = vec![F::zero(); n_bins];
+        for (bin, event) in data_bins.iter().zip(data_manager.dataset.events.iter()) {
+            if let Some(bin) = bin {
+                counts[*bin] += event.weight;
+                variances[*bin] += event.weight * event.weight;
+            }
+        }
+        let n_data: F = data_manager.dataset.weights().iter().copied().sum();
+        let n_mc: F = mc_manager.dataset.weights().iter().copied().sum();
+        Ok(Self {
+            data_manager,
+            mc_manager,
+            edges,
+            mc_bins,
+            counts,
+            variances,
+            scale: n_data / n_mc,
+        })
+    }
+
+    /// Returns the edges of the bins of each variable.
+    pub fn edges(&self) -> &[Vec<F>] {
+        &self.edges
+    }
+
+    /// Returns the total number of bins, flattened over every variable in row-major order.
+    pub const fn n_bins(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns the number of bins which contain data and contribute to the $`\chi^2`$.
+    pub fn n_filled(&self) -> usize {
+        self.variances.iter().filter(|v| **v > F::zero()).count()
+    }
+
+    /// Returns the data yield $`n_b`$ in each bin.
+    pub fn counts(&self) -> &[F] {
+        &self.counts
+    }
+
+    /// Get the number of free parameters.
+    pub fn get_n_free(&self) -> usize {
+        self.model().get_n_free()
+    }
+
+    /// Get the initial values of the free parameters.
+    pub fn get_initial(&self) -> Vec<F> {
+        self.model().get_initial()
+    }
+
+    /// Get the bounds of the free parameters.
+    pub fn get_bounds(&self) -> Vec<(F, F)> {
+        self.model().get_bounds()
+    }
+
+    /// The [`Model`] which holds the free parameters and constraints.
+    const fn model(&self) -> &Model<F> {
+        &self.data_manager.model
+    }
+
+    /// Computes the predicted yield $`\mu_b`$ in each bin.
+    fn predicted_with(&self, parameters: &[F], parallel: bool) -> Result<Vec<F>, RustitudeError> {
+        let mc_res = if parallel {
+            self.mc_manager.par_evaluate(parameters)?
+        } else {
+            self.mc_manager.evaluate(parameters)?
+        };
+        let mut predicted = vec![F::zero(); self.n_bins()];
+        for ((value, bin), event) in mc_res
+            .iter()
+            .zip(&self.mc_bins)
+            .zip(self.mc_manager.dataset.events.iter())
+        {
+            if let Some(bin) = bin {
+                predicted[*bin] += self.scale * event.weight * *value;
+            }
+        }
+        Ok(predicted)
+    }
+
+    /// Returns the predicted yield $`\mu_b`$ in each bin with the given free parameters.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the [`Model`] cannot be evaluated.
+    pub fn predicted(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        self.predicted_with(parameters, false)
+    }
+
+    /// Evaluates the $`\chi^2`$ with the given free parameters.
+    fn evaluate_with(&self, parameters: &[F], parallel: bool) -> Result<F, RustitudeError> {
+        let predicted = self.predicted_with(parameters, parallel)?;
+        let chi2: F = self
+            .counts
+            .iter()
+            .zip(&self.variances)
+            .zip(&predicted)
+            .filter(|((_, variance), _)| **variance > F::zero())
+            .map(|((n, variance), mu)| (*n - *mu) * (*n - *mu) / *variance)
+            .sum();
+        Ok(chi2 + self.model().constraint_penalty(parameters)?)
+    }
+
+    /// Evaluate the $`\chi^2`$ (plus any constraints) with the given free parameters.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the [`Model`] or a constraint cannot be
+    /// evaluated.
+    pub fn evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        self.evaluate_with(parameters, false)
+    }
+
+    /// Evaluate the $`\chi^2`$ (plus any constraints) with the given free parameters, using a
+    /// parallel loop over Monte-Carlo events.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the [`Model`] or a constraint cannot be
+    /// evaluated.
+    pub fn par_evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        self.mc_manager
+            .install(|| self.evaluate_with(parameters, true))
+    }
+
+    /// Computes the analytic gradient of the $`\chi^2`$ with respect to the free parameters.
+    fn gradient_with(&self, parameters: &[F], parallel: bool) -> Result<Vec<F>, RustitudeError> {
+        // d(chi2)/dp = sum_b -2 (n_b - mu_b) / sigma_b^2 d(mu_b)/dp
+        let predicted = self.predicted_with(parameters, parallel)?;
+        let factors: Vec<F> = self
+            .counts
+            .iter()
+            .zip(&self.variances)
+            .zip(&predicted)
+            .map(|((n, variance), mu)| {
+                if *variance > F::zero() {
+                    convert!(-2, F) * self.scale * (*n - *mu) / *variance
+                } else {
+                    F::zero()
+                }
+            })
+            .collect();
+        let weight = |i: usize, _: F, event: &Event<F>| {
+            self.mc_bins[i].map_or_else(F::zero, |bin| factors[bin] * event.weight)
+        };
+        let gradient = if parallel {
+            self.mc_manager.par_gradient_sum(parameters, weight)?
+        } else {
+            self.mc_manager.gradient_sum(parameters, weight)?
+        };
+        Ok(self
+            .model()
+            .free_gradient(parameters, &gradient)
+            .into_iter()
+            .zip(self.model().constraint_penalty_gradient(parameters)?)
+            .map(|(g, c)| g + c)
+            .collect())
+    }
+
+    /// Evaluate the gradient of the $`\chi^2`$ (plus any constraints) with respect to the free
+    /// parameters.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the [`Model`] or a constraint cannot be
+    /// evaluated.
+    pub fn gradient(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        self.gradient_with(parameters, false)
+    }
+
+    /// Parallel version of [`BinnedChi2::gradient`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the [`Model`] or a constraint cannot be
+    /// evaluated.
+    pub fn par_gradient(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        self.mc_manager
+            .install(|| self.gradient_with(parameters, true))
+    }
+}
+
+#[cfg(feature = "ganesh")]
+impl<F: Field + ganesh::core::Field> Function<F, (), RustitudeError> for BinnedChi2<F> {
+    fn evaluate(&self, x: &DVector<F>, _args: Option<&()>) -> Result<F, RustitudeError> {
+        self.par_evaluate(x.as_slice())
+    }
+
+    fn gradient(&self, x: &DVector<F>, _args: Option<&()>) -> Result<DVector<F>, RustitudeError> {
+        Ok(DVector::from_vec(self.par_gradient(x.as_slice())?))
+    }
+}
@@ -0,0 +1,178 @@
+//! This module contains a process-wide cache of precalculated [`Node`]s which can be shared by
+//! every [`Manager`](crate::manager::Manager) loading the same [`Dataset`].
+//!
+//! Each [`Manager`](crate::manager::Manager) normally runs [`Node::precalculate`] for every
+//! [`Amplitude`] in its [`Model`](crate::amplitude::Model), so a data, Monte-Carlo, and plotting
+//! [`Manager`](crate::manager::Manager) built over the same [`Dataset`] repeat the same work. When
+//! the [`PrecalculationCache`] is enabled, [`Model::load`](crate::amplitude::Model::load) stores
+//! a copy of each precalculated [`Node`] keyed by the [`Dataset::fingerprint`], the name of the
+//! [`Amplitude`], and the [`NodeSpec`](crate::serialization::NodeSpec) of the [`Node`], and later
+//! loads with the same key copy the stored [`Node`] instead of precalculating it again.
+//!
+//! Only [`Node`]s which provide a [`Node::spec`] can be cached, since the spec is what identifies
+//! the configuration of a [`Node`]. The cache holds its [`Node`]s (and their precalculated data)
+//! until it is cleared, so it is disabled by default.
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! use rustitude_core::utils::generate_test_dataset_f64;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let cache = PrecalculationCache::<f64>::global();
+//! cache.set_enabled(true);
+//! let dataset = generate_test_dataset_f64();
+//! let model = model!(cscalar("a"));
+//! let data_manager = Manager::new(&model, &dataset)?;
+//! let mc_manager = Manager::new(&model, &dataset)?; // reuses the precalculated "a"
+//! cache.set_enabled(false);
+//! cache.clear();
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use parking_lot::RwLock;
+
+use crate::{
+    amplitude::{Amplitude, Node, SharedData},
+    dataset::Dataset,
+    errors::RustitudeError,
+    Field,
+};
+
+/// The key of a cached [`Node`]: a [`Dataset::fingerprint`], the name of the [`Amplitude`], and
+/// the JSON representation of the [`Node::spec`].
+type CacheKey = (u64, String, String);
+
+/// A thread-safe cache of precalculated [`Node`]s. See the [module-level
+/// documentation](crate::cache) for more information.
+pub struct PrecalculationCache<F: Field> {
+    enabled: AtomicBool,
+    nodes: RwLock<HashMap<CacheKey, Box<dyn Node<F>>>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl<F: Field> Default for PrecalculationCache<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> PrecalculationCache<F> {
+    /// Create a new, empty, and disabled [`PrecalculationCache`].
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            nodes: RwLock::new(HashMap::new()),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the process-wide [`PrecalculationCache`] used by
+    /// [`Model::load`](crate::amplitude::Model::load) for this precision.
+    pub fn global() -> &'static Self {
+        F::precalculation_cache()
+    }
+
+    /// Enables or disables the [`PrecalculationCache`]. Disabling the cache does not remove the
+    /// stored [`Node`]s (see [`PrecalculationCache::clear`]).
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Checks if the [`PrecalculationCache`] is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Removes every stored [`Node`] and resets the hit and miss counters.
+    pub fn clear(&self) {
+        self.nodes.write().clear();
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns the number of stored [`Node`]s.
+    pub fn len(&self) -> usize {
+        self.nodes.read().len()
+    }
+
+    /// Checks if the [`PrecalculationCache`] has no stored [`Node`]s.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.read().is_empty()
+    }
+
+    /// Returns the number of precalculations which were skipped by copying a stored [`Node`].
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of cacheable precalculations which were run and stored.
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Precalculates the [`Node`] of an [`Amplitude`] over a [`Dataset`] with the given
+    /// [`Dataset::fingerprint`], copying a stored [`Node`] if one exists.
+    ///
+    /// [`Amplitude`]s whose [`Node`] has no [`Node::spec`] are always precalculated. The
+    /// precalculation itself runs outside of any lock, so two threads which miss at the same time
+    /// may both precalculate the same [`Node`], in which case the first one is stored.
+    ///
+    /// # Errors
+    ///
+    /// This method will return any error produced by [`Node::precalculate`] or a
+    /// [`RustitudeError::SerializationError`] if the [`Node::spec`] cannot be serialized.
+    pub fn precalculate(
+        &self,
+        amplitude: &mut Amplitude<F>,
+        fingerprint: u64,
+        dataset: &Dataset<F>,
+    ) -> Result<(), RustitudeError> {
+        self.precalculate_shared(amplitude, fingerprint, dataset, &mut SharedData::default())
+    }
+
+    /// A version of [`PrecalculationCache::precalculate`] which reads and extends a
+    /// [`SharedData`] held by the caller. Any [`Node::requirements`] which are missing from
+    /// `shared` are only computed when the [`Node`] is precalculated.
+    ///
+    /// # Errors
+    ///
+    /// This method will return any error produced by a
+    /// [`SharedQuantity`](crate::amplitude::SharedQuantity) or [`Node::precalculate_shared`], or a
+    /// [`RustitudeError::SerializationError`] if the [`Node::spec`] cannot be serialized.
+    pub fn precalculate_shared(
+        &self,
+        amplitude: &mut Amplitude<F>,
+        fingerprint: u64,
+        dataset: &Dataset<F>,
+        shared: &mut SharedData,
+    ) -> Result<(), RustitudeError> {
+        let Some(spec) = amplitude.node.spec() else {
+            return amplitude.precalculate_with(dataset, shared);
+        };
+        let key = (
+            fingerprint,
+            amplitude.name.clone(),
+            serde_json::to_string(&spec)?,
+        );
+        if let Some(node) = self.nodes.read().get(&key) {
+            amplitude.node = node.clone();
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+        amplitude.precalculate_with(dataset, shared)?;
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        self.nodes
+            .write()
+            .entry(key)
+            .or_insert_with(|| amplitude.node.clone());
+        Ok(())
+    }
+}
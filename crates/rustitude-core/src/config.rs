@@ -0,0 +1,546 @@
+//! This module contains a parser for a line-based configuration language which describes a
+//! [`Model`].
+//!
+//! The format is intended to be familiar to users of AmpTools-style configuration files, and
+//! makes it possible to keep a full model definition (amplitudes, coherent sums, and parameter
+//! settings) outside of the code which performs the fit.
+//!
+//! Each line contains a single directive followed by whitespace-separated arguments. Arguments
+//! which contain whitespace can be wrapped in double quotes, and anything after a `#` is a
+//! comment. The following directives are supported:
+//!
+//! | Directive | Arguments | Effect |
+//! |---|---|---|
+//! | `amplitude` | `<name> <kind> [key=value ...]` | Creates an [`Amplitude`] with a [`Node`](crate::amplitude::Node) built by the [`NodeRegistry`] |
+//! | `sum` | `<expression>` | Adds a coherent sum built from `+`, `*`, `real(...)`, `imag(...)`, and parentheses (products distribute over sums, as they do in Rust) |
+//! | `initial` | `<amplitude> <parameter> <value>` | See [`Model::set_initial`] |
+//! | `bounds` | `<amplitude> <parameter> <lower> <upper>` | See [`Model::set_bounds`] (`inf` and `-inf` are allowed) |
+//! | `transform` | `<amplitude> <parameter> (log \| sqrt \| none \| bounded <lower> <upper>)` | See [`Model::set_transform`] |
+//! | `fix` | `<amplitude> <parameter> <value>` | See [`Model::fix`] |
+//! | `free` | `<amplitude> <parameter>` | See [`Model::free`] |
+//! | `constrain` | `<amplitude> <parameter> <amplitude> <parameter>` | See [`Model::constrain`] |
+//! | `gaussian` | `<amplitude> <parameter> <mean> <sigma>` | See [`Model::add_gaussian_constraint`] |
+//! | `deactivate` | `<amplitude>` | See [`Model::deactivate`] |
+//!
+//! The `key=value` arguments of an `amplitude` directive are parsed as YAML values and passed
+//! to the registered builder as the arguments of a [`NodeSpec`], so any
+//! [`Node`](crate::amplitude::Node) registered in a [`NodeRegistry`] (including those from other
+//! crates) can be used in a configuration file.
+//! Parameter directives are applied in the order they appear, after the [`Model`] is built.
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let config = r#"
+//! amplitude a Scalar
+//! amplitude b ComplexScalar
+//! amplitude d PiecewiseM bins=4 range=[1.0, 2.0]  # four bins in invariant mass
+//!
+//! sum (a + b) * b
+//! sum real(d)
+//!
+//! fix a value 2.0
+//! bounds b real -1.0 1.0
+//! constrain b imag d "bin 0 re"
+//! "#;
+//! let registry: NodeRegistry<f64> = NodeRegistry::new();
+//! let model = Model::from_config(config, &registry)?;
+//! assert_eq!(model.get_n_free(), 9);
+//! # Ok(())
+//! # }
+//! ```
+use std::{collections::HashMap, fmt::Display, path::Path};
+
+use crate::{
+    amplitude::{Amplitude, Model, Transform},
+    errors::RustitudeError,
+    serialization::{AmpTree, NodeRegistry, NodeSpec},
+    Field,
+};
+
+fn parse_error(line: usize, message: impl Display) -> RustitudeError {
+    RustitudeError::ParseError(format!("line {line}: {message}"))
+}
+
+/// Splits a line into whitespace-separated tokens, keeping quoted strings and bracketed values
+/// together and discarding comments.
+fn split_tokens(line: &str, line_number: usize) -> Result<Vec<String>, RustitudeError> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut in_quotes = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            '#' if !in_quotes => break,
+            '[' | '{' | '(' if !in_quotes => {
+                depth += 1;
+                current.push(c);
+            }
+            ']' | '}' | ')' if !in_quotes => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| parse_error(line_number, format!("unmatched '{c}'")))?;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !in_quotes && depth == 0 => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(parse_error(line_number, "unterminated string"));
+    }
+    if depth != 0 {
+        return Err(parse_error(line_number, "unclosed bracket"));
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+fn unquote(token: &str) -> &str {
+    token
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .unwrap_or(token)
+}
+
+fn parse_value<F: Field>(token: &str, line_number: usize) -> Result<F, RustitudeError> {
+    let value: f64 = token
+        .parse()
+        .map_err(|_| parse_error(line_number, format!("invalid number \"{token}\"")))?;
+    Ok(F::from_f64(value).unwrap_or_else(F::nan))
+}
+
+fn expect_args<'a>(
+    tokens: &'a [String],
+    n: usize,
+    usage: &str,
+    line_number: usize,
+) -> Result<&'a [String], RustitudeError> {
+    if tokens.len() == n + 1 {
+        Ok(&tokens[1..])
+    } else {
+        Err(parse_error(
+            line_number,
+            format!("expected \"{} {}\"", tokens[0], usage),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ExprToken {
+    Name(String),
+    Plus,
+    Star,
+    Open,
+    Close,
+}
+
+fn lex_expression(expression: &str, line_number: usize) -> Result<Vec<ExprToken>, RustitudeError> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '#' => break,
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '+' => {
+                chars.next();
+                tokens.push(ExprToken::Plus);
+            }
+            '*' => {
+                chars.next();
+                tokens.push(ExprToken::Star);
+            }
+            '(' => {
+                chars.next();
+                tokens.push(ExprToken::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(ExprToken::Close);
+            }
+            '"' => {
+                chars.next();
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(parse_error(line_number, "unterminated string")),
+                    }
+                }
+                tokens.push(ExprToken::Name(name));
+            }
+            _ => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "+*()\"#".contains(c) {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                tokens.push(ExprToken::Name(name));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Adds two trees, flattening nested sums in the same way as the [`Add`](std::ops::Add)
+/// implementations for [`AmpLike`](crate::amplitude::AmpLike)s.
+fn add(lhs: AmpTree, rhs: AmpTree) -> AmpTree {
+    match (lhs, rhs) {
+        (AmpTree::Sum(mut a), AmpTree::Sum(b)) => {
+            a.extend(b);
+            AmpTree::Sum(a)
+        }
+        (AmpTree::Sum(mut a), b) => {
+            a.push(b);
+            AmpTree::Sum(a)
+        }
+        (a, AmpTree::Sum(mut b)) => {
+            b.insert(0, a);
+            AmpTree::Sum(b)
+        }
+        (a, b) => AmpTree::Sum(vec![a, b]),
+    }
+}
+
+/// Multiplies two trees, distributing over sums and flattening nested products in the same way
+/// as the [`Mul`](std::ops::Mul) implementations for [`AmpLike`](crate::amplitude::AmpLike)s.
+fn multiply(lhs: AmpTree, rhs: AmpTree) -> AmpTree {
+    match (lhs, rhs) {
+        (AmpTree::Sum(a), b) => {
+            AmpTree::Sum(a.into_iter().map(|t| multiply(t, b.clone())).collect())
+        }
+        (a, AmpTree::Sum(b)) => {
+            AmpTree::Sum(b.into_iter().map(|t| multiply(a.clone(), t)).collect())
+        }
+        (AmpTree::Product(mut a), AmpTree::Product(b)) => {
+            a.extend(b);
+            AmpTree::Product(a)
+        }
+        (AmpTree::Product(mut a), b) => {
+            a.push(b);
+            AmpTree::Product(a)
+        }
+        (a, AmpTree::Product(mut b)) => {
+            b.insert(0, a);
+            AmpTree::Product(b)
+        }
+        (a, b) => AmpTree::Product(vec![a, b]),
+    }
+}
+
+/// A recursive-descent parser for coherent sum expressions.
+struct ExprParser<'a> {
+    tokens: &'a [ExprToken],
+    position: usize,
+    line_number: usize,
+}
+
+impl<'a> ExprParser<'a> {
+    fn parse(tokens: &'a [ExprToken], line_number: usize) -> Result<AmpTree, RustitudeError> {
+        let mut parser = Self {
+            tokens,
+            position: 0,
+            line_number,
+        };
+        let tree = parser.expression()?;
+        if parser.position != tokens.len() {
+            return Err(parse_error(line_number, "unexpected token in expression"));
+        }
+        Ok(tree)
+    }
+
+    fn peek(&self) -> Option<&ExprToken> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&ExprToken> {
+        self.position += 1;
+        self.tokens.get(self.position - 1)
+    }
+
+    fn expect(&mut self, token: &ExprToken) -> Result<(), RustitudeError> {
+        if self.next() == Some(token) {
+            Ok(())
+        } else {
+            Err(parse_error(
+                self.line_number,
+                format!("expected {token:?} in expression"),
+            ))
+        }
+    }
+
+    fn expression(&mut self) -> Result<AmpTree, RustitudeError> {
+        let mut tree = self.term()?;
+        while self.peek() == Some(&ExprToken::Plus) {
+            self.next();
+            tree = add(tree, self.term()?);
+        }
+        Ok(tree)
+    }
+
+    fn term(&mut self) -> Result<AmpTree, RustitudeError> {
+        let mut tree = self.factor()?;
+        while self.peek() == Some(&ExprToken::Star) {
+            self.next();
+            tree = multiply(tree, self.factor()?);
+        }
+        Ok(tree)
+    }
+
+    fn factor(&mut self) -> Result<AmpTree, RustitudeError> {
+        match self.next().cloned() {
+            Some(ExprToken::Open) => {
+                let inner = self.expression()?;
+                self.expect(&ExprToken::Close)?;
+                Ok(inner)
+            }
+            Some(ExprToken::Name(name)) => {
+                if self.peek() == Some(&ExprToken::Open) && (name == "real" || name == "imag") {
+                    self.next();
+                    let inner = Box::new(self.expression()?);
+                    self.expect(&ExprToken::Close)?;
+                    Ok(if name == "real" {
+                        AmpTree::Real(inner)
+                    } else {
+                        AmpTree::Imag(inner)
+                    })
+                } else {
+                    Ok(AmpTree::Amplitude(name))
+                }
+            }
+            _ => Err(parse_error(
+                self.line_number,
+                "expected an amplitude name or '(' in expression",
+            )),
+        }
+    }
+}
+
+fn parse_node_spec(
+    kind: &str,
+    args: &[String],
+    line_number: usize,
+) -> Result<NodeSpec, RustitudeError> {
+    let mut map = serde_json::Map::new();
+    for arg in args {
+        let (key, value) = arg.split_once('=').ok_or_else(|| {
+            parse_error(line_number, format!("expected key=value, got \"{arg}\""))
+        })?;
+        let value: serde_json::Value = serde_yaml::from_str(value)
+            .map_err(|err| parse_error(line_number, format!("invalid value for {key}: {err}")))?;
+        map.insert(key.to_string(), value);
+    }
+    Ok(NodeSpec::new(kind, serde_json::Value::Object(map)))
+}
+
+/// Parses a configuration string into a [`Model`].
+///
+/// The given [`NodeRegistry`] is used to build the [`Node`](crate::amplitude::Node) for each
+/// `amplitude` directive. See the [module-level documentation](self) for a description of the
+/// format.
+///
+/// # Errors
+///
+/// This function will return a [`RustitudeError::ParseError`] (with the offending line number)
+/// if the configuration is malformed, or any error produced while building [`Node`](crate::amplitude::Node)s or applying
+/// parameter directives to the [`Model`].
+pub fn parse_model<F: Field + 'static>(
+    config: &str,
+    registry: &NodeRegistry<F>,
+) -> Result<Model<F>, RustitudeError> {
+    let mut amplitudes: HashMap<String, Amplitude<F>> = HashMap::default();
+    let mut cohsums = Vec::new();
+    let mut directives = Vec::new();
+    for (i, line) in config.lines().enumerate() {
+        let line_number = i + 1;
+        let tokens = split_tokens(line, line_number)?;
+        let Some(directive) = tokens.first() else {
+            continue;
+        };
+        match directive.as_str() {
+            "amplitude" => {
+                if tokens.len() < 3 {
+                    return Err(parse_error(
+                        line_number,
+                        "expected \"amplitude <name> <kind> [key=value ...]\"",
+                    ));
+                }
+                let name = unquote(&tokens[1]);
+                if amplitudes.contains_key(name) {
+                    return Err(parse_error(
+                        line_number,
+                        format!("amplitude \"{name}\" is already defined"),
+                    ));
+                }
+                let spec = parse_node_spec(&tokens[2], &tokens[3..], line_number)?;
+                let node = registry.build(&spec)?;
+                amplitudes.insert(name.to_string(), Amplitude::from_boxed(name, node));
+            }
+            "sum" => {
+                let expression = line.trim_start()["sum".len()..].to_string();
+                let expr_tokens = lex_expression(&expression, line_number)?;
+                cohsums.push(ExprParser::parse(&expr_tokens, line_number)?);
+            }
+            "initial" | "bounds" | "transform" | "fix" | "free" | "constrain" | "gaussian"
+            | "deactivate" => {
+                directives.push((line_number, tokens));
+            }
+            other => {
+                return Err(parse_error(
+                    line_number,
+                    format!("unknown directive \"{other}\""),
+                ))
+            }
+        }
+    }
+    let cohsums = cohsums
+        .iter()
+        .map(|tree| tree.build(&amplitudes))
+        .collect::<Result<Vec<_>, RustitudeError>>()?;
+    let mut model = Model::new(&cohsums);
+    for (line_number, tokens) in directives {
+        match tokens[0].as_str() {
+            "initial" => {
+                let args = expect_args(&tokens, 3, "<amplitude> <parameter> <value>", line_number)?;
+                model.set_initial(
+                    unquote(&args[0]),
+                    unquote(&args[1]),
+                    parse_value(&args[2], line_number)?,
+                )?;
+            }
+            "bounds" => {
+                let args = expect_args(
+                    &tokens,
+                    4,
+                    "<amplitude> <parameter> <lower> <upper>",
+                    line_number,
+                )?;
+                model.set_bounds(
+                    unquote(&args[0]),
+                    unquote(&args[1]),
+                    (
+                        parse_value(&args[2], line_number)?,
+                        parse_value(&args[3], line_number)?,
+                    ),
+                )?;
+            }
+            "transform" => {
+                let usage = "<amplitude> <parameter> (log | sqrt | none | bounded <lower> <upper>)";
+                let kind = tokens.get(3).map(|token| unquote(token));
+                let transform = match kind {
+                    Some("bounded") => {
+                        let args = expect_args(&tokens, 5, usage, line_number)?;
+                        Some(Transform::Bounded(
+                            parse_value(&args[3], line_number)?,
+                            parse_value(&args[4], line_number)?,
+                        ))
+                    }
+                    _ => {
+                        let args = expect_args(&tokens, 3, usage, line_number)?;
+                        match unquote(&args[2]) {
+                            "log" => Some(Transform::Log),
+                            "sqrt" => Some(Transform::Sqrt),
+                            "none" => None,
+                            other => {
+                                return Err(parse_error(
+                                    line_number,
+                                    format!("unknown transform \"{other}\""),
+                                ))
+                            }
+                        }
+                    }
+                };
+                model.set_transform(unquote(&tokens[1]), unquote(&tokens[2]), transform)?;
+            }
+            "fix" => {
+                let args = expect_args(&tokens, 3, "<amplitude> <parameter> <value>", line_number)?;
+                model.fix(
+                    unquote(&args[0]),
+                    unquote(&args[1]),
+                    parse_value(&args[2], line_number)?,
+                )?;
+            }
+            "free" => {
+                let args = expect_args(&tokens, 2, "<amplitude> <parameter>", line_number)?;
+                model.free(unquote(&args[0]), unquote(&args[1]))?;
+            }
+            "constrain" => {
+                let args = expect_args(
+                    &tokens,
+                    4,
+                    "<amplitude> <parameter> <amplitude> <parameter>",
+                    line_number,
+                )?;
+                model.constrain(
+                    unquote(&args[0]),
+                    unquote(&args[1]),
+                    unquote(&args[2]),
+                    unquote(&args[3]),
+                )?;
+            }
+            "gaussian" => {
+                let args = expect_args(
+                    &tokens,
+                    4,
+                    "<amplitude> <parameter> <mean> <sigma>",
+                    line_number,
+                )?;
+                model.add_gaussian_constraint(
+                    unquote(&args[0]),
+                    unquote(&args[1]),
+                    parse_value(&args[2], line_number)?,
+                    parse_value(&args[3], line_number)?,
+                )?;
+            }
+            _ => {
+                let args = expect_args(&tokens, 1, "<amplitude>", line_number)?;
+                model.deactivate(unquote(&args[0]))?;
+            }
+        }
+    }
+    Ok(model)
+}
+
+impl<F: Field + 'static> Model<F> {
+    /// Builds a [`Model`] from a configuration string. See [`parse_model`] and the
+    /// [`config`](crate::config) module for more information.
+    ///
+    /// # Errors
+    ///
+    /// See [`parse_model`].
+    pub fn from_config(config: &str, registry: &NodeRegistry<F>) -> Result<Self, RustitudeError> {
+        parse_model(config, registry)
+    }
+
+    /// Builds a [`Model`] from a configuration file. See [`parse_model`] and the
+    /// [`config`](crate::config) module for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::IOError`] if the file cannot be read, or any
+    /// error from [`parse_model`].
+    pub fn from_config_file<P: AsRef<Path>>(
+        path: P,
+        registry: &NodeRegistry<F>,
+    ) -> Result<Self, RustitudeError> {
+        parse_model(&std::fs::read_to_string(path)?, registry)
+    }
+}
@@ -22,16 +22,34 @@
 //! | `EPS` | \[Float32\] | \[$`P_\gamma \cos(\Phi)`$, $`P_\gamma \sin(\Phi)`$, $`0.0`$\] for linear polarization with magnitude $`P_\gamma`$ and angle $`\Phi`$ |
 //!
 //! The `EPS` branch is optional and files without such a branch can be loaded under the
-//! following conditions. First, if we don't care about polarization, and wish to set `EPS` =
-//! `[0.0, 0.0, 0.0]`, we can do so using the methods [`ReadMethod::EPS(0.0, 0.0, 0.0)`]. If
+//! following conditions. First, if the beam is unpolarized, we can use
+//! [`ReadMethod::Unpolarized`] to set `EPS` = `[0.0, 0.0, 0.0]`. If
 //! a data file contains events with only one polarization, we can compute the `EPS` vector
 //! ourselves and use [`ReadMethod::EPS(x, y, z)`] to load the same vector for every event.
 //! Finally, to provide compatibility with the way polarization is sometimes included in
 //! `AmpTools` files, we can note that the beam is often only moving along the
 //! $`z`$-axis, so the $`x`$ and $`y`$ components are typically `0.0` anyway, so we can store
 //! the $`x`$, $`y`$, and $`z`$ components of `EPS` in the beam's three-momentum and use the
-//! [`ReadMethod::EPSInBeam`] to extract it. All of these methods are used as an input for either
-//! [`Dataset::from_parquet`] or [`Dataset::from_root`].
+//! [`ReadMethod::EPSInBeam`] to extract it. A circularly polarized beam is loaded with
+//! [`ReadMethod::Circular`], which stores the signed degree of polarization in
+//! [`Event::circular_polarization`] rather than in `EPS`. All of these methods are used as an
+//! input for either [`Dataset::from_parquet`] or [`Dataset::from_root`], and the resulting
+//! state of the beam can be queried with [`Event::polarization`].
+//!
+//! Files which store a `RunNumber` column/branch rather than the polarization of each event can
+//! instead be loaded with [`Dataset::from_parquet_with_polarization_table`] or
+//! [`Dataset::from_root_with_polarization_table`], which fill `EPS` from a
+//! [`PolarizationTable`] of the polarization angle and magnitude in each [`RunPeriod`].
+//!
+//! Events are loaded in the frame in which they are stored. Preprocessing steps such as a boost
+//! to the center-of-momentum frame, a reordering of the daughters, or a Gaussian smearing of
+//! generated Monte-Carlo can be applied after loading with [`Dataset::with_transforms`] and a
+//! list of [`DatasetTransform`]s.
+//!
+//! Kinematic variables which several [`Node`](crate::amplitude::Node)s need during
+//! precalculation, such as the mass of a resonance or its decay angles, can be requested by name
+//! with [`Dataset::kinematics`] (or [`Dataset::standard_kinematics`]). Each array is computed
+//! once and shared by every clone of the [`Dataset`].
 //!
 //! There are also several methods used to split up [`Dataset`]s based on their component
 //! values. The [`Dataset::get_selected_indices`] method returns a `Vec<usize>` of event indices
@@ -47,22 +65,37 @@
 //! "lost" by this operation. There is also a convenience method, [`Dataset::split_m`], to split
 //! the dataset by the mass of the summed four-momentum of any of the daughter particles,
 //! specified by their index.
-use std::ops::Add;
-use std::{fmt::Display, fs::File, iter::repeat_with, path::Path, sync::Arc};
+//!
+//! Subsets of a [`Dataset`] can also be taken as [`DatasetView`]s, which share the [`Event`]s of
+//! their parent rather than copying them (see [`Dataset::view`], [`Dataset::view_mask`], and
+//! [`Dataset::get_binned_views`]), and [`Dataset`]s can be concatenated in place with
+//! [`Dataset::append`].
+use std::ops::{Add, Range};
+use std::{collections::HashMap, fmt::Display, iter::repeat_with, sync::Arc};
+#[cfg(feature = "parquet")]
+use std::{fs::File, path::Path};
 
-use itertools::{izip, Either, Itertools};
-use nalgebra::Vector3;
-use oxyroot::{ReaderTree, RootFile, Slice};
+#[cfg(feature = "oxyroot")]
+use itertools::izip;
+use itertools::{Either, Itertools};
+use nalgebra::{Matrix3, Matrix4, Vector3, Vector4};
+#[cfg(feature = "oxyroot")]
+use oxyroot::{Named, ReaderTree, RootFile, Slice};
+use parking_lot::RwLock;
+#[cfg(feature = "parquet")]
 use parquet::record::Field as ParquetField;
+#[cfg(feature = "parquet")]
 use parquet::{
     file::reader::{FileReader, SerializedFileReader},
     record::Row,
 };
 use rayon::prelude::*;
-use tracing::info;
+use tracing::{debug, info};
 
 use crate::convert;
-use crate::{errors::RustitudeError, prelude::FourMomentum, Field};
+use crate::errors::RustitudeError;
+use crate::frames::{self, Frame};
+use crate::{prelude::FourMomentum, Field};
 
 /// The [`Event`] struct contains all the information concerning a single interaction between
 /// particles in the experiment. See the individual fields for additional information.
@@ -78,8 +111,11 @@ pub struct Event<F: Field + 'static> {
     pub recoil_p4: FourMomentum<F>,
     /// [`FourMomentum`] of each other final state particle.
     pub daughter_p4s: Vec<FourMomentum<F>>,
-    /// A vector corresponding to the polarization of the beam.
+    /// A vector corresponding to the linear polarization of the beam.
     pub eps: Vector3<F>,
+    /// The signed degree of circular polarization of the beam, which is positive for a beam with
+    /// helicity $`+1`$ and negative for a beam with helicity $`-1`$.
+    pub circular_polarization: F,
 }
 
 impl<F: Field + 'static> Display for Event<F> {
@@ -97,6 +133,7 @@ impl<F: Field + 'static> Display for Event<F> {
             "EPS: [{}, {}, {}]",
             self.eps[0], self.eps[1], self.eps[2]
         )?;
+        writeln!(f, "Circular Polarization: {}", self.circular_polarization)?;
         Ok(())
     }
 }
@@ -110,6 +147,11 @@ pub enum ReadMethod<F: Field> {
     EPSInBeam,
     /// This variant can be used to provide a custom EPS vec for all events.
     EPS(F, F, F),
+    /// This variant ignores any `EPS` column/branch and loads an unpolarized beam.
+    Unpolarized,
+    /// This variant ignores any `EPS` column/branch and loads a circularly polarized beam with
+    /// the given signed degree of polarization (see [`Event::circular_polarization`]).
+    Circular(F),
 }
 impl<F: Field> ReadMethod<F> {
     /// Creates the EPS vector from a polarization magnitude and angle (in radians).
@@ -117,22 +159,330 @@ impl<F: Field> ReadMethod<F> {
         Self::EPS(p_gamma * F::cos(phi), p_gamma * F::sin(phi), F::zero())
     }
 }
+
+/// A range of runs, from `first_run` to `last_run` inclusive, over which the beam has a common
+/// linear polarization (see [`PolarizationTable`]).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RunPeriod<F: Field> {
+    /// The first run in the period.
+    pub first_run: u32,
+    /// The last run in the period.
+    pub last_run: u32,
+    /// The angle (in radians) of the polarization vector from the $`x`$-axis.
+    pub angle: F,
+    /// The degree of linear polarization.
+    pub magnitude: F,
+}
+
+impl<F: Field> RunPeriod<F> {
+    /// Creates a new [`RunPeriod`] from an inclusive range of runs and the angle (in radians) and
+    /// magnitude of the linear polarization of the beam.
+    pub const fn new(first_run: u32, last_run: u32, angle: F, magnitude: F) -> Self {
+        Self {
+            first_run,
+            last_run,
+            angle,
+            magnitude,
+        }
+    }
+
+    /// Returns the EPS vector of the [`RunPeriod`] (see [`ReadMethod::from_linear_polarization`]).
+    pub fn eps(&self) -> Vector3<F> {
+        Vector3::new(
+            self.magnitude * F::cos(self.angle),
+            self.magnitude * F::sin(self.angle),
+            F::zero(),
+        )
+    }
+}
+
+/// A lookup table of the linear polarization of the beam in each [`RunPeriod`].
+///
+/// This is used to fill the EPS vector of each [`Event`] from its run number when the data files
+/// do not store the polarization of each event (see [`Dataset::with_polarization_table`]).
+#[derive(Clone, Debug, Default)]
+pub struct PolarizationTable<F: Field> {
+    periods: Vec<RunPeriod<F>>,
+}
+
+impl<F: Field> PolarizationTable<F> {
+    /// Creates a new [`PolarizationTable`] from a list of [`RunPeriod`]s in any order.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the first run of a
+    /// [`RunPeriod`] is after its last run or if any two [`RunPeriod`]s share a run.
+    pub fn new(mut periods: Vec<RunPeriod<F>>) -> Result<Self, RustitudeError> {
+        if let Some(period) = periods.iter().find(|p| p.first_run > p.last_run) {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "run period starts at run {} after it ends at run {}",
+                period.first_run, period.last_run
+            )));
+        }
+        periods.sort_by_key(|period| period.first_run);
+        if let Some((a, b)) = periods
+            .iter()
+            .tuple_windows()
+            .find(|(a, b)| b.first_run <= a.last_run)
+        {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "run periods {}-{} and {}-{} overlap",
+                a.first_run, a.last_run, b.first_run, b.last_run
+            )));
+        }
+        Ok(Self { periods })
+    }
+
+    /// Returns the [`RunPeriod`]s of the [`PolarizationTable`], sorted by run.
+    pub fn periods(&self) -> &[RunPeriod<F>] {
+        &self.periods
+    }
+
+    /// Returns the [`RunPeriod`] which contains the given run, if any.
+    pub fn lookup(&self, run: u32) -> Option<&RunPeriod<F>> {
+        let index = self.periods.partition_point(|period| period.last_run < run);
+        self.periods
+            .get(index)
+            .filter(|period| period.first_run <= run)
+    }
+
+    /// Returns the EPS vector of the given run.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the run is not in
+    /// any [`RunPeriod`] of the [`PolarizationTable`].
+    pub fn eps(&self, run: u32) -> Result<Vector3<F>, RustitudeError> {
+        self.lookup(run).map(RunPeriod::eps).ok_or_else(|| {
+            RustitudeError::InvalidParameterValue(format!(
+                "run {run} is not in the polarization table"
+            ))
+        })
+    }
+}
+/// A transformation of the [`Event`]s in a [`Dataset`], such as a change of reference frame,
+/// which is typically applied as a preprocessing step right after loading (see
+/// [`Dataset::with_transforms`]).
+#[derive(Clone, Debug)]
+pub enum DatasetTransform<F: Field + 'static> {
+    /// Boosts every [`FourMomentum`] into the center-of-momentum frame of the final state (the
+    /// recoil and all daughters). The polarization vector is left unchanged.
+    BoostToCOM,
+    /// Rotates every [`FourMomentum`] and the polarization vector by a $`3\times 3`$ rotation
+    /// matrix (see [`FourMomentum::rotate`]).
+    Rotate(Matrix3<F>),
+    /// Replaces the daughters with the daughters at the given indices, in order. This can also
+    /// be used to drop daughters.
+    ReorderDaughters(Vec<usize>),
+    /// Smears the components $`(E, p_x, p_y, p_z)`$ of the recoil and every daughter by a
+    /// Gaussian resolution, as in a toy study of resolution effects. The beam is left unchanged.
+    /// This should be constructed with [`DatasetTransform::smear`].
+    Smear {
+        /// The lower-triangular Cholesky factor $`L`$ of the covariance $`\Sigma = LL^T`$ of the
+        /// resolution.
+        cholesky: Matrix4<F>,
+        /// The seed of the random number generator. Each [`Event`] is smeared with its own
+        /// generator seeded by this value and its index, so the result does not depend on the
+        /// order in which [`Event`]s are processed.
+        seed: u64,
+    },
+}
+impl<F: Field + 'static> DatasetTransform<F> {
+    /// Creates a [`DatasetTransform::Smear`] with the given $`4\times 4`$ covariance of the
+    /// components $`(E, p_x, p_y, p_z)`$ (in $`\text{GeV}^2`$) and random seed.
+    ///
+    /// Each component is smeared independently of the others, so the invariant mass of a
+    /// particle is not preserved. Components with zero variance are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the covariance is
+    /// not symmetric and positive semi-definite.
+    pub fn smear(covariance: Matrix4<F>, seed: u64) -> Result<Self, RustitudeError> {
+        if covariance != covariance.transpose() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "the covariance of a smearing transform must be symmetric".to_string(),
+            ));
+        }
+        let mut cholesky = Matrix4::zeros();
+        for j in 0..4 {
+            let pivot = covariance[(j, j)]
+                - (0..j)
+                    .map(|k| cholesky[(j, k)] * cholesky[(j, k)])
+                    .sum::<F>();
+            if pivot < F::zero() {
+                return Err(RustitudeError::InvalidParameterValue(
+                    "the covariance of a smearing transform must be positive semi-definite"
+                        .to_string(),
+                ));
+            }
+            if pivot == F::zero() {
+                continue;
+            }
+            cholesky[(j, j)] = F::sqrt(pivot);
+            for i in (j + 1)..4 {
+                cholesky[(i, j)] = (covariance[(i, j)]
+                    - (0..j)
+                        .map(|k| cholesky[(i, k)] * cholesky[(j, k)])
+                        .sum::<F>())
+                    / cholesky[(j, j)];
+            }
+        }
+        Ok(Self::Smear { cholesky, seed })
+    }
+
+    /// Applies the [`DatasetTransform`] to an [`Event`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if a
+    /// [`DatasetTransform::ReorderDaughters`] refers to a daughter which the [`Event`] does not
+    /// have.
+    pub fn apply(&self, event: &Event<F>) -> Result<Event<F>, RustitudeError> {
+        let mut event = event.clone();
+        match self {
+            Self::BoostToCOM => {
+                let final_state_p4 =
+                    event.recoil_p4 + event.daughter_p4s.iter().copied().sum::<FourMomentum<F>>();
+                event.beam_p4 = event.beam_p4.boost_along(&final_state_p4);
+                event.recoil_p4 = event.recoil_p4.boost_along(&final_state_p4);
+                for p4 in &mut event.daughter_p4s {
+                    *p4 = p4.boost_along(&final_state_p4);
+                }
+            }
+            Self::Rotate(rotation) => {
+                event.beam_p4 = event.beam_p4.rotate(rotation);
+                event.recoil_p4 = event.recoil_p4.rotate(rotation);
+                for p4 in &mut event.daughter_p4s {
+                    *p4 = p4.rotate(rotation);
+                }
+                event.eps = rotation * event.eps;
+            }
+            Self::ReorderDaughters(order) => {
+                event.daughter_p4s = order
+                    .iter()
+                    .map(|&i| {
+                        event.daughter_p4s.get(i).copied().ok_or_else(|| {
+                            RustitudeError::InvalidParameterValue(format!(
+                                "event #{} has no daughter at index {i}",
+                                event.index
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, RustitudeError>>()?;
+            }
+            Self::Smear { cholesky, seed } => {
+                let mut rng = fastrand::Rng::with_seed(
+                    seed.wrapping_add((event.index as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)),
+                );
+                let mut smear = |p4: &FourMomentum<F>| {
+                    // Standard normal deviates from the Box-Muller transform
+                    let normal = Vector4::from_fn(|_, _| {
+                        let (u1, u2) = (1.0 - rng.f64(), rng.f64());
+                        convert!(
+                            f64::sqrt(-2.0 * u1.ln()) * f64::cos(std::f64::consts::TAU * u2),
+                            F
+                        )
+                    });
+                    let shift = cholesky * normal;
+                    FourMomentum::new(
+                        p4.e() + shift[0],
+                        p4.px() + shift[1],
+                        p4.py() + shift[2],
+                        p4.pz() + shift[3],
+                    )
+                };
+                event.recoil_p4 = smear(&event.recoil_p4);
+                for p4 in &mut event.daughter_p4s {
+                    *p4 = smear(p4);
+                }
+            }
+        }
+        Ok(event)
+    }
+}
+/// The polarization state of the beam of an [`Event`] (see [`Event::polarization`]).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Polarization<F: Field> {
+    /// An unpolarized beam.
+    Unpolarized,
+    /// A linearly polarized beam with the given degree of polarization and angle (in radians) of
+    /// the polarization vector in the lab frame.
+    Linear {
+        /// The degree of linear polarization.
+        degree: F,
+        /// The angle of the polarization vector from the $`x`$-axis.
+        angle: F,
+    },
+    /// A circularly polarized beam with the given signed degree of polarization, which is
+    /// positive for helicity $`+1`$.
+    Circular {
+        /// The signed degree of circular polarization.
+        degree: F,
+    },
+    /// A beam with both linear and circular polarization.
+    Elliptical {
+        /// The degree of linear polarization.
+        degree: F,
+        /// The angle of the linear polarization vector from the $`x`$-axis.
+        angle: F,
+        /// The signed degree of circular polarization.
+        circular_degree: F,
+    },
+}
+
 impl<F: Field> Event<F> {
+    /// Returns the [`Polarization`] state of the beam, from the linear polarization vector
+    /// [`Event::eps`] and [`Event::circular_polarization`].
+    pub fn polarization(&self) -> Polarization<F> {
+        let (degree, angle) = (self.eps_mag(), F::atan2(self.eps.y, self.eps.x));
+        let circular_degree = self.circular_polarization;
+        match (degree > F::zero(), circular_degree != F::zero()) {
+            (false, false) => Polarization::Unpolarized,
+            (true, false) => Polarization::Linear { degree, angle },
+            (false, true) => Polarization::Circular {
+                degree: circular_degree,
+            },
+            (true, true) => Polarization::Elliptical {
+                degree,
+                angle,
+                circular_degree,
+            },
+        }
+    }
+    /// Converts the [`Event`] to another floating-point precision.
+    pub fn convert<G: Field>(&self) -> Event<G> {
+        Event {
+            index: self.index,
+            weight: convert!(self.weight, G),
+            beam_p4: self.beam_p4.convert(),
+            recoil_p4: self.recoil_p4.convert(),
+            daughter_p4s: self
+                .daughter_p4s
+                .iter()
+                .map(FourMomentum::convert)
+                .collect(),
+            eps: self.eps.map(|x| convert!(x, G)),
+            circular_polarization: convert!(self.circular_polarization, G),
+        }
+    }
     /// Returns the magnitude of the EPS vector
     pub fn eps_mag(&self) -> F {
         F::sqrt(F::powi(self.eps.x, 2) + F::powi(self.eps.y, 2) + F::powi(self.eps.z, 2))
     }
-    /// Reads an [`Event`] from a single [`Row`] in a Parquet file.
+    /// Reads an [`Event`] and its run number, if the file has a `RunNumber` column, from a
+    /// single [`Row`] in a Parquet file.
     ///
     /// # Panics
     ///
     /// This method currently panics if the list-like group types don't contain floats. This
     /// eventually needs to be sorted out.
+    #[cfg(feature = "parquet")]
     fn read_parquet_row(
         index: usize,
         row: Result<Row, parquet::errors::ParquetError>,
         method: ReadMethod<F>,
-    ) -> Result<Self, RustitudeError> {
+    ) -> Result<(Self, Option<u32>), RustitudeError> {
         let mut event = Self {
             index,
             ..Default::default()
@@ -141,8 +491,13 @@ impl<F: Field> Event<F> {
         let mut px_fs: Vec<F> = Vec::new();
         let mut py_fs: Vec<F> = Vec::new();
         let mut pz_fs: Vec<F> = Vec::new();
+        let mut run = None;
         for (name, field) in row?.get_column_iter() {
             match (name.as_str(), field) {
+                ("RunNumber", ParquetField::Int(value)) => run = u32::try_from(*value).ok(),
+                ("RunNumber", ParquetField::UInt(value)) => run = Some(*value),
+                ("RunNumber", ParquetField::Long(value)) => run = u32::try_from(*value).ok(),
+                ("RunNumber", ParquetField::ULong(value)) => run = u32::try_from(*value).ok(),
                 ("E_Beam", ParquetField::Float(value)) => {
                     event.beam_p4.set_e(convert!(*value, F));
                     if matches!(method, ReadMethod::EPSInBeam) {
@@ -163,10 +518,10 @@ impl<F: Field> Event<F> {
                         event.beam_p4.set_py(convert!(*value, F));
                     }
                 }
-                ("Pz_Beam", ParquetField::Float(value)) => {
-                    if !matches!(method, ReadMethod::EPSInBeam) {
-                        event.beam_p4.set_pz(convert!(*value, F));
-                    }
+                ("Pz_Beam", ParquetField::Float(value))
+                    if !matches!(method, ReadMethod::EPSInBeam) =>
+                {
+                    event.beam_p4.set_pz(convert!(*value, F));
                 }
                 ("Weight", ParquetField::Float(value)) => {
                     event.weight = convert!(*value, F);
@@ -244,6 +599,9 @@ impl<F: Field> Event<F> {
                 _ => {}
             }
         }
+        if let ReadMethod::Circular(degree) = method {
+            event.circular_polarization = degree;
+        }
         event.recoil_p4 = FourMomentum::new(e_fs[0], px_fs[0], py_fs[0], pz_fs[0]);
         event.daughter_p4s = e_fs[1..]
             .iter()
@@ -252,13 +610,269 @@ impl<F: Field> Event<F> {
             .zip(pz_fs[1..].iter())
             .map(|(((e, px), py), pz)| FourMomentum::new(*e, *px, *py, *pz))
             .collect();
-        // let final_state_p4 = event.recoil_p4 + event.daughter_p4s.iter().sum();
-        // event.beam_p4 = event.beam_p4.boost_along(&final_state_p4);
-        // event.recoil_p4 = event.recoil_p4.boost_along(&final_state_p4);
-        // for dp4 in event.daughter_p4s.iter_mut() {
-        //     *dp4 = dp4.boost_along(&final_state_p4);
-        // }
-        Ok(event)
+        Ok((event, run))
+    }
+}
+
+/// The covariance of the weights of the [`Event`]s in a [`Dataset`], such as the covariance of
+/// sWeights which are computed from a shared fit.
+///
+/// The covariance is stored as a set of disjoint blocks, each of which holds the positions of its
+/// [`Event`]s in the [`Dataset`] and the covariance matrix of their weights. Events which are not
+/// in any block are treated as independent with the usual variance of a weighted event,
+/// $`w_i^2`$. Events in different blocks are uncorrelated.
+#[derive(Clone, Debug, Default)]
+pub struct WeightCovariance<F: Field> {
+    blocks: Vec<(Vec<usize>, Vec<Vec<F>>)>,
+}
+
+impl<F: Field> WeightCovariance<F> {
+    /// Creates a [`WeightCovariance`] of independent weights with the given uncertainty for each
+    /// [`Event`], in the order of the [`Dataset`].
+    pub fn from_errors(errors: &[F]) -> Self {
+        Self {
+            blocks: errors
+                .iter()
+                .enumerate()
+                .map(|(i, error)| (vec![i], vec![vec![*error * *error]]))
+                .collect(),
+        }
+    }
+
+    /// Creates a [`WeightCovariance`] from blocks of correlated weights, each given by the
+    /// positions of its [`Event`]s in the [`Dataset`] and the covariance matrix of their weights.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if a covariance matrix
+    /// is not square with one row per [`Event`] in its block, is not symmetric, or has a negative
+    /// diagonal entry, or if an [`Event`] appears in more than one block.
+    pub fn from_blocks(blocks: Vec<(Vec<usize>, Vec<Vec<F>>)>) -> Result<Self, RustitudeError> {
+        let mut seen = std::collections::HashSet::new();
+        for (indices, covariance) in &blocks {
+            let n = indices.len();
+            if covariance.len() != n || covariance.iter().any(|row| row.len() != n) {
+                return Err(RustitudeError::InvalidParameterValue(format!(
+                    "the covariance of a block of {n} events must have shape ({n}, {n})"
+                )));
+            }
+            for (a, row) in covariance.iter().enumerate() {
+                if row[a] < F::zero() {
+                    return Err(RustitudeError::InvalidParameterValue(
+                        "weight variances must not be negative".to_string(),
+                    ));
+                }
+                if (0..a).any(|b| row[b] != covariance[b][a]) {
+                    return Err(RustitudeError::InvalidParameterValue(
+                        "weight covariance matrices must be symmetric".to_string(),
+                    ));
+                }
+            }
+            if let Some(index) = indices.iter().find(|index| !seen.insert(**index)) {
+                return Err(RustitudeError::InvalidParameterValue(format!(
+                    "event {index} appears in more than one weight covariance block"
+                )));
+            }
+        }
+        Ok(Self { blocks })
+    }
+
+    /// Returns the blocks of the [`WeightCovariance`].
+    pub fn blocks(&self) -> &[(Vec<usize>, Vec<Vec<F>>)] {
+        &self.blocks
+    }
+
+    /// Converts the [`WeightCovariance`] to another floating-point precision.
+    fn convert<G: Field>(&self) -> WeightCovariance<G> {
+        WeightCovariance {
+            blocks: self
+                .blocks
+                .iter()
+                .map(|(indices, matrix)| {
+                    (
+                        indices.clone(),
+                        matrix
+                            .iter()
+                            .map(|row| row.iter().map(|x| convert!(*x, G)).collect())
+                            .collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Combines the [`WeightCovariance`]s of two [`Dataset`]s which are concatenated, where the
+    /// second [`Dataset`] starts at position `offset`.
+    fn concatenate(a: Option<&Self>, b: Option<&Self>, offset: usize) -> Option<Arc<Self>> {
+        if a.is_none() && b.is_none() {
+            return None;
+        }
+        let shifted = b.into_iter().flat_map(|covariance| {
+            covariance.blocks.iter().map(|(indices, matrix)| {
+                (indices.iter().map(|i| i + offset).collect(), matrix.clone())
+            })
+        });
+        Some(Arc::new(Self {
+            blocks: a
+                .into_iter()
+                .flat_map(|covariance| covariance.blocks.iter().cloned())
+                .chain(shifted)
+                .collect(),
+        }))
+    }
+
+    /// Returns the largest [`Event`] position in any block, if there are any blocks.
+    fn max_index(&self) -> Option<usize> {
+        self.blocks
+            .iter()
+            .flat_map(|(indices, _)| indices.iter().copied())
+            .max()
+    }
+}
+
+/// The storage layout of a [`Dataset`] (see [`Dataset::with_layout`]).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DatasetLayout {
+    /// Events are only stored as an array of [`Event`] structs.
+    #[default]
+    Events,
+    /// Events are also stored as [`EventColumns`], with one contiguous array per component.
+    Columns,
+}
+
+/// The components of one [`FourMomentum`] across every [`Event`] of a [`Dataset`], stored as
+/// contiguous arrays.
+#[derive(Default, Debug, Clone)]
+pub struct FourMomentumColumns<F: Field + 'static> {
+    /// The energy of each [`FourMomentum`].
+    pub e: Vec<F>,
+    /// The $`x`$-component of the momentum of each [`FourMomentum`].
+    pub px: Vec<F>,
+    /// The $`y`$-component of the momentum of each [`FourMomentum`].
+    pub py: Vec<F>,
+    /// The $`z`$-component of the momentum of each [`FourMomentum`].
+    pub pz: Vec<F>,
+}
+
+impl<F: Field + 'static> FourMomentumColumns<F> {
+    fn collect<'a>(p4s: impl ExactSizeIterator<Item = &'a FourMomentum<F>>) -> Self {
+        let mut columns = Self {
+            e: Vec::with_capacity(p4s.len()),
+            px: Vec::with_capacity(p4s.len()),
+            py: Vec::with_capacity(p4s.len()),
+            pz: Vec::with_capacity(p4s.len()),
+        };
+        for p4 in p4s {
+            columns.e.push(p4.e());
+            columns.px.push(p4.px());
+            columns.py.push(p4.py());
+            columns.pz.push(p4.pz());
+        }
+        columns
+    }
+
+    /// Returns the [`FourMomentum`] at the given position.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `index` is out of range.
+    pub fn get(&self, index: usize) -> FourMomentum<F> {
+        FourMomentum::new(
+            self.e[index],
+            self.px[index],
+            self.py[index],
+            self.pz[index],
+        )
+    }
+}
+
+/// A structure-of-arrays copy of the [`Event`]s in a [`Dataset`].
+///
+/// Each component of every [`Event`] is stored in its own contiguous array, so loops over a single
+/// component (like the energy of the beam) read memory sequentially and can be vectorized. Every
+/// [`Event`] must have the same number of daughter particles.
+#[derive(Default, Debug, Clone)]
+pub struct EventColumns<F: Field + 'static> {
+    /// The weight of each [`Event`].
+    pub weights: Vec<F>,
+    /// The beam [`FourMomentum`] of each [`Event`].
+    pub beam: FourMomentumColumns<F>,
+    /// The recoil [`FourMomentum`] of each [`Event`].
+    pub recoil: FourMomentumColumns<F>,
+    /// The [`FourMomentum`] of each daughter particle, in the same order as
+    /// [`Event::daughter_p4s`].
+    pub daughters: Vec<FourMomentumColumns<F>>,
+    /// The $`x`$, $`y`$, and $`z`$ components of the polarization vector of each [`Event`].
+    pub eps: [Vec<F>; 3],
+    /// The signed degree of circular polarization of each [`Event`].
+    pub circular_polarization: Vec<F>,
+}
+
+impl<F: Field + 'static> EventColumns<F> {
+    /// Copies a slice of [`Event`]s into [`EventColumns`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the [`Event`]s do
+    /// not all have the same number of daughter particles.
+    pub fn new(events: &[Event<F>]) -> Result<Self, RustitudeError> {
+        let n_daughters = events.first().map_or(0, |event| event.daughter_p4s.len());
+        if let Some(event) = events
+            .iter()
+            .find(|event| event.daughter_p4s.len() != n_daughters)
+        {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "event {} has {} daughters, expected {n_daughters}",
+                event.index,
+                event.daughter_p4s.len()
+            )));
+        }
+        Ok(Self {
+            weights: events.iter().map(|event| event.weight).collect(),
+            beam: FourMomentumColumns::collect(events.iter().map(|event| &event.beam_p4)),
+            recoil: FourMomentumColumns::collect(events.iter().map(|event| &event.recoil_p4)),
+            daughters: (0..n_daughters)
+                .map(|i| {
+                    FourMomentumColumns::collect(events.iter().map(|event| &event.daughter_p4s[i]))
+                })
+                .collect(),
+            eps: [0, 1, 2].map(|i| events.iter().map(|event| event.eps[i]).collect()),
+            circular_polarization: events
+                .iter()
+                .map(|event| event.circular_polarization)
+                .collect(),
+        })
+    }
+
+    /// Returns the number of events stored in the columns.
+    pub const fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Checks if there are no events stored in the columns.
+    pub const fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Rebuilds the [`Event`] at the given position.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `index` is out of range.
+    pub fn event(&self, index: usize) -> Event<F> {
+        Event {
+            index,
+            weight: self.weights[index],
+            beam_p4: self.beam.get(index),
+            recoil_p4: self.recoil.get(index),
+            daughter_p4s: self
+                .daughters
+                .iter()
+                .map(|daughter| daughter.get(index))
+                .collect(),
+            eps: Vector3::new(self.eps[0][index], self.eps[1][index], self.eps[2][index]),
+            circular_polarization: self.circular_polarization[index],
+        }
     }
 }
 
@@ -268,32 +882,90 @@ impl<F: Field> Event<F> {
 /// A [`Dataset`] can be loaded from either Parquet and ROOT files using the corresponding
 /// `Dataset::from_*` methods. Events are stored in an [`Arc<Vec<Event>>`], since we
 /// rarely need to write data to a dataset (splitting/selecting/rejecting events) but often need to
-/// read events from a dataset.
+/// read events from a dataset. Cloning a [`Dataset`] only increases the reference counts of its
+/// storage, so several [`Manager`](crate::manager::Manager)s or [`DatasetView`]s can share the
+/// same [`Event`]s, and methods which modify the [`Event`]s in place only copy them if they are
+/// shared.
+///
+/// The [`DatasetLayout::Columns`] layout additionally keeps a structure-of-arrays copy of the
+/// events as [`EventColumns`], which can be selected when the data is loaded:
+///
+/// ```ignore
+/// let dataset = Dataset::from_parquet(path, ReadMethod::Standard)?
+///     .with_layout(DatasetLayout::Columns)?;
+/// ```
 #[derive(Default, Debug, Clone)]
 pub struct Dataset<F: Field + 'static> {
     /// Storage for events.
     pub events: Arc<Vec<Event<F>>>,
+    /// An optional [`WeightCovariance`] of the weights of the events (see
+    /// [`Dataset::with_weight_covariance`]).
+    pub weight_covariance: Option<Arc<WeightCovariance<F>>>,
+    /// An optional structure-of-arrays copy of the events (see [`Dataset::with_layout`]).
+    pub columns: Option<Arc<EventColumns<F>>>,
+    kinematics: KinematicsCache<F>,
+}
+
+/// A memoized set of named per-event arrays computed from the [`Event`]s of a [`Dataset`] (see
+/// [`Dataset::kinematics`]).
+///
+/// Clones of a [`Dataset`] share the same [`KinematicsCache`], so an array computed while
+/// loading one [`Manager`](crate::manager::Manager) is reused by every other
+/// [`Manager`](crate::manager::Manager) built over the same [`Dataset`].
+#[derive(Clone)]
+struct KinematicsCache<F: Field + 'static> {
+    arrays: Arc<RwLock<HashMap<String, Arc<[F]>>>>,
+}
+
+impl<F: Field + 'static> Default for KinematicsCache<F> {
+    fn default() -> Self {
+        Self {
+            arrays: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
 }
 
+impl<F: Field + 'static> std::fmt::Debug for KinematicsCache<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.arrays.read().keys()).finish()
+    }
+}
+
+/// The names of the arrays provided by [`Dataset::standard_kinematics`].
+pub const STANDARD_KINEMATICS: [&str; 5] = [
+    "resonance_mass",
+    "helicity_costheta",
+    "helicity_phi",
+    "gj_costheta",
+    "gj_phi",
+];
+
 impl<F: Field + 'static> Dataset<F> {
     /// Resets the indices of events in a dataset so they start at `0`.
+    ///
+    /// The [`Event`]s are left untouched (and are not copied if they are shared) if they are
+    /// already indexed in order.
     pub fn reindex(&mut self) {
-        self.events = Arc::new(
-            (*self.events)
-                .clone()
-                .iter_mut()
-                .enumerate()
-                .map(|(i, event)| {
-                    event.index = i;
-                    event.clone()
-                })
-                .collect(),
-        )
+        if self
+            .events
+            .iter()
+            .enumerate()
+            .all(|(i, event)| event.index == i)
+        {
+            return;
+        }
+        Arc::make_mut(&mut self.events)
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, event)| event.index = i);
     }
     // TODO: can we make an events(&self) -> &Vec<Field> method that actually works without cloning?
 
     /// Retrieves the weights from the events in the dataset
     pub fn weights(&self) -> Vec<F> {
+        if let Some(columns) = self.columns() {
+            return columns.weights.clone();
+        }
         self.events.iter().map(|e| e.weight).collect()
     }
 
@@ -335,6 +1007,7 @@ impl<F: Field + 'static> Dataset<F> {
     ///
     /// This method will fail if any individual event is missing all of the required fields, if
     /// they have the wrong type, or if the file doesn't exist/can't be read for any reason.
+    #[cfg(feature = "parquet")]
     pub fn from_parquet(path: &str, method: ReadMethod<F>) -> Result<Self, RustitudeError> {
         let path = Path::new(path);
         let file = File::open(path)?;
@@ -343,13 +1016,49 @@ impl<F: Field + 'static> Dataset<F> {
         Ok(Self::new(
             row_iter
                 .enumerate()
-                .map(|(i, row)| Event::read_parquet_row(i, row, method))
+                .map(|(i, row)| Event::read_parquet_row(i, row, method).map(|(event, _)| event))
                 .collect::<Result<Vec<Event<F>>, RustitudeError>>()?,
         ))
     }
 
+    /// Generates a new [`Dataset`] from a Parquet file with a `RunNumber` column, filling the EPS
+    /// vector of each [`Event`] from the given [`PolarizationTable`] rather than reading it from
+    /// the file.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail for the same reasons as [`Dataset::from_parquet`], if any row does
+    /// not have a non-negative integer `RunNumber` which fits in a [`u32`], or if any run is not
+    /// in the [`PolarizationTable`].
+    #[cfg(feature = "parquet")]
+    pub fn from_parquet_with_polarization_table(
+        path: &str,
+        table: &PolarizationTable<F>,
+    ) -> Result<Self, RustitudeError> {
+        let file = File::open(Path::new(path))?;
+        let reader = SerializedFileReader::new(file)?;
+        let row_iter = reader.get_row_iter(None)?;
+        let (events, runs): (Vec<Event<F>>, Vec<u32>) = row_iter
+            .enumerate()
+            .map(|(i, row)| {
+                let (event, run) = Event::read_parquet_row(i, row, ReadMethod::Unpolarized)?;
+                let run = run.ok_or_else(|| {
+                    RustitudeError::DatasetReadError(
+                        format!("RunNumber of row {i}"),
+                        "u32".to_string(),
+                    )
+                })?;
+                Ok((event, run))
+            })
+            .collect::<Result<Vec<_>, RustitudeError>>()?
+            .into_iter()
+            .unzip();
+        Self::new(events).with_polarization_table(&runs, table)
+    }
+
     /// Extract a branch from a ROOT `TTree` containing a [`Field`] (float in C). This method
     /// converts the underlying element to an [`Field`].
+    #[cfg(feature = "oxyroot")]
     fn extract_f32(path: &str, ttree: &ReaderTree, branch: &str) -> Result<Vec<F>, RustitudeError> {
         let res = ttree
             .branch(branch)
@@ -368,6 +1077,7 @@ impl<F: Field + 'static> Dataset<F> {
 
     /// Extract a branch from a ROOT `TTree` containing an array of [`Field`]s (floats in C). This
     /// method converts the underlying elements to [`Field`]s.
+    #[cfg(feature = "oxyroot")]
     fn extract_vec_f32(
         path: &str,
         ttree: &ReaderTree,
@@ -393,15 +1103,51 @@ impl<F: Field + 'static> Dataset<F> {
         Ok(res)
     }
 
+    /// Extract a branch from a ROOT `TTree` containing unsigned integers (such as run numbers).
+    #[cfg(feature = "oxyroot")]
+    fn extract_u32(
+        path: &str,
+        ttree: &ReaderTree,
+        branch: &str,
+    ) -> Result<Vec<u32>, RustitudeError> {
+        let res = ttree
+            .branch(branch)
+            .ok_or_else(|| {
+                RustitudeError::OxyrootError(format!(
+                    "Could not find {} branch in {}",
+                    branch, path
+                ))
+            })?
+            .as_iter::<u32>()
+            .map_err(|err| RustitudeError::OxyrootError(err.to_string()))?
+            .collect();
+        Ok(res)
+    }
+
     /// Generates a new [`Dataset`] from a ROOT file.
     ///
     /// # Errors
     ///
     /// This method will fail if any individual event is missing all of the required fields, if
     /// they have the wrong type, or if the file doesn't exist/can't be read for any reason.
+    /// Data stored as an `RNTuple` rather than a `TTree` cannot be read by [`oxyroot`], so it
+    /// yields an error which points to the Python reader (`rustitude.open`), which reads it
+    /// through `uproot`.
+    #[cfg(feature = "oxyroot")]
     pub fn from_root(path: &str, method: ReadMethod<F>) -> Result<Self, RustitudeError> {
-        let ttree = RootFile::open(path)
-            .map_err(|err| RustitudeError::OxyrootError(err.to_string()))?
+        let mut file =
+            RootFile::open(path).map_err(|err| RustitudeError::OxyrootError(err.to_string()))?;
+        if file
+            .keys()
+            .iter()
+            .any(|key| key.name() == "kin" && key.class_name().contains("RNTuple"))
+        {
+            return Err(RustitudeError::OxyrootError(format!(
+                "\"kin\" in {path} is an RNTuple, which is not supported by the Rust reader \
+                (use rustitude.open in Python, which reads RNTuples through uproot)"
+            )));
+        }
+        let ttree = file
             .get_tree("kin")
             .map_err(|err| RustitudeError::OxyrootError(err.to_string()))?;
         let weight: Vec<F> = Self::extract_f32(path, &ttree, "Weight")?;
@@ -447,6 +1193,9 @@ impl<F: Field + 'static> Dataset<F> {
                             FourMomentum::new(e_b, px_b, py_b, pz_b),
                             Vector3::new(x, y, z),
                         ),
+                        ReadMethod::Unpolarized | ReadMethod::Circular(_) => {
+                            (FourMomentum::new(e_b, px_b, py_b, pz_b), Vector3::zeros())
+                        }
                     };
                     Event {
                         index: i,
@@ -462,6 +1211,11 @@ impl<F: Field + 'static> Dataset<F> {
                         .map(|(e, px, py, pz)| FourMomentum::new(*e, *px, *py, *pz))
                         .collect(),
                         eps,
+                        circular_polarization: if let ReadMethod::Circular(degree) = method {
+                            degree
+                        } else {
+                            F::zero()
+                        },
                     }
                 },
             )
@@ -469,14 +1223,398 @@ impl<F: Field + 'static> Dataset<F> {
         ))
     }
 
+    /// Generates a new [`Dataset`] from a ROOT file with a `RunNumber` branch, filling the EPS
+    /// vector of each [`Event`] from the given [`PolarizationTable`] rather than reading it from
+    /// the file.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail for the same reasons as [`Dataset::from_root`], if the file has no
+    /// `RunNumber` branch of unsigned integers, or if any run is not in the
+    /// [`PolarizationTable`].
+    #[cfg(feature = "oxyroot")]
+    pub fn from_root_with_polarization_table(
+        path: &str,
+        table: &PolarizationTable<F>,
+    ) -> Result<Self, RustitudeError> {
+        let mut file =
+            RootFile::open(path).map_err(|err| RustitudeError::OxyrootError(err.to_string()))?;
+        let ttree = file
+            .get_tree("kin")
+            .map_err(|err| RustitudeError::OxyrootError(err.to_string()))?;
+        let runs = Self::extract_u32(path, &ttree, "RunNumber")?;
+        Self::from_root(path, ReadMethod::Unpolarized)?.with_polarization_table(&runs, table)
+    }
+
+    /// Computes a 64-bit FNV-1a hash of the weights, four-momenta, and polarizations of every
+    /// [`Event`] in the [`Dataset`].
+    ///
+    /// Values are hashed as [`f64`]s, so the result is independent of platform and is the same
+    /// for [`f32`] and [`f64`] [`Dataset`]s read from single-precision files. This is intended to
+    /// identify the [`Dataset`] used in a fit, not as a cryptographic hash.
+    pub fn fingerprint(&self) -> u64 {
+        const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0100_0000_01b3;
+        let mut hash = OFFSET;
+        let mut write = |value: F| {
+            for byte in value.to_f64().unwrap_or(f64::NAN).to_le_bytes() {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(PRIME);
+            }
+        };
+        for event in self.events.iter() {
+            write(event.weight);
+            for p4 in std::iter::once(&event.beam_p4)
+                .chain(std::iter::once(&event.recoil_p4))
+                .chain(event.daughter_p4s.iter())
+            {
+                write(p4.e());
+                write(p4.px());
+                write(p4.py());
+                write(p4.pz());
+            }
+            event.eps.iter().for_each(|&x| write(x));
+            // Only hash a circular polarization if there is one, so that fingerprints of
+            // linearly polarized and unpolarized datasets are unchanged
+            if event.circular_polarization != F::zero() {
+                write(event.circular_polarization);
+            }
+        }
+        hash
+    }
+
+    /// Converts the [`Dataset`] to another floating-point precision, keeping its
+    /// [`DatasetLayout`] and [`WeightCovariance`].
+    ///
+    /// This allows a fit made with [`f32`] to be refined with [`f64`] (or vice versa) without
+    /// reloading the data (see also [`Model::convert`](crate::amplitude::Model::convert)).
+    /// Converting to [`f32`] rounds every value to single precision.
+    pub fn convert<G: Field + 'static>(&self) -> Dataset<G> {
+        let events: Vec<Event<G>> = self.events.par_iter().map(Event::convert).collect();
+        Dataset {
+            columns: self
+                .columns
+                .as_ref()
+                .and_then(|_| EventColumns::new(&events).ok())
+                .map(Arc::new),
+            events: Arc::new(events),
+            weight_covariance: self
+                .weight_covariance
+                .as_ref()
+                .map(|covariance| Arc::new(covariance.convert())),
+            kinematics: KinematicsCache::default(),
+        }
+    }
+
+    /// Converts the [`Dataset`] to [`f64`] precision (see [`Dataset::convert`]).
+    pub fn to_f64(&self) -> Dataset<f64> {
+        self.convert()
+    }
+
+    /// Converts the [`Dataset`] to [`f32`] precision (see [`Dataset::convert`]).
+    pub fn to_f32(&self) -> Dataset<f32> {
+        self.convert()
+    }
+
     /// Generate a new [`Dataset`] from a [`Vec<Event>`].
     pub fn new(events: Vec<Event<F>>) -> Self {
         info!("Dataset created with {} events", events.len());
         Self {
             events: Arc::new(events),
+            weight_covariance: None,
+            columns: None,
+            kinematics: KinematicsCache::default(),
+        }
+    }
+
+    /// Sets the storage layout of the [`Dataset`], building or dropping its [`EventColumns`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if
+    /// [`DatasetLayout::Columns`] is requested and the [`Event`]s do not all have the same
+    /// number of daughter particles.
+    pub fn with_layout(mut self, layout: DatasetLayout) -> Result<Self, RustitudeError> {
+        self.columns = match layout {
+            DatasetLayout::Events => None,
+            DatasetLayout::Columns => Some(Arc::new(EventColumns::new(&self.events)?)),
+        };
+        Ok(self)
+    }
+
+    /// Applies a sequence of [`DatasetTransform`]s, in order, to every [`Event`] in the
+    /// [`Dataset`], keeping its [`DatasetLayout`] and [`WeightCovariance`].
+    ///
+    /// This is intended as a preprocessing pipeline which is applied when the [`Dataset`] is
+    /// loaded:
+    ///
+    /// ```ignore
+    /// let dataset = Dataset::from_parquet(path, ReadMethod::Standard)?.with_transforms(&[
+    ///     DatasetTransform::BoostToCOM,
+    ///     DatasetTransform::ReorderDaughters(vec![1, 0]),
+    /// ])?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if any [`DatasetTransform`] cannot be applied to an
+    /// [`Event`] (see [`DatasetTransform::apply`]), or if the transformed [`Event`]s cannot be
+    /// stored in the [`DatasetLayout`] of the [`Dataset`].
+    pub fn with_transforms(
+        mut self,
+        transforms: &[DatasetTransform<F>],
+    ) -> Result<Self, RustitudeError> {
+        let events = self
+            .events
+            .par_iter()
+            .map(|event| {
+                transforms
+                    .iter()
+                    .try_fold(event.clone(), |event, transform| transform.apply(&event))
+            })
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        let layout = self.layout();
+        self.events = Arc::new(events);
+        self.kinematics = KinematicsCache::default();
+        self.with_layout(layout)
+    }
+
+    /// Sets the EPS vector of every [`Event`] in the [`Dataset`] to the linear polarization of
+    /// the [`RunPeriod`] in the [`PolarizationTable`] which contains its run, where `runs` holds
+    /// the run number of each [`Event`] in order.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there is not one
+    /// run number per [`Event`] or if any run is not in the [`PolarizationTable`].
+    pub fn with_polarization_table(
+        mut self,
+        runs: &[u32],
+        table: &PolarizationTable<F>,
+    ) -> Result<Self, RustitudeError> {
+        if runs.len() != self.len() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "expected {} run numbers, got {}",
+                self.len(),
+                runs.len()
+            )));
+        }
+        let events = self
+            .events
+            .par_iter()
+            .zip(runs)
+            .map(|(event, &run)| {
+                Ok(Event {
+                    eps: table.eps(run)?,
+                    ..event.clone()
+                })
+            })
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        let layout = self.layout();
+        self.events = Arc::new(events);
+        self.kinematics = KinematicsCache::default();
+        self.with_layout(layout)
+    }
+
+    /// Boosts every [`Event`] in the [`Dataset`] into the center-of-momentum frame of its final
+    /// state. This is shorthand for [`Dataset::with_transforms`] with
+    /// [`DatasetTransform::BoostToCOM`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Dataset::with_transforms`].
+    pub fn boost_to_com(self) -> Result<Self, RustitudeError> {
+        self.with_transforms(&[DatasetTransform::BoostToCOM])
+    }
+
+    /// Returns the storage layout of the [`Dataset`].
+    pub const fn layout(&self) -> DatasetLayout {
+        if self.columns.is_some() {
+            DatasetLayout::Columns
+        } else {
+            DatasetLayout::Events
+        }
+    }
+
+    /// Returns the [`EventColumns`] of the [`Dataset`] if it uses the [`DatasetLayout::Columns`]
+    /// layout.
+    pub fn columns(&self) -> Option<&EventColumns<F>> {
+        self.columns.as_deref()
+    }
+
+    /// Attaches a [`WeightCovariance`] to the [`Dataset`], replacing any existing one.
+    ///
+    /// The covariance is used by
+    /// [`ExtendedLogLikelihood::weighted_covariance`](crate::manager::ExtendedLogLikelihood::weighted_covariance)
+    /// to propagate the uncertainties of the weights into the uncertainties of the fit
+    /// parameters, and by [`Dataset::get_bootstrap_indices`] to resample correlated events
+    /// together.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if a block refers to
+    /// an [`Event`] beyond the end of the [`Dataset`].
+    pub fn with_weight_covariance(
+        mut self,
+        weight_covariance: WeightCovariance<F>,
+    ) -> Result<Self, RustitudeError> {
+        if let Some(index) = weight_covariance.max_index().filter(|i| *i >= self.len()) {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "event {index} is out of range for a dataset with {} events",
+                self.len()
+            )));
+        }
+        self.weight_covariance = Some(Arc::new(weight_covariance));
+        Ok(self)
+    }
+
+    /// Returns every block of correlated weights in the [`Dataset`], including a block of one
+    /// [`Event`] with variance $`w_i^2`$ for each [`Event`] which is not covered by the
+    /// [`WeightCovariance`] (or for every [`Event`] if there is none).
+    pub fn weight_blocks(&self) -> Vec<(Vec<usize>, Vec<Vec<F>>)> {
+        let mut blocks = self
+            .weight_covariance
+            .as_ref()
+            .map_or_else(Vec::new, |covariance| covariance.blocks.clone());
+        let mut covered = vec![false; self.len()];
+        blocks
+            .iter()
+            .flat_map(|(indices, _)| indices)
+            .for_each(|i| covered[*i] = true);
+        blocks.extend(
+            self.events
+                .iter()
+                .zip(covered)
+                .enumerate()
+                .filter(|(_, (_, covered))| !covered)
+                .map(|(i, (event, _))| (vec![i], vec![vec![event.weight * event.weight]])),
+        );
+        blocks
+    }
+
+    /// Returns the per-[`Event`] array named `key`, computing it with `compute` the first time
+    /// it is requested.
+    ///
+    /// The array is memoized in a cache which is shared by every clone of the [`Dataset`], so
+    /// several [`Node`](crate::amplitude::Node)s (or [`Manager`](crate::manager::Manager)s) which
+    /// request the same key in [`Node::precalculate`](crate::amplitude::Node::precalculate) only
+    /// compute it once. The key must uniquely describe the computation, since `compute` is
+    /// ignored if an array with the same key is already stored. The cache is reset by methods
+    /// which replace the [`Event`]s, but not if [`Dataset::events`] is modified directly, in
+    /// which case [`Dataset::clear_kinematics`] should be called.
+    ///
+    /// # Errors
+    ///
+    /// This method will return any error produced by `compute`.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use rustitude_core::prelude::*;
+    /// use rustitude_core::utils::generate_test_dataset_f64;
+    /// # fn main() -> Result<(), RustitudeError> {
+    /// let dataset = generate_test_dataset_f64();
+    /// let energies = dataset.kinematics("beam_energy", |event| Ok(event.beam_p4.e()))?;
+    /// assert_eq!(energies.len(), dataset.len());
+    /// // The second request reuses the stored array
+    /// let again = dataset.clone().kinematics("beam_energy", |_| Ok(0.0))?;
+    /// assert_eq!(energies, again);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn kinematics(
+        &self,
+        key: &str,
+        compute: impl Fn(&Event<F>) -> Result<F, RustitudeError> + Sync + Send,
+    ) -> Result<Arc<[F]>, RustitudeError> {
+        if let Some(array) = self.kinematics.arrays.read().get(key) {
+            return Ok(Arc::clone(array));
+        }
+        // The array is computed outside of the lock, so two threads which miss at the same time
+        // may both compute it, in which case the first one is stored
+        let array: Arc<[F]> = self
+            .events
+            .par_iter()
+            .map(compute)
+            .collect::<Result<Vec<F>, RustitudeError>>()?
+            .into();
+        debug!("Computed kinematics array {key}");
+        Ok(Arc::clone(
+            self.kinematics
+                .arrays
+                .write()
+                .entry(key.to_string())
+                .or_insert(array),
+        ))
+    }
+
+    /// Returns one of the [`STANDARD_KINEMATICS`] arrays, memoized as in
+    /// [`Dataset::kinematics`].
+    ///
+    /// These treat the sum of every daughter in [`Event::daughter_p4s`] as the resonance and
+    /// measure the decay angles of the first daughter (see [`frames::decay_angles`]):
+    ///
+    /// - `"resonance_mass"`: the invariant mass of the resonance.
+    /// - `"helicity_costheta"` and `"helicity_phi"`: the decay angles in the [`Frame::Helicity`]
+    ///   frame.
+    /// - `"gj_costheta"` and `"gj_phi"`: the decay angles in the [`Frame::GottfriedJackson`]
+    ///   frame.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParseError`] if `name` is not one of the
+    /// [`STANDARD_KINEMATICS`], or a [`RustitudeError::EvaluationError`] if an angle is requested
+    /// for an [`Event`] without daughters.
+    pub fn standard_kinematics(&self, name: &str) -> Result<Arc<[F]>, RustitudeError> {
+        let resonance =
+            |event: &Event<F>| event.daughter_p4s.iter().copied().sum::<FourMomentum<F>>();
+        let angles = |frame: Frame, event: &Event<F>| -> Result<(F, F), RustitudeError> {
+            let daughter = event.daughter_p4s.first().ok_or_else(|| {
+                RustitudeError::EvaluationError(format!("event #{} has no daughters", event.index))
+            })?;
+            Ok(frames::decay_angles(
+                frame,
+                event,
+                &resonance(event),
+                daughter,
+            ))
+        };
+        match name {
+            "resonance_mass" => self.kinematics(name, |event| Ok(resonance(event).m())),
+            "helicity_costheta" => {
+                self.kinematics(name, |event| Ok(angles(Frame::Helicity, event)?.0))
+            }
+            "helicity_phi" => self.kinematics(name, |event| Ok(angles(Frame::Helicity, event)?.1)),
+            "gj_costheta" => {
+                self.kinematics(name, |event| Ok(angles(Frame::GottfriedJackson, event)?.0))
+            }
+            "gj_phi" => {
+                self.kinematics(name, |event| Ok(angles(Frame::GottfriedJackson, event)?.1))
+            }
+            _ => Err(RustitudeError::ParseError(format!(
+                "Unknown kinematics: {name} (expected one of {})",
+                STANDARD_KINEMATICS.join(", ")
+            ))),
         }
     }
 
+    /// Returns the keys of every memoized array (see [`Dataset::kinematics`]).
+    pub fn kinematics_keys(&self) -> Vec<String> {
+        self.kinematics
+            .arrays
+            .read()
+            .keys()
+            .cloned()
+            .sorted()
+            .collect()
+    }
+
+    /// Removes every memoized array (see [`Dataset::kinematics`]) from this [`Dataset`] and all
+    /// of its clones.
+    pub fn clear_kinematics(&self) {
+        self.kinematics.arrays.write().clear();
+    }
+
     /// Checks if the dataset is empty.
     pub fn is_empty(&self) -> bool {
         self.events.is_empty()
@@ -487,14 +1625,178 @@ impl<F: Field + 'static> Dataset<F> {
         self.events.len()
     }
 
+    /// Returns the `rank`-th of `size` contiguous, nearly equal shards of the [`Dataset`], with
+    /// its [`Event`]s reindexed to start at `0`.
+    ///
+    /// The first `len % size` shards get one extra [`Event`]. The shard keeps the
+    /// [`DatasetLayout`] of the [`Dataset`], but not its [`WeightCovariance`], since correlated
+    /// blocks may cross shards.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `rank` is not less than `size`.
+    pub fn shard(&self, rank: usize, size: usize) -> Self {
+        assert!(rank < size, "rank {rank} is out of range for {size} shards");
+        let (base, extra) = (self.len() / size, self.len() % size);
+        let start = rank * base + rank.min(extra);
+        let end = start + base + usize::from(rank < extra);
+        let mut shard = Self::new(self.events[start..end].to_vec());
+        shard.reindex();
+        shard.columns = self
+            .columns
+            .as_ref()
+            .and_then(|_| EventColumns::new(&shard.events).ok())
+            .map(Arc::new);
+        shard
+    }
+
+    /// Appends the [`Event`]s of `other` to the end of the [`Dataset`] in place, reindexing them
+    /// to follow the existing [`Event`]s.
+    ///
+    /// The existing [`Event`]s are only copied if their storage is shared with another
+    /// [`Dataset`] or [`DatasetView`]. As with the addition of two [`Dataset`]s, the
+    /// [`WeightCovariance`]s are combined and the [`DatasetLayout::Columns`] layout is kept if the
+    /// [`Event`]s all have the same number of daughters.
+    pub fn append(&mut self, other: Self) {
+        let offset = self.len();
+        self.weight_covariance = WeightCovariance::concatenate(
+            self.weight_covariance.as_deref(),
+            other.weight_covariance.as_deref(),
+            offset,
+        );
+        self.kinematics = KinematicsCache::default();
+        let events = Arc::make_mut(&mut self.events);
+        events.extend(
+            Arc::try_unwrap(other.events)
+                .unwrap_or_else(|arc| (*arc).clone())
+                .into_iter()
+                .enumerate()
+                .map(|(i, event)| Event {
+                    index: offset + i,
+                    ..event
+                }),
+        );
+        if self.columns.is_some() || other.columns.is_some() {
+            self.columns = EventColumns::new(events).ok().map(Arc::new);
+        }
+    }
+
+    /// Returns a [`DatasetView`] of the [`Event`]s at the given range of positions.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the range is not
+    /// within the [`Dataset`].
+    pub fn view(&self, range: Range<usize>) -> Result<DatasetView<F>, RustitudeError> {
+        if range.start > range.end || range.end > self.len() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "range {range:?} is out of bounds for a dataset of {} events",
+                self.len()
+            )));
+        }
+        Ok(DatasetView {
+            parent: self.clone(),
+            selection: Selection::Range(range),
+        })
+    }
+
+    /// Returns a [`DatasetView`] of the [`Event`]s at the given positions, which may be repeated
+    /// (such as in a bootstrap) and need not be sorted.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if any position is
+    /// not within the [`Dataset`].
+    pub fn view_indices(&self, indices: Vec<usize>) -> Result<DatasetView<F>, RustitudeError> {
+        if let Some(index) = indices.iter().find(|index| **index >= self.len()) {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "index {index} is out of bounds for a dataset of {} events",
+                self.len()
+            )));
+        }
+        Ok(DatasetView {
+            parent: self.clone(),
+            selection: Selection::Indices(indices.into()),
+        })
+    }
+
+    /// Returns a [`DatasetView`] of the [`Event`]s for which `mask` is `true`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the mask does not
+    /// have one entry per [`Event`].
+    pub fn view_mask(&self, mask: &[bool]) -> Result<DatasetView<F>, RustitudeError> {
+        if mask.len() != self.len() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "expected a mask of {} entries, got {}",
+                self.len(),
+                mask.len()
+            )));
+        }
+        self.view_indices(mask.iter().positions(|selected| *selected).collect())
+    }
+
+    /// Splits the [`Dataset`] by the given query into [`DatasetView`]s of each bin, the
+    /// underflow bin, and the overflow bin respectively.
+    ///
+    /// This is equivalent to [`Dataset::get_binned_indices`] but computes the bins in a single
+    /// pass over the [`Event`]s and does not copy them.
+    pub fn get_binned_views(
+        &self,
+        variable: impl Fn(&Event<F>) -> F + Sync + Send,
+        range: (F, F),
+        nbins: usize,
+    ) -> (Vec<DatasetView<F>>, DatasetView<F>, DatasetView<F>) {
+        let width = (range.1 - range.0) / convert!(nbins, F);
+        let edges: Vec<F> = (0..=nbins)
+            .map(|m| F::mul_add(width, convert!(m, F), range.0))
+            .collect();
+        let bin_of: Vec<usize> = self
+            .events
+            .par_iter()
+            .map(|event| edges.partition_point(|edge| *edge <= variable(event)))
+            .collect();
+        // Bin 0 is the underflow, bins 1 to nbins are the bins, and bin nbins + 1 is the overflow
+        let mut positions = vec![Vec::new(); nbins + 2];
+        for (position, bin) in bin_of.into_iter().enumerate() {
+            positions[bin].push(position);
+        }
+        let mut views = positions.into_iter().map(|indices| DatasetView {
+            parent: self.clone(),
+            selection: Selection::Indices(indices.into()),
+        });
+        let underflow = views.next().unwrap_or_else(|| unreachable!());
+        let mut binned: Vec<DatasetView<F>> = views.collect();
+        let overflow = binned.pop().unwrap_or_else(|| unreachable!());
+        (binned, underflow, overflow)
+    }
+
     /// Returns a set of indices which represent a bootstrapped [`Dataset`]. This method is to be
     /// used in conjunction with
     /// [`Manager::evaluate_indexed`](crate::manager::Manager::evaluate_indexed).
+    ///
+    /// If the [`Dataset`] has a [`WeightCovariance`] with correlated blocks, whole blocks are
+    /// resampled together (along with every uncovered [`Event`] as its own block), so the
+    /// bootstrapped [`Dataset`] may have a different number of events.
     pub fn get_bootstrap_indices(&self, seed: usize) -> Vec<usize> {
         fastrand::seed(seed as u64);
-        let mut inds: Vec<usize> = repeat_with(|| fastrand::usize(0..self.len()))
-            .take(self.len())
-            .collect();
+        let correlated = self
+            .weight_covariance
+            .as_ref()
+            .is_some_and(|covariance| covariance.blocks.iter().any(|(b, _)| b.len() > 1));
+        let mut inds: Vec<usize> = if correlated {
+            let blocks = self.weight_blocks();
+            repeat_with(|| &blocks[fastrand::usize(0..blocks.len())].0)
+                .take(blocks.len())
+                .flatten()
+                .copied()
+                .collect()
+        } else {
+            repeat_with(|| fastrand::usize(0..self.len()))
+                .take(self.len())
+                .collect()
+        };
         inds.sort_unstable();
         inds
     }
@@ -552,15 +1854,159 @@ impl<F: Field + 'static> Dataset<F> {
     }
 }
 
+/// The positions of the [`Event`]s of a [`Dataset`] which belong to a [`DatasetView`].
+#[derive(Clone, Debug)]
+enum Selection {
+    Range(Range<usize>),
+    Indices(Arc<[usize]>),
+}
+
+/// A view of a subset of the [`Event`]s of a parent [`Dataset`].
+///
+/// The subset is selected by a range of positions, a list of positions, or a mask (see
+/// [`Dataset::view`], [`Dataset::view_indices`], and [`Dataset::view_mask`]). A [`DatasetView`] shares the storage of its parent, so subsets such as the bins of a binned
+/// fit (see [`Dataset::get_binned_views`]) do not copy any [`Event`]s. The positions of the
+/// selected [`Event`]s can be passed to
+/// [`Manager::evaluate_indexed`](crate::manager::Manager::evaluate_indexed) with
+/// [`DatasetView::indices`], or the view can be copied into a standalone [`Dataset`] with
+/// [`DatasetView::to_dataset`].
+#[derive(Clone, Debug)]
+pub struct DatasetView<F: Field + 'static> {
+    parent: Dataset<F>,
+    selection: Selection,
+}
+
+impl<F: Field + 'static> DatasetView<F> {
+    /// Returns the parent [`Dataset`] of the [`DatasetView`].
+    pub const fn parent(&self) -> &Dataset<F> {
+        &self.parent
+    }
+
+    /// Returns the number of [`Event`]s in the [`DatasetView`].
+    pub fn len(&self) -> usize {
+        match &self.selection {
+            Selection::Range(range) => range.len(),
+            Selection::Indices(indices) => indices.len(),
+        }
+    }
+
+    /// Checks if the [`DatasetView`] has no [`Event`]s.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the positions of the [`Event`]s of the [`DatasetView`] in its
+    /// parent [`Dataset`].
+    pub fn iter_indices(&self) -> impl ExactSizeIterator<Item = usize> + '_ {
+        match &self.selection {
+            Selection::Range(range) => Either::Left(range.clone()),
+            Selection::Indices(indices) => Either::Right(indices.iter().copied()),
+        }
+    }
+
+    /// Returns the positions of the [`Event`]s of the [`DatasetView`] in its parent [`Dataset`].
+    pub fn indices(&self) -> Vec<usize> {
+        self.iter_indices().collect()
+    }
+
+    /// Returns the [`Event`] at the given position in the [`DatasetView`], if there is one.
+    pub fn get(&self, index: usize) -> Option<&Event<F>> {
+        let position = match &self.selection {
+            Selection::Range(range) => (index < range.len()).then(|| range.start + index)?,
+            Selection::Indices(indices) => *indices.get(index)?,
+        };
+        self.parent.events.get(position)
+    }
+
+    /// Returns an iterator over the [`Event`]s of the [`DatasetView`].
+    pub fn events(&self) -> impl ExactSizeIterator<Item = &Event<F>> + '_ {
+        self.iter_indices()
+            .map(|position| &self.parent.events[position])
+    }
+
+    /// Retrieves the weights of the [`Event`]s of the [`DatasetView`].
+    pub fn weights(&self) -> Vec<F> {
+        if let Some(columns) = self.parent.columns() {
+            return self
+                .iter_indices()
+                .map(|position| columns.weights[position])
+                .collect();
+        }
+        self.events().map(|event| event.weight).collect()
+    }
+
+    /// Returns a [`DatasetView`] of the given range of positions within this [`DatasetView`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the range is not
+    /// within the [`DatasetView`].
+    pub fn view(&self, range: Range<usize>) -> Result<Self, RustitudeError> {
+        if range.start > range.end || range.end > self.len() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "range {range:?} is out of bounds for a view of {} events",
+                self.len()
+            )));
+        }
+        let selection = match &self.selection {
+            Selection::Range(outer) => {
+                Selection::Range(outer.start + range.start..outer.start + range.end)
+            }
+            Selection::Indices(indices) => Selection::Indices(indices[range].into()),
+        };
+        Ok(Self {
+            parent: self.parent.clone(),
+            selection,
+        })
+    }
+
+    /// Copies the [`Event`]s of the [`DatasetView`] into a new [`Dataset`], reindexed to start
+    /// at `0`.
+    ///
+    /// The new [`Dataset`] keeps the [`DatasetLayout`] of the parent, but not its
+    /// [`WeightCovariance`], since correlated blocks may only be partially selected.
+    pub fn to_dataset(&self) -> Dataset<F> {
+        let mut dataset = Dataset::new(self.events().cloned().collect());
+        dataset.reindex();
+        dataset.columns = self
+            .parent
+            .columns
+            .as_ref()
+            .and_then(|_| EventColumns::new(&dataset.events).ok())
+            .map(Arc::new);
+        dataset
+    }
+}
+
+impl<F: Field + 'static> From<DatasetView<F>> for Dataset<F> {
+    fn from(view: DatasetView<F>) -> Self {
+        view.to_dataset()
+    }
+}
+
 impl<F: Field + 'static> Add for Dataset<F> {
     type Output = Self;
 
     fn add(self, other: Self) -> Self::Output {
         let mut combined_events = Vec::with_capacity(self.events.len() + other.events.len());
         combined_events.extend(Arc::try_unwrap(self.events).unwrap_or_else(|arc| (*arc).clone()));
+        let weight_covariance = WeightCovariance::concatenate(
+            self.weight_covariance.as_deref(),
+            other.weight_covariance.as_deref(),
+            combined_events.len(),
+        );
         combined_events.extend(Arc::try_unwrap(other.events).unwrap_or_else(|arc| (*arc).clone()));
+        // The combination keeps the columnar layout if both sides share a number of daughters
+        let columns = if self.columns.is_some() || other.columns.is_some() {
+            EventColumns::new(&combined_events).ok().map(Arc::new)
+        } else {
+            None
+        };
         Self {
             events: Arc::new(combined_events),
+            weight_covariance,
+            columns,
+            kinematics: KinematicsCache::default(),
         }
     }
 }
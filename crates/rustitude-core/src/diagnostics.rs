@@ -0,0 +1,314 @@
+//! This module contains goodness-of-fit and model-selection utilities.
+//!
+//! A [`BinnedChiSquare`] compares a binned distribution of the data to the same distribution of
+//! Monte-Carlo weighted by the fitted intensity (see [`binned_chi2`]). The information criteria
+//! [`aic`] and [`bic`] and the [`likelihood_ratio_test`] compare [`FitResult`]s of different
+//! [`Model`](crate::amplitude::Model)s (for instance, different sets of partial waves) fit to
+//! the same data.
+//!
+//! The objective function of an [`ExtendedLogLikelihood`] is $`-2\ln\mathcal{L}`$ up to a
+//! constant which depends only on the data, so these criteria are only meaningful when comparing
+//! fits to the same data [`Dataset`].
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let chi2 = BinnedChiSquare::new(
+//!     vec![0.0, 1.0, 2.0],
+//!     &[0.5, 0.5, 1.5],
+//!     None,
+//!     &[0.5, 1.5],
+//!     Some(&[2.0, 1.0]),
+//! )?;
+//! assert_eq!(chi2.chi2, 0.0);
+//! assert_eq!(chi2.ndf, 2);
+//! # Ok(())
+//! # }
+//! ```
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    convert,
+    dataset::{Dataset, Event},
+    errors::RustitudeError,
+    fit::FitResult,
+    histogram::Histogram,
+    manager::ExtendedLogLikelihood,
+    Field,
+};
+
+/// A binned comparison of the data to weighted Monte-Carlo.
+///
+/// The statistic is
+///
+/// ```math
+/// \chi^2 = \sum_{b} \frac{(D_b - M_b)^2}{\sigma_{D,b}^2 + \sigma_{M,b}^2}
+/// ```
+///
+/// where $`D_b = \sum_{i \in b} w_i`$ and $`\sigma_{D,b}^2 = \sum_{i \in b} w_i^2`$ (and likewise
+/// for the Monte-Carlo). Bins in which both variances are zero are skipped, and the number of
+/// degrees of freedom is the number of remaining bins. The number of fitted parameters is not
+/// subtracted, since the fit is not performed on these bins.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct BinnedChiSquare<F: Field> {
+    /// The edges of the bins.
+    pub edges: Vec<F>,
+    /// The sum of data weights in each bin.
+    pub data: Vec<F>,
+    /// The sum of squared data weights in each bin.
+    pub data_variance: Vec<F>,
+    /// The sum of Monte-Carlo weights in each bin.
+    pub mc: Vec<F>,
+    /// The sum of squared Monte-Carlo weights in each bin.
+    pub mc_variance: Vec<F>,
+    /// The $`\chi^2`$ statistic.
+    pub chi2: F,
+    /// The number of degrees of freedom.
+    pub ndf: usize,
+}
+
+impl<F: Field> BinnedChiSquare<F> {
+    /// Create a new [`BinnedChiSquare`] from the values of a variable for the data and
+    /// Monte-Carlo with optional weights (the default weight is one).
+    ///
+    /// Each bin includes its lower edge and excludes its upper edge, and values outside of the
+    /// edges are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are fewer
+    /// than two edges, the edges are not strictly increasing, or the number of weights does not
+    /// match the number of values.
+    pub fn new(
+        edges: Vec<F>,
+        data_values: &[F],
+        data_weights: Option<&[F]>,
+        mc_values: &[F],
+        mc_weights: Option<&[F]>,
+    ) -> Result<Self, RustitudeError> {
+        let Histogram {
+            edges,
+            contents: data,
+            variances: data_variance,
+        } = Histogram::new(edges, data_values, data_weights)?;
+        let Histogram {
+            contents: mc,
+            variances: mc_variance,
+            ..
+        } = Histogram::new(edges.clone(), mc_values, mc_weights)?;
+        let mut chi2 = F::zero();
+        let mut ndf = 0;
+        for b in 0..data.len() {
+            let variance = data_variance[b] + mc_variance[b];
+            if variance > F::zero() {
+                chi2 += (data[b] - mc[b]).powi(2) / variance;
+                ndf += 1;
+            }
+        }
+        Ok(Self {
+            edges,
+            data,
+            data_variance,
+            mc,
+            mc_variance,
+            chi2,
+            ndf,
+        })
+    }
+
+    /// Returns $`\chi^2`$ divided by the number of degrees of freedom.
+    pub fn reduced(&self) -> F {
+        self.chi2 / convert!(self.ndf, F)
+    }
+
+    /// Returns the probability of a $`\chi^2`$ at least as large as the observed one (see
+    /// [`chi2_sf`]).
+    pub fn p_value(&self) -> F {
+        chi2_sf(self.chi2, self.ndf)
+    }
+}
+
+/// Compares the data to Monte-Carlo weighted by the fitted intensity in bins of a variable.
+///
+/// The data are those of the [`ExtendedLogLikelihood`], and the Monte-Carlo [`Dataset`] is
+/// weighted by [`ExtendedLogLikelihood::intensity`]. With accepted Monte-Carlo, the weighted
+/// Monte-Carlo is the fit prediction for the data.
+///
+/// # Errors
+///
+/// This function will return any error produced while calculating the intensity or see
+/// [`BinnedChiSquare::new`].
+pub fn binned_chi2<F: Field + 'static>(
+    ell: &ExtendedLogLikelihood<F>,
+    parameters: &[F],
+    dataset_mc: &Dataset<F>,
+    variable: impl Fn(&Event<F>) -> F + Sync,
+    edges: Vec<F>,
+) -> Result<BinnedChiSquare<F>, RustitudeError> {
+    let intensity = if ell.data_manager.model.contains_python_amplitudes {
+        ell.intensity(parameters, dataset_mc)?
+    } else {
+        ell.par_intensity(parameters, dataset_mc)?
+    };
+    let data = &ell.data_manager.dataset;
+    let data_values: Vec<F> = data.events.par_iter().map(&variable).collect();
+    let mc_values: Vec<F> = dataset_mc.events.par_iter().map(&variable).collect();
+    BinnedChiSquare::new(
+        edges,
+        &data_values,
+        Some(&data.weights()),
+        &mc_values,
+        Some(&intensity),
+    )
+}
+
+/// Computes the Akaike information criterion, $`\text{AIC} = -2\ln\mathcal{L} + 2k`$, of a
+/// [`FitResult`] with $`k`$ free parameters.
+pub fn aic<F: Field + 'static>(result: &FitResult<F>) -> F {
+    result.fx + convert!(2 * result.best.len(), F)
+}
+
+/// Computes the Bayesian information criterion, $`\text{BIC} = -2\ln\mathcal{L} + k\ln n`$, of
+/// a [`FitResult`] with $`k`$ free parameters and $`n`$ data events.
+pub fn bic<F: Field + 'static>(result: &FitResult<F>) -> F {
+    result.fx + convert!(result.best.len(), F) * convert!(result.data.n_events, F).ln()
+}
+
+/// The result of a [`likelihood_ratio_test`].
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct LikelihoodRatioTest<F: Field> {
+    /// The test statistic, $`\Lambda = -2\ln\mathcal{L}_0 + 2\ln\mathcal{L}_1`$.
+    pub statistic: F,
+    /// The difference in the number of free parameters.
+    pub ndf: usize,
+    /// The asymptotic $`p`$-value of the null hypothesis (see [`chi2_sf`]).
+    pub p_value: F,
+}
+
+/// Performs a likelihood-ratio test of a null [`FitResult`] against an alternative.
+///
+/// The [`Model`](crate::amplitude::Model) of the alternative must contain the null
+/// [`Model`](crate::amplitude::Model) (for instance, by adding partial waves).
+///
+/// By Wilks' theorem, the test statistic asymptotically follows a $`\chi^2`$ distribution with
+/// degrees of freedom equal to the number of additional free parameters. This does not hold if
+/// the null hypothesis lies on the boundary of the alternative, such as a wave whose magnitude
+/// is zero.
+///
+/// # Errors
+///
+/// This function will return a [`RustitudeError::InvalidParameterValue`] if the fits were not
+/// performed on the same data [`Dataset`] or the alternative does not have more free parameters
+/// than the null.
+pub fn likelihood_ratio_test<F: Field + 'static>(
+    null: &FitResult<F>,
+    alternative: &FitResult<F>,
+) -> Result<LikelihoodRatioTest<F>, RustitudeError> {
+    if null.data != alternative.data {
+        return Err(RustitudeError::InvalidParameterValue(
+            "fits must be performed on the same data".to_string(),
+        ));
+    }
+    if alternative.best.len() <= null.best.len() {
+        return Err(RustitudeError::InvalidParameterValue(format!(
+            "alternative must have more free parameters than the null ({} <= {})",
+            alternative.best.len(),
+            null.best.len()
+        )));
+    }
+    let statistic = null.fx - alternative.fx;
+    let ndf = alternative.best.len() - null.best.len();
+    Ok(LikelihoodRatioTest {
+        statistic,
+        ndf,
+        p_value: chi2_sf(statistic, ndf),
+    })
+}
+
+/// Computes the upper-tail probability of a $`\chi^2`$ distribution with `ndf` degrees of freedom.
+///
+/// This is $`Q(\nu/2, x/2)`$, where $`Q`$ is the regularized upper incomplete gamma function.
+pub fn chi2_sf<F: Field>(x: F, ndf: usize) -> F {
+    if x <= F::zero() || ndf == 0 {
+        return F::one();
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let a = ndf as f64 / 2.0;
+    let x: f64 = convert!(x, f64);
+    convert!(gamma_q(a, x / 2.0), F)
+}
+
+/// Computes the natural logarithm of the gamma function with the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+    let tmp = (x + 0.5).mul_add(-(x + 5.5).ln(), x + 5.5);
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .fold(1.000_000_000_190_015, |acc, (j, c)| {
+            #[allow(clippy::cast_precision_loss)]
+            let y = x + 1.0 + j as f64;
+            acc + c / y
+        });
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// Computes the regularized upper incomplete gamma function $`Q(a, x)`$ with a series expansion
+/// for $`x < a + 1`$ and a continued fraction otherwise.
+fn gamma_q(a: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 1000;
+    const EPSILON: f64 = 1e-15;
+    const TINY: f64 = 1e-300;
+    let prefactor = a.mul_add(x.ln(), -x - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut ap = a;
+        for _ in 0..MAX_ITERATIONS {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        (1.0 - sum * prefactor).max(0.0)
+    } else {
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..MAX_ITERATIONS {
+            #[allow(clippy::cast_precision_loss)]
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an.mul_add(d, b);
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = b + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+        prefactor * h
+    }
+}
@@ -0,0 +1,406 @@
+//! This module contains a [`DistributedLikelihood`] which evaluates an
+//! [`ExtendedLogLikelihood`] whose data and Monte-Carlo are split over several processes (or
+//! nodes).
+//!
+//! Every sum in $`-2\ln\mathcal{L}`$ (and in its gradient) is a sum over events, so each process
+//! (or "rank") only needs to hold a shard of each [`Dataset`](crate::dataset::Dataset) (see
+//! [`Dataset::shard`](crate::dataset::Dataset::shard)) and evaluate the partial sums over its own
+//! events. These are added over every rank and combined into the full likelihood.
+//!
+//! The transport is abstracted by the [`Communicator`] trait, which only needs a broadcast from
+//! rank `0` and an element-wise sum over every rank. With the `mpi` feature, it is implemented
+//! over MPI (through `rsmpi`) by `MpiCommunicator`, and a `DistributedManager` is a
+//! [`DistributedLikelihood`] which uses it. The minimizer only runs on rank `0`, where the
+//! [`DistributedLikelihood`] can be used like any other likelihood, while every other rank calls
+//! [`DistributedLikelihood::serve`] to answer its requests until rank `0` calls
+//! [`DistributedLikelihood::finish`].
+//!
+//! Explicit yields and [`RobustLoss`](crate::manager::RobustLoss)es cannot be split over ranks
+//! and are not supported.
+//!
+//! # Example:
+//!
+//! ```ignore
+//! let communicator = MpiCommunicator::initialize()?;
+//! let (rank, size) = (communicator.rank(), communicator.size());
+//! let data = Dataset::from_parquet("data.parquet", ReadMethod::Standard)?.shard(rank, size);
+//! let mc = Dataset::from_parquet("mc.parquet", ReadMethod::Standard)?.shard(rank, size);
+//! let ell = ExtendedLogLikelihood::new(Manager::new(&model, &data)?, Manager::new(&model, &mc)?);
+//! let distributed = DistributedLikelihood::new(ell, communicator)?;
+//! if distributed.is_root() {
+//!     let nll = distributed.evaluate(&parameters)?;
+//!     distributed.finish();
+//! } else {
+//!     distributed.serve()?;
+//! }
+//! ```
+#[cfg(feature = "ganesh")]
+use ganesh::prelude::{DVector, Function};
+#[cfg(feature = "mpi")]
+use mpi::{
+    collective::SystemOperation,
+    environment::Universe,
+    topology::SimpleCommunicator,
+    traits::{Communicator as _, CommunicatorCollectives, Equivalence, Root},
+    Threading,
+};
+
+use crate::{convert, errors::RustitudeError, manager::ExtendedLogLikelihood, Field};
+
+/// The collective operations used by a [`DistributedLikelihood`] to exchange values between
+/// ranks.
+///
+/// Both operations are collective: every rank must call them in the same order with buffers of
+/// the same length.
+pub trait Communicator<F: Field>: Sync + Send {
+    /// Returns the rank of this process, which is `0` on the root process.
+    fn rank(&self) -> usize;
+    /// Returns the number of ranks.
+    fn size(&self) -> usize;
+    /// Replaces `values` on every rank with the values on rank `0`.
+    fn broadcast(&self, values: &mut [F]);
+    /// Replaces `values` on every rank with their element-wise sum over every rank.
+    fn all_reduce_sum(&self, values: &mut [F]);
+}
+
+/// A [`Communicator`] with a single rank, for which every operation does nothing.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SingleProcess;
+
+impl<F: Field> Communicator<F> for SingleProcess {
+    fn rank(&self) -> usize {
+        0
+    }
+    fn size(&self) -> usize {
+        1
+    }
+    fn broadcast(&self, _values: &mut [F]) {}
+    fn all_reduce_sum(&self, _values: &mut [F]) {}
+}
+
+/// A [`Communicator`] over every process of an MPI job, which requires MPI to support
+/// [`Threading::Multiple`].
+#[cfg(feature = "mpi")]
+pub struct MpiCommunicator {
+    world: SimpleCommunicator,
+    universe: Option<Universe>,
+}
+
+// SAFETY: Both constructors check that MPI supports `Threading::Multiple`, in which MPI may be
+// called from any thread, including concurrently.
+#[cfg(feature = "mpi")]
+unsafe impl Send for MpiCommunicator {}
+#[cfg(feature = "mpi")]
+unsafe impl Sync for MpiCommunicator {}
+
+#[cfg(feature = "mpi")]
+impl MpiCommunicator {
+    /// Initializes MPI with [`Threading::Multiple`] and creates a [`MpiCommunicator`] over every
+    /// process. MPI is finalized when the [`MpiCommunicator`] is dropped.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::MpiError`] if MPI was already initialized or
+    /// does not support [`Threading::Multiple`].
+    pub fn initialize() -> Result<Self, RustitudeError> {
+        let (universe, threading) = mpi::initialize_with_threading(Threading::Multiple)
+            .ok_or_else(|| RustitudeError::MpiError("MPI was already initialized".to_string()))?;
+        Self::check_threading(threading)?;
+        Ok(Self {
+            world: universe.world(),
+            universe: Some(universe),
+        })
+    }
+
+    /// Creates a [`MpiCommunicator`] over every process after MPI was initialized elsewhere,
+    /// which is responsible for finalizing it.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::MpiError`] if MPI is not initialized or does
+    /// not support [`Threading::Multiple`].
+    pub fn world() -> Result<Self, RustitudeError> {
+        if !mpi::is_initialized() || mpi::is_finalized() {
+            return Err(RustitudeError::MpiError(
+                "MPI is not initialized".to_string(),
+            ));
+        }
+        Self::check_threading(mpi::environment::threading_support())?;
+        Ok(Self {
+            world: SimpleCommunicator::world(),
+            universe: None,
+        })
+    }
+
+    fn check_threading(threading: Threading) -> Result<(), RustitudeError> {
+        if threading == Threading::Multiple {
+            Ok(())
+        } else {
+            Err(RustitudeError::MpiError(format!(
+                "MPI provides {threading:?} threading, but Multiple is required"
+            )))
+        }
+    }
+
+    /// Returns the MPI environment if it was initialized by [`MpiCommunicator::initialize`].
+    pub const fn universe(&self) -> Option<&Universe> {
+        self.universe.as_ref()
+    }
+}
+
+#[cfg(feature = "mpi")]
+impl<F: Field + Equivalence> Communicator<F> for MpiCommunicator {
+    fn rank(&self) -> usize {
+        self.world.rank().unsigned_abs() as usize
+    }
+    fn size(&self) -> usize {
+        self.world.size().unsigned_abs() as usize
+    }
+    fn broadcast(&self, values: &mut [F]) {
+        self.world.process_at_rank(0).broadcast_into(values);
+    }
+    fn all_reduce_sum(&self, values: &mut [F]) {
+        let local = values.to_vec();
+        self.world
+            .all_reduce_into(&local[..], values, SystemOperation::sum());
+    }
+}
+
+/// A [`DistributedLikelihood`] whose ranks are the processes of an MPI job.
+#[cfg(feature = "mpi")]
+pub type DistributedManager<F> = DistributedLikelihood<F, MpiCommunicator>;
+
+/// The requests sent from rank `0` to the other ranks.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Request {
+    Finish,
+    Evaluate,
+    Gradient,
+}
+
+impl Request {
+    fn encode<F: Field>(self) -> F {
+        match self {
+            Self::Finish => F::zero(),
+            Self::Evaluate => F::one(),
+            Self::Gradient => convert!(2, F),
+        }
+    }
+
+    fn decode<F: Field>(value: F) -> Self {
+        if value == F::one() {
+            Self::Evaluate
+        } else if value == convert!(2, F) {
+            Self::Gradient
+        } else {
+            Self::Finish
+        }
+    }
+}
+
+/// An [`ExtendedLogLikelihood`] split over several ranks. See the
+/// [module-level documentation](crate::distributed) for more information.
+#[derive(Clone)]
+pub struct DistributedLikelihood<F: Field + 'static, C: Communicator<F>> {
+    ell: ExtendedLogLikelihood<F>,
+    communicator: C,
+}
+
+impl<F: Field, C: Communicator<F>> DistributedLikelihood<F, C> {
+    /// Create a new [`DistributedLikelihood`] from the [`ExtendedLogLikelihood`] over the shards
+    /// of data and Monte-Carlo held by this rank.
+    ///
+    /// Every rank must configure its [`ExtendedLogLikelihood`] with the same [`Model`] and
+    /// parameters.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the
+    /// [`ExtendedLogLikelihood`] has explicit yields or a
+    /// [`RobustLoss`](crate::manager::RobustLoss).
+    ///
+    /// [`Model`]: crate::amplitude::Model
+    pub fn new(ell: ExtendedLogLikelihood<F>, communicator: C) -> Result<Self, RustitudeError> {
+        ell.check_additive()?;
+        Ok(Self { ell, communicator })
+    }
+
+    /// Checks if this is the root rank, which runs the minimizer.
+    pub fn is_root(&self) -> bool {
+        self.communicator.rank() == 0
+    }
+
+    /// Returns the [`ExtendedLogLikelihood`] over the events held by this rank.
+    pub const fn local(&self) -> &ExtendedLogLikelihood<F> {
+        &self.ell
+    }
+
+    /// Get the number of free parameters.
+    pub fn get_n_free(&self) -> usize {
+        self.ell.get_n_free()
+    }
+
+    /// Get the initial values of the free parameters.
+    pub fn get_initial(&self) -> Vec<F> {
+        self.ell.get_initial()
+    }
+
+    /// Get the bounds of the free parameters.
+    pub fn get_bounds(&self) -> Vec<(F, F)> {
+        self.ell.get_bounds()
+    }
+
+    /// Checks if the local sums can use Rust parallelism.
+    const fn parallel(&self) -> bool {
+        !(self.ell.data_manager.model.contains_python_amplitudes
+            || self.ell.mc_manager.model.contains_python_amplitudes)
+    }
+
+    /// Sends a [`Request`] and the free parameters from rank `0` to every rank.
+    fn send(&self, request: Request, parameters: &[F]) {
+        let mut message = Vec::with_capacity(parameters.len() + 1);
+        message.push(request.encode());
+        message.extend_from_slice(parameters);
+        self.communicator.broadcast(&mut message);
+    }
+
+    /// Adds `partial` over every rank, along with a status word which counts the ranks whose
+    /// local computation failed. A failed rank contributes zeros, so that every rank takes part
+    /// in the reduction either way, and a `NaN` sum (for example, from a bad parameter point) is
+    /// returned as a value rather than mistaken for a failure.
+    fn reduce(
+        &self,
+        partial: Result<Vec<F>, RustitudeError>,
+        len: usize,
+    ) -> Result<Vec<F>, RustitudeError> {
+        let (mut values, error) = match partial {
+            Ok(values) => (values, None),
+            Err(err) => (vec![F::zero(); len], Some(err)),
+        };
+        values.push(if error.is_some() { F::one() } else { F::zero() });
+        self.communicator.all_reduce_sum(&mut values);
+        if let Some(err) = error {
+            return Err(err);
+        }
+        let failures = values.pop().unwrap_or_else(F::zero);
+        if failures > F::zero() {
+            return Err(RustitudeError::EvaluationError(format!(
+                "the evaluation failed on {failures} other rank(s)"
+            )));
+        }
+        Ok(values)
+    }
+
+    /// Evaluates the local partial sums, adds them over every rank, and combines them into
+    /// $`-2\ln\mathcal{L}`$.
+    #[allow(clippy::suboptimal_flops)]
+    fn evaluate_collective(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        let partial = self
+            .ell
+            .partial_sums(parameters, self.parallel())
+            .map(Vec::from);
+        let sums = self.reduce(partial, 4)?;
+        let (data_sum, n_data, n_mc, mc_sum) = (sums[0], sums[1], sums[2], sums[3]);
+        let ln_l = data_sum - (n_data / n_mc) * mc_sum;
+        Ok(convert!(-2, F) * ln_l + self.ell.data_manager.model.constraint_penalty(parameters)?)
+    }
+
+    /// Evaluates the local gradient sums, adds them over every rank, and combines them into the
+    /// gradient of $`-2\ln\mathcal{L}`$.
+    fn gradient_collective(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        let n = self.ell.data_manager.model.parameters.len();
+        let weights = |ell: &ExtendedLogLikelihood<F>| {
+            let n_data = ell
+                .data_manager
+                .dataset
+                .weights()
+                .iter()
+                .copied()
+                .sum::<F>();
+            let n_mc = ell.mc_manager.dataset.weights().iter().copied().sum::<F>();
+            [n_data, n_mc]
+        };
+        let partial = self
+            .ell
+            .partial_gradient_sums(parameters, self.parallel())
+            .map(|(data_gradient, mc_gradient)| {
+                let mut values = data_gradient;
+                values.extend(mc_gradient);
+                values.extend(weights(&self.ell));
+                values
+            });
+        let sums = self.reduce(partial, 2 * n + 2)?;
+        self.ell.combine_gradients(
+            parameters,
+            &sums[..n],
+            &sums[n..2 * n],
+            sums[2 * n] / sums[2 * n + 1],
+        )
+    }
+
+    /// Evaluate $`-2\ln\mathcal{L}`$ over every rank with the given free parameters. This must
+    /// only be called on rank `0`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the evaluation fails on any rank.
+    pub fn evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        self.send(Request::Evaluate, parameters);
+        self.evaluate_collective(parameters)
+    }
+
+    /// Evaluate the gradient of $`-2\ln\mathcal{L}`$ over every rank with respect to the free
+    /// parameters. This must only be called on rank `0`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the evaluation fails on any rank.
+    pub fn gradient(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        self.send(Request::Gradient, parameters);
+        self.gradient_collective(parameters)
+    }
+
+    /// Tells every other rank to return from [`DistributedLikelihood::serve`]. This must only be
+    /// called on rank `0`.
+    pub fn finish(&self) {
+        self.send(Request::Finish, &vec![F::zero(); self.get_n_free()]);
+    }
+
+    /// Answers the requests of rank `0` until it calls [`DistributedLikelihood::finish`]. This
+    /// must be called on every rank other than rank `0`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the first [`RustitudeError`] produced on this rank, after rank `0`
+    /// finishes. Failed evaluations are also reported to rank `0` as they happen.
+    pub fn serve(&self) -> Result<(), RustitudeError> {
+        let mut first_error = None;
+        let mut message = vec![F::zero(); self.get_n_free() + 1];
+        loop {
+            self.communicator.broadcast(&mut message);
+            let parameters = &message[1..];
+            let result = match Request::decode(message[0]) {
+                Request::Finish => break,
+                Request::Evaluate => self.evaluate_collective(parameters).map(|_| ()),
+                Request::Gradient => self.gradient_collective(parameters).map(|_| ()),
+            };
+            if let Err(err) = result {
+                first_error.get_or_insert(err);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(feature = "ganesh")]
+impl<F: Field + ganesh::core::Field, C: Communicator<F>> Function<F, (), RustitudeError>
+    for DistributedLikelihood<F, C>
+{
+    fn evaluate(&self, x: &DVector<F>, _args: Option<&()>) -> Result<F, RustitudeError> {
+        self.evaluate(x.as_slice())
+    }
+
+    fn gradient(&self, x: &DVector<F>, _args: Option<&()>) -> Result<DVector<F>, RustitudeError> {
+        Ok(DVector::from_vec(self.gradient(x.as_slice())?))
+    }
+}
@@ -0,0 +1,365 @@
+//! This module contains the [`Ensemble`] struct, a container for a weighted collection of samples
+//! of the free parameters of a [`Model`](crate::amplitude::Model).
+//!
+//! An [`Ensemble`] is the common output of any sampling-based procedure, such as refitting
+//! bootstrapped datasets (see [`Dataset::get_bootstrap_indices`](crate::dataset::Dataset::get_bootstrap_indices)),
+//! fitting from many starting points, or a Markov-chain Monte-Carlo run. It provides weighted
+//! summary statistics (means, covariances, quantiles, and highest-posterior-density intervals)
+//! and can be laid out per chain in the `(chain, draw)` shape expected by tools like `arviz`.
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let ensemble = Ensemble::new(
+//!     vec!["a".to_string()],
+//!     (1..=100).map(|i| vec![f64::from(i)]).collect(),
+//!     None,
+//! )?;
+//! assert_eq!(ensemble.quantile(0, 0.5)?, 50.0);
+//! assert_eq!(ensemble.hpd(0, 0.9)?, (1.0, 90.0));
+//! # Ok(())
+//! # }
+//! ```
+use serde::{Deserialize, Serialize};
+
+use crate::{convert, errors::RustitudeError, fit::FitResult, Field};
+
+/// A weighted collection of samples of a set of named parameters, optionally grouped into
+/// chains.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Ensemble<F: Field> {
+    /// The names of the parameters.
+    pub names: Vec<String>,
+    /// The samples, each of which holds one value per parameter.
+    pub samples: Vec<Vec<F>>,
+    /// The weight of each sample.
+    pub weights: Vec<F>,
+    /// The chain each sample belongs to.
+    pub chains: Vec<usize>,
+}
+
+impl<F: Field> Ensemble<F> {
+    /// Create a new [`Ensemble`] from the names of the parameters and a set of samples with
+    /// optional weights (the default weight is one). Every sample is assigned to chain `0`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are no
+    /// samples, any sample does not have one value per name, the number of weights does not match
+    /// the number of samples, any weight is negative, or the sum of weights is not positive.
+    pub fn new(
+        names: Vec<String>,
+        samples: Vec<Vec<F>>,
+        weights: Option<Vec<F>>,
+    ) -> Result<Self, RustitudeError> {
+        if samples.is_empty() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "an ensemble requires at least one sample".to_string(),
+            ));
+        }
+        if let Some(sample) = samples.iter().find(|s| s.len() != names.len()) {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "ensemble has {} parameters but a sample has {} values",
+                names.len(),
+                sample.len()
+            )));
+        }
+        let weights = weights.unwrap_or_else(|| vec![F::one(); samples.len()]);
+        if weights.len() != samples.len() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "ensemble has {} samples but {} weights",
+                samples.len(),
+                weights.len()
+            )));
+        }
+        if weights.iter().any(|w| !w.is_finite() || *w < F::zero())
+            || weights.iter().copied().sum::<F>() <= F::zero()
+        {
+            return Err(RustitudeError::InvalidParameterValue(
+                "ensemble weights must be finite, non-negative, and have a positive sum"
+                    .to_string(),
+            ));
+        }
+        let chains = vec![0; samples.len()];
+        Ok(Self {
+            names,
+            samples,
+            weights,
+            chains,
+        })
+    }
+
+    /// Assigns each sample to a chain.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the number of
+    /// chain labels does not match the number of samples.
+    pub fn with_chains(mut self, chains: Vec<usize>) -> Result<Self, RustitudeError> {
+        if chains.len() != self.samples.len() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "ensemble has {} samples but {} chain labels",
+                self.samples.len(),
+                chains.len()
+            )));
+        }
+        self.chains = chains;
+        Ok(self)
+    }
+
+    /// Create a new [`Ensemble`] from the best-fit values of a set of [`FitResult`]s (for
+    /// instance, fits to bootstrapped datasets or fits from different starting points), each
+    /// with unit weight. The parameters are named `"<amplitude>::<parameter>"`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are no
+    /// results or the results do not all have the same free parameters.
+    pub fn from_fit_results(results: &[FitResult<F>]) -> Result<Self, RustitudeError>
+    where
+        F: 'static,
+    {
+        let names: Vec<String> = results
+            .first()
+            .map(|result| {
+                result
+                    .free_parameters()
+                    .iter()
+                    .map(|p| format!("{}::{}", p.amplitude, p.name))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self::new(
+            names,
+            results.iter().map(|result| result.best.clone()).collect(),
+            None,
+        )
+    }
+
+    /// Returns the number of samples.
+    pub const fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Checks if the [`Ensemble`] has no samples.
+    pub const fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the number of parameters.
+    pub const fn n_parameters(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns the index of the parameter with the given name.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if no parameter has
+    /// the given name.
+    pub fn index(&self, name: &str) -> Result<usize, RustitudeError> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .ok_or_else(|| RustitudeError::ParameterNotFoundError(name.to_string()))
+    }
+
+    /// Returns the values of a single parameter across all samples.
+    pub fn column(&self, index: usize) -> Vec<F> {
+        self.samples.iter().map(|s| s[index]).collect()
+    }
+
+    /// Returns the effective number of samples, $`(\sum_i w_i)^2 / \sum_i w_i^2`$.
+    pub fn effective_size(&self) -> F {
+        self.weights.iter().copied().sum::<F>().powi(2)
+            / self.weights.iter().map(|w| w.powi(2)).sum::<F>()
+    }
+
+    /// Returns the weighted mean of each parameter.
+    pub fn mean(&self) -> Vec<F> {
+        let w_sum: F = self.weights.iter().copied().sum();
+        (0..self.n_parameters())
+            .map(|j| {
+                self.samples
+                    .iter()
+                    .zip(&self.weights)
+                    .map(|(s, w)| *w * s[j])
+                    .sum::<F>()
+                    / w_sum
+            })
+            .collect()
+    }
+
+    /// Returns the weighted covariance matrix of the parameters,
+    /// $`C_{jk} = \sum_i w_i (x_{ij} - \bar{x}_j)(x_{ik} - \bar{x}_k) / \sum_i w_i`$.
+    pub fn covariance(&self) -> Vec<Vec<F>> {
+        let w_sum: F = self.weights.iter().copied().sum();
+        let mean = self.mean();
+        (0..self.n_parameters())
+            .map(|j| {
+                (0..self.n_parameters())
+                    .map(|k| {
+                        self.samples
+                            .iter()
+                            .zip(&self.weights)
+                            .map(|(s, w)| *w * (s[j] - mean[j]) * (s[k] - mean[k]))
+                            .sum::<F>()
+                            / w_sum
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the weighted standard deviation of each parameter.
+    pub fn std(&self) -> Vec<F> {
+        self.covariance()
+            .iter()
+            .enumerate()
+            .map(|(j, row)| row[j].sqrt())
+            .collect()
+    }
+
+    /// Returns the weighted correlation matrix of the parameters (see
+    /// [`correlation`](crate::fit::correlation)).
+    pub fn correlation(&self) -> Vec<Vec<F>> {
+        crate::fit::correlation(&self.covariance())
+    }
+
+    /// Returns the values of a parameter sorted in ascending order, paired with the cumulative
+    /// fraction of the total weight up to and including each value.
+    fn sorted_cdf(&self, index: usize) -> Result<Vec<(F, F)>, RustitudeError> {
+        if index >= self.n_parameters() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "ensemble has {} parameters but index {} was requested",
+                self.n_parameters(),
+                index
+            )));
+        }
+        let mut values: Vec<(F, F)> = self
+            .samples
+            .iter()
+            .zip(&self.weights)
+            .map(|(s, w)| (s[index], *w))
+            .collect();
+        values.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        let w_sum: F = self.weights.iter().copied().sum();
+        let mut cumulative = F::zero();
+        Ok(values
+            .into_iter()
+            .map(|(x, w)| {
+                cumulative += w;
+                (x, cumulative / w_sum)
+            })
+            .collect())
+    }
+
+    /// Returns the weighted quantile `q` of a parameter, the smallest sampled value for which
+    /// the fraction of the total weight at or below it is at least `q`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the index is out
+    /// of range or `q` is not in $`[0, 1]`$.
+    pub fn quantile(&self, index: usize, q: F) -> Result<F, RustitudeError> {
+        if !(F::zero()..=F::one()).contains(&q) {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "quantile must be in [0, 1] (got {q})"
+            )));
+        }
+        let cdf = self.sorted_cdf(index)?;
+        Ok(cdf
+            .iter()
+            .find(|(_, c)| *c >= q)
+            .unwrap_or(&cdf[cdf.len() - 1])
+            .0)
+    }
+
+    /// Returns the weighted median of a parameter.
+    ///
+    /// # Errors
+    ///
+    /// See [`Ensemble::quantile`].
+    pub fn median(&self, index: usize) -> Result<F, RustitudeError> {
+        self.quantile(index, convert!(0.5, F))
+    }
+
+    /// Returns the highest-posterior-density interval of a parameter, the narrowest interval
+    /// between two sampled values which contains at least the given fraction of the total weight.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the index is out
+    /// of range or `probability` is not in $`(0, 1]`$.
+    pub fn hpd(&self, index: usize, probability: F) -> Result<(F, F), RustitudeError> {
+        if probability <= F::zero() || probability > F::one() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "HPD probability must be in (0, 1] (got {probability})"
+            )));
+        }
+        let cdf = self.sorted_cdf(index)?;
+        let mut best = (cdf[0].0, cdf[cdf.len() - 1].0);
+        let mut end = 0;
+        for start in 0..cdf.len() {
+            let below = if start == 0 {
+                F::zero()
+            } else {
+                cdf[start - 1].1
+            };
+            end = end.max(start);
+            while end < cdf.len() - 1 && cdf[end].1 - below < probability {
+                end += 1;
+            }
+            if cdf[end].1 - below < probability {
+                break;
+            }
+            if cdf[end].0 - cdf[start].0 < best.1 - best.0 {
+                best = (cdf[start].0, cdf[end].0);
+            }
+        }
+        Ok(best)
+    }
+
+    /// Returns the values of a parameter laid out as `(chain, draw)`, with one row per chain in
+    /// ascending order of chain label.
+    ///
+    /// This is the layout expected for the posterior group of an `arviz` `InferenceData`.
+    pub fn chain_draws(&self, index: usize) -> Vec<Vec<F>> {
+        let mut labels = self.chains.clone();
+        labels.sort_unstable();
+        labels.dedup();
+        labels
+            .iter()
+            .map(|label| {
+                self.samples
+                    .iter()
+                    .zip(&self.chains)
+                    .filter(|(_, c)| *c == label)
+                    .map(|(s, _)| s[index])
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Serializes the [`Ensemble`] to a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::SerializationError`] if serialization fails.
+    pub fn to_json(&self) -> Result<String, RustitudeError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Loads an [`Ensemble`] from a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::SerializationError`] if the string cannot be
+    /// parsed.
+    pub fn from_json(json: &str) -> Result<Self, RustitudeError> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
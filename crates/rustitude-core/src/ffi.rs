@@ -0,0 +1,314 @@
+//! This module contains a stable C ABI for [`Node`]s written in other compiled languages and a
+//! [`CNode`] adapter which turns them into ordinary [`Node`]s.
+//!
+//! Legacy amplitudes written in C, C++, or Fortran can be used without rewriting them in Rust by
+//! exposing a [`CNodeVTable`] of `extern "C"` functions. All values cross the boundary as
+//! `double`s regardless of the precision of the [`Model`](crate::amplitude::Model), and events
+//! are passed as a plain [`CEvent`] struct. The matching C declarations are:
+//!
+//! ```c
+//! #define RUSTITUDE_NODE_ABI_VERSION 1
+//!
+//! typedef struct { double e, px, py, pz; } RustitudeFourMomentum;
+//! typedef struct { double re, im; } RustitudeComplex;
+//! typedef struct {
+//!     size_t index;
+//!     double weight;
+//!     RustitudeFourMomentum beam_p4;
+//!     RustitudeFourMomentum recoil_p4;
+//!     const RustitudeFourMomentum *daughter_p4s;
+//!     size_t n_daughters;
+//!     double eps[3];
+//! } RustitudeEvent;
+//!
+//! typedef struct {
+//!     uint32_t abi_version;
+//!     size_t (*n_parameters)(const void *state);
+//!     const char *(*parameter_name)(const void *state, size_t index);
+//!     int32_t (*precalculate)(void *state, const RustitudeEvent *events, size_t n_events);
+//!     int32_t (*calculate)(const void *state, const double *parameters, size_t n_parameters,
+//!                          const RustitudeEvent *event, RustitudeComplex *result);
+//!     void *(*clone)(const void *state);
+//!     void (*drop)(void *state);
+//! } RustitudeNodeVTable;
+//! ```
+//!
+//! # Contract
+//!
+//! - Every function receives the opaque `state` pointer which was given to [`CNode::new`] (or
+//!   returned by `clone`). The state may be null if the [`Node`] needs none.
+//! - `precalculate` is optional (it may be null) and is called with exclusive access to the
+//!   state, once per [`Dataset`] the [`Node`] is loaded with. The events, including their
+//!   `daughter_p4s` arrays, are only valid for the duration of the call. An event's `index` is
+//!   its position in the [`Dataset`], so precalculated values can be stored by index.
+//! - `calculate` may be called concurrently from several threads with the same state and must
+//!   not modify it.
+//! - `precalculate` and `calculate` return zero on success. Any other value is reported as a
+//!   [`RustitudeError::EvaluationError`].
+//! - `parameter_name` returns a null-terminated string which must stay valid until the state is
+//!   dropped. The names are copied when the [`CNode`] is created.
+//! - `clone` returns an independent copy of the state (including any precalculated values), and
+//!   `drop` releases a state. Each [`Manager`](crate::manager::Manager) precalculates its own
+//!   copy, so the data and Monte-Carlo [`Dataset`]s never share a state.
+//!
+//! The [`NODE_ABI_VERSION`] is only incremented when the layout of these types changes.
+use std::ffi::{c_char, c_void, CStr};
+
+use num::Complex;
+
+use crate::{
+    amplitude::Node,
+    convert,
+    dataset::{Dataset, Event},
+    errors::RustitudeError,
+    four_momentum::FourMomentum,
+    Field,
+};
+
+/// The version of the C ABI described in the [module-level documentation](crate::ffi).
+pub const NODE_ABI_VERSION: u32 = 1;
+
+/// A [`FourMomentum`] as passed across the C ABI.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CFourMomentum {
+    /// The energy component.
+    pub e: f64,
+    /// The $`x`$-component of the momentum.
+    pub px: f64,
+    /// The $`y`$-component of the momentum.
+    pub py: f64,
+    /// The $`z`$-component of the momentum.
+    pub pz: f64,
+}
+
+impl<F: Field> From<&FourMomentum<F>> for CFourMomentum {
+    fn from(p4: &FourMomentum<F>) -> Self {
+        Self {
+            e: convert!(p4.e(), f64),
+            px: convert!(p4.px(), f64),
+            py: convert!(p4.py(), f64),
+            pz: convert!(p4.pz(), f64),
+        }
+    }
+}
+
+/// A complex number as passed across the C ABI.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CComplex {
+    /// The real part.
+    pub re: f64,
+    /// The imaginary part.
+    pub im: f64,
+}
+
+/// An [`Event`] as passed across the C ABI. The `daughter_p4s` pointer refers to an array of
+/// `n_daughters` [`CFourMomentum`]s which is only valid for the duration of a call.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct CEvent {
+    /// The index of the event within its [`Dataset`].
+    pub index: usize,
+    /// The weight of the event.
+    pub weight: f64,
+    /// The beam four-momentum.
+    pub beam_p4: CFourMomentum,
+    /// The recoil four-momentum.
+    pub recoil_p4: CFourMomentum,
+    /// A pointer to the four-momenta of the other final state particles.
+    pub daughter_p4s: *const CFourMomentum,
+    /// The number of other final state particles.
+    pub n_daughters: usize,
+    /// The polarization vector of the beam.
+    pub eps: [f64; 3],
+}
+
+impl CEvent {
+    /// Creates a [`CEvent`] from an [`Event`] and the converted four-momenta of its daughters,
+    /// which must outlive the [`CEvent`].
+    fn new<F: Field>(event: &Event<F>, daughter_p4s: &[CFourMomentum]) -> Self {
+        Self {
+            index: event.index,
+            weight: convert!(event.weight, f64),
+            beam_p4: CFourMomentum::from(&event.beam_p4),
+            recoil_p4: CFourMomentum::from(&event.recoil_p4),
+            daughter_p4s: daughter_p4s.as_ptr(),
+            n_daughters: daughter_p4s.len(),
+            eps: [
+                convert!(event.eps[0], f64),
+                convert!(event.eps[1], f64),
+                convert!(event.eps[2], f64),
+            ],
+        }
+    }
+}
+
+/// The table of `extern "C"` functions which implement a [`Node`] in another language. See the
+/// [module-level documentation](crate::ffi) for the contract each function must satisfy.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct CNodeVTable {
+    /// Must equal [`NODE_ABI_VERSION`].
+    pub abi_version: u32,
+    /// Returns the number of parameters of the [`Node`].
+    pub n_parameters: unsafe extern "C" fn(state: *const c_void) -> usize,
+    /// Returns the name of the parameter at `index` as a null-terminated string.
+    pub parameter_name: unsafe extern "C" fn(state: *const c_void, index: usize) -> *const c_char,
+    /// Precalculates values over an array of events (optional).
+    pub precalculate: Option<
+        unsafe extern "C" fn(state: *mut c_void, events: *const CEvent, n_events: usize) -> i32,
+    >,
+    /// Writes the value of the [`Node`] for the given parameters and event to `result`.
+    pub calculate: unsafe extern "C" fn(
+        state: *const c_void,
+        parameters: *const f64,
+        n_parameters: usize,
+        event: *const CEvent,
+        result: *mut CComplex,
+    ) -> i32,
+    /// Returns an independent copy of the state.
+    pub clone: unsafe extern "C" fn(state: *const c_void) -> *mut c_void,
+    /// Releases the state.
+    pub drop: unsafe extern "C" fn(state: *mut c_void),
+}
+
+/// A [`Node`] implemented by a [`CNodeVTable`] and an opaque state. See the [module-level
+/// documentation](crate::ffi) for more information.
+pub struct CNode {
+    vtable: CNodeVTable,
+    state: *mut c_void,
+    parameters: Vec<String>,
+}
+
+// SAFETY: The contract of `CNode::new` requires the foreign functions to be callable from any
+// thread and `calculate` to be safe to call concurrently, while `precalculate` is only reachable
+// through `&mut self`.
+unsafe impl Send for CNode {}
+unsafe impl Sync for CNode {}
+
+impl CNode {
+    /// Creates a new [`CNode`] from a [`CNodeVTable`] and a state, which the [`CNode`] owns and
+    /// releases with the `drop` function of the [`CNodeVTable`] (even if this method fails).
+    ///
+    /// # Safety
+    ///
+    /// Every function in the [`CNodeVTable`] must be valid for the given state and satisfy the
+    /// contract in the [module-level documentation](crate::ffi), and the state must not be used
+    /// elsewhere after this call.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the ABI version
+    /// does not match [`NODE_ABI_VERSION`] or if a parameter name is null or not valid UTF-8.
+    pub unsafe fn new(vtable: CNodeVTable, state: *mut c_void) -> Result<Self, RustitudeError> {
+        let mut node = Self {
+            vtable,
+            state,
+            parameters: Vec::new(),
+        };
+        if vtable.abi_version != NODE_ABI_VERSION {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "expected a node with ABI version {NODE_ABI_VERSION}, got {}",
+                vtable.abi_version
+            )));
+        }
+        let n_parameters = (vtable.n_parameters)(state);
+        node.parameters = (0..n_parameters)
+            .map(|i| {
+                let name = (vtable.parameter_name)(state, i);
+                if name.is_null() {
+                    return Err(RustitudeError::InvalidParameterValue(format!(
+                        "the name of parameter {i} is null"
+                    )));
+                }
+                CStr::from_ptr(name)
+                    .to_str()
+                    .map(str::to_string)
+                    .map_err(|err| RustitudeError::InvalidParameterValue(err.to_string()))
+            })
+            .collect::<Result<Vec<String>, RustitudeError>>()?;
+        Ok(node)
+    }
+}
+
+/// Converts a status code from a foreign function into a [`Result`].
+fn check(status: i32, function: &str) -> Result<(), RustitudeError> {
+    if status == 0 {
+        Ok(())
+    } else {
+        Err(RustitudeError::EvaluationError(format!(
+            "foreign node function {function} returned status {status}"
+        )))
+    }
+}
+
+impl Clone for CNode {
+    fn clone(&self) -> Self {
+        Self {
+            vtable: self.vtable,
+            // SAFETY: The state is valid for the vtable by the contract of `CNode::new`.
+            state: unsafe { (self.vtable.clone)(self.state) },
+            parameters: self.parameters.clone(),
+        }
+    }
+}
+
+impl Drop for CNode {
+    fn drop(&mut self) {
+        // SAFETY: The state is owned by this `CNode` and never used again.
+        unsafe { (self.vtable.drop)(self.state) }
+    }
+}
+
+impl<F: Field> Node<F> for CNode {
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        let Some(precalculate) = self.vtable.precalculate else {
+            return Ok(());
+        };
+        let daughters: Vec<Vec<CFourMomentum>> = dataset
+            .events
+            .iter()
+            .map(|event| event.daughter_p4s.iter().map(CFourMomentum::from).collect())
+            .collect();
+        let events: Vec<CEvent> = dataset
+            .events
+            .iter()
+            .zip(&daughters)
+            .map(|(event, daughter_p4s)| CEvent::new(event, daughter_p4s))
+            .collect();
+        // SAFETY: The events and their daughters outlive the call, and `&mut self` gives
+        // exclusive access to the state.
+        check(
+            unsafe { precalculate(self.state, events.as_ptr(), events.len()) },
+            "precalculate",
+        )
+    }
+
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let daughters: Vec<CFourMomentum> =
+            event.daughter_p4s.iter().map(CFourMomentum::from).collect();
+        let c_event = CEvent::new(event, &daughters);
+        let parameters: Vec<f64> = parameters.iter().map(|p| convert!(*p, f64)).collect();
+        let mut result = CComplex::default();
+        // SAFETY: Every pointer refers to a live local value, and `calculate` may be called
+        // concurrently by the contract of `CNode::new`.
+        check(
+            unsafe {
+                (self.vtable.calculate)(
+                    self.state,
+                    parameters.as_ptr(),
+                    parameters.len(),
+                    &c_event,
+                    &mut result,
+                )
+            },
+            "calculate",
+        )?;
+        Ok(Complex::new(convert!(result.re, F), convert!(result.im, F)))
+    }
+
+    fn parameters(&self) -> Vec<String> {
+        self.parameters.clone()
+    }
+}
@@ -0,0 +1,522 @@
+//! This module contains a [`FitResult`] type which records the outcome of a fit along with
+//! enough provenance to archive and reproduce it.
+//!
+//! Minimizers typically only report a vector of free parameter values, which loses the mapping
+//! back to named [`Parameter`]s as well as any fixed or constrained parameters. A [`FitResult`]
+//! stores the full parameter table of the [`Model`], the best-fit values and (optionally) their
+//! covariance, the expression trees of the [`Model`], and fingerprints of the [`Dataset`]s used
+//! in the fit, and can be saved to and loaded from JSON. The covariance matrix at the minimum can
+//! be estimated with [`ExtendedLogLikelihood::covariance`].
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! use rustitude_core::utils::generate_test_dataset_f64;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let dataset = generate_test_dataset_f64();
+//! let model = model!(scalar("a"));
+//! let manager = Manager::new(&model, &dataset)?;
+//! let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+//! let best = vec![1.0];
+//! let fx = ell.evaluate(&best)?;
+//! let result = FitResult::new(&ell, &best, fx, Some(vec![vec![0.01]]))?;
+//! assert_eq!(result.value("a", "value")?, 1.0);
+//! let reloaded: FitResult<f64> = FitResult::from_json(&result.to_json()?)?;
+//! assert_eq!(reloaded.errors(), Some(vec![0.1]));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Dataset`]: crate::dataset::Dataset
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    amplitude::{GaussianConstraint, Model, NormSqr, Parameter},
+    convert,
+    dataset::Dataset,
+    errors::RustitudeError,
+    manager::{ExtendedLogLikelihood, YIELD_AMPLITUDE},
+    serialization::{AmpTree, ModelSpec},
+    Field,
+};
+
+/// The signed `(lower, upper)` MINOS-style uncertainties of a parameter (see
+/// [`ExtendedLogLikelihood::minos`]). Either side is [`None`] if it could not be found.
+pub type MinosError<F> = (Option<F>, Option<F>);
+
+/// The fraction of the total intensity attributable to a group of
+/// [`Amplitude`](crate::amplitude::Amplitude)s (see [`ExtendedLogLikelihood::fit_fractions`]).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct FitFraction<F: Field> {
+    /// The name of the group (the name of the amplitude for a single amplitude).
+    pub name: String,
+    /// The names of the amplitudes in the group.
+    pub amplitudes: Vec<String>,
+    /// The fit fraction.
+    pub value: F,
+    /// The propagated uncertainty of the fit fraction, if a covariance matrix was given.
+    pub error: Option<F>,
+}
+
+/// A summary of a [`Dataset`](crate::dataset::Dataset) used in a fit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetInfo {
+    /// The number of events in the [`Dataset`](crate::dataset::Dataset).
+    pub n_events: usize,
+    /// A hexadecimal representation of
+    /// [`Dataset::fingerprint`](crate::dataset::Dataset::fingerprint).
+    pub fingerprint: String,
+}
+
+/// Returns the `(amplitude, parameter, index)` triple of every free [`Parameter`] of an
+/// [`ExtendedLogLikelihood`], including any yield parameters.
+fn index_triples<F: Field>(ell: &ExtendedLogLikelihood<F>) -> Vec<(String, String, usize)> {
+    ell.data_manager
+        .model
+        .parameters
+        .iter()
+        .cloned()
+        .chain(
+            ell.free_parameters()
+                .into_iter()
+                .filter(|p| p.amplitude == YIELD_AMPLITUDE),
+        )
+        .filter_map(|p| p.index.map(|i| (p.amplitude, p.name, i)))
+        .collect()
+}
+
+/// The result of a fit of an [`ExtendedLogLikelihood`], including provenance information.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct FitResult<F: Field> {
+    /// The version of `rustitude-core` which produced the result.
+    pub version: String,
+    /// The time the result was created, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The value of the objective function at the minimum.
+    pub fx: F,
+    /// The standard $`-2\ln\mathcal{L}`$ at the minimum, if the fit used a
+    /// [`RobustLoss`](crate::manager::RobustLoss) (see
+    /// [`ExtendedLogLikelihood::evaluate_standard`]).
+    #[serde(default)]
+    pub standard_fx: Option<F>,
+    /// The best-fit values of the free parameters, in the order of [`FitResult::free_parameters`].
+    /// Parameters with a [`Transform`](crate::amplitude::Transform) store their internal value
+    /// (see [`FitResult::value`]).
+    pub best: Vec<F>,
+    /// The covariance matrix of the free parameters, if it was computed.
+    pub covariance: Option<Vec<Vec<F>>>,
+    /// The signed `(lower, upper)` MINOS-style uncertainties of each free parameter, if they
+    /// were computed (see [`ExtendedLogLikelihood::minos`]).
+    #[serde(default)]
+    pub minos_errors: Option<Vec<MinosError<F>>>,
+    /// Every [`Parameter`] in the [`Model`], followed by any yield parameters (see
+    /// [`ExtendedLogLikelihood::add_signal_yield`]). Fixed parameters have no index, and
+    /// constrained parameters share an index.
+    pub parameters: Vec<Parameter<F>>,
+    /// The `(amplitude, parameter, index)` triple of every free [`Parameter`] (including any
+    /// yield parameters), where the index is the position of its value in [`FitResult::best`].
+    /// See [`FitResult::restore`].
+    #[serde(default)]
+    pub indices: Vec<(String, String, usize)>,
+    /// The [`GaussianConstraint`]s applied during the fit.
+    pub gaussian_constraints: Vec<GaussianConstraint<F>>,
+    /// Expression trees for each coherent sum in the [`Model`].
+    pub cohsums: Vec<AmpTree>,
+    /// The full [`ModelSpec`], if every [`Node`](crate::amplitude::Node) in the [`Model`] can be
+    /// serialized (see [`Model::to_spec`]).
+    pub model: Option<ModelSpec<F>>,
+    /// The names of the [`Amplitude`](crate::amplitude::Amplitude)s which were active during
+    /// the fit.
+    pub active_amplitudes: Vec<String>,
+    /// A summary of the data [`Dataset`](crate::dataset::Dataset).
+    pub data: DatasetInfo,
+    /// A summary of the Monte-Carlo [`Dataset`](crate::dataset::Dataset).
+    pub mc: DatasetInfo,
+}
+
+impl<F: Field + 'static> FitResult<F> {
+    /// Creates a new [`FitResult`] from the [`ExtendedLogLikelihood`] which was minimized, the
+    /// best-fit free parameter values, the minimum value of the objective function, and an
+    /// optional covariance matrix.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the number of
+    /// values or the shape of the covariance matrix does not match the number of free parameters,
+    /// or any error from [`ExtendedLogLikelihood::evaluate_standard`] if the
+    /// [`ExtendedLogLikelihood`] uses a [`RobustLoss`](crate::manager::RobustLoss).
+    pub fn new(
+        ell: &ExtendedLogLikelihood<F>,
+        best: &[F],
+        fx: F,
+        covariance: Option<Vec<Vec<F>>>,
+    ) -> Result<Self, RustitudeError> {
+        let model: &Model<F> = &ell.data_manager.model;
+        let n_free = ell.get_n_free();
+        if best.len() != n_free {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "expected {} free parameter values, got {}",
+                n_free,
+                best.len()
+            )));
+        }
+        if let Some(cov) = &covariance {
+            if cov.len() != n_free || cov.iter().any(|row| row.len() != n_free) {
+                return Err(RustitudeError::InvalidParameterValue(format!(
+                    "covariance matrix must have shape ({n_free}, {n_free})"
+                )));
+            }
+        }
+        let standard_fx = match ell.robust_loss {
+            Some(_) => Some(ell.evaluate_standard(best)?),
+            None => None,
+        };
+        let dataset_info = |dataset: &crate::dataset::Dataset<F>| DatasetInfo {
+            n_events: dataset.len(),
+            fingerprint: format!("{:016x}", dataset.fingerprint()),
+        };
+        Ok(Self {
+            indices: index_triples(ell),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+            fx,
+            standard_fx,
+            best: best.to_vec(),
+            covariance,
+            minos_errors: None,
+            parameters: model
+                .parameters
+                .iter()
+                .cloned()
+                .chain(
+                    ell.free_parameters()
+                        .into_iter()
+                        .filter(|p| p.amplitude == YIELD_AMPLITUDE),
+                )
+                .collect(),
+            gaussian_constraints: model.gaussian_constraints.clone(),
+            cohsums: model.cohsums.iter().map(NormSqr::to_amp_tree).collect(),
+            model: model.to_spec().ok(),
+            active_amplitudes: model
+                .amplitudes
+                .read()
+                .iter()
+                .filter(|amp| amp.active)
+                .map(|amp| amp.name.clone())
+                .collect(),
+            data: dataset_info(&ell.data_manager.dataset),
+            mc: dataset_info(&ell.mc_manager.dataset),
+        })
+    }
+
+    /// Returns one representative [`Parameter`] for each free parameter, in the order of
+    /// [`FitResult::best`].
+    pub fn free_parameters(&self) -> Vec<&Parameter<F>> {
+        let mut free: Vec<&Parameter<F>> = Vec::with_capacity(self.best.len());
+        for i in 0..self.best.len() {
+            if let Some(par) = self.parameters.iter().find(|p| p.index == Some(i)) {
+                free.push(par);
+            }
+        }
+        free
+    }
+
+    /// Returns the uncertainties of the free parameters (the square roots of the diagonal of
+    /// the covariance matrix), if the covariance matrix is available.
+    pub fn errors(&self) -> Option<Vec<F>> {
+        self.covariance.as_ref().map(|cov| {
+            cov.iter()
+                .enumerate()
+                .map(|(i, row)| row[i].sqrt())
+                .collect()
+        })
+    }
+
+    /// Returns the correlation matrix of the free parameters (see [`correlation`]), if the
+    /// covariance matrix is available.
+    pub fn correlation(&self) -> Option<Vec<Vec<F>>> {
+        self.covariance.as_deref().map(correlation)
+    }
+
+    /// Returns the best-fit physical value of a [`Parameter`] by name (see
+    /// [`Model::set_transform`]). Fixed parameters return their fixed value.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if the parameter
+    /// does not exist.
+    pub fn value(&self, amplitude: &str, parameter: &str) -> Result<F, RustitudeError> {
+        let par = self.get_parameter(amplitude, parameter)?;
+        Ok(par.value(&self.best))
+    }
+
+    /// Returns the uncertainty of a [`Parameter`] by name. Fixed parameters and results without
+    /// a covariance matrix return [`None`].
+    ///
+    /// The covariance matrix is given in the internal space of any
+    /// [`Transform`](crate::amplitude::Transform), so the uncertainty of a transformed parameter
+    /// is propagated linearly, $`\sigma_x = |dx/du|\,\sigma_u`$.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if the parameter
+    /// does not exist.
+    pub fn error(&self, amplitude: &str, parameter: &str) -> Result<Option<F>, RustitudeError> {
+        let par = self.get_parameter(amplitude, parameter)?;
+        Ok(par.index.and_then(|i| {
+            self.covariance
+                .as_ref()
+                .map(|cov| cov[i][i].sqrt() * par.derivative(self.best[i]).abs())
+        }))
+    }
+
+    /// Returns the signed `(lower, upper)` MINOS-style uncertainties of a [`Parameter`] by name.
+    /// Fixed parameters and results without MINOS uncertainties return [`None`].
+    ///
+    /// The endpoints of the interval of a transformed parameter are mapped to physical values,
+    /// so the uncertainties are asymmetric in general.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if the parameter
+    /// does not exist.
+    pub fn minos_error(
+        &self,
+        amplitude: &str,
+        parameter: &str,
+    ) -> Result<Option<MinosError<F>>, RustitudeError> {
+        let par = self.get_parameter(amplitude, parameter)?;
+        Ok(par.index.and_then(|i| {
+            self.minos_errors.as_ref().map(|errors| {
+                let best = self.best[i];
+                let (lower, upper) = errors[i];
+                let shift = |error: F| par.physical(best + error) - par.physical(best);
+                let (lower, upper) = (lower.map(shift), upper.map(shift));
+                if par.derivative(best) < F::zero() {
+                    (upper, lower)
+                } else {
+                    (lower, upper)
+                }
+            })
+        }))
+    }
+
+    fn get_parameter(
+        &self,
+        amplitude: &str,
+        parameter: &str,
+    ) -> Result<&Parameter<F>, RustitudeError> {
+        self.parameters
+            .iter()
+            .find(|p| p.amplitude == amplitude && p.name == parameter)
+            .ok_or_else(|| {
+                RustitudeError::ParameterNotFoundError(format!("{amplitude}::{parameter}"))
+            })
+    }
+
+    /// Computes the fit fractions at the best-fit parameters, propagating the stored covariance
+    /// matrix if it exists. See [`ExtendedLogLikelihood::fit_fractions`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if a group contains an unknown amplitude or
+    /// if the intensity calculation fails.
+    pub fn fit_fractions(
+        &self,
+        ell: &ExtendedLogLikelihood<F>,
+        dataset_mc: &Dataset<F>,
+        groups: &[(&str, Vec<&str>)],
+    ) -> Result<Vec<FitFraction<F>>, RustitudeError> {
+        ell.par_fit_fractions(&self.best, dataset_mc, groups, self.covariance.as_deref())
+    }
+
+    /// Starts another fit from the best-fit values of this [`FitResult`].
+    ///
+    /// Each free parameter of `ell` (including any yield parameters) has its initial value set to
+    /// the best-fit value of the parameter with the same amplitude and parameter names in this
+    /// result, and the new initial values are returned. The precision of `ell` may differ from
+    /// the precision of the fit, so a fast [`f32`] fit can be refined by a shorter [`f64`] fit
+    /// which starts close to the minimum. Bounds, fixed parameters, and constraints of `ell` are
+    /// left unchanged. The normalization integrals of the first fit can be reused in the same
+    /// way with [`ExtendedLogLikelihood::warm_start_normalization`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if a free parameter
+    /// of `ell` does not exist in this result. In this case, `ell` is not modified.
+    pub fn warm_start<G: Field>(
+        &self,
+        ell: &mut ExtendedLogLikelihood<G>,
+    ) -> Result<Vec<G>, RustitudeError> {
+        let values = ell
+            .free_parameters()
+            .into_iter()
+            .map(|p| {
+                self.value(&p.amplitude, &p.name)
+                    .map(|value| (p.amplitude, p.name, convert!(value, G)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for (amplitude, parameter, value) in values {
+            ell.set_initial(&amplitude, &parameter, value)?;
+        }
+        Ok(ell.get_initial())
+    }
+
+    /// Restores the best-fit values of this result as the initial values of the free parameters
+    /// of `ell`, which must have exactly the same free parameters at the same indices.
+    ///
+    /// Unlike [`FitResult::warm_start`], which matches parameters by name, this method requires
+    /// the `(amplitude, parameter, index)` triples of `ell` to match [`FitResult::indices`], so
+    /// a result loaded into a modified [`Model`] (for example, one with an added, removed, fixed,
+    /// or constrained parameter) fails rather than silently permuting values. The precision of
+    /// `ell` may differ from the precision of the fit. The new initial values are returned, and
+    /// are identical to [`FitResult::best`] in order (up to rounding if any parameter has a
+    /// [`Transform`](crate::amplitude::Transform)).
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if a free parameter
+    /// exists in only one of `ell` and this result, or a
+    /// [`RustitudeError::InvalidParameterValue`] if a free parameter has a different index. In
+    /// either case, `ell` is not modified.
+    pub fn restore<G: Field>(
+        &self,
+        ell: &mut ExtendedLogLikelihood<G>,
+    ) -> Result<Vec<G>, RustitudeError> {
+        let mut expected: Vec<(String, String, usize)> = if self.indices.is_empty() {
+            // Results saved before the triples were stored still have indexed parameters
+            self.parameters
+                .iter()
+                .filter_map(|p| p.index.map(|i| (p.amplitude.clone(), p.name.clone(), i)))
+                .collect()
+        } else {
+            self.indices.clone()
+        };
+        let mut found = index_triples(ell);
+        expected.sort();
+        found.sort();
+        for (amplitude, parameter, index) in &found {
+            match expected
+                .iter()
+                .find(|(a, p, _)| a == amplitude && p == parameter)
+            {
+                None => {
+                    return Err(RustitudeError::ParameterNotFoundError(format!(
+                        "{amplitude}::{parameter} is free but does not exist in the fit result"
+                    )))
+                }
+                Some((_, _, i)) if i != index => {
+                    return Err(RustitudeError::InvalidParameterValue(format!(
+                        "{amplitude}::{parameter} has index {index} but index {i} in the fit result"
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+        if let Some((amplitude, parameter, _)) = expected.iter().find(|(a, p, _)| {
+            !found
+                .iter()
+                .any(|(amplitude, parameter, _)| a == amplitude && p == parameter)
+        }) {
+            return Err(RustitudeError::ParameterNotFoundError(format!(
+                "{amplitude}::{parameter} is free in the fit result but not in the likelihood"
+            )));
+        }
+        if self.best.len() != ell.get_n_free() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "expected {} free parameter values, got {}",
+                ell.get_n_free(),
+                self.best.len()
+            )));
+        }
+        let values = found
+            .into_iter()
+            .map(|(amplitude, parameter, _)| {
+                self.value(&amplitude, &parameter)
+                    .map(|value| (amplitude, parameter, convert!(value, G)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for (amplitude, parameter, value) in values {
+            ell.set_initial(&amplitude, &parameter, value)?;
+        }
+        Ok(ell.get_initial())
+    }
+
+    /// Serializes the [`FitResult`] to a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::SerializationError`] if serialization fails.
+    pub fn to_json(&self) -> Result<String, RustitudeError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Loads a [`FitResult`] from a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::SerializationError`] if the string cannot be
+    /// parsed.
+    pub fn from_json(json: &str) -> Result<Self, RustitudeError> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// Computes the correlation matrix $`\rho_{ij} = C_{ij} / \sqrt{C_{ii} C_{jj}}`$ from a
+/// covariance matrix $`C`$.
+pub fn correlation<F: Field>(covariance: &[Vec<F>]) -> Vec<Vec<F>> {
+    covariance
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(j, c)| *c / (covariance[i][i] * covariance[j][j]).sqrt())
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(feature = "ganesh")]
+impl<F: Field + ganesh::core::Field + 'static> FitResult<F> {
+    /// Computes the MINOS-style uncertainties of every free parameter of the [`Model`] with
+    /// [`ExtendedLogLikelihood::minos`] and stores them in [`FitResult::minos_errors`].
+    ///
+    /// The initial step for each parameter is its parabolic uncertainty scaled to `delta`, taken
+    /// from [`FitResult::covariance`] or, if that is missing, from
+    /// [`ExtendedLogLikelihood::covariance`]. Yield parameters are skipped.
+    ///
+    /// # Errors
+    ///
+    /// This method will return any error produced during the minimizations.
+    pub fn compute_minos_errors(
+        &mut self,
+        ell: &ExtendedLogLikelihood<F>,
+        delta: F,
+        max_steps: usize,
+    ) -> Result<(), RustitudeError> {
+        let covariance = match &self.covariance {
+            Some(covariance) => Some(covariance.clone()),
+            None => ell.covariance(&self.best).ok(),
+        };
+        let n_model = ell.data_manager.model.get_n_free();
+        let errors = (0..self.best.len())
+            .map(|i| {
+                if i >= n_model {
+                    return Ok((None, None));
+                }
+                let step = covariance
+                    .as_ref()
+                    .map_or_else(F::nan, |cov| (delta * cov[i][i]).sqrt());
+                ell.minos(&self.best, self.fx, i, delta, step, max_steps)
+            })
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        self.minos_errors = Some(errors);
+        Ok(())
+    }
+}
@@ -4,8 +4,15 @@
 //! manipulations for physics four-vectors representing momentum coordinates. In particular,
 //! this struct has the same layout as a `[Field; 4]` with components identified as
 //! $`(E, p_x, p_y, p_z)`$.
-use crate::Field;
-use nalgebra::{Matrix4, Vector3, Vector4};
+//!
+//! Boosts use the passive convention of [`FourMomentum::boost_along`]: boosting by
+//! $`\vec{\beta}`$ gives the components of a [`FourMomentum`] in a frame which moves with
+//! velocity $`\vec{\beta}`$. Rotations are available in both conventions, where an active
+//! rotation turns the vector itself and a passive rotation turns the coordinate axes, giving the
+//! components of the same vector in the rotated frame. The rotation matrices are built with
+//! [`rotation_matrix`] and [`euler_rotation_matrix`].
+use crate::{convert, Field, UnitVector};
+use nalgebra::{Matrix3, Matrix4, Vector3, Vector4};
 use std::{
     fmt::Display,
     ops::{Add, Sub},
@@ -53,6 +60,16 @@ impl<F: Field> FourMomentum<F> {
         Self(Vector4::new(e, px, py, pz))
     }
 
+    /// Converts the [`FourMomentum`] to another floating-point precision.
+    pub fn convert<G: Field>(&self) -> FourMomentum<G> {
+        FourMomentum::new(
+            convert!(self.e(), G),
+            convert!(self.px(), G),
+            convert!(self.py(), G),
+            convert!(self.pz(), G),
+        )
+    }
+
     /// Returns the energy of the given [`FourMomentum`].
     #[allow(clippy::missing_const_for_fn)]
     pub fn e(&self) -> F {
@@ -138,6 +155,84 @@ impl<F: Field> FourMomentum<F> {
         let m_boost = other.boost_matrix();
         (m_boost * self.0).into()
     }
+
+    /// Boosts an instance of [`FourMomentum`] into the rest frame of another [`FourMomentum`].
+    ///
+    /// Alias for [`FourMomentum::boost_along`].
+    ///
+    /// # Examples
+    /// ```
+    /// use rustitude_core::prelude::*;
+    ///
+    /// let vec_a = FourMomentum::new(20.0, 1.0, -3.2, 4.0);
+    /// let vec_b = FourMomentum::new(5.0, 0.1, 0.2, 0.3);
+    /// let vec_b_rest = vec_b.boost_to_rest_frame_of(&vec_b);
+    /// assert!(f64::abs(vec_b_rest.e() - vec_b.m()) < 1e-7);
+    /// assert!(f64::abs(vec_a.boost_to_rest_frame_of(&vec_b).m() - vec_a.m()) < 1e-7);
+    /// ```
+    pub fn boost_to_rest_frame_of(&self, other: &Self) -> Self {
+        self.boost_along(other)
+    }
+
+    /// Boosts an instance of [`FourMomentum`] into a frame which moves with velocity
+    /// $`\vec{\beta}`$ (see [`FourMomentum::beta_boost_matrix`]).
+    ///
+    /// # Examples
+    /// ```
+    /// use rustitude_core::prelude::*;
+    ///
+    /// let vec_a = FourMomentum::new(20.0, 1.0, -3.2, 4.0);
+    /// let beta = Vector3::new(0.1, -0.4, 0.3);
+    /// let vec_b = vec_a.boost(&beta).boost(&-beta);
+    /// assert!(f64::abs(vec_b.e() - vec_a.e()) < 1e-7);
+    /// assert!(f64::abs(vec_b.px() - vec_a.px()) < 1e-7);
+    /// ```
+    pub fn boost(&self, beta: &Vector3<F>) -> Self {
+        (Self::beta_boost_matrix(beta) * self.0).into()
+    }
+
+    /// Rotates an instance of [`FourMomentum`] by a $`3\times 3`$ rotation matrix, leaving the
+    /// energy unchanged.
+    pub fn rotate(&self, rotation: &Matrix3<F>) -> Self {
+        let p = rotation * self.momentum();
+        Self::new(self.e(), p.x, p.y, p.z)
+    }
+
+    /// Actively rotates the 3-momentum of an instance of [`FourMomentum`] by `angle` around
+    /// `axis` (see [`rotation_matrix`]).
+    ///
+    /// # Examples
+    /// ```
+    /// use rustitude_core::prelude::*;
+    ///
+    /// let vec_a = FourMomentum::new(2.0, 1.0, 0.0, 0.0);
+    /// let vec_b = vec_a.rotate_axis_angle(&Vector3::z(), std::f64::consts::FRAC_PI_2);
+    /// assert!(f64::abs(vec_b.px()) < 1e-7);
+    /// assert!(f64::abs(vec_b.py() - 1.0) < 1e-7);
+    /// ```
+    pub fn rotate_axis_angle(&self, axis: &Vector3<F>, angle: F) -> Self {
+        self.rotate(&rotation_matrix(axis, angle))
+    }
+
+    /// Actively rotates the 3-momentum of an instance of [`FourMomentum`] by the Euler angles
+    /// $`(\alpha, \beta, \gamma)`$ in the $`zyz`$ convention (see [`euler_rotation_matrix`]).
+    pub fn rotate_euler(&self, alpha: F, beta: F, gamma: F) -> Self {
+        self.rotate(&euler_rotation_matrix(alpha, beta, gamma))
+    }
+
+    /// Passively rotates an instance of [`FourMomentum`], returning its components along
+    /// coordinate axes which are rotated by `angle` around `axis`. This is the inverse of
+    /// [`FourMomentum::rotate_axis_angle`].
+    pub fn rotate_frame_axis_angle(&self, axis: &Vector3<F>, angle: F) -> Self {
+        self.rotate(&rotation_matrix(axis, angle).transpose())
+    }
+
+    /// Passively rotates an instance of [`FourMomentum`], returning its components along
+    /// coordinate axes which are rotated by the Euler angles $`(\alpha, \beta, \gamma)`$ in the
+    /// $`zyz`$ convention. This is the inverse of [`FourMomentum::rotate_euler`].
+    pub fn rotate_frame_euler(&self, alpha: F, beta: F, gamma: F) -> Self {
+        self.rotate(&euler_rotation_matrix(alpha, beta, gamma).transpose())
+    }
     /// Extract the 3-momentum as a [`nalgebra::Vector3<Field>`]
     ///
     /// # Examples
@@ -204,8 +299,18 @@ impl<F: Field> FourMomentum<F> {
     /// where
     /// $`\vec{\beta} = \frac{\vec{p}}{E}`$ and $`\gamma = \frac{1}{\sqrt{1 - \vec{\beta}^2}}`$.
     pub fn boost_matrix(&self) -> Matrix4<F> {
-        let b = self.beta3();
-        let b2 = b.dot(&b);
+        Self::beta_boost_matrix(&self.beta3())
+    }
+
+    /// Construct the Lorentz boost matrix $`\mathbf{\Lambda}`$ (see
+    /// [`FourMomentum::boost_matrix`]) for an arbitrary velocity $`\vec{\beta}`$. This is the
+    /// identity when $`\vec{\beta} = 0`$.
+    pub fn beta_boost_matrix(beta: &Vector3<F>) -> Matrix4<F> {
+        let b = beta;
+        let b2 = b.dot(b);
+        if b2 == F::zero() {
+            return Matrix4::identity();
+        }
         let g = F::one() / F::sqrt(F::one() - b2);
         Matrix4::new(
             g,
@@ -228,6 +333,39 @@ impl<F: Field> FourMomentum<F> {
     }
 }
 
+/// Construct the matrix of an active rotation by `angle` (counterclockwise) around `axis`, which
+/// does not need to be normalized, with Rodrigues' formula
+///
+/// ```math
+/// \mathbf{R} = \cos\theta\,\mathbf{I} + \sin\theta\,[\hat{n}]_\times + (1 - \cos\theta)\,\hat{n}\hat{n}^T
+/// ```
+pub fn rotation_matrix<F: Field + 'static>(axis: &Vector3<F>, angle: F) -> Matrix3<F> {
+    let n = axis.unit();
+    let (s, c) = angle.sin_cos();
+    let t = F::one() - c;
+    Matrix3::new(
+        c + t * n.x * n.x,
+        t * n.x * n.y - s * n.z,
+        t * n.x * n.z + s * n.y,
+        t * n.y * n.x + s * n.z,
+        c + t * n.y * n.y,
+        t * n.y * n.z - s * n.x,
+        t * n.z * n.x - s * n.y,
+        t * n.z * n.y + s * n.x,
+        c + t * n.z * n.z,
+    )
+}
+
+/// Construct the matrix of an active rotation by Euler angles in the $`zyz`$ convention.
+///
+/// The rotation is $`\mathbf{R} = \mathbf{R}_z(\alpha)\mathbf{R}_y(\beta)\mathbf{R}_z(\gamma)`$,
+/// which is the convention of the Wigner $`D`$-matrices.
+pub fn euler_rotation_matrix<F: Field + 'static>(alpha: F, beta: F, gamma: F) -> Matrix3<F> {
+    rotation_matrix(&Vector3::z(), alpha)
+        * rotation_matrix(&Vector3::y(), beta)
+        * rotation_matrix(&Vector3::z(), gamma)
+}
+
 impl<F: Field> From<FourMomentum<F>> for Vector4<F> {
     fn from(val: FourMomentum<F>) -> Self {
         Self::new(val.e(), val.px(), val.py(), val.pz())
@@ -327,6 +465,48 @@ mod tests {
         assert_is_close!(d.pz(), 44.4, f64);
     }
 
+    #[test]
+    fn test_boosts() {
+        let a = FourMomentum::new(20.0, 1.0, -3.2, 4.0);
+        let b = FourMomentum::new(5.0, 0.1, 0.2, 0.3);
+        let c = a.boost_to_rest_frame_of(&b);
+        let d = a.boost(&b.beta3());
+        assert_is_close!(c.e(), d.e(), f64);
+        assert_is_close!(c.px(), d.px(), f64);
+        assert_is_close!(c.m(), a.m(), f64);
+        let e = a.boost(&Vector3::zeros());
+        assert_is_close!(e.e(), a.e(), f64);
+        assert_is_close!(e.pz(), a.pz(), f64);
+    }
+
+    #[test]
+    fn test_rotations() {
+        let a = FourMomentum::new(20.0, 1.0, -3.2, 4.0);
+        let axis = Vector3::new(1.0, 2.0, -0.5);
+        let b = a.rotate_axis_angle(&axis, 0.7);
+        assert_is_close!(b.e(), a.e(), f64);
+        assert_is_close!(b.m(), a.m(), f64);
+        assert_is_close!(b.momentum().dot(&axis), a.momentum().dot(&axis), f64);
+        let c = b.rotate_frame_axis_angle(&axis, 0.7);
+        assert_is_close!(c.px(), a.px(), f64);
+        assert_is_close!(c.py(), a.py(), f64);
+        assert_is_close!(c.pz(), a.pz(), f64);
+        let d = a.rotate_euler(0.3, 0.0, 0.4);
+        let e = a.rotate_axis_angle(&Vector3::z(), 0.7);
+        assert_is_close!(d.px(), e.px(), f64);
+        assert_is_close!(d.py(), e.py(), f64);
+        let f = a
+            .rotate_euler(0.3, 1.1, -0.4)
+            .rotate_frame_euler(0.3, 1.1, -0.4);
+        assert_is_close!(f.px(), a.px(), f64);
+        assert_is_close!(f.py(), a.py(), f64);
+        assert_is_close!(f.pz(), a.pz(), f64);
+        // The Euler rotation carries the z-axis to the direction (beta, alpha)
+        let z = FourMomentum::new(1.0, 0.0, 0.0, 1.0).rotate_euler(0.3, 1.1, -0.4);
+        assert_is_close!(z.theta(), 1.1, f64);
+        assert_is_close!(z.phi(), 0.3, f64);
+    }
+
     #[test]
     fn test_ops() {
         let a = FourMomentum::new(0.1, 0.2, 0.3, 0.4);
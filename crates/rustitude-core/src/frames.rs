@@ -0,0 +1,170 @@
+//! This module contains utilities to compute the standard reference frames of a two-step
+//! production and decay and the decay angles measured in them.
+//!
+//! Each [`Frame`] defines a set of [`Axes`] in the rest frame of a resonance, built from the
+//! beam and recoil momenta of an [`Event`]:
+//!
+//! - [`Frame::Helicity`]: $`\hat{z}`$ is opposite to the recoil, $`\hat{y}`$ is along
+//!   $`\vec{p}_{\text{beam}} \times \hat{z}`$, and $`\hat{x} = \hat{y} \times \hat{z}`$.
+//! - [`Frame::GottfriedJackson`]: $`\hat{z}`$ is along the beam, $`\hat{y}`$ is along
+//!   $`\vec{p}_{\text{beam}}^{\text{lab}} \times (-\vec{p}_{\text{recoil}})`$, and
+//!   $`\hat{x} = \hat{y} \times \hat{z}`$.
+//!
+//! where every momentum is boosted to the rest frame of the resonance unless noted otherwise.
+//! The [`Axes`] of the production plane itself are given by [`Axes::production`], and the angle
+//! $`\Phi`$ between the polarization of the beam and the production plane by
+//! [`polarization_angle`].
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::frames::{self, Frame};
+//! use rustitude_core::prelude::*;
+//! use rustitude_core::utils::generate_test_event_f64;
+//!
+//! let event = generate_test_event_f64();
+//! let resonance = event.daughter_p4s[0] + event.daughter_p4s[1];
+//! let (costheta, phi) =
+//!     frames::decay_angles(Frame::Helicity, &event, &resonance, &event.daughter_p4s[0]);
+//! assert!(costheta.abs() <= 1.0);
+//! assert!(phi.abs() <= std::f64::consts::PI);
+//! ```
+use std::{fmt::Display, str::FromStr};
+
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    dataset::Event, errors::RustitudeError, four_momentum::FourMomentum, Field, UnitVector,
+};
+
+/// The reference frame in the rest frame of a resonance in which decay angles are measured.
+/// See the [module-level documentation](crate::frames) for the definition of each [`Frame`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Frame {
+    /// The helicity frame.
+    #[default]
+    Helicity,
+    /// The Gottfried-Jackson frame.
+    GottfriedJackson,
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Helicity => write!(f, "helicity"),
+            Self::GottfriedJackson => write!(f, "gottfried-jackson"),
+        }
+    }
+}
+
+/// The error returned when a [`Frame`] cannot be parsed from a string.
+#[derive(Debug, PartialEq, Eq, Error)]
+#[error("Unknown frame: {0}")]
+pub struct ParseFrameError(String);
+
+impl From<ParseFrameError> for RustitudeError {
+    fn from(value: ParseFrameError) -> Self {
+        Self::ParseError(value.to_string())
+    }
+}
+
+impl FromStr for Frame {
+    type Err = ParseFrameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "helicity" | "hx" => Ok(Self::Helicity),
+            "gottfried-jackson" | "gj" => Ok(Self::GottfriedJackson),
+            _ => Err(ParseFrameError(s.to_string())),
+        }
+    }
+}
+
+/// A right-handed set of unit axes.
+#[derive(Clone, Copy, Debug)]
+pub struct Axes<F: Field + 'static> {
+    /// The $`\hat{x}`$ axis.
+    pub x: Vector3<F>,
+    /// The $`\hat{y}`$ axis.
+    pub y: Vector3<F>,
+    /// The $`\hat{z}`$ axis.
+    pub z: Vector3<F>,
+}
+
+impl<F: Field> Axes<F> {
+    /// Computes the [`Axes`] of a [`Frame`] in the rest frame of `resonance`.
+    pub fn new(frame: Frame, event: &Event<F>, resonance: &FourMomentum<F>) -> Self {
+        let beam_res_vec = event.beam_p4.boost_along(resonance).momentum();
+        let recoil_res_vec = event.recoil_p4.boost_along(resonance).momentum();
+        let (y, z) = match frame {
+            Frame::Helicity => {
+                let z = -recoil_res_vec.unit();
+                (beam_res_vec.cross(&z).unit(), z)
+            }
+            Frame::GottfriedJackson => (
+                event.beam_p4.momentum().cross(&(-recoil_res_vec)).unit(),
+                beam_res_vec.unit(),
+            ),
+        };
+        Self {
+            x: y.cross(&z),
+            y,
+            z,
+        }
+    }
+
+    /// Computes the [`Axes`] of the production plane in the lab frame, where $`\hat{z}`$ is
+    /// along the beam, $`\hat{y}`$ is along $`\vec{p}_{\text{beam}} \times
+    /// (-\vec{p}_{\text{recoil}})`$, and $`\hat{x} = \hat{y} \times \hat{z}`$.
+    pub fn production(event: &Event<F>) -> Self {
+        let z = event.beam_p4.direction();
+        let y = event
+            .beam_p4
+            .momentum()
+            .cross(&(-event.recoil_p4.momentum()))
+            .unit();
+        Self {
+            x: y.cross(&z),
+            y,
+            z,
+        }
+    }
+
+    /// Returns the components of `vector` along each of the [`Axes`].
+    pub fn components(&self, vector: &Vector3<F>) -> Vector3<F> {
+        Vector3::new(
+            vector.dot(&self.x),
+            vector.dot(&self.y),
+            vector.dot(&self.z),
+        )
+    }
+
+    /// Returns $`(\cos\theta, \phi)`$ of `vector`, measured from $`\hat{z}`$ and $`\hat{x}`$
+    /// respectively.
+    pub fn angles(&self, vector: &Vector3<F>) -> (F, F) {
+        let v = self.components(vector);
+        (v.z / F::sqrt(v.dot(&v)), F::atan2(v.y, v.x))
+    }
+}
+
+/// Computes the decay angles $`(\cos\theta, \phi)`$ of `daughter` (given in the lab frame) in a
+/// [`Frame`] in the rest frame of `resonance`.
+pub fn decay_angles<F: Field>(
+    frame: Frame,
+    event: &Event<F>,
+    resonance: &FourMomentum<F>,
+    daughter: &FourMomentum<F>,
+) -> (F, F) {
+    Axes::new(frame, event, resonance).angles(&daughter.boost_along(resonance).momentum())
+}
+
+/// Computes the angle $`\Phi`$ between the polarization vector of the beam and the plane
+/// normal to `y`, the $`\hat{y}`$ axis of a set of [`Axes`].
+pub fn polarization_angle<F: Field>(event: &Event<F>, y: &Vector3<F>) -> F {
+    F::atan2(
+        y.dot(&event.eps),
+        event.beam_p4.direction().dot(&event.eps.cross(y)),
+    )
+}
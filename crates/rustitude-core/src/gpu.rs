@@ -0,0 +1,296 @@
+//! This module contains an experimental CUDA backend for [`LinearLikelihood`]s, enabled by the
+//! `cuda` feature.
+//!
+//! A [`GpuLikelihood`] copies the data amplitude matrix of a [`LinearLikelihood`] to the device
+//! once. Each evaluation then only uploads the production coefficients, and the coherent sums,
+//! the logarithm of the intensity, and the weighted sum over data events are computed by a
+//! kernel, leaving a single partial sum per thread block to be added on the host. The
+//! Monte-Carlo normalization is already reduced to the (small) quadratic form of the
+//! [`NormalizationIntegrals`](crate::linear::NormalizationIntegrals), so it stays on the host.
+//!
+//! This supports every [`Model`](crate::amplitude::Model) a [`LinearLikelihood`] does, i.e.
+//! event-independent production coefficients (such as scalars) multiplying precalculated,
+//! parameter-free event amplitudes. [`IntensityFloor`](crate::manager::IntensityFloor)s are not
+//! supported on the device.
+//!
+//! The kernel is compiled with NVRTC when the [`GpuLikelihood`] is created and the CUDA
+//! libraries are loaded at runtime, so the crate builds without a CUDA toolkit, but evaluation
+//! requires a CUDA 12.2 (or newer) driver.
+//!
+//! # Example:
+//!
+//! ```ignore
+//! let linear = LinearLikelihood::new(&ell)?;
+//! let gpu = GpuLikelihood::new(linear, 0)?;
+//! let nll = gpu.evaluate(&parameters)?;
+//! ```
+use std::sync::Arc;
+
+use cudarc::{
+    driver::{CudaDevice, CudaSlice, DeviceRepr, LaunchAsync, LaunchConfig, ValidAsZeroBits},
+    nvrtc::compile_ptx,
+};
+#[cfg(feature = "ganesh")]
+use ganesh::prelude::{DVector, Function};
+
+use crate::{convert, errors::RustitudeError, linear::LinearLikelihood, Field};
+
+/// The number of threads in each block of the likelihood kernel (a power of two).
+const BLOCK_SIZE: u32 = 256;
+
+/// The likelihood kernel, written for a floating point type `T` and logarithm `LOG` which are
+/// defined when it is compiled.
+///
+/// Each thread computes $`w_e \ln \sum_s |\sum_k c_k A_k(e)|^2`$ for one event and each block
+/// reduces the values of its threads in shared memory.
+const KERNEL: &str = r#"
+extern "C" __global__ void ln_likelihood(
+    const T* data,
+    const T* coefficients,
+    const unsigned int* offsets,
+    const T* weights,
+    unsigned int n_events,
+    unsigned int n_terms,
+    unsigned int n_sums,
+    T* partial
+) {
+    extern __shared__ unsigned char shared_bytes[];
+    T* shared = (T*)shared_bytes;
+    unsigned int e = blockIdx.x * blockDim.x + threadIdx.x;
+    T value = 0;
+    if (e < n_events) {
+        const T* row = data + 2 * (size_t)e * n_terms;
+        T intensity = 0;
+        for (unsigned int s = 0; s < n_sums; s++) {
+            T re = 0;
+            T im = 0;
+            for (unsigned int k = offsets[s]; k < offsets[s + 1]; k++) {
+                T c_re = coefficients[2 * k];
+                T c_im = coefficients[2 * k + 1];
+                T a_re = row[2 * k];
+                T a_im = row[2 * k + 1];
+                re += c_re * a_re - c_im * a_im;
+                im += c_re * a_im + c_im * a_re;
+            }
+            intensity += re * re + im * im;
+        }
+        value = weights[e] * LOG(intensity);
+    }
+    shared[threadIdx.x] = value;
+    __syncthreads();
+    for (unsigned int stride = blockDim.x / 2; stride > 0; stride >>= 1) {
+        if (threadIdx.x < stride) {
+            shared[threadIdx.x] += shared[threadIdx.x + stride];
+        }
+        __syncthreads();
+    }
+    if (threadIdx.x == 0) {
+        partial[blockIdx.x] = shared[0];
+    }
+}
+"#;
+
+/// A [`Field`] which can be stored on a CUDA device.
+pub trait GpuField: Field + DeviceRepr + ValidAsZeroBits + Unpin {
+    /// The name of the type in CUDA C.
+    const C_TYPE: &'static str;
+    /// The name of the natural logarithm for the type in CUDA C.
+    const LOG: &'static str;
+}
+impl GpuField for f64 {
+    const C_TYPE: &'static str = "double";
+    const LOG: &'static str = "log";
+}
+impl GpuField for f32 {
+    const C_TYPE: &'static str = "float";
+    const LOG: &'static str = "logf";
+}
+
+/// A [`LinearLikelihood`] whose data term is evaluated on a CUDA device. See the
+/// [module-level documentation](crate::gpu) for more information.
+pub struct GpuLikelihood<F: GpuField + 'static> {
+    linear: LinearLikelihood<F>,
+    device: Arc<CudaDevice>,
+    /// The data amplitude matrix, one row of interleaved real and imaginary parts per event.
+    data: CudaSlice<F>,
+    /// The position of the first term of each coherent sum in a row, followed by the number of
+    /// terms.
+    offsets: CudaSlice<u32>,
+    weights: CudaSlice<F>,
+    n_events: u32,
+    n_terms: u32,
+    n_sums: u32,
+}
+
+impl<F: GpuField> GpuLikelihood<F> {
+    /// Copies a [`LinearLikelihood`] to the CUDA device with the given ordinal and compiles the
+    /// likelihood kernel.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the
+    /// [`LinearLikelihood`] has an [`IntensityFloor`](crate::manager::IntensityFloor) or is too
+    /// large to index with 32-bit integers, or a [`RustitudeError::CudaError`] if the CUDA
+    /// driver library cannot be loaded, the device cannot be opened, the kernel cannot be compiled, or
+    /// the data cannot be copied.
+    pub fn new(linear: LinearLikelihood<F>, ordinal: usize) -> Result<Self, RustitudeError> {
+        if linear.intensity_floor.is_some() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "a GpuLikelihood does not support intensity floors".to_string(),
+            ));
+        }
+        let too_large = |_| {
+            RustitudeError::InvalidParameterValue(
+                "the LinearLikelihood is too large for the GPU backend".to_string(),
+            )
+        };
+        let n_events = u32::try_from(linear.data_weights.len()).map_err(too_large)?;
+        let mut offsets = vec![0];
+        for sum in &linear.data {
+            let n = sum.n_columns();
+            offsets.push(offsets[offsets.len() - 1] + u32::try_from(n).map_err(too_large)?);
+        }
+        let n_terms = offsets[offsets.len() - 1];
+        let n_sums = u32::try_from(linear.data.len()).map_err(too_large)?;
+        let mut data = Vec::with_capacity(2 * n_events as usize * n_terms as usize);
+        for e in 0..n_events as usize {
+            for sum in &linear.data {
+                for m in 0..sum.n_columns() {
+                    let value = sum.get(e, m);
+                    data.push(convert!(value.re, F));
+                    data.push(convert!(value.im, F));
+                }
+            }
+        }
+        let source = format!("#define T {}\n#define LOG {}\n{KERNEL}", F::C_TYPE, F::LOG);
+        // cudarc panics rather than returning an error if the driver library is missing, so check
+        // for it first. SAFETY: this loads the same library cudarc loads when opening the device.
+        if !["cuda", "nvcuda"].into_iter().any(|name| unsafe {
+            libloading::Library::new(libloading::library_filename(name)).is_ok()
+        }) {
+            return Err(RustitudeError::CudaError(
+                "the CUDA driver library could not be loaded".to_string(),
+            ));
+        }
+        let device = CudaDevice::new(ordinal)?;
+        device.load_ptx(compile_ptx(source)?, "rustitude", &["ln_likelihood"])?;
+        Ok(Self {
+            data: device.htod_copy(data)?,
+            offsets: device.htod_copy(offsets)?,
+            weights: device.htod_sync_copy(&linear.data_weights)?,
+            linear,
+            device,
+            n_events,
+            n_terms,
+            n_sums,
+        })
+    }
+
+    /// Returns the [`LinearLikelihood`] evaluated by the [`GpuLikelihood`].
+    pub const fn linear(&self) -> &LinearLikelihood<F> {
+        &self.linear
+    }
+
+    /// Get the number of free parameters.
+    pub fn get_n_free(&self) -> usize {
+        self.linear.get_n_free()
+    }
+
+    /// Get the initial values of the free parameters.
+    pub fn get_initial(&self) -> Vec<F> {
+        self.linear.get_initial()
+    }
+
+    /// Get the bounds of the free parameters.
+    pub fn get_bounds(&self) -> Vec<(F, F)> {
+        self.linear.get_bounds()
+    }
+
+    /// Evaluate $`-2\ln\mathcal{L}`$ with the given free parameters. This matches
+    /// [`LinearLikelihood::evaluate`] up to rounding.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if a production coefficient or a constraint
+    /// cannot be evaluated, or a [`RustitudeError::CudaError`] if the kernel fails.
+    pub fn evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        let coefficients = self
+            .linear
+            .normalization
+            .coefficients(&self.linear.model, parameters)?;
+        let mc_sum = self.linear.normalization.quadratic_form(&coefficients);
+        let flat: Vec<F> = coefficients
+            .iter()
+            .flatten()
+            .flat_map(|c| [c.re, c.im])
+            .collect();
+        let n_blocks = self.n_events.div_ceil(BLOCK_SIZE).max(1);
+        let device_coefficients = self.device.htod_copy(flat)?;
+        let mut partial = self.device.alloc_zeros::<F>(n_blocks as usize)?;
+        let kernel = self
+            .device
+            .get_func("rustitude", "ln_likelihood")
+            .ok_or_else(|| {
+                RustitudeError::CudaError("the likelihood kernel is not loaded".to_string())
+            })?;
+        let config = LaunchConfig {
+            grid_dim: (n_blocks, 1, 1),
+            block_dim: (BLOCK_SIZE, 1, 1),
+            shared_mem_bytes: BLOCK_SIZE * std::mem::size_of::<F>() as u32,
+        };
+        // SAFETY: the argument types and order match the kernel signature, each row of `data`
+        // holds `n_terms` complex values, and `partial` holds one value per block.
+        unsafe {
+            kernel.launch(
+                config,
+                (
+                    &self.data,
+                    &device_coefficients,
+                    &self.offsets,
+                    &self.weights,
+                    self.n_events,
+                    self.n_terms,
+                    self.n_sums,
+                    &mut partial,
+                ),
+            )
+        }?;
+        let data_sum: F = self.device.dtoh_sync_copy(&partial)?.into_iter().sum();
+        let ln_l = data_sum - (self.linear.n_data / self.linear.normalization.n_mc()) * mc_sum;
+        Ok(convert!(-2, F) * ln_l + self.linear.model.constraint_penalty(parameters)?)
+    }
+
+    /// Evaluate the gradient of $`-2\ln\mathcal{L}`$ with respect to the free parameters by
+    /// central finite differences, which costs two evaluations per free parameter.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if any evaluation fails (see
+    /// [`GpuLikelihood::evaluate`]).
+    pub fn gradient(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        let mut x = parameters.to_vec();
+        (0..x.len())
+            .map(|i| {
+                let h = F::epsilon().cbrt() * F::max(F::one(), x[i].abs());
+                let x_i = x[i];
+                x[i] = x_i + h;
+                let f_plus = self.evaluate(&x)?;
+                x[i] = x_i - h;
+                let f_minus = self.evaluate(&x)?;
+                x[i] = x_i;
+                Ok((f_plus - f_minus) / (convert!(2, F) * h))
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "ganesh")]
+impl<F: GpuField + ganesh::core::Field> Function<F, (), RustitudeError> for GpuLikelihood<F> {
+    fn evaluate(&self, x: &DVector<F>, _args: Option<&()>) -> Result<F, RustitudeError> {
+        self.evaluate(x.as_slice())
+    }
+
+    fn gradient(&self, x: &DVector<F>, _args: Option<&()>) -> Result<DVector<F>, RustitudeError> {
+        Ok(DVector::from_vec(self.gradient(x.as_slice())?))
+    }
+}
@@ -0,0 +1,167 @@
+//! This module contains a weighted one-dimensional [`Histogram`] with sum-of-squared-weights
+//! errors.
+//!
+//! A [`Histogram`] can bin any per-event quantity, weighted either by the weights of a
+//! [`Dataset`] or by arbitrary per-event values such as the intensities returned by
+//! [`ExtendedLogLikelihood::intensity`](crate::manager::ExtendedLogLikelihood::intensity), which
+//! gives the model prediction to compare to a histogram of the data.
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let hist = Histogram::new(
+//!     vec![0.0, 1.0, 2.0],
+//!     &[0.5, 0.5, 1.5, 3.0],
+//!     Some(&[2.0, 1.0, 1.0, 1.0]),
+//! )?;
+//! assert_eq!(hist.contents, vec![3.0, 1.0]);
+//! assert_eq!(hist.variances, vec![5.0, 1.0]);
+//! assert_eq!(hist.errors(), vec![5.0f64.sqrt(), 1.0]);
+//! # Ok(())
+//! # }
+//! ```
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    convert,
+    dataset::{Dataset, Event},
+    errors::RustitudeError,
+    Field,
+};
+
+/// A weighted histogram of a single variable.
+///
+/// Each bin holds the sum of weights $`\sum_{i \in b} w_i`$ and the sum of squared weights
+/// $`\sum_{i \in b} w_i^2`$, whose square root is the statistical error on the bin. Each bin
+/// includes its lower edge and excludes its upper edge, and values outside of the edges (or NaN
+/// values) are ignored.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Histogram<F: Field> {
+    /// The edges of the bins.
+    pub edges: Vec<F>,
+    /// The sum of weights in each bin.
+    pub contents: Vec<F>,
+    /// The sum of squared weights in each bin.
+    pub variances: Vec<F>,
+}
+
+impl<F: Field> Histogram<F> {
+    /// Bins the given values with optional weights (the default weight is one).
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are fewer
+    /// than two edges, the edges are not strictly increasing, or the number of weights does not
+    /// match the number of values.
+    pub fn new(edges: Vec<F>, values: &[F], weights: Option<&[F]>) -> Result<Self, RustitudeError> {
+        if edges.len() < 2 || edges.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(RustitudeError::InvalidParameterValue(
+                "bin edges must contain at least two strictly increasing values".to_string(),
+            ));
+        }
+        if let Some(weights) = weights {
+            if weights.len() != values.len() {
+                return Err(RustitudeError::InvalidParameterValue(format!(
+                    "expected {} weights, got {}",
+                    values.len(),
+                    weights.len()
+                )));
+            }
+        }
+        let n_bins = edges.len() - 1;
+        let mut contents = vec![F::zero(); n_bins];
+        let mut variances = vec![F::zero(); n_bins];
+        for (i, value) in values.iter().enumerate() {
+            if *value < edges[0] || *value >= edges[n_bins] || value.is_nan() {
+                continue;
+            }
+            let index = edges.partition_point(|edge| edge <= value) - 1;
+            let weight = weights.map_or_else(F::one, |w| w[i]);
+            contents[index] += weight;
+            variances[index] += weight * weight;
+        }
+        Ok(Self {
+            edges,
+            contents,
+            variances,
+        })
+    }
+
+    /// Bins the given values into `n_bins` bins of equal width spanning `range`. See
+    /// [`Histogram::new`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are no bins,
+    /// the lower end of the range is not below the upper end, or the number of weights does not
+    /// match the number of values.
+    pub fn uniform(
+        n_bins: usize,
+        range: (F, F),
+        values: &[F],
+        weights: Option<&[F]>,
+    ) -> Result<Self, RustitudeError> {
+        let width = (range.1 - range.0) / convert!(n_bins, F);
+        let edges = (0..=n_bins)
+            .map(|i| {
+                if i == n_bins {
+                    range.1
+                } else {
+                    range.0 + convert!(i, F) * width
+                }
+            })
+            .collect();
+        Self::new(edges, values, weights)
+    }
+
+    /// Bins the value of `variable` for each [`Event`] in the [`Dataset`], weighted by the
+    /// given per-event weights or by the weights of the [`Event`]s if none are given.
+    ///
+    /// To plot a fit, the data are usually binned with their own weights, and the Monte-Carlo is
+    /// binned with the weights returned by
+    /// [`ExtendedLogLikelihood::intensity`](crate::manager::ExtendedLogLikelihood::intensity).
+    ///
+    /// # Errors
+    ///
+    /// See [`Histogram::new`].
+    pub fn from_dataset(
+        edges: Vec<F>,
+        dataset: &Dataset<F>,
+        variable: impl Fn(&Event<F>) -> F + Sync,
+        weights: Option<&[F]>,
+    ) -> Result<Self, RustitudeError> {
+        let values: Vec<F> = dataset.events.par_iter().map(&variable).collect();
+        match weights {
+            Some(weights) => Self::new(edges, &values, Some(weights)),
+            None => Self::new(edges, &values, Some(&dataset.weights())),
+        }
+    }
+
+    /// Returns the number of bins.
+    pub const fn n_bins(&self) -> usize {
+        self.contents.len()
+    }
+
+    /// Returns the statistical error on each bin, the square root of the sum of squared weights.
+    pub fn errors(&self) -> Vec<F> {
+        self.variances.iter().map(|v| v.sqrt()).collect()
+    }
+
+    /// Returns the center of each bin.
+    pub fn centers(&self) -> Vec<F> {
+        self.edges
+            .windows(2)
+            .map(|w| (w[0] + w[1]) / convert!(2, F))
+            .collect()
+    }
+
+    /// Returns the sum of weights over every bin (values outside of the edges are not
+    /// included).
+    pub fn total(&self) -> F {
+        self.contents.iter().copied().sum()
+    }
+}
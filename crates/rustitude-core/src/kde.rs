@@ -0,0 +1,245 @@
+//! This module contains a weighted, multidimensional Gaussian kernel density estimate ([`Kde`])
+//! which can be built from the [`Event`]s of a [`Dataset`] and evaluated at any point or
+//! [`Event`].
+//!
+//! A [`Kde`] is the backing machinery of the KDE form of a
+//! [`Template`](crate::amplitude::Template), and can also be used directly as a smooth
+//! representation of a distribution such as the detector acceptance.
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! use rustitude_core::utils::generate_test_dataset_f64;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let dataset = generate_test_dataset_f64();
+//! let kde = Kde::from_dataset(
+//!     &dataset,
+//!     |e: &Event<f64>| vec![(e.daughter_p4s[0] + e.daughter_p4s[1]).m()],
+//!     &Bandwidth::Scott,
+//! )?;
+//! let density = kde.evaluate_dataset(&dataset, |e: &Event<f64>| {
+//!     vec![(e.daughter_p4s[0] + e.daughter_p4s[1]).m()]
+//! })?;
+//! assert!(density.iter().all(|d| *d > 0.0));
+//! # Ok(())
+//! # }
+//! ```
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    convert,
+    dataset::{Dataset, Event},
+    errors::RustitudeError,
+    Field,
+};
+
+/// A rule used to choose the bandwidth of each axis of a [`Kde`].
+///
+/// The rules of thumb use the weighted standard deviation $`\sigma_j`$ of each axis and the
+/// effective number of points $`n = (\sum_i w_i)^2 / \sum_i w_i^2`$ in $`d`$ dimensions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum Bandwidth<F: Field> {
+    /// Scott's rule, $`h_j = \sigma_j n^{-1/(d+4)}`$.
+    Scott,
+    /// Silverman's rule, $`h_j = \sigma_j \left(\frac{4}{(d+2)n}\right)^{1/(d+4)}`$.
+    Silverman,
+    /// A fixed bandwidth along each axis.
+    Fixed(Vec<F>),
+}
+
+impl<F: Field> Bandwidth<F> {
+    /// Computes the bandwidth along each axis for the given points and weights.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are no
+    /// points, the points do not all have the same dimension, the number of weights does not
+    /// match the number of points, the sum of weights is not positive, or any resulting bandwidth
+    /// is not positive (for instance, if every point has the same value along some axis).
+    pub fn select(&self, points: &[Vec<F>], weights: &[F]) -> Result<Vec<F>, RustitudeError> {
+        let dimension = points.first().map_or(0, Vec::len);
+        if dimension == 0 || points.iter().any(|p| p.len() != dimension) {
+            return Err(RustitudeError::InvalidParameterValue(
+                "KDE requires at least one point and every point must have the same (nonzero) dimension"
+                    .to_string(),
+            ));
+        }
+        if weights.len() != points.len() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "KDE has {} points but {} weights",
+                points.len(),
+                weights.len()
+            )));
+        }
+        let w_sum: F = weights.iter().copied().sum();
+        if w_sum <= F::zero() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "KDE weights must have a positive sum".to_string(),
+            ));
+        }
+        let bandwidths = match self {
+            Self::Fixed(bandwidths) => {
+                if bandwidths.len() != dimension {
+                    return Err(RustitudeError::InvalidParameterValue(format!(
+                        "KDE has {} dimensions but {} bandwidths",
+                        dimension,
+                        bandwidths.len()
+                    )));
+                }
+                bandwidths.clone()
+            }
+            Self::Scott | Self::Silverman => {
+                let n_eff = w_sum.powi(2) / weights.iter().map(|w| w.powi(2)).sum::<F>();
+                let d: F = convert!(dimension, F);
+                let exponent = F::one() / (d + convert!(4, F));
+                let factor = match self {
+                    Self::Scott => n_eff.powf(-exponent),
+                    _ => (convert!(4, F) / ((d + convert!(2, F)) * n_eff)).powf(exponent),
+                };
+                (0..dimension)
+                    .map(|axis| {
+                        let mean = points
+                            .iter()
+                            .zip(weights)
+                            .map(|(p, w)| *w * p[axis])
+                            .sum::<F>()
+                            / w_sum;
+                        let variance = points
+                            .iter()
+                            .zip(weights)
+                            .map(|(p, w)| *w * (p[axis] - mean).powi(2))
+                            .sum::<F>()
+                            / w_sum;
+                        variance.max(F::zero()).sqrt() * factor
+                    })
+                    .collect()
+            }
+        };
+        if bandwidths.iter().any(|h| !h.is_finite() || *h <= F::zero()) {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "KDE bandwidths must be positive (got {bandwidths:?})"
+            )));
+        }
+        Ok(bandwidths)
+    }
+}
+
+/// A weighted Gaussian kernel density estimate with a (diagonal) bandwidth along each axis.
+///
+/// The density is normalized to unity:
+///
+/// ```math
+/// \hat{f}(x) = \frac{1}{\sum_i w_i} \sum_i w_i \prod_j \frac{1}{\sqrt{2\pi} h_j} \exp\left(-\frac{(x_j - x_{ij})^2}{2 h_j^2}\right)
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Kde<F: Field> {
+    points: Vec<Vec<F>>,
+    weights: Vec<F>,
+    bandwidths: Vec<F>,
+}
+
+impl<F: Field> Kde<F> {
+    /// Create a new [`Kde`] from a set of points with optional weights (the default weight is
+    /// one) and a [`Bandwidth`] rule.
+    ///
+    /// # Errors
+    ///
+    /// See [`Bandwidth::select`].
+    pub fn new(
+        points: Vec<Vec<F>>,
+        weights: Option<Vec<F>>,
+        bandwidth: &Bandwidth<F>,
+    ) -> Result<Self, RustitudeError> {
+        let weights = weights.unwrap_or_else(|| vec![F::one(); points.len()]);
+        let bandwidths = bandwidth.select(&points, &weights)?;
+        Ok(Self {
+            points,
+            weights,
+            bandwidths,
+        })
+    }
+
+    /// Create a new [`Kde`] from the given variables of each [`Event`] in a [`Dataset`], using
+    /// the [`Event`] weights.
+    ///
+    /// # Errors
+    ///
+    /// See [`Bandwidth::select`].
+    pub fn from_dataset(
+        dataset: &Dataset<F>,
+        variables: impl Fn(&Event<F>) -> Vec<F> + Sync,
+        bandwidth: &Bandwidth<F>,
+    ) -> Result<Self, RustitudeError> {
+        let points = dataset.events.par_iter().map(&variables).collect();
+        Self::new(points, Some(dataset.weights()), bandwidth)
+    }
+
+    /// Returns the number of variables (axes) of the [`Kde`].
+    pub const fn dimension(&self) -> usize {
+        self.bandwidths.len()
+    }
+
+    /// Returns the bandwidth along each axis.
+    pub fn bandwidths(&self) -> &[F] {
+        &self.bandwidths
+    }
+
+    /// Evaluates the density at a point.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::EvaluationError`] if the point does not have
+    /// [`Kde::dimension`] coordinates.
+    pub fn evaluate(&self, x: &[F]) -> Result<F, RustitudeError> {
+        if x.len() != self.dimension() {
+            return Err(RustitudeError::EvaluationError(format!(
+                "KDE expects {} variables but got {}",
+                self.dimension(),
+                x.len()
+            )));
+        }
+        let norm = self
+            .bandwidths
+            .iter()
+            .map(|h| *h * F::TAU().sqrt())
+            .product::<F>()
+            * self.weights.iter().copied().sum::<F>();
+        Ok(self
+            .points
+            .iter()
+            .zip(&self.weights)
+            .map(|(point, w)| {
+                let chi2 = point
+                    .iter()
+                    .zip(x)
+                    .zip(&self.bandwidths)
+                    .map(|((p, v), h)| ((*v - *p) / *h).powi(2))
+                    .sum::<F>();
+                *w * F::exp(-chi2 / convert!(2, F))
+            })
+            .sum::<F>()
+            / norm)
+    }
+
+    /// Evaluates the density at the given variables of each [`Event`] in a [`Dataset`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::EvaluationError`] if the variables of any
+    /// [`Event`] do not have [`Kde::dimension`] coordinates.
+    pub fn evaluate_dataset(
+        &self,
+        dataset: &Dataset<F>,
+        variables: impl Fn(&Event<F>) -> Vec<F> + Sync,
+    ) -> Result<Vec<F>, RustitudeError> {
+        dataset
+            .events
+            .par_iter()
+            .map(|event| self.evaluate(&variables(event)))
+            .collect()
+    }
+}
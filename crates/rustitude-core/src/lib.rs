@@ -19,6 +19,7 @@
 //! * [Combining Amplitudes into Models](#combining-amplitudes-into-models)
 //! * [Managing Parameters](#managing-parameters)
 //! * [Evaluating Likelihoods](#evaluating-likelihoods)
+//! * [Feature Flags](#feature-flags)
 //!
 //! # Dataset Structure
 //!
@@ -32,13 +33,16 @@
 //!     pub beam_p4: FourMomentum,           // Beam four-momentum
 //!     pub recoil_p4: FourMomentum,         // Recoil four-momentum
 //!     pub daughter_p4s: Vec<FourMomentum>, // Four-momenta of final state particles sans recoil
-//!     pub eps: Vector3<f32>,               // Beam polarization vector
+//!     pub eps: Vector3<f32>,               // Beam linear polarization vector
+//!     pub circular_polarization: f32,      // Signed degree of beam circular polarization
 //! }
 //! ```
 //!
 //! In the Rust API, we can create [`Dataset`](`crate::dataset::Dataset`)s from `ROOT` files as well as
 //! `Parquet` files. `ROOT` file reading is done through [`oxyroot`] - This still has some issues,
-//! and large files or files with user metadata might fail to load. The alternative `Parquet`
+//! and large files or files with user metadata might fail to load. `RNTuple`s are not yet
+//! supported by [`oxyroot`], but can be read with `rustitude.open` in Python (which uses `uproot`)
+//! or converted with the script linked below. The alternative `Parquet`
 //! format can be obtained from a `ROOT` file by using a conversion script like the one provided
 //! [here](https://github.com/denehoffman/rustitude/blob/main/bin/convert). By default, we expect
 //! all of the [`Event`](`crate::dataset::Event`) fields to be mirrored as the following branches:
@@ -343,6 +347,36 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! # Feature Flags
+//!
+//! The heavier dependencies of this crate can be disabled to embed the evaluation engine in
+//! environments where they are unwanted (such as WASM or FFI consumers). All of the following
+//! features are enabled by default:
+//!
+//! | Feature | Enables |
+//! |---|---|
+//! | `parquet` | [`Dataset::from_parquet`](`crate::dataset::Dataset::from_parquet`) and [`RustitudeError::ParquetError`](`crate::errors::RustitudeError::ParquetError`) |
+//! | `oxyroot` | [`Dataset::from_root`](`crate::dataset::Dataset::from_root`) |
+//! | `pyo3` | Conversions between [`RustitudeError`](`crate::errors::RustitudeError`) and `pyo3::PyErr` |
+//! | `ganesh` | The `ganesh::core::Function` implementation for [`ExtendedLogLikelihood`](`crate::manager::ExtendedLogLikelihood`) |
+//!
+//! To build only the evaluation engine, depend on the crate with `default-features = false`.
+//!
+//! The `simd` feature is disabled by default. It makes [`Manager::evaluate`](`crate::manager::Manager::evaluate`)
+//! and [`Manager::par_evaluate`](`crate::manager::Manager::par_evaluate`) evaluate chunks of
+//! events with [`Model::compute_batch`](`crate::amplitude::Model::compute_batch`), so that
+//! [`Node`](`crate::amplitude::Node`)s which override
+//! [`Node::calculate_batch`](`crate::amplitude::Node::calculate_batch`) run in loops the compiler
+//! can vectorize.
+//!
+//! The `cuda` feature is also disabled by default. It adds the experimental `gpu` module, which
+//! evaluates [`LinearLikelihood`](`crate::linear::LinearLikelihood`)s on a CUDA device.
+//!
+//! The `mpi` feature is also disabled by default. It adds an MPI implementation of
+//! [`Communicator`](`crate::distributed::Communicator`) (and requires an MPI installation), so a
+//! [`DistributedLikelihood`](`crate::distributed::DistributedLikelihood`) can be split over the
+//! processes of an MPI job.
 #![warn(
     clippy::nursery,
     clippy::unwrap_used,
@@ -359,32 +393,121 @@
 #![allow(deprecated)]
 
 use std::fmt::{Debug, Display};
-use std::iter::{Product, Sum};
+use std::{
+    iter::{Product, Sum},
+    sync::LazyLock,
+};
 
-use nalgebra::Vector3;
+use nalgebra::{Complex, DMatrix, Vector3};
 use num::{
     traits::{FloatConst, NumAssignOps},
     Float, FromPrimitive,
 };
+use serde::{de::DeserializeOwned, Serialize};
 pub mod amplitude;
+pub mod binned;
+pub mod cache;
+pub mod config;
 pub mod dataset;
+pub mod diagnostics;
+pub mod distributed;
+pub mod ensemble;
+pub mod ffi;
+pub mod fit;
 pub mod four_momentum;
+pub mod frames;
+#[cfg(feature = "cuda")]
+pub mod gpu;
+pub mod histogram;
+pub mod kde;
+pub mod linear;
 pub mod manager;
+pub mod math;
+pub mod phase_space;
+pub mod profiling;
+pub mod serialization;
+pub mod simultaneous;
+#[cfg(feature = "ganesh")]
+pub mod studies;
 /// Recommended namespace for use and development.
 pub mod prelude {
     pub use crate::amplitude::{
-        cscalar, pcscalar, piecewise_m, scalar, AmpLike, Amplitude, AsTree, Imag, Model, Node,
-        Parameter, Piecewise, Product, Real, Sum,
+        cscalar, gather_batch, pcscalar, piecewise, piecewise_2d, piecewise_beam_energy,
+        piecewise_costheta, piecewise_m, piecewise_m_t, piecewise_t, polynomial_m, scalar,
+        spline_m, template_hist_m, template_kde_m, ActivationSnapshot, AdlerZero, AmpLike, AmpNode,
+        Amplitude, AsTree, BreitWigner, Flatte, Fraction, Gaussian, GaussianConstraint, Imag,
+        KMatrix, KMatrixChannel, KMatrixConfig, KMatrixPole, KnotForm, Model, ModelOperation, Node,
+        Parameter, Piecewise, Piecewise2D, Polynomial, PolynomialBasis, Product, Real, SharedData,
+        SharedQuantity, Spline, SplineKind, Sum, Template, Transform, Visitor, Voigtian,
+    };
+    pub use crate::binned::BinnedChi2;
+    pub use crate::cache::PrecalculationCache;
+    pub use crate::dataset::{
+        Dataset, DatasetLayout, DatasetTransform, DatasetView, Event, EventColumns,
+        FourMomentumColumns, Polarization, PolarizationTable, ReadMethod, RunPeriod,
+        WeightCovariance,
     };
-    pub use crate::dataset::{Dataset, Event, ReadMethod};
+    pub use crate::diagnostics::{
+        aic, bic, binned_chi2, chi2_sf, likelihood_ratio_test, BinnedChiSquare, LikelihoodRatioTest,
+    };
+    pub use crate::distributed::{Communicator, DistributedLikelihood, SingleProcess};
+    #[cfg(feature = "mpi")]
+    pub use crate::distributed::{DistributedManager, MpiCommunicator};
+    pub use crate::ensemble::Ensemble;
     pub use crate::errors::RustitudeError;
+    pub use crate::fit::{correlation, FitFraction, FitResult};
     pub use crate::four_momentum::FourMomentum;
-    pub use crate::manager::{ExtendedLogLikelihood, Manager};
+    pub use crate::histogram::Histogram;
+    pub use crate::kde::{Bandwidth, Kde};
+    pub use crate::linear::{LinearLikelihood, NormalizationIntegrals, StoragePrecision};
+    pub use crate::manager::{
+        Binning, CohSumReport, EventReport, ExtendedLogLikelihood, IntensityFloor, IntensityPolicy,
+        InvalidIntensity, LikelihoodKind, Manager, Observer, RobustLoss, YIELD_AMPLITUDE,
+    };
+    pub use crate::phase_space::{PhaseSpace, Sobol};
+    pub use crate::serialization::{AmpTree, AmplitudeSpec, ModelSpec, NodeRegistry, NodeSpec};
+    pub use crate::simultaneous::{SimultaneousLikelihood, NORMALIZATION_AMPLITUDE};
+    #[cfg(feature = "ganesh")]
+    pub use crate::studies::{PullSummary, ToyFit, ToySource, ToyStudy, ToyStudyResult};
     pub use crate::{convert, convert_array, convert_vec, model, Field, UnitVector};
     pub use nalgebra::Vector3;
     pub use num::Complex;
 }
 
+pub mod fitting {
+    //! This module collects the types used to fit [`Model`](crate::amplitude::Model)s and study
+    //! the results in one place.
+    //!
+    //! The general [`prelude`](crate::prelude) is enough to build and evaluate a
+    //! [`Model`](crate::amplitude::Model), while [`fitting::prelude`](prelude) adds the less
+    //! common types which describe likelihoods, fit results, and the diagnostics run on them.
+
+    /// Recommended namespace for fitting and studying fit results.
+    pub mod prelude {
+        pub use crate::amplitude::{GaussianConstraint, Transform};
+        pub use crate::binned::BinnedChi2;
+        pub use crate::diagnostics::{
+            aic, bic, binned_chi2, chi2_sf, likelihood_ratio_test, BinnedChiSquare,
+            LikelihoodRatioTest,
+        };
+        pub use crate::distributed::{Communicator, DistributedLikelihood, SingleProcess};
+        #[cfg(feature = "mpi")]
+        pub use crate::distributed::{DistributedManager, MpiCommunicator};
+        pub use crate::ensemble::Ensemble;
+        pub use crate::fit::{correlation, DatasetInfo, FitFraction, FitResult, MinosError};
+        #[cfg(feature = "cuda")]
+        pub use crate::gpu::{GpuField, GpuLikelihood};
+        pub use crate::linear::{LinearLikelihood, NormalizationIntegrals, StoragePrecision};
+        pub use crate::manager::{
+            Binning, ExtendedLogLikelihood, IntensityFloor, LikelihoodKind, Observer, RobustLoss,
+            YIELD_AMPLITUDE,
+        };
+        pub use crate::simultaneous::{SimultaneousLikelihood, NORMALIZATION_AMPLITUDE};
+        #[cfg(feature = "ganesh")]
+        pub use crate::studies::{PullSummary, ToyFit, ToySource, ToyStudy, ToyStudyResult};
+    }
+}
+
 /// A trait representing a numeric field which can be used in calculating amplitudes.
 pub trait Field:
     Float
@@ -398,10 +521,37 @@ pub trait Field:
     + Send
     + Sync
     + FromPrimitive
+    + Serialize
+    + DeserializeOwned
 {
+    /// Returns the process-wide [`PrecalculationCache`](crate::cache::PrecalculationCache) for
+    /// this type (see [`PrecalculationCache::global`](crate::cache::PrecalculationCache::global)).
+    fn precalculation_cache() -> &'static cache::PrecalculationCache<Self>;
+
+    /// Attempts to invert a square complex matrix with [`nalgebra`], returning [`None`] if it is
+    /// singular.
+    fn try_inverse(matrix: DMatrix<Complex<Self>>) -> Option<DMatrix<Complex<Self>>>;
+}
+impl Field for f64 {
+    fn precalculation_cache() -> &'static cache::PrecalculationCache<Self> {
+        static CACHE: LazyLock<cache::PrecalculationCache<f64>> =
+            LazyLock::new(cache::PrecalculationCache::new);
+        &CACHE
+    }
+    fn try_inverse(matrix: DMatrix<Complex<Self>>) -> Option<DMatrix<Complex<Self>>> {
+        matrix.try_inverse()
+    }
+}
+impl Field for f32 {
+    fn precalculation_cache() -> &'static cache::PrecalculationCache<Self> {
+        static CACHE: LazyLock<cache::PrecalculationCache<f32>> =
+            LazyLock::new(cache::PrecalculationCache::new);
+        &CACHE
+    }
+    fn try_inverse(matrix: DMatrix<Complex<Self>>) -> Option<DMatrix<Complex<Self>>> {
+        matrix.try_inverse()
+    }
 }
-impl Field for f64 {}
-impl Field for f32 {}
 
 #[macro_export]
 /// Convenience macro for converting raw numeric values to a generic.
@@ -450,7 +600,13 @@ impl<F: Field + 'static> UnitVector for Vector3<F> {
 
 #[macro_export]
 /// Convenience macro for boxing up coherent sum terms into a [`Model`](`crate::amplitude::Model`).
+///
+/// Coherent sums may be named by writing `"name" => term` for every term (see
+/// [`Model::new_named`](`crate::amplitude::Model::new_named`)).
 macro_rules! model {
+    ($($name:literal => $term:expr),+ $(,)?) => {
+        Model::new_named(&[$(($name, Box::new($term) as Box<dyn $crate::amplitude::AmpLike<_>>),)+])
+    };
     ($($term:expr),+ $(,)?) => {
         Model::new(&[$(Box::new($term),)+])
     };
@@ -459,6 +615,7 @@ macro_rules! model {
 pub mod errors {
     //! This module contains an all-encompassing error enum that almost every crate method will
     //! produce if it returns a Result.
+    #[cfg(feature = "pyo3")]
     use pyo3::{exceptions::PyException, PyErr};
     use thiserror::Error;
 
@@ -471,6 +628,7 @@ pub mod errors {
         #[error(transparent)]
         IOError(#[from] std::io::Error),
 
+        #[cfg(feature = "parquet")]
         #[allow(missing_docs)]
         #[error(transparent)]
         ParquetError(#[from] parquet::errors::ParquetError),
@@ -495,6 +653,10 @@ pub mod errors {
         #[error("Amplitude not found: {0}")]
         AmplitudeNotFoundError(String),
 
+        #[allow(missing_docs)]
+        #[error("Coherent sum not found: {0}")]
+        SumNotFoundError(String),
+
         #[allow(missing_docs)]
         #[error("Invalid parameter value: {0}")]
         InvalidParameterValue(String),
@@ -510,12 +672,50 @@ pub mod errors {
         #[allow(missing_docs)]
         #[error("Parsing error: {0}")]
         ParseError(String),
+
+        #[allow(missing_docs)]
+        #[error("Serialization error: {0}")]
+        SerializationError(String),
+
+        #[cfg(feature = "cuda")]
+        #[allow(missing_docs)]
+        #[error("CUDA error: {0}")]
+        CudaError(String),
+
+        #[cfg(feature = "mpi")]
+        #[allow(missing_docs)]
+        #[error("MPI error: {0}")]
+        MpiError(String),
+    }
+    #[cfg(feature = "cuda")]
+    impl From<cudarc::driver::DriverError> for RustitudeError {
+        fn from(err: cudarc::driver::DriverError) -> Self {
+            Self::CudaError(err.to_string())
+        }
+    }
+    #[cfg(feature = "cuda")]
+    impl From<cudarc::nvrtc::CompileError> for RustitudeError {
+        fn from(err: cudarc::nvrtc::CompileError) -> Self {
+            Self::CudaError(err.to_string())
+        }
+    }
+    impl From<serde_json::Error> for RustitudeError {
+        fn from(err: serde_json::Error) -> Self {
+            Self::SerializationError(err.to_string())
+        }
+    }
+    impl From<serde_yaml::Error> for RustitudeError {
+        fn from(err: serde_yaml::Error) -> Self {
+            Self::SerializationError(err.to_string())
+        }
     }
+    #[cfg(feature = "pyo3")]
     impl From<RustitudeError> for PyErr {
         fn from(err: RustitudeError) -> Self {
             PyException::new_err(err.to_string())
         }
     }
+    #[cfg(feature = "pyo3")]
     impl From<PyErr> for RustitudeError {
         fn from(err: PyErr) -> Self {
             Self::PythonError(err.to_string())
@@ -539,6 +739,7 @@ pub mod utils {
                 FourMomentum::new(5.509_043, -0.007_335_639, -0.667_373_54, 5.445_778),
             ],
             eps: Vector3::from([0.385_109_57, 0.022_205_278, 0.0]),
+            circular_polarization: 0.0,
         }
     }
 
@@ -555,6 +756,7 @@ pub mod utils {
                     FourMomentum::new(4.869_362, -0.590_033, -0.663_383, 4.761_812),
                 ],
                 eps: Vector3::from([-0.016_172, 0.319_243, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 1,
@@ -566,6 +768,7 @@ pub mod utils {
                     FourMomentum::new(1.408_791, -0.344_344, 0.387_849, 1.211_640),
                 ],
                 eps: Vector3::from([-0.016_172, 0.319_243, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 2,
@@ -577,6 +780,7 @@ pub mod utils {
                     FourMomentum::new(5.235_301, -0.133_726, -0.606_628, 5.174_445),
                 ],
                 eps: Vector3::from([-0.018_940, 0.373_890, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 3,
@@ -588,6 +792,7 @@ pub mod utils {
                     FourMomentum::new(3.276_772, 0.171_372, -0.349_153, 3.215_329),
                 ],
                 eps: Vector3::from([-0.018_940, 0.373_890, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 4,
@@ -599,6 +804,7 @@ pub mod utils {
                     FourMomentum::new(3.200_482, 0.167_133, -0.345_072, 3.138_225),
                 ],
                 eps: Vector3::from([-0.016_448, 0.324_690, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 5,
@@ -610,6 +816,7 @@ pub mod utils {
                     FourMomentum::new(4.315_006, 0.376_439, 0.627_807, 4.223_246),
                 ],
                 eps: Vector3::from([-0.018_940, 0.373_890, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 6,
@@ -621,6 +828,7 @@ pub mod utils {
                     FourMomentum::new(2.480_163, 0.072_306, -0.363_136, 2.401_352),
                 ],
                 eps: Vector3::from([-0.016_172, 0.319_243, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 7,
@@ -632,6 +840,7 @@ pub mod utils {
                     FourMomentum::new(2.903_734, 0.116_919, -0.233_331, 2.848_849),
                 ],
                 eps: Vector3::from([-0.018_940, 0.373_890, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 8,
@@ -643,6 +852,7 @@ pub mod utils {
                     FourMomentum::new(2.866_588, 0.114_713, -0.229_491, 2.811_384),
                 ],
                 eps: Vector3::from([-0.018_940, 0.373_890, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 9,
@@ -654,6 +864,7 @@ pub mod utils {
                     FourMomentum::new(6.349_971, -0.280_504, 0.469_139, 6.306_800),
                 ],
                 eps: Vector3::from([-0.016_448, 0.324_690, 0.0]),
+                circular_polarization: 0.0,
             },
         ])
     }
@@ -670,6 +881,7 @@ pub mod utils {
                 FourMomentum::new(5.509_043, -0.007_335_639, -0.667_373_54, 5.445_778),
             ],
             eps: Vector3::from([0.385_109_57, 0.022_205_278, 0.0]),
+            circular_polarization: 0.0,
         }
     }
 
@@ -686,6 +898,7 @@ pub mod utils {
                     FourMomentum::new(4.869_362, -0.590_033, -0.663_383, 4.761_812),
                 ],
                 eps: Vector3::from([-0.016_172, 0.319_243, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 1,
@@ -697,6 +910,7 @@ pub mod utils {
                     FourMomentum::new(1.408_791, -0.344_344, 0.387_849, 1.211_64),
                 ],
                 eps: Vector3::from([-0.016_172, 0.319_243, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 2,
@@ -708,6 +922,7 @@ pub mod utils {
                     FourMomentum::new(5.235_301, -0.133_726, -0.606_628, 5.174_445),
                 ],
                 eps: Vector3::from([-0.018_940, 0.373_890, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 3,
@@ -719,6 +934,7 @@ pub mod utils {
                     FourMomentum::new(3.276_772, 0.171_372, -0.349_153, 3.215_329),
                 ],
                 eps: Vector3::from([-0.018_940, 0.373_890, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 4,
@@ -730,6 +946,7 @@ pub mod utils {
                     FourMomentum::new(3.200_482, 0.167_133, -0.345_072, 3.138_225),
                 ],
                 eps: Vector3::from([-0.016_448, 0.324_690, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 5,
@@ -741,6 +958,7 @@ pub mod utils {
                     FourMomentum::new(4.315_006, 0.376_439, 0.627_807, 4.223_246),
                 ],
                 eps: Vector3::from([-0.018_940, 0.373_890, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 6,
@@ -752,6 +970,7 @@ pub mod utils {
                     FourMomentum::new(2.480_163, 0.072_306, -0.363_136, 2.401_352),
                 ],
                 eps: Vector3::from([-0.016_172, 0.319_243, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 7,
@@ -763,6 +982,7 @@ pub mod utils {
                     FourMomentum::new(2.903_734, 0.116_919, -0.233_331, 2.848_849),
                 ],
                 eps: Vector3::from([-0.018_940, 0.373_890, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 8,
@@ -774,6 +994,7 @@ pub mod utils {
                     FourMomentum::new(2.866_588, 0.114_713, -0.229_491, 2.811_384),
                 ],
                 eps: Vector3::from([-0.018_940, 0.373_890, 0.0]),
+                circular_polarization: 0.0,
             },
             Event {
                 index: 9,
@@ -785,6 +1006,7 @@ pub mod utils {
                     FourMomentum::new(6.349_971, -0.280_504, 0.469_139, 6.306_80),
                 ],
                 eps: Vector3::from([-0.016_448, 0.324_690, 0.0]),
+                circular_polarization: 0.0,
             },
         ])
     }
@@ -0,0 +1,952 @@
+//! This module contains a [`LinearLikelihood`] which evaluates an [`ExtendedLogLikelihood`]
+//! from precomputed sufficient statistics when the [`Model`] is linear in its production
+//! coefficients.
+//!
+//! Most amplitude analyses write each coherent sum as $`\sum_m c_m(\theta) A_m(e)`$, where the
+//! production coefficients $`c_m`$ depend only on the free parameters and the amplitudes
+//! $`A_m`$ depend only on the event. In that case, the Monte-Carlo normalization reduces to
+//! $`\sum_{mn} c_m c_n^* \mathcal{I}_{mn}`$ with the normalization integrals
+//! $`\mathcal{I}_{mn} = \sum_{e\in\text{MC}} w_e A_m(e) A_n^*(e)`$, and the data term only needs
+//! the matrix of amplitude values $`A_m(e)`$ on each data event. Both are computed once when the
+//! [`LinearLikelihood`] is created, so no [`Node`] is called again while fitting.
+//!
+//! Each iteration costs $`O(K^2)`$ for the normalization, independent of the size of the
+//! Monte-Carlo sample, plus $`O(NK)`$ for the data term, where $`K`$ is the number of terms and
+//! $`N`$ is the number of data events. The logarithm of the data term cannot be reduced to
+//! aggregate statistics, so the data amplitude matrix is kept in memory instead.
+//!
+//! The normalization integrals are accumulated in [`f64`] one Monte-Carlo event at a time, so
+//! the amplitudes of the Monte-Carlo are never stored. The data amplitude matrix can be stored in
+//! reduced precision with [`LinearLikelihood::new_with_storage`] (see [`StoragePrecision`]),
+//! which halves or quarters its memory while the likelihood is still accumulated in [`f64`].
+//!
+//! [`Amplitude`]s with parameters are treated as production coefficients and must not depend on
+//! the event, while [`Amplitude`]s without parameters are treated as event amplitudes. The
+//! [`Model`] may combine them with any [`Product`](crate::amplitude::Product)s and
+//! [`Sum`](crate::amplitude::Sum)s, but a [`Real`](crate::amplitude::Real) or
+//! [`Imag`](crate::amplitude::Imag) must not mix the two kinds.
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! use rustitude_core::utils::generate_test_dataset_f64;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let dataset = generate_test_dataset_f64();
+//! let edges: Vec<f64> = (0..=20).map(|i| 0.5 * i as f64).collect();
+//! let rising: Vec<f64> = (0..20).map(|i| i as f64 + 1.0).collect();
+//! let falling: Vec<f64> = (0..20).map(|i| 20.0 - i as f64).collect();
+//! let model = model!(
+//!     template_hist_m("a", edges.clone(), rising)? * cscalar("ca")
+//!         + template_hist_m("b", edges, falling)? * cscalar("cb")
+//! );
+//! let manager = Manager::new(&model, &dataset)?;
+//! let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+//! let linear = LinearLikelihood::new(&ell)?;
+//! let parameters = [1.0, 0.0, 0.5, 0.5];
+//! assert!((linear.evaluate(&parameters)? - ell.evaluate(&parameters)?).abs() < 1e-8);
+//! # Ok(())
+//! # }
+//! ```
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+#[cfg(feature = "ganesh")]
+use ganesh::prelude::{DVector, Function};
+use half::f16;
+use num::Complex;
+use rayon::prelude::*;
+
+use crate::{
+    amplitude::{Amplitude, Model, Node},
+    convert,
+    dataset::Event,
+    errors::RustitudeError,
+    manager::{ExtendedLogLikelihood, IntensityFloor},
+    serialization::AmpTree,
+    Field,
+};
+
+/// A single term of an expanded coherent sum, stored as its coefficient factors and event
+/// factors.
+type Monomial = (Vec<AmpTree>, Vec<AmpTree>);
+
+/// The precision in which the data amplitude matrix of a [`LinearLikelihood`] is stored (see
+/// [`LinearLikelihood::new_with_storage`]).
+///
+/// Stored values are converted to [`f64`] when the likelihood is evaluated, so reduced precision
+/// only affects the rounding of each amplitude value, not the accumulation of the likelihood.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StoragePrecision {
+    /// Values are stored in the precision of the [`LinearLikelihood`].
+    #[default]
+    Full,
+    /// Values are stored as [`f32`]s.
+    Single,
+    /// Values are stored as 16-bit floats, which have a relative precision of about $`10^{-3}`$
+    /// and a largest value of $`65504`$.
+    Half,
+}
+
+impl Display for StoragePrecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full => write!(f, "full"),
+            Self::Single => write!(f, "single"),
+            Self::Half => write!(f, "half"),
+        }
+    }
+}
+
+impl FromStr for StoragePrecision {
+    type Err = RustitudeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "full" => Ok(Self::Full),
+            "single" | "f32" => Ok(Self::Single),
+            "half" | "f16" => Ok(Self::Half),
+            _ => Err(RustitudeError::ParseError(format!(
+                "Unknown storage precision: {s}"
+            ))),
+        }
+    }
+}
+
+/// The stored values of an [`EventMatrix`].
+#[derive(Clone)]
+enum StoredValues<F: Field> {
+    Full(Vec<Complex<F>>),
+    Single(Vec<Complex<f32>>),
+    Half(Vec<Complex<f16>>),
+}
+
+/// A matrix with one row of complex values per event, stored with a [`StoragePrecision`].
+#[derive(Clone)]
+pub(crate) struct EventMatrix<F: Field> {
+    n_columns: usize,
+    values: StoredValues<F>,
+}
+
+impl<F: Field> EventMatrix<F> {
+    const fn new(precision: StoragePrecision, n_columns: usize) -> Self {
+        let values = match precision {
+            StoragePrecision::Full => StoredValues::Full(Vec::new()),
+            StoragePrecision::Single => StoredValues::Single(Vec::new()),
+            StoragePrecision::Half => StoredValues::Half(Vec::new()),
+        };
+        Self { n_columns, values }
+    }
+
+    fn push_row(&mut self, row: impl Iterator<Item = Complex<F>>) {
+        match &mut self.values {
+            StoredValues::Full(values) => values.extend(row),
+            StoredValues::Single(values) => {
+                values.extend(row.map(|v| Complex::new(convert!(v.re, f32), convert!(v.im, f32))));
+            }
+            StoredValues::Half(values) => values.extend(row.map(|v| {
+                Complex::new(
+                    f16::from_f64(convert!(v.re, f64)),
+                    f16::from_f64(convert!(v.im, f64)),
+                )
+            })),
+        }
+    }
+
+    /// Returns the number of values in each row.
+    #[cfg_attr(not(feature = "cuda"), allow(dead_code))]
+    pub(crate) const fn n_columns(&self) -> usize {
+        self.n_columns
+    }
+
+    /// Returns the value at the given row and column.
+    #[cfg_attr(not(feature = "cuda"), allow(dead_code))]
+    pub(crate) fn get(&self, row: usize, column: usize) -> Complex<f64> {
+        let i = row * self.n_columns + column;
+        match &self.values {
+            StoredValues::Full(values) => {
+                Complex::new(convert!(values[i].re, f64), convert!(values[i].im, f64))
+            }
+            StoredValues::Single(values) => {
+                Complex::new(f64::from(values[i].re), f64::from(values[i].im))
+            }
+            StoredValues::Half(values) => {
+                Complex::new(values[i].re.to_f64(), values[i].im.to_f64())
+            }
+        }
+    }
+
+    /// Computes $`\sum_m c_m v_m`$ over the values $`v_m`$ of the given row.
+    fn dot(&self, row: usize, coefficients: &[Complex<f64>]) -> Complex<f64> {
+        let range = row * self.n_columns..(row + 1) * self.n_columns;
+        match &self.values {
+            StoredValues::Full(values) => values[range]
+                .iter()
+                .zip(coefficients)
+                .map(|(v, c)| *c * Complex::new(convert!(v.re, f64), convert!(v.im, f64)))
+                .sum(),
+            StoredValues::Single(values) => values[range]
+                .iter()
+                .zip(coefficients)
+                .map(|(v, c)| *c * Complex::new(f64::from(v.re), f64::from(v.im)))
+                .sum(),
+            StoredValues::Half(values) => values[range]
+                .iter()
+                .zip(coefficients)
+                .map(|(v, c)| *c * Complex::new(v.re.to_f64(), v.im.to_f64()))
+                .sum(),
+        }
+    }
+}
+
+/// The normalization statistics of a single coherent sum.
+#[derive(Clone)]
+struct Term<F: Field> {
+    /// The coefficient factors of each term in the sum.
+    coefficients: Vec<Vec<AmpTree>>,
+    /// The event factors of each term in the sum.
+    factors: Vec<Vec<AmpTree>>,
+    /// The normalization integrals $`\mathcal{I}_{mn}`$ over the Monte-Carlo.
+    integrals: Vec<Vec<Complex<F>>>,
+}
+
+/// The normalization integrals $`\mathcal{I}_{mn}`$ of an [`ExtendedLogLikelihood`].
+///
+/// These reduce the Monte-Carlo normalization to a quadratic form in the production
+/// coefficients. See the [module-level documentation](crate::linear) for more information.
+///
+/// These are cached by [`ExtendedLogLikelihood::cache_normalization`] and shared with
+/// [`LinearLikelihood`]. The production coefficients are evaluated with the [`Model`] passed to
+/// each method, so fixing, freeing, or constraining [`Parameter`](crate::amplitude::Parameter)s
+/// keeps the integrals valid, but changing which [`Amplitude`]s are active does not (see
+/// [`NormalizationIntegrals::is_valid_for`]).
+#[derive(Clone)]
+pub struct NormalizationIntegrals<F: Field + 'static> {
+    reference: Event<F>,
+    index: HashMap<String, usize>,
+    active: Vec<bool>,
+    terms: Vec<Term<F>>,
+    n_mc: F,
+}
+
+/// Returns the activity of each [`Amplitude`] in a [`Model`] and whether it is a production
+/// coefficient, along with the initial and shifted free parameters used to check that production
+/// coefficients do not depend on the event.
+#[allow(clippy::type_complexity)]
+fn probe<F: Field>(
+    model: &Model<F>,
+) -> (HashMap<String, usize>, Vec<(bool, bool)>, Vec<F>, Vec<F>) {
+    let initial: Vec<F> = model.parameters.iter().map(|p| p.initial).collect();
+    let shifted: Vec<F> = initial
+        .iter()
+        .enumerate()
+        .map(|(i, x)| *x + convert!(i + 1, F) / convert!(7, F))
+        .collect();
+    let (index, kinds) = model
+        .amplitudes
+        .read()
+        .iter()
+        .enumerate()
+        .map(|(i, amp)| {
+            (
+                (amp.name.clone(), i),
+                (amp.active, !amp.parameters.is_empty()),
+            )
+        })
+        .unzip();
+    (index, kinds, initial, shifted)
+}
+
+impl<F: Field> NormalizationIntegrals<F> {
+    /// Computes the normalization integrals of an [`ExtendedLogLikelihood`], including any
+    /// acceptance (see [`ExtendedLogLikelihood::set_acceptance`]).
+    ///
+    /// Each production coefficient is checked against the first data event on every
+    /// Monte-Carlo event at the initial parameters and at a shifted set of parameters.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are no data
+    /// events, a [`RustitudeError::EvaluationError`] if the [`Model`] is not linear in its
+    /// production coefficients, or any error produced by [`Node::calculate`].
+    pub fn new(ell: &ExtendedLogLikelihood<F>) -> Result<Self, RustitudeError> {
+        let model = &ell.data_manager.model;
+        let Some(reference) = ell.data_manager.dataset.events.first().cloned() else {
+            return Err(RustitudeError::InvalidParameterValue(
+                "normalization integrals require at least one data event".to_string(),
+            ));
+        };
+        let (index, kinds, initial, shifted) = probe(model);
+        let is_active = |name: &str| index.get(name).is_some_and(|&i| kinds[i].0);
+        let is_coefficient = |name: &str| index.get(name).is_some_and(|&i| kinds[i].1);
+        let mut expansions = Vec::new();
+        for cohsum in model.cohsums.iter().filter(|cohsum| cohsum.active) {
+            if let Some(monomials) = expand(&cohsum.to_amp_tree(), &is_active, &is_coefficient)? {
+                expansions.push(monomials);
+            }
+        }
+        let mc_amplitudes = ell.mc_manager.model.amplitudes.read();
+        let expected =
+            expected_coefficients(&mc_amplitudes, &reference, &kinds, &initial, &shifted)?;
+        let mc_weights = ell.mc_manager.dataset.weights();
+        let mc_accepted_weights: Vec<F> = ell.acceptance.as_ref().map_or_else(
+            || mc_weights.clone(),
+            |acceptance| {
+                mc_weights
+                    .iter()
+                    .zip(acceptance)
+                    .map(|(w, a)| *w * *a)
+                    .collect()
+            },
+        );
+        #[allow(clippy::type_complexity)]
+        let (coefficients, factors): (Vec<Vec<Vec<AmpTree>>>, Vec<Vec<Vec<AmpTree>>>) = expansions
+            .into_iter()
+            .map(|monomials| monomials.into_iter().unzip())
+            .unzip();
+        // The integrals are accumulated in f64 one event at a time, so the amplitudes of the
+        // Monte-Carlo are never stored
+        let mut integrals: Vec<Vec<Vec<Complex<f64>>>> = factors
+            .iter()
+            .map(|f| vec![vec![Complex::default(); f.len()]; f.len()])
+            .collect();
+        for (event, w) in ell
+            .mc_manager
+            .dataset
+            .events
+            .iter()
+            .zip(&mc_accepted_weights)
+        {
+            let values =
+                event_values(&mc_amplitudes, event, &kinds, &expected, &initial, &shifted)?;
+            let w: f64 = convert!(*w, f64);
+            for (integrals, factors) in integrals.iter_mut().zip(&factors) {
+                let e: Vec<Complex<f64>> = factors
+                    .iter()
+                    .map(|f| {
+                        let v = product(f, &index, &values);
+                        Complex::new(convert!(v.re, f64), convert!(v.im, f64))
+                    })
+                    .collect();
+                for (row, e_m) in integrals.iter_mut().zip(&e) {
+                    for (entry, e_n) in row.iter_mut().zip(&e) {
+                        *entry += *e_m * e_n.conj() * w;
+                    }
+                }
+            }
+        }
+        drop(mc_amplitudes);
+        let terms = coefficients
+            .into_iter()
+            .zip(factors)
+            .zip(integrals)
+            .map(|((coefficients, factors), integrals)| Term {
+                coefficients,
+                factors,
+                integrals: integrals
+                    .into_iter()
+                    .map(|row| {
+                        row.into_iter()
+                            .map(|v| Complex::new(convert!(v.re, F), convert!(v.im, F)))
+                            .collect()
+                    })
+                    .collect(),
+            })
+            .collect();
+        Ok(Self {
+            reference,
+            index,
+            active: kinds.iter().map(|(active, _)| *active).collect(),
+            terms,
+            n_mc: mc_weights.iter().copied().sum(),
+        })
+    }
+
+    /// Converts the [`NormalizationIntegrals`] to another floating-point precision.
+    ///
+    /// The integrals only depend on the Monte-Carlo, so integrals computed in a fast [`f32`] fit
+    /// can be reused to start a [`f64`] fit of the same [`Model`] (see
+    /// [`ExtendedLogLikelihood::warm_start_normalization`]) and only recomputed for its final
+    /// iterations. Converting to [`f32`] rounds every integral to single precision.
+    pub fn convert<G: Field>(&self) -> NormalizationIntegrals<G> {
+        NormalizationIntegrals {
+            reference: self.reference.convert(),
+            index: self.index.clone(),
+            active: self.active.clone(),
+            terms: self
+                .terms
+                .iter()
+                .map(|term| Term {
+                    coefficients: term.coefficients.clone(),
+                    factors: term.factors.clone(),
+                    integrals: term
+                        .integrals
+                        .iter()
+                        .map(|row| {
+                            row.iter()
+                                .map(|v| Complex::new(convert!(v.re, G), convert!(v.im, G)))
+                                .collect()
+                        })
+                        .collect(),
+                })
+                .collect(),
+            n_mc: convert!(self.n_mc, G),
+        }
+    }
+
+    /// Returns the number of terms $`K`$ in the expanded [`Model`], summed over each coherent
+    /// sum.
+    pub fn n_terms(&self) -> usize {
+        self.terms.iter().map(|term| term.coefficients.len()).sum()
+    }
+
+    /// Checks that the [`Amplitude`]s of a [`Model`] have the same activity as when the
+    /// integrals were computed, so that they still describe its expansion.
+    pub fn is_valid_for(&self, model: &Model<F>) -> bool {
+        let amplitudes = model.amplitudes.read();
+        amplitudes.len() == self.active.len()
+            && amplitudes
+                .iter()
+                .zip(&self.active)
+                .all(|(amp, active)| amp.active == *active)
+    }
+
+    /// Evaluates the production coefficients of each term for the given free parameters.
+    pub(crate) fn coefficients(
+        &self,
+        model: &Model<F>,
+        parameters: &[F],
+    ) -> Result<Vec<Vec<Complex<F>>>, RustitudeError> {
+        let pars = model.physical_parameters(parameters);
+        let values = model
+            .amplitudes
+            .read()
+            .iter()
+            .map(|amp| {
+                if amp.active && !amp.parameters.is_empty() {
+                    amp.calculate(&pars, &self.reference).map(Some)
+                } else {
+                    Ok(None)
+                }
+            })
+            .collect::<Result<Vec<Option<Complex<F>>>, RustitudeError>>()?;
+        Ok(self
+            .terms
+            .iter()
+            .map(|term| {
+                term.coefficients
+                    .iter()
+                    .map(|factors| product(factors, &self.index, &values))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Evaluates the quadratic form $`\sum_{mn} c_m c_n^* \mathcal{I}_{mn}`$ for the given
+    /// production coefficients.
+    pub(crate) fn quadratic_form(&self, coefficients: &[Vec<Complex<F>>]) -> F {
+        self.terms
+            .iter()
+            .zip(coefficients)
+            .map(|(term, c)| {
+                term.integrals
+                    .iter()
+                    .zip(c)
+                    .map(|(row, c_m)| {
+                        row.iter()
+                            .zip(c)
+                            .map(|(entry, c_n)| (*c_m * entry * c_n.conj()).re)
+                            .sum::<F>()
+                    })
+                    .sum::<F>()
+            })
+            .sum()
+    }
+
+    /// Returns the total Monte-Carlo weight $`\sum_j w_j`$.
+    pub const fn n_mc(&self) -> F {
+        self.n_mc
+    }
+
+    /// Evaluates the weighted sum of intensities over the Monte-Carlo,
+    /// $`\sum_j w_j a_j I(x_j) = \sum_{mn} c_m c_n^* \mathcal{I}_{mn}`$, for the given free
+    /// parameters of a [`Model`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if a production coefficient cannot be
+    /// evaluated.
+    pub fn mc_sum(&self, model: &Model<F>, parameters: &[F]) -> Result<F, RustitudeError> {
+        Ok(self.quadratic_form(&self.coefficients(model, parameters)?))
+    }
+
+    /// Evaluates the gradient of [`NormalizationIntegrals::mc_sum`] with respect to the free
+    /// parameters by central finite differences, which costs $`O(K^2)`$ per free parameter.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if a production coefficient cannot be
+    /// evaluated.
+    pub fn mc_sum_gradient(
+        &self,
+        model: &Model<F>,
+        parameters: &[F],
+    ) -> Result<Vec<F>, RustitudeError> {
+        let mut x = parameters.to_vec();
+        (0..x.len())
+            .map(|i| {
+                let h = F::epsilon().cbrt() * F::max(F::one(), x[i].abs());
+                let x_i = x[i];
+                x[i] = x_i + h;
+                let f_plus = self.mc_sum(model, &x)?;
+                x[i] = x_i - h;
+                let f_minus = self.mc_sum(model, &x)?;
+                x[i] = x_i;
+                Ok((f_plus - f_minus) / (convert!(2, F) * h))
+            })
+            .collect()
+    }
+}
+
+impl NormalizationIntegrals<f64> {
+    /// Converts [`f32`] [`NormalizationIntegrals`] to [`f64`] precision (see
+    /// [`NormalizationIntegrals::convert`]).
+    pub fn from_lower_precision(integrals: &NormalizationIntegrals<f32>) -> Self {
+        integrals.convert()
+    }
+}
+
+/// An [`ExtendedLogLikelihood`] evaluated from precomputed amplitude matrices and normalization
+/// integrals. See the [module-level documentation](crate::linear) for more information.
+#[derive(Clone)]
+pub struct LinearLikelihood<F: Field + 'static> {
+    pub(crate) model: Model<F>,
+    pub(crate) normalization: NormalizationIntegrals<F>,
+    /// The value of each term's event factors on each data event, for each coherent sum.
+    pub(crate) data: Vec<EventMatrix<F>>,
+    pub(crate) storage: StoragePrecision,
+    pub(crate) data_weights: Vec<F>,
+    pub(crate) n_data: F,
+    pub(crate) intensity_floor: Option<IntensityFloor<F>>,
+}
+
+impl<F: Field> LinearLikelihood<F> {
+    /// Create a new [`LinearLikelihood`] from an [`ExtendedLogLikelihood`], computing the data
+    /// amplitude matrix and the normalization integrals.
+    ///
+    /// The [`Parameter`](crate::amplitude::Parameter)s, constraints, and
+    /// [`IntensityFloor`] are taken from the [`ExtendedLogLikelihood`] at this point, and the
+    /// activity of each [`Amplitude`] is fixed, so the [`LinearLikelihood`] should be created
+    /// after the [`Model`] is configured. Each production coefficient is checked against the
+    /// first data event on every event at the initial parameters and at a shifted set of
+    /// parameters. Normalization integrals cached by
+    /// [`ExtendedLogLikelihood::cache_normalization`] are reused if they are still valid.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the
+    /// [`ExtendedLogLikelihood`] has explicit yields, a
+    /// [`RobustLoss`](crate::manager::RobustLoss), or a
+    /// [`LikelihoodKind`](crate::manager::LikelihoodKind) other than
+    /// [`LikelihoodKind::Extended`](crate::manager::LikelihoodKind::Extended), or if there are no
+    /// data events, a
+    /// [`RustitudeError::EvaluationError`] if the [`Model`] is not linear in its production
+    /// coefficients, or any error produced by [`Node::calculate`].
+    pub fn new(ell: &ExtendedLogLikelihood<F>) -> Result<Self, RustitudeError> {
+        Self::new_with_storage(ell, StoragePrecision::Full)
+    }
+
+    /// Create a new [`LinearLikelihood`] from an [`ExtendedLogLikelihood`] as in
+    /// [`LinearLikelihood::new`], storing the data amplitude matrix with the given
+    /// [`StoragePrecision`].
+    ///
+    /// # Errors
+    ///
+    /// See [`LinearLikelihood::new`].
+    pub fn new_with_storage(
+        ell: &ExtendedLogLikelihood<F>,
+        storage: StoragePrecision,
+    ) -> Result<Self, RustitudeError> {
+        if !ell.yields.is_empty() || ell.robust_loss.is_some() || !ell.kind.is_extended() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "a LinearLikelihood only supports the extended likelihood without explicit yields \
+                 or robust losses"
+                    .to_string(),
+            ));
+        }
+        let model = ell.data_manager.model.clone();
+        let normalization = match &ell.normalization_integrals {
+            Some(integrals) if integrals.is_valid_for(&model) => integrals.clone(),
+            _ => NormalizationIntegrals::new(ell)?,
+        };
+        let (_, kinds, initial, shifted) = probe(&model);
+        let amplitudes = ell.data_manager.model.amplitudes.read();
+        let expected = expected_coefficients(
+            &amplitudes,
+            &normalization.reference,
+            &kinds,
+            &initial,
+            &shifted,
+        )?;
+        let mut data: Vec<EventMatrix<F>> = normalization
+            .terms
+            .iter()
+            .map(|term| EventMatrix::new(storage, term.factors.len()))
+            .collect();
+        for event in ell.data_manager.dataset.events.iter() {
+            let values = event_values(&amplitudes, event, &kinds, &expected, &initial, &shifted)?;
+            for (matrix, term) in data.iter_mut().zip(&normalization.terms) {
+                matrix.push_row(
+                    term.factors
+                        .iter()
+                        .map(|f| product(f, &normalization.index, &values)),
+                );
+            }
+        }
+        drop(amplitudes);
+        let data_weights = ell.data_manager.dataset.weights();
+        Ok(Self {
+            model,
+            normalization,
+            data,
+            storage,
+            n_data: data_weights.iter().copied().sum(),
+            data_weights,
+            intensity_floor: ell.intensity_floor,
+        })
+    }
+
+    /// Returns the number of terms $`K`$ in the expanded [`Model`], summed over each coherent
+    /// sum.
+    pub fn n_terms(&self) -> usize {
+        self.normalization.n_terms()
+    }
+
+    /// Returns the [`StoragePrecision`] of the data amplitude matrix.
+    pub const fn storage(&self) -> StoragePrecision {
+        self.storage
+    }
+
+    /// Get the number of free parameters.
+    pub fn get_n_free(&self) -> usize {
+        self.model.get_n_free()
+    }
+
+    /// Get the initial values of the free parameters.
+    pub fn get_initial(&self) -> Vec<F> {
+        self.model.get_initial()
+    }
+
+    /// Get the bounds of the free parameters.
+    pub fn get_bounds(&self) -> Vec<(F, F)> {
+        self.model.get_bounds()
+    }
+
+    /// Evaluates $`-2\ln\mathcal{L}`$ with the given free parameters.
+    #[allow(clippy::suboptimal_flops)]
+    fn evaluate_with(&self, parameters: &[F], parallel: bool) -> Result<F, RustitudeError> {
+        let coefficients = self.normalization.coefficients(&self.model, parameters)?;
+        let mc_sum = self.normalization.quadratic_form(&coefficients);
+        let coefficients: Vec<Vec<Complex<f64>>> = coefficients
+            .iter()
+            .map(|c| {
+                c.iter()
+                    .map(|c| Complex::new(convert!(c.re, f64), convert!(c.im, f64)))
+                    .collect()
+            })
+            .collect();
+        let term = |(i, w): (usize, &F)| -> f64 {
+            let intensity: f64 = self
+                .data
+                .iter()
+                .zip(&coefficients)
+                .map(|(data, c)| data.dot(i, c).norm_sqr())
+                .sum();
+            let ln = self.intensity_floor.map_or_else(
+                || intensity.ln(),
+                |floor| convert!(floor.ln(convert!(intensity, F)), f64),
+            );
+            convert!(*w, f64) * ln
+        };
+        let data_sum: f64 = if parallel {
+            self.data_weights.par_iter().enumerate().map(term).sum()
+        } else {
+            self.data_weights.iter().enumerate().map(term).sum()
+        };
+        let ln_l = convert!(data_sum, F) - (self.n_data / self.normalization.n_mc) * mc_sum;
+        Ok(convert!(-2, F) * ln_l + self.model.constraint_penalty(parameters)?)
+    }
+
+    /// Evaluate $`-2\ln\mathcal{L}`$ with the given free parameters. This matches
+    /// [`ExtendedLogLikelihood::evaluate`] up to rounding.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if a production coefficient or a constraint
+    /// cannot be evaluated.
+    pub fn evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        self.evaluate_with(parameters, false)
+    }
+
+    /// Evaluate $`-2\ln\mathcal{L}`$ with the given free parameters, using a parallel loop over
+    /// data events.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if a production coefficient or a constraint
+    /// cannot be evaluated.
+    pub fn par_evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        self.evaluate_with(parameters, true)
+    }
+
+    /// Evaluates the gradient with central finite differences, which costs two evaluations per
+    /// free parameter.
+    fn gradient_with(&self, parameters: &[F], parallel: bool) -> Result<Vec<F>, RustitudeError> {
+        let mut x = parameters.to_vec();
+        (0..x.len())
+            .map(|i| {
+                let h = F::epsilon().cbrt() * F::max(F::one(), x[i].abs());
+                let x_i = x[i];
+                x[i] = x_i + h;
+                let f_plus = self.evaluate_with(&x, parallel)?;
+                x[i] = x_i - h;
+                let f_minus = self.evaluate_with(&x, parallel)?;
+                x[i] = x_i;
+                Ok((f_plus - f_minus) / (convert!(2, F) * h))
+            })
+            .collect()
+    }
+
+    /// Evaluate the gradient of $`-2\ln\mathcal{L}`$ with respect to the free parameters by
+    /// central finite differences.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if a production coefficient or a constraint
+    /// cannot be evaluated.
+    pub fn gradient(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        self.gradient_with(parameters, false)
+    }
+
+    /// Parallel version of [`LinearLikelihood::gradient`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if a production coefficient or a constraint
+    /// cannot be evaluated.
+    pub fn par_gradient(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        self.gradient_with(parameters, true)
+    }
+}
+
+/// The values of each active production coefficient on the reference event at the initial and
+/// shifted parameters.
+type Expected<F> = Vec<Option<(Complex<F>, Complex<F>)>>;
+
+/// Computes the values of each active production coefficient on the reference event at both
+/// sets of parameters (see [`event_values`]).
+fn expected_coefficients<F: Field>(
+    amplitudes: &[Amplitude<F>],
+    reference: &Event<F>,
+    kinds: &[(bool, bool)],
+    initial: &[F],
+    shifted: &[F],
+) -> Result<Expected<F>, RustitudeError> {
+    let mut expected = Vec::with_capacity(amplitudes.len());
+    for (amp, &(active, coefficient)) in amplitudes.iter().zip(kinds) {
+        expected.push(if active && coefficient {
+            Some((
+                amp.calculate(initial, reference)?,
+                amp.calculate(shifted, reference)?,
+            ))
+        } else {
+            None
+        });
+    }
+    Ok(expected)
+}
+
+/// Computes the values of the active [`Amplitude`]s on an event, checking that every
+/// production coefficient matches its value on the reference event at both sets of parameters
+/// (see [`expected_coefficients`]). Production coefficients are left as [`None`], since only the
+/// event factors are needed.
+fn event_values<F: Field>(
+    amplitudes: &[Amplitude<F>],
+    event: &Event<F>,
+    kinds: &[(bool, bool)],
+    expected: &Expected<F>,
+    initial: &[F],
+    shifted: &[F],
+) -> Result<Vec<Option<Complex<F>>>, RustitudeError> {
+    let tolerance = F::epsilon().sqrt();
+    amplitudes
+        .iter()
+        .zip(kinds)
+        .zip(expected)
+        .map(|((amp, &(active, _)), expected)| {
+            if !active {
+                return Ok(None);
+            }
+            let Some(expected) = expected else {
+                return amp.calculate(initial, event).map(Some);
+            };
+            for (pars, value) in [(initial, expected.0), (shifted, expected.1)] {
+                let actual = amp.calculate(pars, event)?;
+                if (actual - value).norm() > tolerance * (F::one() + value.norm()) {
+                    return Err(RustitudeError::EvaluationError(format!(
+                        "the production coefficient {} depends on the event (event #{})",
+                        amp.name, event.index
+                    )));
+                }
+            }
+            Ok(None)
+        })
+        .collect()
+}
+
+/// Multiplies the values of a list of factors, each of which is active by construction.
+fn product<F: Field>(
+    factors: &[AmpTree],
+    index: &HashMap<String, usize>,
+    values: &[Option<Complex<F>>],
+) -> Complex<F> {
+    factors
+        .iter()
+        .filter_map(|factor| compute(factor, index, values))
+        .product()
+}
+
+/// Computes the value of an [`AmpTree`] from the values of its [`Amplitude`]s, mirroring
+/// [`AmpLike::compute`](crate::amplitude::AmpLike::compute).
+fn compute<F: Field>(
+    tree: &AmpTree,
+    index: &HashMap<String, usize>,
+    values: &[Option<Complex<F>>],
+) -> Option<Complex<F>> {
+    match tree {
+        AmpTree::Amplitude(name) => index.get(name).and_then(|&i| values[i]),
+        AmpTree::Real(inner) => compute(inner, index, values).map(|v| v.re.into()),
+        AmpTree::Imag(inner) => compute(inner, index, values).map(|v| v.im.into()),
+        AmpTree::Product(trees) => {
+            let mut values = trees
+                .iter()
+                .filter_map(|t| compute(t, index, values))
+                .peekable();
+            Some(if values.peek().is_none() {
+                Complex::default()
+            } else {
+                values.product()
+            })
+        }
+        AmpTree::Sum(trees) => Some(trees.iter().filter_map(|t| compute(t, index, values)).sum()),
+    }
+}
+
+/// Checks if an [`AmpTree`] always computes to [`None`], which only depends on which
+/// [`Amplitude`]s are active.
+fn is_none(tree: &AmpTree, is_active: &impl Fn(&str) -> bool) -> bool {
+    match tree {
+        AmpTree::Amplitude(name) => !is_active(name),
+        AmpTree::Real(inner) | AmpTree::Imag(inner) => is_none(inner, is_active),
+        AmpTree::Product(_) | AmpTree::Sum(_) => false,
+    }
+}
+
+/// Collects the names of the active [`Amplitude`]s in an [`AmpTree`].
+fn active_leaves<'a>(
+    tree: &'a AmpTree,
+    is_active: &impl Fn(&str) -> bool,
+    leaves: &mut Vec<&'a str>,
+) {
+    match tree {
+        AmpTree::Amplitude(name) => {
+            if is_active(name) {
+                leaves.push(name);
+            }
+        }
+        AmpTree::Real(inner) | AmpTree::Imag(inner) => active_leaves(inner, is_active, leaves),
+        AmpTree::Product(trees) | AmpTree::Sum(trees) => trees
+            .iter()
+            .for_each(|t| active_leaves(t, is_active, leaves)),
+    }
+}
+
+/// Expands an [`AmpTree`] into a sum of [`Monomial`]s, returning [`None`] if the tree always
+/// computes to [`None`] and an empty sum if it always computes to zero.
+fn expand(
+    tree: &AmpTree,
+    is_active: &impl Fn(&str) -> bool,
+    is_coefficient: &impl Fn(&str) -> bool,
+) -> Result<Option<Vec<Monomial>>, RustitudeError> {
+    match tree {
+        AmpTree::Amplitude(name) => Ok(is_active(name).then(|| {
+            if is_coefficient(name) {
+                vec![(vec![tree.clone()], Vec::new())]
+            } else {
+                vec![(Vec::new(), vec![tree.clone()])]
+            }
+        })),
+        AmpTree::Real(inner) | AmpTree::Imag(inner) => {
+            if is_none(inner, is_active) {
+                return Ok(None);
+            }
+            let mut leaves = Vec::new();
+            active_leaves(inner, is_active, &mut leaves);
+            let coefficients = leaves.iter().filter(|name| is_coefficient(name)).count();
+            if coefficients == 0 {
+                Ok(Some(vec![(Vec::new(), vec![tree.clone()])]))
+            } else if coefficients == leaves.len() {
+                Ok(Some(vec![(vec![tree.clone()], Vec::new())]))
+            } else {
+                Err(RustitudeError::EvaluationError(format!(
+                    "{tree:?} mixes production coefficients and event amplitudes inside a real or imaginary part"
+                )))
+            }
+        }
+        AmpTree::Product(trees) => {
+            let mut factors = Vec::new();
+            for t in trees {
+                if let Some(monomials) = expand(t, is_active, is_coefficient)? {
+                    factors.push(monomials);
+                }
+            }
+            if factors.is_empty() {
+                return Ok(Some(Vec::new()));
+            }
+            Ok(Some(factors.into_iter().fold(
+                vec![(Vec::new(), Vec::new())],
+                |acc, monomials| {
+                    acc.iter()
+                        .flat_map(|(c_a, e_a)| {
+                            monomials.iter().map(move |(c_b, e_b)| {
+                                (
+                                    c_a.iter().chain(c_b).cloned().collect(),
+                                    e_a.iter().chain(e_b).cloned().collect(),
+                                )
+                            })
+                        })
+                        .collect()
+                },
+            )))
+        }
+        AmpTree::Sum(trees) => {
+            let mut monomials = Vec::new();
+            for t in trees {
+                if let Some(terms) = expand(t, is_active, is_coefficient)? {
+                    monomials.extend(terms);
+                }
+            }
+            Ok(Some(monomials))
+        }
+    }
+}
+
+#[cfg(feature = "ganesh")]
+impl<F: Field + ganesh::core::Field> Function<F, (), RustitudeError> for LinearLikelihood<F> {
+    fn evaluate(&self, x: &DVector<F>, _args: Option<&()>) -> Result<F, RustitudeError> {
+        self.par_evaluate(x.as_slice())
+    }
+
+    fn gradient(&self, x: &DVector<F>, _args: Option<&()>) -> Result<DVector<F>, RustitudeError> {
+        Ok(DVector::from_vec(self.par_gradient(x.as_slice())?))
+    }
+}
@@ -1,20 +1,171 @@
 //! This module contains methods to link [`Model`]s with [`Dataset`]s via a [`Manager::evaluate`]
-//! method. This module also holds a [`ExtendedLogLikelihood`] struct which holds two [`Manager`]s
+//! method.
+//!
+//! This module also holds a [`ExtendedLogLikelihood`] struct which holds two [`Manager`]s
 //! and, as the name suggests, calculates an extended log-likelihood using a very basic method over
 //! data and (accepted) Monte-Carlo.
 
-use std::fmt::{Debug, Display};
+use std::{
+    collections::HashSet,
+    fmt::{Debug, Display},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
-use ganesh::prelude::{DVector, Function};
-use rayon::prelude::*;
+#[cfg(feature = "ganesh")]
+use crate::fit::FitResult;
+#[cfg(feature = "ganesh")]
+use ganesh::{
+    algorithms::NelderMead,
+    prelude::{DVector, Function, Minimizer},
+};
+use num::Complex;
+use parking_lot::RwLock;
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+use tracing::{debug_span, warn};
 
 use crate::{
+    amplitude::{ActivationSnapshot, Node, NormSqr},
     convert,
     errors::RustitudeError,
-    prelude::{Amplitude, Dataset, Event, Model, Parameter},
+    fit::FitFraction,
+    linear::NormalizationIntegrals,
+    phase_space::PhaseSpace,
+    prelude::{Amplitude, Dataset, Event, Model, Parameter, Transform},
+    profiling::{self, Stage},
+    serialization::AmpTree,
     Field,
 };
 
+/// The number of events evaluated together by [`Model::compute_batch`] when the `simd` feature is
+/// enabled.
+pub const BATCH_SIZE: usize = 256;
+
+/// The physical parameters and [`Amplitude`] values of each event from the previous evaluation
+/// of a [`Manager`] (see [`Manager::with_incremental`]).
+///
+/// Cloning an [`IncrementalCache`] gives an empty cache, so clones of a [`Manager`] never share
+/// cached values.
+#[derive(Default)]
+#[allow(clippy::type_complexity)]
+pub struct IncrementalCache<F: Field>(RwLock<Option<(Vec<F>, Vec<Vec<Option<Complex<F>>>>)>>);
+
+impl<F: Field> Clone for IncrementalCache<F> {
+    fn clone(&self) -> Self {
+        Self(RwLock::new(None))
+    }
+}
+
+/// How a [`Manager`] treats events whose intensity is zero, negative, or not finite (see
+/// [`Manager::with_intensity_policy`]).
+///
+/// Such an event makes $`\ln I`$ infinite or NaN, which spoils the whole likelihood. Each policy
+/// is applied by [`Manager::evaluate`], [`Manager::par_evaluate`], their indexed and batched
+/// versions, and the gradient methods of [`ExtendedLogLikelihood`], and the offending events can
+/// be listed with [`Manager::invalid_intensities`].
+#[derive(Clone, Copy, Debug)]
+pub enum IntensityPolicy<F: Field> {
+    /// Return a [`RustitudeError::EvaluationError`] listing the offending events and the values
+    /// of their [`Amplitude`]s.
+    Error,
+    /// Replace the intensity of any offending event with the given (positive) value.
+    Clamp(F),
+    /// Replace the intensity of any offending event with zero and log a warning listing the
+    /// offending events. An [`ExtendedLogLikelihood`] whose data [`Manager`] uses this policy
+    /// drops these events from the sum over data, just like an [`IntensityFloor::Drop`].
+    Skip,
+}
+
+/// An event whose intensity is zero, negative, or not finite, as reported by
+/// [`Manager::invalid_intensities`].
+#[derive(Clone, Debug)]
+pub struct InvalidIntensity<F: Field> {
+    /// The index of the event in the [`Dataset`].
+    pub index: usize,
+    /// The intensity of the event.
+    pub intensity: F,
+    /// The name and value of each active [`Amplitude`] on the event.
+    pub amplitudes: Vec<(String, Complex<F>)>,
+}
+
+impl<F: Field> Display for InvalidIntensity<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "event #{}: intensity = {}", self.index, self.intensity)?;
+        for (name, value) in &self.amplitudes {
+            write!(f, ", {name} = {value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The number of [`InvalidIntensity`] reports included in the error or warning of an
+/// [`IntensityPolicy`].
+const MAX_INVALID_REPORTS: usize = 10;
+
+/// Checks if an intensity is positive and finite.
+fn is_valid_intensity<F: Field>(value: F) -> bool {
+    value > F::zero() && value.is_finite()
+}
+
+/// The breakdown of a single coherent sum in an [`EventReport`].
+#[derive(Clone, Debug)]
+pub struct CohSumReport<F: Field> {
+    /// The name of the coherent sum, if any.
+    pub name: Option<String>,
+    /// A flag which is `false` if the coherent sum is excluded from the [`Model`].
+    pub active: bool,
+    /// The formula and value of each term of the coherent sum, which is [`None`] if the term
+    /// only contains inactive [`Amplitude`]s.
+    pub terms: Vec<(String, Option<Complex<F>>)>,
+    /// The value of the coherent sum before the absolute square is taken.
+    pub value: Option<Complex<F>>,
+    /// The contribution $`|\sum_k T_k|^2`$ of the coherent sum to the intensity, which is
+    /// [`None`] if the coherent sum is inactive.
+    pub intensity: Option<F>,
+}
+
+/// The breakdown of the intensity of a single event, as returned by [`Manager::debug_event`].
+#[derive(Clone, Debug)]
+pub struct EventReport<F: Field> {
+    /// The index of the event in the [`Dataset`].
+    pub index: usize,
+    /// The name and value of each [`Amplitude`], which is [`None`] if it is inactive.
+    pub amplitudes: Vec<(String, Option<Complex<F>>)>,
+    /// The breakdown of each coherent sum of the [`Model`].
+    pub cohsums: Vec<CohSumReport<F>>,
+    /// The intensity of the event, the sum of the intensities of the active coherent sums.
+    pub intensity: F,
+}
+
+impl<F: Field> Display for EventReport<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Event #{}: intensity = {}", self.index, self.intensity)?;
+        writeln!(f, "  Amplitudes:")?;
+        for (name, value) in &self.amplitudes {
+            match value {
+                Some(value) => writeln!(f, "    {name} = {value}")?,
+                None => writeln!(f, "    {name} (inactive)")?,
+            }
+        }
+        for (i, cohsum) in self.cohsums.iter().enumerate() {
+            let name = cohsum.name.clone().unwrap_or_else(|| format!("#{i}"));
+            match cohsum.intensity {
+                Some(intensity) => writeln!(f, "  Coherent sum {name}: |.|^2 = {intensity}")?,
+                None => writeln!(f, "  Coherent sum {name} (inactive)")?,
+            }
+            for (formula, value) in &cohsum.terms {
+                match value {
+                    Some(value) => writeln!(f, "    {formula} = {value}")?,
+                    None => writeln!(f, "    {formula} (inactive)")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// The [`Manager`] struct links a [`Model`] to a [`Dataset`] and provides methods to manipulate
 /// the [`Model`] and evaluate it over the [`Dataset`].
 #[derive(Clone)]
@@ -23,6 +174,15 @@ pub struct Manager<F: Field + 'static> {
     pub model: Model<F>,
     /// The associated [`Dataset`].
     pub dataset: Dataset<F>,
+    /// Cached [`Amplitude`] values for incremental evaluation, if enabled. See
+    /// [`Manager::with_incremental`].
+    pub incremental: Option<IncrementalCache<F>>,
+    /// The [`ThreadPool`] used by the parallel methods, or [`None`] to use the global [`rayon`]
+    /// pool. See [`Manager::with_threads`].
+    pub thread_pool: Option<Arc<ThreadPool>>,
+    /// An optional [`IntensityPolicy`] for events whose intensity is zero, negative, or not
+    /// finite. See [`Manager::with_intensity_policy`].
+    pub intensity_policy: Option<IntensityPolicy<F>>,
 }
 impl<F: Field> Debug for Manager<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -39,6 +199,9 @@ impl<F: Field> Display for Manager<F> {
 impl<F: Field> Manager<F> {
     /// Generates a new [`Manager`] from a [`Model`] and [`Dataset`].
     ///
+    /// The [`Model`] is copied so that its precalculated values belong to this [`Manager`], but
+    /// the [`Event`]s of the [`Dataset`] are shared with the caller rather than copied.
+    ///
     /// # Errors
     ///
     /// This method will return a [`RustitudeError`] if the precaluclation phase of the [`Model`]
@@ -47,32 +210,423 @@ impl<F: Field> Manager<F> {
         let mut model = model.deep_clone();
         model.load(dataset)?;
         Ok(Self {
-            model: model.clone(),
+            model,
             dataset: dataset.clone(),
+            incremental: None,
+            thread_pool: None,
+            intensity_policy: None,
         })
     }
 
-    /// Evaluate the [`Model`] over the [`Dataset`] with the given free parameters.
+    /// Runs the parallel methods of the [`Manager`] on a dedicated [`ThreadPool`] with `n`
+    /// threads rather than the global [`rayon`] pool (if `n` is `0`, [`rayon`] picks the number
+    /// of threads as it does for the global pool).
+    ///
+    /// This limits the number of cores used by a fit without relying on the `RAYON_NUM_THREADS`
+    /// environment variable. The pool is shared by clones of the [`Manager`]. It is safe to call
+    /// a parallel method from inside another pool (for instance, from a parallel loop over
+    /// several fits): the calling thread waits while the work runs on this pool, and calls made
+    /// from inside this pool run directly on it.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ThreadPoolBuildError`] if the pool cannot be
+    /// created.
+    pub fn with_threads(self, n: usize) -> Result<Self, RustitudeError> {
+        let pool = ThreadPoolBuilder::new().num_threads(n).build()?;
+        Ok(self.with_thread_pool(Some(Arc::new(pool))))
+    }
+
+    /// Runs the parallel methods of the [`Manager`] on the given [`ThreadPool`], which can be
+    /// shared with other [`Manager`]s, or on the global [`rayon`] pool if it is [`None`]. See
+    /// [`Manager::with_threads`].
+    #[must_use]
+    pub fn with_thread_pool(mut self, thread_pool: Option<Arc<ThreadPool>>) -> Self {
+        self.thread_pool = thread_pool;
+        self
+    }
+
+    /// Returns the number of threads used by the parallel methods of the [`Manager`].
+    pub fn num_threads(&self) -> usize {
+        self.thread_pool
+            .as_ref()
+            .map_or_else(rayon::current_num_threads, |pool| {
+                pool.current_num_threads()
+            })
+    }
+
+    /// Runs `op` on the [`ThreadPool`] of the [`Manager`], if it has one, so that any [`rayon`]
+    /// parallelism inside it uses that pool.
+    pub fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        match &self.thread_pool {
+            Some(pool) => pool.install(op),
+            None => op(),
+        }
+    }
+
+    /// Set (or remove) the [`IntensityPolicy`] of the [`Manager`] for events whose intensity is
+    /// zero, negative, or not finite. Without a policy, these intensities are returned as they
+    /// are.
+    #[must_use]
+    pub const fn with_intensity_policy(
+        mut self,
+        intensity_policy: Option<IntensityPolicy<F>>,
+    ) -> Self {
+        self.intensity_policy = intensity_policy;
+        self
+    }
+
+    /// Lists every event whose intensity is zero, negative, or not finite for the given free
+    /// parameters, along with the value of each active [`Amplitude`] on it. The
+    /// [`IntensityPolicy`] of the [`Manager`] is not applied.
     ///
     /// # Errors
     ///
     /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
     /// [`Model::compute`] for more information.
-    pub fn evaluate(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
-        let pars: Vec<F> = self
+    pub fn invalid_intensities(
+        &self,
+        parameters: &[F],
+    ) -> Result<Vec<InvalidIntensity<F>>, RustitudeError> {
+        let values = self.evaluate_unchecked(parameters)?;
+        let invalid: Vec<usize> = (0..values.len())
+            .filter(|&i| !is_valid_intensity(values[i]))
+            .collect();
+        self.describe_invalid(parameters, &values, &invalid, None)
+    }
+
+    /// Breaks the intensity of the event at `index` down into the value of every [`Amplitude`],
+    /// the value of each term of every coherent sum, and the absolute square of each coherent
+    /// sum (see [`EventReport`]).
+    ///
+    /// This is meant for debugging a [`Model`] which misbehaves on a handful of events, such as
+    /// those listed by [`Manager::invalid_intensities`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if `index` is out of
+    /// range, or a [`RustitudeError`] if the amplitude calculation fails (see [`Model::compute`]).
+    pub fn debug_event(
+        &self,
+        parameters: &[F],
+        index: usize,
+    ) -> Result<EventReport<F>, RustitudeError> {
+        let Some(event) = self.dataset.events.get(index) else {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "event {index} is out of range for a dataset with {} events",
+                self.dataset.len()
+            )));
+        };
+        let pars = self.model.physical_parameters(parameters);
+        let amplitudes = self.model.amplitudes.read();
+        let values = amplitudes
+            .iter()
+            .map(|amp| {
+                if amp.active {
+                    amp.calculate(&pars, event).map(Some)
+                } else {
+                    Ok(None)
+                }
+            })
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        let cohsums: Vec<CohSumReport<F>> = self
             .model
-            .parameters
+            .cohsums
             .iter()
-            .map(|p| p.index.map_or_else(|| p.initial, |i| parameters[i]))
+            .map(|cohsum| {
+                let terms = match cohsum.to_amp_tree() {
+                    AmpTree::Sum(formulas) => formulas
+                        .iter()
+                        .map(ToString::to_string)
+                        .zip(cohsum.term.get_cloned_terms().unwrap_or_default())
+                        .map(|(formula, term)| (formula, term.compute(&values)))
+                        .collect(),
+                    tree => vec![(tree.to_string(), cohsum.term.compute(&values))],
+                };
+                CohSumReport {
+                    name: cohsum.name.clone(),
+                    active: cohsum.active,
+                    terms,
+                    value: cohsum.term.compute(&values),
+                    intensity: cohsum.compute(&values),
+                }
+            })
             .collect();
+        Ok(EventReport {
+            index,
+            amplitudes: amplitudes
+                .iter()
+                .map(|amp| amp.name.clone())
+                .zip(values)
+                .collect(),
+            intensity: cohsums.iter().filter_map(|cohsum| cohsum.intensity).sum(),
+            cohsums,
+        })
+    }
+
+    /// Builds an [`InvalidIntensity`] for each of the given positions in `values`, which are
+    /// positions in `indices` if given.
+    fn describe_invalid(
+        &self,
+        parameters: &[F],
+        values: &[F],
+        positions: &[usize],
+        indices: Option<&[usize]>,
+    ) -> Result<Vec<InvalidIntensity<F>>, RustitudeError> {
+        let pars = self.model.physical_parameters(parameters);
         let amplitudes = self.model.amplitudes.read();
-        self.dataset
-            .events
+        positions
             .iter()
-            .map(|event: &Event<F>| self.model.compute(&amplitudes, &pars, event))
+            .map(|&position| {
+                let index = indices.map_or(position, |indices| indices[position]);
+                let event = &self.dataset.events[index];
+                Ok(InvalidIntensity {
+                    index,
+                    intensity: values[position],
+                    amplitudes: amplitudes
+                        .iter()
+                        .filter(|amp| amp.active)
+                        .map(|amp| Ok((amp.name.clone(), amp.calculate(&pars, event)?)))
+                        .collect::<Result<_, RustitudeError>>()?,
+                })
+            })
             .collect()
     }
 
+    /// Applies the [`IntensityPolicy`] of the [`Manager`] to the evaluated intensities, which
+    /// belong to the events at `indices` if given.
+    fn apply_intensity_policy(
+        &self,
+        parameters: &[F],
+        mut values: Vec<F>,
+        indices: Option<&[usize]>,
+    ) -> Result<Vec<F>, RustitudeError> {
+        if self.intensity_policy.is_none() {
+            return Ok(values);
+        }
+        let invalid: Vec<usize> = (0..values.len())
+            .filter(|&i| !is_valid_intensity(values[i]))
+            .collect();
+        self.report_invalid(parameters, &values, &invalid, indices)?;
+        for &i in &invalid {
+            if let Some(replacement) = self.policy_replacement(values[i]) {
+                values[i] = replacement;
+            }
+        }
+        Ok(values)
+    }
+
+    /// Returns the value which replaces an invalid intensity under the [`IntensityPolicy`] of the
+    /// [`Manager`], or [`None`] if the intensity is kept as it is.
+    fn policy_replacement(&self, value: F) -> Option<F> {
+        if is_valid_intensity(value) {
+            return None;
+        }
+        match self.intensity_policy? {
+            IntensityPolicy::Error => None,
+            IntensityPolicy::Clamp(replacement) => Some(replacement),
+            IntensityPolicy::Skip => Some(F::zero()),
+        }
+    }
+
+    /// Reports the invalid intensities at the given positions in `values` (which are positions
+    /// in `indices` if given) as required by the [`IntensityPolicy`] of the [`Manager`], either
+    /// as an error or as a warning.
+    fn report_invalid(
+        &self,
+        parameters: &[F],
+        values: &[F],
+        invalid: &[usize],
+        indices: Option<&[usize]>,
+    ) -> Result<(), RustitudeError> {
+        if invalid.is_empty() {
+            return Ok(());
+        }
+        let report = || -> Result<String, RustitudeError> {
+            let shown = &invalid[..invalid.len().min(MAX_INVALID_REPORTS)];
+            let mut report = format!(
+                "{} events have a zero, negative, or non-finite intensity",
+                invalid.len()
+            );
+            for invalid in self.describe_invalid(parameters, values, shown, indices)? {
+                report.push_str(&format!("\n  {invalid}"));
+            }
+            if invalid.len() > shown.len() {
+                report.push_str(&format!("\n  ... and {} more", invalid.len() - shown.len()));
+            }
+            Ok(report)
+        };
+        match self.intensity_policy {
+            Some(IntensityPolicy::Error) => Err(RustitudeError::EvaluationError(report()?)),
+            Some(IntensityPolicy::Skip) => {
+                warn!("skipping events: {}", report()?);
+                Ok(())
+            }
+            Some(IntensityPolicy::Clamp(_)) | None => Ok(()),
+        }
+    }
+
+    /// Reports the `(index, intensity)` pairs of the invalid events found while summing over
+    /// the [`Dataset`] (see [`Manager::report_invalid`]).
+    fn report_invalid_events(
+        &self,
+        parameters: &[F],
+        mut invalid: Vec<(usize, F)>,
+    ) -> Result<(), RustitudeError> {
+        invalid.sort_unstable_by_key(|(index, _)| *index);
+        let (indices, values): (Vec<usize>, Vec<F>) = invalid.into_iter().unzip();
+        let positions: Vec<usize> = (0..indices.len()).collect();
+        self.report_invalid(parameters, &values, &positions, Some(&indices))
+    }
+
+    /// Enable or disable incremental evaluation.
+    ///
+    /// With incremental evaluation, [`Manager::evaluate`] and [`Manager::par_evaluate`] keep the
+    /// value of every active [`Amplitude`] on every event, and on the next call only recalculate
+    /// the [`Amplitude`]s whose (physical) parameters changed. Scans over a single parameter,
+    /// such as a piecewise bin or a likelihood profile, then only recalculate the
+    /// [`Amplitude`] which owns it. This costs one complex value per [`Amplitude`] per event of
+    /// memory, and enabling it again clears any cached values.
+    ///
+    /// The cache assumes that the value of an [`Amplitude`] on an event only depends on its own
+    /// parameters, so it should be disabled (or re-enabled to clear it) if a [`Node`] is
+    /// modified in place.
+    #[must_use]
+    pub fn with_incremental(mut self, incremental: bool) -> Self {
+        self.incremental = incremental.then(IncrementalCache::default);
+        self
+    }
+
+    /// Evaluates the [`Model`] over the [`Dataset`], reusing the values of [`Amplitude`]s whose
+    /// parameters have not changed since the last call (see [`Manager::with_incremental`]).
+    fn evaluate_incremental(
+        &self,
+        parameters: &[F],
+        cache: &IncrementalCache<F>,
+        parallel: bool,
+    ) -> Result<Vec<F>, RustitudeError> {
+        let pars = self.model.physical_parameters(parameters);
+        let amplitudes = self.model.amplitudes.read();
+        let mut cache = cache.0.write();
+        let (last, mut values) = cache
+            .take()
+            .filter(|(last, values)| {
+                last.len() == pars.len()
+                    && values.len() == self.dataset.len()
+                    && values.iter().all(|v| v.len() == amplitudes.len())
+            })
+            .unwrap_or_else(|| {
+                (
+                    Vec::new(),
+                    vec![vec![None; amplitudes.len()]; self.dataset.len()],
+                )
+            });
+        let changed: Vec<bool> = amplitudes
+            .iter()
+            .map(|amp| {
+                let range =
+                    amp.parameter_index_start..amp.parameter_index_start + amp.parameters.len();
+                last.is_empty() || last[range.clone()] != pars[range]
+            })
+            .collect();
+        let update = |(event, values): (&Event<F>, &mut Vec<Option<Complex<F>>>)| {
+            for ((value, amp), changed) in values.iter_mut().zip(amplitudes.iter()).zip(&changed) {
+                if !amp.active {
+                    *value = None;
+                } else if *changed || value.is_none() {
+                    *value = Some(amp.calculate(&pars, event)?);
+                }
+            }
+            self.model
+                .compute_precomputed(&amplitudes, values, &pars, event)
+        };
+        let result = if parallel {
+            self.dataset
+                .events
+                .par_iter()
+                .zip(values.par_iter_mut())
+                .map(update)
+                .collect::<Result<Vec<F>, RustitudeError>>()
+        } else {
+            self.dataset
+                .events
+                .iter()
+                .zip(values.iter_mut())
+                .map(update)
+                .collect::<Result<Vec<F>, RustitudeError>>()
+        };
+        // A failed evaluation leaves the cache empty, since some values may be stale
+        if result.is_ok() {
+            *cache = Some((pars, values));
+        }
+        drop(cache);
+        drop(amplitudes);
+        result
+    }
+
+    /// Evaluates the [`Model`] over contiguous chunks of [`BATCH_SIZE`] events with
+    /// [`Model::compute_batch`] (used when the `simd` feature is enabled).
+    fn evaluate_batched(&self, parameters: &[F], parallel: bool) -> Result<Vec<F>, RustitudeError> {
+        let pars = self.model.physical_parameters(parameters);
+        let amplitudes = self.model.amplitudes.read();
+        let precomputed = self.precompute(&amplitudes, &pars)?;
+        let mut output = vec![F::zero(); self.dataset.len()];
+        let compute = |(events, out): (&[Event<F>], &mut [F])| {
+            self.model
+                .compute_batch(&amplitudes, &precomputed, &pars, events, out)
+        };
+        if parallel {
+            self.dataset
+                .events
+                .par_chunks(BATCH_SIZE)
+                .zip(output.par_chunks_mut(BATCH_SIZE))
+                .try_for_each(compute)?;
+        } else {
+            self.dataset
+                .events
+                .chunks(BATCH_SIZE)
+                .zip(output.chunks_mut(BATCH_SIZE))
+                .try_for_each(compute)?;
+        }
+        drop(amplitudes);
+        Ok(output)
+    }
+
+    /// Evaluate the [`Model`] over the [`Dataset`] with the given free parameters.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails (see
+    /// [`Model::compute`] for more information) or if an event violates an
+    /// [`IntensityPolicy::Error`].
+    pub fn evaluate(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        let values = self.evaluate_unchecked(parameters)?;
+        self.apply_intensity_policy(parameters, values, None)
+    }
+
+    /// Evaluates the [`Model`] over the [`Dataset`] without applying the [`IntensityPolicy`].
+    fn evaluate_unchecked(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        if let Some(cache) = &self.incremental {
+            return self.evaluate_incremental(parameters, cache, false);
+        }
+        if cfg!(feature = "simd") {
+            return self.evaluate_batched(parameters, false);
+        }
+        let pars = self.model.physical_parameters(parameters);
+        let amplitudes = self.model.amplitudes.read();
+        self.precompute(&amplitudes, &pars).and_then(|precomputed| {
+            let mut scratch = Vec::with_capacity(amplitudes.len());
+            self.dataset
+                .events
+                .iter()
+                .map(|event: &Event<F>| {
+                    self.model
+                        .compute_into(&amplitudes, &precomputed, &pars, event, &mut scratch)
+                })
+                .collect()
+        })
+    }
+
     /// Evaluate the [`Model`] over the [`Dataset`] with the given free parameters.
     ///
     /// This method allows the user to supply a list of indices and will only evaluate events at
@@ -81,12 +635,23 @@ impl<F: Field> Manager<F> {
     ///
     /// # Errors
     ///
-    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
-    /// [`Model::compute`] for more information.
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails (see
+    /// [`Model::compute`] for more information) or if an event violates an
+    /// [`IntensityPolicy::Error`].
     pub fn evaluate_indexed(
         &self,
         parameters: &[F],
         indices: &[usize],
+    ) -> Result<Vec<F>, RustitudeError> {
+        let values = self.evaluate_indexed_unchecked(parameters, indices)?;
+        self.apply_intensity_policy(parameters, values, Some(indices))
+    }
+
+    /// Version of [`Manager::evaluate_indexed`] which does not apply the [`IntensityPolicy`].
+    fn evaluate_indexed_unchecked(
+        &self,
+        parameters: &[F],
+        indices: &[usize],
     ) -> Result<Vec<F>, RustitudeError> {
         if self.model.contains_python_amplitudes {
             return Err(RustitudeError::PythonError(
@@ -94,20 +659,23 @@ impl<F: Field> Manager<F> {
                     .to_string(),
             ));
         }
-        let pars: Vec<F> = self
-            .model
-            .parameters
-            .iter()
-            .map(|p| p.index.map_or_else(|| p.initial, |i| parameters[i]))
-            .collect();
+        let pars = self.model.physical_parameters(parameters);
         let amplitudes = self.model.amplitudes.read();
-        indices
-            .iter()
-            .map(|index| {
-                self.model
-                    .compute(&amplitudes, &pars, &self.dataset.events[*index])
-            })
-            .collect()
+        self.precompute(&amplitudes, &pars).and_then(|precomputed| {
+            let mut scratch = Vec::with_capacity(amplitudes.len());
+            indices
+                .iter()
+                .map(|index| {
+                    self.model.compute_into(
+                        &amplitudes,
+                        &precomputed,
+                        &pars,
+                        &self.dataset.events[*index],
+                        &mut scratch,
+                    )
+                })
+                .collect()
+        })
     }
 
     /// Evaluate the [`Model`] over the [`Dataset`] with the given free parameters.
@@ -116,29 +684,52 @@ impl<F: Field> Manager<F> {
     ///
     /// # Errors
     ///
-    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
-    /// [`Model::compute`] for more information.
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails (see
+    /// [`Model::compute`] for more information) or if an event violates an
+    /// [`IntensityPolicy::Error`].
     pub fn par_evaluate(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
-        if self.model.contains_python_amplitudes {
-            return Err(RustitudeError::PythonError(
-                "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
-                    .to_string(),
-            ));
-        }
-        let mut output = Vec::with_capacity(self.dataset.len());
-        let pars: Vec<F> = self
-            .model
-            .parameters
-            .iter()
-            .map(|p| p.index.map_or_else(|| p.initial, |i| parameters[i]))
-            .collect();
-        let amplitudes = self.model.amplitudes.read();
-        self.dataset
-            .events
-            .par_iter()
-            .map(|event| self.model.compute(&amplitudes, &pars, event))
-            .collect_into_vec(&mut output);
-        output.into_iter().collect()
+        let values = self.par_evaluate_unchecked(parameters)?;
+        self.apply_intensity_policy(parameters, values, None)
+    }
+
+    /// Version of [`Manager::par_evaluate`] which does not apply the [`IntensityPolicy`].
+    fn par_evaluate_unchecked(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        self.install(|| {
+            if self.model.contains_python_amplitudes {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            if let Some(cache) = &self.incremental {
+                return self.evaluate_incremental(parameters, cache, true);
+            }
+            if cfg!(feature = "simd") {
+                return self.evaluate_batched(parameters, true);
+            }
+            let mut output = Vec::with_capacity(self.dataset.len());
+            let pars = self.model.physical_parameters(parameters);
+            let amplitudes = self.model.amplitudes.read();
+            self.precompute(&amplitudes, &pars).and_then(|precomputed| {
+                self.dataset
+                    .events
+                    .par_iter()
+                    .map_init(
+                        || Vec::with_capacity(amplitudes.len()),
+                        |scratch, event| {
+                            self.model.compute_into(
+                                &amplitudes,
+                                &precomputed,
+                                &pars,
+                                event,
+                                scratch,
+                            )
+                        },
+                    )
+                    .collect_into_vec(&mut output);
+                output.into_iter().collect()
+            })
+        })
     }
 
     /// Evaluate the [`Model`] over the [`Dataset`] with the given free parameters.
@@ -151,94 +742,592 @@ impl<F: Field> Manager<F> {
     ///
     /// # Errors
     ///
-    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
-    /// [`Model::compute`] for more information.
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails (see
+    /// [`Model::compute`] for more information) or if an event violates an
+    /// [`IntensityPolicy::Error`].
     pub fn par_evaluate_indexed(
         &self,
         parameters: &[F],
         indices: &[usize],
     ) -> Result<Vec<F>, RustitudeError> {
-        if self.model.contains_python_amplitudes {
-            return Err(RustitudeError::PythonError(
-                "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
-                    .to_string(),
-            ));
-        }
-        let mut output = Vec::with_capacity(indices.len());
-        let pars: Vec<F> = self
-            .model
-            .parameters
-            .iter()
-            .map(|p| p.index.map_or_else(|| p.initial, |i| parameters[i]))
-            .collect();
-        // indices
-        //     .par_iter()
-        //     .map(|index| self.model.compute(&pars, &self.dataset.events[*index]))
-        //     .collect_into_vec(&mut output);
-        let amplitudes = self.model.amplitudes.read();
-        let view: Vec<&Event<F>> = indices
-            .par_iter()
-            .map(|&index| &self.dataset.events[index])
-            .collect();
-        view.par_iter()
-            .map(|&event| self.model.compute(&amplitudes, &pars, event))
-            .collect_into_vec(&mut output);
-        output.into_iter().collect()
+        let values = self.par_evaluate_indexed_unchecked(parameters, indices)?;
+        self.apply_intensity_policy(parameters, values, Some(indices))
     }
 
-    /// Get a copy of an [`Amplitude`] in the [`Model`] by name.
+    /// Version of [`Manager::par_evaluate_indexed`] which does not apply the
+    /// [`IntensityPolicy`].
+    fn par_evaluate_indexed_unchecked(
+        &self,
+        parameters: &[F],
+        indices: &[usize],
+    ) -> Result<Vec<F>, RustitudeError> {
+        self.install(|| {
+            if self.model.contains_python_amplitudes {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            let mut output = Vec::with_capacity(indices.len());
+            let pars = self.model.physical_parameters(parameters);
+            // indices
+            //     .par_iter()
+            //     .map(|index| self.model.compute(&pars, &self.dataset.events[*index]))
+            //     .collect_into_vec(&mut output);
+            let amplitudes = self.model.amplitudes.read();
+            let view: Vec<&Event<F>> = indices
+                .par_iter()
+                .map(|&index| &self.dataset.events[index])
+                .collect();
+            self.precompute(&amplitudes, &pars).and_then(|precomputed| {
+                view.par_iter()
+                    .map_init(
+                        || Vec::with_capacity(amplitudes.len()),
+                        |scratch, &event| {
+                            self.model.compute_into(
+                                &amplitudes,
+                                &precomputed,
+                                &pars,
+                                event,
+                                scratch,
+                            )
+                        },
+                    )
+                    .collect_into_vec(&mut output);
+                output.into_iter().collect()
+            })
+        })
+    }
+
+    /// Evaluate the [`Model`] over the [`Dataset`] for each of several sets of free parameters,
+    /// such as the points of a likelihood scan or the fits of a toy study.
+    ///
+    /// The result holds one [`Vec`] of intensities (as returned by [`Manager::evaluate`], with the
+    /// [`IntensityPolicy`] applied to each parameter set) per parameter set. The [`Amplitude`]s
+    /// are locked once for the whole grid and any precalculated data is shared between the
+    /// parameter sets, so this avoids the setup cost of a separate call per set. Incremental
+    /// evaluation (see [`Manager::with_incremental`]) is not used.
     ///
     /// # Errors
     ///
-    /// This method will return a [`RustitudeError`] if there is no amplitude found with the given
-    /// name in the parent [`Model`]. See [`Model::get_amplitude`] for more information.
-    pub fn get_amplitude(&self, amplitude_name: &str) -> Result<Amplitude<F>, RustitudeError> {
-        self.model.get_amplitude(amplitude_name)
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails for any
+    /// parameter set (see [`Model::compute`] for more information) or if an event violates an
+    /// [`IntensityPolicy::Error`].
+    pub fn evaluate_many(&self, parameter_sets: &[Vec<F>]) -> Result<Vec<Vec<F>>, RustitudeError> {
+        let amplitudes = self.model.amplitudes.read();
+        let mut scratch = Vec::with_capacity(amplitudes.len());
+        let values = parameter_sets
+            .iter()
+            .map(|parameters| {
+                let pars = self.model.physical_parameters(parameters);
+                let precomputed = self.precompute(&amplitudes, &pars)?;
+                self.dataset
+                    .events
+                    .iter()
+                    .map(|event| {
+                        self.model.compute_into(
+                            &amplitudes,
+                            &precomputed,
+                            &pars,
+                            event,
+                            &mut scratch,
+                        )
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<Vec<F>>, RustitudeError>>();
+        drop(amplitudes);
+        parameter_sets
+            .iter()
+            .zip(values?)
+            .map(|(parameters, values)| self.apply_intensity_policy(parameters, values, None))
+            .collect()
     }
 
-    /// Get a copy of a [`Parameter`] in a [`Model`] by name and the name of the parent
-    /// [`Amplitude`].
+    /// Evaluate the [`Model`] over the [`Dataset`] for each of several sets of free parameters.
+    ///
+    /// This version uses a single parallel loop over every pair of parameter set and event, so
+    /// it stays efficient for both large grids over small [`Dataset`]s and small grids over large
+    /// [`Dataset`]s. See [`Manager::evaluate_many`] for more information.
     ///
     /// # Errors
     ///
-    /// This method will return a [`RustitudeError`] if there is no parameter found with the given
-    /// name in the parent [`Model`]. It will also first check if the given amplitude exists, and
-    /// this method can also fail in the same way (see [`Model::get_amplitude`] and
-    /// [`Model::get_parameter`]).
-    pub fn get_parameter(
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails for any
+    /// parameter set (see [`Model::compute`] for more information) or if an event violates an
+    /// [`IntensityPolicy::Error`].
+    pub fn par_evaluate_many(
         &self,
-        amplitude_name: &str,
-        parameter_name: &str,
-    ) -> Result<Parameter<F>, RustitudeError> {
-        self.model.get_parameter(amplitude_name, parameter_name)
-    }
-
-    /// Print the free parameters in the [`Model`]. See [`Model::print_parameters`] for more
-    /// information.
-    pub fn print_parameters(&self) {
-        self.model.print_parameters()
+        parameter_sets: &[Vec<F>],
+    ) -> Result<Vec<Vec<F>>, RustitudeError> {
+        self.install(|| {
+            if self.model.contains_python_amplitudes {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            let n_events = self.dataset.len();
+            if n_events == 0 {
+                return Ok(vec![Vec::new(); parameter_sets.len()]);
+            }
+            let amplitudes = self.model.amplitudes.read();
+            let prepared = parameter_sets
+                .iter()
+                .map(|parameters| {
+                    let pars = self.model.physical_parameters(parameters);
+                    let precomputed = self.precompute(&amplitudes, &pars)?;
+                    Ok((pars, precomputed))
+                })
+                .collect::<Result<Vec<_>, RustitudeError>>()?;
+            let mut output = Vec::with_capacity(prepared.len() * n_events);
+            (0..prepared.len() * n_events)
+                .into_par_iter()
+                .map_init(
+                    || Vec::with_capacity(amplitudes.len()),
+                    |scratch, k| {
+                        let (pars, precomputed) = &prepared[k / n_events];
+                        self.model.compute_into(
+                            &amplitudes,
+                            precomputed,
+                            pars,
+                            &self.dataset.events[k % n_events],
+                            scratch,
+                        )
+                    },
+                )
+                .collect_into_vec(&mut output);
+            drop(amplitudes);
+            let values = output.into_iter().collect::<Result<Vec<F>, _>>()?;
+            parameter_sets
+                .iter()
+                .zip(values.chunks(n_events))
+                .map(|(parameters, values)| {
+                    self.apply_intensity_policy(parameters, values.to_vec(), None)
+                })
+                .collect()
+        })
     }
 
-    /// Returns a [`Vec<Parameter<F>>`] containing the free parameters in the [`Model`].
+    /// Evaluate the interference between two groups of [`Amplitude`]s over the [`Dataset`] with
+    /// the given free parameters. See [`Model::compute_interference`] for more information.
     ///
-    /// See [`Model::free_parameters`] for more information.
-    pub fn free_parameters(&self) -> Vec<Parameter<F>> {
-        self.model.free_parameters()
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute`] for more information.
+    pub fn interference(
+        &self,
+        parameters: &[F],
+        amplitudes_a: &[&str],
+        amplitudes_b: &[&str],
+    ) -> Result<Vec<F>, RustitudeError> {
+        let pars = self.model.physical_parameters(parameters);
+        let amplitudes = self.model.amplitudes.read();
+        self.dataset
+            .events
+            .iter()
+            .map(|event| {
+                self.model.compute_interference(
+                    &amplitudes,
+                    &pars,
+                    event,
+                    amplitudes_a,
+                    amplitudes_b,
+                )
+            })
+            .collect()
     }
 
-    /// Returns a [`Vec<Parameter<F>>`] containing the fixed parameters in the [`Model`].
+    /// Evaluate the interference between two groups of [`Amplitude`]s over the [`Dataset`] with
+    /// the given free parameters.
     ///
-    /// See [`Model::fixed_parameters`] for more information.
-    pub fn fixed_parameters(&self) -> Vec<Parameter<F>> {
-        self.model.fixed_parameters()
+    /// This version uses a parallel loop over events.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute`] for more information.
+    pub fn par_interference(
+        &self,
+        parameters: &[F],
+        amplitudes_a: &[&str],
+        amplitudes_b: &[&str],
+    ) -> Result<Vec<F>, RustitudeError> {
+        self.install(|| {
+            if self.model.contains_python_amplitudes {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            let pars = self.model.physical_parameters(parameters);
+            let amplitudes = self.model.amplitudes.read();
+            self.dataset
+                .events
+                .par_iter()
+                .map(|event| {
+                    self.model.compute_interference(
+                        &amplitudes,
+                        &pars,
+                        event,
+                        amplitudes_a,
+                        amplitudes_b,
+                    )
+                })
+                .collect()
+        })
     }
 
-    /// Constrain two parameters by name, reducing the number of free parameters by one.
+    /// Evaluate the absolute square of a single coherent sum (the `sum_index`-th entry of
+    /// [`Model::cohsums`]) over the [`Dataset`] with the given free parameters.
+    ///
+    /// This is the contribution of the coherent sum to [`Manager::evaluate`], so the results for
+    /// the active coherent sums add up to the full intensity. The coherent sum is evaluated even
+    /// if it has been deactivated (see [`Model::deactivate_sum`]), but inactive [`Amplitude`]s
+    /// are still excluded, and only the [`Amplitude`]s it contains are calculated.
     ///
     /// # Errors
     ///
-    /// This method will fail if any of the given amplitude or parameter names don't correspond to
-    /// a valid amplitude-parameter pair. See [`Model::constrain`] for more information.
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if `sum_index` is out
+    /// of range, or a [`RustitudeError`] if the amplitude calculation fails (see
+    /// [`Model::compute`] for more information).
+    pub fn evaluate_cohsum(
+        &self,
+        parameters: &[F],
+        sum_index: usize,
+    ) -> Result<Vec<F>, RustitudeError> {
+        self.evaluate_cohsum_with(parameters, sum_index, false)
+    }
+
+    /// Evaluate the absolute square of a single coherent sum over the [`Dataset`] with the given
+    /// free parameters.
+    ///
+    /// This version uses a parallel loop over events. See [`Manager::evaluate_cohsum`] for more
+    /// information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if `sum_index` is out
+    /// of range, or a [`RustitudeError`] if the amplitude calculation fails (see
+    /// [`Model::compute`] for more information).
+    pub fn par_evaluate_cohsum(
+        &self,
+        parameters: &[F],
+        sum_index: usize,
+    ) -> Result<Vec<F>, RustitudeError> {
+        self.install(|| {
+            if self.model.contains_python_amplitudes {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            self.evaluate_cohsum_with(parameters, sum_index, true)
+        })
+    }
+
+    /// Shared implementation of [`Manager::evaluate_cohsum`] and
+    /// [`Manager::par_evaluate_cohsum`].
+    fn evaluate_cohsum_with(
+        &self,
+        parameters: &[F],
+        sum_index: usize,
+        parallel: bool,
+    ) -> Result<Vec<F>, RustitudeError> {
+        let Some(cohsum) = self.model.cohsums.get(sum_index) else {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "coherent sum {sum_index} is out of range for a model with {} coherent sums",
+                self.model.cohsums.len()
+            )));
+        };
+        let pars = self.model.physical_parameters(parameters);
+        let amplitudes = self.model.amplitudes.read();
+        let precomputed = self.precompute(&amplitudes, &pars)?;
+        let used: HashSet<usize> = cohsum.walk().iter().map(|amp| amp.cache_position).collect();
+        let compute = |event: &Event<F>| {
+            let values = amplitudes
+                .iter()
+                .enumerate()
+                .map(|(i, amp)| {
+                    if let Some(Some(value)) = precomputed.get(i) {
+                        Ok(Some(*value))
+                    } else if amp.active && used.contains(&i) {
+                        amp.calculate(&pars, event).map(Some)
+                    } else {
+                        Ok(None)
+                    }
+                })
+                .collect::<Result<Vec<_>, RustitudeError>>()?;
+            Ok(cohsum
+                .term
+                .compute(&values)
+                .map_or_else(F::zero, |value| value.norm_sqr()))
+        };
+        let result = if parallel {
+            self.dataset.events.par_iter().map(compute).collect()
+        } else {
+            self.dataset.events.iter().map(compute).collect()
+        };
+        drop(amplitudes);
+        result
+    }
+
+    /// Calculates the event-independent [`Amplitude`]s once for the whole [`Dataset`] (see
+    /// [`Model::precompute`]).
+    fn precompute(
+        &self,
+        amplitudes: &[Amplitude<F>],
+        parameters: &[F],
+    ) -> Result<Vec<Option<Complex<F>>>, RustitudeError> {
+        self.dataset.events.first().map_or_else(
+            || Ok(Vec::new()),
+            |event| self.model.precompute(amplitudes, parameters, event),
+        )
+    }
+
+    /// Gradient version of [`Manager::precompute`] (see [`Model::precompute_gradient`]).
+    #[allow(clippy::type_complexity)]
+    fn precompute_gradient(
+        &self,
+        amplitudes: &[Amplitude<F>],
+        parameters: &[F],
+    ) -> Result<Vec<Option<(Complex<F>, Vec<Complex<F>>)>>, RustitudeError> {
+        self.dataset.events.first().map_or_else(
+            || Ok(Vec::new()),
+            |event| {
+                self.model
+                    .precompute_gradient(amplitudes, parameters, event)
+            },
+        )
+    }
+
+    /// Computes the value and the gradient with respect to the free parameters of the intensity
+    /// of every [`Event`] in the [`Dataset`] (see [`Model::compute_gradient`]).
+    fn event_gradients(
+        &self,
+        parameters: &[F],
+        parallel: bool,
+    ) -> Result<Vec<(F, Vec<F>)>, RustitudeError> {
+        let pars = self.model.physical_parameters(parameters);
+        let amplitudes = self.model.amplitudes.read();
+        self.precompute_gradient(&amplitudes, &pars)
+            .and_then(|precomputed| {
+                let compute = |event: &Event<F>| {
+                    self.model
+                        .compute_gradient_precomputed(&amplitudes, &precomputed, &pars, event)
+                        .map(|(value, gradient)| {
+                            (value, self.model.free_gradient(parameters, &gradient))
+                        })
+                };
+                if parallel {
+                    self.dataset.events.par_iter().map(compute).collect()
+                } else {
+                    self.dataset.events.iter().map(compute).collect()
+                }
+            })
+    }
+
+    /// Sums `weight(i, value, event) * gradient` over the [`Dataset`], where `i` is the position
+    /// of the [`Event`] in the [`Dataset`] and `value` and `gradient` come from
+    /// [`Model::compute_gradient`]. The result is a gradient with respect to every [`Parameter`]
+    /// in the [`Model`].
+    pub(crate) fn gradient_sum(
+        &self,
+        parameters: &[F],
+        weight: impl Fn(usize, F, &Event<F>) -> F,
+    ) -> Result<Vec<F>, RustitudeError> {
+        self.value_gradient_sum(parameters, |i, value, event| {
+            (F::zero(), weight(i, value, event))
+        })
+        .map(|(_, gradient)| gradient)
+    }
+
+    /// Parallel version of [`Manager::gradient_sum`].
+    pub(crate) fn par_gradient_sum(
+        &self,
+        parameters: &[F],
+        weight: impl Fn(usize, F, &Event<F>) -> F + Sync,
+    ) -> Result<Vec<F>, RustitudeError> {
+        self.install(|| {
+            self.par_value_gradient_sum(parameters, |i, value, event| {
+                (F::zero(), weight(i, value, event))
+            })
+            .map(|(_, gradient)| gradient)
+        })
+    }
+
+    /// Computes [`Manager::gradient_sum`] along with a sum of per-event values in the same pass
+    /// over the [`Dataset`], where `term(i, value, event)` returns the value to add to the sum and
+    /// the weight of the gradient.
+    fn value_gradient_sum(
+        &self,
+        parameters: &[F],
+        term: impl Fn(usize, F, &Event<F>) -> (F, F),
+    ) -> Result<(F, Vec<F>), RustitudeError> {
+        let pars = self.model.physical_parameters(parameters);
+        let amplitudes = self.model.amplitudes.read();
+        let (sum, total, invalid) =
+            self.precompute_gradient(&amplitudes, &pars)
+                .and_then(|precomputed| {
+                    let mut sum = F::zero();
+                    let mut total = vec![F::zero(); pars.len()];
+                    let mut invalid = Vec::new();
+                    for (i, event) in self.dataset.events.iter().enumerate() {
+                        let (value, gradient) = self.model.compute_gradient_precomputed(
+                            &amplitudes,
+                            &precomputed,
+                            &pars,
+                            event,
+                        )?;
+                        if self.intensity_policy.is_some() && !is_valid_intensity(value) {
+                            invalid.push((i, value));
+                        }
+                        let (v, w) = self.policy_term(&term, i, value, event);
+                        sum += v;
+                        if let Some(w) = w {
+                            total
+                                .iter_mut()
+                                .zip(gradient)
+                                .for_each(|(t, g)| *t += w * g);
+                        }
+                    }
+                    Ok((sum, total, invalid))
+                })?;
+        drop(amplitudes);
+        self.report_invalid_events(parameters, invalid)?;
+        Ok((sum, total))
+    }
+
+    /// Evaluates `term` for a single event of [`Manager::value_gradient_sum`], returning the
+    /// value to add to the sum and the weight of the gradient.
+    ///
+    /// An intensity replaced by the [`IntensityPolicy`] of the [`Manager`] is passed to `term`
+    /// as its (constant) replacement, so it has no gradient and the weight is [`None`].
+    fn policy_term(
+        &self,
+        term: &impl Fn(usize, F, &Event<F>) -> (F, F),
+        i: usize,
+        value: F,
+        event: &Event<F>,
+    ) -> (F, Option<F>) {
+        self.policy_replacement(value).map_or_else(
+            || {
+                let (v, w) = term(i, value, event);
+                (v, Some(w))
+            },
+            |replacement| (term(i, replacement, event).0, None),
+        )
+    }
+
+    /// Parallel version of [`Manager::value_gradient_sum`].
+    fn par_value_gradient_sum(
+        &self,
+        parameters: &[F],
+        term: impl Fn(usize, F, &Event<F>) -> (F, F) + Sync,
+    ) -> Result<(F, Vec<F>), RustitudeError> {
+        self.install(|| {
+            let pars = self.model.physical_parameters(parameters);
+            let amplitudes = self.model.amplitudes.read();
+            let (sum, total, invalid) =
+                self.precompute_gradient(&amplitudes, &pars)
+                    .and_then(|precomputed| {
+                        self.dataset
+                            .events
+                            .par_iter()
+                            .enumerate()
+                            .try_fold(
+                                || (F::zero(), vec![F::zero(); pars.len()], Vec::new()),
+                                |(mut sum, mut total, mut invalid), (i, event)| {
+                                    let (value, gradient) =
+                                        self.model.compute_gradient_precomputed(
+                                            &amplitudes,
+                                            &precomputed,
+                                            &pars,
+                                            event,
+                                        )?;
+                                    if self.intensity_policy.is_some() && !is_valid_intensity(value)
+                                    {
+                                        invalid.push((i, value));
+                                    }
+                                    let (v, w) = self.policy_term(&term, i, value, event);
+                                    sum += v;
+                                    if let Some(w) = w {
+                                        total
+                                            .iter_mut()
+                                            .zip(gradient)
+                                            .for_each(|(t, g)| *t += w * g);
+                                    }
+                                    Ok((sum, total, invalid))
+                                },
+                            )
+                            .try_reduce(
+                                || (F::zero(), vec![F::zero(); pars.len()], Vec::new()),
+                                |(sum_a, mut a, mut invalid_a), (sum_b, b, invalid_b)| {
+                                    a.iter_mut().zip(b).for_each(|(x, y)| *x += y);
+                                    invalid_a.extend(invalid_b);
+                                    Ok((sum_a + sum_b, a, invalid_a))
+                                },
+                            )
+                    })?;
+            drop(amplitudes);
+            self.report_invalid_events(parameters, invalid)?;
+            Ok((sum, total))
+        })
+    }
+
+    /// Get a copy of an [`Amplitude`] in the [`Model`] by name.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if there is no amplitude found with the given
+    /// name in the parent [`Model`]. See [`Model::get_amplitude`] for more information.
+    pub fn get_amplitude(&self, amplitude_name: &str) -> Result<Amplitude<F>, RustitudeError> {
+        self.model.get_amplitude(amplitude_name)
+    }
+
+    /// Get a copy of a [`Parameter`] in a [`Model`] by name and the name of the parent
+    /// [`Amplitude`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if there is no parameter found with the given
+    /// name in the parent [`Model`]. It will also first check if the given amplitude exists, and
+    /// this method can also fail in the same way (see [`Model::get_amplitude`] and
+    /// [`Model::get_parameter`]).
+    pub fn get_parameter(
+        &self,
+        amplitude_name: &str,
+        parameter_name: &str,
+    ) -> Result<Parameter<F>, RustitudeError> {
+        self.model.get_parameter(amplitude_name, parameter_name)
+    }
+
+    /// Print the free parameters in the [`Model`]. See [`Model::print_parameters`] for more
+    /// information.
+    pub fn print_parameters(&self) {
+        self.model.print_parameters()
+    }
+
+    /// Returns a [`Vec<Parameter<F>>`] containing the free parameters in the [`Model`].
+    ///
+    /// See [`Model::free_parameters`] for more information.
+    pub fn free_parameters(&self) -> Vec<Parameter<F>> {
+        self.model.free_parameters()
+    }
+
+    /// Returns a [`Vec<Parameter<F>>`] containing the fixed parameters in the [`Model`].
+    ///
+    /// See [`Model::fixed_parameters`] for more information.
+    pub fn fixed_parameters(&self) -> Vec<Parameter<F>> {
+        self.model.fixed_parameters()
+    }
+
+    /// Constrain two parameters by name, reducing the number of free parameters by one.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if any of the given amplitude or parameter names don't correspond to
+    /// a valid amplitude-parameter pair. See [`Model::constrain`] for more information.
     pub fn constrain(
         &mut self,
         amplitude_1: &str,
@@ -265,6 +1354,53 @@ impl<F: Field> Manager<F> {
         self.model.fix(amplitude, parameter, value)
     }
 
+    /// Add a Gaussian constraint to a parameter by name.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the given amplitude-parameter pair does not exist or if `sigma`
+    /// is not positive. See [`Model::add_gaussian_constraint`] for more information.
+    pub fn add_gaussian_constraint(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: F,
+        sigma: F,
+    ) -> Result<(), RustitudeError> {
+        self.model
+            .add_gaussian_constraint(amplitude, parameter, mean, sigma)
+    }
+
+    /// Add a Gaussian prior to a parameter by name.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the given amplitude-parameter pair does not exist or if `sigma`
+    /// is not positive. See [`Model::add_prior`] for more information.
+    pub fn add_prior(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: F,
+        sigma: F,
+    ) -> Result<(), RustitudeError> {
+        self.model.add_prior(amplitude, parameter, mean, sigma)
+    }
+
+    /// Add a custom penalty on a set of parameters given by `(amplitude, parameter)` names.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if any of the given amplitude-parameter pairs do not exist. See
+    /// [`Model::add_penalty`] for more information.
+    pub fn add_penalty(
+        &mut self,
+        parameters: &[(&str, &str)],
+        function: impl Fn(&[F]) -> Result<F, RustitudeError> + Send + Sync + 'static,
+    ) -> Result<(), RustitudeError> {
+        self.model.add_penalty(parameters, function)
+    }
+
     /// Free a fixed parameter by name.
     ///
     /// # Errors
@@ -290,6 +1426,21 @@ impl<F: Field> Manager<F> {
         self.model.set_bounds(amplitude, parameter, bounds)
     }
 
+    /// Set (or remove) the [`Transform`] of a parameter by name.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the given amplitude-parameter pair does not exist. See
+    /// [`Model::set_transform`] for more information.
+    pub fn set_transform(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        transform: Option<Transform<F>>,
+    ) -> Result<(), RustitudeError> {
+        self.model.set_transform(amplitude, parameter, transform)
+    }
+
     /// Set the initial value of a parameter by name.
     ///
     /// # Errors
@@ -305,6 +1456,64 @@ impl<F: Field> Manager<F> {
         self.model.set_initial(amplitude, parameter, initial)
     }
 
+    /// Fix every parameter whose name matches a pattern to the given value.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if no parameter matches the pattern. See [`Model::fix_matching`]
+    /// for more information.
+    pub fn fix_matching(&mut self, pattern: &str, value: F) -> Result<(), RustitudeError> {
+        self.model.fix_matching(pattern, value)
+    }
+
+    /// Free every fixed parameter whose name matches a pattern.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if no parameter matches the pattern. See [`Model::free_matching`]
+    /// for more information.
+    pub fn free_matching(&mut self, pattern: &str) -> Result<(), RustitudeError> {
+        self.model.free_matching(pattern)
+    }
+
+    /// Set the bounds of every parameter whose name matches a pattern.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if no parameter matches the pattern. See
+    /// [`Model::set_bounds_matching`] for more information.
+    pub fn set_bounds_matching(
+        &mut self,
+        pattern: &str,
+        bounds: (F, F),
+    ) -> Result<(), RustitudeError> {
+        self.model.set_bounds_matching(pattern, bounds)
+    }
+
+    /// Set the initial value of every parameter whose name matches a pattern.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if no parameter matches the pattern. See
+    /// [`Model::set_initial_matching`] for more information.
+    pub fn set_initial_matching(
+        &mut self,
+        pattern: &str,
+        initial: F,
+    ) -> Result<(), RustitudeError> {
+        self.model.set_initial_matching(pattern, initial)
+    }
+
+    /// Constrain every parameter whose name matches a pattern to the first match.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if no parameter matches the pattern. See
+    /// [`Model::constrain_matching`] for more information.
+    pub fn constrain_matching(&mut self, pattern: &str) -> Result<(), RustitudeError> {
+        self.model.constrain_matching(pattern)
+    }
+
     /// Get a list of bounds for all free parameters in the [`Model`]. See
     /// [`Model::get_bounds`] for more information.
     pub fn get_bounds(&self) -> Vec<(F, F)> {
@@ -359,197 +1568,2616 @@ impl<F: Field> Manager<F> {
     pub fn deactivate_all(&mut self) {
         self.model.deactivate_all()
     }
+    /// Retrieves a copy of a coherent sum by name. See [`Model::get_sum`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::SumNotFoundError`] if no coherent sum has
+    /// the given name.
+    pub fn get_sum(&self, name: &str) -> Result<NormSqr<F>, RustitudeError> {
+        self.model.get_sum(name)
+    }
+    /// Activate the coherent sums with the given name. See [`Model::activate_sum`] for more
+    /// information.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::SumNotFoundError`] if no coherent sum has
+    /// the given name.
+    pub fn activate_sum(&mut self, name: &str) -> Result<(), RustitudeError> {
+        self.model.activate_sum(name)
+    }
+    /// Deactivate the coherent sums with the given name. See [`Model::deactivate_sum`] for more
+    /// information.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::SumNotFoundError`] if no coherent sum has
+    /// the given name.
+    pub fn deactivate_sum(&mut self, name: &str) -> Result<(), RustitudeError> {
+        self.model.deactivate_sum(name)
+    }
+    /// Returns the [`ActivationSnapshot`] of the [`Model`]. See [`Model::activation_state`] for
+    /// more information.
+    pub fn activation_state(&self) -> ActivationSnapshot {
+        self.model.activation_state()
+    }
+    /// Restores the activation state of the [`Model`]. See [`Model::restore`] for more
+    /// information.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::AmplitudeNotFoundError`] or a
+    /// [`RustitudeError::InvalidParameterValue`] if the snapshot does not match the [`Model`].
+    pub fn restore(&mut self, snapshot: &ActivationSnapshot) -> Result<(), RustitudeError> {
+        self.model.restore(snapshot)
+    }
 }
 
-/// The [`ExtendedLogLikelihood`] stores two [`Manager`]s, one for data and one for a Monte-Carlo
-/// dataset used for acceptance correction. These should probably have the same [`Manager`] in
-/// practice, but this is left to the user.
-#[derive(Clone)]
-pub struct ExtendedLogLikelihood<F: Field + 'static> {
-    /// [`Manager`] for data
-    pub data_manager: Manager<F>,
-    /// [`Manager`] for Monte-Carlo
-    pub mc_manager: Manager<F>,
+/// The name used as the parent amplitude of the yield [`Parameter`]s of an
+/// [`ExtendedLogLikelihood`] (see [`ExtendedLogLikelihood::add_signal_yield`]).
+pub const YIELD_AMPLITUDE: &str = "yield";
+
+/// A lower bound on the intensity of a data event inside the logarithm of an
+/// [`ExtendedLogLikelihood`].
+///
+/// Without a floor, a model which momentarily predicts zero (or negative) intensity for an
+/// observed event makes $`-2\ln\mathcal{L}`$ infinite (or NaN), which can derail a minimizer.
+/// With explicit yields (see [`ExtendedLogLikelihood::add_signal_yield`]), the floor applies to
+/// the total density of signal and background. Events below the floor can be listed with
+/// [`ExtendedLogLikelihood::floored_events`].
+#[derive(Clone, Copy, Debug)]
+pub enum IntensityFloor<F: Field> {
+    /// Replace the intensity of any event below the floor with the floor itself.
+    Clamp(F),
+    /// Drop any event below the floor from the sum over data. Dropped events still count towards
+    /// the number of data events used to normalize the Monte-Carlo term.
+    Drop(F),
 }
-impl<F: Field> Debug for ExtendedLogLikelihood<F> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "ExtendedLogLikelihood [ ")?;
-        write!(f, "{:?} ", self.data_manager)?;
-        write!(f, "{:?} ", self.mc_manager)?;
-        write!(f, "]")
+
+impl<F: Field> IntensityFloor<F> {
+    /// Returns the value of the floor.
+    pub const fn value(&self) -> F {
+        match self {
+            Self::Clamp(floor) | Self::Drop(floor) => *floor,
+        }
+    }
+
+    /// Computes the logarithm of an intensity subject to the floor. Intensities below an
+    /// [`IntensityFloor::Drop`] contribute zero.
+    pub(crate) fn ln(&self, value: F) -> F {
+        match *self {
+            Self::Clamp(floor) => value.max(floor).ln(),
+            Self::Drop(floor) => {
+                if value < floor {
+                    F::zero()
+                } else {
+                    value.ln()
+                }
+            }
+        }
     }
 }
-impl<F: Field> Display for ExtendedLogLikelihood<F> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", self.data_manager)?;
-        writeln!(f, "{}", self.mc_manager)
+
+/// A robust loss applied to the contribution of each data event to an
+/// [`ExtendedLogLikelihood`], which reduces the sensitivity of a fit to events in mismodeled
+/// tails.
+///
+/// The contribution of an event is $`c_i = -\ln(I_i / \bar{I})`$, where $`\bar{I}`$ is the mean
+/// Monte-Carlo intensity, so that events which the [`Model`] finds less likely than average have
+/// $`c_i > 0`$. The standard likelihood sums $`c_i`$, while a robust loss sums $`\rho(c_i)`$
+/// instead. Events with $`c_i \le 0`$ are never modified, and a threshold of infinity recovers
+/// the standard likelihood.
+#[derive(Clone, Copy, Debug)]
+pub enum RobustLoss<F: Field> {
+    /// Cap the contribution of each event at the threshold, $`\rho(c) = \min(c, k)`$.
+    Cap(F),
+    /// Smoothly down-weight events with Tukey's biweight, $`\rho'(c) = (1 - (c/k)^2)^2`$ for
+    /// $`0 < c < k`$, so that events beyond the threshold contribute a constant $`8k/15`$.
+    Tukey(F),
+}
+
+impl<F: Field> RobustLoss<F> {
+    /// Returns the threshold of the loss.
+    pub const fn threshold(&self) -> F {
+        match self {
+            Self::Cap(threshold) | Self::Tukey(threshold) => *threshold,
+        }
+    }
+
+    /// Evaluates the robust contribution $`\rho(c)`$ of an event with standard contribution `c`.
+    pub fn value(&self, c: F) -> F {
+        match *self {
+            Self::Cap(k) => c.min(k),
+            Self::Tukey(k) => {
+                if c <= F::zero() {
+                    c
+                } else if c < k {
+                    let r2 = (c / k).powi(2);
+                    c * (F::one() - convert!(2, F) * r2 / convert!(3, F) + r2 * r2 / convert!(5, F))
+                } else {
+                    convert!(8, F) * k / convert!(15, F)
+                }
+            }
+        }
+    }
+
+    /// Evaluates the derivative $`\rho'(c)`$, which is the effective weight of an event with
+    /// standard contribution `c`.
+    pub fn weight(&self, c: F) -> F {
+        match *self {
+            Self::Cap(k) => {
+                if c < k {
+                    F::one()
+                } else {
+                    F::zero()
+                }
+            }
+            Self::Tukey(k) => {
+                if c <= F::zero() {
+                    F::one()
+                } else if c < k {
+                    (F::one() - (c / k).powi(2)).powi(2)
+                } else {
+                    F::zero()
+                }
+            }
+        }
     }
 }
-impl<F: Field> ExtendedLogLikelihood<F> {
-    /// Create a new [`ExtendedLogLikelihood`] from a data and Monte-Carlo [`Manager`]s.
-    pub const fn new(data_manager: Manager<F>, mc_manager: Manager<F>) -> Self {
-        Self {
-            data_manager,
-            mc_manager,
+
+/// The form of the likelihood computed by an [`ExtendedLogLikelihood`] (see
+/// [`ExtendedLogLikelihood::kind`]).
+///
+/// In every form, $`w_i`$ and $`I_i`$ are the weight and intensity of each data event, $`w_j`$,
+/// $`a_j`$, and $`I_j`$ are the weight, acceptance, and intensity of each Monte-Carlo event, and
+/// $`N_{\text{data}}`$ and $`N_{\text{MC}}`$ are the total weights of the data and Monte-Carlo.
+/// [`ExtendedLogLikelihood::evaluate`] always returns $`-2\ln\mathcal{L}`$ (plus any
+/// constraints) up to a constant which depends only on the data.
+#[derive(Clone, Debug, Default)]
+pub enum LikelihoodKind<F: Field> {
+    /// The extended unbinned likelihood,
+    /// $`\ln\mathcal{L} = \sum_i w_i\ln I_i - \frac{N_{\text{data}}}{N_{\text{MC}}}\sum_j w_j a_j I_j`$,
+    /// which also fits the overall scale of the intensity to the number of data events.
+    #[default]
+    Extended,
+    /// The non-extended (shape-only) unbinned likelihood,
+    /// $`\ln\mathcal{L} = \sum_i w_i\ln(I_i / \bar{I})`$, where
+    /// $`\bar{I} = \sum_j w_j a_j I_j / N_{\text{MC}}`$ is the mean accepted Monte-Carlo
+    /// intensity. This does not depend on the overall scale of the intensity.
+    Unextended,
+    /// The binned extended likelihood with a Poisson term for each bin of a [`Binning`],
+    /// $`\ln\mathcal{L} = \sum_b (n_b\ln\mu_b - \mu_b)`$, where $`n_b`$ is the total data weight
+    /// in the bin and
+    /// $`\mu_b = \frac{N_{\text{data}}}{N_{\text{MC}}}\sum_{j \in b} w_j a_j I_j`$ is the
+    /// predicted yield. Events outside of the bins are ignored.
+    Binned(Binning<F>),
+}
+
+impl<F: Field> LikelihoodKind<F> {
+    /// Checks if this is [`LikelihoodKind::Extended`].
+    pub const fn is_extended(&self) -> bool {
+        matches!(self, Self::Extended)
+    }
+}
+
+/// The bin of each data and Monte-Carlo event for a [`LikelihoodKind::Binned`] likelihood.
+#[derive(Clone, Debug)]
+pub struct Binning<F: Field> {
+    /// The edges of the bins. Each bin includes its lower edge and excludes its upper edge.
+    pub edges: Vec<F>,
+    /// The bin of each data [`Event`], or [`None`] if it lies outside of the edges.
+    pub data_bins: Vec<Option<usize>>,
+    /// The bin of each Monte-Carlo [`Event`], or [`None`] if it lies outside of the edges.
+    pub mc_bins: Vec<Option<usize>>,
+}
+
+impl<F: Field> Binning<F> {
+    /// Bins the data and Monte-Carlo [`Dataset`]s in the value of `variable`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are fewer
+    /// than two edges or the edges are not strictly increasing.
+    pub fn new(
+        edges: Vec<F>,
+        variable: impl Fn(&Event<F>) -> F + Sync,
+        data: &Dataset<F>,
+        mc: &Dataset<F>,
+    ) -> Result<Self, RustitudeError> {
+        let data_values: Vec<F> = data.events.par_iter().map(&variable).collect();
+        let mc_values: Vec<F> = mc.events.par_iter().map(&variable).collect();
+        Self::from_values(edges, &data_values, &mc_values)
+    }
+
+    /// Bins precomputed values of the binning variable for each data and Monte-Carlo [`Event`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are fewer
+    /// than two edges or the edges are not strictly increasing.
+    pub fn from_values(
+        edges: Vec<F>,
+        data_values: &[F],
+        mc_values: &[F],
+    ) -> Result<Self, RustitudeError> {
+        if edges.len() < 2 || edges.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(RustitudeError::InvalidParameterValue(
+                "bin edges must contain at least two strictly increasing values".to_string(),
+            ));
+        }
+        let n_bins = edges.len() - 1;
+        let bins = |values: &[F]| {
+            values
+                .iter()
+                .map(|&value| {
+                    if value < edges[0] || value >= edges[n_bins] || value.is_nan() {
+                        None
+                    } else {
+                        Some(edges.partition_point(|edge| *edge <= value) - 1)
+                    }
+                })
+                .collect()
+        };
+        Ok(Self {
+            data_bins: bins(data_values),
+            mc_bins: bins(mc_values),
+            edges,
+        })
+    }
+
+    /// Returns the number of bins.
+    pub const fn n_bins(&self) -> usize {
+        self.edges.len() - 1
+    }
+
+    /// Sums `values` in each bin, where `bins` holds the bin of each event in a [`Dataset`] and
+    /// the value at position `k` belongs to the event at `indices[k]` (or `k`).
+    fn sums(&self, bins: &[Option<usize>], values: &[F], indices: Option<&[usize]>) -> Vec<F> {
+        let mut sums = vec![F::zero(); self.n_bins()];
+        for (k, value) in values.iter().enumerate() {
+            if let Some(bin) = bins[indices.map_or(k, |indices| indices[k])] {
+                sums[bin] += *value;
+            }
+        }
+        sums
+    }
+}
+
+/// An observer of the progress of an [`ExtendedLogLikelihood`] (see
+/// [`ExtendedLogLikelihood::with_observer`]), which can drive progress bars or live monitoring.
+///
+/// This is implemented for every closure `Fn(usize, F, &[F])`.
+pub trait Observer<F: Field>: Send + Sync {
+    /// Called with the iteration number, the current value of $`-2\ln\mathcal{L}`$, and the
+    /// current free parameters.
+    fn observe(&self, iteration: usize, fx: F, parameters: &[F]);
+}
+
+impl<F: Field, T: Fn(usize, F, &[F]) + Send + Sync> Observer<F> for T {
+    fn observe(&self, iteration: usize, fx: F, parameters: &[F]) {
+        self(iteration, fx, parameters);
+    }
+}
+
+/// The [`ExtendedLogLikelihood`] stores two [`Manager`]s, one for data and one for a Monte-Carlo
+/// dataset used for acceptance correction.
+///
+/// These should probably have the same [`Manager`] in
+/// practice, but this is left to the user.
+#[derive(Clone)]
+pub struct ExtendedLogLikelihood<F: Field + 'static> {
+    /// [`Manager`] for data
+    pub data_manager: Manager<F>,
+    /// [`Manager`] for Monte-Carlo
+    pub mc_manager: Manager<F>,
+    /// An optional [`Manager`] for generated Monte-Carlo, of which the Monte-Carlo of
+    /// [`ExtendedLogLikelihood::mc_manager`] is the accepted subset. It does not enter the
+    /// likelihood, but gives the efficiency of the [`Model`] (see
+    /// [`ExtendedLogLikelihood::set_generated`] and [`ExtendedLogLikelihood::efficiency`]).
+    pub generated_manager: Option<Manager<F>>,
+    /// If `true`, the Monte-Carlo normalization sum (and the total Monte-Carlo weight) is
+    /// accumulated in [`f64`] regardless of the precision of `F`. This is off by default since it
+    /// only matters for [`f32`] models, where this single large sum dominates the precision loss.
+    pub wide_mc_accumulation: bool,
+    /// If `true`, the sums over events in [`ExtendedLogLikelihood::evaluate`] and
+    /// [`ExtendedLogLikelihood::par_evaluate`] use compensated pairwise summation with a
+    /// reduction tree fixed by the number of events (see [`reproducible_sum`]), so the value is
+    /// bit-for-bit identical for any number of threads. This is off by default since it stores
+    /// the contribution of every event before summing.
+    pub deterministic_reduction: bool,
+    /// Explicit yield [`Parameter`]s (the signal yield, followed by the background yield if a
+    /// background template is used), which come after the free parameters of the [`Model`]. See
+    /// [`ExtendedLogLikelihood::add_signal_yield`].
+    pub yields: Vec<Parameter<F>>,
+    /// The background template evaluated at each data and Monte-Carlo event. See
+    /// [`ExtendedLogLikelihood::add_background_template`].
+    pub background_template: Option<(Vec<F>, Vec<F>)>,
+    /// An optional [`IntensityFloor`] applied to each data event inside the logarithm.
+    pub intensity_floor: Option<IntensityFloor<F>>,
+    /// An optional [`RobustLoss`] applied to the contribution of each data event. The standard
+    /// value is still available through [`ExtendedLogLikelihood::evaluate_standard`].
+    pub robust_loss: Option<RobustLoss<F>>,
+    /// An optional acceptance (or efficiency) for each Monte-Carlo event, which multiplies the
+    /// event in the normalization integral separately from its weight. See
+    /// [`ExtendedLogLikelihood::set_acceptance`].
+    pub acceptance: Option<Vec<F>>,
+    /// Cached [`NormalizationIntegrals`] over the Monte-Carlo, if any. See
+    /// [`ExtendedLogLikelihood::cache_normalization`].
+    pub normalization_integrals: Option<NormalizationIntegrals<F>>,
+    /// The form of the likelihood (see [`LikelihoodKind`]), which is
+    /// [`LikelihoodKind::Extended`] by default. Forms other than [`LikelihoodKind::Extended`]
+    /// cannot be combined with explicit yields or a [`RobustLoss`], and do not use cached
+    /// [`NormalizationIntegrals`].
+    pub kind: LikelihoodKind<F>,
+    /// An optional [`Observer`] of the progress of the [`ExtendedLogLikelihood`]. See
+    /// [`ExtendedLogLikelihood::with_observer`].
+    pub observer: Option<Arc<dyn Observer<F>>>,
+    /// The number of evaluations reported to the [`Observer`], which is shared between clones.
+    evaluations: Arc<AtomicUsize>,
+}
+impl<F: Field> Debug for ExtendedLogLikelihood<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ExtendedLogLikelihood [ ")?;
+        write!(f, "{:?} ", self.data_manager)?;
+        write!(f, "{:?} ", self.mc_manager)?;
+        write!(f, "]")
+    }
+}
+impl<F: Field> Display for ExtendedLogLikelihood<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.data_manager)?;
+        writeln!(f, "{}", self.mc_manager)
+    }
+}
+impl<F: Field> ExtendedLogLikelihood<F> {
+    /// Create a new [`ExtendedLogLikelihood`] from a data and Monte-Carlo [`Manager`]s.
+    pub fn new(data_manager: Manager<F>, mc_manager: Manager<F>) -> Self {
+        Self {
+            data_manager,
+            mc_manager,
+            generated_manager: None,
+            wide_mc_accumulation: false,
+            deterministic_reduction: false,
+            yields: Vec::new(),
+            background_template: None,
+            intensity_floor: None,
+            robust_loss: None,
+            acceptance: None,
+            normalization_integrals: None,
+            kind: LikelihoodKind::Extended,
+            observer: None,
+            evaluations: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Create a new [`ExtendedLogLikelihood`] from a data [`Manager`] and Monte-Carlo generated
+    /// on the fly from a [`PhaseSpace`], rather than read from a file.
+    ///
+    /// Events are drawn from the [`PhaseSpace`] in batches which double the size of the sample
+    /// (starting with `1024` events), and the normalization integral
+    /// $`\sum_j w_j I(x_j) / \sum_j w_j`$ is evaluated at the given free `parameters` (usually
+    /// the initial values) after each batch. Generation stops once the integral changes by no
+    /// more than `precision` relative to its value, or once the sample holds `max_events` events,
+    /// in which case a warning is emitted. Since the events cover phase space with perfect
+    /// acceptance, an acceptance can be applied afterwards with
+    /// [`ExtendedLogLikelihood::set_acceptance_fn`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if `max_events` is
+    /// zero, or any error produced while generating or evaluating the events.
+    pub fn from_phase_space(
+        data_manager: Manager<F>,
+        phase_space: &PhaseSpace<F>,
+        parameters: &[F],
+        precision: F,
+        max_events: usize,
+    ) -> Result<Self, RustitudeError> {
+        if max_events == 0 {
+            return Err(RustitudeError::InvalidParameterValue(
+                "phase space integration requires at least one event".to_string(),
+            ));
+        }
+        let model = &data_manager.model;
+        let mut events: Vec<Event<F>> = Vec::new();
+        let (mut sum, mut weight_sum) = (F::zero(), F::zero());
+        let mut previous: Option<F> = None;
+        loop {
+            let n_batch = usize::min(events.len().max(1024), max_events - events.len());
+            let batch = phase_space.generate(n_batch, events.len())?;
+            let batch_manager = Manager::new(model, &batch)?;
+            let values = if model.contains_python_amplitudes {
+                batch_manager.evaluate(parameters)?
+            } else {
+                data_manager.install(|| batch_manager.par_evaluate(parameters))?
+            };
+            for (value, event) in values.into_iter().zip(batch.events.iter()) {
+                sum += event.weight * value;
+                weight_sum += event.weight;
+            }
+            events.extend(batch.events.iter().cloned());
+            let integral = sum / weight_sum;
+            if previous
+                .is_some_and(|previous| (integral - previous).abs() <= precision * integral.abs())
+            {
+                break;
+            }
+            if events.len() >= max_events {
+                warn!(
+                    "phase space integral did not reach a relative precision of {precision} within {max_events} events"
+                );
+                break;
+            }
+            previous = Some(integral);
+        }
+        let mut dataset = Dataset::new(events);
+        dataset.reindex();
+        let mc_manager =
+            Manager::new(model, &dataset)?.with_thread_pool(data_manager.thread_pool.clone());
+        Ok(Self::new(data_manager, mc_manager))
+    }
+
+    /// Runs the parallel methods of the [`ExtendedLogLikelihood`] on a dedicated
+    /// [`ThreadPool`] with `n` threads, which is shared by the data and Monte-Carlo
+    /// [`Manager`]s. See [`Manager::with_threads`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ThreadPoolBuildError`] if the pool cannot be
+    /// created.
+    pub fn with_threads(mut self, n: usize) -> Result<Self, RustitudeError> {
+        let pool = Some(Arc::new(ThreadPoolBuilder::new().num_threads(n).build()?));
+        self.data_manager = self.data_manager.with_thread_pool(pool.clone());
+        self.mc_manager = self.mc_manager.with_thread_pool(pool.clone());
+        self.generated_manager = self
+            .generated_manager
+            .map(|generated| generated.with_thread_pool(pool));
+        Ok(self)
+    }
+
+    /// Returns the number of threads used by the parallel methods of the
+    /// [`ExtendedLogLikelihood`].
+    pub fn num_threads(&self) -> usize {
+        self.data_manager.num_threads()
+    }
+
+    /// Runs `op` on the [`ThreadPool`] of the data [`Manager`] (see
+    /// [`Manager::install`]).
+    pub fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        self.data_manager.install(op)
+    }
+
+    /// Reports the progress of the [`ExtendedLogLikelihood`] to an [`Observer`].
+    ///
+    /// Every successful call to [`ExtendedLogLikelihood::evaluate`] or
+    /// [`ExtendedLogLikelihood::par_evaluate`] (including those made by a minimizer through the
+    /// `ganesh` `Function` implementation) is reported with the number of evaluations so far,
+    /// starting at `1`. The built-in minimization drivers, such as
+    /// [`ExtendedLogLikelihood::multistart`] and [`ExtendedLogLikelihood::profile`], instead
+    /// report the best point after each step of the minimizer, with the step number. Clones of
+    /// the [`ExtendedLogLikelihood`] share the [`Observer`] and the evaluation count.
+    #[must_use]
+    pub fn with_observer(mut self, observer: impl Observer<F> + 'static) -> Self {
+        self.observer = Some(Arc::new(observer));
+        self.evaluations = Arc::new(AtomicUsize::new(0));
+        self
+    }
+
+    /// Removes the [`Observer`] of the [`ExtendedLogLikelihood`], if any.
+    pub fn clear_observer(&mut self) {
+        self.observer = None;
+    }
+
+    /// Returns the number of evaluations reported to the [`Observer`] (see
+    /// [`ExtendedLogLikelihood::with_observer`]).
+    pub fn evaluations(&self) -> usize {
+        self.evaluations.load(Ordering::Relaxed)
+    }
+
+    /// Reports an evaluation to the [`Observer`], if any.
+    fn notify(&self, parameters: &[F], fx: F) {
+        if let Some(observer) = &self.observer {
+            let iteration = self.evaluations.fetch_add(1, Ordering::Relaxed) + 1;
+            observer.observe(iteration, fx, parameters);
+        }
+    }
+
+    /// Precompute and cache the [`NormalizationIntegrals`] of the Monte-Carlo.
+    ///
+    /// When the [`Model`] is linear in its production coefficients, the Monte-Carlo
+    /// normalization $`\sum_j w_j a_j I(x_j)`$ is a quadratic form in those coefficients (see
+    /// [`crate::linear`]). Once the integrals are cached, evaluating the normalization costs
+    /// $`O(K^2)`$ for $`K`$ terms rather than a loop over every Monte-Carlo event, and the
+    /// Monte-Carlo part of the gradient costs $`O(K^2)`$ per free parameter. This is a large
+    /// speedup for fits where only production coefficients float. The data term is still
+    /// evaluated event-by-event.
+    ///
+    /// The cache is used by the unindexed evaluation methods and by
+    /// [`ExtendedLogLikelihood::gradient`] and [`ExtendedLogLikelihood::evaluate_with_gradient`]
+    /// (and their parallel versions) without explicit yields or a [`RobustLoss`]. It is ignored
+    /// (but kept) while the activity of the [`Amplitude`]s differs from when it was computed, it
+    /// is cleared if the acceptance changes, and it does not respect
+    /// [`ExtendedLogLikelihood::wide_mc_accumulation`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are no data
+    /// events, a [`RustitudeError::EvaluationError`] if the [`Model`] is not linear in its
+    /// production coefficients, or any error produced by [`Node::calculate`](crate::amplitude::Node::calculate).
+    pub fn cache_normalization(&mut self) -> Result<(), RustitudeError> {
+        self.normalization_integrals = Some(NormalizationIntegrals::new(self)?);
+        Ok(())
+    }
+
+    /// Cache [`NormalizationIntegrals`] computed by another [`ExtendedLogLikelihood`] of the
+    /// same [`Model`] and Monte-Carlo, possibly at another precision.
+    ///
+    /// This lets a two-stage fit reuse the integrals of a fast [`f32`] stage as a starting
+    /// approximation in the [`f64`] stage (see also [`FitResult::warm_start`](crate::fit::FitResult::warm_start)),
+    /// and only recompute them with [`ExtendedLogLikelihood::cache_normalization`] for the final
+    /// iterations. The integrals are rounded to the lower of the two precisions until then.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the integrals do
+    /// not describe the [`Amplitude`]s of the [`Model`] (see
+    /// [`NormalizationIntegrals::is_valid_for`]). In this case, the cache is not modified.
+    pub fn warm_start_normalization<G: Field>(
+        &mut self,
+        integrals: &NormalizationIntegrals<G>,
+    ) -> Result<(), RustitudeError> {
+        let integrals = integrals.convert();
+        if !integrals.is_valid_for(&self.mc_manager.model) {
+            return Err(RustitudeError::InvalidParameterValue(
+                "the normalization integrals do not match the amplitudes of the model".to_string(),
+            ));
+        }
+        self.normalization_integrals = Some(integrals);
+        Ok(())
+    }
+
+    /// Remove any cached [`NormalizationIntegrals`] (see
+    /// [`ExtendedLogLikelihood::cache_normalization`]).
+    pub fn clear_normalization_cache(&mut self) {
+        self.normalization_integrals = None;
+    }
+
+    /// Returns the cached [`NormalizationIntegrals`] if they are still valid for the [`Model`].
+    fn cached_integrals(&self) -> Option<&NormalizationIntegrals<F>> {
+        self.normalization_integrals
+            .as_ref()
+            .filter(|integrals| integrals.is_valid_for(&self.mc_manager.model))
+    }
+
+    /// Computes the total Monte-Carlo weight and the weighted sum of accepted Monte-Carlo
+    /// intensities over every Monte-Carlo event, using the cached [`NormalizationIntegrals`] if
+    /// possible.
+    fn mc_terms(&self, parameters: &[F], parallel: bool) -> Result<(F, F), RustitudeError> {
+        if let Some(integrals) = self.cached_integrals() {
+            return Ok((
+                integrals.n_mc(),
+                integrals.mc_sum(&self.mc_manager.model, parameters)?,
+            ));
+        }
+        let mc_weights = self.mc_manager.dataset.weights();
+        if parallel {
+            let mc_res = self.accepted(self.mc_manager.par_evaluate(parameters)?, None);
+            Ok(self.par_mc_normalization(&mc_res, &mc_weights))
+        } else {
+            let mc_res = self.accepted(self.mc_manager.evaluate(parameters)?, None);
+            Ok(self.mc_normalization(&mc_res, &mc_weights))
+        }
+    }
+
+    /// Evaluates $`-2\ln\mathcal{L}`$ and its gradient with the Monte-Carlo normalization
+    /// taken from the cached [`NormalizationIntegrals`].
+    #[allow(clippy::suboptimal_flops)]
+    fn cached_evaluate_gradient(
+        &self,
+        parameters: &[F],
+        integrals: &NormalizationIntegrals<F>,
+        parallel: bool,
+    ) -> Result<(F, Vec<F>), RustitudeError> {
+        let n_data = self
+            .data_manager
+            .dataset
+            .weights()
+            .iter()
+            .copied()
+            .sum::<F>();
+        let data_term = |_: usize, value: F, event: &Event<F>| {
+            let weight = if self.above_floor(value) {
+                event.weight / value
+            } else {
+                F::zero()
+            };
+            (event.weight * self.ln_intensity(value), weight)
+        };
+        let (data_sum, data_gradient) = if parallel {
+            self.data_manager
+                .par_value_gradient_sum(parameters, data_term)?
+        } else {
+            self.data_manager
+                .value_gradient_sum(parameters, data_term)?
+        };
+        let model = &self.mc_manager.model;
+        let scale = n_data / integrals.n_mc();
+        let mc_sum = integrals.mc_sum(model, parameters)?;
+        let ln_l = data_sum - scale * mc_sum;
+        let value =
+            convert!(-2, F) * ln_l + self.data_manager.model.constraint_penalty(parameters)?;
+        // The cached gradient is already taken with respect to the free parameters
+        let gradient = self
+            .combine_gradients(
+                parameters,
+                &data_gradient,
+                &vec![F::zero(); data_gradient.len()],
+                scale,
+            )?
+            .into_iter()
+            .zip(integrals.mc_sum_gradient(model, parameters)?)
+            .map(|(g, m)| g + convert!(2, F) * scale * m)
+            .collect();
+        Ok((value, gradient))
+    }
+
+    /// Set (or remove) the acceptance of each Monte-Carlo event.
+    ///
+    /// The acceptance $`a_j`$ multiplies each Monte-Carlo event in the normalization integral,
+    /// $`\sum_j w_j a_j I(x_j)`$, while the total Monte-Carlo weight $`\sum_j w_j`$ is unchanged.
+    /// This allows the Monte-Carlo to be generated (rather than accepted) phase space with the
+    /// detector acceptance applied event-by-event. Unlike folding the acceptance into the weights,
+    /// this keeps the acceptance available for
+    /// [`ExtendedLogLikelihood::average_acceptance`], from which acceptance-corrected yields can
+    /// be computed.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the number of
+    /// acceptances does not match the number of Monte-Carlo events.
+    pub fn set_acceptance(&mut self, acceptance: Option<Vec<F>>) -> Result<(), RustitudeError> {
+        if let Some(values) = &acceptance {
+            if values.len() != self.mc_manager.dataset.len() {
+                return Err(RustitudeError::InvalidParameterValue(format!(
+                    "acceptance must have {} Monte-Carlo values, got {}",
+                    self.mc_manager.dataset.len(),
+                    values.len()
+                )));
+            }
+        }
+        self.acceptance = acceptance;
+        self.normalization_integrals = None;
+        Ok(())
+    }
+
+    /// Set the acceptance of each Monte-Carlo event from a function of the [`Event`] (see
+    /// [`ExtendedLogLikelihood::set_acceptance`]).
+    pub fn set_acceptance_fn(&mut self, acceptance: impl Fn(&Event<F>) -> F + Sync + Send) {
+        self.acceptance = Some(
+            self.mc_manager
+                .dataset
+                .events
+                .par_iter()
+                .map(acceptance)
+                .collect(),
+        );
+        self.normalization_integrals = None;
+    }
+
+    /// Returns the acceptance of the Monte-Carlo event at the given index, which is one if no
+    /// acceptance has been set.
+    fn acceptance_at(&self, index: usize) -> F {
+        self.acceptance
+            .as_ref()
+            .map_or_else(F::one, |acceptance| acceptance[index])
+    }
+
+    /// Multiplies the values of each (indexed) Monte-Carlo event by its acceptance.
+    fn accepted(&self, mut values: Vec<F>, indices_mc: Option<&[usize]>) -> Vec<F> {
+        if self.acceptance.is_some() {
+            for (i, value) in values.iter_mut().enumerate() {
+                *value *= self.acceptance_at(indices_mc.map_or(i, |indices| indices[i]));
+            }
+        }
+        values
+    }
+
+    /// Computes the average acceptance of the model over the Monte-Carlo,
+    /// $`\bar{a} = \sum_j w_j a_j I(x_j) / \sum_j w_j I(x_j)`$, for the given free parameters.
+    ///
+    /// This is one if no acceptance has been set (see [`ExtendedLogLikelihood::set_acceptance`]).
+    /// Dividing a fitted (accepted) yield by $`\bar{a}`$ gives the acceptance-corrected yield.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute`] for more information.
+    pub fn average_acceptance(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        let mc_res = self.mc_manager.evaluate(parameters)?;
+        let mc_weights = self.mc_manager.dataset.weights();
+        let (_, total) = self.mc_normalization(&mc_res, &mc_weights);
+        let (_, accepted) = self.mc_normalization(&self.accepted(mc_res, None), &mc_weights);
+        Ok(accepted / total)
+    }
+
+    /// Set (or remove) the generated Monte-Carlo, of which the Monte-Carlo of the
+    /// [`ExtendedLogLikelihood`] is the accepted subset (with the same event weights).
+    ///
+    /// The Monte-Carlo of [`ExtendedLogLikelihood::mc_manager`] keeps its role in the
+    /// normalization of the likelihood, while the generated Monte-Carlo gives the efficiency of
+    /// the [`Model`] (see [`ExtendedLogLikelihood::efficiency`]). The generated [`Manager`] is
+    /// built from the current [`Model`] and follows every later change to its parameters and
+    /// activation.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the precalculation phase of the [`Model`]
+    /// fails for any generated events. See [`Model::load`] for more information.
+    pub fn set_generated(&mut self, dataset: Option<&Dataset<F>>) -> Result<(), RustitudeError> {
+        self.generated_manager = match dataset {
+            Some(dataset) => Some(
+                Manager::new(&self.data_manager.model, dataset)?
+                    .with_thread_pool(self.data_manager.thread_pool.clone()),
+            ),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Applies `op` to the generated Monte-Carlo [`Manager`], if there is one.
+    fn update_generated(
+        &mut self,
+        op: impl FnOnce(&mut Manager<F>) -> Result<(), RustitudeError>,
+    ) -> Result<(), RustitudeError> {
+        self.generated_manager.as_mut().map_or(Ok(()), op)
+    }
+
+    /// Computes the efficiency of the model for the given free parameters,
+    ///
+    /// ```math
+    /// \varepsilon = \frac{\sum_{j \in \text{acc}} w_j a_j I(x_j)}{\sum_{j \in \text{gen}} w_j I(x_j)},
+    /// ```
+    ///
+    /// where the numerator runs over the accepted Monte-Carlo (with the acceptance $`a_j`$ of
+    /// [`ExtendedLogLikelihood::set_acceptance`], if any) and the denominator over the generated
+    /// Monte-Carlo (see [`ExtendedLogLikelihood::set_generated`]). Without generated
+    /// Monte-Carlo, the denominator runs over the accepted Monte-Carlo instead, which gives
+    /// [`ExtendedLogLikelihood::average_acceptance`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute`] for more information.
+    pub fn efficiency(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        self.efficiency_with(
+            &self.mc_manager,
+            self.generated_manager.as_ref(),
+            parameters,
+            false,
+        )
+    }
+
+    /// Computes the efficiency of the model for the given free parameters.
+    ///
+    /// This version uses a parallel loop over events. See [`ExtendedLogLikelihood::efficiency`]
+    /// for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::PythonError`] if the [`Model`] contains Python
+    /// amplitudes, or any error produced while calculating the amplitudes. See
+    /// [`Model::compute`] for more information.
+    pub fn par_efficiency(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        self.install(|| {
+            if self.mc_manager.model.contains_python_amplitudes {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            self.efficiency_with(
+                &self.mc_manager,
+                self.generated_manager.as_ref(),
+                parameters,
+                true,
+            )
+        })
+    }
+
+    /// Computes the efficiency of each active [`Amplitude`] and of each named group of
+    /// [`Amplitude`]s, with only that group active (see [`Model::isolate`]).
+    ///
+    /// Each efficiency is computed as in [`ExtendedLogLikelihood::efficiency`], and is NaN if the
+    /// isolated intensity vanishes everywhere (for instance, a single factor of a product). The
+    /// activation of the [`ExtendedLogLikelihood`] itself is not changed.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::AmplitudeNotFoundError`] if a group contains
+    /// an unknown amplitude, or any error produced while calculating the amplitudes.
+    pub fn wave_efficiencies(
+        &self,
+        parameters: &[F],
+        groups: &[(&str, Vec<&str>)],
+    ) -> Result<Vec<(String, F)>, RustitudeError> {
+        self.compute_wave_efficiencies(parameters, groups, false)
+    }
+
+    /// Computes the efficiency of each active [`Amplitude`] and of each named group of
+    /// [`Amplitude`]s.
+    ///
+    /// This version uses a parallel loop over events. See
+    /// [`ExtendedLogLikelihood::wave_efficiencies`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::PythonError`] if the [`Model`] contains Python
+    /// amplitudes, a [`RustitudeError::AmplitudeNotFoundError`] if a group contains an unknown
+    /// amplitude, or any error produced while calculating the amplitudes.
+    pub fn par_wave_efficiencies(
+        &self,
+        parameters: &[F],
+        groups: &[(&str, Vec<&str>)],
+    ) -> Result<Vec<(String, F)>, RustitudeError> {
+        self.install(|| {
+            if self.mc_manager.model.contains_python_amplitudes {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            self.compute_wave_efficiencies(parameters, groups, true)
+        })
+    }
+
+    /// Computes the acceptance-corrected yield for the given free parameters: the signal yield
+    /// (see [`ExtendedLogLikelihood::add_signal_yield`]), or the weighted number of data events
+    /// if there is none, divided by [`ExtendedLogLikelihood::efficiency`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute`] for more information.
+    pub fn corrected_yield(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        let observed = if self.yields.is_empty() {
+            self.data_manager.dataset.weights().iter().copied().sum()
+        } else {
+            parameters[self.mc_manager.get_n_free()]
+        };
+        Ok(observed / self.efficiency(parameters)?)
+    }
+
+    fn efficiency_with(
+        &self,
+        accepted: &Manager<F>,
+        generated: Option<&Manager<F>>,
+        parameters: &[F],
+        parallel: bool,
+    ) -> Result<F, RustitudeError> {
+        let evaluate = |manager: &Manager<F>| {
+            if parallel {
+                manager.par_evaluate(parameters)
+            } else {
+                manager.evaluate(parameters)
+            }
+        };
+        let mc_res = evaluate(accepted)?;
+        let mc_weights = accepted.dataset.weights();
+        let (_, total) = match generated {
+            Some(generated) => {
+                self.mc_normalization(&evaluate(generated)?, &generated.dataset.weights())
+            }
+            None => self.mc_normalization(&mc_res, &mc_weights),
+        };
+        let (_, accepted) = self.mc_normalization(&self.accepted(mc_res, None), &mc_weights);
+        Ok(accepted / total)
+    }
+
+    fn compute_wave_efficiencies(
+        &self,
+        parameters: &[F],
+        groups: &[(&str, Vec<&str>)],
+        parallel: bool,
+    ) -> Result<Vec<(String, F)>, RustitudeError> {
+        // Detached copies, so isolating amplitudes does not touch the shared models
+        let detach = |manager: &Manager<F>| {
+            let mut manager = manager.clone();
+            manager.model = manager.model.deep_clone();
+            manager
+        };
+        let mut accepted = detach(&self.mc_manager);
+        let mut generated = self.generated_manager.as_ref().map(detach);
+        let active: Vec<String> = accepted
+            .model
+            .amplitudes
+            .read()
+            .iter()
+            .filter(|amp| amp.active)
+            .map(|amp| amp.name.clone())
+            .collect();
+        active
+            .iter()
+            .map(|name| (name.as_str(), vec![name.as_str()]))
+            .chain(groups.iter().cloned())
+            .map(|(name, amplitudes)| {
+                accepted.isolate(amplitudes.clone())?;
+                if let Some(generated) = &mut generated {
+                    generated.isolate(amplitudes)?;
+                }
+                let efficiency =
+                    self.efficiency_with(&accepted, generated.as_ref(), parameters, parallel)?;
+                Ok((name.to_string(), efficiency))
+            })
+            .collect()
+    }
+
+    /// Fit the signal yield as an explicit free parameter.
+    ///
+    /// By default, the yield is absorbed into the production coefficients, and the likelihood is
+    /// normalized by scaling the Monte-Carlo sum by the ratio of data to Monte-Carlo events. With
+    /// an explicit signal yield $`N_s`$, the intensity $`I`$ is instead normalized to a
+    /// probability density over the (accepted) Monte-Carlo, and the likelihood gains the
+    /// extended-ML Poisson term:
+    ///
+    /// ```math
+    /// \ln\mathcal{L} = \sum_{i \in \text{data}} w_i \ln\left(N_s \frac{I(x_i)}{\langle I \rangle_{\text{MC}}} + N_b \frac{b(x_i)}{\langle b \rangle_{\text{MC}}}\right) - N_s - N_b
+    /// ```
+    ///
+    /// where the background term is only present if a template $`b`$ has been added with
+    /// [`ExtendedLogLikelihood::add_background_template`]. The yields are the expected numbers of
+    /// accepted events. Since the overall scale of $`I`$ no longer affects the likelihood, one
+    /// production coefficient should be fixed.
+    ///
+    /// The signal yield is named `signal` and belongs to the amplitude [`YIELD_AMPLITUDE`]. It
+    /// is the first parameter after the free parameters of the [`Model`] and starts at the
+    /// weighted number of data events. This method does nothing if the signal yield already
+    /// exists.
+    pub fn add_signal_yield(&mut self) {
+        if self.yields.is_empty() {
+            let mut signal = Parameter::new(YIELD_AMPLITUDE, "signal", 0);
+            signal.initial = self.data_manager.dataset.weights().iter().copied().sum();
+            self.yields.push(signal);
+        }
+    }
+
+    /// Add a background component with an explicit yield to the likelihood (see
+    /// [`ExtendedLogLikelihood::add_signal_yield`], which is implied).
+    ///
+    /// The template is given as its (unnormalized) value at each data and each Monte-Carlo
+    /// event, and it is normalized over the Monte-Carlo. The background yield is named
+    /// `background` and belongs to the amplitude [`YIELD_AMPLITUDE`]. When it is first added,
+    /// the signal and background yields each start at half of the weighted number of data
+    /// events. Adding another template replaces the previous one.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the lengths of the
+    /// templates do not match the numbers of data and Monte-Carlo events.
+    pub fn add_background_template(
+        &mut self,
+        data_template: Vec<F>,
+        mc_template: Vec<F>,
+    ) -> Result<(), RustitudeError> {
+        if data_template.len() != self.data_manager.dataset.len()
+            || mc_template.len() != self.mc_manager.dataset.len()
+        {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "background template must have {} data and {} Monte-Carlo values, got {} and {}",
+                self.data_manager.dataset.len(),
+                self.mc_manager.dataset.len(),
+                data_template.len(),
+                mc_template.len()
+            )));
+        }
+        self.add_signal_yield();
+        if self.background_template.is_none() {
+            let n_data: F = self.data_manager.dataset.weights().iter().copied().sum();
+            self.yields[0].initial = n_data / convert!(2, F);
+            let mut background = Parameter::new(YIELD_AMPLITUDE, "background", 1);
+            background.initial = n_data / convert!(2, F);
+            self.yields.push(background);
+        }
+        self.background_template = Some((data_template, mc_template));
+        Ok(())
+    }
+
+    /// Returns the yield [`Parameter`]s with their indices relative to the full list of free
+    /// parameters.
+    fn yield_parameters(&self) -> Vec<Parameter<F>> {
+        let n_model = self.mc_manager.get_n_free();
+        self.yields
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let mut p = p.clone();
+                p.index = Some(n_model + i);
+                p
+            })
+            .collect()
+    }
+
+    /// Returns the background yield and the normalized background density at each (indexed)
+    /// data event, if a background template is used.
+    fn background_density(
+        &self,
+        parameters: &[F],
+        indices_data: Option<&[usize]>,
+        indices_mc: Option<&[usize]>,
+    ) -> Option<(F, Vec<F>)> {
+        let (data_template, mc_template) = self.background_template.as_ref()?;
+        let n_b = parameters[self.mc_manager.get_n_free() + 1];
+        let (mc_values, mc_weights) = indices_mc.map_or_else(
+            || (mc_template.clone(), self.mc_manager.dataset.weights()),
+            |indices| {
+                (
+                    indices.iter().map(|&i| mc_template[i]).collect(),
+                    self.mc_manager.dataset.weights_indexed(indices),
+                )
+            },
+        );
+        let mc_values = self.accepted(mc_values, indices_mc);
+        let (n_mc, mc_sum) = self.mc_normalization(&mc_values, &mc_weights);
+        let mean = mc_sum / n_mc;
+        let density = indices_data.map_or_else(
+            || data_template.iter().map(|b| *b / mean).collect(),
+            |indices| indices.iter().map(|&i| data_template[i] / mean).collect(),
+        );
+        Some((n_b, density))
+    }
+
+    /// Computes $`\ln\mathcal{L}`$ with explicit yields (see
+    /// [`ExtendedLogLikelihood::add_signal_yield`]).
+    #[allow(clippy::too_many_arguments)]
+    fn yield_ln_likelihood(
+        &self,
+        parameters: &[F],
+        data_res: &[F],
+        data_weights: &[F],
+        n_mc: F,
+        mc_sum: F,
+        indices_data: Option<&[usize]>,
+        indices_mc: Option<&[usize]>,
+    ) -> F {
+        let n_s = parameters[self.mc_manager.get_n_free()];
+        let mean = mc_sum / n_mc;
+        let (n_b, background) = self
+            .background_density(parameters, indices_data, indices_mc)
+            .unwrap_or_else(|| (F::zero(), vec![F::zero(); data_res.len()]));
+        data_res
+            .iter()
+            .zip(data_weights)
+            .zip(background)
+            .map(|((l, w), b)| *w * self.ln_intensity(n_s * *l / mean + n_b * b))
+            .sum::<F>()
+            - n_s
+            - n_b
+    }
+
+    /// Enable or disable [`f64`] accumulation of the Monte-Carlo normalization sum. See
+    /// [`ExtendedLogLikelihood::wide_mc_accumulation`] for more information.
+    #[must_use]
+    pub const fn with_wide_mc_accumulation(mut self, wide_mc_accumulation: bool) -> Self {
+        self.wide_mc_accumulation = wide_mc_accumulation;
+        self
+    }
+
+    /// Enable or disable reproducible summation over events. See
+    /// [`ExtendedLogLikelihood::deterministic_reduction`] for more information.
+    #[must_use]
+    pub const fn with_deterministic_reduction(mut self, deterministic_reduction: bool) -> Self {
+        self.deterministic_reduction = deterministic_reduction;
+        self
+    }
+
+    /// Set (or remove) the [`IntensityFloor`] of the [`ExtendedLogLikelihood`]. See
+    /// [`ExtendedLogLikelihood::intensity_floor`] for more information.
+    #[must_use]
+    pub const fn with_intensity_floor(
+        mut self,
+        intensity_floor: Option<IntensityFloor<F>>,
+    ) -> Self {
+        self.intensity_floor = intensity_floor;
+        self
+    }
+
+    /// Sets the [`RobustLoss`] applied to each data event in the [`ExtendedLogLikelihood`]. See
+    /// [`ExtendedLogLikelihood::robust_loss`] for more information.
+    pub const fn with_robust_loss(mut self, robust_loss: Option<RobustLoss<F>>) -> Self {
+        self.robust_loss = robust_loss;
+        self
+    }
+
+    /// Checks that a [`RobustLoss`] is not combined with explicit yields, which it does not
+    /// support.
+    fn check_robust_loss(&self) -> Result<(), RustitudeError> {
+        if self.robust_loss.is_some() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "a robust loss cannot be applied to a likelihood with explicit yields".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks if a data event is dropped from the likelihood by an [`IntensityFloor::Drop`] or
+    /// by an [`IntensityPolicy::Skip`] of the data [`Manager`].
+    fn is_dropped(&self, value: F) -> bool {
+        self.is_skipped(value)
+            || matches!(self.intensity_floor, Some(IntensityFloor::Drop(floor)) if value < floor)
+    }
+
+    /// Checks if a data event is skipped by an [`IntensityPolicy::Skip`] of the data
+    /// [`Manager`].
+    fn is_skipped(&self, value: F) -> bool {
+        matches!(
+            self.data_manager.intensity_policy,
+            Some(IntensityPolicy::Skip)
+        ) && !is_valid_intensity(value)
+    }
+
+    /// Computes the sum over data events of $`w_i\ln(I_i)`$, or of its robust counterpart
+    /// $`w_i(\ln\bar{I} - \rho(c_i))`$ if a [`RobustLoss`] is given.
+    fn data_ln_likelihood(
+        &self,
+        data_res: &[F],
+        data_weights: &[F],
+        n_mc: F,
+        mc_sum: F,
+        robust_loss: Option<RobustLoss<F>>,
+        parallel: bool,
+    ) -> F {
+        let ln_mean = (mc_sum / n_mc).ln();
+        let term = |l: &F, w: &F| match robust_loss {
+            None => *w * self.ln_intensity(*l),
+            Some(_) if self.is_dropped(*l) => F::zero(),
+            Some(loss) => *w * (ln_mean - loss.value(ln_mean - self.ln_intensity(*l))),
+        };
+        if self.deterministic_reduction {
+            let terms: Vec<F> = if parallel {
+                data_res
+                    .par_iter()
+                    .zip(data_weights)
+                    .map(|(l, w)| term(l, w))
+                    .collect()
+            } else {
+                data_res
+                    .iter()
+                    .zip(data_weights)
+                    .map(|(l, w)| term(l, w))
+                    .collect()
+            };
+            return reproducible_sum(&terms, parallel);
+        }
+        if parallel {
+            data_res
+                .par_iter()
+                .zip(data_weights)
+                .map(|(l, w)| term(l, w))
+                .sum()
+        } else {
+            data_res
+                .iter()
+                .zip(data_weights)
+                .map(|(l, w)| term(l, w))
+                .sum()
+        }
+    }
+
+    /// Computes the logarithm of the intensity of a data event, respecting the
+    /// [`IntensityFloor`].
+    fn ln_intensity(&self, value: F) -> F {
+        if self.is_skipped(value) {
+            return F::zero();
+        }
+        self.intensity_floor
+            .map_or_else(|| value.ln(), |floor| floor.ln(value))
+    }
+
+    /// Checks that the intensity of a data event is not below the [`IntensityFloor`], so that it
+    /// contributes to the gradient.
+    fn above_floor(&self, value: F) -> bool {
+        !self.is_skipped(value)
+            && self
+                .intensity_floor
+                .is_none_or(|floor| value >= floor.value())
+    }
+
+    /// Returns the indices of the data events whose intensity (or, with explicit yields, total
+    /// density) is below the [`IntensityFloor`] for the given free parameters.
+    ///
+    /// This is always empty if there is no [`IntensityFloor`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute`] for more information.
+    pub fn floored_events(&self, parameters: &[F]) -> Result<Vec<usize>, RustitudeError> {
+        let Some(floor) = self.intensity_floor.map(|floor| floor.value()) else {
+            return Ok(Vec::new());
+        };
+        let mut values = self.data_manager.evaluate(parameters)?;
+        if !self.yields.is_empty() {
+            let n_s = parameters[self.mc_manager.get_n_free()];
+            let (n_mc, mc_sum) = self.mc_terms(parameters, false)?;
+            let mean = mc_sum / n_mc;
+            let (n_b, background) = self
+                .background_density(parameters, None, None)
+                .unwrap_or_else(|| (F::zero(), vec![F::zero(); values.len()]));
+            values = values
+                .iter()
+                .zip(background)
+                .map(|(l, b)| n_s * *l / mean + n_b * b)
+                .collect();
+        }
+        Ok(values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value < floor)
+            .map(|(i, _)| i)
+            .collect())
+    }
+
+    /// Computes the total Monte-Carlo weight and the weighted sum of Monte-Carlo intensities,
+    /// respecting [`ExtendedLogLikelihood::wide_mc_accumulation`].
+    fn mc_normalization(&self, mc_norm_int: &[F], mc_weights: &[F]) -> (F, F) {
+        if self.deterministic_reduction {
+            return self.reproducible_mc_normalization(mc_norm_int, mc_weights, false);
+        }
+        if self.wide_mc_accumulation {
+            let n_mc: f64 = mc_weights.iter().map(|w| convert!(*w, f64)).sum();
+            let mc_sum: f64 = mc_norm_int
+                .iter()
+                .zip(mc_weights)
+                .map(|(l, w)| convert!(*w, f64) * convert!(*l, f64))
+                .sum();
+            (convert!(n_mc, F), convert!(mc_sum, F))
+        } else {
+            let n_mc = mc_weights.iter().copied().sum::<F>();
+            let mc_sum = mc_norm_int
+                .iter()
+                .zip(mc_weights)
+                .map(|(l, w)| *w * *l)
+                .sum::<F>();
+            (n_mc, mc_sum)
+        }
+    }
+
+    /// Parallel version of [`ExtendedLogLikelihood::mc_normalization`].
+    fn par_mc_normalization(&self, mc_norm_int: &[F], mc_weights: &[F]) -> (F, F) {
+        if self.deterministic_reduction {
+            return self.reproducible_mc_normalization(mc_norm_int, mc_weights, true);
+        }
+        if self.wide_mc_accumulation {
+            let n_mc: f64 = mc_weights.par_iter().map(|w| convert!(*w, f64)).sum();
+            let mc_sum: f64 = mc_norm_int
+                .par_iter()
+                .zip(mc_weights)
+                .map(|(l, w)| convert!(*w, f64) * convert!(*l, f64))
+                .sum();
+            (convert!(n_mc, F), convert!(mc_sum, F))
+        } else {
+            let n_mc = mc_weights.iter().copied().sum::<F>();
+            let mc_sum = mc_norm_int
+                .par_iter()
+                .zip(mc_weights)
+                .map(|(l, w)| *w * *l)
+                .sum::<F>();
+            (n_mc, mc_sum)
+        }
+    }
+
+    /// Version of [`ExtendedLogLikelihood::mc_normalization`] which uses [`reproducible_sum`].
+    fn reproducible_mc_normalization(
+        &self,
+        mc_norm_int: &[F],
+        mc_weights: &[F],
+        parallel: bool,
+    ) -> (F, F) {
+        if self.wide_mc_accumulation {
+            let weights: Vec<f64> = mc_weights.iter().map(|w| convert!(*w, f64)).collect();
+            let terms: Vec<f64> = mc_norm_int
+                .iter()
+                .zip(&weights)
+                .map(|(l, w)| *w * convert!(*l, f64))
+                .collect();
+            (
+                convert!(reproducible_sum(&weights, parallel), F),
+                convert!(reproducible_sum(&terms, parallel), F),
+            )
+        } else {
+            let terms: Vec<F> = mc_norm_int
+                .iter()
+                .zip(mc_weights)
+                .map(|(l, w)| *w * *l)
+                .collect();
+            (
+                reproducible_sum(mc_weights, parallel),
+                reproducible_sum(&terms, parallel),
+            )
+        }
+    }
+
+    /// Use a [`LikelihoodKind::Binned`] likelihood, binning the data and Monte-Carlo in the value
+    /// of `variable` (see [`Binning::new`]).
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are fewer
+    /// than two edges or the edges are not strictly increasing.
+    pub fn set_binned(
+        &mut self,
+        edges: Vec<F>,
+        variable: impl Fn(&Event<F>) -> F + Sync,
+    ) -> Result<(), RustitudeError> {
+        self.kind = LikelihoodKind::Binned(Binning::new(
+            edges,
+            variable,
+            &self.data_manager.dataset,
+            &self.mc_manager.dataset,
+        )?);
+        Ok(())
+    }
+
+    /// Checks that the [`LikelihoodKind`] can be evaluated with the current configuration.
+    fn check_kind(&self) -> Result<(), RustitudeError> {
+        if self.kind.is_extended() {
+            return Ok(());
+        }
+        if !self.yields.is_empty() || self.robust_loss.is_some() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "only the extended likelihood supports explicit yields and robust losses"
+                    .to_string(),
+            ));
+        }
+        if let LikelihoodKind::Binned(binning) = &self.kind {
+            if binning.data_bins.len() != self.data_manager.dataset.len()
+                || binning.mc_bins.len() != self.mc_manager.dataset.len()
+            {
+                return Err(RustitudeError::InvalidParameterValue(
+                    "the binning does not match the number of data and Monte-Carlo events"
+                        .to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluates the data intensities and the accepted Monte-Carlo intensities, optionally at the
+    /// given data and Monte-Carlo indices.
+    fn event_values(
+        &self,
+        parameters: &[F],
+        indices: Option<(&[usize], &[usize])>,
+        parallel: bool,
+    ) -> Result<(Vec<F>, Vec<F>), RustitudeError> {
+        let (data_res, mc_res) = match (indices, parallel) {
+            (None, false) => (
+                self.data_manager.evaluate(parameters)?,
+                self.mc_manager.evaluate(parameters)?,
+            ),
+            (None, true) => (
+                self.data_manager.par_evaluate(parameters)?,
+                self.mc_manager.par_evaluate(parameters)?,
+            ),
+            (Some((indices_data, indices_mc)), false) => (
+                self.data_manager
+                    .evaluate_indexed(parameters, indices_data)?,
+                self.mc_manager.evaluate_indexed(parameters, indices_mc)?,
+            ),
+            (Some((indices_data, indices_mc)), true) => (
+                self.data_manager
+                    .par_evaluate_indexed(parameters, indices_data)?,
+                self.mc_manager
+                    .par_evaluate_indexed(parameters, indices_mc)?,
+            ),
+        };
+        Ok((
+            data_res,
+            self.accepted(mc_res, indices.map(|(_, indices_mc)| indices_mc)),
+        ))
+    }
+
+    /// Evaluates $`-2\ln\mathcal{L}`$ for the [`LikelihoodKind`] of the
+    /// [`ExtendedLogLikelihood`], optionally at the given data and Monte-Carlo indices.
+    fn evaluate_kind(
+        &self,
+        parameters: &[F],
+        indices: Option<(&[usize], &[usize])>,
+        parallel: bool,
+    ) -> Result<F, RustitudeError> {
+        self.check_kind()?;
+        let (data_res, mc_res) = self.event_values(parameters, indices, parallel)?;
+        let (data_weights, mc_weights) = match indices {
+            Some((indices_data, indices_mc)) => (
+                self.data_manager.dataset.weights_indexed(indices_data),
+                self.mc_manager.dataset.weights_indexed(indices_mc),
+            ),
+            None => (
+                self.data_manager.dataset.weights(),
+                self.mc_manager.dataset.weights(),
+            ),
+        };
+        let _span = debug_span!("reduction").entered();
+        profiling::timer(Stage::Reduction, "ExtendedLogLikelihood").time(1, || {
+            let n_data = data_weights.iter().copied().sum::<F>();
+            let (n_mc, mc_sum) = self.mc_normalization(&mc_res, &mc_weights);
+            let ln_l = match &self.kind {
+                LikelihoodKind::Extended => {
+                    self.data_ln_likelihood(&data_res, &data_weights, n_mc, mc_sum, None, false)
+                        - (n_data / n_mc) * mc_sum
+                }
+                LikelihoodKind::Unextended => {
+                    self.data_ln_likelihood(&data_res, &data_weights, n_mc, mc_sum, None, false)
+                        - n_data * (mc_sum / n_mc).ln()
+                }
+                LikelihoodKind::Binned(binning) => {
+                    let counts = binning.sums(
+                        &binning.data_bins,
+                        &data_weights,
+                        indices.map(|(indices_data, _)| indices_data),
+                    );
+                    let weighted: Vec<F> = mc_res
+                        .iter()
+                        .zip(&mc_weights)
+                        .map(|(l, w)| *w * *l)
+                        .collect();
+                    let scale = n_data / n_mc;
+                    binning
+                        .sums(
+                            &binning.mc_bins,
+                            &weighted,
+                            indices.map(|(_, indices_mc)| indices_mc),
+                        )
+                        .into_iter()
+                        .zip(counts)
+                        .map(|(mc_b, n_b)| {
+                            let mu_b = scale * mc_b;
+                            if n_b == F::zero() {
+                                -mu_b
+                            } else {
+                                n_b * mu_b.ln() - mu_b
+                            }
+                        })
+                        .sum()
+                }
+            };
+            Ok(convert!(-2, F) * ln_l + self.data_manager.model.constraint_penalty(parameters)?)
+        })
+    }
+
+    /// Evaluates the gradient of $`-2\ln\mathcal{L}`$ for a [`LikelihoodKind`] other than
+    /// [`LikelihoodKind::Extended`].
+    fn kind_gradient(&self, parameters: &[F], parallel: bool) -> Result<Vec<F>, RustitudeError> {
+        self.check_kind()?;
+        let n_data = self
+            .data_manager
+            .dataset
+            .weights()
+            .iter()
+            .copied()
+            .sum::<F>();
+        let n_mc = self.mc_manager.dataset.weights().iter().copied().sum::<F>();
+        if let LikelihoodKind::Binned(binning) = &self.kind {
+            // d(ln L)/dp = sum_b (n_b / mu_b - 1) d(mu_b)/dp only depends on the Monte-Carlo
+            let mc_res = self.accepted(
+                if parallel {
+                    self.mc_manager.par_evaluate(parameters)?
+                } else {
+                    self.mc_manager.evaluate(parameters)?
+                },
+                None,
+            );
+            let mc_weights = self.mc_manager.dataset.weights();
+            let weighted: Vec<F> = mc_res
+                .iter()
+                .zip(&mc_weights)
+                .map(|(l, w)| *w * *l)
+                .collect();
+            let scale = n_data / n_mc;
+            let counts = binning.sums(
+                &binning.data_bins,
+                &self.data_manager.dataset.weights(),
+                None,
+            );
+            let factors: Vec<F> = binning
+                .sums(&binning.mc_bins, &weighted, None)
+                .into_iter()
+                .zip(counts)
+                .map(|(mc_b, n_b)| scale * (n_b / (scale * mc_b) - F::one()))
+                .collect();
+            let mc_weight = |i: usize, _: F, event: &Event<F>| {
+                binning.mc_bins[i].map_or_else(F::zero, |bin| {
+                    factors[bin] * event.weight * self.acceptance_at(event.index)
+                })
+            };
+            let mc_gradient = if parallel {
+                self.mc_manager.par_gradient_sum(parameters, mc_weight)?
+            } else {
+                self.mc_manager.gradient_sum(parameters, mc_weight)?
+            };
+            return self.combine_gradients(
+                parameters,
+                &vec![F::zero(); mc_gradient.len()],
+                &mc_gradient,
+                -F::one(),
+            );
+        }
+        let data_weight = |_: usize, value: F, event: &Event<F>| {
+            if self.above_floor(value) {
+                event.weight / value
+            } else {
+                F::zero()
+            }
+        };
+        let mc_term = |_: usize, value: F, event: &Event<F>| {
+            let weight = event.weight * self.acceptance_at(event.index);
+            (weight * value, weight)
+        };
+        let (data_gradient, (mc_sum, mc_gradient)) = if parallel {
+            (
+                self.data_manager
+                    .par_gradient_sum(parameters, data_weight)?,
+                self.mc_manager
+                    .par_value_gradient_sum(parameters, mc_term)?,
+            )
+        } else {
+            (
+                self.data_manager.gradient_sum(parameters, data_weight)?,
+                self.mc_manager.value_gradient_sum(parameters, mc_term)?,
+            )
+        };
+        let mc_scale = match self.kind {
+            LikelihoodKind::Unextended => n_data / mc_sum,
+            _ => n_data / n_mc,
+        };
+        self.combine_gradients(parameters, &data_gradient, &mc_gradient, mc_scale)
+    }
+
+    /// Evaluate the [`ExtendedLogLikelihood`] over the [`Dataset`] with the given free parameters.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute`] for more information.
+    pub fn evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        let fx = self.evaluate_with_loss(parameters, self.robust_loss)?;
+        self.notify(parameters, fx);
+        Ok(fx)
+    }
+
+    /// Evaluate the standard $`-2\ln\mathcal{L}`$ of the [`ExtendedLogLikelihood`] with the
+    /// given free parameters, ignoring any [`RobustLoss`]. This is identical to
+    /// [`ExtendedLogLikelihood::evaluate`] if [`ExtendedLogLikelihood::robust_loss`] is `None`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute`] for more information.
+    pub fn evaluate_standard(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        self.evaluate_with_loss(parameters, None)
+    }
+
+    /// Evaluates $`-2\ln\mathcal{L}`$ with an optional [`RobustLoss`].
+    #[allow(clippy::suboptimal_flops)]
+    fn evaluate_with_loss(
+        &self,
+        parameters: &[F],
+        robust_loss: Option<RobustLoss<F>>,
+    ) -> Result<F, RustitudeError> {
+        if !self.kind.is_extended() {
+            return self.evaluate_kind(parameters, None, false);
+        }
+        let data_res = self.data_manager.evaluate(parameters)?;
+        let data_weights = self.data_manager.dataset.weights();
+        let (n_mc, mc_sum) = self.mc_terms(parameters, false)?;
+        self.reduce(
+            parameters,
+            &data_res,
+            &data_weights,
+            n_mc,
+            mc_sum,
+            robust_loss,
+            false,
+        )
+    }
+
+    /// Reduces the evaluated data intensities and the Monte-Carlo terms to
+    /// $`-2\ln\mathcal{L}`$, recording the time spent (see [`crate::profiling`]).
+    #[allow(clippy::suboptimal_flops, clippy::too_many_arguments)]
+    fn reduce(
+        &self,
+        parameters: &[F],
+        data_res: &[F],
+        data_weights: &[F],
+        n_mc: F,
+        mc_sum: F,
+        robust_loss: Option<RobustLoss<F>>,
+        parallel: bool,
+    ) -> Result<F, RustitudeError> {
+        let _span = debug_span!("reduction").entered();
+        profiling::timer(Stage::Reduction, "ExtendedLogLikelihood").time(1, || {
+            if !self.yields.is_empty() {
+                self.check_robust_loss()?;
+                let ln_l = self.yield_ln_likelihood(
+                    parameters,
+                    data_res,
+                    data_weights,
+                    n_mc,
+                    mc_sum,
+                    None,
+                    None,
+                );
+                return Ok(convert!(-2, F) * ln_l
+                    + self.data_manager.model.constraint_penalty(parameters)?);
+            }
+            let n_data = data_weights.iter().copied().sum::<F>();
+            let ln_l = self.data_ln_likelihood(
+                data_res,
+                data_weights,
+                n_mc,
+                mc_sum,
+                robust_loss,
+                parallel,
+            ) - (n_data / n_mc) * mc_sum;
+            Ok(convert!(-2, F) * ln_l + self.data_manager.model.constraint_penalty(parameters)?)
+        })
+    }
+
+    /// Evaluate the [`ExtendedLogLikelihood`] over the [`Dataset`] with the given free parameters.
+    ///
+    /// This method allows the user to supply two lists of indices and will only evaluate events at
+    /// those indices. This can be used to evaluate only a subset of events or to resample events
+    /// with replacement, such as in a bootstrap.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute`] for more information.
+    #[allow(clippy::suboptimal_flops)]
+    pub fn evaluate_indexed(
+        &self,
+        parameters: &[F],
+        indices_data: &[usize],
+        indices_mc: &[usize],
+    ) -> Result<F, RustitudeError> {
+        if !self.kind.is_extended() {
+            return self.evaluate_kind(parameters, Some((indices_data, indices_mc)), false);
+        }
+        let data_res = self
+            .data_manager
+            .evaluate_indexed(parameters, indices_data)?;
+        let data_weights = self.data_manager.dataset.weights_indexed(indices_data);
+        let n_data = data_weights.iter().copied().sum::<F>();
+        let mc_norm_int = self.accepted(
+            self.mc_manager.evaluate_indexed(parameters, indices_mc)?,
+            Some(indices_mc),
+        );
+        let mc_weights = self.mc_manager.dataset.weights_indexed(indices_mc);
+        let (n_mc, mc_sum) = self.mc_normalization(&mc_norm_int, &mc_weights);
+        if !self.yields.is_empty() {
+            self.check_robust_loss()?;
+            let ln_l = self.yield_ln_likelihood(
+                parameters,
+                &data_res,
+                &data_weights,
+                n_mc,
+                mc_sum,
+                Some(indices_data),
+                Some(indices_mc),
+            );
+            return Ok(
+                convert!(-2, F) * ln_l + self.data_manager.model.constraint_penalty(parameters)?
+            );
+        }
+        let ln_l = self.data_ln_likelihood(
+            &data_res,
+            &data_weights,
+            n_mc,
+            mc_sum,
+            self.robust_loss,
+            false,
+        ) - (n_data / n_mc) * mc_sum;
+        Ok(convert!(-2, F) * ln_l + self.data_manager.model.constraint_penalty(parameters)?)
+    }
+
+    /// Evaluate the [`ExtendedLogLikelihood`] over the [`Dataset`] with the given free parameters.
+    ///
+    /// This method also allows the user to input a maximum number of threads to use in the
+    /// calculation, as it uses a parallel loop over events.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute`] for more information.
+    #[allow(clippy::suboptimal_flops)]
+    pub fn par_evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        let fx = self.install(|| {
+            if self.data_manager.model.contains_python_amplitudes
+                || self.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            if !self.kind.is_extended() {
+                return self.evaluate_kind(parameters, None, true);
+            }
+            let data_res = self.data_manager.par_evaluate(parameters)?;
+            let data_weights = self.data_manager.dataset.weights();
+            let (n_mc, mc_sum) = self.mc_terms(parameters, true)?;
+            self.reduce(
+                parameters,
+                &data_res,
+                &data_weights,
+                n_mc,
+                mc_sum,
+                self.robust_loss,
+                true,
+            )
+        })?;
+        self.notify(parameters, fx);
+        Ok(fx)
+    }
+
+    /// Evaluate the [`ExtendedLogLikelihood`] over the [`Dataset`] with the given free parameters.
+    ///
+    /// This method allows the user to supply two lists of indices and will only evaluate events at
+    /// those indices. This can be used to evaluate only a subset of events or to resample events
+    /// with replacement, such as in a bootstrap.
+    ///
+    /// This method also allows the user to input a maximum number of threads to use in the
+    /// calculation, as it uses a parallel loop over events.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute`] for more information.
+    #[allow(clippy::suboptimal_flops)]
+    pub fn par_evaluate_indexed(
+        &self,
+        parameters: &[F],
+        indices_data: &[usize],
+        indices_mc: &[usize],
+    ) -> Result<F, RustitudeError> {
+        self.install(|| {
+            if self.data_manager.model.contains_python_amplitudes
+                || self.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            if !self.kind.is_extended() {
+                return self.evaluate_kind(parameters, Some((indices_data, indices_mc)), true);
+            }
+            let data_res = self
+                .data_manager
+                .par_evaluate_indexed(parameters, indices_data)?;
+            let data_weights = self.data_manager.dataset.weights_indexed(indices_data);
+            let n_data = data_weights.iter().copied().sum::<F>();
+            let mc_norm_int = self.accepted(
+                self.mc_manager
+                    .par_evaluate_indexed(parameters, indices_mc)?,
+                Some(indices_mc),
+            );
+            let mc_weights = self.mc_manager.dataset.weights_indexed(indices_mc);
+            let (n_mc, mc_sum) = self.par_mc_normalization(&mc_norm_int, &mc_weights);
+            if !self.yields.is_empty() {
+                self.check_robust_loss()?;
+                let ln_l = self.yield_ln_likelihood(
+                    parameters,
+                    &data_res,
+                    &data_weights,
+                    n_mc,
+                    mc_sum,
+                    Some(indices_data),
+                    Some(indices_mc),
+                );
+                return Ok(convert!(-2, F) * ln_l
+                    + self.data_manager.model.constraint_penalty(parameters)?);
+            }
+            let ln_l = self.data_ln_likelihood(
+                &data_res,
+                &data_weights,
+                n_mc,
+                mc_sum,
+                self.robust_loss,
+                true,
+            ) - (n_data / n_mc) * mc_sum;
+            Ok(convert!(-2, F) * ln_l + self.data_manager.model.constraint_penalty(parameters)?)
+        })
+    }
+
+    /// Evaluate the gradient of [`ExtendedLogLikelihood::evaluate`] with respect to the free
+    /// parameters.
+    ///
+    /// Derivatives are computed analytically through the [`Model`] (see
+    /// [`Model::compute_gradient`]), so this requires a single pass over each [`Dataset`] rather
+    /// than the two passes per free parameter needed for finite differences.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute_gradient`] for more information.
+    pub fn gradient(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        if !self.kind.is_extended() {
+            return self.kind_gradient(parameters, false);
+        }
+        if !self.yields.is_empty() {
+            return self.yield_gradient(parameters, false);
+        }
+        if let Some(robust_loss) = self.robust_loss {
+            return self.robust_gradient(parameters, robust_loss, false);
+        }
+        if let Some(integrals) = self.cached_integrals() {
+            return Ok(self
+                .cached_evaluate_gradient(parameters, integrals, false)?
+                .1);
+        }
+        let n_data = self
+            .data_manager
+            .dataset
+            .weights()
+            .iter()
+            .copied()
+            .sum::<F>();
+        let n_mc = self.mc_manager.dataset.weights().iter().copied().sum::<F>();
+        let data_gradient = self
+            .data_manager
+            .gradient_sum(parameters, |_, value, event| {
+                if self.above_floor(value) {
+                    event.weight / value
+                } else {
+                    F::zero()
+                }
+            })?;
+        let mc_gradient = self.mc_manager.gradient_sum(parameters, |_, _, event| {
+            event.weight * self.acceptance_at(event.index)
+        })?;
+        self.combine_gradients(parameters, &data_gradient, &mc_gradient, n_data / n_mc)
+    }
+
+    /// Evaluate the gradient of [`ExtendedLogLikelihood::evaluate`] with respect to the free
+    /// parameters.
+    ///
+    /// This version uses a parallel loop over events. See [`ExtendedLogLikelihood::gradient`] for
+    /// more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute_gradient`] for more information.
+    pub fn par_gradient(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        self.install(|| {
+            if self.data_manager.model.contains_python_amplitudes
+                || self.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            if !self.kind.is_extended() {
+                return self.kind_gradient(parameters, true);
+            }
+            if !self.yields.is_empty() {
+                return self.yield_gradient(parameters, true);
+            }
+            if let Some(robust_loss) = self.robust_loss {
+                return self.robust_gradient(parameters, robust_loss, true);
+            }
+            if let Some(integrals) = self.cached_integrals() {
+                return Ok(self
+                    .cached_evaluate_gradient(parameters, integrals, true)?
+                    .1);
+            }
+            let n_data = self
+                .data_manager
+                .dataset
+                .weights()
+                .iter()
+                .copied()
+                .sum::<F>();
+            let n_mc = self.mc_manager.dataset.weights().iter().copied().sum::<F>();
+            let data_gradient =
+                self.data_manager
+                    .par_gradient_sum(parameters, |_, value, event| {
+                        if self.above_floor(value) {
+                            event.weight / value
+                        } else {
+                            F::zero()
+                        }
+                    })?;
+            let mc_gradient = self
+                .mc_manager
+                .par_gradient_sum(parameters, |_, _, event| {
+                    event.weight * self.acceptance_at(event.index)
+                })?;
+            self.combine_gradients(parameters, &data_gradient, &mc_gradient, n_data / n_mc)
+        })
+    }
+
+    /// Evaluate [`ExtendedLogLikelihood::evaluate`] and [`ExtendedLogLikelihood::gradient`]
+    /// together.
+    ///
+    /// The value of each event is a by-product of its gradient (see
+    /// [`Model::compute_gradient`]), so both are accumulated in a single pass over each
+    /// [`Dataset`] rather than the two passes needed by separate calls. Likelihoods with explicit
+    /// yields, a [`RobustLoss`], [`ExtendedLogLikelihood::wide_mc_accumulation`], or
+    /// [`ExtendedLogLikelihood::deterministic_reduction`] need the Monte-Carlo normalization
+    /// before the data pass (or every term before summing) and fall back to separate calls.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute_gradient`] for more information.
+    pub fn evaluate_with_gradient(&self, parameters: &[F]) -> Result<(F, Vec<F>), RustitudeError> {
+        self.fused_evaluate_gradient(parameters, false)
+    }
+
+    /// Evaluate [`ExtendedLogLikelihood::evaluate`] and [`ExtendedLogLikelihood::gradient`]
+    /// together.
+    ///
+    /// This version uses a parallel loop over events. See
+    /// [`ExtendedLogLikelihood::evaluate_with_gradient`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute_gradient`] for more information.
+    pub fn par_evaluate_with_gradient(
+        &self,
+        parameters: &[F],
+    ) -> Result<(F, Vec<F>), RustitudeError> {
+        self.install(|| {
+            if self.data_manager.model.contains_python_amplitudes
+                || self.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            self.fused_evaluate_gradient(parameters, true)
+        })
+    }
+
+    /// Accumulates $`-2\ln\mathcal{L}`$ and its gradient in a single pass over each
+    /// [`Dataset`].
+    #[allow(clippy::suboptimal_flops)]
+    fn fused_evaluate_gradient(
+        &self,
+        parameters: &[F],
+        parallel: bool,
+    ) -> Result<(F, Vec<F>), RustitudeError> {
+        if self.yields.is_empty()
+            && self.robust_loss.is_none()
+            && !self.deterministic_reduction
+            && self.kind.is_extended()
+        {
+            if let Some(integrals) = self.cached_integrals() {
+                return self.cached_evaluate_gradient(parameters, integrals, parallel);
+            }
+        }
+        if !self.yields.is_empty()
+            || self.robust_loss.is_some()
+            || self.wide_mc_accumulation
+            || self.deterministic_reduction
+            || !self.kind.is_extended()
+        {
+            return if parallel {
+                Ok((
+                    self.par_evaluate(parameters)?,
+                    self.par_gradient(parameters)?,
+                ))
+            } else {
+                Ok((self.evaluate(parameters)?, self.gradient(parameters)?))
+            };
+        }
+        let n_data = self
+            .data_manager
+            .dataset
+            .weights()
+            .iter()
+            .copied()
+            .sum::<F>();
+        let n_mc = self.mc_manager.dataset.weights().iter().copied().sum::<F>();
+        let data_term = |_: usize, value: F, event: &Event<F>| {
+            let weight = if self.above_floor(value) {
+                event.weight / value
+            } else {
+                F::zero()
+            };
+            (event.weight * self.ln_intensity(value), weight)
+        };
+        let mc_term = |_: usize, value: F, event: &Event<F>| {
+            let weight = event.weight * self.acceptance_at(event.index);
+            (weight * value, weight)
+        };
+        let ((data_sum, data_gradient), (mc_sum, mc_gradient)) = if parallel {
+            (
+                self.data_manager
+                    .par_value_gradient_sum(parameters, data_term)?,
+                self.mc_manager
+                    .par_value_gradient_sum(parameters, mc_term)?,
+            )
+        } else {
+            (
+                self.data_manager
+                    .value_gradient_sum(parameters, data_term)?,
+                self.mc_manager.value_gradient_sum(parameters, mc_term)?,
+            )
+        };
+        let ln_l = data_sum - (n_data / n_mc) * mc_sum;
+        let value =
+            convert!(-2, F) * ln_l + self.data_manager.model.constraint_penalty(parameters)?;
+        let gradient =
+            self.combine_gradients(parameters, &data_gradient, &mc_gradient, n_data / n_mc)?;
+        Ok((value, gradient))
+    }
+
+    /// Computes the gradient of $`-2\ln\mathcal{L}`$ with explicit yields (see
+    /// [`ExtendedLogLikelihood::add_signal_yield`]).
+    fn yield_gradient(&self, parameters: &[F], parallel: bool) -> Result<Vec<F>, RustitudeError> {
+        self.check_robust_loss()?;
+        let n_model = self.mc_manager.get_n_free();
+        let n_s = parameters[n_model];
+        let (data_res, mc_res) = if parallel {
+            (
+                self.data_manager.par_evaluate(parameters)?,
+                self.accepted(self.mc_manager.par_evaluate(parameters)?, None),
+            )
+        } else {
+            (
+                self.data_manager.evaluate(parameters)?,
+                self.accepted(self.mc_manager.evaluate(parameters)?, None),
+            )
+        };
+        let data_weights = self.data_manager.dataset.weights();
+        let (n_mc, mc_sum) = self.mc_normalization(&mc_res, &self.mc_manager.dataset.weights());
+        let mean = mc_sum / n_mc;
+        let (n_b, background) = self
+            .background_density(parameters, None, None)
+            .unwrap_or_else(|| (F::zero(), vec![F::zero(); data_res.len()]));
+        let totals: Vec<F> = data_res
+            .iter()
+            .zip(&background)
+            .map(|(l, b)| n_s * *l / mean + n_b * *b)
+            .collect();
+        // Events below the intensity floor do not depend on the parameters
+        let data_weights: Vec<F> = data_weights
+            .iter()
+            .zip(&totals)
+            .map(|(w, t)| if self.above_floor(*t) { *w } else { F::zero() })
+            .collect();
+        let data_weight = |i: usize, _: F, _: &Event<F>| data_weights[i] / totals[i];
+        let mc_weight =
+            |_: usize, _: F, event: &Event<F>| event.weight * self.acceptance_at(event.index);
+        let (data_gradient, mc_gradient) = if parallel {
+            (
+                self.data_manager
+                    .par_gradient_sum(parameters, data_weight)?,
+                self.mc_manager.par_gradient_sum(parameters, mc_weight)?,
+            )
+        } else {
+            (
+                self.data_manager.gradient_sum(parameters, data_weight)?,
+                self.mc_manager.gradient_sum(parameters, mc_weight)?,
+            )
+        };
+        // A = sum_i w_i I_i / t_i, where t_i is the total density at each data event
+        let a: F = data_res
+            .iter()
+            .zip(&data_weights)
+            .zip(&totals)
+            .map(|((l, w), t)| *w * *l / *t)
+            .sum();
+        let data_gradient: Vec<F> = data_gradient.iter().map(|g| n_s / mean * *g).collect();
+        let mut gradient = self.combine_gradients(
+            parameters,
+            &data_gradient,
+            &mc_gradient,
+            n_s * a / (mean * mean * n_mc),
+        )?;
+        gradient.push(convert!(-2, F) * (a / mean - F::one()));
+        if self.background_template.is_some() {
+            let b_sum: F = background
+                .iter()
+                .zip(&data_weights)
+                .zip(&totals)
+                .map(|((b, w), t)| *w * *b / *t)
+                .sum();
+            gradient.push(convert!(-2, F) * (b_sum - F::one()));
+        }
+        Ok(gradient)
+    }
+
+    /// Computes the gradient of $`-2\ln\mathcal{L}`$ with a [`RobustLoss`].
+    fn robust_gradient(
+        &self,
+        parameters: &[F],
+        robust_loss: RobustLoss<F>,
+        parallel: bool,
+    ) -> Result<Vec<F>, RustitudeError> {
+        let (data_res, mc_res) = if parallel {
+            (
+                self.data_manager.par_evaluate(parameters)?,
+                self.accepted(self.mc_manager.par_evaluate(parameters)?, None),
+            )
+        } else {
+            (
+                self.data_manager.evaluate(parameters)?,
+                self.accepted(self.mc_manager.evaluate(parameters)?, None),
+            )
+        };
+        let data_weights = self.data_manager.dataset.weights();
+        let n_data = data_weights.iter().copied().sum::<F>();
+        let (n_mc, mc_sum) = self.mc_normalization(&mc_res, &self.mc_manager.dataset.weights());
+        let ln_mean = (mc_sum / n_mc).ln();
+        // Each event depends on the parameters through I_i and through the mean intensity
+        let robust_weights: Vec<F> = data_res
+            .iter()
+            .map(|l| {
+                if self.is_dropped(*l) {
+                    F::zero()
+                } else {
+                    robust_loss.weight(ln_mean - self.ln_intensity(*l))
+                }
+            })
+            .collect();
+        let mean_weight: F = data_res
+            .iter()
+            .zip(&data_weights)
+            .zip(&robust_weights)
+            .filter(|((l, _), _)| !self.is_dropped(**l))
+            .map(|((_, w), r)| *w * (F::one() - *r))
+            .sum();
+        let data_weight = |i: usize, value: F, event: &Event<F>| {
+            if self.above_floor(value) {
+                event.weight * robust_weights[i] / value
+            } else {
+                F::zero()
+            }
+        };
+        let mc_weight =
+            |_: usize, _: F, event: &Event<F>| event.weight * self.acceptance_at(event.index);
+        let (data_gradient, mc_gradient) = if parallel {
+            (
+                self.data_manager
+                    .par_gradient_sum(parameters, data_weight)?,
+                self.mc_manager.par_gradient_sum(parameters, mc_weight)?,
+            )
+        } else {
+            (
+                self.data_manager.gradient_sum(parameters, data_weight)?,
+                self.mc_manager.gradient_sum(parameters, mc_weight)?,
+            )
+        };
+        self.combine_gradients(
+            parameters,
+            &data_gradient,
+            &mc_gradient,
+            n_data / n_mc - mean_weight / mc_sum,
+        )
+    }
+
+    /// Combines the data and Monte-Carlo gradient sums into the gradient of $`-2\ln\mathcal{L}`$
+    /// with respect to the free parameters, including any Gaussian constraints.
+    pub(crate) fn combine_gradients(
+        &self,
+        parameters: &[F],
+        data_gradient: &[F],
+        mc_gradient: &[F],
+        mc_scale: F,
+    ) -> Result<Vec<F>, RustitudeError> {
+        let model = &self.data_manager.model;
+        let full: Vec<F> = data_gradient
+            .iter()
+            .zip(mc_gradient)
+            .map(|(d, m)| convert!(-2, F) * (*d - mc_scale * *m))
+            .collect();
+        Ok(model
+            .free_gradient(parameters, &full)
+            .into_iter()
+            .zip(model.constraint_penalty_gradient(parameters)?)
+            .map(|(g, c)| g + c)
+            .collect())
+    }
+
+    /// Checks that the [`ExtendedLogLikelihood`] can be split into additive partial sums, which
+    /// is not the case with explicit yields, a [`RobustLoss`], or a [`LikelihoodKind`] other
+    /// than [`LikelihoodKind::Extended`].
+    pub(crate) fn check_additive(&self) -> Result<(), RustitudeError> {
+        if !self.yields.is_empty() || self.robust_loss.is_some() || !self.kind.is_extended() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "explicit yields, robust losses, and likelihoods other than the extended \
+                 likelihood cannot be split over partial datasets"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Computes the sums over the events of this [`ExtendedLogLikelihood`] which add up to the
+    /// sums of a larger likelihood split over several datasets, namely
+    /// $`\sum_{\text{data}} w_i\ln(I_i)`$, the total data weight, the total Monte-Carlo weight,
+    /// and the weighted sum of accepted Monte-Carlo intensities.
+    pub(crate) fn partial_sums(
+        &self,
+        parameters: &[F],
+        parallel: bool,
+    ) -> Result<[F; 4], RustitudeError> {
+        let (data_res, (n_mc, mc_sum)) = if parallel {
+            (
+                self.data_manager.par_evaluate(parameters)?,
+                self.mc_terms(parameters, true)?,
+            )
+        } else {
+            (
+                self.data_manager.evaluate(parameters)?,
+                self.mc_terms(parameters, false)?,
+            )
+        };
+        let data_weights = self.data_manager.dataset.weights();
+        let n_data = data_weights.iter().copied().sum::<F>();
+        let data_sum =
+            self.data_ln_likelihood(&data_res, &data_weights, n_mc, mc_sum, None, parallel);
+        Ok([data_sum, n_data, n_mc, mc_sum])
+    }
+
+    /// Computes the gradient sums over the data and Monte-Carlo events of this
+    /// [`ExtendedLogLikelihood`] with respect to every [`Parameter`], which add up to the
+    /// gradient sums of a larger likelihood split over several datasets (see
+    /// [`ExtendedLogLikelihood::combine_gradients`]).
+    pub(crate) fn partial_gradient_sums(
+        &self,
+        parameters: &[F],
+        parallel: bool,
+    ) -> Result<(Vec<F>, Vec<F>), RustitudeError> {
+        let data_weight = |_: usize, value: F, event: &Event<F>| {
+            if self.above_floor(value) {
+                event.weight / value
+            } else {
+                F::zero()
+            }
+        };
+        let mc_weight =
+            |_: usize, _: F, event: &Event<F>| event.weight * self.acceptance_at(event.index);
+        if parallel {
+            Ok((
+                self.data_manager
+                    .par_gradient_sum(parameters, data_weight)?,
+                self.mc_manager.par_gradient_sum(parameters, mc_weight)?,
+            ))
+        } else {
+            Ok((
+                self.data_manager.gradient_sum(parameters, data_weight)?,
+                self.mc_manager.gradient_sum(parameters, mc_weight)?,
+            ))
         }
     }
 
-    /// Evaluate the [`ExtendedLogLikelihood`] over the [`Dataset`] with the given free parameters.
-    ///
-    /// # Errors
-    ///
-    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
-    /// [`Model::compute`] for more information.
-    #[allow(clippy::suboptimal_flops)]
-    pub fn evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
-        let data_res = self.data_manager.evaluate(parameters)?;
+    /// Evaluates [`ExtendedLogLikelihood::evaluate`] with every intensity multiplied by a
+    /// `normalization` factor, which is used for the per-dataset normalizations of a
+    /// [`SimultaneousLikelihood`](crate::simultaneous::SimultaneousLikelihood).
+    pub(crate) fn evaluate_normalized(
+        &self,
+        parameters: &[F],
+        normalization: F,
+        parallel: bool,
+    ) -> Result<F, RustitudeError> {
+        let (data_res, n_mc, mc_sum) = self.normalized_terms(parameters, parallel)?;
         let data_weights = self.data_manager.dataset.weights();
         let n_data = data_weights.iter().copied().sum::<F>();
-        let mc_norm_int = self.mc_manager.evaluate(parameters)?;
-        let mc_weights = self.mc_manager.dataset.weights();
-        let n_mc = mc_weights.iter().copied().sum::<F>();
         let ln_l = (data_res
             .iter()
             .zip(data_weights)
-            .map(|(l, w)| w * F::ln(*l))
+            .map(|(l, w)| w * self.ln_intensity(normalization * *l))
             .sum::<F>())
-            - (n_data / n_mc)
-                * (mc_norm_int
-                    .iter()
-                    .zip(mc_weights)
-                    .map(|(l, w)| w * *l)
-                    .sum::<F>());
-        Ok(convert!(-2, F) * ln_l)
+            - normalization * (n_data / n_mc) * mc_sum;
+        Ok(convert!(-2, F) * ln_l + self.data_manager.model.constraint_penalty(parameters)?)
     }
 
-    /// Evaluate the [`ExtendedLogLikelihood`] over the [`Dataset`] with the given free parameters.
+    /// Evaluates the gradient of [`ExtendedLogLikelihood::evaluate_normalized`] with respect to
+    /// the free parameters, followed by its derivative with respect to the `normalization`.
+    pub(crate) fn gradient_normalized(
+        &self,
+        parameters: &[F],
+        normalization: F,
+        parallel: bool,
+    ) -> Result<Vec<F>, RustitudeError> {
+        let (data_res, n_mc, mc_sum) = self.normalized_terms(parameters, parallel)?;
+        let data_weights = self.data_manager.dataset.weights();
+        let n_data = data_weights.iter().copied().sum::<F>();
+        let data_weight = |_: usize, value: F, event: &Event<F>| {
+            if self.above_floor(normalization * value) {
+                event.weight / value
+            } else {
+                F::zero()
+            }
+        };
+        let mc_weight =
+            |_: usize, _: F, event: &Event<F>| event.weight * self.acceptance_at(event.index);
+        let (data_gradient, mc_gradient) = if parallel {
+            (
+                self.data_manager
+                    .par_gradient_sum(parameters, data_weight)?,
+                self.mc_manager.par_gradient_sum(parameters, mc_weight)?,
+            )
+        } else {
+            (
+                self.data_manager.gradient_sum(parameters, data_weight)?,
+                self.mc_manager.gradient_sum(parameters, mc_weight)?,
+            )
+        };
+        let mut gradient = self.combine_gradients(
+            parameters,
+            &data_gradient,
+            &mc_gradient,
+            normalization * n_data / n_mc,
+        )?;
+        // Events below the intensity floor do not depend on the normalization
+        let n_above: F = data_res
+            .iter()
+            .zip(&data_weights)
+            .filter(|(l, _)| self.above_floor(normalization * **l))
+            .map(|(_, w)| *w)
+            .sum();
+        gradient.push(convert!(-2, F) * (n_above / normalization - (n_data / n_mc) * mc_sum));
+        Ok(gradient)
+    }
+
+    /// Evaluates the data intensities along with the total Monte-Carlo weight and the weighted
+    /// Monte-Carlo sum.
+    fn normalized_terms(
+        &self,
+        parameters: &[F],
+        parallel: bool,
+    ) -> Result<(Vec<F>, F, F), RustitudeError> {
+        if !self.yields.is_empty() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "a normalization cannot be applied to a likelihood with explicit yields"
+                    .to_string(),
+            ));
+        }
+        if self.robust_loss.is_some() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "a normalization cannot be applied to a likelihood with a robust loss".to_string(),
+            ));
+        }
+        if parallel {
+            if self.data_manager.model.contains_python_amplitudes
+                || self.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            let data_res = self.data_manager.par_evaluate(parameters)?;
+            let (n_mc, mc_sum) = self.mc_terms(parameters, true)?;
+            Ok((data_res, n_mc, mc_sum))
+        } else {
+            let data_res = self.data_manager.evaluate(parameters)?;
+            let (n_mc, mc_sum) = self.mc_terms(parameters, false)?;
+            Ok((data_res, n_mc, mc_sum))
+        }
+    }
+
+    /// Evaluate the Hessian matrix of [`ExtendedLogLikelihood::evaluate`] with respect to the
+    /// free parameters.
     ///
-    /// This method allows the user to supply two lists of indices and will only evaluate events at
-    /// those indices. This can be used to evaluate only a subset of events or to resample events
-    /// with replacement, such as in a bootstrap.
+    /// Each row is computed by central differences of the analytic gradient
+    /// ([`ExtendedLogLikelihood::gradient`]), and the result is symmetrized.
     ///
     /// # Errors
     ///
     /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
-    /// [`Model::compute`] for more information.
-    #[allow(clippy::suboptimal_flops)]
-    pub fn evaluate_indexed(
+    /// [`Model::compute_gradient`] for more information.
+    pub fn hessian(&self, parameters: &[F]) -> Result<Vec<Vec<F>>, RustitudeError> {
+        let rows = (0..parameters.len())
+            .map(|i| self.hessian_row(parameters, i, |x| self.gradient(x)))
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        Ok(symmetrize(rows))
+    }
+
+    /// Evaluate the Hessian matrix of [`ExtendedLogLikelihood::evaluate`] with respect to the
+    /// free parameters.
+    ///
+    /// This version evaluates the rows of the Hessian in parallel, each using
+    /// [`ExtendedLogLikelihood::par_gradient`]. See [`ExtendedLogLikelihood::hessian`] for more
+    /// information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
+    /// [`Model::compute_gradient`] for more information.
+    pub fn par_hessian(&self, parameters: &[F]) -> Result<Vec<Vec<F>>, RustitudeError> {
+        self.install(|| {
+            let rows = (0..parameters.len())
+                .into_par_iter()
+                .map(|i| self.hessian_row(parameters, i, |x| self.par_gradient(x)))
+                .collect::<Result<Vec<_>, RustitudeError>>()?;
+            Ok(symmetrize(rows))
+        })
+    }
+
+    /// Computes the `i`th row of the Hessian by central differences of the given gradient
+    /// function.
+    fn hessian_row(
         &self,
         parameters: &[F],
-        indices_data: &[usize],
-        indices_mc: &[usize],
-    ) -> Result<F, RustitudeError> {
-        let data_res = self
-            .data_manager
-            .evaluate_indexed(parameters, indices_data)?;
-        let data_weights = self.data_manager.dataset.weights_indexed(indices_data);
-        let n_data = data_weights.iter().copied().sum::<F>();
-        let mc_norm_int = self.mc_manager.evaluate_indexed(parameters, indices_mc)?;
-        let mc_weights = self.mc_manager.dataset.weights_indexed(indices_mc);
-        let n_mc = mc_weights.iter().copied().sum::<F>();
-        let ln_l = (data_res
+        i: usize,
+        gradient: impl Fn(&[F]) -> Result<Vec<F>, RustitudeError>,
+    ) -> Result<Vec<F>, RustitudeError> {
+        let h = F::epsilon().cbrt() * (parameters[i].abs() + F::one());
+        let mut x = parameters.to_vec();
+        x[i] = parameters[i] + h;
+        let g_plus = gradient(&x)?;
+        x[i] = parameters[i] - h;
+        let g_minus = gradient(&x)?;
+        Ok(g_plus
             .iter()
-            .zip(data_weights)
-            .map(|(l, w)| w * F::ln(*l))
-            .sum::<F>())
-            - (n_data / n_mc)
-                * (mc_norm_int
-                    .iter()
-                    .zip(mc_weights)
-                    .map(|(l, w)| w * *l)
-                    .sum::<F>());
-        Ok(convert!(-2, F) * ln_l)
+            .zip(g_minus)
+            .map(|(gp, gm)| (*gp - gm) / (convert!(2, F) * h))
+            .collect())
     }
 
-    /// Evaluate the [`ExtendedLogLikelihood`] over the [`Dataset`] with the given free parameters.
+    /// Estimate the covariance matrix of the free parameters at the given (best-fit) parameters.
     ///
-    /// This method also allows the user to input a maximum number of threads to use in the
-    /// calculation, as it uses a parallel loop over events.
+    /// Since [`ExtendedLogLikelihood::evaluate`] returns $`-2\ln\mathcal{L}`$, the covariance
+    /// matrix is $`2 H^{-1}`$, where $`H`$ is the matrix given by
+    /// [`ExtendedLogLikelihood::hessian`].
     ///
     /// # Errors
     ///
-    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
-    /// [`Model::compute`] for more information.
-    #[allow(clippy::suboptimal_flops)]
-    pub fn par_evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
-        if self.data_manager.model.contains_python_amplitudes
-            || self.mc_manager.model.contains_python_amplitudes
-        {
-            return Err(RustitudeError::PythonError(
-                "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
-                    .to_string(),
-            ));
-        }
-        let data_res = self.data_manager.par_evaluate(parameters)?;
-        let data_weights = self.data_manager.dataset.weights();
-        let n_data = data_weights.iter().copied().sum::<F>();
-        let mc_norm_int = self.mc_manager.par_evaluate(parameters)?;
-        let mc_weights = self.mc_manager.dataset.weights();
-        let n_mc = mc_weights.iter().copied().sum::<F>();
-        let ln_l = (data_res
-            .par_iter()
-            .zip(data_weights)
-            .map(|(l, w)| w * F::ln(*l))
-            .sum::<F>())
-            - (n_data / n_mc)
-                * (mc_norm_int
-                    .par_iter()
-                    .zip(mc_weights)
-                    .map(|(l, w)| w * *l)
-                    .sum::<F>());
-        Ok(convert!(-2, F) * ln_l)
+    /// This method will return a [`RustitudeError::EvaluationError`] if the Hessian is singular,
+    /// or any error produced while calculating the Hessian.
+    pub fn covariance(&self, parameters: &[F]) -> Result<Vec<Vec<F>>, RustitudeError> {
+        covariance_from_hessian(self.hessian(parameters)?)
     }
 
-    /// Evaluate the [`ExtendedLogLikelihood`] over the [`Dataset`] with the given free parameters.
+    /// Estimate the covariance matrix of the free parameters at the given (best-fit) parameters.
     ///
-    /// This method allows the user to supply two lists of indices and will only evaluate events at
-    /// those indices. This can be used to evaluate only a subset of events or to resample events
-    /// with replacement, such as in a bootstrap.
+    /// This version uses [`ExtendedLogLikelihood::par_hessian`]. See
+    /// [`ExtendedLogLikelihood::covariance`] for more information.
     ///
-    /// This method also allows the user to input a maximum number of threads to use in the
-    /// calculation, as it uses a parallel loop over events.
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::EvaluationError`] if the Hessian is singular,
+    /// or any error produced while calculating the Hessian.
+    pub fn par_covariance(&self, parameters: &[F]) -> Result<Vec<Vec<F>>, RustitudeError> {
+        self.install(|| covariance_from_hessian(self.par_hessian(parameters)?))
+    }
+
+    /// Estimate the covariance matrix of the free parameters at the given (best-fit) parameters,
+    /// accounting for the uncertainties of the data weights.
+    ///
+    /// A fluctuation $`\delta w_i`$ of the weight of a data event shifts the gradient of
+    /// $`\ln\mathcal{L}`$ by $`g_i\,\delta w_i`$, where
+    /// $`g_i = \partial_\theta \ln I_i - \partial_\theta \sum_j w_j I_j / N_{\text{MC}}`$ (with
+    /// the sum over Monte-Carlo events). The covariance of the weights $`C_{ij}`$ (see
+    /// [`Dataset::weight_blocks`]) therefore propagates into the sandwich estimate
+    /// $`V = H^{-1} D H^{-1}`$ with $`D = \sum_{ij} C_{ij}\, g_i g_j^T`$, where $`H^{-1}`$ is the
+    /// usual covariance given by [`ExtendedLogLikelihood::covariance`]. Without a
+    /// [`WeightCovariance`](crate::dataset::WeightCovariance), events are treated as independent
+    /// with variance $`w_i^2`$, which is the usual correction for weighted events.
     ///
     /// # Errors
     ///
-    /// This method will return a [`RustitudeError`] if the amplitude calculation fails. See
-    /// [`Model::compute`] for more information.
-    #[allow(clippy::suboptimal_flops)]
-    pub fn par_evaluate_indexed(
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the
+    /// [`ExtendedLogLikelihood`] has explicit yields or a [`RobustLoss`], a
+    /// [`RustitudeError::EvaluationError`] if the Hessian is singular, or any error produced
+    /// while calculating the Hessian or gradients.
+    pub fn weighted_covariance(&self, parameters: &[F]) -> Result<Vec<Vec<F>>, RustitudeError> {
+        let d = self.weight_fluctuations(parameters, false)?;
+        Ok(sandwich(&self.covariance(parameters)?, &d))
+    }
+
+    /// Estimate the covariance matrix of the free parameters at the given (best-fit) parameters,
+    /// accounting for the uncertainties of the data weights.
+    ///
+    /// This version uses a parallel loop over events. See
+    /// [`ExtendedLogLikelihood::weighted_covariance`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the
+    /// [`ExtendedLogLikelihood`] has explicit yields or a [`RobustLoss`], a
+    /// [`RustitudeError::EvaluationError`] if the Hessian is singular, or any error produced
+    /// while calculating the Hessian or gradients.
+    pub fn par_weighted_covariance(&self, parameters: &[F]) -> Result<Vec<Vec<F>>, RustitudeError> {
+        self.install(|| {
+            if self.data_manager.model.contains_python_amplitudes
+                || self.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            let d = self.weight_fluctuations(parameters, true)?;
+            Ok(sandwich(&self.par_covariance(parameters)?, &d))
+        })
+    }
+
+    /// Computes $`D = \sum_{ij} C_{ij}\, g_i g_j^T`$ for
+    /// [`ExtendedLogLikelihood::weighted_covariance`], in units of $`-2\ln\mathcal{L}`$.
+    fn weight_fluctuations(
         &self,
         parameters: &[F],
-        indices_data: &[usize],
-        indices_mc: &[usize],
-    ) -> Result<F, RustitudeError> {
-        if self.data_manager.model.contains_python_amplitudes
-            || self.mc_manager.model.contains_python_amplitudes
-        {
-            return Err(RustitudeError::PythonError(
-                "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+        parallel: bool,
+    ) -> Result<Vec<Vec<F>>, RustitudeError> {
+        if !self.yields.is_empty() || self.robust_loss.is_some() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "weight uncertainties cannot be propagated with explicit yields or a robust loss"
                     .to_string(),
             ));
         }
-        let data_res = self
-            .data_manager
-            .par_evaluate_indexed(parameters, indices_data)?;
-        let data_weights = self.data_manager.dataset.weights_indexed(indices_data);
-        let n_data = data_weights.iter().copied().sum::<F>();
-        let mc_norm_int = self
+        let n = self.get_n_free();
+        let n_mc = self.mc_manager.dataset.weights().iter().copied().sum::<F>();
+        let mc_gradient = if parallel {
+            self.mc_manager
+                .par_gradient_sum(parameters, |_, _, event| {
+                    event.weight * self.acceptance_at(event.index)
+                })?
+        } else {
+            self.mc_manager.gradient_sum(parameters, |_, _, event| {
+                event.weight * self.acceptance_at(event.index)
+            })?
+        };
+        let mc_gradient = self
             .mc_manager
-            .par_evaluate_indexed(parameters, indices_mc)?;
-        let mc_weights = self.mc_manager.dataset.weights_indexed(indices_mc);
-        let n_mc = mc_weights.iter().copied().sum::<F>();
-        let ln_l = (data_res
-            .par_iter()
-            .zip(data_weights)
-            .map(|(l, w)| w * F::ln(*l))
-            .sum::<F>())
-            - (n_data / n_mc)
-                * (mc_norm_int
-                    .par_iter()
-                    .zip(mc_weights)
-                    .map(|(l, w)| w * *l)
-                    .sum::<F>());
-        Ok(convert!(-2, F) * ln_l)
+            .model
+            .free_gradient(parameters, &mc_gradient);
+        // Each g_i is scaled by 2 to match the units of -2 ln(L)
+        let scores: Vec<Vec<F>> = self
+            .data_manager
+            .event_gradients(parameters, parallel)?
+            .into_iter()
+            .map(|(value, gradient)| {
+                let above = self.above_floor(value);
+                gradient
+                    .iter()
+                    .zip(&mc_gradient)
+                    .map(|(g, m)| {
+                        let data = if above { *g / value } else { F::zero() };
+                        convert!(2, F) * (data - *m / n_mc)
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut d = vec![vec![F::zero(); n]; n];
+        for (indices, block) in self.data_manager.dataset.weight_blocks() {
+            for (a, row) in indices.iter().zip(&block) {
+                for (b, c_ab) in indices.iter().zip(row) {
+                    for (d_row, g_a) in d.iter_mut().zip(&scores[*a]) {
+                        for (d_ij, g_b) in d_row.iter_mut().zip(&scores[*b]) {
+                            *d_ij += *c_ab * *g_a * *g_b;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(d)
+    }
+
+    /// Returns the factor which converts the intensity of each event into an expected number of
+    /// events, $`N_{\text{data}} / N_{\text{MC}}`$, or, with explicit yields, the signal yield
+    /// divided by the weighted sum of intensities over the Monte-Carlo [`Manager`]'s [`Dataset`].
+    fn intensity_scale(
+        &self,
+        parameters: &[F],
+        data_len_weighted: F,
+        mc_len_weighted: F,
+    ) -> Result<F, RustitudeError> {
+        if self.yields.is_empty() {
+            return Ok(data_len_weighted / mc_len_weighted);
+        }
+        let (_, mc_sum) = self.mc_terms(parameters, false)?;
+        Ok(parameters[self.mc_manager.get_n_free()] / mc_sum)
     }
 
     /// Evaluate the normalized intensity function over the given Monte-Carlo [`Dataset`] with the
@@ -569,11 +4197,12 @@ impl<F: Field> ExtendedLogLikelihood<F> {
         let mc_manager = Manager::new(&self.data_manager.model, dataset_mc)?;
         let data_len_weighted: F = self.data_manager.dataset.weights().iter().copied().sum();
         let mc_len_weighted: F = dataset_mc.weights().iter().copied().sum();
+        let scale = self.intensity_scale(parameters, data_len_weighted, mc_len_weighted)?;
         mc_manager.evaluate(parameters).map(|r_vec| {
             r_vec
                 .into_iter()
                 .zip(dataset_mc.events.iter())
-                .map(|(r, e)| r * data_len_weighted / mc_len_weighted * e.weight)
+                .map(|(r, e)| r * scale * e.weight)
                 .collect()
         })
     }
@@ -611,6 +4240,7 @@ impl<F: Field> ExtendedLogLikelihood<F> {
             .iter()
             .copied()
             .sum::<F>();
+        let scale = self.intensity_scale(parameters, data_len_weighted, mc_len_weighted)?;
         let view: Vec<&Event<F>> = indices_mc
             .par_iter()
             .map(|&index| &mc_manager.dataset.events[index])
@@ -621,7 +4251,7 @@ impl<F: Field> ExtendedLogLikelihood<F> {
                 r_vec
                     .into_iter()
                     .zip(view.iter())
-                    .map(|(r, e)| r * data_len_weighted / mc_len_weighted * e.weight)
+                    .map(|(r, e)| r * scale * e.weight)
                     .collect()
             })
     }
@@ -642,23 +4272,26 @@ impl<F: Field> ExtendedLogLikelihood<F> {
         parameters: &[F],
         dataset_mc: &Dataset<F>,
     ) -> Result<Vec<F>, RustitudeError> {
-        if self.data_manager.model.contains_python_amplitudes
-            || self.mc_manager.model.contains_python_amplitudes
-        {
-            return Err(RustitudeError::PythonError(
-                "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
-                    .to_string(),
-            ));
-        }
-        let mc_manager = Manager::new(&self.data_manager.model, dataset_mc)?;
-        let data_len_weighted: F = self.data_manager.dataset.weights().iter().copied().sum();
-        let mc_len_weighted: F = dataset_mc.weights().iter().copied().sum();
-        mc_manager.par_evaluate(parameters).map(|r_vec| {
-            r_vec
-                .into_iter()
-                .zip(dataset_mc.events.iter())
-                .map(|(r, e)| r * data_len_weighted / mc_len_weighted * e.weight)
-                .collect()
+        self.install(|| {
+            if self.data_manager.model.contains_python_amplitudes
+                || self.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            let mc_manager = Manager::new(&self.data_manager.model, dataset_mc)?;
+            let data_len_weighted: F = self.data_manager.dataset.weights().iter().copied().sum();
+            let mc_len_weighted: F = dataset_mc.weights().iter().copied().sum();
+            let scale = self.intensity_scale(parameters, data_len_weighted, mc_len_weighted)?;
+            mc_manager.par_evaluate(parameters).map(|r_vec| {
+                r_vec
+                    .into_iter()
+                    .zip(dataset_mc.events.iter())
+                    .map(|(r, e)| r * scale * e.weight)
+                    .collect()
+            })
         })
     }
 
@@ -682,31 +4315,325 @@ impl<F: Field> ExtendedLogLikelihood<F> {
         &self,
         parameters: &[F],
         dataset_mc: &Dataset<F>,
-        indices_data: &[usize],
-        indices_mc: &[usize],
-    ) -> Result<Vec<F>, RustitudeError> {
-        let mc_manager = Manager::new(&self.data_manager.model, dataset_mc)?;
-        let data_len_weighted: F = self
-            .data_manager
-            .dataset
-            .weights_indexed(indices_data)
+        indices_data: &[usize],
+        indices_mc: &[usize],
+    ) -> Result<Vec<F>, RustitudeError> {
+        self.install(|| {
+            let mc_manager = Manager::new(&self.data_manager.model, dataset_mc)?;
+            let data_len_weighted: F = self
+                .data_manager
+                .dataset
+                .weights_indexed(indices_data)
+                .iter()
+                .copied()
+                .sum();
+            let mc_len_weighted: F = dataset_mc.weights_indexed(indices_mc).iter().copied().sum();
+            let scale = self.intensity_scale(parameters, data_len_weighted, mc_len_weighted)?;
+            let view: Vec<&Event<F>> = indices_mc
+                .par_iter()
+                .map(|&index| &mc_manager.dataset.events[index])
+                .collect();
+            mc_manager
+                .par_evaluate_indexed(parameters, indices_mc)
+                .map(|r_vec| {
+                    r_vec
+                        .into_par_iter()
+                        .zip(view.par_iter())
+                        .map(|(r, e)| r * scale * e.weight)
+                        .collect()
+                })
+        })
+    }
+
+    /// Evaluate the normalized intensity function over the given Monte-Carlo [`Dataset`] with
+    /// only the given [`Amplitude`]s active. This is intended to be used to plot the contribution
+    /// of a wave or group of waves over the dataset.
+    ///
+    /// The normalization is the same as in [`ExtendedLogLikelihood::intensity`] (the full
+    /// [`Model`] is used to convert intensities into numbers of events), so the projections of
+    /// each wave are directly comparable to the total. The activation state of the
+    /// [`ExtendedLogLikelihood`] is not modified.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::AmplitudeNotFoundError`] if any of the
+    /// amplitudes do not exist, or any error produced while calculating the intensity. See
+    /// [`Model::compute`] for more information.
+    pub fn project(
+        &self,
+        parameters: &[F],
+        dataset_mc: &Dataset<F>,
+        amplitudes: &[&str],
+    ) -> Result<Vec<F>, RustitudeError> {
+        let mut mc_manager = Manager::new(&self.data_manager.model, dataset_mc)?;
+        mc_manager.isolate(amplitudes.to_vec())?;
+        let data_len_weighted: F = self.data_manager.dataset.weights().iter().copied().sum();
+        let mc_len_weighted: F = dataset_mc.weights().iter().copied().sum();
+        let scale = self.intensity_scale(parameters, data_len_weighted, mc_len_weighted)?;
+        mc_manager.evaluate(parameters).map(|r_vec| {
+            r_vec
+                .into_iter()
+                .zip(dataset_mc.events.iter())
+                .map(|(r, e)| r * scale * e.weight)
+                .collect()
+        })
+    }
+
+    /// Evaluate the normalized intensity function over the given Monte-Carlo [`Dataset`] with
+    /// only the given [`Amplitude`]s active.
+    ///
+    /// This version uses a parallel loop over events. See [`ExtendedLogLikelihood::project`] for
+    /// more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::AmplitudeNotFoundError`] if any of the
+    /// amplitudes do not exist, or any error produced while calculating the intensity. See
+    /// [`Model::compute`] for more information.
+    pub fn par_project(
+        &self,
+        parameters: &[F],
+        dataset_mc: &Dataset<F>,
+        amplitudes: &[&str],
+    ) -> Result<Vec<F>, RustitudeError> {
+        self.install(|| {
+            if self.data_manager.model.contains_python_amplitudes
+                || self.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            let mut mc_manager = Manager::new(&self.data_manager.model, dataset_mc)?;
+            mc_manager.isolate(amplitudes.to_vec())?;
+            let data_len_weighted: F = self.data_manager.dataset.weights().iter().copied().sum();
+            let mc_len_weighted: F = dataset_mc.weights().iter().copied().sum();
+            let scale = self.intensity_scale(parameters, data_len_weighted, mc_len_weighted)?;
+            mc_manager.par_evaluate(parameters).map(|r_vec| {
+                r_vec
+                    .into_par_iter()
+                    .zip(dataset_mc.events.par_iter())
+                    .map(|(r, e)| r * scale * e.weight)
+                    .collect()
+            })
+        })
+    }
+
+    /// Evaluate the interference between two groups of [`Amplitude`]s over the given Monte-Carlo
+    /// [`Dataset`], normalized in the same way as [`ExtendedLogLikelihood::project`]. See
+    /// [`Model::compute_interference`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::AmplitudeNotFoundError`] if any of the
+    /// amplitudes do not exist, or any error produced while calculating the amplitudes. See
+    /// [`Model::compute`] for more information.
+    pub fn interference(
+        &self,
+        parameters: &[F],
+        dataset_mc: &Dataset<F>,
+        amplitudes_a: &[&str],
+        amplitudes_b: &[&str],
+    ) -> Result<Vec<F>, RustitudeError> {
+        for amplitude in amplitudes_a.iter().chain(amplitudes_b) {
+            self.get_amplitude(amplitude)?;
+        }
+        let mc_manager = Manager::new(&self.data_manager.model, dataset_mc)?;
+        let data_len_weighted: F = self.data_manager.dataset.weights().iter().copied().sum();
+        let mc_len_weighted: F = dataset_mc.weights().iter().copied().sum();
+        let scale = self.intensity_scale(parameters, data_len_weighted, mc_len_weighted)?;
+        mc_manager
+            .interference(parameters, amplitudes_a, amplitudes_b)
+            .map(|r_vec| {
+                r_vec
+                    .into_iter()
+                    .zip(dataset_mc.events.iter())
+                    .map(|(r, e)| r * scale * e.weight)
+                    .collect()
+            })
+    }
+
+    /// Evaluate the interference between two groups of [`Amplitude`]s over the given Monte-Carlo
+    /// [`Dataset`].
+    ///
+    /// This version uses a parallel loop over events. See
+    /// [`ExtendedLogLikelihood::interference`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::AmplitudeNotFoundError`] if any of the
+    /// amplitudes do not exist, or any error produced while calculating the amplitudes. See
+    /// [`Model::compute`] for more information.
+    pub fn par_interference(
+        &self,
+        parameters: &[F],
+        dataset_mc: &Dataset<F>,
+        amplitudes_a: &[&str],
+        amplitudes_b: &[&str],
+    ) -> Result<Vec<F>, RustitudeError> {
+        self.install(|| {
+            for amplitude in amplitudes_a.iter().chain(amplitudes_b) {
+                self.get_amplitude(amplitude)?;
+            }
+            let mc_manager = Manager::new(&self.data_manager.model, dataset_mc)?;
+            let data_len_weighted: F = self.data_manager.dataset.weights().iter().copied().sum();
+            let mc_len_weighted: F = dataset_mc.weights().iter().copied().sum();
+            let scale = self.intensity_scale(parameters, data_len_weighted, mc_len_weighted)?;
+            mc_manager
+                .par_interference(parameters, amplitudes_a, amplitudes_b)
+                .map(|r_vec| {
+                    r_vec
+                        .into_par_iter()
+                        .zip(dataset_mc.events.par_iter())
+                        .map(|(r, e)| r * scale * e.weight)
+                        .collect()
+                })
+        })
+    }
+
+    /// Computes the fit fraction of each active [`Amplitude`] and of each named group of
+    /// [`Amplitude`]s over the given Monte-Carlo [`Dataset`].
+    ///
+    /// The fit fraction of a group is the weighted sum of the intensity with only that group
+    /// active (see [`Model::isolate`]) divided by the weighted sum of the full intensity. Using
+    /// generated rather than accepted Monte-Carlo gives acceptance-corrected fractions.
+    /// Interference terms are not assigned to any group, so the fractions need not sum to one.
+    ///
+    /// If a covariance matrix of the free parameters is given (see
+    /// [`ExtendedLogLikelihood::covariance`]), the uncertainty of each fraction is propagated
+    /// linearly using the analytic gradient of the intensity (see [`Model::compute_gradient`]).
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the covariance
+    /// matrix does not match the number of free parameters, a
+    /// [`RustitudeError::AmplitudeNotFoundError`] if a group contains an unknown amplitude, or
+    /// any error produced while calculating the intensity.
+    pub fn fit_fractions(
+        &self,
+        parameters: &[F],
+        dataset_mc: &Dataset<F>,
+        groups: &[(&str, Vec<&str>)],
+        covariance: Option<&[Vec<F>]>,
+    ) -> Result<Vec<FitFraction<F>>, RustitudeError> {
+        self.compute_fit_fractions(parameters, dataset_mc, groups, covariance, false)
+    }
+
+    /// Computes the fit fraction of each active [`Amplitude`] and of each named group of
+    /// [`Amplitude`]s over the given Monte-Carlo [`Dataset`].
+    ///
+    /// This version uses a parallel loop over events. See
+    /// [`ExtendedLogLikelihood::fit_fractions`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the covariance
+    /// matrix does not match the number of free parameters, a
+    /// [`RustitudeError::AmplitudeNotFoundError`] if a group contains an unknown amplitude, or
+    /// any error produced while calculating the intensity.
+    pub fn par_fit_fractions(
+        &self,
+        parameters: &[F],
+        dataset_mc: &Dataset<F>,
+        groups: &[(&str, Vec<&str>)],
+        covariance: Option<&[Vec<F>]>,
+    ) -> Result<Vec<FitFraction<F>>, RustitudeError> {
+        self.install(|| {
+            if self.data_manager.model.contains_python_amplitudes
+                || self.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            self.compute_fit_fractions(parameters, dataset_mc, groups, covariance, true)
+        })
+    }
+
+    fn compute_fit_fractions(
+        &self,
+        parameters: &[F],
+        dataset_mc: &Dataset<F>,
+        groups: &[(&str, Vec<&str>)],
+        covariance: Option<&[Vec<F>]>,
+        parallel: bool,
+    ) -> Result<Vec<FitFraction<F>>, RustitudeError> {
+        let n_free = self.get_n_free();
+        if let Some(cov) = covariance {
+            if cov.len() != n_free || cov.iter().any(|row| row.len() != n_free) {
+                return Err(RustitudeError::InvalidParameterValue(format!(
+                    "expected a {n_free}x{n_free} covariance matrix"
+                )));
+            }
+        }
+        let mut manager = Manager::new(&self.data_manager.model, dataset_mc)?;
+        let weighted_sum = |manager: &Manager<F>| -> Result<(F, Vec<F>), RustitudeError> {
+            let (values, gradient) = if parallel {
+                (
+                    manager.par_evaluate(parameters)?,
+                    manager.par_gradient_sum(parameters, |_, _, event| event.weight)?,
+                )
+            } else {
+                (
+                    manager.evaluate(parameters)?,
+                    manager.gradient_sum(parameters, |_, _, event| event.weight)?,
+                )
+            };
+            let sum = values
+                .iter()
+                .zip(dataset_mc.events.iter())
+                .map(|(value, event)| *value * event.weight)
+                .sum();
+            Ok((sum, manager.model.free_gradient(parameters, &gradient)))
+        };
+        let (total, total_gradient) = weighted_sum(&manager)?;
+        let active: Vec<String> = manager
+            .model
+            .amplitudes
+            .read()
             .iter()
-            .copied()
-            .sum();
-        let mc_len_weighted: F = dataset_mc.weights_indexed(indices_mc).iter().copied().sum();
-        let view: Vec<&Event<F>> = indices_mc
-            .par_iter()
-            .map(|&index| &mc_manager.dataset.events[index])
+            .filter(|amp| amp.active)
+            .map(|amp| amp.name.clone())
             .collect();
-        mc_manager
-            .par_evaluate_indexed(parameters, indices_mc)
-            .map(|r_vec| {
-                r_vec
-                    .into_par_iter()
-                    .zip(view.par_iter())
-                    .map(|(r, e)| r * data_len_weighted / mc_len_weighted * e.weight)
-                    .collect()
+        active
+            .iter()
+            .map(|name| (name.as_str(), vec![name.as_str()]))
+            .chain(groups.iter().cloned())
+            .map(|(name, amplitudes)| {
+                manager.isolate(amplitudes.clone())?;
+                let (sum, gradient) = weighted_sum(&manager)?;
+                let value = sum / total;
+                // Gradient of the fraction, with zeros for any yield parameters
+                let mut fraction_gradient: Vec<F> = gradient
+                    .iter()
+                    .zip(&total_gradient)
+                    .map(|(g, g_total)| (*g - value * *g_total) / total)
+                    .collect();
+                fraction_gradient.resize(n_free, F::zero());
+                let error = covariance.map(|cov| {
+                    cov.iter()
+                        .zip(&fraction_gradient)
+                        .map(|(row, g_i)| {
+                            *g_i * row
+                                .iter()
+                                .zip(&fraction_gradient)
+                                .map(|(c, g_j)| *c * *g_j)
+                                .sum::<F>()
+                        })
+                        .sum::<F>()
+                        .max(F::zero())
+                        .sqrt()
+                });
+                Ok(FitFraction {
+                    name: name.to_string(),
+                    amplitudes: amplitudes.iter().map(|amp| amp.to_string()).collect(),
+                    value,
+                    error,
+                })
             })
+            .collect()
     }
 
     /// Get a copy of an [`Amplitude`] in the [`Model`] by name.
@@ -733,6 +4660,13 @@ impl<F: Field> ExtendedLogLikelihood<F> {
         amplitude_name: &str,
         parameter_name: &str,
     ) -> Result<Parameter<F>, RustitudeError> {
+        if let Some(par) = self
+            .yield_parameters()
+            .into_iter()
+            .find(|p| p.amplitude == amplitude_name && p.name == parameter_name)
+        {
+            return Ok(par);
+        }
         self.data_manager
             .get_parameter(amplitude_name, parameter_name)
     }
@@ -740,14 +4674,22 @@ impl<F: Field> ExtendedLogLikelihood<F> {
     /// Print the free parameters in the [`Model`]. See [`Model::print_parameters`] for more
     /// information.
     pub fn print_parameters(&self) {
-        self.data_manager.print_parameters()
+        self.data_manager.print_parameters();
+        for par in self.yield_parameters() {
+            if let Some(i) = par.index {
+                println!("{i}: {par:?}");
+            }
+        }
     }
 
-    /// Returns a [`Vec<Parameter<F>>`] containing the free parameters in the data [`Manager`].
+    /// Returns a [`Vec<Parameter<F>>`] containing the free parameters in the data [`Manager`]
+    /// followed by any yield parameters.
     ///
     /// See [`Model::free_parameters`] for more information.
     pub fn free_parameters(&self) -> Vec<Parameter<F>> {
-        self.data_manager.free_parameters()
+        let mut parameters = self.data_manager.free_parameters();
+        parameters.extend(self.yield_parameters());
+        parameters
     }
 
     /// Returns a [`Vec<Parameter<F>>`] containing the fixed parameters in the data [`Manager`].
@@ -773,7 +4715,10 @@ impl<F: Field> ExtendedLogLikelihood<F> {
         self.data_manager
             .constrain(amplitude_1, parameter_1, amplitude_2, parameter_2)?;
         self.mc_manager
-            .constrain(amplitude_1, parameter_1, amplitude_2, parameter_2)
+            .constrain(amplitude_1, parameter_1, amplitude_2, parameter_2)?;
+        self.update_generated(|generated| {
+            generated.constrain(amplitude_1, parameter_1, amplitude_2, parameter_2)
+        })
     }
 
     /// Fix a parameter by name to the given value.
@@ -789,7 +4734,77 @@ impl<F: Field> ExtendedLogLikelihood<F> {
         value: F,
     ) -> Result<(), RustitudeError> {
         self.data_manager.fix(amplitude, parameter, value)?;
-        self.mc_manager.fix(amplitude, parameter, value)
+        self.mc_manager.fix(amplitude, parameter, value)?;
+        self.update_generated(|generated| generated.fix(amplitude, parameter, value))
+    }
+
+    /// Add a Gaussian constraint to a parameter by name. The constraint term is added to the
+    /// result of every evaluation of the [`ExtendedLogLikelihood`].
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the given amplitude-parameter pair does not exist or if `sigma`
+    /// is not positive. See [`Model::add_gaussian_constraint`] for more information.
+    pub fn add_gaussian_constraint(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: F,
+        sigma: F,
+    ) -> Result<(), RustitudeError> {
+        self.data_manager
+            .add_gaussian_constraint(amplitude, parameter, mean, sigma)?;
+        self.mc_manager
+            .add_gaussian_constraint(amplitude, parameter, mean, sigma)?;
+        self.update_generated(|generated| {
+            generated.add_gaussian_constraint(amplitude, parameter, mean, sigma)
+        })
+    }
+
+    /// Add a Gaussian prior to a parameter by name. The prior term is added to the result of
+    /// every evaluation of the [`ExtendedLogLikelihood`].
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the given amplitude-parameter pair does not exist or if `sigma`
+    /// is not positive. See [`Model::add_prior`] for more information.
+    pub fn add_prior(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: F,
+        sigma: F,
+    ) -> Result<(), RustitudeError> {
+        self.data_manager
+            .add_prior(amplitude, parameter, mean, sigma)?;
+        self.mc_manager
+            .add_prior(amplitude, parameter, mean, sigma)?;
+        self.update_generated(|generated| generated.add_prior(amplitude, parameter, mean, sigma))
+    }
+
+    /// Add a custom penalty on a set of parameters given by `(amplitude, parameter)` names. The
+    /// value of the penalty is added to the result of every evaluation of the
+    /// [`ExtendedLogLikelihood`].
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if any of the given amplitude-parameter pairs do not exist. See
+    /// [`Model::add_penalty`] for more information.
+    pub fn add_penalty(
+        &mut self,
+        parameters: &[(&str, &str)],
+        function: impl Fn(&[F]) -> Result<F, RustitudeError> + Send + Sync + 'static,
+    ) -> Result<(), RustitudeError> {
+        let data_function = Arc::new(function);
+        let mc_function = Arc::clone(&data_function);
+        let generated_function = Arc::clone(&data_function);
+        self.data_manager
+            .add_penalty(parameters, move |values| data_function(values))?;
+        self.mc_manager
+            .add_penalty(parameters, move |values| mc_function(values))?;
+        self.update_generated(|generated| {
+            generated.add_penalty(parameters, move |values| generated_function(values))
+        })
     }
 
     /// Free a fixed parameter by name.
@@ -800,10 +4815,12 @@ impl<F: Field> ExtendedLogLikelihood<F> {
     /// [`Model::free`] for more information.
     pub fn free(&mut self, amplitude: &str, parameter: &str) -> Result<(), RustitudeError> {
         self.data_manager.free(amplitude, parameter)?;
-        self.mc_manager.free(amplitude, parameter)
+        self.mc_manager.free(amplitude, parameter)?;
+        self.update_generated(|generated| generated.free(amplitude, parameter))
     }
 
-    /// Set the bounds of a parameter by name.
+    /// Set the bounds of a parameter by name. Yield parameters (see
+    /// [`ExtendedLogLikelihood::add_signal_yield`]) belong to the amplitude [`YIELD_AMPLITUDE`].
     ///
     /// # Errors
     ///
@@ -815,11 +4832,52 @@ impl<F: Field> ExtendedLogLikelihood<F> {
         parameter: &str,
         bounds: (F, F),
     ) -> Result<(), RustitudeError> {
+        if let Some(par) = self
+            .yields
+            .iter_mut()
+            .find(|p| p.amplitude == amplitude && p.name == parameter)
+        {
+            par.bounds = bounds;
+            return Ok(());
+        }
         self.data_manager.set_bounds(amplitude, parameter, bounds)?;
-        self.mc_manager.set_bounds(amplitude, parameter, bounds)
+        self.mc_manager.set_bounds(amplitude, parameter, bounds)?;
+        self.update_generated(|generated| generated.set_bounds(amplitude, parameter, bounds))
     }
 
-    /// Set the initial value of a parameter by name.
+    /// Set (or remove) the [`Transform`] of a parameter by name.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the given amplitude-parameter pair does not exist. Yield
+    /// parameters (see [`ExtendedLogLikelihood::add_signal_yield`]) cannot be transformed and
+    /// return a [`RustitudeError::InvalidParameterValue`]. See [`Model::set_transform`] for more
+    /// information.
+    pub fn set_transform(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        transform: Option<Transform<F>>,
+    ) -> Result<(), RustitudeError> {
+        if amplitude == YIELD_AMPLITUDE
+            && self
+                .yields
+                .iter()
+                .any(|p| p.amplitude == amplitude && p.name == parameter)
+        {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "the yield parameter {amplitude}::{parameter} cannot be transformed"
+            )));
+        }
+        self.data_manager
+            .set_transform(amplitude, parameter, transform)?;
+        self.mc_manager
+            .set_transform(amplitude, parameter, transform)?;
+        self.update_generated(|generated| generated.set_transform(amplitude, parameter, transform))
+    }
+
+    /// Set the initial value of a parameter by name. Yield parameters (see
+    /// [`ExtendedLogLikelihood::add_signal_yield`]) belong to the amplitude [`YIELD_AMPLITUDE`].
     ///
     /// # Errors
     ///
@@ -831,30 +4889,141 @@ impl<F: Field> ExtendedLogLikelihood<F> {
         parameter: &str,
         initial: F,
     ) -> Result<(), RustitudeError> {
+        if let Some(par) = self
+            .yields
+            .iter_mut()
+            .find(|p| p.amplitude == amplitude && p.name == parameter)
+        {
+            par.initial = initial;
+            return Ok(());
+        }
         self.data_manager
             .set_initial(amplitude, parameter, initial)?;
-        self.mc_manager.set_initial(amplitude, parameter, initial)
+        self.mc_manager.set_initial(amplitude, parameter, initial)?;
+        self.update_generated(|generated| generated.set_initial(amplitude, parameter, initial))
     }
 
-    /// Get a list of bounds for all free parameters in the [`Model`]. See
-    /// [`Model::get_bounds`] for more information.
+    /// Fix every parameter whose name matches a pattern to the given value.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if no parameter matches the pattern. See [`Model::fix_matching`]
+    /// for more information.
+    pub fn fix_matching(&mut self, pattern: &str, value: F) -> Result<(), RustitudeError> {
+        self.data_manager.fix_matching(pattern, value)?;
+        self.mc_manager.fix_matching(pattern, value)?;
+        self.update_generated(|generated| generated.fix_matching(pattern, value))
+    }
+
+    /// Free every fixed parameter whose name matches a pattern.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if no parameter matches the pattern. See [`Model::free_matching`]
+    /// for more information.
+    pub fn free_matching(&mut self, pattern: &str) -> Result<(), RustitudeError> {
+        self.data_manager.free_matching(pattern)?;
+        self.mc_manager.free_matching(pattern)?;
+        self.update_generated(|generated| generated.free_matching(pattern))
+    }
+
+    /// Set the bounds of every parameter whose name matches a pattern, including yield
+    /// parameters (see [`ExtendedLogLikelihood::add_signal_yield`]).
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if no parameter matches the pattern. See
+    /// [`Model::set_bounds_matching`] for more information.
+    pub fn set_bounds_matching(
+        &mut self,
+        pattern: &str,
+        bounds: (F, F),
+    ) -> Result<(), RustitudeError> {
+        let mut matched = false;
+        for par in self.yields.iter_mut().filter(|p| p.matches(pattern)) {
+            par.bounds = bounds;
+            matched = true;
+        }
+        if matched
+            && self
+                .data_manager
+                .model
+                .matching_parameters(pattern)
+                .is_empty()
+        {
+            return Ok(());
+        }
+        self.data_manager.set_bounds_matching(pattern, bounds)?;
+        self.mc_manager.set_bounds_matching(pattern, bounds)?;
+        self.update_generated(|generated| generated.set_bounds_matching(pattern, bounds))
+    }
+
+    /// Set the initial value of every parameter whose name matches a pattern, including
+    /// yield parameters (see [`ExtendedLogLikelihood::add_signal_yield`]).
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if no parameter matches the pattern. See
+    /// [`Model::set_initial_matching`] for more information.
+    pub fn set_initial_matching(
+        &mut self,
+        pattern: &str,
+        initial: F,
+    ) -> Result<(), RustitudeError> {
+        let mut matched = false;
+        for par in self.yields.iter_mut().filter(|p| p.matches(pattern)) {
+            par.initial = initial;
+            matched = true;
+        }
+        if matched
+            && self
+                .data_manager
+                .model
+                .matching_parameters(pattern)
+                .is_empty()
+        {
+            return Ok(());
+        }
+        self.data_manager.set_initial_matching(pattern, initial)?;
+        self.mc_manager.set_initial_matching(pattern, initial)?;
+        self.update_generated(|generated| generated.set_initial_matching(pattern, initial))
+    }
+
+    /// Constrain every parameter whose name matches a pattern to the first match.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if no parameter matches the pattern. See
+    /// [`Model::constrain_matching`] for more information.
+    pub fn constrain_matching(&mut self, pattern: &str) -> Result<(), RustitudeError> {
+        self.data_manager.constrain_matching(pattern)?;
+        self.mc_manager.constrain_matching(pattern)?;
+        self.update_generated(|generated| generated.constrain_matching(pattern))
+    }
+
+    /// Get a list of bounds for all free parameters in the [`Model`], followed by any yield
+    /// parameters. See [`Model::get_bounds`] for more information.
     pub fn get_bounds(&self) -> Vec<(F, F)> {
         self.data_manager.get_bounds();
-        self.mc_manager.get_bounds()
+        let mut bounds = self.mc_manager.get_bounds();
+        bounds.extend(self.yields.iter().map(|p| p.bounds));
+        bounds
     }
 
-    /// Get a list of initial values for all free parameters in the [`Model`]. See
-    /// [`Model::get_initial`] for more information.
+    /// Get a list of initial values for all free parameters in the [`Model`], followed by any
+    /// yield parameters. See [`Model::get_initial`] for more information.
     pub fn get_initial(&self) -> Vec<F> {
         self.data_manager.get_initial();
-        self.mc_manager.get_initial()
+        let mut initial = self.mc_manager.get_initial();
+        initial.extend(self.yields.iter().map(|p| p.initial));
+        initial
     }
 
-    /// Get the number of free parameters in the [`Model`] See [`Model::get_n_free`] for
-    /// more information.
+    /// Get the number of free parameters in the [`Model`] plus the number of yield parameters.
+    /// See [`Model::get_n_free`] for more information.
     pub fn get_n_free(&self) -> usize {
         self.data_manager.get_n_free();
-        self.mc_manager.get_n_free()
+        self.mc_manager.get_n_free() + self.yields.len()
     }
 
     /// Activate an [`Amplitude`] by name. See [`Model::activate`] for more information.
@@ -865,12 +5034,16 @@ impl<F: Field> ExtendedLogLikelihood<F> {
     /// amplitude is not present in the [`Model`].
     pub fn activate(&mut self, amplitude: &str) -> Result<(), RustitudeError> {
         self.data_manager.activate(amplitude)?;
-        self.mc_manager.activate(amplitude)
+        self.mc_manager.activate(amplitude)?;
+        self.update_generated(|generated| generated.activate(amplitude))
     }
     /// Activates all [`Amplitude`]s by name. See [`Model::activate_all`] for more information.
     pub fn activate_all(&mut self) {
         self.data_manager.activate_all();
-        self.mc_manager.activate_all()
+        self.mc_manager.activate_all();
+        if let Some(generated) = &mut self.generated_manager {
+            generated.activate_all();
+        }
     }
     /// Activate only the specified [`Amplitude`]s while deactivating the rest. See
     /// [`Model::isolate`] for more information.
@@ -881,7 +5054,8 @@ impl<F: Field> ExtendedLogLikelihood<F> {
     /// amplitude is not present in the [`Model`].
     pub fn isolate(&mut self, amplitudes: Vec<&str>) -> Result<(), RustitudeError> {
         self.data_manager.isolate(amplitudes.clone())?;
-        self.mc_manager.isolate(amplitudes)
+        self.mc_manager.isolate(amplitudes.clone())?;
+        self.update_generated(|generated| generated.isolate(amplitudes))
     }
     /// Deactivate an [`Amplitude`] by name. See [`Model::deactivate`] for more information.
     ///
@@ -891,17 +5065,567 @@ impl<F: Field> ExtendedLogLikelihood<F> {
     /// amplitude is not present in the [`Model`].
     pub fn deactivate(&mut self, amplitude: &str) -> Result<(), RustitudeError> {
         self.data_manager.deactivate(amplitude)?;
-        self.mc_manager.deactivate(amplitude)
+        self.mc_manager.deactivate(amplitude)?;
+        self.update_generated(|generated| generated.deactivate(amplitude))
     }
     /// Deactivates all [`Amplitude`]s by name. See [`Model::deactivate_all`] for more information.
     pub fn deactivate_all(&mut self) {
         self.data_manager.deactivate_all();
-        self.mc_manager.deactivate_all()
+        self.mc_manager.deactivate_all();
+        if let Some(generated) = &mut self.generated_manager {
+            generated.deactivate_all();
+        }
+    }
+    /// Retrieves a copy of a coherent sum by name. See [`Model::get_sum`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::SumNotFoundError`] if no coherent sum has
+    /// the given name.
+    pub fn get_sum(&self, name: &str) -> Result<NormSqr<F>, RustitudeError> {
+        self.data_manager.get_sum(name)
+    }
+    /// Activate the coherent sums with the given name. See [`Model::activate_sum`] for more
+    /// information.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::SumNotFoundError`] if no coherent sum has
+    /// the given name.
+    pub fn activate_sum(&mut self, name: &str) -> Result<(), RustitudeError> {
+        self.data_manager.activate_sum(name)?;
+        self.mc_manager.activate_sum(name)?;
+        self.update_generated(|generated| generated.activate_sum(name))
+    }
+    /// Deactivate the coherent sums with the given name. See [`Model::deactivate_sum`] for more
+    /// information.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::SumNotFoundError`] if no coherent sum has
+    /// the given name.
+    pub fn deactivate_sum(&mut self, name: &str) -> Result<(), RustitudeError> {
+        self.data_manager.deactivate_sum(name)?;
+        self.mc_manager.deactivate_sum(name)?;
+        self.update_generated(|generated| generated.deactivate_sum(name))
+    }
+    /// Returns the [`ActivationSnapshot`] of the [`Model`] of the data [`Manager`], which matches
+    /// that of the Monte-Carlo [`Manager`] unless they were changed separately. See
+    /// [`Model::activation_state`] for more information.
+    pub fn activation_state(&self) -> ActivationSnapshot {
+        self.data_manager.activation_state()
+    }
+    /// Restores the activation state of the [`Model`]s of the data, Monte-Carlo, and generated
+    /// Monte-Carlo [`Manager`]s. See [`Model::restore`] for more information.
+    ///
+    /// If the snapshot does not match any of the [`Model`]s, the others are returned to their
+    /// previous state, so none is left half-restored.
+    ///
+    /// # Errors
+    ///
+    /// This function will return a [`RustitudeError::AmplitudeNotFoundError`] or a
+    /// [`RustitudeError::InvalidParameterValue`] if the snapshot does not match the [`Model`].
+    pub fn restore(&mut self, snapshot: &ActivationSnapshot) -> Result<(), RustitudeError> {
+        let previous = self.data_manager.activation_state();
+        self.data_manager.restore(snapshot)?;
+        if let Err(err) = self.mc_manager.restore(snapshot) {
+            self.data_manager.restore(&previous)?;
+            return Err(err);
+        }
+        if let Err(err) = self.update_generated(|generated| generated.restore(snapshot)) {
+            self.data_manager.restore(&previous)?;
+            self.mc_manager.restore(&previous)?;
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+/// Averages a square matrix with its transpose.
+/// The number of values below which [`reproducible_sum`] stops splitting.
+const REPRODUCIBLE_SUM_BLOCK: usize = 1024;
+
+/// Sums `values` with a reduction tree which only depends on their number, so the result is
+/// bit-for-bit identical whether or not it is computed in parallel and for any number of
+/// threads.
+///
+/// The values are split in half until fewer than 1024 remain, each block is summed with
+/// Neumaier's compensated summation, and the halves are added pairwise, which also keeps the
+/// rounding error much smaller than a naive sum.
+pub fn reproducible_sum<F: Field>(values: &[F], parallel: bool) -> F {
+    if values.len() <= REPRODUCIBLE_SUM_BLOCK {
+        let (sum, compensation) =
+            values
+                .iter()
+                .fold((F::zero(), F::zero()), |(sum, compensation), &value| {
+                    let t = sum + value;
+                    let compensation = if sum.abs() >= value.abs() {
+                        compensation + ((sum - t) + value)
+                    } else {
+                        compensation + ((value - t) + sum)
+                    };
+                    (t, compensation)
+                });
+        return sum + compensation;
+    }
+    let (left, right) = values.split_at(values.len() / 2);
+    let (left, right) = if parallel {
+        rayon::join(
+            || reproducible_sum(left, true),
+            || reproducible_sum(right, true),
+        )
+    } else {
+        (
+            reproducible_sum(left, false),
+            reproducible_sum(right, false),
+        )
+    };
+    left + right
+}
+
+fn symmetrize<F: Field>(matrix: Vec<Vec<F>>) -> Vec<Vec<F>> {
+    let n = matrix.len();
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| (matrix[i][j] + matrix[j][i]) / convert!(2, F))
+                .collect()
+        })
+        .collect()
+}
+
+/// Computes the sandwich product $`V D V / 4`$ of a covariance matrix $`V = 2H^{-1}`$ and the
+/// weight fluctuation matrix $`D`$ (both in units of $`-2\ln\mathcal{L}`$).
+fn sandwich<F: Field>(covariance: &[Vec<F>], d: &[Vec<F>]) -> Vec<Vec<F>> {
+    let n = covariance.len();
+    let vd: Vec<Vec<F>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| (0..n).map(|k| covariance[i][k] * d[k][j]).sum())
+                .collect()
+        })
+        .collect();
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| (0..n).map(|k| vd[i][k] * covariance[k][j]).sum::<F>() / convert!(4, F))
+                .collect()
+        })
+        .collect()
+}
+
+/// Computes $`2 H^{-1}`$ by Gauss-Jordan elimination with partial pivoting.
+fn covariance_from_hessian<F: Field>(hessian: Vec<Vec<F>>) -> Result<Vec<Vec<F>>, RustitudeError> {
+    let n = hessian.len();
+    let mut a = hessian;
+    let mut inverse: Vec<Vec<F>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| if i == j { convert!(2, F) } else { F::zero() })
+                .collect()
+        })
+        .collect();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| {
+                a[i][col]
+                    .abs()
+                    .partial_cmp(&a[j][col].abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or(col);
+        if a[pivot][col] == F::zero() || !a[pivot][col].is_finite() {
+            return Err(RustitudeError::EvaluationError(
+                "Hessian matrix is singular".to_string(),
+            ));
+        }
+        a.swap(col, pivot);
+        inverse.swap(col, pivot);
+        let scale = a[col][col].recip();
+        for j in 0..n {
+            a[col][j] *= scale;
+            inverse[col][j] *= scale;
+        }
+        for row in 0..n {
+            if row != col {
+                let factor = a[row][col];
+                for j in 0..n {
+                    let (a_col, inverse_col) = (a[col][j], inverse[col][j]);
+                    a[row][j] -= factor * a_col;
+                    inverse[row][j] -= factor * inverse_col;
+                }
+            }
+        }
     }
+    Ok(inverse)
 }
 
+#[cfg(feature = "ganesh")]
 impl<F: Field + ganesh::core::Field> Function<F, (), RustitudeError> for ExtendedLogLikelihood<F> {
     fn evaluate(&self, x: &DVector<F>, _args: Option<&()>) -> Result<F, RustitudeError> {
         self.par_evaluate(x.as_slice())
     }
+
+    fn gradient(&self, x: &DVector<F>, _args: Option<&()>) -> Result<DVector<F>, RustitudeError> {
+        Ok(DVector::from_vec(self.par_gradient(x.as_slice())?))
+    }
+}
+
+/// The maximum number of bracketing and refinement steps in [`ExtendedLogLikelihood::minos`].
+#[cfg(feature = "ganesh")]
+const MINOS_MAX_ITERATIONS: usize = 30;
+
+#[cfg(feature = "ganesh")]
+impl<F: Field + ganesh::core::Field + 'static> ExtendedLogLikelihood<F> {
+    /// Compute a likelihood profile scan of a single [`Parameter`].
+    ///
+    /// At each of the given `values`, the [`Parameter`] is fixed and
+    /// [`ExtendedLogLikelihood::evaluate`] is minimized over all remaining free parameters with
+    /// the Nelder-Mead algorithm, taking at most `max_steps` steps. Each minimization starts from
+    /// the best point of the previous one (the first starts at
+    /// [`ExtendedLogLikelihood::get_initial`]). The result is the minimum value of
+    /// $`-2\ln\mathcal{L}`$ at each scan point, so the points where the curve rises by one unit
+    /// above its minimum give the (possibly asymmetric) $`1\sigma`$ interval.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if the parameter
+    /// does not exist, a [`RustitudeError::InvalidParameterValue`] if it is fixed, or any error
+    /// produced during the minimization.
+    pub fn profile(
+        &self,
+        amplitude: &str,
+        parameter: &str,
+        values: &[F],
+        max_steps: usize,
+    ) -> Result<Vec<F>, RustitudeError> {
+        let mut x0: Option<Vec<F>> = None;
+        values
+            .iter()
+            .map(|value| {
+                let (x_best, fx_best) =
+                    self.profile_point(amplitude, parameter, *value, x0.as_deref(), max_steps)?;
+                x0 = Some(x_best);
+                Ok(fx_best)
+            })
+            .collect()
+    }
+
+    /// Compute a likelihood profile scan of a single [`Parameter`].
+    ///
+    /// This version minimizes at each scan point in parallel, and every minimization starts at
+    /// [`ExtendedLogLikelihood::get_initial`]. See [`ExtendedLogLikelihood::profile`] for more
+    /// information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if the parameter
+    /// does not exist, a [`RustitudeError::InvalidParameterValue`] if it is fixed, or any error
+    /// produced during the minimization.
+    pub fn par_profile(
+        &self,
+        amplitude: &str,
+        parameter: &str,
+        values: &[F],
+        max_steps: usize,
+    ) -> Result<Vec<F>, RustitudeError> {
+        self.install(|| {
+            if self.data_manager.model.contains_python_amplitudes
+                || self.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            values
+                .par_iter()
+                .map(|value| {
+                    self.profile_point(amplitude, parameter, *value, None, max_steps)
+                        .map(|(_, fx_best)| fx_best)
+                })
+                .collect()
+        })
+    }
+
+    /// Find the asymmetric (MINOS-style) uncertainties of a free parameter.
+    ///
+    /// Starting from the minimum `best` with value `fx`, the free parameter at `index` is moved in
+    /// each direction until the profile likelihood (see [`ExtendedLogLikelihood::profile`])
+    /// rises by `delta` above `fx`. Since [`ExtendedLogLikelihood::evaluate`] returns
+    /// $`-2\ln\mathcal{L}`$, a `delta` of `1.0` gives $`1\sigma`$ uncertainties (a change of
+    /// $`0.5`$ in the negative log-likelihood). The search starts with steps of size `step`
+    /// (usually the parabolic uncertainty), and each point re-minimizes all other free
+    /// parameters with at most `max_steps` Nelder-Mead steps.
+    ///
+    /// The result is the signed `(lower, upper)` offsets from the best value, so the lower error
+    /// is negative. A side is [`None`] if the crossing lies beyond the bounds of the parameter
+    /// (see [`ExtendedLogLikelihood::set_bounds`]) or could not be bracketed. Like `best`, the
+    /// offsets are given in the internal space of any [`Transform`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if `index` is not the
+    /// index of a free parameter of the [`Model`], or any error produced during the
+    /// minimizations.
+    pub fn minos(
+        &self,
+        best: &[F],
+        fx: F,
+        index: usize,
+        delta: F,
+        step: F,
+        max_steps: usize,
+    ) -> Result<(Option<F>, Option<F>), RustitudeError> {
+        let par = self
+            .data_manager
+            .free_parameters()
+            .into_iter()
+            .find(|p| p.index == Some(index))
+            .ok_or_else(|| {
+                RustitudeError::InvalidParameterValue(format!(
+                    "{index} is not the index of a free parameter in the model"
+                ))
+            })?;
+        let x0: Vec<F> = best
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, x)| *x)
+            .collect();
+        let g = |value: F| -> Result<F, RustitudeError> {
+            let (_, fx_value) = self.profile_point(
+                &par.amplitude,
+                &par.name,
+                par.physical(value),
+                Some(&x0),
+                max_steps,
+            )?;
+            Ok(fx_value - fx - delta)
+        };
+        let step = if step > F::zero() && step.is_finite() {
+            step
+        } else {
+            convert!(0.1, F) * (best[index].abs() + F::one())
+        };
+        let mut errors = [None, None];
+        let bounds = par
+            .transform
+            .map_or(par.bounds, |transform| transform.bounds(par.bounds));
+        for (error, direction) in errors.iter_mut().zip([-F::one(), F::one()]) {
+            let bound = if direction < F::zero() {
+                bounds.0
+            } else {
+                bounds.1
+            };
+            // Bracket the crossing by doubling the step
+            let (mut inside, mut g_inside) = (best[index], -delta);
+            let mut outside = None;
+            let mut offset = step;
+            for _ in 0..MINOS_MAX_ITERATIONS {
+                let mut value = best[index] + direction * offset;
+                let at_bound = (value - bound) * direction >= F::zero();
+                if at_bound {
+                    value = bound;
+                }
+                let g_value = g(value)?;
+                if g_value >= F::zero() {
+                    outside = Some((value, g_value));
+                    break;
+                }
+                if at_bound {
+                    break;
+                }
+                (inside, g_inside) = (value, g_value);
+                offset *= convert!(2, F);
+            }
+            let Some((mut outside, mut g_outside)) = outside else {
+                continue;
+            };
+            // Refine the crossing with the Illinois variant of regula falsi
+            let tolerance = convert!(1e-3, F) * delta;
+            let mut crossing = outside;
+            let mut last_side = 0;
+            for _ in 0..MINOS_MAX_ITERATIONS {
+                crossing = (inside * g_outside - outside * g_inside) / (g_outside - g_inside);
+                let g_crossing = g(crossing)?;
+                if g_crossing.abs() < tolerance {
+                    break;
+                }
+                if g_crossing < F::zero() {
+                    (inside, g_inside) = (crossing, g_crossing);
+                    if last_side == -1 {
+                        g_outside /= convert!(2, F);
+                    }
+                    last_side = -1;
+                } else {
+                    (outside, g_outside) = (crossing, g_crossing);
+                    if last_side == 1 {
+                        g_inside /= convert!(2, F);
+                    }
+                    last_side = 1;
+                }
+            }
+            *error = Some(crossing - best[index]);
+        }
+        Ok(errors.into())
+    }
+
+    /// Minimize [`ExtendedLogLikelihood::evaluate`] from many random starting points.
+    ///
+    /// Starting points are drawn uniformly within the bounds of each free parameter (see
+    /// [`ExtendedLogLikelihood::set_bounds`]) by a random number generator seeded with `seed`, so
+    /// the same `seed` always gives the same starting points. An unbounded side is replaced by the
+    /// initial value (see [`ExtendedLogLikelihood::get_initial`]) shifted by the larger of one and
+    /// its magnitude. Each start is minimized with the Nelder-Mead algorithm, taking at most
+    /// `max_steps` steps, and the resulting [`FitResult`]s (without covariance matrices) are
+    /// sorted from the lowest to the highest minimum. The collection can be inspected for
+    /// distinct minima or summarized with
+    /// [`Ensemble::from_fit_results`](crate::ensemble::Ensemble::from_fit_results).
+    ///
+    /// # Errors
+    ///
+    /// This method will return any error produced during the minimizations.
+    pub fn multistart(
+        &self,
+        n_starts: usize,
+        seed: u64,
+        max_steps: usize,
+    ) -> Result<Vec<FitResult<F>>, RustitudeError> {
+        let mut results = self
+            .random_starts(n_starts, seed)
+            .iter()
+            .map(|x0| self.minimize_from(x0, max_steps))
+            .collect::<Result<Vec<_>, _>>()?;
+        results.sort_by(|a, b| a.fx.partial_cmp(&b.fx).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(results)
+    }
+
+    /// Minimize [`ExtendedLogLikelihood::evaluate`] from many random starting points.
+    ///
+    /// This version minimizes from each starting point in parallel. The starting points are drawn
+    /// before any minimization, so they are identical to those of
+    /// [`ExtendedLogLikelihood::multistart`] with the same `seed`. See
+    /// [`ExtendedLogLikelihood::multistart`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::PythonError`] if the [`Model`] contains Python
+    /// amplitudes, or any error produced during the minimizations.
+    pub fn par_multistart(
+        &self,
+        n_starts: usize,
+        seed: u64,
+        max_steps: usize,
+    ) -> Result<Vec<FitResult<F>>, RustitudeError> {
+        self.install(|| {
+            if self.data_manager.model.contains_python_amplitudes
+                || self.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            let mut results = self
+                .random_starts(n_starts, seed)
+                .par_iter()
+                .map(|x0| self.minimize_from(x0, max_steps))
+                .collect::<Result<Vec<_>, _>>()?;
+            results.sort_by(|a, b| a.fx.partial_cmp(&b.fx).unwrap_or(std::cmp::Ordering::Equal));
+            Ok(results)
+        })
+    }
+
+    /// Draws `n_starts` random starting points for [`ExtendedLogLikelihood::multistart`].
+    fn random_starts(&self, n_starts: usize, seed: u64) -> Vec<Vec<F>> {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        let ranges: Vec<(F, F)> = self
+            .get_bounds()
+            .into_iter()
+            .zip(self.get_initial())
+            .map(|((lower, upper), initial)| {
+                let width = F::max(F::one(), initial.abs());
+                let lower = if lower.is_finite() {
+                    lower
+                } else {
+                    F::min(initial, upper) - width
+                };
+                let upper = if upper.is_finite() {
+                    upper
+                } else {
+                    F::max(initial, lower) + width
+                };
+                (lower, upper)
+            })
+            .collect();
+        (0..n_starts)
+            .map(|_| {
+                ranges
+                    .iter()
+                    .map(|(lower, upper)| *lower + (*upper - *lower) * convert!(rng.f64(), F))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Minimizes over all free parameters from the starting point `x0`.
+    pub(crate) fn minimize_from(
+        &self,
+        x0: &[F],
+        max_steps: usize,
+    ) -> Result<FitResult<F>, RustitudeError> {
+        if x0.is_empty() {
+            return FitResult::new(self, x0, self.evaluate(x0)?, None);
+        }
+        let (x_best, fx_best) = self.nelder_mead(self.clone(), x0, max_steps)?;
+        FitResult::new(self, &x_best, fx_best, None)
+    }
+
+    /// Minimizes `ell` from the starting point `x0` with the Nelder-Mead algorithm, reporting the
+    /// best point after each step to the [`Observer`] of this [`ExtendedLogLikelihood`] (if any)
+    /// rather than every evaluation.
+    fn nelder_mead(
+        &self,
+        mut ell: Self,
+        x0: &[F],
+        max_steps: usize,
+    ) -> Result<(Vec<F>, F), RustitudeError> {
+        ell.observer = None;
+        let mut nelder_mead = NelderMead::new(ell, x0, None);
+        let step = std::cell::Cell::new(0);
+        nelder_mead.minimize(None, max_steps, |minimizer| {
+            if let Some(observer) = &self.observer {
+                step.set(step.get() + 1);
+                let (x_best, fx_best) = minimizer.best();
+                observer.observe(step.get(), *fx_best, x_best.as_slice());
+            }
+        })?;
+        let (x_best, fx_best) = nelder_mead.best();
+        Ok((x_best.as_slice().to_vec(), *fx_best))
+    }
+
+    /// Minimizes over all free parameters except the given one, which is fixed to `value`,
+    /// returning the best remaining free parameters and the minimum.
+    fn profile_point(
+        &self,
+        amplitude: &str,
+        parameter: &str,
+        value: F,
+        x0: Option<&[F]>,
+        max_steps: usize,
+    ) -> Result<(Vec<F>, F), RustitudeError> {
+        if self.get_parameter(amplitude, parameter)?.is_fixed() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "cannot profile fixed parameter {amplitude}::{parameter}"
+            )));
+        }
+        let mut ell = self.clone();
+        ell.fix(amplitude, parameter, value)?;
+        let x0 = x0.map_or_else(|| ell.get_initial(), <[F]>::to_vec);
+        if x0.is_empty() {
+            return Ok((x0, ell.evaluate(&[])?));
+        }
+        self.nelder_mead(ell, &x0, max_steps)
+    }
 }
@@ -0,0 +1,322 @@
+//! This module contains the angular-momentum, barrier-factor, and two-body kinematic building
+//! blocks shared by many [`Node`](crate::amplitude::Node)s.
+//!
+//! The kinematic functions ([`breakup_momentum`], [`rho`], [`chew_mandelstam`], ...) describe a
+//! channel with masses $`m_1`$ and $`m_2`$ and are continued analytically below threshold where
+//! they return a [`Complex`] value. The [`faddeeva`] function gives the Voigt profile of a peak
+//! which is smeared by the detector resolution.
+//!
+//! Spins and their projections are given as twice their value (`two_j`, `two_m`, ...) so that
+//! half-integer spins can be represented exactly, so $`j = 1/2`$ is written as `two_j = 1` and
+//! $`j = 1`$ as `two_j = 2`. Combinations which do not describe a valid state (a projection
+//! larger than the spin or with the wrong parity) give zero.
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::math::{clebsch_gordan, wigner_small_d};
+//!
+//! // <1/2, +1/2; 1/2, -1/2 | 1, 0> = 1/sqrt(2)
+//! let cg: f64 = clebsch_gordan(1, 1, 1, -1, 2, 0);
+//! assert!((cg - f64::sqrt(0.5)).abs() < 1e-12);
+//! // d^1_{00}(beta) = cos(beta)
+//! let d: f64 = wigner_small_d(0.3, 2, 0, 0);
+//! assert!((d - f64::cos(0.3)).abs() < 1e-12);
+//! ```
+use std::sync::OnceLock;
+
+use num::Complex;
+
+use crate::{convert, Field};
+
+/// The momentum scale $`q_R`$ (in $`\text{GeV}`$) of the Blatt-Weisskopf barrier factors, corresponding to
+/// an interaction radius of $`1\,\text{fm}`$.
+pub const BARRIER_SCALE: f64 = 0.1973;
+
+/// Computes $`n!`$.
+fn factorial<F: Field>(n: i32) -> F {
+    (2..=n).map(|k| convert!(k, F)).product()
+}
+
+/// Checks that `two_m` is a valid projection of `two_j`.
+const fn is_projection(two_j: i32, two_m: i32) -> bool {
+    two_m.abs() <= two_j && (two_j + two_m) % 2 == 0
+}
+
+/// Computes the Wigner small-$`d`$ matrix element $`d^j_{mn}(\beta)`$ with Wigner's formula
+///
+/// ```math
+/// d^j_{mn}(\beta) = \sqrt{(j+m)!(j-m)!(j+n)!(j-n)!} \sum_s \frac{(-1)^{m-n+s} \cos^{2j+n-m-2s}(\beta/2) \sin^{m-n+2s}(\beta/2)}{(j+n-s)!\,s!\,(m-n+s)!\,(j-m-s)!}
+/// ```
+///
+/// where the spin and projections are given as twice their value (see the
+/// [module-level documentation](crate::math)).
+pub fn wigner_small_d<F: Field>(beta: F, two_j: u32, two_m: i32, two_n: i32) -> F {
+    #[allow(clippy::cast_possible_wrap)]
+    let two_j = two_j as i32;
+    if !is_projection(two_j, two_m) || !is_projection(two_j, two_n) {
+        return F::zero();
+    }
+    let (jpm, jmm) = ((two_j + two_m) / 2, (two_j - two_m) / 2);
+    let (jpn, jmn) = ((two_j + two_n) / 2, (two_j - two_n) / 2);
+    let m_n = (two_m - two_n) / 2;
+    let prefactor = F::sqrt(factorial::<F>(jpm) * factorial(jmm) * factorial(jpn) * factorial(jmn));
+    let (sin, cos) = (beta / convert!(2, F)).sin_cos();
+    let sum: F = (i32::max(0, -m_n)..=i32::min(jpn, jmm))
+        .map(|s| {
+            let sign = if (m_n + s) % 2 == 0 {
+                F::one()
+            } else {
+                -F::one()
+            };
+            sign * cos.powi(jpn + jmm - 2 * s) * sin.powi(m_n + 2 * s)
+                / (factorial::<F>(jpn - s) * factorial(s) * factorial(m_n + s) * factorial(jmm - s))
+        })
+        .sum();
+    prefactor * sum
+}
+
+/// Computes the Wigner $`D`$-matrix element $`D^j_{mn}(\alpha, \beta, \gamma)`$.
+///
+/// This is $`D^j_{mn}(\alpha, \beta, \gamma) = e^{-im\alpha} d^j_{mn}(\beta) e^{-in\gamma}`$
+/// (see [`wigner_small_d`]), where the spin and projections are given as twice their value.
+pub fn wigner_d<F: Field>(
+    alpha: F,
+    beta: F,
+    gamma: F,
+    two_j: u32,
+    two_m: i32,
+    two_n: i32,
+) -> Complex<F> {
+    let half = convert!(0.5, F);
+    Complex::cis(-convert!(two_m, F) * half * alpha)
+        * wigner_small_d(beta, two_j, two_m, two_n)
+        * Complex::cis(-convert!(two_n, F) * half * gamma)
+}
+
+/// Computes the Clebsch-Gordan coefficient $`\langle j_1 m_1; j_2 m_2 | j m\rangle`$ with
+/// Racah's formula, where each spin and projection is given as twice its value.
+pub fn clebsch_gordan<F: Field>(
+    two_j1: u32,
+    two_m1: i32,
+    two_j2: u32,
+    two_m2: i32,
+    two_j: u32,
+    two_m: i32,
+) -> F {
+    #[allow(clippy::cast_possible_wrap)]
+    let (two_j1, two_j2, two_j) = (two_j1 as i32, two_j2 as i32, two_j as i32);
+    if !is_projection(two_j1, two_m1)
+        || !is_projection(two_j2, two_m2)
+        || !is_projection(two_j, two_m)
+        || two_m1 + two_m2 != two_m
+        || two_j > two_j1 + two_j2
+        || two_j < (two_j1 - two_j2).abs()
+        || (two_j1 + two_j2 + two_j) % 2 != 0
+    {
+        return F::zero();
+    }
+    let (j1pm1, j1mm1) = ((two_j1 + two_m1) / 2, (two_j1 - two_m1) / 2);
+    let (j2pm2, j2mm2) = ((two_j2 + two_m2) / 2, (two_j2 - two_m2) / 2);
+    let (jpm, jmm) = ((two_j + two_m) / 2, (two_j - two_m) / 2);
+    // j1 + j2 - j, j1 - j2 + j, and -j1 + j2 + j
+    let a = (two_j1 + two_j2 - two_j) / 2;
+    let b = (two_j1 - two_j2 + two_j) / 2;
+    let c = (-two_j1 + two_j2 + two_j) / 2;
+    let prefactor = F::sqrt(
+        convert!(two_j + 1, F) * factorial::<F>(a) * factorial(b) * factorial(c)
+            / factorial::<F>((two_j1 + two_j2 + two_j) / 2 + 1)
+            * factorial(jpm)
+            * factorial(jmm)
+            * factorial(j1pm1)
+            * factorial(j1mm1)
+            * factorial(j2pm2)
+            * factorial(j2mm2),
+    );
+    // Every factorial in the sum must have a non-negative argument
+    let k_min = i32::max(0, i32::max(j1mm1 - b, j2pm2 - c));
+    let k_max = i32::min(a, i32::min(j1mm1, j2pm2));
+    let sum: F = (k_min..=k_max)
+        .map(|k| {
+            let sign = if k % 2 == 0 { F::one() } else { -F::one() };
+            sign / (factorial::<F>(k)
+                * factorial(a - k)
+                * factorial(j1mm1 - k)
+                * factorial(j2pm2 - k)
+                * factorial(b - j1mm1 + k)
+                * factorial(c - j2pm2 + k))
+        })
+        .sum();
+    prefactor * sum
+}
+
+/// Returns the coefficients $`c_k`$ of the polynomial
+/// $`P_l(z) = \sum_{k=0}^{l} c_k z^k = z^l |x h^{(1)}_l(x)|^2`$ with $`x^2 = z`$, where
+/// $`h^{(1)}_l`$ is the spherical Hankel function of the first kind.
+fn barrier_coefficients<F: Field>(l: usize) -> Vec<F> {
+    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+    let l = l as i32;
+    (0..=l)
+        .map(|k| {
+            factorial::<F>(2 * l - k) * factorial(2 * l - 2 * k)
+                / (factorial::<F>(k) * factorial::<F>(l - k).powi(2) * convert!(4, F).powi(l - k))
+        })
+        .collect()
+}
+
+/// Computes the Blatt-Weisskopf barrier factor $`B_l(q)`$ for a breakup momentum `q` (in $`\text{GeV}`$) and
+/// orbital angular momentum `l`,
+///
+/// ```math
+/// B_l(q) = \sqrt{\frac{z^l P_l(1)}{P_l(z)}}
+/// ```
+///
+/// where $`z = (q / q_R)^2`$ with $`q_R`$ given by [`BARRIER_SCALE`] and
+/// $`P_l(z) = z^l |x h^{(1)}_l(x)|^2`$ (with $`x^2 = z`$) is the polynomial in the denominator of
+/// the usual tabulated forms, such as $`B_2(q) = \sqrt{13z^2 / ((z-3)^2 + 9z)}`$. The factors are
+/// normalized so that $`B_l = 1`$ at $`q = q_R`$, and $`B_0 = 1`$.
+pub fn blatt_weisskopf<F: Field>(q: F, l: usize) -> F {
+    let z = q.powi(2) / convert!(BARRIER_SCALE, F).powi(2);
+    let coefficients = barrier_coefficients::<F>(l);
+    let polynomial = coefficients
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, c| acc * z + *c);
+    let normalization: F = coefficients.iter().copied().sum();
+    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+    F::sqrt(z.powi(l as i32) * normalization / polynomial)
+}
+
+/// Complex version of [`blatt_weisskopf`] for a complex breakup momentum `q`, which continues
+/// the barrier factor below threshold.
+pub fn blatt_weisskopf_c<F: Field>(q: Complex<F>, l: usize) -> Complex<F> {
+    let z = q.powi(2) / convert!(BARRIER_SCALE, F).powi(2);
+    let coefficients = barrier_coefficients::<F>(l);
+    let polynomial = coefficients
+        .iter()
+        .rev()
+        .fold(Complex::from(F::zero()), |acc, c| acc * z + *c);
+    let normalization: F = coefficients.iter().copied().sum();
+    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+    Complex::sqrt(z.powi(l as i32) * normalization / polynomial)
+}
+
+/// Computes the breakup momentum $`q`$ of a particle with mass `m` decaying into two particles
+/// with masses `m1` and `m2`,
+///
+/// ```math
+/// q(m; m_1, m_2) = \frac{\sqrt{|\lambda(m^2, m_1^2, m_2^2)|}}{2m}
+/// ```
+///
+/// where $`\lambda`$ is the Källén function. Below threshold this gives the magnitude of the
+/// (imaginary) momentum, see [`breakup_momentum_c`] for the analytic continuation.
+pub fn breakup_momentum<F: Field>(m: F, m1: F, m2: F) -> F {
+    F::sqrt(F::abs(
+        m.powi(4) + m1.powi(4) + m2.powi(4)
+            - convert!(2, F)
+                * (m.powi(2) * m1.powi(2) + m.powi(2) * m2.powi(2) + m1.powi(2) * m2.powi(2)),
+    )) / (convert!(2, F) * m)
+}
+
+/// Computes the complex breakup momentum $`q = m\rho(m^2)/2`$ of a particle with mass `m`
+/// decaying into two particles with masses `m1` and `m2` (see [`rho`]).
+///
+/// This is real above threshold and purely imaginary between the pseudothreshold
+/// $`|m_1 - m_2|`$ and the threshold $`m_1 + m_2`$.
+pub fn breakup_momentum_c<F: Field>(m: F, m1: F, m2: F) -> Complex<F> {
+    rho(m.powi(2), m1, m2) * m / convert!(2, F)
+}
+
+/// Computes $`\chi_+(s) = 1 - (m_1 + m_2)^2 / s`$.
+pub fn chi_plus<F: Field>(s: F, m1: F, m2: F) -> Complex<F> {
+    Complex::from(F::one() - ((m1 + m2) * (m1 + m2)) / s)
+}
+
+/// Computes $`\chi_-(s) = 1 - (m_1 - m_2)^2 / s`$.
+pub fn chi_minus<F: Field>(s: F, m1: F, m2: F) -> Complex<F> {
+    Complex::from(F::one() - ((m1 - m2) * (m1 - m2)) / s)
+}
+
+/// Computes the two-body phase-space factor $`\rho(s) = \sqrt{\chi_+(s)\chi_-(s)}`$ for a channel
+/// with masses `m1` and `m2` (see [`chi_plus`] and [`chi_minus`]).
+///
+/// The principal branch of the square root continues $`\rho`$ below threshold, where it becomes
+/// purely imaginary with a positive imaginary part.
+pub fn rho<F: Field>(s: F, m1: F, m2: F) -> Complex<F> {
+    Complex::sqrt(chi_plus(s, m1, m2) * chi_minus(s, m1, m2))
+}
+
+/// Computes the Chew-Mandelstam function for a channel with masses `m1` and `m2`,
+///
+/// ```math
+/// C(s) = \frac{\rho(s)}{\pi} \ln\left(\frac{\chi_+(s) + \rho(s)}{\chi_+(s) - \rho(s)}\right)
+///     - \frac{\chi_+(s)}{\pi} \frac{m_2 - m_1}{m_1 + m_2} \ln\frac{m_2}{m_1}
+/// ```
+///
+/// which replaces $`-i\rho(s)`$ in a $`K`$-matrix denominator with a function that is analytic
+/// below threshold (see [`rho`], [`chi_plus`], and [`chi_minus`]).
+pub fn chew_mandelstam<F: Field>(s: F, m1: F, m2: F) -> Complex<F> {
+    let chi_plus = chi_plus(s, m1, m2);
+    let rho = rho(s, m1, m2);
+    rho / F::PI() * ((chi_plus + rho) / (chi_plus - rho)).ln()
+        - chi_plus / F::PI() * ((m2 - m1) / (m1 + m2)) * F::ln(m2 / m1)
+}
+
+/// The number of terms in the rational approximation of [`faddeeva`].
+const FADDEEVA_TERMS: usize = 32;
+
+/// Computes the coefficients $`a_n`$ of the polynomial in Weideman's approximation of
+/// [`faddeeva`] as a discrete cosine transform.
+fn faddeeva_coefficients() -> &'static [f64; FADDEEVA_TERMS] {
+    static COEFFICIENTS: OnceLock<[f64; FADDEEVA_TERMS]> = OnceLock::new();
+    COEFFICIENTS.get_or_init(|| {
+        let n_terms = FADDEEVA_TERMS as f64;
+        let scale = f64::sqrt(n_terms / f64::sqrt(2.0));
+        let k_max = 2 * FADDEEVA_TERMS as i64;
+        let samples: Vec<(f64, f64)> = ((1 - k_max)..k_max)
+            .map(|k| {
+                let k = k as f64;
+                let t = scale * f64::tan(k * std::f64::consts::PI / (4.0 * n_terms));
+                (k, f64::exp(-t.powi(2)) * t.mul_add(t, scale.powi(2)))
+            })
+            .collect();
+        let mut coefficients = [0.0; FADDEEVA_TERMS];
+        for (n, coefficient) in coefficients.iter_mut().enumerate() {
+            let frequency = (n + 1) as f64;
+            *coefficient = samples
+                .iter()
+                .map(|(k, f)| f * f64::cos(std::f64::consts::PI * frequency * k / (2.0 * n_terms)))
+                .sum::<f64>()
+                / (4.0 * n_terms);
+        }
+        coefficients
+    })
+}
+
+/// Computes the Faddeeva function $`w(z) = e^{-z^2}\operatorname{erfc}(-iz)`$.
+///
+/// In the upper half-plane, this uses Weideman's rational approximation
+///
+/// ```math
+/// w(z) \approx \frac{2 p(Z)}{(L - iz)^2} + \frac{1}{\sqrt{\pi}(L - iz)}, \quad Z = \frac{L + iz}{L - iz}
+/// ```
+///
+/// where $`p`$ is a polynomial of degree 31 and $`L = 2^{9/4}`$, which is accurate to about
+/// $`10^{-12}`$. The lower half-plane follows from $`w(z) = 2e^{-z^2} - w(-z)`$. The real part of
+/// $`w`$ gives the Voigt profile (see [`Voigtian`](crate::amplitude::Voigtian)).
+pub fn faddeeva<F: Field>(z: Complex<F>) -> Complex<F> {
+    if z.im < F::zero() {
+        return (-z.powi(2)).exp() * convert!(2, F) - faddeeva(-z);
+    }
+    let scale: F = convert!(f64::sqrt(FADDEEVA_TERMS as f64 / f64::sqrt(2.0)), F);
+    let iz = Complex::<F>::i() * z;
+    let big_z = (iz + scale) / (-iz + scale);
+    let polynomial = faddeeva_coefficients()
+        .iter()
+        .rev()
+        .fold(Complex::from(F::zero()), |acc, &a| {
+            acc * big_z + convert!(a, F)
+        });
+    polynomial * convert!(2, F) / (-iz + scale).powi(2)
+        + Complex::from(F::one() / F::sqrt(F::PI())) / (-iz + scale)
+}
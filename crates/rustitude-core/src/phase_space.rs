@@ -0,0 +1,303 @@
+//! This module contains a quasi-random [`PhaseSpace`] generator for Monte-Carlo without files.
+//!
+//! A [`PhaseSpace`] produces weighted [`Event`]s uniformly distributed over the Lorentz-invariant
+//! phase space of a reaction, without any external Monte-Carlo files.
+//!
+//! The [`Event`]s are built with the Raubold-Lynch (GENBOD) method from the points of a [`Sobol`]
+//! sequence, which covers phase space much more evenly than pseudo-random points, so the
+//! normalization integral of a [`Model`](crate::amplitude::Model) converges faster than
+//! $`1/\sqrt{N}`$. The resulting [`Dataset`] stands in for generated Monte-Carlo (with perfect
+//! acceptance), and can be used directly as the Monte-Carlo of an
+//! [`ExtendedLogLikelihood`](crate::manager::ExtendedLogLikelihood) (see
+//! [`ExtendedLogLikelihood::from_phase_space`](crate::manager::ExtendedLogLikelihood::from_phase_space),
+//! which generates events until the integral reaches a requested precision).
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! # fn main() -> Result<(), RustitudeError> {
+//! // gamma p -> p pi+ pi- with an 8.5 GeV beam
+//! let phase_space: PhaseSpace<f64> =
+//!     PhaseSpace::new(8.5, 0.938_272, 0.938_272, vec![0.139_570, 0.139_570])?;
+//! let dataset = phase_space.generate(1024, 0)?;
+//! assert_eq!(dataset.len(), 1024);
+//! let event = &dataset.events[0];
+//! let final_state = event.recoil_p4 + event.daughter_p4s[0] + event.daughter_p4s[1];
+//! assert!((final_state.m() - phase_space.cm_energy()).abs() < 1e-9);
+//! # Ok(())
+//! # }
+//! ```
+use nalgebra::Vector3;
+use rayon::prelude::*;
+
+use crate::{
+    convert,
+    dataset::{Dataset, Event},
+    errors::RustitudeError,
+    four_momentum::FourMomentum,
+    math::breakup_momentum,
+    Field,
+};
+
+/// The number of bits in each coordinate of a [`Sobol`] point.
+const SOBOL_BITS: usize = 32;
+
+/// The degree $`s`$, polynomial coefficients $`a`$, and initial direction numbers $`m_i`$ of
+/// every dimension of a [`Sobol`] sequence after the first, from the tables of Joe and Kuo.
+const SOBOL_DIRECTIONS: [(usize, u32, &[u32]); 20] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+    (6, 19, &[1, 1, 1, 15, 7, 5]),
+    (6, 22, &[1, 3, 1, 15, 13, 25]),
+    (6, 25, &[1, 1, 5, 5, 19, 61]),
+    (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+    (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+];
+
+/// A Sobol low-discrepancy sequence of points in the unit hypercube.
+///
+/// Points are computed directly from their index (through its Gray code), so any range of the
+/// sequence can be generated independently and in parallel. Up to `21` dimensions are supported.
+#[derive(Clone, Debug)]
+pub struct Sobol {
+    directions: Vec<[u32; SOBOL_BITS]>,
+}
+
+impl Sobol {
+    /// The largest supported number of dimensions.
+    pub const MAX_DIMENSION: usize = SOBOL_DIRECTIONS.len() + 1;
+
+    /// Create a new [`Sobol`] sequence with the given number of dimensions.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the dimension is
+    /// zero or larger than [`Sobol::MAX_DIMENSION`].
+    pub fn new(dimension: usize) -> Result<Self, RustitudeError> {
+        if dimension == 0 || dimension > Self::MAX_DIMENSION {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "Sobol sequences support between 1 and {} dimensions, got {dimension}",
+                Self::MAX_DIMENSION
+            )));
+        }
+        let mut directions = vec![std::array::from_fn(|i| 1 << (SOBOL_BITS - 1 - i))];
+        for &(s, a, m) in &SOBOL_DIRECTIONS[..dimension - 1] {
+            let mut v = [0u32; SOBOL_BITS];
+            for i in 0..SOBOL_BITS {
+                v[i] = if i < s {
+                    m[i] << (SOBOL_BITS - 1 - i)
+                } else {
+                    let mut value = v[i - s] ^ (v[i - s] >> s);
+                    for k in 1..s {
+                        if (a >> (s - 1 - k)) & 1 == 1 {
+                            value ^= v[i - k];
+                        }
+                    }
+                    value
+                };
+            }
+            directions.push(v);
+        }
+        Ok(Self { directions })
+    }
+
+    /// Returns the number of dimensions.
+    pub const fn dimension(&self) -> usize {
+        self.directions.len()
+    }
+
+    /// Returns the point at position `index` in the sequence. The point at index `0` is the
+    /// origin.
+    pub fn point(&self, index: u32) -> Vec<f64> {
+        let gray = index ^ (index >> 1);
+        self.directions
+            .iter()
+            .map(|v| {
+                let x = (0..SOBOL_BITS)
+                    .filter(|bit| (gray >> bit) & 1 == 1)
+                    .fold(0, |x, bit| x ^ v[bit]);
+                f64::from(x) / 2f64.powi(SOBOL_BITS as i32)
+            })
+            .collect()
+    }
+}
+
+/// The phase space of a photoproduction-style reaction, in which a beam along the $`z`$-axis hits
+/// a target at rest and produces a recoil particle and any number of daughter particles.
+#[derive(Clone, Debug)]
+pub struct PhaseSpace<F: Field + 'static> {
+    beam_p4: FourMomentum<F>,
+    target_mass: F,
+    masses: Vec<F>,
+    eps: Vector3<F>,
+    sobol: Sobol,
+}
+
+impl<F: Field + 'static> PhaseSpace<F> {
+    /// Create a new [`PhaseSpace`] for a massless beam with the given energy, the mass of the
+    /// target, and the masses of the recoil and daughter particles. The beam is unpolarized
+    /// unless [`PhaseSpace::with_polarization`] is used.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if there are no
+    /// daughters, more final state particles than the [`Sobol`] sequence can describe
+    /// ($`3n - 4 \leq`$ [`Sobol::MAX_DIMENSION`] for $`n`$ particles including the recoil), or the
+    /// center-of-momentum energy is below the final state threshold.
+    pub fn new(
+        beam_energy: F,
+        target_mass: F,
+        recoil_mass: F,
+        daughter_masses: Vec<F>,
+    ) -> Result<Self, RustitudeError> {
+        if daughter_masses.is_empty() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "phase space requires at least one daughter particle".to_string(),
+            ));
+        }
+        let mut masses = vec![recoil_mass];
+        masses.extend(daughter_masses);
+        let beam_p4 = FourMomentum::new(beam_energy, F::zero(), F::zero(), beam_energy);
+        let phase_space = Self {
+            beam_p4,
+            target_mass,
+            sobol: Sobol::new(3 * masses.len() - 4)?,
+            masses,
+            eps: Vector3::zeros(),
+        };
+        if phase_space.cm_energy() <= phase_space.masses.iter().copied().sum() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "the center-of-momentum energy {} is below the final state threshold",
+                phase_space.cm_energy()
+            )));
+        }
+        Ok(phase_space)
+    }
+
+    /// Sets the linear polarization vector of the beam in every generated [`Event`].
+    #[must_use]
+    pub const fn with_polarization(mut self, eps: Vector3<F>) -> Self {
+        self.eps = eps;
+        self
+    }
+
+    /// Returns the center-of-momentum energy $`\sqrt{s}`$ of the reaction.
+    pub fn cm_energy(&self) -> F {
+        (self.beam_p4 + FourMomentum::new(self.target_mass, F::zero(), F::zero(), F::zero())).m()
+    }
+
+    /// Generates `n_events` [`Event`]s from the points of the [`Sobol`] sequence which follow the
+    /// first `offset` points (the origin is always skipped), so consecutive calls with increasing
+    /// offsets extend the same sequence.
+    ///
+    /// Each [`Event`] is weighted by its phase-space density relative to the uniform sampling of
+    /// the intermediate masses and decay angles (the product of the breakup momenta at each step
+    /// of the decay chain), so weighted sums over the [`Dataset`] estimate integrals over phase
+    /// space.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the requested
+    /// points run past the end of the [`Sobol`] sequence ($`2^{32}`$ points).
+    pub fn generate(&self, n_events: usize, offset: usize) -> Result<Dataset<F>, RustitudeError> {
+        let end = offset
+            .checked_add(n_events)
+            .filter(|end| *end < 1 << SOBOL_BITS)
+            .ok_or_else(|| {
+                RustitudeError::InvalidParameterValue(format!(
+                    "cannot generate {n_events} events after {offset} from a Sobol sequence of 2^{SOBOL_BITS} points"
+                ))
+            })?;
+        let events = (offset..end)
+            .into_par_iter()
+            .map(|i| {
+                let mut event = self.event(&self.sobol.point((i + 1) as u32));
+                event.index = i - offset;
+                event
+            })
+            .collect();
+        Ok(Dataset::new(events))
+    }
+
+    /// Builds the [`Event`] for a point in the unit hypercube with the Raubold-Lynch method.
+    fn event(&self, point: &[f64]) -> Event<F> {
+        let n = self.masses.len();
+        let w = self.cm_energy();
+        // The invariant mass of the first j + 1 particles, from sorted uniform numbers
+        let mut r: Vec<F> = point[..n - 2].iter().map(|u| convert!(*u, F)).collect();
+        r.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let kinetic = w - self.masses.iter().copied().sum::<F>();
+        let mut partial_sum = F::zero();
+        let invariant_masses: Vec<F> = (0..n)
+            .map(|j| {
+                partial_sum += self.masses[j];
+                match j {
+                    0 => self.masses[0],
+                    _ if j == n - 1 => w,
+                    _ => partial_sum + r[j - 1] * kinetic,
+                }
+            })
+            .collect();
+        let mut angles = point[n - 2..].chunks(2);
+        let mut weight = F::one();
+        let mut p4s = vec![FourMomentum::new(
+            self.masses[0],
+            F::zero(),
+            F::zero(),
+            F::zero(),
+        )];
+        for j in 1..n {
+            let q = breakup_momentum(invariant_masses[j], invariant_masses[j - 1], self.masses[j]);
+            weight *= q;
+            let angle = angles.next().unwrap_or_else(|| unreachable!());
+            let costheta = convert!(2.0f64.mul_add(angle[0], -1.0), F);
+            let sintheta = (F::one() - costheta * costheta).max(F::zero()).sqrt();
+            let phi = convert!(std::f64::consts::TAU * angle[1], F);
+            let direction = Vector3::new(sintheta * phi.cos(), sintheta * phi.sin(), costheta);
+            let momentum = direction * q;
+            let particle = FourMomentum::new(
+                (q * q + self.masses[j] * self.masses[j]).sqrt(),
+                momentum.x,
+                momentum.y,
+                momentum.z,
+            );
+            let system = FourMomentum::new(
+                (q * q + invariant_masses[j - 1] * invariant_masses[j - 1]).sqrt(),
+                -momentum.x,
+                -momentum.y,
+                -momentum.z,
+            );
+            // Move the previous particles from the rest frame of their system to the rest frame
+            // of the new system
+            let beta = -system.beta3();
+            p4s = p4s.iter().map(|p4| p4.boost(&beta)).collect();
+            p4s.push(particle);
+        }
+        let target_p4 = FourMomentum::new(self.target_mass, F::zero(), F::zero(), F::zero());
+        let beta = -(self.beam_p4 + target_p4).beta3();
+        let mut p4s = p4s.into_iter().map(|p4| p4.boost(&beta));
+        Event {
+            index: 0,
+            weight,
+            beam_p4: self.beam_p4,
+            recoil_p4: p4s.next().unwrap_or_else(|| unreachable!()),
+            daughter_p4s: p4s.collect(),
+            eps: self.eps,
+            circular_polarization: F::zero(),
+        }
+    }
+}
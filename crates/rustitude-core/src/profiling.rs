@@ -0,0 +1,256 @@
+//! This module contains a lightweight built-in profiler which reports where the time of a fit is
+//! spent without attaching an external profiler.
+//!
+//! Every [`Amplitude`](crate::amplitude::Amplitude) records the time spent in its
+//! precalculation and in each call to [`Node::calculate`](crate::amplitude::Node::calculate)
+//! (or [`Node::calculate_batch`](crate::amplitude::Node::calculate_batch)), and every
+//! [`ExtendedLogLikelihood`](crate::manager::ExtendedLogLikelihood) records the time spent
+//! reducing the evaluated intensities to $`-2\ln\mathcal{L}`$. Timing is off by default, since
+//! reading the clock for every [`Event`](crate::dataset::Event) is not free, and is turned on
+//! with [`enable`]. The accumulated timings are summarized by [`report`], which also implements
+//! [`Display`] as a table.
+//!
+//! The same stages are also wrapped in `tracing` spans (`precalculate` and `reduction` at the
+//! `DEBUG` level and `calculate` at the `TRACE` level, each with an `amplitude` field where it
+//! applies), so any `tracing` subscriber can time them independently of this module.
+//!
+//! Timings are global and keyed by the name of each [`Amplitude`](crate::amplitude::Amplitude),
+//! so amplitudes with the same name in different [`Model`](crate::amplitude::Model)s (or the
+//! data and Monte-Carlo copies of a [`Model`](crate::amplitude::Model)) are added together.
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! use rustitude_core::profiling::{self, Stage};
+//! use rustitude_core::utils::generate_test_dataset_f64;
+//! # fn main() -> Result<(), RustitudeError> {
+//! profiling::enable();
+//! let dataset = generate_test_dataset_f64();
+//! let model = model!(scalar("a"));
+//! let manager = Manager::new(&model, &dataset)?;
+//! let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+//! ell.evaluate(&[1.0])?;
+//! let report = profiling::report();
+//! let entry = report.get(Stage::Calculate, "a").unwrap();
+//! assert!(entry.calls > 0);
+//! println!("{report}");
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+/// Whether timings are currently being recorded.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Every [`Timer`] which has been created, in order of creation.
+static TIMERS: Mutex<Vec<Arc<Timer>>> = parking_lot::const_mutex(Vec::new());
+
+/// The stage of an evaluation measured by a [`ProfileEntry`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    /// [`Node::precalculate`](crate::amplitude::Node::precalculate) over a
+    /// [`Dataset`](crate::dataset::Dataset).
+    Precalculate,
+    /// [`Node::calculate`](crate::amplitude::Node::calculate) (or
+    /// [`Node::calculate_batch`](crate::amplitude::Node::calculate_batch)), counted once per
+    /// [`Event`](crate::dataset::Event).
+    Calculate,
+    /// The reduction of the evaluated intensities of data and Monte-Carlo to
+    /// $`-2\ln\mathcal{L}`$.
+    Reduction,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Precalculate => write!(f, "precalculate"),
+            Self::Calculate => write!(f, "calculate"),
+            Self::Reduction => write!(f, "reduction"),
+        }
+    }
+}
+
+/// Accumulates the time spent in one [`Stage`] of a named object.
+#[derive(Debug)]
+pub(crate) struct Timer {
+    stage: Stage,
+    name: String,
+    calls: AtomicU64,
+    nanos: AtomicU64,
+}
+
+impl Timer {
+    /// Runs `op`, adding its duration and `calls` calls to the [`Timer`] if timing is enabled.
+    pub(crate) fn time<R>(&self, calls: usize, op: impl FnOnce() -> R) -> R {
+        if !is_enabled() {
+            return op();
+        }
+        let start = Instant::now();
+        let result = op();
+        let nanos = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        self.nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.calls.fetch_add(calls as u64, Ordering::Relaxed);
+        result
+    }
+}
+
+/// Returns the [`Timer`] for the given [`Stage`] and name, creating it if it does not exist.
+pub(crate) fn timer(stage: Stage, name: &str) -> Arc<Timer> {
+    let mut timers = TIMERS.lock();
+    if let Some(timer) = timers
+        .iter()
+        .find(|timer| timer.stage == stage && timer.name == name)
+    {
+        return timer.clone();
+    }
+    let timer = Arc::new(Timer {
+        stage,
+        name: name.to_string(),
+        calls: AtomicU64::new(0),
+        nanos: AtomicU64::new(0),
+    });
+    timers.push(timer.clone());
+    timer
+}
+
+/// Start recording timings.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Stop recording timings. Timings which were already recorded are kept.
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+/// Checks if timings are being recorded.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Discard every recorded timing.
+pub fn reset() {
+    for timer in TIMERS.lock().iter() {
+        timer.calls.store(0, Ordering::Relaxed);
+        timer.nanos.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Returns a [`ProfileReport`] of every timing recorded since the last [`reset`].
+pub fn report() -> ProfileReport {
+    let mut entries: Vec<ProfileEntry> = TIMERS
+        .lock()
+        .iter()
+        .map(|timer| ProfileEntry {
+            stage: timer.stage,
+            name: timer.name.clone(),
+            calls: timer.calls.load(Ordering::Relaxed),
+            total: Duration::from_nanos(timer.nanos.load(Ordering::Relaxed)),
+        })
+        .filter(|entry| entry.calls > 0)
+        .collect();
+    entries.sort_by(|a, b| a.stage.cmp(&b.stage).then(b.total.cmp(&a.total)));
+    ProfileReport { entries }
+}
+
+/// The time spent in one [`Stage`] of a named object.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProfileEntry {
+    /// The [`Stage`] which was timed.
+    pub stage: Stage,
+    /// The name of the [`Amplitude`](crate::amplitude::Amplitude) (or the likelihood, for
+    /// [`Stage::Reduction`]).
+    pub name: String,
+    /// The number of calls (or [`Event`](crate::dataset::Event)s, for [`Stage::Calculate`]).
+    pub calls: u64,
+    /// The total time spent in the [`Stage`].
+    pub total: Duration,
+}
+
+impl ProfileEntry {
+    /// Returns the mean time per call.
+    pub fn mean(&self) -> Duration {
+        if self.calls == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos(
+            u64::try_from(self.total.as_nanos() / u128::from(self.calls)).unwrap_or(u64::MAX),
+        )
+    }
+}
+
+/// A summary of every timing recorded by the profiler (see [`report`]).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProfileReport {
+    /// The recorded timings, grouped by [`Stage`] and sorted from the largest to the smallest
+    /// total time within each [`Stage`].
+    pub entries: Vec<ProfileEntry>,
+}
+
+impl ProfileReport {
+    /// Returns the entry for the given [`Stage`] and name, if any time was recorded for it.
+    pub fn get(&self, stage: Stage, name: &str) -> Option<&ProfileEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.stage == stage && entry.name == name)
+    }
+
+    /// Returns the total time spent in a [`Stage`].
+    pub fn stage_total(&self, stage: Stage) -> Duration {
+        self.entries
+            .iter()
+            .filter(|entry| entry.stage == stage)
+            .map(|entry| entry.total)
+            .sum()
+    }
+
+    /// Returns the entry with the largest total time in a [`Stage`], such as the
+    /// [`Amplitude`](crate::amplitude::Amplitude) which dominates the time spent in
+    /// [`Stage::Calculate`].
+    pub fn dominant(&self, stage: Stage) -> Option<&ProfileEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.stage == stage)
+            .max_by_key(|entry| entry.total)
+    }
+}
+
+impl Display for ProfileReport {
+    #[allow(clippy::cast_precision_loss)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<14} {:<24} {:>12} {:>14} {:>12} {:>8}",
+            "Stage", "Name", "Calls", "Total (ms)", "Mean (us)", "Share"
+        )?;
+        for entry in &self.entries {
+            let stage_total = self.stage_total(entry.stage).as_secs_f64();
+            let share = if stage_total > 0.0 {
+                100.0 * entry.total.as_secs_f64() / stage_total
+            } else {
+                0.0
+            };
+            writeln!(
+                f,
+                "{:<14} {:<24} {:>12} {:>14.3} {:>12.3} {:>7.1}%",
+                entry.stage.to_string(),
+                entry.name,
+                entry.calls,
+                entry.total.as_secs_f64() * 1e3,
+                entry.mean().as_secs_f64() * 1e6,
+                share
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,618 @@
+//! This module contains serializable descriptions of [`Model`]s, which allow a full model
+//! configuration (including the state of every [`Parameter`]) to be saved to and reloaded from
+//! JSON or YAML.
+//!
+//! Because [`Node`]s are arbitrary trait objects which may hold precalculated data, they are not
+//! serialized directly. Instead, each [`Node`] can describe itself with a [`NodeSpec`] (see
+//! [`Node::spec`]), which consists of a registered kind and a set of constructor arguments. A
+//! [`NodeRegistry`] maps these kinds back to builder functions when a [`Model`] is loaded.
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let model: Model<f64> = model!(scalar("a") * cscalar("b") + pcscalar("c"));
+//! let json = model.to_json()?;
+//! let registry: NodeRegistry<f64> = NodeRegistry::new();
+//! let reloaded = Model::from_json(&json, &registry)?;
+//! assert_eq!(reloaded.parameters.len(), model.parameters.len());
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    amplitude::{
+        piecewise_beam_energy, piecewise_costheta, piecewise_m, piecewise_m_t, piecewise_t,
+        polynomial_m, spline_m, template_hist_m, template_kde_m, AmpLike, Amplitude, BreitWigner,
+        ComplexScalar, Flatte, Fraction, Gaussian, GaussianConstraint, Imag, KMatrix, Model, Node,
+        NormSqr, Parameter, PolarComplexScalar, PolynomialBasis, Product, Real, Scalar, Sum,
+        Voigtian,
+    },
+    convert,
+    errors::RustitudeError,
+    Field,
+};
+
+/// A serializable description of a [`Node`], consisting of the kind of [`Node`] (which must be
+/// registered in a [`NodeRegistry`] to be rebuilt) and the arguments needed to construct it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeSpec {
+    /// The registered name of the [`Node`] type.
+    pub kind: String,
+    /// Constructor arguments for the [`Node`].
+    #[serde(default)]
+    pub args: serde_json::Value,
+}
+
+impl NodeSpec {
+    /// Creates a new [`NodeSpec`] from a kind and a set of arguments.
+    pub fn new(kind: &str, args: serde_json::Value) -> Self {
+        Self {
+            kind: kind.to_string(),
+            args,
+        }
+    }
+
+    /// Deserializes the argument with the given key.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::SerializationError`] if the argument is missing
+    /// or cannot be deserialized into the requested type.
+    pub fn arg<T: DeserializeOwned>(&self, key: &str) -> Result<T, RustitudeError> {
+        let value = self.args.get(key).ok_or_else(|| {
+            RustitudeError::SerializationError(format!(
+                "missing argument \"{}\" for node kind \"{}\"",
+                key, self.kind
+            ))
+        })?;
+        serde_json::from_value(value.clone()).map_err(RustitudeError::from)
+    }
+}
+
+impl Display for NodeSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", self.kind, self.args)
+    }
+}
+
+/// A serializable representation of an [`AmpLike`] expression tree. [`Amplitude`]s are referred
+/// to by name, and their [`Node`]s are stored separately in a [`ModelSpec`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", content = "args", rename_all = "snake_case")]
+pub enum AmpTree {
+    /// A named [`Amplitude`].
+    Amplitude(String),
+    /// The real part of the contained tree (see [`Real`]).
+    Real(Box<Self>),
+    /// The imaginary part of the contained tree (see [`Imag`]).
+    Imag(Box<Self>),
+    /// The product of the contained trees (see [`Product`]).
+    Product(Vec<Self>),
+    /// The sum of the contained trees (see [`Sum`]).
+    Sum(Vec<Self>),
+}
+
+impl AmpTree {
+    /// Rebuilds an [`AmpLike`] from the tree, taking [`Amplitude`]s from the given map.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::AmplitudeNotFoundError`] if the tree refers to
+    /// an [`Amplitude`] which is not in the map.
+    pub fn build<F: Field + 'static>(
+        &self,
+        amplitudes: &HashMap<String, Amplitude<F>>,
+    ) -> Result<Box<dyn AmpLike<F>>, RustitudeError> {
+        Ok(match self {
+            Self::Amplitude(name) => Box::new(
+                amplitudes
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| RustitudeError::AmplitudeNotFoundError(name.clone()))?,
+            ),
+            Self::Real(inner) => Box::new(Real(inner.build(amplitudes)?)),
+            Self::Imag(inner) => Box::new(Imag(inner.build(amplitudes)?)),
+            Self::Product(terms) => Box::new(Product(
+                terms
+                    .iter()
+                    .map(|term| term.build(amplitudes))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+            Self::Sum(terms) => Box::new(Sum(terms
+                .iter()
+                .map(|term| term.build(amplitudes))
+                .collect::<Result<Vec<_>, _>>()?)),
+        })
+    }
+}
+
+/// Formats the tree as a compact formula such as `Re[a * (b + c)]`.
+impl Display for AmpTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Amplitude(name) => write!(f, "{name}"),
+            Self::Real(inner) => write!(f, "Re[{inner}]"),
+            Self::Imag(inner) => write!(f, "Im[{inner}]"),
+            Self::Product(terms) => {
+                for (i, term) in terms.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " * ")?;
+                    }
+                    if matches!(term, Self::Sum(_)) {
+                        write!(f, "({term})")?;
+                    } else {
+                        write!(f, "{term}")?;
+                    }
+                }
+                Ok(())
+            }
+            Self::Sum(terms) => {
+                for (i, term) in terms.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " + ")?;
+                    }
+                    write!(f, "{term}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A serializable description of a single [`Amplitude`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AmplitudeSpec {
+    /// The name of the [`Amplitude`].
+    pub name: String,
+    /// Whether or not the [`Amplitude`] is active.
+    pub active: bool,
+    /// The fixed scale of the [`Amplitude`] (see [`Amplitude::scaled`]), if it is not one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    /// A description of the [`Amplitude`]'s [`Node`].
+    pub node: NodeSpec,
+}
+
+/// A serializable description of a full [`Model`], including the state of all [`Parameter`]s.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ModelSpec<F: Field> {
+    /// Descriptions of each unique [`Amplitude`] in the [`Model`].
+    pub amplitudes: Vec<AmplitudeSpec>,
+    /// Expression trees for each coherent sum in the [`Model`].
+    pub cohsums: Vec<AmpTree>,
+    /// The name of each coherent sum in the [`Model`] (see [`Model::new_named`]).
+    #[serde(default)]
+    pub sum_names: Vec<Option<String>>,
+    /// The positions of the inactive coherent sums in the [`Model`] (see
+    /// [`Model::deactivate_sum`]).
+    #[serde(default)]
+    pub inactive_sums: Vec<usize>,
+    /// The [`Model`]'s [`Parameter`]s.
+    pub parameters: Vec<Parameter<F>>,
+    /// The [`Model`]'s [`GaussianConstraint`]s.
+    #[serde(default)]
+    pub gaussian_constraints: Vec<GaussianConstraint<F>>,
+    /// The `(alias, amplitude, parameter)` names of the [`Model`]'s aliases (see
+    /// [`Model::alias`]).
+    #[serde(default)]
+    pub aliases: Vec<(String, String, String)>,
+}
+
+impl<F: Field> ModelSpec<F> {
+    /// Converts the [`Parameter`]s and [`GaussianConstraint`]s of the [`ModelSpec`] to another
+    /// floating-point precision.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::SerializationError`] if any value cannot be
+    /// represented in the new precision.
+    pub fn convert<G: Field>(&self) -> Result<ModelSpec<G>, RustitudeError> {
+        Ok(serde_json::from_value(serde_json::to_value(self)?)?)
+    }
+}
+
+/// A function which builds a boxed [`Node`] from a [`NodeSpec`].
+pub type NodeBuilder<F> = fn(&NodeSpec) -> Result<Box<dyn Node<F>>, RustitudeError>;
+
+/// A registry of named [`NodeBuilder`]s used to reconstruct [`Node`]s when loading a [`Model`].
+///
+/// [`NodeRegistry::new`] registers all of the [`Node`]s defined in this crate. Other crates can
+/// add their own [`Node`]s with [`NodeRegistry::register`].
+#[derive(Clone)]
+pub struct NodeRegistry<F: Field> {
+    builders: HashMap<String, NodeBuilder<F>>,
+}
+
+impl<F: Field> Debug for NodeRegistry<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut kinds: Vec<&String> = self.builders.keys().collect();
+        kinds.sort();
+        write!(f, "NodeRegistry {kinds:?}")
+    }
+}
+
+impl<F: Field + 'static> Default for NodeRegistry<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field + 'static> NodeRegistry<F> {
+    /// Creates a new [`NodeRegistry`] containing the [`Node`]s defined in this crate.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register("Scalar", |_| Ok(Box::new(Scalar)));
+        registry.register("ComplexScalar", |_| Ok(Box::new(ComplexScalar)));
+        registry.register("PolarComplexScalar", |_| Ok(Box::new(PolarComplexScalar)));
+        registry.register("Fraction", |spec| {
+            let index: usize = spec.arg("index")?;
+            let n: usize = spec.arg("n")?;
+            Ok(Box::new(Fraction::new(index, n)))
+        });
+        registry.register("RelativisticBreitWigner", |spec| {
+            let daughters_1: Vec<usize> = spec.arg("daughters_1")?;
+            let daughters_2: Vec<usize> = spec.arg("daughters_2")?;
+            Ok(Box::new(BreitWigner::new(
+                &daughters_1,
+                &daughters_2,
+                spec.arg("l")?,
+            )?))
+        });
+        registry.register("GaussianLineshape", |spec| {
+            let daughters: Vec<usize> = spec.arg("daughters")?;
+            Ok(Box::new(Gaussian::new(&daughters)?))
+        });
+        registry.register("VoigtianLineshape", |spec| {
+            let daughters: Vec<usize> = spec.arg("daughters")?;
+            Ok(Box::new(Voigtian::new(&daughters)?))
+        });
+        registry.register("FlatteLineshape", |spec| {
+            let daughters_1: Vec<usize> = spec.arg("daughters_1")?;
+            let daughters_2: Vec<usize> = spec.arg("daughters_2")?;
+            Ok(Box::new(Flatte::new(
+                &daughters_1,
+                &daughters_2,
+                spec.arg("channel")?,
+                spec.arg("m1s")?,
+                spec.arg("m2s")?,
+            )?))
+        });
+        registry.register("KMatrix", |spec| {
+            let daughters_1: Vec<usize> = spec.arg("daughters_1")?;
+            let daughters_2: Vec<usize> = spec.arg("daughters_2")?;
+            let non_resonant: Vec<usize> = if spec.args.get("non_resonant").is_some() {
+                spec.arg("non_resonant")?
+            } else {
+                Vec::new()
+            };
+            Ok(Box::new(
+                KMatrix::new(
+                    &daughters_1,
+                    &daughters_2,
+                    spec.arg("channel")?,
+                    spec.arg("config")?,
+                )?
+                .with_non_resonant(&non_resonant)?,
+            ))
+        });
+        registry.register("PiecewiseM", |spec| {
+            let bins: usize = spec.arg("bins")?;
+            let range: (F, F) = spec.arg("range")?;
+            Ok(piecewise_m("", bins, range).node)
+        });
+        registry.register("PiecewiseT", |spec| {
+            let bins: usize = spec.arg("bins")?;
+            let range: (F, F) = spec.arg("range")?;
+            Ok(piecewise_t("", bins, range).node)
+        });
+        registry.register("PiecewiseMT", |spec| {
+            let bins: (usize, usize) = spec.arg("bins")?;
+            let ranges: ((F, F), (F, F)) = spec.arg("ranges")?;
+            Ok(piecewise_m_t("", bins, ranges).node)
+        });
+        registry.register("PiecewiseCosTheta", |spec| {
+            let bins: usize = spec.arg("bins")?;
+            let range: (F, F) = spec.arg("range")?;
+            Ok(piecewise_costheta("", bins, range).node)
+        });
+        registry.register("PiecewiseBeamEnergy", |spec| {
+            let bins: usize = spec.arg("bins")?;
+            let range: (F, F) = spec.arg("range")?;
+            Ok(piecewise_beam_energy("", bins, range).node)
+        });
+        registry.register("PolynomialM", |spec| {
+            let order: usize = spec.arg("order")?;
+            let basis: PolynomialBasis<F> = spec.arg("basis")?;
+            Ok(polynomial_m("", order, basis)?.node)
+        });
+        registry.register("SplineM", |spec| {
+            let knots: Vec<F> = spec.arg("knots")?;
+            Ok(spline_m("", knots, spec.arg("kind")?, spec.arg("form")?)?.node)
+        });
+        registry.register("TemplateHistM", |spec| {
+            let edges: Vec<F> = spec.arg("edges")?;
+            let contents: Vec<F> = spec.arg("contents")?;
+            Ok(template_hist_m("", edges, contents)?.node)
+        });
+        registry.register("TemplateKDEM", |spec| {
+            let points: Vec<F> = spec.arg("points")?;
+            let weights: Option<Vec<F>> = if spec.args.get("weights").is_some() {
+                spec.arg("weights")?
+            } else {
+                None
+            };
+            let bandwidth: F = spec.arg("bandwidth")?;
+            Ok(template_kde_m("", points, weights, bandwidth)?.node)
+        });
+        registry
+    }
+
+    /// Creates a new [`NodeRegistry`] with no registered [`Node`]s.
+    pub fn empty() -> Self {
+        Self {
+            builders: HashMap::default(),
+        }
+    }
+
+    /// Registers a [`NodeBuilder`] under the given kind, replacing any existing builder with the
+    /// same kind.
+    pub fn register(&mut self, kind: &str, builder: NodeBuilder<F>) {
+        self.builders.insert(kind.to_string(), builder);
+    }
+
+    /// Returns `true` if a [`NodeBuilder`] is registered under the given kind.
+    pub fn contains(&self, kind: &str) -> bool {
+        self.builders.contains_key(kind)
+    }
+
+    /// Returns the registered kinds in alphabetical order.
+    pub fn kinds(&self) -> Vec<&str> {
+        let mut kinds: Vec<&str> = self.builders.keys().map(String::as_str).collect();
+        kinds.sort_unstable();
+        kinds
+    }
+
+    /// Builds a [`Node`] from a [`NodeSpec`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::SerializationError`] if the kind is not
+    /// registered, or any error produced by the registered builder.
+    pub fn build(&self, spec: &NodeSpec) -> Result<Box<dyn Node<F>>, RustitudeError> {
+        let builder = self.builders.get(&spec.kind).ok_or_else(|| {
+            RustitudeError::SerializationError(format!("unregistered node kind: {}", spec.kind))
+        })?;
+        builder(spec)
+    }
+}
+
+impl<F: Field + 'static> Model<F> {
+    /// Creates a serializable [`ModelSpec`] from the [`Model`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::SerializationError`] if any [`Amplitude`]
+    /// contains a [`Node`] which does not provide a [`NodeSpec`] (see [`Node::spec`]).
+    pub fn to_spec(&self) -> Result<ModelSpec<F>, RustitudeError> {
+        let amplitudes = self
+            .amplitudes
+            .read()
+            .iter()
+            .map(|amp| {
+                let node = amp.node.spec().ok_or_else(|| {
+                    RustitudeError::SerializationError(format!(
+                        "amplitude \"{}\" contains a node which cannot be serialized",
+                        amp.name
+                    ))
+                })?;
+                Ok(AmplitudeSpec {
+                    name: amp.name.clone(),
+                    active: amp.active,
+                    scale: (amp.scale != F::one()).then(|| amp.scale.to_f64().unwrap_or(f64::NAN)),
+                    node,
+                })
+            })
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        Ok(ModelSpec {
+            amplitudes,
+            cohsums: self.cohsums.iter().map(NormSqr::to_amp_tree).collect(),
+            sum_names: self
+                .cohsums
+                .iter()
+                .map(|cohsum| cohsum.name.clone())
+                .collect(),
+            inactive_sums: self
+                .cohsums
+                .iter()
+                .enumerate()
+                .filter(|(_, cohsum)| !cohsum.active)
+                .map(|(i, _)| i)
+                .collect(),
+            parameters: self.parameters.clone(),
+            gaussian_constraints: self.gaussian_constraints.clone(),
+            aliases: self.aliases().to_vec(),
+        })
+    }
+
+    /// Rebuilds a [`Model`] from a [`ModelSpec`], using the given [`NodeRegistry`] to construct
+    /// each [`Node`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if any [`Node`] cannot be built, if the
+    /// expression trees refer to unknown [`Amplitude`]s, or if the stored [`Parameter`]s do not
+    /// match those of the rebuilt [`Amplitude`]s.
+    pub fn from_spec(
+        spec: &ModelSpec<F>,
+        registry: &NodeRegistry<F>,
+    ) -> Result<Self, RustitudeError> {
+        let amplitudes = spec
+            .amplitudes
+            .iter()
+            .map(|amp_spec| {
+                let node = registry.build(&amp_spec.node)?;
+                let mut amp = Amplitude::from_boxed(&amp_spec.name, node);
+                amp.active = amp_spec.active;
+                if let Some(scale) = amp_spec.scale {
+                    amp = amp.scaled(convert!(scale, F));
+                }
+                Ok((amp_spec.name.clone(), amp))
+            })
+            .collect::<Result<HashMap<String, Amplitude<F>>, RustitudeError>>()?;
+        let cohsums = spec
+            .cohsums
+            .iter()
+            .map(|tree| tree.build(&amplitudes))
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        let mut model = Self::new(&cohsums);
+        for (i, cohsum) in model.cohsums.iter_mut().enumerate() {
+            cohsum.name = spec.sum_names.get(i).cloned().flatten();
+            cohsum.active = !spec.inactive_sums.contains(&i);
+        }
+        let expected: Vec<(&str, &str)> = model
+            .parameters
+            .iter()
+            .map(|p| (p.amplitude.as_str(), p.name.as_str()))
+            .collect();
+        let found: Vec<(&str, &str)> = spec
+            .parameters
+            .iter()
+            .map(|p| (p.amplitude.as_str(), p.name.as_str()))
+            .collect();
+        if expected != found {
+            return Err(RustitudeError::SerializationError(
+                "stored parameters do not match the parameters of the rebuilt model".to_string(),
+            ));
+        }
+        model.parameters.clone_from(&spec.parameters);
+        model
+            .gaussian_constraints
+            .clone_from(&spec.gaussian_constraints);
+        for (alias, amplitude, parameter) in &spec.aliases {
+            model.alias(alias, amplitude, parameter)?;
+        }
+        Ok(model)
+    }
+
+    /// Converts the [`Model`] to another floating-point precision, such as to refine a fit made
+    /// with [`f32`] using [`f64`] (see also [`Dataset::convert`](crate::dataset::Dataset::convert)).
+    ///
+    /// Each [`Node`] is rebuilt from its [`NodeSpec`] with the given [`NodeRegistry`], so
+    /// the converted [`Model`] must be loaded into a new
+    /// [`Manager`](crate::manager::Manager) before it is evaluated. [`Parameter`]s keep their
+    /// values, bounds, and fixed states.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the [`Model`] cannot be described by a
+    /// [`ModelSpec`] (see [`Model::to_spec`]) or rebuilt from one (see [`Model::from_spec`]).
+    pub fn convert<G: Field + 'static>(
+        &self,
+        registry: &NodeRegistry<G>,
+    ) -> Result<Model<G>, RustitudeError> {
+        Model::from_spec(&self.to_spec()?.convert()?, registry)
+    }
+
+    /// Converts the [`Model`] to [`f64`] precision (see [`Model::convert`]).
+    ///
+    /// # Errors
+    ///
+    /// See [`Model::convert`].
+    pub fn to_f64(&self, registry: &NodeRegistry<f64>) -> Result<Model<f64>, RustitudeError> {
+        self.convert(registry)
+    }
+
+    /// Converts the [`Model`] to [`f32`] precision (see [`Model::convert`]).
+    ///
+    /// # Errors
+    ///
+    /// See [`Model::convert`].
+    pub fn to_f32(&self, registry: &NodeRegistry<f32>) -> Result<Model<f32>, RustitudeError> {
+        self.convert(registry)
+    }
+
+    /// Serializes the [`Model`] to a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// See [`Model::to_spec`].
+    pub fn to_json(&self) -> Result<String, RustitudeError> {
+        Ok(serde_json::to_string_pretty(&self.to_spec()?)?)
+    }
+
+    /// Loads a [`Model`] from a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the string cannot be parsed or if
+    /// [`Model::from_spec`] fails.
+    pub fn from_json(json: &str, registry: &NodeRegistry<F>) -> Result<Self, RustitudeError> {
+        Self::from_spec(&serde_json::from_str(json)?, registry)
+    }
+
+    /// Serializes the [`Model`] to a YAML string.
+    ///
+    /// # Errors
+    ///
+    /// See [`Model::to_spec`].
+    pub fn to_yaml(&self) -> Result<String, RustitudeError> {
+        Ok(serde_yaml::to_string(&self.to_spec()?)?)
+    }
+
+    /// Loads a [`Model`] from a YAML string.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if the string cannot be parsed or if
+    /// [`Model::from_spec`] fails.
+    pub fn from_yaml(yaml: &str, registry: &NodeRegistry<F>) -> Result<Self, RustitudeError> {
+        Self::from_spec(&serde_yaml::from_str(yaml)?, registry)
+    }
+}
+
+impl<F: Field + 'static> Serialize for Model<F> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.to_spec()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+/// Serialization helpers for [`Parameter::bounds`]. Infinite bounds are stored as `null` since
+/// neither JSON nor YAML has a portable representation of infinity.
+pub(crate) mod bounds {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::Field;
+
+    pub fn serialize<F: Field, S: Serializer>(
+        bounds: &(F, F),
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let lower = bounds.0.is_finite().then_some(bounds.0);
+        let upper = bounds.1.is_finite().then_some(bounds.1);
+        (lower, upper).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, F: Field, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<(F, F), D::Error> {
+        let (lower, upper) = <(Option<F>, Option<F>)>::deserialize(deserializer)?;
+        Ok((
+            lower.unwrap_or_else(F::neg_infinity),
+            upper.unwrap_or_else(F::infinity),
+        ))
+    }
+}
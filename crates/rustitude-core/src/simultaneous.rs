@@ -0,0 +1,446 @@
+//! This module contains a [`SimultaneousLikelihood`] which fits several
+//! [`ExtendedLogLikelihood`]s (for instance, one for each polarization orientation) at once.
+//!
+//! Each [`ExtendedLogLikelihood`] keeps its own data and Monte-Carlo [`Manager`]s, and the
+//! [`SimultaneousLikelihood`] maps a single global vector of free parameters onto the free
+//! parameters of each term. By default, parameters with the same amplitude and parameter name are
+//! shared by every term which has them. A parameter can instead be scoped to each dataset with
+//! [`SimultaneousLikelihood::scope`], in which case every term gets its own copy. Yield parameters
+//! (see [`ExtendedLogLikelihood::add_signal_yield`]) are always scoped to each dataset.
+//!
+//! Differences in flux or luminosity between datasets can be absorbed by normalization nuisance
+//! parameters (see [`SimultaneousLikelihood::add_normalizations`]), which scale the intensity of
+//! every dataset except a reference dataset and may be constrained to one by a Gaussian
+//! penalty.
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! use rustitude_core::utils::generate_test_dataset_f64;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let dataset = generate_test_dataset_f64();
+//! let model = model!(scalar("a"), scalar("b"));
+//! let manager = Manager::new(&model, &dataset)?;
+//! let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+//! let mut simultaneous =
+//!     SimultaneousLikelihood::new(vec![("para", ell.clone()), ("perp", ell.clone())])?;
+//! simultaneous.scope("b", "value")?;
+//! assert_eq!(
+//!     simultaneous.parameter_names(),
+//!     vec!["a::value", "para/b::value", "perp/b::value"]
+//! );
+//! let total = simultaneous.evaluate(&[1.0, 0.5, 0.5])?;
+//! assert_eq!(total, 2.0 * ell.evaluate(&[1.0, 0.5])?);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Manager`]: crate::manager::Manager
+#[cfg(feature = "ganesh")]
+use ganesh::prelude::{DVector, Function};
+
+use crate::{
+    amplitude::Parameter,
+    convert,
+    errors::RustitudeError,
+    manager::{ExtendedLogLikelihood, YIELD_AMPLITUDE},
+    Field,
+};
+
+/// The name of the amplitude to which the normalization parameters of a
+/// [`SimultaneousLikelihood`] belong (see [`SimultaneousLikelihood::add_normalizations`]).
+pub const NORMALIZATION_AMPLITUDE: &str = "normalization";
+
+/// A sum of [`ExtendedLogLikelihood`]s over several datasets with a global parameter map. See
+/// the [module-level documentation](crate::simultaneous) for more information.
+#[derive(Clone)]
+pub struct SimultaneousLikelihood<F: Field + 'static> {
+    terms: Vec<(String, ExtendedLogLikelihood<F>)>,
+    scoped: Vec<(String, String)>,
+    parameters: Vec<(Option<usize>, Parameter<F>)>,
+    maps: Vec<Vec<usize>>,
+    normalized: Vec<bool>,
+    normalization_sigma: Option<F>,
+    normalizations: Vec<Option<usize>>,
+}
+
+impl<F: Field> SimultaneousLikelihood<F> {
+    /// Create a new [`SimultaneousLikelihood`] from labeled [`ExtendedLogLikelihood`]s.
+    ///
+    /// The free parameters of each term should be fixed, bounded, or constrained before the
+    /// [`SimultaneousLikelihood`] is created. Each free parameter of a term is identified by the
+    /// first [`Parameter`] with its index, and shared parameters take their initial value and
+    /// bounds from the first term which has them.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if two terms have the
+    /// same label.
+    pub fn new(terms: Vec<(&str, ExtendedLogLikelihood<F>)>) -> Result<Self, RustitudeError> {
+        let mut labels: Vec<&str> = Vec::with_capacity(terms.len());
+        for (label, _) in &terms {
+            if labels.contains(label) {
+                return Err(RustitudeError::InvalidParameterValue(format!(
+                    "duplicate dataset label \"{label}\""
+                )));
+            }
+            labels.push(label);
+        }
+        let n_terms = terms.len();
+        let mut simultaneous = Self {
+            terms: terms
+                .into_iter()
+                .map(|(label, ell)| (label.to_string(), ell))
+                .collect(),
+            scoped: Vec::new(),
+            parameters: Vec::new(),
+            maps: Vec::new(),
+            normalized: vec![false; n_terms],
+            normalization_sigma: None,
+            normalizations: Vec::new(),
+        };
+        simultaneous.build_parameter_map();
+        Ok(simultaneous)
+    }
+
+    /// Returns the labeled [`ExtendedLogLikelihood`]s in the order they were given.
+    pub fn terms(&self) -> &[(String, ExtendedLogLikelihood<F>)] {
+        &self.terms
+    }
+
+    /// Returns the labels of each term.
+    pub fn labels(&self) -> Vec<&str> {
+        self.terms.iter().map(|(label, _)| label.as_str()).collect()
+    }
+
+    /// Scope a parameter to each dataset, so that every term which has it gets its own global
+    /// free parameter.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if no term has a free
+    /// parameter with the given amplitude and parameter names.
+    pub fn scope(&mut self, amplitude: &str, parameter: &str) -> Result<(), RustitudeError> {
+        if !self
+            .parameters
+            .iter()
+            .any(|(_, p)| p.amplitude == amplitude && p.name == parameter)
+        {
+            return Err(RustitudeError::ParameterNotFoundError(format!(
+                "{amplitude}::{parameter}"
+            )));
+        }
+        let key = (amplitude.to_string(), parameter.to_string());
+        if !self.scoped.contains(&key) {
+            self.scoped.push(key);
+            self.build_parameter_map();
+        }
+        Ok(())
+    }
+
+    /// Share a parameter which was scoped with [`SimultaneousLikelihood::scope`] between all
+    /// datasets again.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if the parameter is
+    /// not scoped.
+    pub fn share(&mut self, amplitude: &str, parameter: &str) -> Result<(), RustitudeError> {
+        let position = self
+            .scoped
+            .iter()
+            .position(|(a, p)| a == amplitude && p == parameter)
+            .ok_or_else(|| {
+                RustitudeError::ParameterNotFoundError(format!("{amplitude}::{parameter}"))
+            })?;
+        self.scoped.remove(position);
+        self.build_parameter_map();
+        Ok(())
+    }
+
+    /// Add a normalization nuisance parameter to every term except the `reference`.
+    ///
+    /// The intensity of each of these terms is multiplied by its own free parameter
+    /// `label/normalization::scale` (see [`NORMALIZATION_AMPLITUDE`]), which starts at one and is
+    /// bounded below by zero. If `sigma` is given, each normalization $`s`$ is constrained to one
+    /// by adding $`((s - 1) / \sigma)^2`$ to $`-2\ln\mathcal{L}`$. The normalization parameters
+    /// come after all other global free parameters. Calling this method again replaces the
+    /// previous normalizations.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if no term has the
+    /// `reference` label, if `sigma` is not positive, or if a normalized term has explicit yield
+    /// parameters (see [`ExtendedLogLikelihood::add_signal_yield`]), since the yields already
+    /// determine its normalization, or a [`LikelihoodKind`](crate::manager::LikelihoodKind) other
+    /// than [`LikelihoodKind::Extended`](crate::manager::LikelihoodKind::Extended).
+    pub fn add_normalizations(
+        &mut self,
+        reference: &str,
+        sigma: Option<F>,
+    ) -> Result<(), RustitudeError> {
+        if !self.terms.iter().any(|(label, _)| label == reference) {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "no dataset labeled \"{reference}\""
+            )));
+        }
+        if sigma.is_some_and(|sigma| sigma <= F::zero()) {
+            return Err(RustitudeError::InvalidParameterValue(
+                "the width of a normalization constraint must be positive".to_string(),
+            ));
+        }
+        if let Some((label, _)) = self.terms.iter().find(|(label, ell)| {
+            label != reference && (!ell.yields.is_empty() || !ell.kind.is_extended())
+        }) {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "dataset \"{label}\" has explicit yields or a non-extended likelihood and cannot \
+                 be normalized"
+            )));
+        }
+        self.normalized = self
+            .terms
+            .iter()
+            .map(|(label, _)| label != reference)
+            .collect();
+        self.normalization_sigma = sigma;
+        self.build_parameter_map();
+        Ok(())
+    }
+
+    /// Removes the normalization parameters added by
+    /// [`SimultaneousLikelihood::add_normalizations`].
+    pub fn remove_normalizations(&mut self) {
+        self.normalized = vec![false; self.terms.len()];
+        self.normalization_sigma = None;
+        self.build_parameter_map();
+    }
+
+    /// Checks if a parameter is scoped to each dataset.
+    pub fn is_scoped(&self, amplitude: &str, parameter: &str) -> bool {
+        amplitude == YIELD_AMPLITUDE
+            || amplitude == NORMALIZATION_AMPLITUDE
+            || self
+                .scoped
+                .iter()
+                .any(|(a, p)| a == amplitude && p == parameter)
+    }
+
+    /// Returns the names of the global free parameters. Shared parameters are named
+    /// `amplitude::parameter`, and scoped parameters are prefixed by the label of their term,
+    /// `label/amplitude::parameter`.
+    pub fn parameter_names(&self) -> Vec<String> {
+        self.parameters
+            .iter()
+            .map(|(term, p)| {
+                let name = format!("{}::{}", p.amplitude, p.name);
+                term.map_or_else(|| name.clone(), |t| format!("{}/{name}", self.terms[t].0))
+            })
+            .collect()
+    }
+
+    /// Returns the number of global free parameters.
+    pub const fn get_n_free(&self) -> usize {
+        self.parameters.len()
+    }
+
+    /// Get a list of initial values for all global free parameters.
+    pub fn get_initial(&self) -> Vec<F> {
+        self.parameters.iter().map(|(_, p)| p.initial).collect()
+    }
+
+    /// Get a list of bounds for all global free parameters.
+    pub fn get_bounds(&self) -> Vec<(F, F)> {
+        self.parameters.iter().map(|(_, p)| p.bounds).collect()
+    }
+
+    /// Returns the free parameters of the term with the given label which correspond to the
+    /// global free `parameters`.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if no term has the
+    /// given label or if the number of parameters is incorrect.
+    pub fn term_parameters(&self, label: &str, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        let t = self
+            .terms
+            .iter()
+            .position(|(l, _)| l == label)
+            .ok_or_else(|| {
+                RustitudeError::InvalidParameterValue(format!("no dataset labeled \"{label}\""))
+            })?;
+        self.check_length(parameters)?;
+        Ok(self.local_parameters(t, parameters))
+    }
+
+    /// Evaluate the sum of [`ExtendedLogLikelihood::evaluate`] over every term.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the number of
+    /// parameters is incorrect, or any error produced by [`ExtendedLogLikelihood::evaluate`].
+    pub fn evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        self.evaluate_terms(parameters, false)
+    }
+
+    /// Evaluate the sum of [`ExtendedLogLikelihood::evaluate`] over every term.
+    ///
+    /// This version evaluates each term with [`ExtendedLogLikelihood::par_evaluate`]. See
+    /// [`SimultaneousLikelihood::evaluate`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the number of
+    /// parameters is incorrect, or any error produced by [`ExtendedLogLikelihood::par_evaluate`].
+    pub fn par_evaluate(&self, parameters: &[F]) -> Result<F, RustitudeError> {
+        self.evaluate_terms(parameters, true)
+    }
+
+    /// Evaluate the gradient of [`SimultaneousLikelihood::evaluate`] with respect to the global
+    /// free parameters.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the number of
+    /// parameters is incorrect, or any error produced by [`ExtendedLogLikelihood::gradient`].
+    pub fn gradient(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        self.gradient_terms(parameters, false)
+    }
+
+    /// Evaluate the gradient of [`SimultaneousLikelihood::evaluate`] with respect to the global
+    /// free parameters.
+    ///
+    /// This version evaluates each term with [`ExtendedLogLikelihood::par_gradient`]. See
+    /// [`SimultaneousLikelihood::gradient`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the number of
+    /// parameters is incorrect, or any error produced by [`ExtendedLogLikelihood::par_gradient`].
+    pub fn par_gradient(&self, parameters: &[F]) -> Result<Vec<F>, RustitudeError> {
+        self.gradient_terms(parameters, true)
+    }
+
+    /// Sums the (normalized) terms and the normalization constraints.
+    fn evaluate_terms(&self, parameters: &[F], parallel: bool) -> Result<F, RustitudeError> {
+        self.check_length(parameters)?;
+        let mut total = F::zero();
+        for (t, (_, ell)) in self.terms.iter().enumerate() {
+            let local = self.local_parameters(t, parameters);
+            total += match (self.normalizations[t], parallel) {
+                (Some(g), _) => {
+                    let scale = parameters[g];
+                    let penalty = self
+                        .normalization_sigma
+                        .map_or_else(F::zero, |sigma| ((scale - F::one()) / sigma).powi(2));
+                    ell.evaluate_normalized(&local, scale, parallel)? + penalty
+                }
+                (None, true) => ell.par_evaluate(&local)?,
+                (None, false) => ell.evaluate(&local)?,
+            };
+        }
+        Ok(total)
+    }
+
+    /// Sums the gradients of each term and the normalization constraints into the global free
+    /// parameters.
+    fn gradient_terms(&self, parameters: &[F], parallel: bool) -> Result<Vec<F>, RustitudeError> {
+        self.check_length(parameters)?;
+        let mut total = vec![F::zero(); parameters.len()];
+        for (t, (_, ell)) in self.terms.iter().enumerate() {
+            let local_parameters = self.local_parameters(t, parameters);
+            let local = match (self.normalizations[t], parallel) {
+                (Some(g), _) => {
+                    let scale = parameters[g];
+                    let mut local = ell.gradient_normalized(&local_parameters, scale, parallel)?;
+                    total[g] += local.pop().unwrap_or_else(F::zero);
+                    if let Some(sigma) = self.normalization_sigma {
+                        total[g] += convert!(2, F) * (scale - F::one()) / sigma.powi(2);
+                    }
+                    local
+                }
+                (None, true) => ell.par_gradient(&local_parameters)?,
+                (None, false) => ell.gradient(&local_parameters)?,
+            };
+            for (value, global) in local.into_iter().zip(&self.maps[t]) {
+                total[*global] += value;
+            }
+        }
+        Ok(total)
+    }
+
+    fn check_length(&self, parameters: &[F]) -> Result<(), RustitudeError> {
+        if parameters.len() != self.parameters.len() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "expected {} free parameter values, got {}",
+                self.parameters.len(),
+                parameters.len()
+            )));
+        }
+        Ok(())
+    }
+
+    fn local_parameters(&self, term: usize, parameters: &[F]) -> Vec<F> {
+        self.maps[term].iter().map(|i| parameters[*i]).collect()
+    }
+
+    /// Rebuilds the global free parameters and the map from each term's free parameters to them.
+    fn build_parameter_map(&mut self) {
+        let mut parameters: Vec<(Option<usize>, Parameter<F>)> = Vec::new();
+        let mut maps = Vec::with_capacity(self.terms.len());
+        for (t, (_, ell)) in self.terms.iter().enumerate() {
+            let mut map = vec![None; ell.get_n_free()];
+            for parameter in ell.free_parameters() {
+                let Some(index) = parameter.index else {
+                    continue;
+                };
+                if map[index].is_some() {
+                    continue;
+                }
+                let term = self
+                    .is_scoped(&parameter.amplitude, &parameter.name)
+                    .then_some(t);
+                let global = parameters
+                    .iter()
+                    .position(|(other_term, other)| {
+                        *other_term == term
+                            && other.amplitude == parameter.amplitude
+                            && other.name == parameter.name
+                    })
+                    .unwrap_or_else(|| {
+                        parameters.push((term, parameter));
+                        parameters.len() - 1
+                    });
+                map[index] = Some(global);
+            }
+            maps.push(map.into_iter().flatten().collect());
+        }
+        self.normalizations = self
+            .normalized
+            .iter()
+            .enumerate()
+            .map(|(t, normalized)| {
+                normalized.then(|| {
+                    let mut scale =
+                        Parameter::new(NORMALIZATION_AMPLITUDE, "scale", parameters.len());
+                    scale.bounds = (F::zero(), F::infinity());
+                    parameters.push((Some(t), scale));
+                    parameters.len() - 1
+                })
+            })
+            .collect();
+        self.parameters = parameters;
+        self.maps = maps;
+    }
+}
+
+#[cfg(feature = "ganesh")]
+impl<F: Field + ganesh::core::Field> Function<F, (), RustitudeError> for SimultaneousLikelihood<F> {
+    fn evaluate(&self, x: &DVector<F>, _args: Option<&()>) -> Result<F, RustitudeError> {
+        self.par_evaluate(x.as_slice())
+    }
+
+    fn gradient(&self, x: &DVector<F>, _args: Option<&()>) -> Result<DVector<F>, RustitudeError> {
+        Ok(DVector::from_vec(self.par_gradient(x.as_slice())?))
+    }
+}
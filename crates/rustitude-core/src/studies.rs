@@ -0,0 +1,571 @@
+//! This module contains a [`ToyStudy`] driver for validating fits with toy datasets.
+//!
+//! A [`ToyStudy`] generates toy datasets from a [`Model`](crate::amplitude::Model), refits each
+//! one, and accumulates the pulls and biases of every free parameter.
+//! Each toy is drawn from a pool of Monte-Carlo events with probabilities given by
+//! [`ExtendedLogLikelihood::intensity`] at the true parameters (see [`ToySource`]), and its size
+//! is Poisson-distributed around the predicted yield unless a fixed size is requested. The
+//! predicted yield is the true signal yield if the [`ExtendedLogLikelihood`] has one (see
+//! [`ExtendedLogLikelihood::add_signal_yield`]), and the weighted number of data events
+//! otherwise. The true
+//! parameters can optionally be smeared for each toy (see
+//! [`ToyStudy::with_truth_covariance`]). Each toy is then fitted with the Nelder-Mead algorithm
+//! starting at its true parameters, and the uncertainties come from
+//! [`ExtendedLogLikelihood::covariance`]. A correct fit gives pulls
+//! $`(\hat{\theta} - \theta)/\sigma_{\hat{\theta}}`$ with zero mean and unit width (see
+//! [`ToyStudyResult::summary`]).
+//!
+//! # Example:
+//!
+//! ```
+//! use rustitude_core::prelude::*;
+//! use rustitude_core::utils::generate_test_dataset_f64;
+//! # fn main() -> Result<(), RustitudeError> {
+//! let events = generate_test_dataset_f64()
+//!     .events
+//!     .iter()
+//!     .cycle()
+//!     .take(200)
+//!     .enumerate()
+//!     .map(|(index, event)| Event { index, weight: 1.0, ..event.clone() })
+//!     .collect();
+//! let dataset = Dataset::new(events);
+//! let model = model!(scalar("a"));
+//! let manager = Manager::new(&model, &dataset)?;
+//! let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+//! ell.fix("a", "value", 1.0)?;
+//! ell.add_signal_yield();
+//! let study = ToyStudy::new(ell, &[200.0])?.with_source(ToySource::Bootstrap);
+//! let result = study.run(10, 0)?;
+//! assert_eq!(result.len(), 10);
+//! assert_eq!(result.summary()[0].name, format!("{YIELD_AMPLITUDE}::signal"));
+//! # Ok(())
+//! # }
+//! ```
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    convert,
+    dataset::Dataset,
+    errors::RustitudeError,
+    fit::FitResult,
+    manager::{ExtendedLogLikelihood, Manager},
+    Field,
+};
+
+/// The largest mean of a single step of the Poisson sampler used for the size of each toy.
+const POISSON_STEP: f64 = 16.0;
+
+/// How the events of each toy in a [`ToyStudy`] are drawn from the Monte-Carlo pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToySource {
+    /// Accept-reject sampling: the pool is visited in a random order and each event is kept with
+    /// probability $`I_i / \max_j I_j`$, so no event appears twice in a toy. The pool must hold
+    /// enough events to reach the size of every toy.
+    #[default]
+    AcceptReject,
+    /// A weighted bootstrap of the pool: events are drawn with replacement with probability
+    /// $`I_i / \sum_j I_j`$, so toys of any size can be drawn from a small pool at the cost of
+    /// repeated events.
+    Bootstrap,
+}
+
+/// A toy study of an [`ExtendedLogLikelihood`] at a set of true parameters.
+///
+/// The data [`Dataset`] of the [`ExtendedLogLikelihood`] is replaced by each toy, while every
+/// other setting (the Monte-Carlo, yields, bounds, constraints, and so on) is kept for the fits.
+#[derive(Clone, Debug)]
+pub struct ToyStudy<F: Field + 'static> {
+    ell: ExtendedLogLikelihood<F>,
+    truth: Vec<F>,
+    source: ToySource,
+    pool: Option<Dataset<F>>,
+    n_events: Option<usize>,
+    truth_cholesky: Option<Vec<Vec<F>>>,
+    max_steps: usize,
+}
+
+impl<F: Field + ganesh::core::Field + 'static> ToyStudy<F> {
+    /// Create a new [`ToyStudy`] of the given [`ExtendedLogLikelihood`] with the true values of
+    /// its free parameters. By default, toys are drawn with [`ToySource::AcceptReject`] from the
+    /// Monte-Carlo [`Dataset`] of the [`ExtendedLogLikelihood`] and each fit takes at most `1000`
+    /// steps.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the number of true
+    /// values does not match the number of free parameters, or if the [`ExtendedLogLikelihood`]
+    /// has a background template (see [`ExtendedLogLikelihood::add_background_template`]), which
+    /// cannot be evaluated over a toy.
+    pub fn new(ell: ExtendedLogLikelihood<F>, truth: &[F]) -> Result<Self, RustitudeError> {
+        if truth.len() != ell.get_n_free() {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "expected {} true parameter values, got {}",
+                ell.get_n_free(),
+                truth.len()
+            )));
+        }
+        if ell.background_template.is_some() {
+            return Err(RustitudeError::InvalidParameterValue(
+                "toy studies do not support background templates".to_string(),
+            ));
+        }
+        Ok(Self {
+            ell,
+            truth: truth.to_vec(),
+            source: ToySource::default(),
+            pool: None,
+            n_events: None,
+            truth_cholesky: None,
+            max_steps: 1000,
+        })
+    }
+
+    /// Sets the [`ToySource`] used to draw the events of each toy.
+    #[must_use]
+    pub const fn with_source(mut self, source: ToySource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Draws toys from the given pool of (usually generated or accepted) Monte-Carlo events
+    /// rather than the Monte-Carlo [`Dataset`] of the [`ExtendedLogLikelihood`].
+    #[must_use]
+    pub fn with_pool(mut self, pool: Dataset<F>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Gives every toy exactly `n_events` events rather than a Poisson-distributed number around
+    /// the predicted yield (see the [module-level documentation](self)).
+    #[must_use]
+    pub const fn with_events(mut self, n_events: usize) -> Self {
+        self.n_events = Some(n_events);
+        self
+    }
+
+    /// Sets the maximum number of Nelder-Mead steps taken by the fit of each toy.
+    #[must_use]
+    pub const fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Draws the true parameters of each toy from a multivariate normal distribution centered on
+    /// the true values with the given covariance matrix (usually from a fit to data), so the
+    /// study covers the region of parameter space allowed by the data.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if the covariance
+    /// matrix does not have one row and column per free parameter, or is not symmetric and
+    /// positive semi-definite.
+    pub fn with_truth_covariance(mut self, covariance: &[Vec<F>]) -> Result<Self, RustitudeError> {
+        let n = self.truth.len();
+        if covariance.len() != n || covariance.iter().any(|row| row.len() != n) {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "covariance matrix must have shape ({n}, {n})"
+            )));
+        }
+        if (0..n).any(|i| (0..i).any(|j| covariance[i][j] != covariance[j][i])) {
+            return Err(RustitudeError::InvalidParameterValue(
+                "the covariance of the true parameters must be symmetric".to_string(),
+            ));
+        }
+        let mut cholesky = vec![vec![F::zero(); n]; n];
+        for j in 0..n {
+            let pivot =
+                covariance[j][j] - (0..j).map(|k| cholesky[j][k] * cholesky[j][k]).sum::<F>();
+            if pivot < F::zero() {
+                return Err(RustitudeError::InvalidParameterValue(
+                    "the covariance of the true parameters must be positive semi-definite"
+                        .to_string(),
+                ));
+            }
+            if pivot == F::zero() {
+                continue;
+            }
+            cholesky[j][j] = F::sqrt(pivot);
+            for i in (j + 1)..n {
+                cholesky[i][j] = (covariance[i][j]
+                    - (0..j).map(|k| cholesky[i][k] * cholesky[j][k]).sum::<F>())
+                    / cholesky[j][j];
+            }
+        }
+        self.truth_cholesky = Some(cholesky);
+        Ok(self)
+    }
+
+    /// Generates toy number `index` of a study with the given `seed`, returning its true
+    /// parameters and its [`Dataset`] of unit-weight events. The same `index` and `seed` always
+    /// give the same toy.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if any event in the
+    /// pool has a negative or non-finite intensity, or if the pool holds too few events for
+    /// [`ToySource::AcceptReject`], or any error from [`ExtendedLogLikelihood::intensity`].
+    pub fn generate(
+        &self,
+        index: usize,
+        seed: u64,
+    ) -> Result<(Vec<F>, Dataset<F>), RustitudeError> {
+        let mut rng = fastrand::Rng::with_seed(
+            seed.wrapping_add((index as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)),
+        );
+        let truth = self.truth_cholesky.as_ref().map_or_else(
+            || self.truth.clone(),
+            |cholesky| {
+                // Standard normal deviates from the Box-Muller transform
+                let normal: Vec<F> = (0..self.truth.len())
+                    .map(|_| {
+                        let (u1, u2) = (1.0 - rng.f64(), rng.f64());
+                        convert!(
+                            f64::sqrt(-2.0 * u1.ln()) * f64::cos(std::f64::consts::TAU * u2),
+                            F
+                        )
+                    })
+                    .collect();
+                self.truth
+                    .iter()
+                    .zip(cholesky)
+                    .map(|(t, row)| *t + row.iter().zip(&normal).map(|(l, z)| *l * *z).sum::<F>())
+                    .collect()
+            },
+        );
+        let pool = self.pool.as_ref().unwrap_or(&self.ell.mc_manager.dataset);
+        let intensities = self.ell.intensity(&truth, pool)?;
+        if intensities.iter().any(|i| !i.is_finite() || *i < F::zero()) {
+            return Err(RustitudeError::InvalidParameterValue(
+                "toys require a non-negative and finite intensity for every event in the pool"
+                    .to_string(),
+            ));
+        }
+        let total: F = intensities.iter().copied().sum();
+        let n_events = self.n_events.unwrap_or_else(|| {
+            let expected = if self.ell.yields.is_empty() {
+                self.ell
+                    .data_manager
+                    .dataset
+                    .weights()
+                    .iter()
+                    .copied()
+                    .sum()
+            } else {
+                truth[self.ell.mc_manager.get_n_free()]
+            };
+            poisson(&mut rng, expected.to_f64().unwrap_or(0.0))
+        });
+        let indices = match self.source {
+            ToySource::AcceptReject => {
+                let max = intensities.iter().copied().fold(F::zero(), F::max);
+                let mut order: Vec<usize> = (0..intensities.len()).collect();
+                rng.shuffle(&mut order);
+                let indices: Vec<usize> = order
+                    .into_iter()
+                    .filter(|&i| convert!(rng.f64(), F) * max < intensities[i])
+                    .take(n_events)
+                    .collect();
+                if indices.len() < n_events {
+                    return Err(RustitudeError::InvalidParameterValue(format!(
+                        "the pool only yields {} of the {n_events} events requested for toy {index}",
+                        indices.len()
+                    )));
+                }
+                indices
+            }
+            ToySource::Bootstrap => {
+                let cumulative: Vec<F> = intensities
+                    .iter()
+                    .scan(F::zero(), |sum, i| {
+                        *sum += *i;
+                        Some(*sum)
+                    })
+                    .collect();
+                (0..n_events)
+                    .map(|_| {
+                        let u = convert!(rng.f64(), F) * total;
+                        cumulative
+                            .partition_point(|c| *c <= u)
+                            .min(cumulative.len() - 1)
+                    })
+                    .collect()
+            }
+        };
+        let events = indices
+            .into_iter()
+            .enumerate()
+            .map(|(position, i)| {
+                let mut event = pool.events[i].clone();
+                event.index = position;
+                event.weight = F::one();
+                event
+            })
+            .collect();
+        Ok((truth, Dataset::new(events)))
+    }
+
+    /// Generates and fits toy number `index` of a study with the given `seed`.
+    fn toy(&self, index: usize, seed: u64) -> Result<ToyFit<F>, RustitudeError> {
+        let (truth, dataset) = self.generate(index, seed)?;
+        let n_events = dataset.len();
+        let mut ell = self.ell.clone();
+        ell.data_manager = Manager::new(&self.ell.data_manager.model, &dataset)?;
+        let mut fit = ell.minimize_from(&truth, self.max_steps)?;
+        let errors = match ell.covariance(&fit.best) {
+            Ok(covariance) => {
+                let errors = (0..covariance.len())
+                    .map(|i| covariance[i][i].sqrt())
+                    .collect();
+                fit.covariance = Some(covariance);
+                errors
+            }
+            Err(_) => vec![F::nan(); truth.len()],
+        };
+        Ok(ToyFit {
+            truth,
+            n_events,
+            errors,
+            fit,
+        })
+    }
+
+    /// Generates and fits `n_toys` toys, where the toys are determined by `seed` (see
+    /// [`ToyStudy::generate`]).
+    ///
+    /// # Errors
+    ///
+    /// This method will return any error produced while generating or fitting a toy. A toy whose
+    /// covariance matrix cannot be estimated is kept with NaN uncertainties (see
+    /// [`ToyFit::errors`]).
+    pub fn run(&self, n_toys: usize, seed: u64) -> Result<ToyStudyResult<F>, RustitudeError> {
+        let toys = (0..n_toys)
+            .map(|index| self.toy(index, seed))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.result(toys))
+    }
+
+    /// Generates and fits `n_toys` toys, where the toys are determined by `seed` (see
+    /// [`ToyStudy::generate`]).
+    ///
+    /// This version generates and fits the toys in parallel, and gives the same toys as
+    /// [`ToyStudy::run`] with the same `seed`. See [`ToyStudy::run`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::PythonError`] if the [`Model`](crate::amplitude::Model)
+    /// contains Python amplitudes, or any error produced while generating or fitting a toy.
+    pub fn par_run(&self, n_toys: usize, seed: u64) -> Result<ToyStudyResult<F>, RustitudeError> {
+        self.ell.install(|| {
+            if self.ell.data_manager.model.contains_python_amplitudes {
+                return Err(RustitudeError::PythonError(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!"
+                        .to_string(),
+                ));
+            }
+            let toys = (0..n_toys)
+                .into_par_iter()
+                .map(|index| self.toy(index, seed))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(self.result(toys))
+        })
+    }
+
+    fn result(&self, toys: Vec<ToyFit<F>>) -> ToyStudyResult<F> {
+        ToyStudyResult {
+            names: self
+                .ell
+                .free_parameters()
+                .iter()
+                .map(|p| format!("{}::{}", p.amplitude, p.name))
+                .collect(),
+            toys,
+        }
+    }
+}
+
+/// Draws a Poisson-distributed number with the given mean, as a sum of draws from Knuth's method
+/// with means of at most [`POISSON_STEP`] (which keeps each exponential from underflowing).
+fn poisson(rng: &mut fastrand::Rng, mean: f64) -> usize {
+    let mean = mean.max(0.0);
+    let n_steps = (mean / POISSON_STEP).ceil() as usize;
+    let mut count = 0;
+    for step in 0..n_steps {
+        let limit = (-f64::min((step as f64).mul_add(-POISSON_STEP, mean), POISSON_STEP)).exp();
+        let mut product = rng.f64();
+        loop {
+            if product <= limit {
+                break;
+            }
+            count += 1;
+            product *= rng.f64();
+        }
+    }
+    count
+}
+
+/// The outcome of the fit of a single toy in a [`ToyStudy`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ToyFit<F: Field> {
+    /// The true values of the free parameters used to generate the toy.
+    pub truth: Vec<F>,
+    /// The number of events in the toy.
+    pub n_events: usize,
+    /// The uncertainty of each free parameter, which is NaN if the covariance matrix could not be
+    /// estimated.
+    pub errors: Vec<F>,
+    /// The [`FitResult`] of the toy.
+    pub fit: FitResult<F>,
+}
+
+impl<F: Field> ToyFit<F> {
+    /// Returns the bias $`\hat{\theta} - \theta`$ of each free parameter.
+    pub fn biases(&self) -> Vec<F> {
+        self.fit
+            .best
+            .iter()
+            .zip(&self.truth)
+            .map(|(best, truth)| *best - *truth)
+            .collect()
+    }
+
+    /// Returns the pull $`(\hat{\theta} - \theta)/\sigma_{\hat{\theta}}`$ of each free parameter.
+    pub fn pulls(&self) -> Vec<F> {
+        self.biases()
+            .into_iter()
+            .zip(&self.errors)
+            .map(|(bias, error)| bias / *error)
+            .collect()
+    }
+}
+
+/// The summary statistics of the biases and pulls of one free parameter over a
+/// [`ToyStudyResult`]. Each width is the sample standard deviation, and each statistic comes
+/// with its standard error.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PullSummary<F: Field> {
+    /// The name of the parameter, as `amplitude::parameter`.
+    pub name: String,
+    /// The number of toys with a finite pull, which are the only toys included in the summary.
+    pub n_toys: usize,
+    /// The mean bias.
+    pub bias_mean: F,
+    /// The standard error of the mean bias.
+    pub bias_mean_error: F,
+    /// The width of the bias distribution.
+    pub bias_std: F,
+    /// The mean pull, which should be consistent with zero.
+    pub pull_mean: F,
+    /// The standard error of the mean pull.
+    pub pull_mean_error: F,
+    /// The width of the pull distribution, which should be consistent with one.
+    pub pull_std: F,
+    /// The standard error of the width of the pull distribution.
+    pub pull_std_error: F,
+}
+
+/// The fits of every toy in a [`ToyStudy`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ToyStudyResult<F: Field> {
+    /// The names of the free parameters, as `amplitude::parameter`.
+    pub names: Vec<String>,
+    /// The fit of each toy.
+    pub toys: Vec<ToyFit<F>>,
+}
+
+impl<F: Field> ToyStudyResult<F> {
+    /// Returns the number of toys.
+    pub const fn len(&self) -> usize {
+        self.toys.len()
+    }
+
+    /// Checks if there are no toys.
+    pub const fn is_empty(&self) -> bool {
+        self.toys.is_empty()
+    }
+
+    /// Returns the position of the free parameter with the given name (`amplitude::parameter`).
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParameterNotFoundError`] if there is no such
+    /// parameter.
+    pub fn index(&self, name: &str) -> Result<usize, RustitudeError> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .ok_or_else(|| RustitudeError::ParameterNotFoundError(name.to_string()))
+    }
+
+    /// Returns the bias of the free parameter at position `index` in every toy.
+    pub fn biases(&self, index: usize) -> Vec<F> {
+        self.toys.iter().map(|toy| toy.biases()[index]).collect()
+    }
+
+    /// Returns the pull of the free parameter at position `index` in every toy.
+    pub fn pulls(&self, index: usize) -> Vec<F> {
+        self.toys.iter().map(|toy| toy.pulls()[index]).collect()
+    }
+
+    /// Summarizes the biases and pulls of every free parameter, skipping toys whose pull is not
+    /// finite.
+    pub fn summary(&self) -> Vec<PullSummary<F>> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let (biases, pulls): (Vec<F>, Vec<F>) = self
+                    .biases(index)
+                    .into_iter()
+                    .zip(self.pulls(index))
+                    .filter(|(_, pull)| pull.is_finite())
+                    .unzip();
+                let n = convert!(pulls.len(), F);
+                let (bias_mean, bias_std) = mean_std(&biases);
+                let (pull_mean, pull_std) = mean_std(&pulls);
+                PullSummary {
+                    name: name.clone(),
+                    n_toys: pulls.len(),
+                    bias_mean,
+                    bias_mean_error: bias_std / n.sqrt(),
+                    bias_std,
+                    pull_mean,
+                    pull_mean_error: pull_std / n.sqrt(),
+                    pull_std,
+                    pull_std_error: pull_std / (convert!(2, F) * (n - F::one())).sqrt(),
+                }
+            })
+            .collect()
+    }
+
+    /// Serializes the [`ToyStudyResult`] to a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::SerializationError`] if serialization fails.
+    pub fn to_json(&self) -> Result<String, RustitudeError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Loads a [`ToyStudyResult`] from a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::SerializationError`] if deserialization fails.
+    pub fn from_json(json: &str) -> Result<Self, RustitudeError> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// Returns the mean and sample standard deviation of the given values (NaN if there are too
+/// few).
+fn mean_std<F: Field>(values: &[F]) -> (F, F) {
+    let n = convert!(values.len(), F);
+    let mean = values.iter().copied().sum::<F>() / n;
+    let variance = values.iter().map(|v| (*v - mean) * (*v - mean)).sum::<F>() / (n - F::one());
+    (mean, variance.sqrt())
+}
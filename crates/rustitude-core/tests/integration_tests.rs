@@ -79,6 +79,4275 @@ mod f64_tests {
         assert_is_close!(manager.evaluate(&[2.0, 4.0, 3.0, 10.0])?[0], 900.0, f64);
         Ok(())
     }
+    #[test]
+    fn test_wide_mc_accumulation() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let ell_wide = ell.clone().with_wide_mc_accumulation(true);
+        let n: f64 = dataset.weights().iter().sum();
+        // -2 * (N ln(a^2) - N a^2) with a = 2
+        let expected = -2.0 * (n * f64::ln(4.0) - n * 4.0);
+        assert_is_close!(ell.evaluate(&[2.0])?, expected, f64);
+        assert_is_close!(ell_wide.evaluate(&[2.0])?, expected, f64);
+        assert_is_close!(ell_wide.par_evaluate(&[2.0])?, expected, f64);
+        Ok(())
+    }
+    #[test]
+    fn test_intensity_policy() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let edges: Vec<f64> = (0..=20).map(|i| 0.5 * i as f64).collect();
+        let values: Vec<f64> = (0..20).map(|i| f64::from(i % 2)).collect();
+        let model = model!(template_hist_m("a", edges, values)? * cscalar("c"));
+        let manager = Manager::new(&model, &dataset)?;
+        let parameters = [1.0, 0.5];
+        let raw = manager.evaluate(&parameters)?;
+        let invalid = manager.invalid_intensities(&parameters)?;
+        let expected: Vec<usize> = (0..raw.len()).filter(|&i| raw[i] <= 0.0).collect();
+        assert!(!expected.is_empty() && expected.len() < raw.len());
+        assert_eq!(
+            invalid
+                .iter()
+                .map(|invalid| invalid.index)
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(invalid[0].amplitudes.len(), 2);
+        assert_eq!(invalid[0].amplitudes[0].0, "a");
+        let error = manager
+            .clone()
+            .with_intensity_policy(Some(IntensityPolicy::Error))
+            .par_evaluate(&parameters)
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains(&format!("event #{}", expected[0])));
+        let clamped = manager
+            .clone()
+            .with_intensity_policy(Some(IntensityPolicy::Clamp(1e-3)))
+            .evaluate_indexed(&parameters, &expected)?;
+        assert!(clamped.iter().all(|value| *value == 1e-3));
+        // Batched evaluation applies the policy to every parameter set
+        let grid = vec![parameters.to_vec(), vec![2.0, -1.0]];
+        let clamping = manager
+            .clone()
+            .with_intensity_policy(Some(IntensityPolicy::Clamp(1e-3)));
+        for (set, values) in grid.iter().zip(clamping.evaluate_many(&grid)?) {
+            assert_eq!(values, clamping.evaluate(set)?);
+        }
+        assert_eq!(
+            clamping.par_evaluate_many(&grid)?,
+            clamping.evaluate_many(&grid)?
+        );
+        let erroring = manager
+            .clone()
+            .with_intensity_policy(Some(IntensityPolicy::Error));
+        assert!(erroring.evaluate_many(&grid).is_err());
+        assert!(erroring.par_evaluate_many(&grid).is_err());
+        // The gradient paths run the same check as the likelihood
+        let ell = ExtendedLogLikelihood::new(erroring.clone(), erroring);
+        assert!(ell.evaluate(&parameters).is_err());
+        assert!(ell.gradient(&parameters).is_err());
+        assert!(ell.par_gradient(&parameters).is_err());
+        assert!(ell.evaluate_with_gradient(&parameters).is_err());
+        assert!(ell.par_evaluate_with_gradient(&parameters).is_err());
+        // Skipped events are dropped from the likelihood like an IntensityFloor::Drop
+        let skipping = manager
+            .clone()
+            .with_intensity_policy(Some(IntensityPolicy::Skip));
+        let ell = ExtendedLogLikelihood::new(skipping.clone(), skipping);
+        let dropped = ExtendedLogLikelihood::new(manager.clone(), manager)
+            .with_intensity_floor(Some(IntensityFloor::Drop(1e-300)));
+        assert!(ell.evaluate(&parameters)?.is_finite());
+        assert_is_close!(
+            ell.evaluate(&parameters)?,
+            dropped.evaluate(&parameters)?,
+            f64
+        );
+        let (value, gradient) = ell.evaluate_with_gradient(&parameters)?;
+        assert_is_close!(value, ell.evaluate(&parameters)?, f64);
+        for (skipped, expected) in gradient.iter().zip(dropped.gradient(&parameters)?) {
+            assert!(skipped.is_finite());
+            assert_is_close!(*skipped, expected, f64);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_model_optimize() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let edges: Vec<f64> = (0..=20).map(|i| 0.5 * i as f64).collect();
+        let rising: Vec<f64> = (0..20).map(|i| i as f64 + 1.0).collect();
+        let shape = template_hist_m("shape", edges, rising)?;
+        let production = scalar("p") * cscalar("c");
+        let wave: Box<dyn AmpLike<f64>> = Box::new(&shape * &production);
+        let nested = Sum(vec![
+            wave.clone(),
+            Box::new(Sum(vec![Box::new(scalar("a")), wave.clone()])),
+        ]);
+        let model = model!(
+            Product(vec![Box::new(nested), Box::new(cscalar("b"))]),
+            (&shape * &production).real(),
+            scalar("d")
+        );
+        let mut optimized = model.clone();
+        optimized.optimize()?;
+        assert!(optimized.is_optimized() && !model.is_optimized());
+        assert_eq!(
+            optimized.cohsums[0].to_amp_tree().to_string(),
+            "(shape * p * c + a + shape * p * c) * b"
+        );
+        let parameters = [0.7, 1.2, -0.4, 0.3, 0.5, 0.1, 2.0];
+        let mut manager = Manager::new(&model, &dataset)?;
+        let mut optimized = Manager::new(&optimized, &dataset)?;
+        let check =
+            |manager: &Manager<f64>, optimized: &Manager<f64>| -> Result<(), RustitudeError> {
+                for (a, b) in manager
+                    .evaluate(&parameters)?
+                    .iter()
+                    .zip(optimized.par_evaluate(&parameters)?)
+                {
+                    assert_is_close!(*a, b, 1e-12, f64);
+                }
+                Ok(())
+            };
+        check(&manager, &optimized)?;
+        // The plan does not depend on which amplitudes or coherent sums are active
+        manager.deactivate("c")?;
+        optimized.deactivate("c")?;
+        check(&manager, &optimized)?;
+        manager.model.cohsums[1].active = false;
+        optimized.model.cohsums[1].active = false;
+        check(&manager, &optimized)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_debug_event() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(
+            scalar("a") * cscalar("b") + cscalar("c"),
+            scalar("d").real()
+        );
+        let mut manager = Manager::new(&model, &dataset)?;
+        manager.deactivate("c")?;
+        let parameters = [2.0, 0.5, -1.0, 9.0, 9.0, 0.3];
+        let report = manager.debug_event(&parameters, 3)?;
+        assert_eq!(report.index, 3);
+        assert_is_close!(report.intensity, manager.evaluate(&parameters)?[3], f64);
+        assert_eq!(report.amplitudes.len(), 4);
+        assert_eq!(report.amplitudes[2], ("c".to_string(), None));
+        assert_eq!(report.cohsums.len(), 2);
+        let sum = &report.cohsums[0];
+        assert_eq!(sum.terms.len(), 2);
+        assert_eq!(sum.terms[0].0, "a * b");
+        assert_eq!(sum.terms[0].1, Some(Complex::new(1.0, -2.0)));
+        assert_eq!(sum.terms[1], ("c".to_string(), None));
+        assert_is_close!(sum.intensity.unwrap(), 5.0, f64);
+        assert_eq!(report.cohsums[1].terms[0].0, "Re[d]");
+        assert!(report.to_string().contains("c (inactive)"));
+        assert!(manager.debug_event(&parameters, dataset.len()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_deterministic_reduction() -> Result<(), RustitudeError> {
+        use rustitude_core::manager::reproducible_sum;
+        let data = generate_test_dataset_f64();
+        let events: Vec<Event<f64>> = (0..5000)
+            .map(|i| {
+                let mut event = data.events[i % data.len()].clone();
+                event.index = i;
+                event.weight = 1.0 + 1e-3 * i as f64;
+                event
+            })
+            .collect();
+        let dataset = Dataset::new(events);
+        let model = model!(scalar("a") * cscalar("c"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell =
+            ExtendedLogLikelihood::new(manager.clone(), manager).with_deterministic_reduction(true);
+        let parameters = [1.3, 0.4, -0.7];
+        let expected = ell.evaluate(&parameters)?;
+        let standard = ell.clone().with_deterministic_reduction(false);
+        assert_is_close!(standard.evaluate(&parameters)?, expected, 1e-10, f64);
+        for n in [1, 2, 3, 8] {
+            let ell = ell.clone().with_threads(n)?;
+            assert_eq!(ell.par_evaluate(&parameters)?.to_bits(), expected.to_bits());
+            let wide = ell.with_wide_mc_accumulation(true);
+            assert_eq!(
+                wide.par_evaluate(&parameters)?.to_bits(),
+                wide.evaluate(&parameters)?.to_bits()
+            );
+        }
+        let values: Vec<f64> = (0..10_000).map(|i| 0.1 * f64::from(i % 7) + 1e-8).collect();
+        assert_eq!(
+            reproducible_sum(&values, true).to_bits(),
+            reproducible_sum(&values, false).to_bits()
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_gaussian_constraint() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let mut ell_constrained = ell.clone();
+        assert!(ell_constrained
+            .add_gaussian_constraint("a", "value", 1.5, 0.0)
+            .is_err());
+        assert!(ell_constrained
+            .add_gaussian_constraint("a", "missing", 1.5, 0.25)
+            .is_err());
+        ell_constrained.add_gaussian_constraint("a", "value", 1.5, 0.25)?;
+        // ((2 - 1.5) / 0.25)^2 = 4
+        assert_is_close!(
+            ell_constrained.evaluate(&[2.0])?,
+            ell.evaluate(&[2.0])? + 4.0,
+            f64
+        );
+        assert_is_close!(
+            ell_constrained.par_evaluate(&[2.0])?,
+            ell.par_evaluate(&[2.0])? + 4.0,
+            f64
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_priors_and_penalties() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a") + scalar("b"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let mut ell_penalized = ell.clone();
+        assert!(ell_penalized
+            .add_penalty(&[("a", "value"), ("c", "value")], |_| Ok(0.0))
+            .is_err());
+        ell_penalized.add_prior("a", "value", 1.5, 0.25)?;
+        ell_penalized.add_penalty(&[("a", "value"), ("b", "value")], |v| {
+            Ok((v[0] - v[1]).powi(2))
+        })?;
+        // ((2 - 1.5) / 0.25)^2 + (2 - 3)^2 = 5
+        assert_is_close!(
+            ell_penalized.evaluate(&[2.0, 3.0])?,
+            ell.evaluate(&[2.0, 3.0])? + 5.0,
+            f64
+        );
+        let gradient = ell_penalized.gradient(&[2.0, 3.0])?;
+        let expected = ell.gradient(&[2.0, 3.0])?;
+        assert_is_close!(gradient[0], expected[0] + 16.0 - 2.0, 1e-6, f64);
+        assert_is_close!(gradient[1], expected[1] + 2.0, 1e-6, f64);
+        Ok(())
+    }
+    #[test]
+    fn test_robust_loss() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(piecewise_m("p", 4, (0.5, 2.0)), cscalar("c"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let parameters = [1.0, 0.5, 2.0, 0.0, 3.0, -1.0, 4.0, 0.0, 1.5, 0.5];
+        let standard = ell.evaluate(&parameters)?;
+        for loss in [RobustLoss::Cap(f64::INFINITY), RobustLoss::Tukey(1e6)] {
+            let robust = ell.clone().with_robust_loss(Some(loss));
+            assert_is_close!(robust.evaluate(&parameters)?, standard, 1e-6, f64);
+            assert_is_close!(robust.evaluate_standard(&parameters)?, standard, f64);
+        }
+        for loss in [RobustLoss::Cap(0.0), RobustLoss::Tukey(0.5)] {
+            let robust = ell.clone().with_robust_loss(Some(loss));
+            let value = robust.evaluate(&parameters)?;
+            assert!(value.is_finite() && (value - standard).abs() > 1e-6);
+            assert_is_close!(value, robust.par_evaluate(&parameters)?, 1e-10, f64);
+            // Compare the analytic gradient to central differences
+            let gradient = robust.gradient(&parameters)?;
+            assert_eq!(gradient.len(), parameters.len());
+            for (i, g) in gradient.iter().enumerate() {
+                let h = 1e-6;
+                let mut up = parameters;
+                up[i] += h;
+                let mut down = parameters;
+                down[i] -= h;
+                let numeric = (robust.evaluate(&up)? - robust.evaluate(&down)?) / (2.0 * h);
+                assert!((g - numeric).abs() < 1e-4 * (1.0 + numeric.abs()));
+            }
+            let result = FitResult::new(&robust, &parameters, value, None)?;
+            assert_is_close!(result.standard_fx.unwrap(), standard, f64);
+        }
+        let mut with_yields = ell.clone().with_robust_loss(Some(RobustLoss::Cap(1.0)));
+        with_yields.add_signal_yield();
+        let mut yield_parameters = parameters.to_vec();
+        yield_parameters.push(dataset.len() as f64);
+        assert!(with_yields.evaluate(&yield_parameters).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_likelihood_kinds() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(piecewise_m("p", 4, (0.5, 2.0)), cscalar("c"));
+        let manager = Manager::new(&model, &dataset)?;
+        let extended = ExtendedLogLikelihood::new(manager.clone(), manager.clone());
+        let parameters = [1.0, 0.5, 2.0, 0.0, 3.0, -1.0, 4.0, 0.0, 1.5, 0.5];
+        let mass = |event: &Event<f64>| {
+            event
+                .daughter_p4s
+                .iter()
+                .copied()
+                .sum::<FourMomentum<f64>>()
+                .m()
+        };
+        let mut unextended = extended.clone();
+        unextended.kind = LikelihoodKind::Unextended;
+        let mut binned = extended.clone();
+        binned.set_binned(vec![0.5, 1.0, 1.5, 2.0], mass)?;
+        // The shape-only likelihood does not depend on the overall scale of the intensity
+        let scaled: Vec<f64> = parameters.iter().map(|p| 2.0 * p).collect();
+        assert_is_close!(
+            unextended.evaluate(&parameters)?,
+            unextended.evaluate(&scaled)?,
+            1e-8,
+            f64
+        );
+        assert!((extended.evaluate(&parameters)? - extended.evaluate(&scaled)?).abs() > 1e-6);
+        // Compare the binned likelihood to a direct sum over bins
+        let values = manager.evaluate(&parameters)?;
+        let weights = dataset.weights();
+        let n_data: f64 = weights.iter().sum();
+        let (mut counts, mut predicted) = ([0.0; 3], [0.0; 3]);
+        for ((event, value), weight) in dataset.events.iter().zip(&values).zip(&weights) {
+            let m = mass(event);
+            if (0.5..2.0).contains(&m) {
+                let bin = ((m - 0.5) / 0.5) as usize;
+                counts[bin] += weight;
+                predicted[bin] += weight * value;
+            }
+        }
+        let expected: f64 = counts
+            .iter()
+            .zip(&predicted)
+            .map(|(n, mc)| {
+                let mu = n_data / n_data * mc;
+                if *n == 0.0 {
+                    -mu
+                } else {
+                    n * mu.ln() - mu
+                }
+            })
+            .sum();
+        assert_is_close!(binned.evaluate(&parameters)?, -2.0 * expected, 1e-8, f64);
+        let all: Vec<usize> = (0..dataset.len()).collect();
+        for ell in [&unextended, &binned] {
+            let value = ell.evaluate(&parameters)?;
+            assert_is_close!(value, ell.par_evaluate(&parameters)?, 1e-10, f64);
+            assert_is_close!(
+                value,
+                ell.evaluate_indexed(&parameters, &all, &all)?,
+                1e-10,
+                f64
+            );
+            assert_is_close!(
+                value,
+                ell.par_evaluate_indexed(&parameters, &all, &all)?,
+                1e-10,
+                f64
+            );
+            // Compare the analytic gradient to central differences
+            let gradient = ell.gradient(&parameters)?;
+            let (fused_value, fused_gradient) = ell.par_evaluate_with_gradient(&parameters)?;
+            assert_is_close!(fused_value, value, 1e-10, f64);
+            for (i, g) in gradient.iter().enumerate() {
+                let h = 1e-6;
+                let mut up = parameters;
+                up[i] += h;
+                let mut down = parameters;
+                down[i] -= h;
+                let numeric = (ell.evaluate(&up)? - ell.evaluate(&down)?) / (2.0 * h);
+                assert!((g - numeric).abs() < 1e-4 * (1.0 + numeric.abs()));
+                assert_is_close!(fused_gradient[i], *g, 1e-8, f64);
+            }
+        }
+        let mut with_yields = unextended.clone();
+        with_yields.add_signal_yield();
+        let mut yield_parameters = parameters.to_vec();
+        yield_parameters.push(dataset.len() as f64);
+        assert!(with_yields.evaluate(&yield_parameters).is_err());
+        assert!(LinearLikelihood::new(&binned).is_err());
+        assert!(binned.set_binned(vec![1.0, 1.0], mass).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_binned_chi2() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(piecewise_m("p", 4, (0.5, 2.0)), cscalar("c"));
+        let manager = Manager::new(&model, &dataset)?;
+        let parameters = [1.0, 0.5, 2.0, 0.0, 3.0, -1.0, 4.0, 0.0, 1.5, 0.5];
+        let variables = |event: &Event<f64>| {
+            vec![
+                (event.daughter_p4s[0] + event.daughter_p4s[1]).m(),
+                event.beam_p4.e(),
+            ]
+        };
+        let edges = vec![vec![0.5, 1.0, 1.5, 2.0], vec![0.0, 8.5, 9.0, 20.0]];
+        let chi2 = BinnedChi2::new(manager.clone(), manager.clone(), edges.clone(), variables)?;
+        assert_eq!(chi2.n_bins(), 9);
+        // Compare to a direct sum over the flattened bins
+        let values = manager.evaluate(&parameters)?;
+        let (mut counts, mut variances, mut predicted) = ([0.0; 9], [0.0; 9], [0.0; 9]);
+        for (event, value) in dataset.events.iter().zip(&values) {
+            let v = variables(event);
+            let find = |x: f64, e: &[f64]| e.windows(2).position(|w| w[0] <= x && x < w[1]);
+            if let (Some(i), Some(j)) = (find(v[0], &edges[0]), find(v[1], &edges[1])) {
+                counts[i * 3 + j] += event.weight;
+                variances[i * 3 + j] += event.weight * event.weight;
+                predicted[i * 3 + j] += event.weight * value;
+            }
+        }
+        assert_eq!(chi2.counts(), &counts);
+        let expected: f64 = (0..9)
+            .filter(|b| variances[*b] > 0.0)
+            .map(|b| (counts[b] - predicted[b]).powi(2) / variances[b])
+            .sum();
+        let value = chi2.evaluate(&parameters)?;
+        assert!(chi2.n_filled() > 1 && value > 0.0);
+        assert_is_close!(value, expected, 1e-8, f64);
+        assert_is_close!(value, chi2.par_evaluate(&parameters)?, 1e-10, f64);
+        // Compare the analytic gradient to central differences
+        let gradient = chi2.gradient(&parameters)?;
+        let par_gradient = chi2.par_gradient(&parameters)?;
+        for (i, g) in gradient.iter().enumerate() {
+            let h = 1e-6;
+            let mut up = parameters;
+            up[i] += h;
+            let mut down = parameters;
+            down[i] -= h;
+            let numeric = (chi2.evaluate(&up)? - chi2.evaluate(&down)?) / (2.0 * h);
+            assert!((g - numeric).abs() < 1e-4 * (1.0 + numeric.abs()));
+            assert_is_close!(par_gradient[i], *g, 1e-8, f64);
+        }
+        assert!(BinnedChi2::new(manager.clone(), manager.clone(), vec![], variables).is_err());
+        assert!(BinnedChi2::new(
+            manager.clone(),
+            manager.clone(),
+            vec![vec![1.0, 0.5]],
+            |e| { vec![e.beam_p4.e()] }
+        )
+        .is_err());
+        assert!(
+            BinnedChi2::new(manager.clone(), manager, vec![vec![0.0, 1.0]], variables).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_frames() -> Result<(), RustitudeError> {
+        use rustitude_core::frames::{self, Axes, Frame};
+        let event = generate_test_event_f64();
+        let resonance = event.daughter_p4s[0] + event.daughter_p4s[1];
+        let daughter = event.daughter_p4s[0].boost_along(&resonance).momentum();
+        for frame in [Frame::Helicity, Frame::GottfriedJackson] {
+            let axes = Axes::new(frame, &event, &resonance);
+            // The axes are orthonormal and right-handed (up to the single-precision momentum
+            // conservation of the test event)
+            for (a, b) in [(axes.x, axes.y), (axes.y, axes.z), (axes.z, axes.x)] {
+                assert_is_close!(a.dot(&a), 1.0, 1e-5, f64);
+                assert!(a.dot(&b).abs() < 1e-5);
+            }
+            assert_is_close!(axes.x.cross(&axes.y).dot(&axes.z), 1.0, 1e-5, f64);
+            let (costheta, phi) =
+                frames::decay_angles(frame, &event, &resonance, &event.daughter_p4s[0]);
+            let v = axes.components(&daughter);
+            assert_is_close!(costheta, v.z / daughter.dot(&daughter).sqrt(), f64);
+            assert_is_close!(phi, v.y.atan2(v.x), f64);
+        }
+        // The Gottfried-Jackson axis is the beam direction in the rest frame of the resonance
+        let beam = event.beam_p4.boost_along(&resonance).direction();
+        let (costheta, _) = frames::decay_angles(
+            Frame::GottfriedJackson,
+            &event,
+            &resonance,
+            &event.daughter_p4s[0],
+        );
+        assert_is_close!(costheta, daughter.unit().dot(&beam), f64);
+        let production = Axes::production(&event);
+        assert!(production.y.dot(&event.recoil_p4.momentum()).abs() < 1e-10);
+        assert!(production.y.dot(&event.beam_p4.momentum()).abs() < 1e-10);
+        let big_phi = frames::polarization_angle(&event, &production.y);
+        assert_is_close!(
+            big_phi.sin() * event.eps_mag(),
+            production.y.dot(&event.eps),
+            f64
+        );
+        assert!(matches!("GJ".parse::<Frame>(), Ok(Frame::GottfriedJackson)));
+        assert!(matches!("hx".parse::<Frame>(), Ok(Frame::Helicity)));
+        assert!("lab".parse::<Frame>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_math() {
+        use rustitude_core::math::*;
+        let beta = 0.7;
+        assert_is_close!(wigner_small_d(beta, 2, 0, 0), f64::cos(beta), f64);
+        assert_is_close!(wigner_small_d(beta, 1, 1, 1), f64::cos(beta / 2.0), f64);
+        assert_is_close!(
+            wigner_small_d(beta, 2, 2, 0),
+            -f64::sin(beta) / f64::sqrt(2.0),
+            f64
+        );
+        assert_is_close!(
+            wigner_small_d(beta, 2, 0, 2),
+            f64::sin(beta) / f64::sqrt(2.0),
+            f64
+        );
+        assert_eq!(wigner_small_d(beta, 2, 4, 0), 0.0);
+        // Each column of d^j is normalized
+        for two_j in [3, 4, 5] {
+            for two_n in (-two_j..=two_j).step_by(2) {
+                let norm: f64 = (-two_j..=two_j)
+                    .step_by(2)
+                    .map(|two_m| wigner_small_d(beta, two_j as u32, two_m, two_n).powi(2))
+                    .sum();
+                assert_is_close!(norm, 1.0, f64);
+            }
+        }
+        let big_d = wigner_d(0.3, beta, -0.4, 2, 2, 0);
+        assert_is_close!(
+            big_d.re,
+            f64::cos(-0.3) * wigner_small_d(beta, 2, 2, 0),
+            f64
+        );
+        assert_is_close!(
+            big_d.im,
+            f64::sin(-0.3) * wigner_small_d(beta, 2, 2, 0),
+            f64
+        );
+        assert_is_close!(
+            clebsch_gordan::<f64>(1, 1, 1, -1, 2, 0),
+            f64::sqrt(0.5),
+            f64
+        );
+        assert_is_close!(
+            clebsch_gordan::<f64>(1, 1, 1, -1, 0, 0),
+            f64::sqrt(0.5),
+            f64
+        );
+        assert_is_close!(
+            clebsch_gordan::<f64>(1, -1, 1, 1, 0, 0),
+            -f64::sqrt(0.5),
+            f64
+        );
+        assert_is_close!(
+            clebsch_gordan::<f64>(2, 2, 2, -2, 0, 0),
+            f64::sqrt(1.0 / 3.0),
+            f64
+        );
+        assert_is_close!(
+            clebsch_gordan::<f64>(2, 2, 1, -1, 1, 1),
+            f64::sqrt(2.0 / 3.0),
+            f64
+        );
+        assert_is_close!(clebsch_gordan::<f64>(0, 0, 2, 2, 2, 2), 1.0, f64);
+        assert_is_close!(
+            clebsch_gordan::<f64>(4, 2, 2, -2, 2, 0),
+            f64::sqrt(3.0 / 10.0),
+            f64
+        );
+        assert_eq!(clebsch_gordan::<f64>(2, 0, 2, 0, 2, 0), 0.0);
+        assert_eq!(clebsch_gordan::<f64>(2, 2, 2, 0, 2, 0), 0.0);
+        // The coefficients coupling to a given (j, m) are normalized
+        let norm: f64 = (-4..=4)
+            .step_by(2)
+            .map(|two_m1| clebsch_gordan::<f64>(4, two_m1, 3, 1 - two_m1, 5, 1).powi(2))
+            .sum();
+        assert_is_close!(norm, 1.0, f64);
+        let q = 0.3;
+        let z = (q / BARRIER_SCALE).powi(2);
+        assert_is_close!(blatt_weisskopf(q, 0), 1.0, f64);
+        assert_is_close!(blatt_weisskopf(q, 1), f64::sqrt(2.0 * z / (z + 1.0)), f64);
+        assert_is_close!(
+            blatt_weisskopf(q, 2),
+            f64::sqrt(13.0 * z.powi(2) / ((z - 3.0).powi(2) + 9.0 * z)),
+            f64
+        );
+        assert_is_close!(
+            blatt_weisskopf(q, 3),
+            f64::sqrt(277.0 * z.powi(3) / (z * (z - 15.0).powi(2) + 9.0 * (2.0 * z - 5.0).powi(2))),
+            f64
+        );
+        assert_is_close!(
+            blatt_weisskopf(q, 4),
+            f64::sqrt(
+                12746.0 * z.powi(4)
+                    / ((z.powi(2) - 45.0 * z + 105.0).powi(2)
+                        + 25.0 * z * (2.0 * z - 21.0).powi(2))
+            ),
+            f64
+        );
+        assert_is_close!(blatt_weisskopf(BARRIER_SCALE, 5), 1.0, f64);
+        let complex = blatt_weisskopf_c(Complex::from(q), 3);
+        assert_is_close!(complex.re, blatt_weisskopf(q, 3), f64);
+        assert!(complex.im.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_two_body_kinematics() {
+        use rustitude_core::math::*;
+        let (m1, m2): (f64, f64) = (0.139, 0.494);
+        // q = sqrt(lambda(m^2, m1^2, m2^2)) / 2m
+        let m = 1.2;
+        let lambda = (m * m - (m1 + m2).powi(2)) * (m * m - (m1 - m2).powi(2));
+        assert_is_close!(breakup_momentum(m, m1, m2), lambda.sqrt() / (2.0 * m), f64);
+        // Above threshold, q and rho are real and C has Im(C) = +-rho
+        let q_c = breakup_momentum_c(m, m1, m2);
+        assert_is_close!(q_c.re, breakup_momentum(m, m1, m2), f64);
+        assert!(q_c.im.abs() < 1e-12);
+        let r = rho(m * m, m1, m2);
+        assert_is_close!(r.re, 2.0 * breakup_momentum(m, m1, m2) / m, f64);
+        assert_is_close!(chew_mandelstam(m * m, m1, m2).im.abs(), r.re, f64);
+        // Below threshold, q and rho are imaginary and C is real
+        let m = 0.5;
+        let q_c = breakup_momentum_c(m, m1, m2);
+        assert!(q_c.re.abs() < 1e-12);
+        assert_is_close!(q_c.im, breakup_momentum(m, m1, m2), f64);
+        assert!(rho(m * m, m1, m2).im > 0.0);
+        assert!(chew_mandelstam(m * m, m1, m2).im.abs() < 1e-12);
+        assert_is_close!(
+            (chi_plus(m * m, m1, m2) * chi_minus(m * m, m1, m2)).re,
+            rho(m * m, m1, m2).powi(2).re,
+            f64
+        );
+    }
+
+    #[test]
+    fn test_incremental_evaluation() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(
+            piecewise_m("p", 4, (0.5, 2.0)) * cscalar("c"),
+            scalar("s") * pcscalar("q")
+        );
+        let plain = Manager::new(&model, &dataset)?;
+        let mut manager = plain.clone().with_incremental(true);
+        let mut parameters = vec![
+            1.0, 0.5, 2.0, 0.0, 3.0, -1.0, 4.0, 0.0, 1.5, 0.5, 0.7, 1.2, 0.3,
+        ];
+        assert_eq!(manager.evaluate(&parameters)?, plain.evaluate(&parameters)?);
+        // Scan a single bin, then change a parameter shared by every event
+        for value in [0.1, 0.2, 0.3] {
+            parameters[2] = value;
+            assert_eq!(manager.evaluate(&parameters)?, plain.evaluate(&parameters)?);
+        }
+        parameters[8] = -0.4;
+        assert_eq!(
+            manager.par_evaluate(&parameters)?,
+            plain.par_evaluate(&parameters)?
+        );
+        // Activity changes and fixed parameters are respected
+        let mut plain = plain;
+        for m in [&mut manager, &mut plain] {
+            m.deactivate("s")?;
+        }
+        assert_eq!(manager.evaluate(&parameters)?, plain.evaluate(&parameters)?);
+        for m in [&mut manager, &mut plain] {
+            m.activate("s")?;
+            m.fix("c", "real", 2.0)?;
+        }
+        parameters.remove(8);
+        assert_eq!(manager.evaluate(&parameters)?, plain.evaluate(&parameters)?);
+        // Clones start with an empty cache
+        assert!(manager.clone().incremental.is_some());
+        Ok(())
+    }
+    #[test]
+    fn test_compute_into() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let mut model = model!(scalar("a") * cscalar("b"), scalar("c") * pcscalar("d"));
+        model.deactivate("c")?;
+        let parameters = vec![1.5, 0.3, -0.2, 0.8, 2.0, 0.4];
+        let amplitudes = model.amplitudes.read().clone();
+        let mut scratch = vec![None; 7];
+        for event in dataset.events.iter() {
+            assert_eq!(
+                model.compute_into(&amplitudes, &[], &parameters, event, &mut scratch)?,
+                model.compute(&amplitudes, &parameters, event)?
+            );
+            assert_eq!(scratch.len(), amplitudes.len());
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_compute_batch() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(
+            piecewise_m("p", 4, (0.5, 2.0)) * cscalar("c"),
+            scalar("s") * pcscalar("q")
+        );
+        let manager = Manager::new(&model, &dataset)?;
+        let parameters = vec![
+            1.0, 0.5, 2.0, 0.0, 3.0, -1.0, 4.0, 0.0, 1.5, 0.5, 0.7, 1.2, 0.3,
+        ];
+        let expected = manager.evaluate(&parameters)?;
+        let amplitudes = manager.model.amplitudes.read().clone();
+        let mut batched = vec![0.0; dataset.len()];
+        manager.model.compute_batch(
+            &amplitudes,
+            &[],
+            &parameters,
+            &dataset.events,
+            &mut batched,
+        )?;
+        for (value, expected) in batched.iter().zip(&expected) {
+            assert_is_close!(*value, *expected, f64);
+        }
+        // Non-contiguous events are gathered by index
+        let events: Vec<Event<f64>> = dataset.events.iter().rev().cloned().collect();
+        let mut values = vec![0.0; events.len()];
+        gather_batch(&expected, &events, &mut values);
+        assert_eq!(values, expected.into_iter().rev().collect::<Vec<_>>());
+        Ok(())
+    }
+    #[test]
+    fn test_columnar_layout() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        assert_eq!(dataset.layout(), DatasetLayout::Events);
+        let columnar = dataset.clone().with_layout(DatasetLayout::Columns)?;
+        assert_eq!(columnar.layout(), DatasetLayout::Columns);
+        let columns = columnar.columns().unwrap();
+        assert_eq!(columns.len(), dataset.len());
+        assert_eq!(columns.daughters.len(), 2);
+        assert_eq!(columnar.weights(), dataset.weights());
+        for event in dataset.events.iter() {
+            let rebuilt = columns.event(event.index);
+            assert_eq!(rebuilt.beam_p4, event.beam_p4);
+            assert_eq!(rebuilt.recoil_p4, event.recoil_p4);
+            assert_eq!(rebuilt.daughter_p4s, event.daughter_p4s);
+            assert_eq!(rebuilt.eps, event.eps);
+            assert_eq!(
+                columns.daughters[1].pz[event.index],
+                event.daughter_p4s[1].pz()
+            );
+        }
+        // Combining keeps the layout, and mismatched daughter counts are rejected
+        assert_eq!(
+            (columnar.clone() + dataset).columns().unwrap().len(),
+            2 * columns.len()
+        );
+        let mut event = generate_test_event_f64();
+        event.daughter_p4s.pop();
+        let mismatched = columnar + Dataset::new(vec![event]);
+        assert_eq!(mismatched.layout(), DatasetLayout::Events);
+        assert!(mismatched.with_layout(DatasetLayout::Columns).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_dataset_transforms() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64().with_layout(DatasetLayout::Columns)?;
+        let boosted = dataset.clone().boost_to_com()?;
+        assert_eq!(boosted.layout(), DatasetLayout::Columns);
+        for (event, original) in boosted.events.iter().zip(dataset.events.iter()) {
+            let final_state: FourMomentum<f64> = event.recoil_p4
+                + event
+                    .daughter_p4s
+                    .iter()
+                    .copied()
+                    .sum::<FourMomentum<f64>>();
+            assert!(final_state.momentum().norm() < 1e-9);
+            assert_is_close!(
+                event.daughter_p4s[0].m(),
+                original.daughter_p4s[0].m(),
+                1e-9,
+                f64
+            );
+            assert_eq!(event.eps, original.eps);
+        }
+        // Transforms are applied in order
+        let rotation = rustitude_core::four_momentum::euler_rotation_matrix(0.3, 0.2, -0.1);
+        let transformed = dataset.clone().with_transforms(&[
+            DatasetTransform::ReorderDaughters(vec![1, 0]),
+            DatasetTransform::Rotate(rotation),
+        ])?;
+        for (event, original) in transformed.events.iter().zip(dataset.events.iter()) {
+            assert_eq!(
+                event.daughter_p4s[0],
+                original.daughter_p4s[1].rotate(&rotation)
+            );
+            assert_eq!(event.eps, rotation * original.eps);
+        }
+        assert_eq!(
+            transformed.columns().unwrap().daughters[0].e,
+            dataset.columns().unwrap().daughters[1].e
+        );
+        let dropped = dataset
+            .clone()
+            .with_transforms(&[DatasetTransform::ReorderDaughters(vec![1])])?;
+        assert_eq!(dropped.events[0].daughter_p4s.len(), 1);
+        assert!(dataset
+            .with_transforms(&[DatasetTransform::ReorderDaughters(vec![2])])
+            .is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_smearing() -> Result<(), RustitudeError> {
+        use nalgebra::Matrix4;
+        assert!(DatasetTransform::<f64>::smear(-Matrix4::identity(), 0).is_err());
+        let mut asymmetric = Matrix4::<f64>::identity();
+        asymmetric[(0, 1)] = 0.5;
+        assert!(DatasetTransform::smear(asymmetric, 0).is_err());
+        // Smear the energy and p_x of the first daughter with a correlation of 0.5
+        let (sigma_e, sigma_x, rho) = (0.02, 0.01, 0.5);
+        let mut covariance = Matrix4::zeros();
+        covariance[(0, 0)] = sigma_e * sigma_e;
+        covariance[(1, 1)] = sigma_x * sigma_x;
+        covariance[(0, 1)] = rho * sigma_e * sigma_x;
+        covariance[(1, 0)] = rho * sigma_e * sigma_x;
+        let smear = DatasetTransform::smear(covariance, 7)?;
+        let event = generate_test_event_f64();
+        let n = 20000;
+        let dataset = Dataset::new(
+            (0..n)
+                .map(|index| Event {
+                    index,
+                    ..event.clone()
+                })
+                .collect(),
+        );
+        let smeared = dataset
+            .clone()
+            .with_transforms(std::slice::from_ref(&smear))?;
+        let shifts: Vec<(f64, f64)> = smeared
+            .events
+            .iter()
+            .map(|e| {
+                assert_eq!(e.beam_p4, event.beam_p4);
+                assert_eq!(e.daughter_p4s[0].py(), event.daughter_p4s[0].py());
+                assert_eq!(e.daughter_p4s[0].pz(), event.daughter_p4s[0].pz());
+                (
+                    e.daughter_p4s[0].e() - event.daughter_p4s[0].e(),
+                    e.daughter_p4s[0].px() - event.daughter_p4s[0].px(),
+                )
+            })
+            .collect();
+        let var_e = shifts.iter().map(|(de, _)| de * de).sum::<f64>() / n as f64;
+        let var_x = shifts.iter().map(|(_, dx)| dx * dx).sum::<f64>() / n as f64;
+        let cov = shifts.iter().map(|(de, dx)| de * dx).sum::<f64>() / n as f64;
+        assert_is_close!(var_e, sigma_e * sigma_e, 0.05, f64);
+        assert_is_close!(var_x, sigma_x * sigma_x, 0.05, f64);
+        assert_is_close!(cov / f64::sqrt(var_e * var_x), rho, 0.05, f64);
+        // The same seed gives the same smearing
+        let again = dataset.with_transforms(&[smear])?;
+        assert_eq!(
+            again.events[123].daughter_p4s,
+            smeared.events[123].daughter_p4s
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_polarization_states() {
+        let event = generate_test_event_f64();
+        match event.polarization() {
+            Polarization::Linear { degree, angle } => {
+                assert_is_close!(degree, event.eps_mag(), f64);
+                assert_is_close!(angle, f64::atan2(event.eps.y, event.eps.x), f64);
+            }
+            other => panic!("expected a linear polarization, got {other:?}"),
+        }
+        let unpolarized = Event {
+            eps: Vector3::zeros(),
+            ..event.clone()
+        };
+        assert_eq!(unpolarized.polarization(), Polarization::Unpolarized);
+        let circular = Event {
+            circular_polarization: -0.8,
+            ..unpolarized.clone()
+        };
+        assert_eq!(
+            circular.polarization(),
+            Polarization::Circular { degree: -0.8 }
+        );
+        let elliptical = Event {
+            circular_polarization: 0.5,
+            ..event.clone()
+        };
+        assert!(matches!(
+            elliptical.polarization(),
+            Polarization::Elliptical { circular_degree, .. } if circular_degree == 0.5
+        ));
+        assert_ne!(
+            Dataset::new(vec![elliptical]).fingerprint(),
+            Dataset::new(vec![event]).fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_dataset_views() -> Result<(), RustitudeError> {
+        let event = generate_test_event_f64();
+        let make = |n: usize| {
+            Dataset::new(
+                (0..n)
+                    .map(|index| Event {
+                        index,
+                        weight: index as f64,
+                        ..event.clone()
+                    })
+                    .collect(),
+            )
+        };
+        let mut dataset = make(6);
+        let shared = dataset.clone();
+        dataset.append(make(4));
+        assert_eq!(dataset.len(), 10);
+        assert_eq!(shared.len(), 6);
+        assert!(dataset
+            .events
+            .iter()
+            .enumerate()
+            .all(|(i, event)| event.index == i));
+        assert_eq!(dataset.events[7].weight, 1.0);
+
+        assert!(dataset.view(3..11).is_err());
+        assert!(dataset.view_mask(&[true]).is_err());
+        assert!(dataset.view_indices(vec![10]).is_err());
+        let view = dataset.view(2..8)?;
+        assert!(std::sync::Arc::ptr_eq(
+            &view.parent().events,
+            &dataset.events
+        ));
+        assert_eq!(view.len(), 6);
+        assert_eq!(view.indices(), vec![2, 3, 4, 5, 6, 7]);
+        assert_eq!(view.get(0).map(|e| e.index), Some(2));
+        assert!(view.get(6).is_none());
+        let inner = view.view(1..3)?;
+        assert_eq!(inner.indices(), vec![3, 4]);
+        let mask: Vec<bool> = (0..10).map(|i| i % 3 == 0).collect();
+        let masked = dataset.view_mask(&mask)?;
+        assert_eq!(masked.indices(), vec![0, 3, 6, 9]);
+        assert_eq!(masked.weights(), vec![0.0, 3.0, 0.0, 3.0]);
+        assert_eq!(masked.view(1..3)?.indices(), vec![3, 6]);
+        let copied = masked.to_dataset();
+        assert_eq!(copied.weights(), masked.weights());
+        assert_eq!(copied.events[3].index, 3);
+
+        let (bins, underflow, overflow) = dataset.get_binned_views(|e| e.weight, (1.0, 4.0), 3);
+        let (bin_indices, underflow_indices, overflow_indices) =
+            dataset.get_binned_indices(|e| e.weight, (1.0, 4.0), 3);
+        assert_eq!(underflow.indices(), underflow_indices);
+        assert_eq!(overflow.indices(), overflow_indices);
+        for (view, indices) in bins.iter().zip(bin_indices) {
+            assert_eq!(view.indices(), indices);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared_dataset_storage() -> Result<(), RustitudeError> {
+        let event = generate_test_event_f64();
+        let mut dataset = Dataset::new(vec![event.clone(), Event { index: 1, ..event }]);
+        let model = model!(scalar("a"));
+        let m1 = Manager::new(&model, &dataset)?;
+        let m2 = Manager::new(&model, &dataset)?;
+        assert!(std::sync::Arc::ptr_eq(&m1.dataset.events, &dataset.events));
+        assert!(std::sync::Arc::ptr_eq(&m2.dataset.events, &dataset.events));
+        // Reindexing an already indexed dataset does not copy shared events
+        dataset.reindex();
+        assert!(std::sync::Arc::ptr_eq(&m1.dataset.events, &dataset.events));
+        let mut shuffled = Dataset::new(dataset.events.iter().rev().cloned().collect());
+        let copy = shuffled.clone();
+        shuffled.reindex();
+        assert!(!std::sync::Arc::ptr_eq(&copy.events, &shuffled.events));
+        assert_eq!(copy.events[0].index, 1);
+        assert_eq!(shuffled.events[0].index, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_polarization_table() -> Result<(), RustitudeError> {
+        assert!(PolarizationTable::new(vec![RunPeriod::new(20, 10, 0.0, 0.4)]).is_err());
+        assert!(PolarizationTable::new(vec![
+            RunPeriod::new(10, 20, 0.0, 0.4),
+            RunPeriod::new(20, 30, 0.0, 0.4),
+        ])
+        .is_err());
+        let table = PolarizationTable::new(vec![
+            RunPeriod::new(30, 39, std::f64::consts::FRAC_PI_2, 0.3),
+            RunPeriod::new(10, 19, 0.0, 0.4),
+        ])?;
+        assert_eq!(table.periods()[0].first_run, 10);
+        assert!(table.lookup(25).is_none());
+        assert!(table.lookup(40).is_none());
+        assert_eq!(table.lookup(39).map(|p| p.first_run), Some(30));
+        let event = generate_test_event_f64();
+        let dataset = Dataset::new(vec![event.clone(), Event { index: 1, ..event }]);
+        assert!(dataset
+            .clone()
+            .with_polarization_table(&[10], &table)
+            .is_err());
+        assert!(dataset
+            .clone()
+            .with_polarization_table(&[10, 25], &table)
+            .is_err());
+        let dataset = dataset
+            .with_layout(DatasetLayout::Columns)?
+            .with_polarization_table(&[15, 30], &table)?;
+        assert_is_close!(dataset.events[0].eps.x, 0.4, f64);
+        assert_is_close!(dataset.events[0].eps.y, 0.0, f64);
+        assert_is_close!(dataset.events[1].eps.y, 0.3, f64);
+        assert!(dataset.events[1].eps.x.abs() < 1e-12);
+        assert_eq!(
+            dataset.columns().map(|columns| columns.eps[1][1]),
+            Some(dataset.events[1].eps.y)
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_distributed_likelihood() -> Result<(), RustitudeError> {
+        use std::sync::{Arc, Barrier, Mutex};
+        struct ThreadCommunicator {
+            rank: usize,
+            size: usize,
+            barrier: Arc<Barrier>,
+            buffer: Arc<Mutex<Vec<f64>>>,
+        }
+        impl Communicator<f64> for ThreadCommunicator {
+            fn rank(&self) -> usize {
+                self.rank
+            }
+            fn size(&self) -> usize {
+                self.size
+            }
+            fn broadcast(&self, values: &mut [f64]) {
+                if self.rank == 0 {
+                    *self.buffer.lock().unwrap() = values.to_vec();
+                }
+                self.barrier.wait();
+                values.copy_from_slice(&self.buffer.lock().unwrap());
+                self.barrier.wait();
+            }
+            fn all_reduce_sum(&self, values: &mut [f64]) {
+                if self.rank == 0 {
+                    *self.buffer.lock().unwrap() = vec![0.0; values.len()];
+                }
+                self.barrier.wait();
+                for (total, value) in self.buffer.lock().unwrap().iter_mut().zip(values.iter()) {
+                    *total += value;
+                }
+                self.barrier.wait();
+                values.copy_from_slice(&self.buffer.lock().unwrap());
+                self.barrier.wait();
+            }
+        }
+        let dataset = generate_test_dataset_f64();
+        let model = model!(piecewise_m("p", 4, (0.5, 2.0)) * cscalar("c"), scalar("s"));
+        let ell = |data: &Dataset<f64>, mc: &Dataset<f64>| -> Result<_, RustitudeError> {
+            let mut ell = ExtendedLogLikelihood::new(
+                Manager::new(&model, data)?,
+                Manager::new(&model, &mc.shard(0, 1))?,
+            );
+            ell.fix("c", "imag", 0.0)?;
+            Ok(ell)
+        };
+        let mc = dataset.shard(1, 2) + dataset.shard(0, 2);
+        let full = ell(&dataset, &mc)?;
+        let parameters = [1.0, 0.5, 2.0, 0.0, 3.0, -1.0, 4.0, 0.0, 1.5, 0.7];
+        let single = DistributedLikelihood::new(full.clone(), SingleProcess)?;
+        assert_is_close!(
+            single.evaluate(&parameters)?,
+            full.evaluate(&parameters)?,
+            f64
+        );
+        // Any number of ranks with uneven shards reproduce the full likelihood and gradient
+        for size in 2..=4 {
+            let barrier = Arc::new(Barrier::new(size));
+            let buffer = Arc::new(Mutex::new(Vec::new()));
+            std::thread::scope(|scope| -> Result<(), RustitudeError> {
+                let mut workers = Vec::new();
+                let mut root = None;
+                for rank in 0..size {
+                    let communicator = ThreadCommunicator {
+                        rank,
+                        size,
+                        barrier: barrier.clone(),
+                        buffer: buffer.clone(),
+                    };
+                    let local = DistributedLikelihood::new(
+                        ell(&dataset.shard(rank, size), &mc.shard(rank, size))?,
+                        communicator,
+                    )?;
+                    if local.is_root() {
+                        root = Some(local);
+                    } else {
+                        workers.push(scope.spawn(move || local.serve()));
+                    }
+                }
+                let root = root.unwrap();
+                assert_is_close!(
+                    root.evaluate(&parameters)?,
+                    full.evaluate(&parameters)?,
+                    1e-10,
+                    f64
+                );
+                for (g, expected) in root
+                    .gradient(&parameters)?
+                    .iter()
+                    .zip(full.gradient(&parameters)?)
+                {
+                    assert_is_close!(*g, expected, 1e-10, f64);
+                }
+                root.finish();
+                for worker in workers {
+                    worker.join().unwrap()?;
+                }
+                Ok(())
+            })?;
+            assert_eq!(
+                (0..size)
+                    .map(|rank| dataset.shard(rank, size).len())
+                    .sum::<usize>(),
+                dataset.len()
+            );
+        }
+        // A NaN likelihood is returned as a value, while a failure on another rank is an error
+        let nan_parameters = [f64::NAN; 10];
+        for fails in [false, true] {
+            let barrier = Arc::new(Barrier::new(2));
+            let buffer = Arc::new(Mutex::new(Vec::new()));
+            let communicator = |rank| ThreadCommunicator {
+                rank,
+                size: 2,
+                barrier: barrier.clone(),
+                buffer: buffer.clone(),
+            };
+            let root = DistributedLikelihood::new(
+                ell(&dataset.shard(0, 2), &mc.shard(0, 2))?,
+                communicator(0),
+            )?;
+            let mut worker = ell(&dataset.shard(1, 2), &mc.shard(1, 2))?;
+            if fails {
+                worker.data_manager = worker
+                    .data_manager
+                    .with_intensity_policy(Some(IntensityPolicy::Error));
+            }
+            let worker = DistributedLikelihood::new(worker, communicator(1))?;
+            std::thread::scope(|scope| {
+                let worker = scope.spawn(move || worker.serve());
+                let result = root.evaluate(&nan_parameters);
+                root.finish();
+                if fails {
+                    assert!(result.unwrap_err().to_string().contains("1 other rank"));
+                    assert!(worker.join().unwrap().is_err());
+                } else {
+                    assert!(result.unwrap().is_nan());
+                    assert!(worker.join().unwrap().is_ok());
+                }
+            });
+        }
+        Ok(())
+    }
+    /// Run with `mpirun -n <size> cargo test --features mpi test_mpi_likelihood` to split the
+    /// dataset over several processes.
+    #[cfg(feature = "mpi")]
+    #[test]
+    fn test_mpi_likelihood() -> Result<(), RustitudeError> {
+        let communicator = MpiCommunicator::initialize()?;
+        let (rank, size) = (communicator.rank(), communicator.size());
+        let dataset = generate_test_dataset_f64();
+        let model = model!(piecewise_m("p", 4, (0.5, 2.0)) * cscalar("c"), scalar("s"));
+        let ell = |data: &Dataset<f64>, mc: &Dataset<f64>| -> Result<_, RustitudeError> {
+            let mut ell = ExtendedLogLikelihood::new(
+                Manager::new(&model, data)?,
+                Manager::new(&model, &mc.shard(0, 1))?,
+            );
+            ell.fix("c", "imag", 0.0)?;
+            Ok(ell)
+        };
+        let mc = dataset.shard(1, 2) + dataset.shard(0, 2);
+        let full = ell(&dataset, &mc)?;
+        let parameters = [1.0, 0.5, 2.0, 0.0, 3.0, -1.0, 4.0, 0.0, 1.5, 0.7];
+        let local: DistributedManager<f64> = DistributedLikelihood::new(
+            ell(&dataset.shard(rank, size), &mc.shard(rank, size))?,
+            communicator,
+        )?;
+        if !local.is_root() {
+            return local.serve();
+        }
+        assert_is_close!(
+            local.evaluate(&parameters)?,
+            full.evaluate(&parameters)?,
+            1e-10,
+            f64
+        );
+        for (g, expected) in local
+            .gradient(&parameters)?
+            .iter()
+            .zip(full.gradient(&parameters)?)
+        {
+            assert_is_close!(*g, expected, 1e-10, f64);
+        }
+        local.finish();
+        Ok(())
+    }
+    #[test]
+    fn test_evaluate_many() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let a = scalar("a");
+        let b = cscalar("b");
+        let model = model!(a * b);
+        let manager = Manager::new(&model, &dataset)?;
+        let grid = vec![
+            vec![1.0, 2.0, 0.5],
+            vec![0.5, -1.0, 3.0],
+            vec![2.0, 0.0, 0.0],
+        ];
+        let many = manager.evaluate_many(&grid)?;
+        let par_many = manager.par_evaluate_many(&grid)?;
+        assert_eq!(many.len(), grid.len());
+        for ((parameters, values), par_values) in grid.iter().zip(&many).zip(&par_many) {
+            let expected = manager.evaluate(parameters)?;
+            assert_eq!(values, &expected);
+            assert_eq!(par_values, &expected);
+        }
+        assert!(manager.par_evaluate_many(&[])?.is_empty());
+        Ok(())
+    }
+    #[test]
+    fn test_profiling() -> Result<(), RustitudeError> {
+        use rustitude_core::profiling::{self, Stage};
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("profiled a") * scalar("profiled b"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        profiling::enable();
+        ell.evaluate(&[1.0, 2.0])?;
+        ell.par_evaluate(&[1.0, 2.0])?;
+        let report = profiling::report();
+        // Other tests may record timings while this one runs, so only lower bounds are checked
+        for name in ["profiled a", "profiled b"] {
+            let entry = report.get(Stage::Calculate, name).unwrap();
+            // Scalars are event-independent, so they are calculated once per Manager evaluation
+            assert_eq!(entry.calls, 4);
+            assert!(entry.mean() <= entry.total);
+        }
+        assert!(report.get(Stage::Precalculate, "profiled a").is_none());
+        assert!(
+            report
+                .get(Stage::Reduction, "ExtendedLogLikelihood")
+                .unwrap()
+                .calls
+                >= 2
+        );
+        assert!(report.dominant(Stage::Calculate).is_some());
+        assert!(report.to_string().contains("profiled a"));
+        Ok(())
+    }
+    #[test]
+    fn test_thread_pools() -> Result<(), RustitudeError> {
+        use rayon::prelude::*;
+        let dataset = generate_test_dataset_f64();
+        let model = model!(piecewise_m("p", 4, (0.5, 2.0)) * cscalar("c"), scalar("s"));
+        let manager = Manager::new(&model, &dataset)?;
+        let pooled = manager.clone().with_threads(2)?;
+        assert_eq!(pooled.num_threads(), 2);
+        let parameters = [1.0, 0.5, 2.0, 0.0, 3.0, -1.0, 4.0, 0.0, 1.5, 0.5, 0.7];
+        assert_eq!(
+            pooled.par_evaluate(&parameters)?,
+            manager.evaluate(&parameters)?
+        );
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager).with_threads(1)?;
+        assert_eq!(ell.num_threads(), 1);
+        assert_eq!(ell.mc_manager.num_threads(), 1);
+        let expected = ell.evaluate(&parameters)?;
+        assert_is_close!(ell.par_evaluate(&parameters)?, expected, 1e-12, f64);
+        // Parallel calls from inside another pool run on the likelihood's own pool
+        let outer = rayon::ThreadPoolBuilder::new().num_threads(3).build()?;
+        let values = outer.install(|| {
+            (0..4)
+                .into_par_iter()
+                .map(|_| ell.par_evaluate(&parameters))
+                .collect::<Result<Vec<f64>, RustitudeError>>()
+        })?;
+        for value in values {
+            assert_is_close!(value, expected, 1e-12, f64);
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_acceptance() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(piecewise_m("p", 4, (0.5, 2.0)), cscalar("c"));
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let parameters = [1.0, 0.5, 2.0, 0.0, 3.0, -1.0, 4.0, 0.0, 1.5, 0.5];
+        let standard = ell.evaluate(&parameters)?;
+        assert!(ell.set_acceptance(Some(vec![0.5; 3])).is_err());
+        assert_is_close!(ell.average_acceptance(&parameters)?, 1.0, f64);
+        ell.set_acceptance(Some(vec![1.0; dataset.len()]))?;
+        assert_is_close!(ell.evaluate(&parameters)?, standard, f64);
+        // The acceptance only enters the normalization integral, not the total weight
+        ell.set_acceptance_fn(|event| 0.5 + 0.1 * (event.index % 4) as f64);
+        let value = ell.evaluate(&parameters)?;
+        assert!((value - standard).abs() > 1e-6);
+        assert_is_close!(value, ell.par_evaluate(&parameters)?, 1e-10, f64);
+        let average = ell.average_acceptance(&parameters)?;
+        assert!(average > 0.5 && average < 0.8);
+        let (fused, gradient) = ell.evaluate_with_gradient(&parameters)?;
+        assert_is_close!(fused, value, 1e-10, f64);
+        for (i, g) in gradient.iter().enumerate() {
+            let h = 1e-6;
+            let mut up = parameters;
+            up[i] += h;
+            let mut down = parameters;
+            down[i] -= h;
+            let numeric = (ell.evaluate(&up)? - ell.evaluate(&down)?) / (2.0 * h);
+            assert!((g - numeric).abs() < 1e-4 * (1.0 + numeric.abs()));
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_generated_efficiency() -> Result<(), RustitudeError> {
+        let generated = generate_test_dataset_f64();
+        let accepted = Dataset::new(
+            generated
+                .events
+                .iter()
+                .step_by(2)
+                .enumerate()
+                .map(|(i, event)| {
+                    let mut event = event.clone();
+                    event.index = i;
+                    event
+                })
+                .collect(),
+        );
+        let model = model!(piecewise_m("p", 4, (0.5, 2.0)), cscalar("c"));
+        let mut ell = ExtendedLogLikelihood::new(
+            Manager::new(&model, &generated)?,
+            Manager::new(&model, &accepted)?,
+        );
+        let parameters = [1.0, 0.5, 2.0, 0.0, 3.0, -1.0, 4.0, 0.0, 1.5, 0.5];
+        assert_is_close!(
+            ell.efficiency(&parameters)?,
+            ell.average_acceptance(&parameters)?,
+            f64
+        );
+        ell.set_generated(Some(&generated))?;
+        let total = |dataset: &Dataset<f64>| -> Result<f64, RustitudeError> {
+            Ok(Manager::new(&model, dataset)?
+                .evaluate(&parameters)?
+                .iter()
+                .zip(dataset.weights())
+                .map(|(i, w)| i * w)
+                .sum())
+        };
+        let expected = total(&accepted)? / total(&generated)?;
+        assert!(expected > 0.0 && expected < 1.0);
+        assert_is_close!(ell.efficiency(&parameters)?, expected, f64);
+        assert_is_close!(ell.par_efficiency(&parameters)?, expected, 1e-10, f64);
+        let n_data: f64 = generated.weights().iter().sum();
+        assert_is_close!(ell.corrected_yield(&parameters)?, n_data / expected, f64);
+        let waves = ell.wave_efficiencies(&parameters, &[("all", vec!["p", "c"])])?;
+        assert_eq!(
+            waves
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["p", "c", "all"]
+        );
+        assert_is_close!(waves[2].1, expected, f64);
+        // A constant wave is accepted like the events themselves
+        let n_accepted: f64 = accepted.weights().iter().sum();
+        assert_is_close!(waves[1].1, n_accepted / n_data, f64);
+        let par_waves = ell.par_wave_efficiencies(&parameters, &[("all", vec!["p", "c"])])?;
+        for ((_, a), (_, b)) in waves.iter().zip(&par_waves) {
+            assert_is_close!(*a, *b, 1e-10, f64);
+        }
+        assert!(ell
+            .wave_efficiencies(&parameters, &[("bad", vec!["nope"])])
+            .is_err());
+        // The generated Monte-Carlo follows the activation of the likelihood
+        ell.isolate(vec!["p"])?;
+        assert_is_close!(ell.efficiency(&parameters)?, waves[0].1, f64);
+        ell.activate_all();
+        assert_is_close!(ell.efficiency(&parameters)?, expected, f64);
+        ell.set_generated(None)?;
+        assert_is_close!(ell.efficiency(&parameters)?, 1.0, f64);
+        Ok(())
+    }
+    #[test]
+    fn test_parameter_index_stability() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let mut model = model!(
+            (scalar("a") + cscalar("b")) * pcscalar("c"),
+            piecewise_m("d", 4, (1.0, 2.0)).real(),
+        );
+        model.fix("a", "value", 2.0)?;
+        model.constrain("c", "mag", "d", "bin 0 re")?;
+        let triples = |model: &Model<f64>| {
+            model
+                .parameters
+                .iter()
+                .map(|p| (p.amplitude.clone(), p.name.clone(), p.index))
+                .collect::<Vec<_>>()
+        };
+        let expected = triples(&model);
+        let n_free = model.get_n_free();
+        let parameters: Vec<f64> = (0..n_free).map(|i| i as f64 + 1.0).collect();
+        let value = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        for _ in 0..3 {
+            model.deactivate("b")?;
+            model.isolate(vec!["c", "d"])?;
+            model.deactivate_all();
+            model.activate("a")?;
+            assert_eq!(triples(&model), expected);
+            assert_eq!(model.get_n_free(), n_free);
+            model.activate_all();
+        }
+        assert_eq!(
+            Manager::new(&model, &dataset)?.evaluate(&parameters)?,
+            value
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_restore_fit_result() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a"), cscalar("b"));
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let best = vec![1.5, 0.5, -0.5];
+        let fx = ell.evaluate(&best)?;
+        let result: FitResult<f64> =
+            FitResult::from_json(&FitResult::new(&ell, &best, fx, None)?.to_json()?)?;
+        assert_eq!(
+            result.indices,
+            vec![
+                ("a".to_string(), "value".to_string(), 0),
+                ("b".to_string(), "real".to_string(), 1),
+                ("b".to_string(), "imag".to_string(), 2),
+            ]
+        );
+        assert_eq!(result.restore(&mut ell)?, best);
+
+        let mut fixed = ell.clone();
+        fixed.fix("b", "imag", 0.0)?;
+        assert!(matches!(
+            result.restore(&mut fixed),
+            Err(RustitudeError::ParameterNotFoundError(_))
+        ));
+        let reordered = model!(cscalar("b"), scalar("a"));
+        let manager = Manager::new(&reordered, &dataset)?;
+        let mut reordered = ExtendedLogLikelihood::new(manager.clone(), manager);
+        assert!(matches!(
+            result.restore(&mut reordered),
+            Err(RustitudeError::InvalidParameterValue(_))
+        ));
+        assert_eq!(reordered.get_initial(), vec![1.0; 3]);
+        assert_eq!(result.warm_start(&mut reordered)?, vec![0.5, -0.5, 1.5]);
+        let extended = model!(scalar("a"), cscalar("b"), scalar("e"));
+        let manager = Manager::new(&extended, &dataset)?;
+        let mut extended = ExtendedLogLikelihood::new(manager.clone(), manager);
+        assert!(result.restore(&mut extended).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_serialization() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let mut model = model!(
+            (scalar("a") + cscalar("b")) * pcscalar("c"),
+            piecewise_m("d", 4, (1.0, 2.0)).real(),
+        );
+        model.fix("a", "value", 2.0)?;
+        model.constrain("c", "mag", "d", "bin 0 re")?;
+        model.set_bounds("b", "real", (-1.0, 1.0))?;
+        model.deactivate("b")?;
+        let registry = NodeRegistry::new();
+        let from_json = Model::from_json(&model.to_json()?, &registry)?;
+        let from_yaml = Model::from_yaml(&model.to_yaml()?, &registry)?;
+        let parameters: Vec<f64> = (0..model.get_n_free()).map(|i| i as f64 + 1.0).collect();
+        let expected = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        for reloaded in [from_json, from_yaml] {
+            assert_eq!(reloaded.get_n_free(), model.get_n_free());
+            assert_eq!(reloaded.get_bounds(), model.get_bounds());
+            assert!(!reloaded.get_amplitude("b")?.active);
+            let result = Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?;
+            for (r, e) in result.iter().zip(&expected) {
+                assert_is_close!(*r, *e, f64);
+            }
+        }
+        Ok(())
+    }
+    #[cfg(feature = "oxyroot")]
+    #[test]
+    fn test_root_rntuple_error() -> Result<(), RustitudeError> {
+        use oxyroot::{RootFile, WriterTree};
+        let path =
+            std::env::temp_dir().join(format!("rustitude_rntuple_{}.root", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut file = RootFile::create(path).unwrap();
+        let mut tree = WriterTree::new("kin_rntuple");
+        tree.new_branch("Weight", [1.0_f32, 2.0].into_iter());
+        tree.write(&mut file).unwrap();
+        file.close().unwrap();
+        drop(file);
+        // oxyroot cannot write an RNTuple, so the key of the tree is renamed in place: the class
+        // and name strings of "ROOT::RNTuple" "kin" have the same length as "TTree" "kin_rntuple"
+        let mut bytes = std::fs::read(path).unwrap();
+        let from = b"\x05TTree\x0bkin_rntuple";
+        let to = b"\x0dROOT::RNTuple\x03kin";
+        let mut renamed = 0;
+        for i in 0..bytes.len() - from.len() {
+            if bytes[i..].starts_with(from) {
+                bytes[i..i + to.len()].copy_from_slice(to);
+                renamed += 1;
+            }
+        }
+        assert!(renamed > 0);
+        std::fs::write(path, bytes).unwrap();
+        let err = Dataset::<f64>::from_root(path, ReadMethod::Standard).unwrap_err();
+        std::fs::remove_file(path).unwrap();
+        assert!(err.to_string().contains("is an RNTuple"));
+        assert!(err.to_string().contains("rustitude.open"));
+        Ok(())
+    }
+    #[test]
+    fn test_registry_round_trip() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let mut model = model!(
+            scalar("scalar") * cscalar("cscalar") * pcscalar("pcscalar"),
+            BreitWigner::new(&[0], &[1], 2)?.named("bw"),
+            Gaussian::new(&[0, 1])?.named("gaussian"),
+            Voigtian::new(&[0, 1])?.named("voigtian"),
+            Flatte::new(&[0], &[1], 1, [0.14, 0.14], [0.49, 0.49])?.named("flatte"),
+            KMatrix::new(&[0], &[1], 0, KMatrixConfig::f0())?
+                .with_non_resonant(&[0])?
+                .named("kmatrix"),
+            piecewise_m("m", 2, (0.5, 2.0)),
+            piecewise_t("t", 2, (-5.0, 0.0)),
+            piecewise_m_t("mt", (2, 2), ((0.5, 2.0), (-5.0, 0.0))),
+            piecewise_costheta("costheta", 2, (-1.0, 1.0)),
+            piecewise_beam_energy("e", 2, (0.0, 20.0)),
+            polynomial_m("polynomial", 2, PolynomialBasis::Chebyshev(0.5, 2.0))?,
+            spline_m(
+                "spline",
+                vec![0.5, 1.0, 2.0],
+                SplineKind::Cubic,
+                KnotForm::Polar
+            )?,
+            template_hist_m("hist", vec![0.5, 1.0, 2.0], vec![1.0, 2.0])?,
+            template_kde_m("kde", vec![1.0, 1.5], Some(vec![1.0, 2.0]), 0.2)?,
+        );
+        model.add_incoherent_sum(
+            "bkg",
+            &[Box::new(scalar("flat")), Box::new(scalar("slope"))],
+        )?;
+        let registry = NodeRegistry::new();
+        let spec = model.to_spec()?;
+        let mut kinds: Vec<&str> = spec
+            .amplitudes
+            .iter()
+            .map(|amplitude| amplitude.node.kind.as_str())
+            .collect();
+        kinds.sort_unstable();
+        kinds.dedup();
+        assert_eq!(kinds, registry.kinds());
+        let parameters: Vec<f64> = (0..model.get_n_free())
+            .map(|i| 0.1f64.mul_add(i as f64, 0.5))
+            .collect();
+        let expected = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        for reloaded in [
+            Model::from_json(&model.to_json()?, &registry)?,
+            Model::from_yaml(&model.to_yaml()?, &registry)?,
+        ] {
+            let reloaded_spec = reloaded.to_spec()?;
+            assert_eq!(reloaded_spec.amplitudes, spec.amplitudes);
+            assert_eq!(reloaded_spec.cohsums, spec.cohsums);
+            assert_eq!(reloaded.get_n_free(), model.get_n_free());
+            let result = Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?;
+            for (r, e) in result.iter().zip(&expected) {
+                assert_is_close!(*r, *e, f64);
+            }
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_precision_conversion() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let errors = vec![0.5; dataset.len()];
+        let dataset = dataset
+            .with_layout(DatasetLayout::Columns)?
+            .with_weight_covariance(WeightCovariance::from_errors(&errors))?;
+        let dataset_32 = dataset.to_f32();
+        assert_eq!(dataset_32.len(), dataset.len());
+        assert_eq!(dataset_32.layout(), DatasetLayout::Columns);
+        assert_eq!(dataset_32.weight_blocks()[0].1, vec![vec![0.25_f32]]);
+        let roundtrip = dataset_32.to_f64();
+        for (a, b) in roundtrip.events.iter().zip(dataset.events.iter()) {
+            assert_eq!(a.index, b.index);
+            assert_is_close!(a.daughter_p4s[1].e(), b.daughter_p4s[1].e(), 1e-6, f64);
+            assert_is_close!(a.eps.x, b.eps.x, 1e-6, f64);
+        }
+
+        let mut model = model!(scalar("a") * cscalar("b"), piecewise_m("c", 4, (1.0, 2.0)));
+        model.fix("a", "value", 2.0)?;
+        model.set_bounds("b", "real", (-1.0, 1.0))?;
+        let model_32 = model.to_f32(&NodeRegistry::new())?;
+        assert_eq!(model_32.get_n_free(), model.get_n_free());
+        assert_eq!(model_32.get_bounds()[0], (-1.0, 1.0));
+        let parameters: Vec<f64> = (0..model.get_n_free()).map(|i| i as f64 + 1.0).collect();
+        let parameters_32: Vec<f32> = parameters.iter().map(|p| *p as f32).collect();
+        let expected = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        let result = Manager::new(&model_32, &dataset_32)?.evaluate(&parameters_32)?;
+        for (r, e) in result.iter().zip(&expected) {
+            assert_is_close!(f64::from(*r), *e, 1e-5, f64);
+        }
+        let model_64 = model_32.to_f64(&NodeRegistry::new())?;
+        assert_eq!(model_64.get_bounds(), model.get_bounds());
+        assert_eq!(model_64.get_initial(), model.get_initial());
+        assert_eq!(model_64.get_parameter("a", "value")?.initial, 2.0);
+        Ok(())
+    }
+    #[test]
+    fn test_fit_result() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let mut model = model!(scalar("a") * cscalar("b"), cscalar("c"));
+        model.fix("a", "value", 2.0)?;
+        model.constrain("b", "imag", "c", "imag")?;
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let best = vec![1.0, 2.0, 3.0];
+        let fx = ell.evaluate(&best)?;
+        assert!(FitResult::new(&ell, &best[..2], fx, None).is_err());
+        assert!(FitResult::new(&ell, &best, fx, Some(vec![vec![1.0; 3]; 2])).is_err());
+        let covariance = vec![
+            vec![4.0, 0.0, 0.0],
+            vec![0.0, 9.0, 0.0],
+            vec![0.0, 0.0, 16.0],
+        ];
+        let result = FitResult::new(&ell, &best, fx, Some(covariance))?;
+        let reloaded: FitResult<f64> = FitResult::from_json(&result.to_json()?)?;
+        assert_eq!(reloaded.data, result.data);
+        assert_eq!(
+            reloaded.data.fingerprint,
+            format!("{:016x}", dataset.fingerprint())
+        );
+        assert_eq!(reloaded.best, best);
+        assert_eq!(reloaded.errors(), Some(vec![2.0, 3.0, 4.0]));
+        assert_eq!(reloaded.value("a", "value")?, 2.0);
+        assert_eq!(reloaded.value("c", "imag")?, reloaded.value("b", "imag")?);
+        assert_eq!(reloaded.error("a", "value")?, None);
+        assert_eq!(reloaded.error("c", "real")?, Some(4.0));
+        let names: Vec<(&str, &str)> = reloaded
+            .free_parameters()
+            .iter()
+            .map(|p| (p.amplitude.as_str(), p.name.as_str()))
+            .collect();
+        assert_eq!(names, vec![("b", "real"), ("b", "imag"), ("c", "real")]);
+        assert!(reloaded.model.is_some());
+        assert_eq!(reloaded.cohsums.len(), 2);
+        Ok(())
+    }
+    #[test]
+    fn test_template() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let masses: Vec<f64> = dataset
+            .events
+            .iter()
+            .map(|e| (e.daughter_p4s[0] + e.daughter_p4s[1]).m())
+            .collect();
+        let edges: Vec<f64> = (0..=20).map(|i| 0.5 * i as f64).collect();
+        let contents: Vec<f64> = (0..20).map(|i| i as f64 + 1.0).collect();
+        let model = model!(template_hist_m("bkg", edges.clone(), contents.clone())? * scalar("s"));
+        assert_eq!(model.get_n_free(), 1);
+        let result = Manager::new(&model, &dataset)?.evaluate(&[2.0])?;
+        for (r, m) in result.iter().zip(&masses) {
+            assert!(*m < 10.0);
+            assert_is_close!(*r, 4.0 * ((m / 0.5).floor() + 1.0), f64);
+        }
+        let (m0, h) = (1.2, 0.3);
+        let model = model!(template_kde_m("bkg", vec![m0], None, h)?);
+        let result = Manager::new(&model, &dataset)?.evaluate(&[])?;
+        for (r, m) in result.iter().zip(&masses) {
+            let expected =
+                f64::exp(-0.5 * ((m - m0) / h).powi(2)) / (h * std::f64::consts::TAU.sqrt());
+            assert_is_close!(*r, expected, f64);
+        }
+        let config = "amplitude t TemplateHistM edges=[0.0, 5.0, 10.0] contents=[1.0, 2.0]\nsum t";
+        let model = Model::from_config(config, &NodeRegistry::new())?;
+        let result = Manager::new(&model, &dataset)?.evaluate(&[])?;
+        for (r, m) in result.iter().zip(&masses) {
+            assert_is_close!(*r, if *m < 5.0 { 1.0 } else { 2.0 }, f64);
+        }
+        assert!(template_hist_m::<f64>("bkg", edges, vec![1.0]).is_err());
+        assert!(template_kde_m::<f64>("bkg", vec![], None, h).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_kde() -> Result<(), RustitudeError> {
+        let points = vec![vec![0.0], vec![1.0], vec![2.0], vec![3.0]];
+        let sigma = 1.25_f64.sqrt();
+        let scott = Kde::new(points.clone(), None, &Bandwidth::Scott)?;
+        assert_is_close!(scott.bandwidths()[0], sigma * 4.0_f64.powf(-0.2), f64);
+        let silverman = Kde::new(points.clone(), None, &Bandwidth::Silverman)?;
+        assert_is_close!(
+            silverman.bandwidths()[0],
+            sigma * (4.0 / 12.0_f64).powf(0.2),
+            f64
+        );
+        let kde = Kde::new(
+            vec![vec![0.0, 0.0]],
+            Some(vec![2.0]),
+            &Bandwidth::Fixed(vec![1.0, 2.0]),
+        )?;
+        assert_eq!(kde.dimension(), 2);
+        assert_is_close!(
+            kde.evaluate(&[1.0, 2.0])?,
+            f64::exp(-1.0) / (2.0 * std::f64::consts::TAU),
+            f64
+        );
+        assert!(kde.evaluate(&[1.0]).is_err());
+        assert!(Kde::new(points.clone(), Some(vec![-1.0; 4]), &Bandwidth::Scott).is_err());
+        assert!(Kde::new(vec![vec![1.0]; 3], None, &Bandwidth::Scott).is_err());
+        assert!(Kde::new(points, None, &Bandwidth::Fixed(vec![1.0, 1.0])).is_err());
+
+        let dataset = generate_test_dataset_f64();
+        let mass = |e: &Event<f64>| vec![(e.daughter_p4s[0] + e.daughter_p4s[1]).m()];
+        let kde = Kde::from_dataset(&dataset, mass, &Bandwidth::Scott)?;
+        let density = kde.evaluate_dataset(&dataset, mass)?;
+        let model = model!(Amplitude::new("bkg", Template::from_kde(kde, mass)));
+        let result = Manager::new(&model, &dataset)?.evaluate(&[])?;
+        for (r, d) in result.iter().zip(&density) {
+            assert_is_close!(*r, *d, f64);
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_ensemble() -> Result<(), RustitudeError> {
+        let names = vec!["x".to_string(), "y".to_string()];
+        let samples = vec![
+            vec![1.0, 2.0],
+            vec![2.0, 4.0],
+            vec![3.0, 6.0],
+            vec![10.0, 20.0],
+        ];
+        let ensemble = Ensemble::new(names.clone(), samples.clone(), None)?;
+        assert_eq!(ensemble.index("y")?, 1);
+        assert!(ensemble.index("z").is_err());
+        assert_is_close!(ensemble.mean()[0], 4.0, f64);
+        assert_is_close!(ensemble.covariance()[0][1], 25.0, f64);
+        assert_is_close!(ensemble.correlation()[0][1], 1.0, f64);
+        assert_is_close!(ensemble.effective_size(), 4.0, f64);
+        assert_eq!(ensemble.median(0)?, 2.0);
+        assert_eq!(ensemble.quantile(1, 0.75)?, 6.0);
+        assert_eq!(ensemble.quantile(0, 0.0)?, 1.0);
+        assert_eq!(ensemble.hpd(0, 0.75)?, (1.0, 3.0));
+        assert!(ensemble.quantile(0, 1.5).is_err());
+        assert!(ensemble.hpd(2, 0.5).is_err());
+
+        let weighted = Ensemble::new(
+            names.clone(),
+            samples.clone(),
+            Some(vec![1.0, 1.0, 1.0, 5.0]),
+        )?;
+        assert_eq!(weighted.median(0)?, 10.0);
+        assert_eq!(weighted.hpd(0, 0.6)?, (10.0, 10.0));
+        assert_is_close!(weighted.effective_size(), 64.0 / 28.0, f64);
+
+        let chained = ensemble.with_chains(vec![1, 0, 1, 0])?;
+        assert_eq!(
+            chained.chain_draws(0),
+            vec![vec![2.0, 10.0], vec![1.0, 3.0]]
+        );
+        assert!(chained.clone().with_chains(vec![0]).is_err());
+        let reloaded = Ensemble::<f64>::from_json(&chained.to_json()?)?;
+        assert_eq!(reloaded.chains, chained.chains);
+        assert_eq!(reloaded.samples, chained.samples);
+
+        assert!(Ensemble::<f64>::new(names.clone(), vec![], None).is_err());
+        assert!(Ensemble::new(names.clone(), vec![vec![1.0]], None).is_err());
+        assert!(Ensemble::new(names, samples, Some(vec![-1.0, 1.0, 1.0, 1.0])).is_err());
+
+        let dataset = generate_test_dataset_f64();
+        let model = model!(cscalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let results = [vec![1.0, 2.0], vec![3.0, 4.0]]
+            .iter()
+            .map(|best| FitResult::new(&ell, best, ell.evaluate(best)?, None))
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        let ensemble = Ensemble::from_fit_results(&results)?;
+        assert_eq!(ensemble.names, vec!["a::real", "a::imag"]);
+        assert_eq!(ensemble.mean(), vec![2.0, 3.0]);
+        Ok(())
+    }
+    #[test]
+    fn test_diagnostics() -> Result<(), RustitudeError> {
+        assert_is_close!(chi2_sf(3.841_458_820_694_124, 1), 0.05, f64);
+        assert_is_close!(chi2_sf(2.0, 2), f64::exp(-1.0), f64);
+        assert_is_close!(chi2_sf(1.0, 4), 1.5 * f64::exp(-0.5), f64);
+        assert_is_close!(chi2_sf(10.0, 4), 6.0 * f64::exp(-5.0), f64);
+        assert_eq!(chi2_sf(0.0, 3), 1.0);
+
+        let chi2 = BinnedChiSquare::new(
+            vec![0.0, 1.0, 2.0, 3.0],
+            &[0.5, 1.5, 1.5, 5.0],
+            Some(&[1.0, 2.0, 2.0, 1.0]),
+            &[0.5, 0.5, 1.5, -1.0],
+            None,
+        )?;
+        assert_eq!(chi2.data, vec![1.0, 4.0, 0.0]);
+        assert_eq!(chi2.mc, vec![2.0, 1.0, 0.0]);
+        assert_eq!(chi2.ndf, 2);
+        assert_is_close!(chi2.chi2, 1.0 / 3.0 + 9.0 / 9.0, f64);
+        assert_is_close!(chi2.reduced(), 2.0 / 3.0, f64);
+        assert!(BinnedChiSquare::new(vec![1.0, 0.0], &[0.5], None, &[0.5], None).is_err());
+        assert!(BinnedChiSquare::new(vec![0.0, 1.0], &[0.5], Some(&[]), &[0.5], None).is_err());
+
+        let dataset = generate_test_dataset_f64();
+        let mass = |e: &Event<f64>| (e.daughter_p4s[0] + e.daughter_p4s[1]).m();
+        let model = model!(scalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let edges: Vec<f64> = (0..=20).map(|i| 0.5 + 0.1 * f64::from(i)).collect();
+        let chi2 = binned_chi2(&ell, &[1.0], &dataset, mass, edges)?;
+        assert!(chi2.chi2.abs() < 1e-10);
+        assert!(chi2.ndf > 0);
+
+        let null = FitResult::new(&ell, &[1.0], ell.evaluate(&[1.0])?, None)?;
+        let model = model!(scalar("a"), cscalar("b"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let best = [1.0, 0.5, 0.5];
+        let alternative = FitResult::new(&ell, &best, ell.evaluate(&best)?, None)?;
+        assert_is_close!(aic(&null), null.fx + 2.0, f64);
+        assert_is_close!(
+            bic(&alternative),
+            alternative.fx + 3.0 * (dataset.len() as f64).ln(),
+            f64
+        );
+        let test = likelihood_ratio_test(&null, &alternative)?;
+        assert_eq!(test.ndf, 2);
+        assert_is_close!(test.statistic, null.fx - alternative.fx, f64);
+        assert_is_close!(test.p_value, chi2_sf(test.statistic, 2), f64);
+        assert!(likelihood_ratio_test(&alternative, &null).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_histogram() -> Result<(), RustitudeError> {
+        let hist = Histogram::uniform(
+            2,
+            (0.0, 2.0),
+            &[0.5, 1.5, 1.5, 2.0, f64::NAN],
+            Some(&[1.0, 2.0, -1.0, 1.0, 1.0]),
+        )?;
+        assert_eq!(hist.edges, vec![0.0, 1.0, 2.0]);
+        assert_eq!(hist.centers(), vec![0.5, 1.5]);
+        assert_eq!(hist.contents, vec![1.0, 1.0]);
+        assert_eq!(hist.errors(), vec![1.0, 5.0f64.sqrt()]);
+        assert_eq!(hist.total(), 2.0);
+        assert!(Histogram::new(vec![0.0], &[0.5], None).is_err());
+        assert!(Histogram::uniform(2, (1.0, 1.0), &[0.5], None).is_err());
+
+        // The model prediction is the Monte-Carlo weighted by the intensity
+        let dataset = generate_test_dataset_f64();
+        let mass = |e: &Event<f64>| (e.daughter_p4s[0] + e.daughter_p4s[1]).m();
+        let manager = Manager::new(&model!(scalar("a")), &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let edges: Vec<f64> = (0..=20).map(|i| 0.5 + 0.1 * f64::from(i)).collect();
+        let data = Histogram::from_dataset(edges.clone(), &dataset, mass, None)?;
+        let intensity = ell.intensity(&[1.0], &dataset)?;
+        let fit = Histogram::from_dataset(edges, &dataset, mass, Some(&intensity))?;
+        assert_eq!(data.n_bins(), 20);
+        for (d, f) in data.contents.iter().zip(&fit.contents) {
+            assert_is_close!(*d, *f, f64);
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_precalculation_cache() -> Result<(), RustitudeError> {
+        #[derive(Clone)]
+        struct Unnamed;
+        impl Node<f64> for Unnamed {
+            fn calculate(
+                &self,
+                _parameters: &[f64],
+                event: &Event<f64>,
+            ) -> Result<Complex<f64>, RustitudeError> {
+                Ok(Complex::new(event.beam_p4.e(), 0.0))
+            }
+        }
+        let dataset = generate_test_dataset_f64();
+        let fingerprint = dataset.fingerprint();
+        let cache = PrecalculationCache::<f64>::new();
+        assert!(!cache.is_enabled());
+        let mut first = piecewise_m("p", 4, (0.5, 2.0));
+        cache.precalculate(&mut first, fingerprint, &dataset)?;
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (0, 1, 1));
+        let mut second = piecewise_m("p", 4, (0.5, 2.0));
+        cache.precalculate(&mut second, fingerprint, &dataset)?;
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 1, 1));
+        let parameters = [1.0, 0.0, 2.0, 0.0, 3.0, 0.0, 4.0, 0.0];
+        for event in dataset.events.iter() {
+            assert_eq!(
+                first.calculate(&parameters, event)?,
+                second.calculate(&parameters, event)?
+            );
+        }
+        cache.precalculate(&mut piecewise_m("p", 5, (0.5, 2.0)), fingerprint, &dataset)?;
+        cache.precalculate(&mut piecewise_m("q", 4, (0.5, 2.0)), fingerprint, &dataset)?;
+        cache.precalculate(&mut Unnamed.named("u"), fingerprint, &dataset)?;
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 3, 3));
+        cache.clear();
+        assert!(cache.is_empty());
+
+        let model = model!(piecewise_m("p", 4, (0.5, 2.0)), cscalar("c"));
+        let parameters = [1.0, 0.5, 2.0, 0.0, 3.0, -1.0, 4.0, 0.0, 1.5, 0.5];
+        let expected = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        let global = PrecalculationCache::<f64>::global();
+        global.set_enabled(true);
+        let data_manager = Manager::new(&model, &dataset)?;
+        let mc_manager = Manager::new(&model, &dataset)?;
+        global.set_enabled(false);
+        assert!(global.len() >= 2);
+        assert!(global.hits() >= 2);
+        assert_eq!(data_manager.evaluate(&parameters)?, expected);
+        assert_eq!(mc_manager.evaluate(&parameters)?, expected);
+        Ok(())
+    }
+    #[test]
+    fn test_intensity_floor() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(piecewise_m("p", 2, (0.5, 2.0)));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager.clone());
+        let parameters = [1.5, 0.5, 0.0, 0.0];
+        let values = manager.evaluate(&parameters)?;
+        let weights = dataset.weights();
+        let mc_term: f64 = values.iter().zip(&weights).map(|(l, w)| w * l).sum();
+        let zero: Vec<usize> = (0..values.len()).filter(|&i| values[i] == 0.0).collect();
+        assert!(!zero.is_empty() && zero.len() < values.len());
+        assert!(!ell.evaluate(&parameters)?.is_finite());
+        assert!(ell.floored_events(&parameters)?.is_empty());
+
+        let floor = 1e-3;
+        let clamped = ell
+            .clone()
+            .with_intensity_floor(Some(IntensityFloor::Clamp(floor)));
+        assert_eq!(clamped.floored_events(&parameters)?, zero);
+        let expected: f64 = values
+            .iter()
+            .zip(&weights)
+            .map(|(l, w)| w * l.max(floor).ln())
+            .sum::<f64>()
+            - mc_term;
+        assert_is_close!(clamped.evaluate(&parameters)?, -2.0 * expected, f64);
+        assert_is_close!(clamped.par_evaluate(&parameters)?, -2.0 * expected, f64);
+
+        let dropped = ell.with_intensity_floor(Some(IntensityFloor::Drop(floor)));
+        let expected: f64 = values
+            .iter()
+            .zip(&weights)
+            .filter(|(l, _)| **l >= floor)
+            .map(|(l, w)| w * l.ln())
+            .sum::<f64>()
+            - mc_term;
+        assert_is_close!(dropped.evaluate(&parameters)?, -2.0 * expected, f64);
+        let gradient = dropped.gradient(&parameters)?;
+        let h = 1e-6;
+        for i in 0..2 {
+            let mut plus = parameters;
+            plus[i] += h;
+            let mut minus = parameters;
+            minus[i] -= h;
+            let numeric = (dropped.evaluate(&plus)? - dropped.evaluate(&minus)?) / (2.0 * h);
+            assert_is_close!(gradient[i], numeric, 1e-5, f64);
+        }
+        assert_eq!(gradient[2], 0.0);
+        Ok(())
+    }
+    #[test]
+    fn test_model_history() -> Result<(), RustitudeError> {
+        let build = || model!(scalar("a") * cscalar("b"), cscalar("c"));
+        let mut model = build();
+        let original = model.parameters.clone();
+        model.fix("a", "value", 2.0)?;
+        model.constrain("b", "imag", "c", "imag")?;
+        model.set_initial("b", "real", 0.5)?;
+        model.set_bounds("c", "real", (-1.0, 1.0))?;
+        model.add_gaussian_constraint("c", "real", 0.2, 0.1)?;
+        assert!(model.fix("d", "value", 1.0).is_err());
+        assert!(model
+            .add_gaussian_constraint("c", "real", 0.2, 0.0)
+            .is_err());
+        assert_eq!(
+            model
+                .history()
+                .iter()
+                .map(|op| op.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "fix(a::value, 2)",
+                "constrain(b::imag, c::imag)",
+                "set_initial(b::real, 0.5)",
+                "set_bounds(c::real, (-1, 1))",
+                "add_gaussian_constraint(c::real, 0.2, 0.1)",
+            ]
+        );
+
+        let mut other = build();
+        model.replay(&mut other)?;
+        assert_eq!(
+            format!("{:?}", other.parameters),
+            format!("{:?}", model.parameters)
+        );
+        assert_eq!(other.get_n_free(), 3);
+        assert_eq!(other.gaussian_constraints.len(), 1);
+        assert_eq!(other.history().len(), 5);
+
+        let undone = model.undo();
+        assert!(matches!(
+            undone,
+            Some(ModelOperation::AddGaussianConstraint(_))
+        ));
+        assert!(model.gaussian_constraints.is_empty());
+        model.undo();
+        model.undo();
+        assert_eq!(model.get_initial(), vec![1.0, 1.0, 1.0]);
+        model.undo();
+        assert_eq!(model.get_n_free(), 4);
+        model.undo();
+        assert_eq!(format!("{:?}", model.parameters), format!("{original:?}"));
+        assert!(model.undo().is_none());
+
+        let mut other = build();
+        other.clear_history();
+        assert!(model!(scalar("x")).replay(&mut other).is_ok());
+        let mut bad = build();
+        bad.fix("a", "value", 1.0)?;
+        assert!(bad.replay(&mut model!(scalar("x"))).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_simultaneous_likelihood() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a"), scalar("b"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let mut fixed = ell.clone();
+        fixed.fix("a", "value", 1.5)?;
+        let mut simultaneous =
+            SimultaneousLikelihood::new(vec![("para", ell.clone()), ("perp", fixed.clone())])?;
+        assert_eq!(simultaneous.labels(), vec!["para", "perp"]);
+        assert_eq!(simultaneous.parameter_names(), vec!["a::value", "b::value"]);
+        let total = simultaneous.evaluate(&[1.2, 0.4])?;
+        assert_is_close!(
+            total,
+            ell.evaluate(&[1.2, 0.4])? + fixed.evaluate(&[0.4])?,
+            f64
+        );
+        assert_is_close!(simultaneous.par_evaluate(&[1.2, 0.4])?, total, f64);
+        let gradient = simultaneous.gradient(&[1.2, 0.4])?;
+        let para_gradient = ell.gradient(&[1.2, 0.4])?;
+        let perp_gradient = fixed.gradient(&[0.4])?;
+        assert_is_close!(gradient[0], para_gradient[0], f64);
+        assert_is_close!(gradient[1], para_gradient[1] + perp_gradient[0], f64);
+
+        simultaneous.scope("b", "value")?;
+        assert!(simultaneous.is_scoped("b", "value"));
+        assert_eq!(
+            simultaneous.parameter_names(),
+            vec!["a::value", "para/b::value", "perp/b::value"]
+        );
+        assert_eq!(
+            simultaneous.term_parameters("perp", &[1.2, 0.4, 0.7])?,
+            vec![0.7]
+        );
+        assert_is_close!(
+            simultaneous.evaluate(&[1.2, 0.4, 0.7])?,
+            ell.evaluate(&[1.2, 0.4])? + fixed.evaluate(&[0.7])?,
+            f64
+        );
+        let gradient = simultaneous.par_gradient(&[1.2, 0.4, 0.7])?;
+        assert_is_close!(gradient[2], fixed.gradient(&[0.7])?[0], f64);
+        simultaneous.share("b", "value")?;
+        assert_eq!(simultaneous.get_n_free(), 2);
+
+        assert!(simultaneous.scope("c", "value").is_err());
+        assert!(simultaneous.share("b", "value").is_err());
+        assert!(simultaneous.evaluate(&[1.0]).is_err());
+        assert!(simultaneous.term_parameters("diag", &[1.0, 1.0]).is_err());
+        assert!(SimultaneousLikelihood::new(vec![("para", ell.clone()), ("para", ell)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_simultaneous_normalizations() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let manager = Manager::new(&model!(scalar("a")), &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let scaled_manager = Manager::new(&model!(scalar("a") * scalar("n")), &dataset)?;
+        let scaled = ExtendedLogLikelihood::new(scaled_manager.clone(), scaled_manager);
+        let mut simultaneous =
+            SimultaneousLikelihood::new(vec![("para", ell.clone()), ("perp", ell.clone())])?;
+        simultaneous.add_normalizations("para", None)?;
+        assert_eq!(
+            simultaneous.parameter_names(),
+            vec!["a::value", "perp/normalization::scale"]
+        );
+        assert_eq!(simultaneous.get_initial(), vec![1.0, 1.0]);
+        // Scaling the intensity by s is the same as scaling the amplitude by sqrt(s)
+        let x = [1.2, 0.8];
+        let expected = ell.evaluate(&[1.2])? + scaled.evaluate(&[1.2, f64::sqrt(0.8)])?;
+        assert_is_close!(simultaneous.evaluate(&x)?, expected, f64);
+        assert_is_close!(simultaneous.par_evaluate(&x)?, expected, f64);
+        let gradient = simultaneous.gradient(&x)?;
+        let par_gradient = simultaneous.par_gradient(&x)?;
+        for i in 0..2 {
+            let h = 1e-6;
+            let mut x_plus = x;
+            let mut x_minus = x;
+            x_plus[i] += h;
+            x_minus[i] -= h;
+            let numeric =
+                (simultaneous.evaluate(&x_plus)? - simultaneous.evaluate(&x_minus)?) / (2.0 * h);
+            assert_is_close!(gradient[i], numeric, 1e-4, f64);
+            assert_is_close!(par_gradient[i], gradient[i], f64);
+        }
+
+        simultaneous.add_normalizations("para", Some(0.1))?;
+        assert_is_close!(simultaneous.evaluate(&x)?, expected + 4.0, f64);
+        assert_is_close!(
+            simultaneous.gradient(&x)?[1],
+            gradient[1] + 2.0 * (0.8 - 1.0) / 0.01,
+            f64
+        );
+        simultaneous.remove_normalizations();
+        assert_eq!(simultaneous.get_n_free(), 1);
+
+        assert!(simultaneous.add_normalizations("diag", None).is_err());
+        assert!(simultaneous.add_normalizations("para", Some(0.0)).is_err());
+        let mut with_yield = ell.clone();
+        with_yield.add_signal_yield();
+        let mut simultaneous =
+            SimultaneousLikelihood::new(vec![("para", ell), ("perp", with_yield)])?;
+        assert!(simultaneous.add_normalizations("para", None).is_err());
+        assert!(simultaneous.add_normalizations("perp", None).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_warm_start() -> Result<(), RustitudeError> {
+        let model_32 = model!(scalar("a") * cscalar("b"));
+        let manager_32 = Manager::new(&model_32, &generate_test_dataset_f32())?;
+        let mut ell_32 = ExtendedLogLikelihood::new(manager_32.clone(), manager_32);
+        ell_32.add_signal_yield();
+        let best_32 = [0.9_f32, 0.1, -0.2, 50.0];
+        let fx_32 = ell_32.evaluate(&best_32)?;
+        let result = FitResult::new(&ell_32, &best_32, fx_32, None)?;
+
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a") * cscalar("b"));
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        ell.add_signal_yield();
+        ell.fix("b", "imag", 0.0)?;
+        let initial = result.warm_start(&mut ell)?;
+        assert_eq!(initial.len(), 3);
+        for (value, expected) in initial.iter().zip([0.9, 0.1, 50.0]) {
+            assert_is_close!(*value, expected, 1e-6, f64);
+        }
+        assert_eq!(ell.get_initial(), initial);
+
+        let mut other = ExtendedLogLikelihood::new(
+            Manager::new(&model!(scalar("c")), &dataset)?,
+            Manager::new(&model!(scalar("c")), &dataset)?,
+        );
+        assert!(result.warm_start(&mut other).is_err());
+        assert_eq!(other.get_initial(), vec![1.0]);
+        Ok(())
+    }
+    #[test]
+    fn test_config() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let config = r#"
+            # amplitudes
+            amplitude a Scalar
+            amplitude b ComplexScalar
+            amplitude c PolarComplexScalar
+            amplitude d PiecewiseM bins=4 range=[1.0, 2.0]
+
+            sum (a + b) * c    # first coherent sum
+            sum real(d)
+
+            fix a value 2.0
+            constrain c mag d "bin 0 re"
+            bounds b real -1.0 1.0
+            initial b imag 0.5
+            deactivate b
+        "#;
+        let registry = NodeRegistry::new();
+        let model = Model::from_config(config, &registry)?;
+        let mut expected_model = model!(
+            (scalar("a") + cscalar("b")) * pcscalar("c"),
+            piecewise_m("d", 4, (1.0, 2.0)).real(),
+        );
+        expected_model.fix("a", "value", 2.0)?;
+        expected_model.constrain("c", "mag", "d", "bin 0 re")?;
+        expected_model.set_bounds("b", "real", (-1.0, 1.0))?;
+        expected_model.set_initial("b", "imag", 0.5)?;
+        expected_model.deactivate("b")?;
+        assert_eq!(model.get_n_free(), expected_model.get_n_free());
+        assert_eq!(model.get_bounds(), expected_model.get_bounds());
+        assert_eq!(model.get_initial(), expected_model.get_initial());
+        let parameters: Vec<f64> = (0..model.get_n_free()).map(|i| i as f64 + 1.0).collect();
+        let result = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        let expected = Manager::new(&expected_model, &dataset)?.evaluate(&parameters)?;
+        for (r, e) in result.iter().zip(&expected) {
+            assert_is_close!(*r, *e, f64);
+        }
+        assert!(Model::from_config("amplitude a Unknown\nsum a", &registry).is_err());
+        assert!(Model::from_config("amplitude a Scalar\nsum (a", &registry).is_err());
+        assert!(Model::from_config("amplitude a Scalar\nsum a\nfix a value", &registry).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_gradient() -> Result<(), RustitudeError> {
+        #[derive(Clone)]
+        struct Cube;
+        impl Node<f64> for Cube {
+            fn calculate(
+                &self,
+                parameters: &[f64],
+                _event: &Event<f64>,
+            ) -> Result<Complex<f64>, RustitudeError> {
+                Ok(Complex::new(parameters[0].powi(3), parameters[0]))
+            }
+            fn parameters(&self) -> Vec<String> {
+                vec!["x".to_string()]
+            }
+        }
+        let dataset = generate_test_dataset_f64();
+        let mut model = model!(
+            (scalar("a") + cscalar("b")) * pcscalar("c") + Cube.named("d"),
+            piecewise_m("e", 2, (0.0, 20.0)).real() * cscalar("f").imag(),
+        );
+        model.fix("b", "imag", 0.5)?;
+        model.constrain("a", "value", "e", "bin 1 re")?;
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        ell.add_gaussian_constraint("c", "mag", 1.0, 0.5)?;
+        let parameters: Vec<f64> = (0..ell.get_n_free())
+            .map(|i| 0.3 * i as f64 + 0.7)
+            .collect();
+        let gradient = ell.gradient(&parameters)?;
+        assert_eq!(gradient.len(), parameters.len());
+        for (g, par_g) in gradient.iter().zip(ell.par_gradient(&parameters)?) {
+            assert_is_close!(*g, par_g, f64);
+        }
+        for i in 0..parameters.len() {
+            let h = 1e-6;
+            let mut plus = parameters.clone();
+            plus[i] += h;
+            let mut minus = parameters.clone();
+            minus[i] -= h;
+            let numeric = (ell.evaluate(&plus)? - ell.evaluate(&minus)?) / (2.0 * h);
+            assert_is_close!(gradient[i], numeric, 1e-5, f64);
+        }
+        let value = ell.evaluate(&parameters)?;
+        for (fused_value, fused_gradient) in [
+            ell.evaluate_with_gradient(&parameters)?,
+            ell.par_evaluate_with_gradient(&parameters)?,
+        ] {
+            assert_is_close!(fused_value, value, 1e-10, f64);
+            for (g, fused_g) in gradient.iter().zip(fused_gradient) {
+                assert_is_close!(*g, fused_g, 1e-10, f64);
+            }
+        }
+        // Likelihoods which cannot be fused fall back to separate passes
+        ell.add_signal_yield();
+        let mut parameters = parameters;
+        parameters.push(2.0);
+        let (fused_value, fused_gradient) = ell.evaluate_with_gradient(&parameters)?;
+        assert_eq!(fused_value, ell.evaluate(&parameters)?);
+        assert_eq!(fused_gradient, ell.gradient(&parameters)?);
+        Ok(())
+    }
+    #[test]
+    fn test_event_independent_amplitudes() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let mut model = model!(
+            pcscalar("a") * piecewise_m("b", 2, (0.0, 20.0)),
+            scalar("c") * cscalar("d"),
+        );
+        model.deactivate("d")?;
+        let manager = Manager::new(&model, &dataset)?;
+        let parameters: Vec<f64> = (0..manager.get_n_free())
+            .map(|i| 0.2 * i as f64 + 0.4)
+            .collect();
+        let pars = manager.model.physical_parameters(&parameters);
+        let amplitudes = manager.model.amplitudes.read();
+        let event = &dataset.events[0];
+        let precomputed = manager.model.precompute(&amplitudes, &pars, event)?;
+        // Only active scalars are calculated ahead of time
+        assert_eq!(
+            precomputed.iter().map(Option::is_some).collect::<Vec<_>>(),
+            vec![true, false, true, false]
+        );
+        let precomputed_gradient = manager
+            .model
+            .precompute_gradient(&amplitudes, &pars, event)?;
+        for (event, value) in dataset.events.iter().zip(manager.evaluate(&parameters)?) {
+            assert_eq!(value, manager.model.compute(&amplitudes, &pars, event)?);
+            let (grad_value, gradient) = manager.model.compute_gradient_precomputed(
+                &amplitudes,
+                &precomputed_gradient,
+                &pars,
+                event,
+            )?;
+            assert_eq!(
+                (grad_value, gradient),
+                manager.model.compute_gradient(&amplitudes, &pars, event)?
+            );
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_incoherent_sum() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let mut model = model!(cscalar("signal"));
+        assert!(model.add_incoherent_sum("bkg", &[]).is_err());
+        model.add_incoherent_sum(
+            "bkg",
+            &[Box::new(scalar("flat")), Box::new(scalar("slope"))],
+        )?;
+        assert!(model
+            .add_incoherent_sum("bkg", &[Box::new(scalar("flat"))])
+            .is_err());
+        assert_eq!(model.get_n_free(), 5);
+        assert_eq!(
+            model.get_parameter("bkg fraction 1", "logit 1")?.index,
+            Some(2)
+        );
+        let manager = Manager::new(&model, &dataset)?;
+        let parameters = [0.3, -0.4, 0.8, 1.5, 2.0];
+        let f_flat = 1.0 / (1.0 + 0.8f64.exp());
+        let expected = 0.3f64.powi(2)
+            + 0.4f64.powi(2)
+            + f_flat * 1.5f64.powi(2)
+            + (1.0 - f_flat) * 2.0f64.powi(2);
+        for value in manager.evaluate(&parameters)? {
+            assert_is_close!(value, expected, f64);
+        }
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager.clone());
+        let gradient = ell.gradient(&parameters)?;
+        for (i, g) in gradient.iter().enumerate() {
+            let h = 1e-6;
+            let mut plus = parameters.to_vec();
+            plus[i] += h;
+            let mut minus = parameters.to_vec();
+            minus[i] -= h;
+            let numeric = (ell.evaluate(&plus)? - ell.evaluate(&minus)?) / (2.0 * h);
+            assert_is_close!(*g, numeric, 1e-5, f64);
+        }
+        let json = model.to_json()?;
+        let reloaded: Model<f64> = Model::from_json(&json, &NodeRegistry::new())?;
+        let reloaded_manager = Manager::new(&reloaded, &dataset)?;
+        assert_eq!(
+            reloaded_manager.evaluate(&parameters)?,
+            manager.evaluate(&parameters)?
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_lineshapes() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let event = &dataset.events[0];
+        let (p1, p2) = (event.daughter_p4s[0], event.daughter_p4s[1]);
+        let m = (p1 + p2).m();
+        assert!(BreitWigner::<f64>::new(&[], &[1], 2).is_err());
+        assert!(BreitWigner::<f64>::new(&[0], &[1], 5).is_err());
+        let mut bw = BreitWigner::new(&[0], &[1], 2)?;
+        bw.precalculate(&dataset)?;
+        // The Breit-Wigner is purely imaginary with unit magnitude at its pole mass
+        let value = bw.calculate(&[m, 0.1], event)?;
+        assert_is_close!(value.re, 0.0, 1e-10, f64);
+        assert_is_close!(value.im, 1.0, f64);
+        assert!(Flatte::<f64>::new(&[0], &[1], 2, [0.14, 0.14], [0.49, 0.49]).is_err());
+        let mut flatte = Flatte::new(&[0], &[1], 0, [0.14, 0.14], [0.49, 0.49])?;
+        flatte.precalculate(&dataset)?;
+        let (m0, g1): (f64, f64) = (0.98, 0.2);
+        let rho = f64::sqrt(1.0 - 4.0 * 0.14f64.powi(2) / m.powi(2));
+        let expected = Complex::new(g1, 0.0) / Complex::new(m0.powi(2) - m.powi(2), -g1 * g1 * rho);
+        let value = flatte.calculate(&[m0, g1, 0.0], event)?;
+        assert_is_close!(value.re, expected.re, f64);
+        assert_is_close!(value.im, expected.im, f64);
+        let model = model!(
+            BreitWigner::new(&[0], &[1], 2)?.named("bw"),
+            Flatte::new(&[0], &[1], 1, [0.14, 0.14], [0.49, 0.49])?.named("flatte"),
+        );
+        let reloaded: Model<f64> = Model::from_json(&model.to_json()?, &NodeRegistry::new())?;
+        let parameters = [1.3, 0.1, 0.98, 0.2, 0.4];
+        assert_eq!(
+            Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?,
+            Manager::new(&model, &dataset)?.evaluate(&parameters)?
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_resolution_lineshapes() -> Result<(), RustitudeError> {
+        // w(1 + i) and w(x) = exp(-x^2) + 2i D(x) / sqrt(pi) on the real axis
+        let w = rustitude_core::math::faddeeva(Complex::new(1.0, 1.0));
+        assert_is_close!(w.re, 0.304_744_205_256_912_6, 1e-10, f64);
+        assert_is_close!(w.im, 0.208_218_938_202_831_6, 1e-10, f64);
+        let w = rustitude_core::math::faddeeva(Complex::new(2.0, 0.0));
+        assert_is_close!(w.re, f64::exp(-4.0), 1e-10, f64);
+        let w = rustitude_core::math::faddeeva(Complex::new(0.0, -1.0));
+        assert_is_close!(
+            w.re,
+            f64::exp(1.0) * 2.0 - 0.427_583_576_155_807,
+            1e-10,
+            f64
+        );
+        let dataset = generate_test_dataset_f64();
+        let event = &dataset.events[0];
+        let m = (event.daughter_p4s[0] + event.daughter_p4s[1]).m();
+        assert!(Gaussian::<f64>::new(&[]).is_err());
+        let mut gaussian = Gaussian::new(&[0, 1])?;
+        gaussian.precalculate(&dataset)?;
+        let sigma: f64 = 0.02;
+        let peak = 1.0 / (sigma * f64::sqrt(2.0 * std::f64::consts::PI));
+        assert_is_close!(
+            gaussian.calculate(&[m, sigma], event)?.norm_sqr(),
+            peak,
+            f64
+        );
+        let value = gaussian.calculate(&[m + sigma, sigma], event)?.norm_sqr();
+        assert_is_close!(value, peak * f64::exp(-0.5), f64);
+        // A Voigtian with a vanishing natural width is a Gaussian
+        let mut voigtian = Voigtian::new(&[0, 1])?;
+        voigtian.precalculate(&dataset)?;
+        for &mass in &[m, m + sigma, m - 3.0 * sigma] {
+            assert_is_close!(
+                voigtian.calculate(&[mass, 1e-12, sigma], event)?.re,
+                gaussian.calculate(&[mass, sigma], event)?.re,
+                1e-6,
+                f64
+            );
+        }
+        // At the peak, V = w(i y) / (sigma sqrt(2 pi)) with y = Gamma / (2 sqrt(2) sigma) = 1
+        let width = 2.0 * f64::sqrt(2.0) * sigma;
+        assert_is_close!(
+            voigtian.calculate(&[m, width, sigma], event)?.norm_sqr(),
+            0.427_583_576_155_807 * peak,
+            1e-10,
+            f64
+        );
+        let model = model!(
+            Gaussian::new(&[0, 1])?.named("gaussian"),
+            Voigtian::new(&[0, 1])?.named("voigtian"),
+        );
+        let reloaded: Model<f64> = Model::from_json(&model.to_json()?, &NodeRegistry::new())?;
+        let parameters = [1.3, 0.05, 1.2, 0.1, 0.03];
+        assert_eq!(
+            Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?,
+            Manager::new(&model, &dataset)?.evaluate(&parameters)?
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_kmatrix() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let event = &dataset.events[0];
+        let s = (event.daughter_p4s[0] + event.daughter_p4s[1]).m2();
+        let channel = |name: &str, m1: f64, m2: f64| KMatrixChannel {
+            name: name.to_string(),
+            m1,
+            m2,
+        };
+        let pole = |name: &str, mass: f64, couplings: Vec<f64>| KMatrixPole {
+            name: name.to_string(),
+            mass,
+            couplings,
+        };
+        // A single channel and pole gives a Breit-Wigner-like shape with a Chew-Mandelstam width
+        let (m0, g) = (1.1, 0.4);
+        let config = KMatrixConfig {
+            channels: vec![channel("pipi", 0.14, 0.14)],
+            poles: vec![pole("f0", m0, vec![g])],
+            background: None,
+            adler_zero: None,
+            l: 0,
+        };
+        assert!(KMatrix::new(&[0], &[1], 1, config.clone()).is_err());
+        assert!(KMatrix::new(&[0], &[1], 0, config.clone())?
+            .with_non_resonant(&[0, 0])
+            .is_err());
+        let mut kmatrix = KMatrix::new(&[0], &[1], 0, config)?;
+        assert_eq!(kmatrix.parameters(), vec!["f0 re", "f0 im"]);
+        kmatrix.precalculate(&dataset)?;
+        let expected = Complex::new(0.3 * g, -0.2 * g)
+            / (Complex::from(m0 * m0 - s)
+                + rustitude_core::math::chew_mandelstam(s, 0.14, 0.14) * g * g);
+        let value = kmatrix.calculate(&[0.3, -0.2], event)?;
+        assert_is_close!(value.re, expected.re, f64);
+        assert_is_close!(value.im, expected.im, f64);
+        // Two channels with a background term and non-resonant production
+        let config = KMatrixConfig {
+            channels: vec![channel("pipi", 0.14, 0.14), channel("kkbar", 0.49, 0.49)],
+            poles: vec![
+                pole("f0_980", 0.98, vec![0.1, 0.6]),
+                pole("f0_1500", 1.5, vec![0.3, 0.1]),
+            ],
+            background: Some(vec![vec![0.05, -0.01], vec![-0.01, 0.02]]),
+            adler_zero: None,
+            l: 0,
+        };
+        let invalid = KMatrixConfig {
+            background: Some(vec![vec![0.05]]),
+            ..config.clone()
+        };
+        assert!(invalid.validate().is_err());
+        let kmatrix = KMatrix::new(&[0], &[1], 1, config)?.with_non_resonant(&[0])?;
+        assert_eq!(
+            kmatrix.parameters(),
+            vec![
+                "f0_980 re",
+                "f0_980 im",
+                "f0_1500 re",
+                "f0_1500 im",
+                "pipi non-resonant re",
+                "pipi non-resonant im"
+            ]
+        );
+        let parameters = [0.3, -0.2, 0.1, 0.5, -0.4, 0.2];
+        let mut precalculated = kmatrix.clone();
+        precalculated.precalculate(&dataset)?;
+        let gradient = precalculated.calculate_gradient(&parameters, event)?;
+        let h = 1e-6;
+        for (i, derivative) in gradient.iter().enumerate() {
+            let mut shifted = parameters;
+            shifted[i] += h;
+            let forward = precalculated.calculate(&shifted, event)?;
+            shifted[i] -= 2.0 * h;
+            let backward = precalculated.calculate(&shifted, event)?;
+            let expected = (forward - backward) / (2.0 * h);
+            assert_is_close!(derivative.re, expected.re, 1e-6, f64);
+            assert_is_close!(derivative.im, expected.im, 1e-6, f64);
+        }
+        let model = model!(kmatrix.named("k"));
+        let reloaded: Model<f64> = Model::from_json(&model.to_json()?, &NodeRegistry::new())?;
+        assert_eq!(
+            Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?,
+            Manager::new(&model, &dataset)?.evaluate(&parameters)?
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_kmatrix_presets() -> Result<(), RustitudeError> {
+        // The squared real and imaginary parts of each pole term, as given by the hard-coded
+        // K-matrices of rustitude-gluex
+        let dataset = Dataset::new(vec![generate_test_event_f64()]);
+        let presets: [(KMatrixConfig<f64>, usize, Vec<f64>); 6] = [
+            (
+                KMatrixConfig::f0(),
+                2,
+                vec![
+                    0.030680506,
+                    0.054_358_566,
+                    0.002_729_284_8,
+                    0.000_001_175_634_7,
+                    0.122108042,
+                    0.153889632,
+                    0.003648740,
+                    0.000_815_257_8,
+                    0.000_078_950_513,
+                    0.017668038,
+                ],
+            ),
+            (
+                KMatrixConfig::f2(),
+                2,
+                vec![
+                    0.079874652,
+                    0.116_876_834,
+                    0.5885590974,
+                    0.025_541_601_7,
+                    0.097405045,
+                    0.004549439,
+                    0.011321180,
+                    0.007075583,
+                ],
+            ),
+            (
+                KMatrixConfig::a0(),
+                1,
+                vec![0.084485367, 0.0099839209, 1.726739721, 1.299_382_378],
+            ),
+            (
+                KMatrixConfig::a2(),
+                1,
+                vec![0.34870050, 0.919_619_85, 0.005_930_287, 0.026281367],
+            ),
+            (
+                KMatrixConfig::rho(),
+                1,
+                vec![0.0007601991, 0.0007605480, 0.266948124, 0.029465809],
+            ),
+            (KMatrixConfig::pi1(), 1, vec![0.6947747815, 0.9365046503]),
+        ];
+        for (config, channel, expected) in presets {
+            config.validate()?;
+            let mut kmatrix = KMatrix::new(&[0], &[1], channel, config)?;
+            assert_eq!(kmatrix.parameters().len(), expected.len());
+            kmatrix.precalculate(&dataset)?;
+            for (i, value) in expected.iter().enumerate() {
+                let mut parameters = vec![0.0; expected.len()];
+                parameters[i] = 1.0;
+                let amplitude = kmatrix.calculate(&parameters, &dataset.events[0])?;
+                assert_is_close!(amplitude.re.powi(2), *value, f64);
+            }
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_piecewise_variables() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let energies: Vec<f64> = dataset.events.iter().map(|e| e.beam_p4.e()).collect();
+        let low = energies.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = energies.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let model = model!(
+            piecewise_t("t", 2, (-5.0, 0.0)),
+            piecewise_costheta("costheta", 2, (-1.0, 1.0)),
+            piecewise_beam_energy("e", 1, (low, high)),
+        );
+        let reloaded: Model<f64> = Model::from_json(&model.to_json()?, &NodeRegistry::new())?;
+        let parameters: Vec<f64> = (0..model.get_n_free()).map(|i| 0.1 * i as f64).collect();
+        assert_eq!(
+            Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?,
+            Manager::new(&model, &dataset)?.evaluate(&parameters)?
+        );
+        // A user-supplied variable bins events the same way as the equivalent built-in one
+        let custom = model!(piecewise("e", 1, (low, high), |e: &Event<f64>| e
+            .beam_p4
+            .e()));
+        let builtin = model!(piecewise_beam_energy("e", 1, (low, high)));
+        assert_eq!(
+            Manager::new(&custom, &dataset)?.evaluate(&[0.3, 0.4])?,
+            Manager::new(&builtin, &dataset)?.evaluate(&[0.3, 0.4])?
+        );
+        for value in Manager::new(&builtin, &dataset)?.evaluate(&[0.3, 0.4])? {
+            assert_is_close!(value, 0.25, f64);
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_piecewise_2d() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let masses: Vec<f64> = dataset
+            .events
+            .iter()
+            .map(|e| (e.daughter_p4s[0] + e.daughter_p4s[1]).m())
+            .collect();
+        let low = masses.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = masses.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let amplitude = piecewise_m_t("mt", (2, 1), ((low, high), (-100.0, 0.0)));
+        assert_eq!(
+            amplitude.parameters(),
+            vec![
+                "m bin 0, t bin 0 re",
+                "m bin 0, t bin 0 im",
+                "m bin 1, t bin 0 re",
+                "m bin 1, t bin 0 im",
+            ]
+        );
+        // With a single bin in t, the amplitude is binned in mass alone
+        let model = model!(amplitude);
+        let parameters = [0.1, 0.2, 0.3, 0.4];
+        assert_eq!(
+            Manager::new(&model, &dataset)?.evaluate(&parameters)?,
+            Manager::new(&model!(piecewise_m("m", 2, (low, high))), &dataset)?
+                .evaluate(&parameters)?
+        );
+        let reloaded: Model<f64> = Model::from_json(&model.to_json()?, &NodeRegistry::new())?;
+        assert_eq!(
+            Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?,
+            Manager::new(&model, &dataset)?.evaluate(&parameters)?
+        );
+        // Events outside of the binned region are zero
+        let empty = model!(piecewise_2d(
+            "empty",
+            (1, 1),
+            ((low, high), (1.0, 2.0)),
+            (
+                |e: &Event<f64>| (e.daughter_p4s[0] + e.daughter_p4s[1]).m(),
+                |_| 0.0
+            )
+        ));
+        for value in Manager::new(&empty, &dataset)?.evaluate(&[1.0, 1.0])? {
+            assert_eq!(value, 0.0);
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_polynomials() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let event = &dataset.events[0];
+        let parameters = [0.5, -1.0, 2.0];
+        assert!(Polynomial::new(2, PolynomialBasis::Chebyshev(1.0, 1.0), |_| 2.0).is_err());
+        let mut power = Polynomial::new(2, PolynomialBasis::Power, |_| 2.0)?;
+        power.precalculate(&dataset)?;
+        assert_eq!(
+            power.calculate(&parameters, event)?,
+            Complex::new(0.5 - 2.0 + 8.0, 0.0)
+        );
+        assert_eq!(
+            power.calculate_gradient(&parameters, event)?,
+            vec![
+                Complex::new(1.0, 0.0),
+                Complex::new(2.0, 0.0),
+                Complex::new(4.0, 0.0)
+            ]
+        );
+        // T_0(0) = 1, T_1(0) = 0, and T_2(0) = -1 at the center of the range
+        let mut chebyshev = Polynomial::new(2, PolynomialBasis::Chebyshev(1.0, 3.0), |_| 2.0)?;
+        chebyshev.precalculate(&dataset)?;
+        assert_is_close!(chebyshev.calculate(&parameters, event)?.re, -1.5, f64);
+        let mut outside = Polynomial::new(2, PolynomialBasis::Chebyshev(3.0, 4.0), |_| 2.0)?;
+        outside.precalculate(&dataset)?;
+        assert_eq!(outside.calculate(&parameters, event)?, Complex::default());
+        let model = model!(polynomial_m(
+            "bkg",
+            3,
+            PolynomialBasis::Chebyshev(0.0, 10.0)
+        )?);
+        assert_eq!(model.parameters[3].name, "c 3");
+        let reloaded: Model<f64> = Model::from_json(&model.to_json()?, &NodeRegistry::new())?;
+        let parameters = [0.4, 0.3, -0.2, 0.1];
+        assert_eq!(
+            Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?,
+            Manager::new(&model, &dataset)?.evaluate(&parameters)?
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_splines() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let event = &dataset.events[0];
+        let knots = vec![0.0, 1.0, 2.5, 3.0];
+        assert!(Spline::new(vec![1.0], SplineKind::Cubic, KnotForm::Cartesian, |_| 1.5).is_err());
+        assert!(Spline::new(
+            vec![1.0, 1.0],
+            SplineKind::Cubic,
+            KnotForm::Cartesian,
+            |_| 1.5
+        )
+        .is_err());
+        // Both interpolations reproduce linear knot values exactly
+        let parameters = [0.0, 3.0, 2.0, 2.0, 5.0, 0.5, 6.0, 0.0];
+        for kind in [SplineKind::Cubic, SplineKind::Monotone] {
+            let mut spline = Spline::new(knots.clone(), kind, KnotForm::Cartesian, |_| 1.5)?;
+            spline.precalculate(&dataset)?;
+            let value = spline.calculate(&parameters, event)?;
+            assert_is_close!(value.re, 3.0, f64);
+            assert_is_close!(value.im, 1.5, f64);
+            let mut spline = Spline::new(knots.clone(), kind, KnotForm::Polar, |_| 2.5)?;
+            spline.precalculate(&dataset)?;
+            let value = spline.calculate(&parameters, event)?;
+            assert_is_close!(value.norm(), 5.0, f64);
+            assert_is_close!(value.arg(), 0.5, f64);
+            let mut spline = Spline::new(knots.clone(), kind, KnotForm::Polar, |_| 3.5)?;
+            spline.precalculate(&dataset)?;
+            assert_eq!(spline.calculate(&parameters, event)?, Complex::default());
+        }
+        let model = model!(spline_m(
+            "s",
+            vec![0.0, 1.0, 2.0, 10.0],
+            SplineKind::Monotone,
+            KnotForm::Cartesian
+        )?);
+        assert_eq!(model.parameters[1].name, "knot 0 im");
+        let reloaded: Model<f64> = Model::from_json(&model.to_json()?, &NodeRegistry::new())?;
+        let parameters = [0.1, 0.2, 0.8, -0.3, 1.1, 0.4, 0.2, 0.0];
+        assert_eq!(
+            Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?,
+            Manager::new(&model, &dataset)?.evaluate(&parameters)?
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_named_sums() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(
+            "PosRe" => (scalar("a") * cscalar("b")).real(),
+            "PosIm" => (scalar("a") * cscalar("b")).imag(),
+            "Neg" => cscalar("c"),
+        );
+        assert_eq!(
+            model.sum_names(),
+            vec![Some("PosRe"), Some("PosIm"), Some("Neg")]
+        );
+        assert!(model.get_sum("PosIm")?.active);
+        assert!(matches!(
+            model.get_sum("Pos"),
+            Err(RustitudeError::SumNotFoundError(_))
+        ));
+        let parameters = [0.5, 1.2, -0.3, 0.7, 0.2];
+        let mut manager = Manager::new(&model, &dataset)?;
+        let total = manager.evaluate(&parameters)?;
+        manager.deactivate_sum("Neg")?;
+        assert!(!manager.get_sum("Neg")?.active);
+        // a^2 |b|^2 is left once the negative-reflectivity sum is removed
+        for value in manager.evaluate(&parameters)? {
+            assert_is_close!(value, 0.25 * (1.2f64.powi(2) + 0.3f64.powi(2)), f64);
+        }
+        let json = manager.model.to_json()?;
+        let reloaded: Model<f64> = Model::from_json(&json, &NodeRegistry::new())?;
+        assert_eq!(reloaded.sum_names(), manager.model.sum_names());
+        assert!(!reloaded.get_sum("Neg")?.active);
+        manager.activate_sum("Neg")?;
+        assert_eq!(manager.evaluate(&parameters)?, total);
+        assert!(manager.deactivate_sum("Pos").is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_cohsum_evaluation() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let mut model = model!("Pos" => cscalar("a") * scalar("b"));
+        model.add_sum(Some("Neg"), Box::new(cscalar("c") * scalar("b")))?;
+        model.add_sum(None, Box::new(scalar("d")))?;
+        assert_eq!(model.sum_names(), vec![Some("Pos"), Some("Neg"), None]);
+        assert_eq!(model.get_n_free(), 6);
+        let parameters = [0.5, 1.2, 2.0, -0.3, 0.7, 0.1];
+        let mut manager = Manager::new(&model, &dataset)?;
+        let total = manager.evaluate(&parameters)?;
+        let pos = manager.evaluate_cohsum(&parameters, 0)?;
+        let neg = manager.par_evaluate_cohsum(&parameters, 1)?;
+        let flat = manager.evaluate_cohsum(&parameters, 2)?;
+        for i in 0..dataset.len() {
+            assert_is_close!(pos[i], 4.0 * (0.5f64.powi(2) + 1.2f64.powi(2)), f64);
+            assert_is_close!(neg[i], 4.0 * (0.3f64.powi(2) + 0.7f64.powi(2)), f64);
+            assert_is_close!(pos[i] + neg[i] + flat[i], total[i], f64);
+        }
+        // Deactivated sums are still evaluated
+        manager.deactivate_sum("Neg")?;
+        assert_eq!(manager.evaluate_cohsum(&parameters, 1)?, neg);
+        assert!(matches!(
+            manager.evaluate_cohsum(&parameters, 3),
+            Err(RustitudeError::InvalidParameterValue(_))
+        ));
+
+        // Removing a sum drops the amplitudes which only it used
+        let removed = model.remove_sum(1)?;
+        assert_eq!(removed.name.as_deref(), Some("Neg"));
+        assert!(model.get_amplitude("c").is_err());
+        assert!(model.get_amplitude("b").is_ok());
+        assert_eq!(model.get_n_free(), 4);
+        assert_eq!(model.get_parameter("d", "value")?.index, Some(3));
+        let manager = Manager::new(&model, &dataset)?;
+        let total = manager.evaluate(&[0.5, 1.2, 2.0, 0.1])?;
+        for i in 0..dataset.len() {
+            assert_is_close!(total[i], pos[i] + flat[i], f64);
+        }
+        assert!(matches!(
+            model.remove_sum(2),
+            Err(RustitudeError::SumNotFoundError(_))
+        ));
+        model.fix("d", "value", 1.0)?;
+        assert!(model.remove_sum(1).is_err());
+        assert!(model.add_sum(None, Box::new(scalar("e"))).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_matching_parameters() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(cscalar("a1"), cscalar("a2"), scalar("b"));
+        let mut manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager.clone());
+        assert_eq!(manager.model.matching_parameters("a?::*").len(), 4);
+        manager.fix_matching("a*::imag", 0.0)?;
+        assert_eq!(manager.get_n_free(), 3);
+        assert_eq!(manager.model.history().len(), 2);
+        manager.set_bounds_matching("a*::real", (-1.0, 1.0))?;
+        assert!(manager
+            .model
+            .matching_parameters("a*::real")
+            .iter()
+            .all(|p| p.bounds == (-1.0, 1.0)));
+        manager.constrain_matching("a*::real")?;
+        assert_eq!(manager.get_n_free(), 2);
+        // Matches which already share the first index are skipped
+        manager.constrain_matching("a*::real")?;
+        // The real parts stay constrained to each other
+        manager.free_matching("*")?;
+        assert_eq!(manager.get_n_free(), 4);
+        assert!(manager.fix_matching("c::*", 0.0).is_err());
+        ell.add_signal_yield();
+        ell.set_initial_matching("yield::*", 10.0)?;
+        assert_eq!(ell.get_initial().last(), Some(&10.0));
+        ell.set_initial_matching("*", 0.5)?;
+        assert!(ell.get_initial().iter().all(|v| *v == 0.5));
+        // Patterns wrapped in slashes are regular expressions
+        let mut model = model!(piecewise_m("p", 12, (0.5, 2.0)), scalar("b"));
+        assert_eq!(model.matching_parameters("/^p::bin [0-9] re$/").len(), 10);
+        assert_eq!(model.matching_parameters("/bin 1[01] (re|im)/").len(), 4);
+        model.fix_matching("/^p::bin ([02468]|10) im$/", 0.0)?;
+        assert_eq!(model.get_n_free(), 19);
+        model.set_bounds_matching("/::(bin 11 re|value)$/", (0.0, 5.0))?;
+        assert_eq!(model.get_parameter("b", "value")?.bounds, (0.0, 5.0));
+        assert!(matches!(
+            model.fix_matching("/bin [/", 0.0),
+            Err(RustitudeError::ParseError(_))
+        ));
+        assert!(model.matching_parameters("/bin [/").is_empty());
+        assert!(matches!(
+            model.fix_matching("/^q::/", 0.0),
+            Err(RustitudeError::ParameterNotFoundError(_))
+        ));
+        Ok(())
+    }
+    #[test]
+    fn test_parameter_transforms() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a"), pcscalar("b"));
+        let manager = Manager::new(&model, &dataset)?;
+        let mut plain = ExtendedLogLikelihood::new(manager.clone(), manager);
+        plain.add_gaussian_constraint("a", "value", 1.5, 0.5)?;
+        let mut ell = plain.clone();
+        ell.set_initial("a", "value", 2.0)?;
+        ell.set_bounds("b", "mag", (0.0, 4.0))?;
+        ell.set_transform("a", "value", Some(Transform::Log))?;
+        ell.set_transform("b", "mag", Some(Transform::Sqrt))?;
+        ell.set_transform("b", "phi", Some(Transform::Bounded(-1.0, 1.0)))?;
+        assert!(ell
+            .set_transform("b", "phi", Some(Transform::Bounded(1.0, -1.0)))
+            .is_err());
+        assert_is_close!(ell.get_initial()[0], 2.0_f64.ln(), f64);
+        assert_eq!(ell.get_bounds()[1], (-2.0, 2.0));
+        assert_eq!(ell.get_bounds()[2], (f64::NEG_INFINITY, f64::INFINITY));
+        // Nodes and constraints see the physical values
+        let internal = [0.3, 1.2, 0.4];
+        let physical = [0.3_f64.exp(), 1.44, -1.0 + 2.0 / (1.0 + (-0.4_f64).exp())];
+        assert_is_close!(ell.evaluate(&internal)?, plain.evaluate(&physical)?, f64);
+        let bounded = Transform::Bounded(-1.0, 1.0);
+        assert_is_close!(bounded.internal(bounded.physical(0.4)), 0.4, f64);
+        let gradient = ell.gradient(&internal)?;
+        for (i, g) in gradient.iter().enumerate() {
+            let h = 1e-6;
+            let mut plus = internal;
+            plus[i] += h;
+            let mut minus = internal;
+            minus[i] -= h;
+            let numeric = (ell.evaluate(&plus)? - ell.evaluate(&minus)?) / (2.0 * h);
+            assert!((g - numeric).abs() < 1e-5 * (1.0 + numeric.abs()));
+        }
+        // Fit results report physical values and propagate uncertainties
+        let covariance = vec![
+            vec![0.01, 0.0, 0.0],
+            vec![0.0, 0.01, 0.0],
+            vec![0.0, 0.0, 0.01],
+        ];
+        let fx = ell.evaluate(&internal)?;
+        let result = FitResult::new(&ell, &internal, fx, Some(covariance))?;
+        assert_is_close!(result.value("a", "value")?, physical[0], f64);
+        assert_is_close!(result.error("a", "value")?.unwrap(), 0.1 * physical[0], f64);
+        assert_is_close!(result.error("b", "mag")?.unwrap(), 0.1 * 2.4, f64);
+        let mut restored = ell.clone();
+        for (value, expected) in result.restore(&mut restored)?.iter().zip(internal) {
+            assert_is_close!(*value, expected, f64);
+        }
+        let config = "amplitude a Scalar\nsum a\ntransform a value bounded 0.0 2.0";
+        let model = Model::<f64>::from_config(config, &NodeRegistry::new())?;
+        assert!(matches!(
+            model.get_parameter("a", "value")?.transform,
+            Some(Transform::Bounded(0.0, 2.0))
+        ));
+        assert!(Model::<f64>::from_config(
+            "amplitude a Scalar\nsum a\ntransform a value exp",
+            &NodeRegistry::new()
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_visitor() {
+        #[derive(Default)]
+        struct Formula {
+            stack: Vec<Vec<String>>,
+            sums: Vec<String>,
+            depth: usize,
+            max_depth: usize,
+        }
+        impl Visitor<f64> for Formula {
+            fn enter(&mut self, node: &AmpNode<'_, f64>) -> bool {
+                self.depth += 1;
+                self.max_depth = self.max_depth.max(self.depth);
+                self.stack.push(Vec::new());
+                !matches!(node, AmpNode::Imag)
+            }
+            fn exit(&mut self, node: &AmpNode<'_, f64>) {
+                self.depth -= 1;
+                let children = self.stack.pop().unwrap();
+                let term = match node {
+                    AmpNode::NormSqr(i) => {
+                        self.sums.push(format!("{i}: |{}|^2", children[0]));
+                        return;
+                    }
+                    AmpNode::Amplitude(amp) => amp.name.clone(),
+                    AmpNode::Real => format!("Re[{}]", children[0]),
+                    AmpNode::Imag => "Im[...]".to_string(),
+                    AmpNode::Product(n) => {
+                        assert_eq!(*n, children.len());
+                        children.join(" * ")
+                    }
+                    AmpNode::Sum(n) => {
+                        assert_eq!(*n, children.len());
+                        format!("({})", children.join(" + "))
+                    }
+                };
+                self.stack.last_mut().unwrap().push(term);
+            }
+        }
+        let model = model!(
+            ((scalar("a") + cscalar("b")) * cscalar("c")).real(),
+            (cscalar("b") * cscalar("d")).imag()
+        );
+        let mut formula = Formula::default();
+        model.visit(&mut formula);
+        assert_eq!(
+            formula.sums,
+            vec!["0: |Re[(a * c + b * c)]|^2", "1: |Im[...]|^2"]
+        );
+        assert_eq!(formula.max_depth, 5);
+        assert_eq!(formula.depth, 0);
+    }
+    #[test]
+    fn test_to_latex() -> Result<(), RustitudeError> {
+        let product = Product(vec![
+            Box::new(scalar("a") + scalar("b")),
+            Box::new(cscalar("c")),
+        ]);
+        let mut model = model!(product.imag(), piecewise_m("d&e", 2, (1.0, 2.0)).real());
+        model.fix("c", "real", 1.5)?;
+        assert_eq!(
+            model.to_latex(),
+            concat!(
+                r"\left|\Im\left[\left(\text{a}\left(\text{value}\right) + ",
+                r"\text{b}\left(\text{value}\right)\right) ",
+                r"\text{c}\left(\text{real} = 1.5, \text{imag}\right)\right]\right|^2 + ",
+                r"\left|\Re\left[\text{d\&e}\left(\text{bin 0 re}, \text{bin 0 im}, ",
+                r"\text{bin 1 re}, \text{bin 1 im}\right)\right]\right|^2"
+            )
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_to_dot_and_mermaid() -> Result<(), RustitudeError> {
+        let mut model = Model::new_named(&[
+            (
+                "S \"wave\"",
+                Box::new(scalar("a") * cscalar("b")) as Box<dyn AmpLike<f64>>,
+            ),
+            ("D", Box::new(scalar("c").real())),
+        ]);
+        model.fix("b", "imag", 0.0)?;
+        model.deactivate("c")?;
+        assert_eq!(
+            model.to_dot(),
+            concat!(
+                "digraph {\n",
+                "    node [shape=box];\n",
+                "    n0 [label=\"+\"];\n",
+                "    n1 [label=\"|_|^2\\nS \\\"wave\\\"\"];\n",
+                "    n2 [label=\"*\"];\n",
+                "    n3 [label=\"a\\nvalue\"];\n",
+                "    n4 [label=\"b\\nreal, imag = 0\"];\n",
+                "    n5 [label=\"|_|^2\\nD\"];\n",
+                "    n6 [label=\"real\"];\n",
+                "    n7 [label=\"c\\nvalue\", style=dashed];\n",
+                "    n0 -> n1;\n",
+                "    n1 -> n2;\n",
+                "    n2 -> n3;\n",
+                "    n2 -> n4;\n",
+                "    n0 -> n5;\n",
+                "    n5 -> n6;\n",
+                "    n6 -> n7;\n",
+                "}\n"
+            )
+        );
+        assert_eq!(
+            model.to_mermaid(),
+            concat!(
+                "flowchart TD\n",
+                "    n0[\"+\"]\n",
+                "    n1[\"|_|^2<br/>S #quot;wave#quot;\"]\n",
+                "    n2[\"*\"]\n",
+                "    n3[\"a<br/>value\"]\n",
+                "    n4[\"b<br/>real, imag = 0\"]\n",
+                "    n5[\"|_|^2<br/>D\"]\n",
+                "    n6[\"real\"]\n",
+                "    n7[\"c<br/>value\"]\n",
+                "    n0 --> n1\n",
+                "    n1 --> n2\n",
+                "    n2 --> n3\n",
+                "    n2 --> n4\n",
+                "    n0 --> n5\n",
+                "    n5 --> n6\n",
+                "    n6 --> n7\n",
+                "    classDef inactive stroke-dasharray: 5 5\n",
+                "    class n7 inactive\n",
+            )
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_project() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a"), scalar("b"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let parameters = [1.2, 0.3];
+        let total = ell.intensity(&parameters, &dataset)?;
+        let a = ell.project(&parameters, &dataset, &["a"])?;
+        let b = ell.par_project(&parameters, &dataset, &["b"])?;
+        let both = ell.project(&parameters, &dataset, &["a", "b"])?;
+        for i in 0..dataset.len() {
+            assert_is_close!(a[i] + b[i], total[i], f64);
+            assert_is_close!(a[i], total[i] * 1.44 / 1.53, f64);
+            assert_is_close!(both[i], total[i], f64);
+        }
+        assert!(ell.get_amplitude("a")?.active);
+        assert!(ell.get_amplitude("b")?.active);
+        assert!(ell.project(&parameters, &dataset, &["c"]).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_interference() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(
+            (cscalar("a") + cscalar("b")) * cscalar("c"),
+            cscalar("b") * scalar("d")
+        );
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        // a = 1.2 + 0.4i, c = 0.8 + 0.6i, b = 0.3 - 0.5i, d = 0.7 (in order of appearance)
+        let parameters = [1.2, 0.4, 0.8, 0.6, 0.3, -0.5, 0.7];
+        let scale = ell.intensity(&parameters, &dataset)?[0]
+            / ell.data_manager.evaluate(&parameters)?[0]
+            / dataset.events[0].weight;
+        // 2Re(ac (bc)^*) = 2|c|^2 Re(ab^*)
+        let expected = 2.0 * (1.2 * 0.3 - 0.4 * 0.5);
+        let interference = ell.interference(&parameters, &dataset, &["a", "c"], &["b", "c"])?;
+        let par_interference =
+            ell.par_interference(&parameters, &dataset, &["a", "c"], &["b", "c"])?;
+        for (i, event) in dataset.events.iter().enumerate() {
+            assert_is_close!(interference[i], expected * scale * event.weight, f64);
+            assert_is_close!(interference[i], par_interference[i], f64);
+        }
+        let incoherent = ell.interference(&parameters, &dataset, &["a", "c"], &["b", "d"])?;
+        assert!(incoherent.iter().all(|i| i.abs() < 1e-12));
+        assert!(ell
+            .interference(&parameters, &dataset, &["a"], &["e"])
+            .is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_fit_fractions() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a"), scalar("b"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let (a, b) = (1.2, 0.3);
+        let s = a * a + b * b;
+        let covariance = vec![vec![0.01, 0.0], vec![0.0, 0.04]];
+        let fractions = ell.fit_fractions(
+            &[a, b],
+            &dataset,
+            &[("sum", vec!["a", "b"])],
+            Some(&covariance),
+        )?;
+        let par_fractions =
+            ell.par_fit_fractions(&[a, b], &dataset, &[("sum", vec!["a", "b"])], None)?;
+        assert_eq!(fractions.len(), 3);
+        assert_eq!(fractions[0].name, "a");
+        assert_eq!(fractions[2].amplitudes, vec!["a", "b"]);
+        assert_is_close!(fractions[0].value, a * a / s, f64);
+        assert_is_close!(fractions[1].value, b * b / s, f64);
+        assert_is_close!(fractions[2].value, 1.0, f64);
+        assert_is_close!(fractions[0].value, par_fractions[0].value, f64);
+        assert!(par_fractions[0].error.is_none());
+        // df/da = 2ab^2/s^2 and df/db = -2ba^2/s^2 for the fraction of "a"
+        let expected = ((2.0 * a * b * b / (s * s)).powi(2) * 0.01
+            + (2.0 * b * a * a / (s * s)).powi(2) * 0.04)
+            .sqrt();
+        assert_is_close!(fractions[0].error.unwrap(), expected, f64);
+        assert_is_close!(fractions[1].error.unwrap(), expected, f64);
+        assert!(fractions[2].error.unwrap() < 1e-8);
+        assert!(ell
+            .fit_fractions(&[a, b], &dataset, &[("bad", vec!["c"])], None)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_weight_covariance() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let n = dataset.len();
+        let w: f64 = dataset.weights().iter().sum();
+        let w2: f64 = dataset.weights().iter().map(|w| w * w).sum();
+        let model = model!(scalar("a"));
+        let mc_manager = Manager::new(&model, &dataset)?;
+        // -2ln(L) = -2w(2ln(a) - a^2) and each event has g = 2/a - 2a
+        let a = 1.3;
+        let hessian = 4.0 * w * (1.0 / (a * a) + 1.0);
+        let g = 2.0 / a - 2.0 * a;
+        let variance = |dataset: &Dataset<f64>| -> Result<f64, RustitudeError> {
+            let ell =
+                ExtendedLogLikelihood::new(Manager::new(&model, dataset)?, mc_manager.clone());
+            let covariance = ell.weighted_covariance(&[a])?;
+            assert_is_close!(
+                covariance[0][0],
+                ell.par_weighted_covariance(&[a])?[0][0],
+                f64
+            );
+            Ok(covariance[0][0])
+        };
+        let expected = (2.0 / hessian).powi(2) * g * g;
+        assert_is_close!(variance(&dataset)?, expected * w2, 1e-8, f64);
+        let errors = vec![0.5; n];
+        let independent = dataset
+            .clone()
+            .with_weight_covariance(WeightCovariance::from_errors(&errors))?;
+        assert_is_close!(
+            variance(&independent)?,
+            expected * 0.25 * n as f64,
+            1e-8,
+            f64
+        );
+        let correlated = dataset
+            .clone()
+            .with_weight_covariance(WeightCovariance::from_blocks(vec![(
+                (0..n).collect(),
+                vec![vec![0.25; n]; n],
+            )])?)?;
+        assert_is_close!(
+            variance(&correlated)?,
+            expected * 0.25 * (n * n) as f64,
+            1e-8,
+            f64
+        );
+
+        assert!(WeightCovariance::<f64>::from_blocks(vec![(vec![0, 1], vec![vec![1.0]])]).is_err());
+        assert!(WeightCovariance::from_blocks(vec![(
+            vec![0, 1],
+            vec![vec![1.0, 0.5], vec![0.2, 1.0]]
+        )])
+        .is_err());
+        assert!(WeightCovariance::from_blocks(vec![
+            (vec![0], vec![vec![1.0]]),
+            (vec![0], vec![vec![1.0]])
+        ])
+        .is_err());
+        assert!(dataset
+            .clone()
+            .with_weight_covariance(WeightCovariance::from_errors(&vec![1.0; n + 1]))
+            .is_err());
+
+        // Correlated events are resampled together
+        let block = WeightCovariance::from_blocks(vec![(vec![0, 1, 2], vec![vec![0.1; 3]; 3])])?;
+        let blocked = dataset.clone().with_weight_covariance(block)?;
+        for seed in 0..10 {
+            let indices = blocked.get_bootstrap_indices(seed);
+            let count = |i: usize| indices.iter().filter(|j| **j == i).count();
+            assert_eq!(count(0), count(1));
+            assert_eq!(count(1), count(2));
+        }
+        assert_eq!(blocked.weight_blocks().len(), n - 2);
+        let combined = dataset.clone() + blocked;
+        assert_eq!(
+            combined.weight_covariance.as_ref().unwrap().blocks()[0].0,
+            vec![n, n + 1, n + 2]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cached_normalization() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let edges: Vec<f64> = (0..=20).map(|i| 0.5 * i as f64).collect();
+        let rising: Vec<f64> = (0..20).map(|i| i as f64 + 1.0).collect();
+        let falling: Vec<f64> = (0..20).map(|i| 20.0 - i as f64).collect();
+        let model = model!(
+            template_hist_m("a", edges.clone(), rising)? * cscalar("ca")
+                + template_hist_m("b", edges, falling)? * pcscalar("cb")
+        );
+        let manager = Manager::new(&model, &dataset)?;
+        let uncached = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let mut ell = uncached.clone();
+        ell.cache_normalization()?;
+        assert_eq!(ell.normalization_integrals.as_ref().unwrap().n_terms(), 2);
+        let parameters = [1.0, 0.5, 0.3, -0.2];
+        let expected = uncached.evaluate(&parameters)?;
+        assert_is_close!(ell.evaluate(&parameters)?, expected, 1e-8, f64);
+        assert_is_close!(ell.par_evaluate(&parameters)?, expected, 1e-8, f64);
+        let (value, gradient) = ell.evaluate_with_gradient(&parameters)?;
+        assert_is_close!(value, expected, 1e-8, f64);
+        for (g, e) in gradient.iter().zip(uncached.gradient(&parameters)?) {
+            assert!((g - e).abs() < 1e-4 * (1.0 + e.abs()));
+        }
+        // A stale cache is ignored rather than used
+        let mut partial = ell.clone();
+        let mut partial_uncached = uncached.clone();
+        partial.deactivate("b")?;
+        partial_uncached.deactivate("b")?;
+        assert_is_close!(
+            partial.evaluate(&parameters)?,
+            partial_uncached.evaluate(&parameters)?,
+            1e-10,
+            f64
+        );
+        // Changing the acceptance clears the cache
+        ell.set_acceptance_fn(|event| 0.5 + 0.1 * (event.index % 4) as f64);
+        assert!(ell.normalization_integrals.is_none());
+        let accepted = ell.evaluate(&parameters)?;
+        ell.cache_normalization()?;
+        assert_is_close!(ell.evaluate(&parameters)?, accepted, 1e-8, f64);
+        Ok(())
+    }
+    #[test]
+    fn test_normalization_warm_start() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let edges: Vec<f64> = (0..=20).map(|i| 0.5 * i as f64).collect();
+        let rising: Vec<f64> = (0..20).map(|i| i as f64 + 1.0).collect();
+        let falling: Vec<f64> = (0..20).map(|i| 20.0 - i as f64).collect();
+        let model = model!(
+            template_hist_m("a", edges.clone(), rising)? * cscalar("ca")
+                + template_hist_m("b", edges, falling)? * pcscalar("cb")
+        );
+        let ell = |mc: &Dataset<f64>| -> Result<_, RustitudeError> {
+            Ok(ExtendedLogLikelihood::new(
+                Manager::new(&model, &dataset)?,
+                Manager::new(&model, mc)?,
+            ))
+        };
+        // The f32 stage only integrates half of the Monte-Carlo, so reused integrals can be
+        // told apart from recomputed ones
+        let half = dataset.shard(0, 2);
+        let model_32 = model.to_f32(&NodeRegistry::new())?;
+        let mut ell_32 = ExtendedLogLikelihood::new(
+            Manager::new(&model_32, &dataset.to_f32())?,
+            Manager::new(&model_32, &half.to_f32())?,
+        );
+        ell_32.cache_normalization()?;
+        let integrals_32 = ell_32.normalization_integrals.as_ref().unwrap();
+        let mut ell_64 = ell(&dataset)?;
+        ell_64.warm_start_normalization(integrals_32)?;
+        let parameters = [1.0, 0.5, 0.3, -0.2];
+        let reused = NormalizationIntegrals::from_lower_precision(integrals_32);
+        assert_eq!(
+            ell_64
+                .normalization_integrals
+                .as_ref()
+                .unwrap()
+                .mc_sum(&model, &parameters)?,
+            reused.mc_sum(&model, &parameters)?
+        );
+        let half_value = ell(&half)?.evaluate(&parameters)?;
+        let full_value = ell(&dataset)?.evaluate(&parameters)?;
+        assert!((half_value - full_value).abs() > 1e-2 * full_value.abs());
+        assert_is_close!(ell_64.evaluate(&parameters)?, half_value, 1e-5, f64);
+        // The final iterations recompute the integrals in f64
+        ell_64.cache_normalization()?;
+        assert_is_close!(ell_64.evaluate(&parameters)?, full_value, 1e-8, f64);
+        let mut partial = ell(&dataset)?;
+        partial.deactivate("b")?;
+        assert!(partial.warm_start_normalization(integrals_32).is_err());
+        assert!(partial.normalization_integrals.is_none());
+        Ok(())
+    }
+    #[test]
+    fn test_linear_likelihood() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let edges: Vec<f64> = (0..=20).map(|i| 0.5 * i as f64).collect();
+        let rising: Vec<f64> = (0..20).map(|i| i as f64 + 1.0).collect();
+        let falling: Vec<f64> = (0..20).map(|i| 20.0 - i as f64).collect();
+        let flat = vec![1.0; 20];
+        let model = model!(
+            (template_hist_m("a", edges.clone(), rising.clone())? * cscalar("ca")
+                + template_hist_m("b", edges.clone(), falling)? * cscalar("cb"))
+                * pcscalar("p"),
+            template_hist_m("c", edges.clone(), flat)? * scalar("s")
+        );
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager)
+            .with_intensity_floor(Some(IntensityFloor::Clamp(1e-3)));
+        ell.add_gaussian_constraint("s", "value", 0.5, 0.1)?;
+        let linear = LinearLikelihood::new(&ell)?;
+        assert_eq!(linear.n_terms(), 3);
+        assert_eq!(linear.get_n_free(), ell.get_n_free());
+        let parameters = [1.0, 0.5, 0.3, -0.2, 1.2, 0.4, 0.7];
+        let expected = ell.evaluate(&parameters)?;
+        assert_is_close!(linear.evaluate(&parameters)?, expected, 1e-8, f64);
+        assert_is_close!(linear.par_evaluate(&parameters)?, expected, 1e-8, f64);
+        let gradient = linear.gradient(&parameters)?;
+        for (g, e) in gradient.iter().zip(ell.gradient(&parameters)?) {
+            assert!((g - e).abs() < 1e-4 * (1.0 + e.abs()));
+        }
+        // Deactivated amplitudes are skipped in products just like in the ExtendedLogLikelihood,
+        // so "cb" * "p" remains as a constant term
+        let mut partial = ell.clone();
+        partial.deactivate("b")?;
+        let linear = LinearLikelihood::new(&partial)?;
+        assert_eq!(linear.n_terms(), 3);
+        assert_is_close!(
+            linear.evaluate(&parameters)?,
+            partial.evaluate(&parameters)?,
+            1e-8,
+            f64
+        );
+        // Production coefficients must not depend on the event
+        let model = model!(
+            template_hist_m("a", edges.clone(), rising.clone())? * piecewise_m("p", 4, (0.5, 2.0))
+        );
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        assert!(LinearLikelihood::new(&ell).is_err());
+        let model = model!((template_hist_m("a", edges, rising)? * cscalar("c")).real());
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        assert!(LinearLikelihood::new(&ell).is_err());
+        ell.add_signal_yield();
+        assert!(LinearLikelihood::new(&ell).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_storage_precision() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let edges: Vec<f64> = (0..=20).map(|i| 0.5 * i as f64).collect();
+        let rising: Vec<f64> = (0..20).map(|i| i as f64 + 1.0).collect();
+        let falling: Vec<f64> = (0..20).map(|i| 20.0 - i as f64).collect();
+        let model = model!(
+            template_hist_m("a", edges.clone(), rising)? * cscalar("ca")
+                + template_hist_m("b", edges, falling)? * cscalar("cb")
+        );
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let full = LinearLikelihood::new(&ell)?;
+        assert_eq!(full.storage(), StoragePrecision::Full);
+        let parameters = [1.0, 0.5, 0.3, -0.2];
+        let expected = full.evaluate(&parameters)?;
+        for (storage, tolerance) in [
+            (StoragePrecision::Single, 1e-6),
+            (StoragePrecision::Half, 1e-2),
+        ] {
+            let linear = LinearLikelihood::new_with_storage(&ell, storage)?;
+            assert_eq!(linear.storage(), storage);
+            assert_is_close!(linear.evaluate(&parameters)?, expected, tolerance, f64);
+            assert_is_close!(linear.par_evaluate(&parameters)?, expected, tolerance, f64);
+        }
+        assert_eq!("f16".parse::<StoragePrecision>()?, StoragePrecision::Half);
+        assert!("double".parse::<StoragePrecision>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hessian() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let w: f64 = dataset.weights().iter().sum();
+        let model = model!(scalar("a"), scalar("b"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        // -2ln(L) = -2w(ln(s) - s) with s = a^2 + b^2
+        let (a, b) = (1.2, 0.3);
+        let s = a * a + b * b;
+        let expected = [
+            [
+                -2.0 * w * (2.0 / s - 4.0 * a * a / (s * s) - 2.0),
+                8.0 * w * a * b / (s * s),
+            ],
+            [
+                8.0 * w * a * b / (s * s),
+                -2.0 * w * (2.0 / s - 4.0 * b * b / (s * s) - 2.0),
+            ],
+        ];
+        let hessian = ell.hessian(&[a, b])?;
+        let par_hessian = ell.par_hessian(&[a, b])?;
+        for i in 0..2 {
+            for j in 0..2 {
+                assert_is_close!(hessian[i][j], expected[i][j], 1e-6, f64);
+                assert_is_close!(hessian[i][j], par_hessian[i][j], f64);
+            }
+        }
+        let covariance = ell.covariance(&[a, b])?;
+        for (i, row) in covariance.iter().enumerate() {
+            for j in 0..2 {
+                let product: f64 = row.iter().zip(&hessian).map(|(c, h)| c * h[j]).sum();
+                assert!((product - if i == j { 2.0 } else { 0.0 }).abs() < 1e-8);
+            }
+        }
+        let rho = correlation(&covariance);
+        assert_is_close!(rho[0][0], 1.0, f64);
+        assert_is_close!(rho[1][1], 1.0, f64);
+        assert_is_close!(
+            rho[0][1],
+            covariance[0][1] / (covariance[0][0] * covariance[1][1]).sqrt(),
+            f64
+        );
+        let fit_result = FitResult::new(&ell, &[a, b], ell.evaluate(&[a, b])?, Some(covariance))?;
+        assert_eq!(fit_result.correlation(), Some(rho));
+        Ok(())
+    }
+
+    #[test]
+    fn test_yields() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let w: f64 = dataset.weights().iter().sum();
+        let model = model!(scalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        ell.add_signal_yield();
+        assert_eq!(ell.get_n_free(), 2);
+        assert_eq!(ell.get_initial(), vec![1.0, w]);
+        assert_eq!(ell.get_parameter(YIELD_AMPLITUDE, "signal")?.index, Some(1));
+        // A constant intensity gives -2ln(L) = -2(w ln(N) - N), independent of "a"
+        assert_is_close!(
+            ell.evaluate(&[3.0, 0.5 * w])?,
+            -2.0 * (w * f64::ln(0.5 * w) - 0.5 * w),
+            f64
+        );
+        let intensity = ell.intensity(&[3.0, 0.5 * w], &dataset)?;
+        assert_is_close!(intensity.iter().sum::<f64>(), 0.5 * w, f64);
+
+        #[derive(Clone)]
+        struct Energy;
+        impl Node<f64> for Energy {
+            fn calculate(
+                &self,
+                parameters: &[f64],
+                event: &Event<f64>,
+            ) -> Result<Complex<f64>, RustitudeError> {
+                Ok(Complex::new(
+                    parameters[0] * event.daughter_p4s[0].e(),
+                    parameters[1],
+                ))
+            }
+            fn parameters(&self) -> Vec<String> {
+                vec!["slope".to_string(), "offset".to_string()]
+            }
+        }
+        let mut model = model!(
+            (scalar("a") + cscalar("b")) * pcscalar("c") + Energy.named("d"),
+            scalar("e") * cscalar("f").imag(),
+        );
+        model.fix("b", "imag", 0.5)?;
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let template: Vec<f64> = dataset
+            .events
+            .iter()
+            .map(|event| event.daughter_p4s[1].e())
+            .collect();
+        ell.add_background_template(template.clone(), template.clone())?;
+        assert!(ell
+            .clone()
+            .add_background_template(vec![1.0], template)
+            .is_err());
+        assert_eq!(ell.get_n_free(), model.get_n_free() + 2);
+        ell.set_initial(YIELD_AMPLITUDE, "background", 0.2 * w)?;
+        assert_eq!(ell.get_initial()[model.get_n_free() + 1], 0.2 * w);
+        let parameters: Vec<f64> = (0..ell.get_n_free())
+            .map(|i| {
+                if i < model.get_n_free() {
+                    0.3 * i as f64 + 0.7
+                } else {
+                    0.4 * w
+                }
+            })
+            .collect();
+        let gradient = ell.gradient(&parameters)?;
+        assert_eq!(gradient.len(), parameters.len());
+        for (g, par_g) in gradient.iter().zip(ell.par_gradient(&parameters)?) {
+            assert!((g - par_g).abs() < 1e-8 * (1.0 + g.abs()));
+        }
+        assert_is_close!(
+            ell.evaluate(&parameters)?,
+            ell.par_evaluate(&parameters)?,
+            f64
+        );
+        for i in 0..parameters.len() {
+            let h = 1e-6 * (parameters[i].abs() + 1.0);
+            let mut plus = parameters.clone();
+            plus[i] += h;
+            let mut minus = parameters.clone();
+            minus[i] -= h;
+            let numeric = (ell.evaluate(&plus)? - ell.evaluate(&minus)?) / (2.0 * h);
+            assert!((gradient[i] - numeric).abs() < 1e-5 * (1.0 + numeric.abs()));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "ganesh")]
+    #[test]
+    fn test_minos() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        // -2ln(L) = -2w(ln(a^2) - a^2) is minimized at a = 1
+        let fx = ell.evaluate(&[1.0])?;
+        let (lower, upper) = ell.minos(&[1.0], fx, 0, 1.0, 0.5, 1000)?;
+        let (lower, upper) = (lower.unwrap(), upper.unwrap());
+        assert!(lower < 0.0 && upper > 0.0);
+        assert!((lower + upper).abs() > 1e-3);
+        assert_is_close!(ell.evaluate(&[1.0 + lower])? - fx, 1.0, 1e-3, f64);
+        assert_is_close!(ell.evaluate(&[1.0 + upper])? - fx, 1.0, 1e-3, f64);
+        assert!(ell.minos(&[1.0], fx, 1, 1.0, 0.5, 1000).is_err());
+
+        let mut bounded = ell.clone();
+        bounded.set_bounds("a", "value", (0.0, 1.0 + 0.5 * upper))?;
+        assert_eq!(bounded.minos(&[1.0], fx, 0, 1.0, 0.5, 1000)?.1, None);
+
+        let mut fit_result = FitResult::new(&ell, &[1.0], fx, None)?;
+        fit_result.compute_minos_errors(&ell, 1.0, 1000)?;
+        let (fit_lower, fit_upper) = fit_result.minos_error("a", "value")?.unwrap();
+        assert_is_close!(fit_lower.unwrap(), lower, 1e-3, f64);
+        assert_is_close!(fit_upper.unwrap(), upper, 1e-3, f64);
+        let reloaded: FitResult<f64> = FitResult::from_json(&fit_result.to_json()?)?;
+        assert_eq!(reloaded.minos_errors, fit_result.minos_errors);
+        Ok(())
+    }
+
+    #[cfg(feature = "ganesh")]
+    #[test]
+    fn test_profile() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let w: f64 = dataset.weights().iter().sum();
+        let model = model!(scalar("a"), scalar("b"));
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        // -2ln(L) = -2w(ln(s) - s) with s = a^2 + b^2 is minimized at s = 1 if |a| <= 1 and at
+        // b = 0 otherwise
+        let expected = [2.0 * w, -2.0 * w * (f64::ln(2.25) - 2.25)];
+        let profile = ell.profile("a", "value", &[0.5, 1.5], 1000)?;
+        let par_profile = ell.par_profile("a", "value", &[0.5, 1.5], 1000)?;
+        for i in 0..2 {
+            assert_is_close!(profile[i], expected[i], 1e-6, f64);
+            assert_is_close!(par_profile[i], expected[i], 1e-6, f64);
+        }
+        ell.fix("b", "value", 0.0)?;
+        assert!(ell.profile("b", "value", &[0.5], 1000).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "ganesh")]
+    #[test]
+    fn test_multistart() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        ell.set_bounds("a", "value", (-2.0, 2.0))?;
+        // -2ln(L) = -2w(ln(a^2) - a^2) has two minima at a = -1 and a = 1
+        let fx = ell.evaluate(&[1.0])?;
+        let results = ell.multistart(8, 7, 1000)?;
+        let par_results = ell.par_multistart(8, 7, 1000)?;
+        assert_eq!(results.len(), 8);
+        assert!(results.windows(2).all(|pair| pair[0].fx <= pair[1].fx));
+        for (result, par_result) in results.iter().zip(&par_results) {
+            assert_is_close!(result.fx, fx, 1e-6, f64);
+            assert_is_close!(result.best[0].abs(), 1.0, 1e-3, f64);
+            assert_is_close!(par_result.fx, result.fx, 1e-9, f64);
+        }
+        assert!(results.iter().any(|result| result.best[0] < 0.0));
+        assert!(results.iter().any(|result| result.best[0] > 0.0));
+        let reseeded = ell.multistart(8, 7, 1000)?;
+        for (result, reseeded) in results.iter().zip(&reseeded) {
+            assert_eq!(result.best, reseeded.best);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_phase_space() -> Result<(), RustitudeError> {
+        assert!(Sobol::new(0).is_err());
+        assert!(Sobol::new(Sobol::MAX_DIMENSION + 1).is_err());
+        let sobol = Sobol::new(2)?;
+        assert_eq!(sobol.point(0), vec![0.0, 0.0]);
+        assert_eq!(sobol.point(1), vec![0.5, 0.5]);
+        assert_eq!(sobol.point(2), vec![0.75, 0.25]);
+        assert_eq!(sobol.point(3), vec![0.25, 0.75]);
+        assert!(PhaseSpace::new(0.1, 0.938_272, 0.938_272, vec![0.139_570]).is_err());
+        // Two-body phase space has a single breakup momentum, so every weight is equal
+        let two_body = PhaseSpace::new(8.5, 0.938_272, 0.938_272, vec![0.547_862])?;
+        let dataset = two_body.generate(256, 0)?;
+        for event in dataset.events.iter() {
+            assert_is_close!(event.weight, dataset.events[0].weight, 1e-9, f64);
+            assert_is_close!(event.recoil_p4.m(), 0.938_272, 1e-6, f64);
+            let total = event.recoil_p4 + event.daughter_p4s[0];
+            assert_is_close!(total.pz(), 8.5, 1e-9, f64);
+            assert_is_close!(total.e(), 8.5 + 0.938_272, 1e-9, f64);
+        }
+        // For three massless particles, the mean weight q_2 q_3 is s / 16
+        let three_body: PhaseSpace<f64> = PhaseSpace::new(8.5, 0.938_272, 0.0, vec![0.0, 0.0])?;
+        let s = three_body.cm_energy().powi(2);
+        let dataset = three_body.generate(4096, 0)?;
+        let mean = dataset.weights().iter().sum::<f64>() / 4096.0;
+        assert_is_close!(mean, s / 16.0, 1e-3, f64);
+        // Later batches continue the same sequence
+        let extension = three_body.generate(16, 4080)?;
+        assert_eq!(
+            extension.events[0].daughter_p4s[0],
+            dataset.events[4080].daughter_p4s[0]
+        );
+        let data = generate_test_dataset_f64();
+        let model = model!(scalar("a"));
+        let ell = ExtendedLogLikelihood::from_phase_space(
+            Manager::new(&model, &data)?,
+            &two_body,
+            &[1.0],
+            1e-6,
+            1 << 16,
+        )?;
+        // A constant intensity is integrated exactly by the first two batches
+        assert_eq!(ell.mc_manager.dataset.len(), 2048);
+        assert!(ell
+            .mc_manager
+            .dataset
+            .events
+            .iter()
+            .enumerate()
+            .all(|(i, event)| event.index == i));
+        assert!(ell.evaluate(&[1.0])?.is_finite());
+        let capped = ExtendedLogLikelihood::from_phase_space(
+            Manager::new(&model, &data)?,
+            &two_body,
+            &[1.0],
+            0.0,
+            1500,
+        )?;
+        assert_eq!(capped.mc_manager.dataset.len(), 1500);
+        Ok(())
+    }
+
+    #[cfg(feature = "ganesh")]
+    #[test]
+    fn test_toy_study() -> Result<(), RustitudeError> {
+        let unweighted = |n_events: usize| {
+            Dataset::new(
+                generate_test_dataset_f64()
+                    .events
+                    .iter()
+                    .cycle()
+                    .take(n_events)
+                    .enumerate()
+                    .map(|(index, event)| Event {
+                        index,
+                        weight: 1.0,
+                        ..event.clone()
+                    })
+                    .collect(),
+            )
+        };
+        let dataset = unweighted(400);
+        let model = model!(scalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        ell.fix("a", "value", 1.0)?;
+        ell.add_signal_yield();
+        assert!(ToyStudy::new(ell.clone(), &[400.0, 1.0]).is_err());
+        assert!(ToyStudy::new(ell.clone(), &[400.0])?
+            .with_events(401)
+            .generate(0, 3)
+            .is_err());
+        let study = ToyStudy::new(ell.clone(), &[400.0])?.with_pool(unweighted(4000));
+        // The toy size fluctuates around the predicted yield of 400 events
+        let (truth, toy) = study.generate(0, 3)?;
+        assert_eq!(truth, vec![400.0]);
+        assert!(toy.len() > 300 && toy.len() < 500);
+        assert!(toy.events.iter().all(|event| event.weight == 1.0));
+        let bootstrap = study
+            .clone()
+            .with_source(ToySource::Bootstrap)
+            .with_events(1000);
+        assert_eq!(bootstrap.generate(0, 3)?.1.len(), 1000);
+        let result = study.run(20, 3)?;
+        let par_result = study.par_run(20, 3)?;
+        assert_eq!(result.len(), 20);
+        assert_eq!(result.index(&format!("{YIELD_AMPLITUDE}::signal"))?, 0);
+        for (toy, par_toy) in result.toys.iter().zip(&par_result.toys) {
+            assert_eq!(toy.n_events, par_toy.n_events);
+            assert_is_close!(toy.fit.best[0], par_toy.fit.best[0], 1e-9, f64);
+            // The fitted yield is the number of events, with an error of sqrt(N)
+            let n_events = toy.n_events as f64;
+            assert_is_close!(toy.fit.best[0], n_events, 1e-3, f64);
+            assert_is_close!(toy.errors[0], n_events.sqrt(), 1e-3, f64);
+        }
+        let summary = &result.summary()[0];
+        assert_eq!(summary.n_toys, 20);
+        assert!(summary.pull_mean.abs() < 4.0 * summary.pull_mean_error);
+        assert!(summary.pull_std > 0.5 && summary.pull_std < 1.5);
+        let smeared = ToyStudy::new(ell, &[400.0])?
+            .with_truth_covariance(&[vec![100.0]])?
+            .generate(0, 3)?;
+        assert!(smeared.0[0] != 400.0);
+        let reloaded = ToyStudyResult::<f64>::from_json(&result.to_json()?)?;
+        assert_eq!(reloaded.names, result.names);
+        for (reloaded, toy) in reloaded.toys.iter().zip(&result.toys) {
+            assert_eq!(reloaded.n_events, toy.n_events);
+            assert_is_close!(reloaded.pulls()[0], toy.pulls()[0], 1e-12, f64);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "ganesh")]
+    #[test]
+    fn test_observer() -> Result<(), RustitudeError> {
+        use std::sync::{Arc, Mutex};
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let sink = records.clone();
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager).with_observer(
+            move |iteration: usize, fx: f64, parameters: &[f64]| {
+                sink.lock()
+                    .unwrap()
+                    .push((iteration, fx, parameters.to_vec()));
+            },
+        );
+        let fx = ell.evaluate(&[1.0])?;
+        let par_fx = ell.par_evaluate(&[2.0])?;
+        assert_eq!(ell.evaluations(), 2);
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![(1, fx, vec![1.0]), (2, par_fx, vec![2.0])]
+        );
+        // The drivers report the best point after each step instead of every evaluation
+        records.lock().unwrap().clear();
+        ell.set_bounds("a", "value", (0.0, 2.0))?;
+        let results = ell.multistart(1, 7, 50)?;
+        let steps = records.lock().unwrap().clone();
+        assert_eq!(ell.evaluations(), 2);
+        assert!(!steps.is_empty() && steps.len() <= 50);
+        assert!(steps
+            .iter()
+            .enumerate()
+            .all(|(i, (iteration, _, parameters))| *iteration == i + 1 && parameters.len() == 1));
+        assert!(steps.windows(2).all(|pair| pair[1].1 <= pair[0].1));
+        assert_eq!(steps[steps.len() - 1].1, results[0].fx);
+        ell.clear_observer();
+        ell.evaluate(&[1.0])?;
+        assert_eq!(ell.evaluations(), 2);
+        Ok(())
+    }
+
+    // A foreign node which stores the resonance mass of each event and returns scale * mass
+    mod foreign_node {
+        use rustitude_core::ffi::{CComplex, CEvent};
+        use std::ffi::{c_char, c_void};
+
+        pub unsafe extern "C" fn n_parameters(_state: *const c_void) -> usize {
+            1
+        }
+
+        pub unsafe extern "C" fn parameter_name(
+            _state: *const c_void,
+            _index: usize,
+        ) -> *const c_char {
+            c"scale".as_ptr()
+        }
+
+        pub unsafe extern "C" fn precalculate(
+            state: *mut c_void,
+            events: *const CEvent,
+            n_events: usize,
+        ) -> i32 {
+            let masses = &mut *(state as *mut Vec<f64>);
+            *masses = std::slice::from_raw_parts(events, n_events)
+                .iter()
+                .map(|event| {
+                    let daughters =
+                        std::slice::from_raw_parts(event.daughter_p4s, event.n_daughters);
+                    let (e, px, py, pz) = daughters.iter().fold((0.0, 0.0, 0.0, 0.0), |acc, p4| {
+                        (acc.0 + p4.e, acc.1 + p4.px, acc.2 + p4.py, acc.3 + p4.pz)
+                    });
+                    (e * e - px * px - py * py - pz * pz).sqrt()
+                })
+                .collect();
+            0
+        }
+
+        pub unsafe extern "C" fn calculate(
+            state: *const c_void,
+            parameters: *const f64,
+            n_parameters: usize,
+            event: *const CEvent,
+            result: *mut CComplex,
+        ) -> i32 {
+            let masses = &*(state as *const Vec<f64>);
+            let parameters = std::slice::from_raw_parts(parameters, n_parameters);
+            let Some(mass) = masses.get((*event).index) else {
+                return 1;
+            };
+            *result = CComplex {
+                re: parameters[0] * mass,
+                im: 0.0,
+            };
+            0
+        }
+
+        pub unsafe extern "C" fn clone(state: *const c_void) -> *mut c_void {
+            Box::into_raw(Box::new((*(state as *const Vec<f64>)).clone())) as *mut c_void
+        }
+
+        pub unsafe extern "C" fn drop(state: *mut c_void) {
+            std::mem::drop(Box::from_raw(state as *mut Vec<f64>));
+        }
+    }
+
+    #[test]
+    fn test_foreign_node() -> Result<(), RustitudeError> {
+        use rustitude_core::ffi::{CNode, CNodeVTable, NODE_ABI_VERSION};
+        let vtable = CNodeVTable {
+            abi_version: NODE_ABI_VERSION,
+            n_parameters: foreign_node::n_parameters,
+            parameter_name: foreign_node::parameter_name,
+            precalculate: Some(foreign_node::precalculate),
+            calculate: foreign_node::calculate,
+            clone: foreign_node::clone,
+            drop: foreign_node::drop,
+        };
+        let new_state = || Box::into_raw(Box::new(Vec::<f64>::new())).cast();
+        let node = unsafe { CNode::new(vtable, new_state())? };
+        assert_eq!(Node::<f64>::parameters(&node), vec!["scale".to_string()]);
+        let dataset = generate_test_dataset_f64();
+        let model = model!(node.named("foreign"));
+        let manager = Manager::new(&model, &dataset)?;
+        let values = manager.evaluate(&[2.0])?;
+        for (value, event) in values.iter().zip(dataset.events.iter()) {
+            let mass = event
+                .daughter_p4s
+                .iter()
+                .copied()
+                .sum::<FourMomentum<f64>>()
+                .m();
+            assert_is_close!(*value, 4.0 * mass * mass, 1e-8, f64);
+        }
+        // A node which was never precalculated reports the failure of the foreign function
+        let unloaded = unsafe { CNode::new(vtable, new_state())? };
+        assert!(matches!(
+            Node::<f64>::calculate(&unloaded, &[1.0], &dataset.events[0]),
+            Err(RustitudeError::EvaluationError(_))
+        ));
+        let incompatible = CNodeVTable {
+            abi_version: NODE_ABI_VERSION + 1,
+            ..vtable
+        };
+        assert!(unsafe { CNode::new(incompatible, new_state()) }.is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_shared_quantities() -> Result<(), RustitudeError> {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+        #[derive(Clone)]
+        struct Scaled {
+            scale: f64,
+            computed: Arc<AtomicUsize>,
+            data: Vec<f64>,
+        }
+        impl Node<f64> for Scaled {
+            fn precalculate(&mut self, dataset: &Dataset<f64>) -> Result<(), RustitudeError> {
+                let shared = SharedData::compute(&self.requirements(), dataset)?;
+                self.precalculate_shared(dataset, &shared)
+            }
+            fn requirements(&self) -> Vec<SharedQuantity<f64>> {
+                let computed = self.computed.clone();
+                vec![SharedQuantity::new(
+                    "mass",
+                    move |dataset: &Dataset<f64>, _| {
+                        computed.fetch_add(1, Ordering::Relaxed);
+                        Ok(dataset
+                            .events
+                            .iter()
+                            .map(|event| (event.daughter_p4s[0] + event.daughter_p4s[1]).m())
+                            .collect::<Vec<f64>>())
+                    },
+                )]
+            }
+            fn precalculate_shared(
+                &mut self,
+                _dataset: &Dataset<f64>,
+                shared: &SharedData,
+            ) -> Result<(), RustitudeError> {
+                let masses = shared.require::<Vec<f64>>("mass")?;
+                self.data = masses.iter().map(|m| self.scale * m).collect();
+                Ok(())
+            }
+            fn calculate(
+                &self,
+                _parameters: &[f64],
+                event: &Event<f64>,
+            ) -> Result<Complex<f64>, RustitudeError> {
+                Ok(Complex::new(self.data[event.index], 0.0))
+            }
+        }
+        let computed = Arc::new(AtomicUsize::new(0));
+        let scaled = |scale: f64| Scaled {
+            scale,
+            computed: computed.clone(),
+            data: Vec::new(),
+        };
+        let dataset = generate_test_dataset_f64();
+        let model = model!(scaled(1.0).named("a") + scaled(2.0).named("b"));
+        let manager = Manager::new(&model, &dataset)?;
+        // Both amplitudes read the same masses, which are computed once
+        assert_eq!(computed.load(Ordering::Relaxed), 1);
+        for (value, event) in manager.evaluate(&[])?.iter().zip(dataset.events.iter()) {
+            let mass = (event.daughter_p4s[0] + event.daughter_p4s[1]).m();
+            assert_is_close!(*value, 9.0 * mass * mass, 1e-8, f64);
+        }
+        // A Node which is precalculated alone computes its own requirements
+        let mut alone = scaled(3.0);
+        alone.precalculate(&dataset)?;
+        assert_eq!(computed.load(Ordering::Relaxed), 2);
+        assert_eq!(alone.data.len(), dataset.len());
+
+        // Dependencies are computed first and quantities are not computed twice
+        let base = SharedQuantity::new("base", |_: &Dataset<f64>, _| Ok(2.0_f64));
+        let double = SharedQuantity::new("double", |_: &Dataset<f64>, shared| {
+            Ok(2.0 * shared.require::<f64>("base")?)
+        })
+        .with_dependencies(vec![base.clone()]);
+        let shared = SharedData::compute(&[double, base], &dataset)?;
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared.get::<f64>("double"), Some(&4.0));
+        assert!(shared.get::<f32>("double").is_none());
+        assert!(matches!(
+            shared.require::<f64>("missing"),
+            Err(RustitudeError::EvaluationError(_))
+        ));
+        Ok(())
+    }
+    #[test]
+    fn test_dataset_kinematics() -> Result<(), RustitudeError> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let dataset = generate_test_dataset_f64();
+        let computed = AtomicUsize::new(0);
+        let energy = |event: &Event<f64>| {
+            computed.fetch_add(1, Ordering::Relaxed);
+            Ok(event.beam_p4.e())
+        };
+        let first = dataset.kinematics("beam_energy", energy)?;
+        // Clones share the cache, so the array is only computed once
+        let second = dataset.clone().kinematics("beam_energy", energy)?;
+        assert_eq!(computed.load(Ordering::Relaxed), dataset.len());
+        assert_eq!(first, second);
+        assert_is_close!(first[0], dataset.events[0].beam_p4.e(), f64);
+        // Errors are propagated and nothing is stored
+        assert!(dataset
+            .kinematics("broken", |_| Err(RustitudeError::EvaluationError(
+                "broken".to_string()
+            )))
+            .is_err());
+        assert_eq!(dataset.kinematics_keys(), vec!["beam_energy".to_string()]);
+
+        let masses = dataset.standard_kinematics("resonance_mass")?;
+        let costheta = dataset.standard_kinematics("helicity_costheta")?;
+        for (i, event) in dataset.events.iter().enumerate() {
+            let resonance = event.daughter_p4s[0] + event.daughter_p4s[1];
+            assert_is_close!(masses[i], resonance.m(), f64);
+            let (expected, _) = rustitude_core::frames::decay_angles(
+                rustitude_core::frames::Frame::Helicity,
+                event,
+                &resonance,
+                &event.daughter_p4s[0],
+            );
+            assert_is_close!(costheta[i], expected, f64);
+        }
+        assert!(matches!(
+            dataset.standard_kinematics("mass"),
+            Err(RustitudeError::ParseError(_))
+        ));
+        // Nodes which need the same mass share the array
+        let model = model!(Gaussian::new(&[0, 1])?.named("g"));
+        Manager::new(&model, &dataset)?;
+        assert!(dataset
+            .kinematics_keys()
+            .contains(&"resonance_mass[0, 1]".to_string()));
+        // Transforms produce a new cache, while clearing affects every clone
+        let boosted = dataset.clone().boost_to_com()?;
+        assert!(boosted.kinematics_keys().is_empty());
+        dataset.clone().clear_kinematics();
+        assert!(dataset.kinematics_keys().is_empty());
+        Ok(())
+    }
+    #[test]
+    fn test_node_parameter_defaults() -> Result<(), RustitudeError> {
+        #[derive(Clone)]
+        struct Width;
+        impl Node<f64> for Width {
+            fn calculate(
+                &self,
+                parameters: &[f64],
+                _event: &Event<f64>,
+            ) -> Result<Complex<f64>, RustitudeError> {
+                Ok(Complex::new(parameters[0] * parameters[1], parameters[2]))
+            }
+            fn parameters(&self) -> Vec<String> {
+                vec![
+                    "scale".to_string(),
+                    "width".to_string(),
+                    "offset".to_string(),
+                ]
+            }
+            fn initial_values(&self) -> Vec<f64> {
+                vec![2.0, 0.1]
+            }
+            fn parameter_bounds(&self) -> Vec<(f64, f64)> {
+                vec![(-5.0, 5.0), (0.0, 1.0)]
+            }
+        }
+        let mut model = model!(cscalar("c") * Width.named("w"), pcscalar("p"));
+        // Missing trailing entries keep the usual defaults
+        assert_eq!(model.get_initial(), vec![1.0, 1.0, 2.0, 0.1, 1.0, 1.0, 1.0]);
+        let bounds = model.get_bounds();
+        assert_eq!(bounds[0], (f64::NEG_INFINITY, f64::INFINITY));
+        assert_eq!(&bounds[2..4], &[(-5.0, 5.0), (0.0, 1.0)]);
+        assert_eq!(bounds[4], (f64::NEG_INFINITY, f64::INFINITY));
+        assert_eq!(bounds[5], (0.0, f64::INFINITY));
+        assert_eq!(bounds[6], (-std::f64::consts::PI, std::f64::consts::PI));
+        // The logits of an incoherent sum start with equal fractions
+        model.add_incoherent_sum("bkg", &[Box::new(scalar("x")), Box::new(scalar("y"))])?;
+        assert_eq!(
+            model.get_parameter("bkg fraction 0", "logit 1")?.initial,
+            0.0
+        );
+        // The defaults can still be overridden
+        model.set_bounds("w", "width", (0.0, 2.0))?;
+        model.set_initial("w", "scale", 3.0)?;
+        assert_eq!(model.get_bounds()[3], (0.0, 2.0));
+        assert_eq!(model.get_initial()[2], 3.0);
+        Ok(())
+    }
+    #[test]
+    fn test_scaled_amplitudes() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let cg = -f64::sqrt(1.0 / 3.0);
+        let scaled = model!(cscalar("a").scaled(cg) + cscalar("b"));
+        let mut fixed = model!(scalar("cg") * cscalar("a") + cscalar("b"));
+        fixed.fix("cg", "value", cg)?;
+        // The scale does not introduce a parameter
+        assert_eq!(scaled.get_n_free(), 4);
+        let parameters = [0.3, -1.2, 0.8, 0.4];
+        let scaled_manager = Manager::new(&scaled, &dataset)?;
+        let fixed_manager = Manager::new(&fixed, &dataset)?;
+        for (a, b) in scaled_manager
+            .evaluate(&parameters)?
+            .iter()
+            .zip(fixed_manager.evaluate(&parameters)?)
+        {
+            assert_is_close!(*a, b, 1e-12, f64);
+        }
+        let event = &dataset.events[0];
+        let amplitude = cscalar("c").scaled(2.0).scaled(1.5);
+        assert_is_close!(amplitude.scale, 3.0, f64);
+        let gradient = amplitude.calculate_gradient(&[1.0, 2.0], event)?;
+        assert_is_close!(gradient[0].re, 3.0, f64);
+        assert_is_close!(gradient[1].im, 3.0, f64);
+        // The scale is kept when the model is serialized
+        let spec = scaled.to_spec()?;
+        assert_eq!(spec.amplitudes[0].scale, Some(cg));
+        assert_eq!(spec.amplitudes[1].scale, None);
+        let rebuilt = Model::from_spec(&spec, &NodeRegistry::new())?;
+        assert_is_close!(rebuilt.get_amplitude("a")?.scale, cg, f64);
+        Ok(())
+    }
+    #[test]
+    fn test_activation_snapshot() -> Result<(), RustitudeError> {
+        let dataset = Dataset::new(vec![generate_test_event_f64()]);
+        let mut model = model!(scalar("a") + scalar("b"), scalar("c"));
+        model.deactivate("b")?;
+        let snapshot = model.activation_state();
+        assert_eq!(
+            snapshot.amplitudes,
+            vec![
+                ("a".to_string(), true),
+                ("b".to_string(), false),
+                ("c".to_string(), true),
+            ]
+        );
+        assert_eq!(snapshot.sums, vec![true, true]);
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let parameters = [1.0, 10.0, 100.0];
+        let before = ell.data_manager.evaluate(&parameters)?[0];
+        ell.isolate(vec!["c"])?;
+        assert_is_close!(ell.mc_manager.evaluate(&parameters)?[0], 10000.0, f64);
+        ell.restore(&snapshot)?;
+        assert_eq!(ell.data_manager.activation_state(), snapshot);
+        assert_eq!(ell.mc_manager.activation_state(), snapshot);
+        assert_is_close!(ell.mc_manager.evaluate(&parameters)?[0], before, f64);
+        // A snapshot of a different model is rejected without changing anything
+        let other = model!(scalar::<f64>("a") + scalar("d")).activation_state();
+        ell.deactivate("a")?;
+        let current = ell.activation_state();
+        assert!(matches!(
+            ell.restore(&other),
+            Err(RustitudeError::AmplitudeNotFoundError(_))
+        ));
+        assert_eq!(ell.activation_state(), current);
+        Ok(())
+    }
+    #[test]
+    fn test_rename_amplitude() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let mut model = model!(cscalar("amp") * scalar("s"), cscalar("amp 2") * scalar("s"));
+        model.fix("s", "value", 2.0)?;
+        model.add_gaussian_constraint("amp", "real", 1.0, 0.1)?;
+        let before =
+            Manager::new(&model.deep_clone(), &dataset)?.evaluate(&[0.3, -0.2, 0.5, 0.1])?;
+        model.rename_amplitude("amp", "f0(980)")?;
+        assert!(model.get_amplitude("amp").is_err());
+        assert_eq!(model.get_parameter("f0(980)", "imag")?.index, Some(1));
+        assert_eq!(model.gaussian_constraints[0].amplitude, "f0(980)");
+        assert!(model.cohsums[0]
+            .walk()
+            .iter()
+            .any(|amp| amp.name == "f0(980)"));
+        let after = Manager::new(&model, &dataset)?.evaluate(&[0.3, -0.2, 0.5, 0.1])?;
+        assert_eq!(before, after);
+        assert!(matches!(
+            model.rename_amplitude("f0(980)", "amp 2"),
+            Err(RustitudeError::InvalidParameterValue(_))
+        ));
+        // The history follows the new name and can still be undone
+        assert_eq!(
+            model.history()[1].to_string(),
+            "add_gaussian_constraint(f0(980)::real, 1, 0.1)"
+        );
+        model.undo();
+        assert!(model.gaussian_constraints.is_empty());
+        assert_eq!(model.get_parameter("f0(980)", "real")?.index, Some(0));
+
+        // Aliases group parameters under friendly names
+        model.alias("phase", "f0(980)", "imag")?;
+        model.alias("phase", "amp 2", "imag")?;
+        assert_eq!(model.matching_parameters("phase").len(), 2);
+        model.constrain_matching("phase")?;
+        assert_eq!(
+            model.get_parameter("f0(980)", "imag")?.index,
+            model.get_parameter("amp 2", "imag")?.index
+        );
+        model.rename_amplitude("amp 2", "a0(980)")?;
+        assert_eq!(model.aliases()[1].1, "a0(980)");
+        let rebuilt = Model::from_spec(&model.to_spec()?, &NodeRegistry::new())?;
+        assert_eq!(rebuilt.aliases(), model.aliases());
+        model.remove_alias("phase")?;
+        assert!(model.fix_matching("phase", 0.0).is_err());
+        Ok(())
+    }
 }
 
 mod f32_tests {
@@ -162,4 +4431,202 @@ mod f32_tests {
         assert_is_close!(manager.evaluate(&[2.0, 4.0, 3.0, 10.0])?[0], 900.0, f32);
         Ok(())
     }
+    #[test]
+    fn test_wide_mc_accumulation() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f32();
+        let model = model!(scalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let ell_wide = ell.clone().with_wide_mc_accumulation(true);
+        let n: f32 = dataset.weights().iter().sum();
+        // -2 * (N ln(a^2) - N a^2) with a = 2
+        let expected = -2.0 * (n * f32::ln(4.0) - n * 4.0);
+        assert_is_close!(ell.evaluate(&[2.0])?, expected, f32);
+        assert_is_close!(ell_wide.evaluate(&[2.0])?, expected, f32);
+        assert_is_close!(ell_wide.par_evaluate(&[2.0])?, expected, f32);
+        Ok(())
+    }
+    #[test]
+    fn test_gaussian_constraint() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f32();
+        let model = model!(scalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let mut ell_constrained = ell.clone();
+        assert!(ell_constrained
+            .add_gaussian_constraint("a", "value", 1.5, 0.0)
+            .is_err());
+        assert!(ell_constrained
+            .add_gaussian_constraint("a", "missing", 1.5, 0.25)
+            .is_err());
+        ell_constrained.add_gaussian_constraint("a", "value", 1.5, 0.25)?;
+        // ((2 - 1.5) / 0.25)^2 = 4
+        assert_is_close!(
+            ell_constrained.evaluate(&[2.0])?,
+            ell.evaluate(&[2.0])? + 4.0,
+            f32
+        );
+        assert_is_close!(
+            ell_constrained.par_evaluate(&[2.0])?,
+            ell.par_evaluate(&[2.0])? + 4.0,
+            f32
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_serialization() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f32();
+        let mut model = model!(
+            (scalar("a") + cscalar("b")) * pcscalar("c"),
+            piecewise_m("d", 4, (1.0, 2.0)).real(),
+        );
+        model.fix("a", "value", 2.0)?;
+        model.constrain("c", "mag", "d", "bin 0 re")?;
+        model.set_bounds("b", "real", (-1.0, 1.0))?;
+        model.deactivate("b")?;
+        let registry = NodeRegistry::new();
+        let from_json = Model::from_json(&model.to_json()?, &registry)?;
+        let from_yaml = Model::from_yaml(&model.to_yaml()?, &registry)?;
+        let parameters: Vec<f32> = (0..model.get_n_free()).map(|i| i as f32 + 1.0).collect();
+        let expected = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        for reloaded in [from_json, from_yaml] {
+            assert_eq!(reloaded.get_n_free(), model.get_n_free());
+            assert_eq!(reloaded.get_bounds(), model.get_bounds());
+            assert!(!reloaded.get_amplitude("b")?.active);
+            let result = Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?;
+            for (r, e) in result.iter().zip(&expected) {
+                assert_is_close!(*r, *e, f32);
+            }
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_fit_result() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f32();
+        let mut model = model!(scalar("a") * cscalar("b"), cscalar("c"));
+        model.fix("a", "value", 2.0)?;
+        model.constrain("b", "imag", "c", "imag")?;
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let best = vec![1.0, 2.0, 3.0];
+        let fx = ell.evaluate(&best)?;
+        assert!(FitResult::new(&ell, &best[..2], fx, None).is_err());
+        assert!(FitResult::new(&ell, &best, fx, Some(vec![vec![1.0; 3]; 2])).is_err());
+        let covariance = vec![
+            vec![4.0, 0.0, 0.0],
+            vec![0.0, 9.0, 0.0],
+            vec![0.0, 0.0, 16.0],
+        ];
+        let result = FitResult::new(&ell, &best, fx, Some(covariance))?;
+        let reloaded: FitResult<f32> = FitResult::from_json(&result.to_json()?)?;
+        assert_eq!(reloaded.data, result.data);
+        assert_eq!(
+            reloaded.data.fingerprint,
+            format!("{:016x}", dataset.fingerprint())
+        );
+        assert_eq!(reloaded.best, best);
+        assert_eq!(reloaded.errors(), Some(vec![2.0, 3.0, 4.0]));
+        assert_eq!(reloaded.value("a", "value")?, 2.0);
+        assert_eq!(reloaded.value("c", "imag")?, reloaded.value("b", "imag")?);
+        assert_eq!(reloaded.error("a", "value")?, None);
+        assert_eq!(reloaded.error("c", "real")?, Some(4.0));
+        let names: Vec<(&str, &str)> = reloaded
+            .free_parameters()
+            .iter()
+            .map(|p| (p.amplitude.as_str(), p.name.as_str()))
+            .collect();
+        assert_eq!(names, vec![("b", "real"), ("b", "imag"), ("c", "real")]);
+        assert!(reloaded.model.is_some());
+        assert_eq!(reloaded.cohsums.len(), 2);
+        Ok(())
+    }
+    #[test]
+    fn test_config() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f32();
+        let config = r#"
+            # amplitudes
+            amplitude a Scalar
+            amplitude b ComplexScalar
+            amplitude c PolarComplexScalar
+            amplitude d PiecewiseM bins=4 range=[1.0, 2.0]
+
+            sum (a + b) * c    # first coherent sum
+            sum real(d)
+
+            fix a value 2.0
+            constrain c mag d "bin 0 re"
+            bounds b real -1.0 1.0
+            initial b imag 0.5
+            deactivate b
+        "#;
+        let registry = NodeRegistry::new();
+        let model = Model::from_config(config, &registry)?;
+        let mut expected_model = model!(
+            (scalar("a") + cscalar("b")) * pcscalar("c"),
+            piecewise_m("d", 4, (1.0, 2.0)).real(),
+        );
+        expected_model.fix("a", "value", 2.0)?;
+        expected_model.constrain("c", "mag", "d", "bin 0 re")?;
+        expected_model.set_bounds("b", "real", (-1.0, 1.0))?;
+        expected_model.set_initial("b", "imag", 0.5)?;
+        expected_model.deactivate("b")?;
+        assert_eq!(model.get_n_free(), expected_model.get_n_free());
+        assert_eq!(model.get_bounds(), expected_model.get_bounds());
+        assert_eq!(model.get_initial(), expected_model.get_initial());
+        let parameters: Vec<f32> = (0..model.get_n_free()).map(|i| i as f32 + 1.0).collect();
+        let result = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        let expected = Manager::new(&expected_model, &dataset)?.evaluate(&parameters)?;
+        for (r, e) in result.iter().zip(&expected) {
+            assert_is_close!(*r, *e, f32);
+        }
+        assert!(Model::from_config("amplitude a Unknown\nsum a", &registry).is_err());
+        assert!(Model::from_config("amplitude a Scalar\nsum (a", &registry).is_err());
+        assert!(Model::from_config("amplitude a Scalar\nsum a\nfix a value", &registry).is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_gradient() -> Result<(), RustitudeError> {
+        #[derive(Clone)]
+        struct Cube;
+        impl Node<f32> for Cube {
+            fn calculate(
+                &self,
+                parameters: &[f32],
+                _event: &Event<f32>,
+            ) -> Result<Complex<f32>, RustitudeError> {
+                Ok(Complex::new(parameters[0].powi(3), parameters[0]))
+            }
+            fn parameters(&self) -> Vec<String> {
+                vec!["x".to_string()]
+            }
+        }
+        let dataset = generate_test_dataset_f32();
+        let mut model = model!(
+            (scalar("a") + cscalar("b")) * pcscalar("c") + Cube.named("d"),
+            piecewise_m("e", 2, (0.0, 20.0)).real() * cscalar("f").imag(),
+        );
+        model.fix("b", "imag", 0.5)?;
+        model.constrain("a", "value", "e", "bin 1 re")?;
+        let manager = Manager::new(&model, &dataset)?;
+        let mut ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        ell.add_gaussian_constraint("c", "mag", 1.0, 0.5)?;
+        let parameters: Vec<f32> = (0..ell.get_n_free())
+            .map(|i| 0.3 * i as f32 + 0.7)
+            .collect();
+        let gradient = ell.gradient(&parameters)?;
+        assert_eq!(gradient.len(), parameters.len());
+        for (g, par_g) in gradient.iter().zip(ell.par_gradient(&parameters)?) {
+            assert_is_close!(*g, par_g, f32);
+        }
+        for i in 0..parameters.len() {
+            let h = 1e-2;
+            let mut plus = parameters.clone();
+            plus[i] += h;
+            let mut minus = parameters.clone();
+            minus[i] -= h;
+            let numeric = (ell.evaluate(&plus)? - ell.evaluate(&minus)?) / (2.0 * h);
+            assert_is_close!(gradient[i], numeric, 1e-2, f32);
+        }
+        Ok(())
+    }
 }
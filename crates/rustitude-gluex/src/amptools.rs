@@ -0,0 +1,773 @@
+//! Import of AmpTools configuration files.
+//!
+//! [`AmpToolsConfig::parse`] reads the `reaction`, `data`/`accmc`/`genmc`/`bkgnd`, `define`,
+//! `sum`, `amplitude`, `initialize`, `parameter`, `constrain`, and `fix` keywords of an AmpTools
+//! `.cfg` file into a single [`Model`] along with a description of the datasets for each
+//! reaction.
+//!
+//! Each AmpTools amplitude `reaction::sum::amp` becomes a production coefficient (a
+//! [`cscalar`], [`pcscalar`], or [`scalar`] for `real` amplitudes, named after the full AmpTools
+//! name) multiplied by one [`Amplitude`] per `amplitude` line, named `reaction::sum::amp::Kind`.
+//! The following AmpTools amplitudes are mapped automatically:
+//!
+//! | AmpTools | Arguments | Rustitude |
+//! |---|---|---|
+//! | `Zlm` | `L M r s [polAngle polFraction]` | The real (`r = +1`) or imaginary (`r = -1`) part of a [`Zlm`] with reflectivity `r * s` |
+//! | `BreitWigner` | `mass width L daughter1 daughter2` | [`BreitWigner`] |
+//! | `Piecewise` | `massMin massMax nBins 23 suffix ReIm values...` | [`piecewise_m`] |
+//!
+//! Numeric arguments are fixed at the given value, while arguments of the form `[name]` refer to
+//! a `parameter`, which may be shared between amplitudes. The polarization arguments of `Zlm` are
+//! ignored, since Rustitude reads the beam polarization from each [`Event`].
+//!
+//! AmpTools indexes final state particles starting from the beam (`0`) and recoil (`1`), so the
+//! daughter `2` corresponds to `event.daughter_p4s[0]`.
+//!
+//! All reactions share the same [`Model`] (and therefore the same parameters), which makes it
+//! possible to keep constraints between reactions. A [`Model`] which only contains the
+//! [`Amplitude`]s of a single reaction can be obtained with [`AmpToolsConfig::reaction_model`].
+use std::{collections::HashMap, path::Path};
+
+use nalgebra::RealField;
+
+use rustitude_core::{
+    amplitude::{cscalar, pcscalar, piecewise_m, scalar},
+    prelude::*,
+};
+
+use crate::{
+    harmonics::Zlm,
+    resonances::BreitWigner,
+    utils::{Decay, Frame, Sign, Wave},
+};
+
+/// A dataset listed in an AmpTools configuration file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DatasetSpec {
+    /// The name of the AmpTools data reader (e.g. `ROOTDataReader`).
+    pub reader: String,
+    /// The arguments passed to the data reader, the first of which is usually the file path.
+    pub args: Vec<String>,
+}
+
+impl DatasetSpec {
+    /// Loads the dataset from the first argument, which must be a `.root` or `.parquet` file.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError`] if there are no arguments, the file type is
+    /// not supported, or the file cannot be read.
+    pub fn load<F: Field>(&self, method: ReadMethod<F>) -> Result<Dataset<F>, RustitudeError> {
+        let path = self.args.first().ok_or_else(|| {
+            RustitudeError::ParseError(format!("{} has no file argument", self.reader))
+        })?;
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("root") => Dataset::from_root(path, method),
+            Some("parquet") => Dataset::from_parquet(path, method),
+            _ => Err(RustitudeError::ParseError(format!(
+                "unsupported file type: {path}"
+            ))),
+        }
+    }
+}
+
+/// A reaction listed in an AmpTools configuration file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Reaction {
+    /// The name of the reaction.
+    pub name: String,
+    /// The names of the particles in the reaction (beam, recoil, then daughters).
+    pub particles: Vec<String>,
+    /// The data sample, if given.
+    pub data: Option<DatasetSpec>,
+    /// The accepted Monte Carlo sample, if given.
+    pub accmc: Option<DatasetSpec>,
+    /// The generated Monte Carlo sample, if given.
+    pub genmc: Option<DatasetSpec>,
+    /// The background sample, if given.
+    pub bkgnd: Option<DatasetSpec>,
+    /// The names of every [`Amplitude`] in the [`Model`] which belongs to this reaction.
+    pub amplitudes: Vec<String>,
+}
+
+/// A [`Model`] and the list of reactions read from an AmpTools configuration file.
+#[derive(Clone)]
+pub struct AmpToolsConfig<F: Field> {
+    /// The [`Model`] containing every coherent sum in the file.
+    pub model: Model<F>,
+    /// The reactions in the file, in the order they were declared.
+    pub reactions: Vec<Reaction>,
+}
+
+enum Arg {
+    Value(f64),
+    Reference(String),
+}
+
+enum ParameterMode {
+    Free,
+    Fixed,
+    Bounded(f64, f64),
+    Gaussian(f64, f64),
+}
+
+struct ParameterDef {
+    value: f64,
+    mode: ParameterMode,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Coordinates {
+    Cartesian,
+    Polar,
+}
+
+struct Coefficient {
+    coordinates: Coordinates,
+    values: (f64, f64),
+    real: bool,
+    fixed: bool,
+}
+
+impl Default for Coefficient {
+    fn default() -> Self {
+        Self {
+            coordinates: Coordinates::Cartesian,
+            values: (1.0, 0.0),
+            real: false,
+            fixed: false,
+        }
+    }
+}
+
+impl Coefficient {
+    fn parameter_names(&self) -> Vec<&'static str> {
+        match (self.real, self.coordinates) {
+            (true, _) => vec!["value"],
+            (false, Coordinates::Cartesian) => vec!["real", "imag"],
+            (false, Coordinates::Polar) => vec!["mag", "phi"],
+        }
+    }
+
+    fn initial_values(&self) -> Vec<f64> {
+        match (self.real, self.coordinates) {
+            (true, Coordinates::Cartesian) => vec![self.values.0],
+            (true, Coordinates::Polar) => vec![self.values.0 * self.values.1.cos()],
+            (false, _) => vec![self.values.0, self.values.1],
+        }
+    }
+}
+
+#[derive(Default)]
+struct AmpDef {
+    factors: Vec<(usize, String, Vec<String>)>,
+    coefficient: Coefficient,
+}
+
+fn parse_error(line: usize, message: impl std::fmt::Display) -> RustitudeError {
+    RustitudeError::ParseError(format!("line {line}: {message}"))
+}
+
+fn parse_number<T: std::str::FromStr>(token: &str, line: usize) -> Result<T, RustitudeError> {
+    token
+        .parse()
+        .map_err(|_| parse_error(line, format!("invalid number \"{token}\"")))
+}
+
+fn parse_arg(token: &str, line: usize) -> Result<Arg, RustitudeError> {
+    match token.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        Some(name) => Ok(Arg::Reference(name.to_string())),
+        None => Ok(Arg::Value(parse_number(token, line)?)),
+    }
+}
+
+fn parse_daughter(token: &str, line: usize) -> Result<usize, RustitudeError> {
+    match parse_number::<usize>(token, line)? {
+        index @ 2..=9 => Ok(index - 2),
+        _ => Err(parse_error(
+            line,
+            format!("unsupported daughter \"{token}\""),
+        )),
+    }
+}
+
+fn expect_len(args: &[String], n: usize, usage: &str, line: usize) -> Result<(), RustitudeError> {
+    if args.len() < n {
+        Err(parse_error(line, format!("expected {usage}")))
+    } else {
+        Ok(())
+    }
+}
+
+/// The result of converting a single AmpTools `amplitude` line.
+struct Factor<F: Field> {
+    amplitude: Amplitude<F>,
+    tree: AmpTree,
+    parameters: Vec<(String, Arg)>,
+}
+
+fn build_factor<F: Field + RealField>(
+    name: &str,
+    kind: &str,
+    args: &[String],
+    line: usize,
+) -> Result<Factor<F>, RustitudeError> {
+    match kind {
+        "Zlm" => {
+            expect_len(args, 4, "\"Zlm L M r s\"", line)?;
+            let l: usize = parse_number(&args[0], line)?;
+            let m: isize = parse_number(&args[1], line)?;
+            let r: i32 = parse_number(&args[2], line)?;
+            let s: i32 = parse_number(&args[3], line)?;
+            if l > 3 || m.unsigned_abs() > l {
+                return Err(parse_error(line, format!("unsupported wave L={l}, M={m}")));
+            }
+            if r.abs() != 1 || s.abs() != 1 {
+                return Err(parse_error(line, "r and s must be either +1 or -1"));
+            }
+            let reflectivity = if r * s > 0 {
+                Sign::Positive
+            } else {
+                Sign::Negative
+            };
+            let amplitude = Amplitude::new(
+                name,
+                Zlm::new(
+                    Wave::new(l, m),
+                    reflectivity,
+                    Decay::default(),
+                    Frame::Helicity,
+                ),
+            );
+            let inner = Box::new(AmpTree::Amplitude(name.to_string()));
+            Ok(Factor {
+                amplitude,
+                tree: if r > 0 {
+                    AmpTree::Real(inner)
+                } else {
+                    AmpTree::Imag(inner)
+                },
+                parameters: Vec::default(),
+            })
+        }
+        "BreitWigner" => {
+            expect_len(
+                args,
+                5,
+                "\"BreitWigner mass width L daughter1 daughter2\"",
+                line,
+            )?;
+            let l: usize = parse_number(&args[2], line)?;
+            let decay = Decay::TwoBodyDecay([
+                parse_daughter(&args[3], line)?,
+                parse_daughter(&args[4], line)?,
+            ]);
+            Ok(Factor {
+                amplitude: Amplitude::new(name, BreitWigner::new(l, decay)),
+                tree: AmpTree::Amplitude(name.to_string()),
+                parameters: vec![
+                    ("mass".to_string(), parse_arg(&args[0], line)?),
+                    ("width".to_string(), parse_arg(&args[1], line)?),
+                ],
+            })
+        }
+        "Piecewise" => {
+            expect_len(
+                args,
+                6,
+                "\"Piecewise massMin massMax nBins daughters suffix ReIm values...\"",
+                line,
+            )?;
+            let min: f64 = parse_number(&args[0], line)?;
+            let max: f64 = parse_number(&args[1], line)?;
+            let bins: usize = parse_number(&args[2], line)?;
+            if args[3] != "23" {
+                return Err(parse_error(
+                    line,
+                    "Piecewise is only supported for the daughters \"23\"",
+                ));
+            }
+            if args[5] != "ReIm" {
+                return Err(parse_error(
+                    line,
+                    "Piecewise is only supported in the \"ReIm\" representation",
+                ));
+            }
+            if args.len() != 6 + 2 * bins {
+                return Err(parse_error(
+                    line,
+                    format!("expected {} Piecewise values", 2 * bins),
+                ));
+            }
+            let parameters = args[6..]
+                .iter()
+                .enumerate()
+                .map(|(i, arg)| {
+                    let part = if i % 2 == 0 { "re" } else { "im" };
+                    Ok((format!("bin {} {}", i / 2, part), parse_arg(arg, line)?))
+                })
+                .collect::<Result<Vec<_>, RustitudeError>>()?;
+            Ok(Factor {
+                amplitude: piecewise_m(name, bins, (convert!(min, F), convert!(max, F))),
+                tree: AmpTree::Amplitude(name.to_string()),
+                parameters,
+            })
+        }
+        other => Err(parse_error(
+            line,
+            format!("unsupported AmpTools amplitude \"{other}\""),
+        )),
+    }
+}
+
+/// Constrains two parameters unless they are already constrained to each other.
+fn constrain_pair<F: Field>(
+    model: &mut Model<F>,
+    (amp_1, par_1): (&str, &str),
+    (amp_2, par_2): (&str, &str),
+) -> Result<(), RustitudeError> {
+    let p1 = model.get_parameter(amp_1, par_1)?;
+    let p2 = model.get_parameter(amp_2, par_2)?;
+    if p1.index != p2.index {
+        model.constrain(amp_1, par_1, amp_2, par_2)?;
+    }
+    Ok(())
+}
+
+impl<F: Field + RealField + 'static> AmpToolsConfig<F> {
+    /// Parses the contents of an AmpTools configuration file. See the
+    /// [module-level documentation](self) for the supported keywords and amplitudes.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParseError`] (with the offending line number)
+    /// if the file uses an unsupported keyword or amplitude or is otherwise malformed.
+    pub fn parse(config: &str) -> Result<Self, RustitudeError> {
+        let mut defines: HashMap<String, Vec<String>> = HashMap::default();
+        let mut reactions: Vec<Reaction> = Vec::default();
+        let mut sums: Vec<(String, Vec<String>)> = Vec::default();
+        let mut amps: Vec<(String, AmpDef)> = Vec::default();
+        let mut parameters: HashMap<String, ParameterDef> = HashMap::default();
+        let mut constraints: Vec<(usize, Vec<String>)> = Vec::default();
+        let mut fixes: Vec<(usize, String)> = Vec::default();
+
+        let mut logical_lines: Vec<(usize, String)> = Vec::default();
+        let mut pending: Option<(usize, String)> = None;
+        for (i, raw) in config.lines().enumerate() {
+            let content = raw.split('#').next().unwrap_or_default().trim_end();
+            let (start, mut text) = pending.take().unwrap_or((i + 1, String::new()));
+            match content.strip_suffix('\\') {
+                Some(continued) => {
+                    text.push_str(continued);
+                    text.push(' ');
+                    pending = Some((start, text));
+                }
+                None => {
+                    text.push_str(content);
+                    logical_lines.push((start, text));
+                }
+            }
+        }
+        if let Some(line) = pending {
+            logical_lines.push(line);
+        }
+
+        for (line, text) in logical_lines {
+            let tokens: Vec<String> = text.split_whitespace().map(str::to_string).collect();
+            let Some((keyword, args)) = tokens.split_first() else {
+                continue;
+            };
+            let reaction_index = |name: &str, reactions: &[Reaction]| {
+                reactions
+                    .iter()
+                    .position(|r| r.name == name)
+                    .ok_or_else(|| parse_error(line, format!("unknown reaction \"{name}\"")))
+            };
+            match keyword.as_str() {
+                "fit" | "normintfile" => {}
+                "define" => {
+                    expect_len(args, 1, "\"define name values...\"", line)?;
+                    defines.insert(args[0].clone(), args[1..].to_vec());
+                }
+                "reaction" => {
+                    expect_len(args, 1, "\"reaction name particles...\"", line)?;
+                    reactions.push(Reaction {
+                        name: args[0].clone(),
+                        particles: args[1..].to_vec(),
+                        ..Default::default()
+                    });
+                }
+                "data" | "accmc" | "genmc" | "bkgnd" => {
+                    expect_len(
+                        args,
+                        2,
+                        &format!("\"{keyword} reaction reader args...\""),
+                        line,
+                    )?;
+                    let index = reaction_index(&args[0], &reactions)?;
+                    let spec = Some(DatasetSpec {
+                        reader: args[1].clone(),
+                        args: args[2..].to_vec(),
+                    });
+                    let reaction = &mut reactions[index];
+                    match keyword.as_str() {
+                        "data" => reaction.data = spec,
+                        "accmc" => reaction.accmc = spec,
+                        "genmc" => reaction.genmc = spec,
+                        _ => reaction.bkgnd = spec,
+                    }
+                }
+                "sum" => {
+                    expect_len(args, 2, "\"sum reaction names...\"", line)?;
+                    reaction_index(&args[0], &reactions)?;
+                    for name in &args[1..] {
+                        sums.push((format!("{}::{}", args[0], name), Vec::default()));
+                    }
+                }
+                "amplitude" => {
+                    expect_len(
+                        args,
+                        2,
+                        "\"amplitude reaction::sum::amp kind args...\"",
+                        line,
+                    )?;
+                    let full_name = &args[0];
+                    let parts: Vec<&str> = full_name.split("::").collect();
+                    if parts.len() != 3 {
+                        return Err(parse_error(
+                            line,
+                            format!("expected reaction::sum::amp, got \"{full_name}\""),
+                        ));
+                    }
+                    reaction_index(parts[0], &reactions)?;
+                    let sum_name = format!("{}::{}", parts[0], parts[1]);
+                    let sum = sums
+                        .iter_mut()
+                        .find(|(name, _)| *name == sum_name)
+                        .ok_or_else(|| parse_error(line, format!("unknown sum \"{sum_name}\"")))?;
+                    if !sum.1.contains(full_name) {
+                        sum.1.push(full_name.clone());
+                    }
+                    let factor_args: Vec<String> = args[2..]
+                        .iter()
+                        .flat_map(|arg| {
+                            defines
+                                .get(arg)
+                                .cloned()
+                                .unwrap_or_else(|| vec![arg.clone()])
+                        })
+                        .collect();
+                    let index = match amps.iter().position(|(name, _)| name == full_name) {
+                        Some(index) => index,
+                        None => {
+                            amps.push((full_name.clone(), AmpDef::default()));
+                            amps.len() - 1
+                        }
+                    };
+                    amps[index]
+                        .1
+                        .factors
+                        .push((line, args[1].clone(), factor_args));
+                }
+                "initialize" => {
+                    expect_len(
+                        args,
+                        4,
+                        "\"initialize amp cartesian|polar a b [real] [fixed]\"",
+                        line,
+                    )?;
+                    let amp = &mut amps
+                        .iter_mut()
+                        .find(|(name, _)| *name == args[0])
+                        .ok_or_else(|| {
+                            parse_error(line, format!("unknown amplitude \"{}\"", args[0]))
+                        })?
+                        .1;
+                    amp.coefficient.coordinates = match args[1].as_str() {
+                        "cartesian" => Coordinates::Cartesian,
+                        "polar" => Coordinates::Polar,
+                        other => {
+                            return Err(parse_error(
+                                line,
+                                format!("unknown coordinate system \"{other}\""),
+                            ))
+                        }
+                    };
+                    amp.coefficient.values =
+                        (parse_number(&args[2], line)?, parse_number(&args[3], line)?);
+                    for flag in &args[4..] {
+                        match flag.as_str() {
+                            "real" => amp.coefficient.real = true,
+                            "fixed" => amp.coefficient.fixed = true,
+                            other => {
+                                return Err(parse_error(line, format!("unknown flag \"{other}\"")))
+                            }
+                        }
+                    }
+                }
+                "parameter" => {
+                    expect_len(
+                        args,
+                        2,
+                        "\"parameter name value [fixed|bounded|gaussian]\"",
+                        line,
+                    )?;
+                    let value = parse_number(&args[1], line)?;
+                    let mode = match args.get(2).map(String::as_str) {
+                        None | Some("floating") => ParameterMode::Free,
+                        Some("fixed") => ParameterMode::Fixed,
+                        Some("bounded") => {
+                            expect_len(
+                                args,
+                                5,
+                                "\"parameter name value bounded lower upper\"",
+                                line,
+                            )?;
+                            ParameterMode::Bounded(
+                                parse_number(&args[3], line)?,
+                                parse_number(&args[4], line)?,
+                            )
+                        }
+                        Some("gaussian") => {
+                            expect_len(
+                                args,
+                                5,
+                                "\"parameter name value gaussian mean sigma\"",
+                                line,
+                            )?;
+                            ParameterMode::Gaussian(
+                                parse_number(&args[3], line)?,
+                                parse_number(&args[4], line)?,
+                            )
+                        }
+                        Some(other) => {
+                            return Err(parse_error(
+                                line,
+                                format!("unknown parameter type \"{other}\""),
+                            ))
+                        }
+                    };
+                    parameters.insert(args[0].clone(), ParameterDef { value, mode });
+                }
+                "constrain" => {
+                    expect_len(args, 2, "\"constrain amp amp...\"", line)?;
+                    constraints.push((line, args.to_vec()));
+                }
+                "fix" => {
+                    expect_len(args, 1, "\"fix amp\"", line)?;
+                    for amp in args {
+                        fixes.push((line, amp.clone()));
+                    }
+                }
+                other => {
+                    return Err(parse_error(
+                        line,
+                        format!("unsupported keyword \"{other}\""),
+                    ))
+                }
+            }
+        }
+
+        // Build every amplitude and the tree for each coherent sum
+        let mut amplitudes: HashMap<String, Amplitude<F>> = HashMap::default();
+        let mut trees: HashMap<String, AmpTree> = HashMap::default();
+        let mut references: Vec<(String, (String, String))> = Vec::default();
+        let mut fixed_values: Vec<(String, String, f64)> = Vec::default();
+        for (name, amp) in &amps {
+            let coefficient = match (amp.coefficient.real, amp.coefficient.coordinates) {
+                (true, _) => scalar(name),
+                (false, Coordinates::Cartesian) => cscalar(name),
+                (false, Coordinates::Polar) => pcscalar(name),
+            };
+            amplitudes.insert(name.clone(), coefficient);
+            let mut factors = vec![AmpTree::Amplitude(name.clone())];
+            let mut kinds: HashMap<&str, usize> = HashMap::default();
+            for (line, kind, args) in &amp.factors {
+                let count = kinds.entry(kind).or_default();
+                let factor_name = if *count == 0 {
+                    format!("{name}::{kind}")
+                } else {
+                    format!("{name}::{kind}::{count}")
+                };
+                *count += 1;
+                let factor = build_factor::<F>(&factor_name, kind, args, *line)?;
+                for (parameter, arg) in factor.parameters {
+                    match arg {
+                        Arg::Value(value) => {
+                            fixed_values.push((factor_name.clone(), parameter, value));
+                        }
+                        Arg::Reference(reference) => {
+                            if !parameters.contains_key(&reference) {
+                                return Err(parse_error(
+                                    *line,
+                                    format!("unknown parameter \"{reference}\""),
+                                ));
+                            }
+                            references.push((reference, (factor_name.clone(), parameter)));
+                        }
+                    }
+                }
+                amplitudes.insert(factor_name, factor.amplitude);
+                factors.push(factor.tree);
+            }
+            trees.insert(name.clone(), AmpTree::Product(factors));
+        }
+        let cohsums = sums
+            .iter()
+            .filter(|(_, amp_names)| !amp_names.is_empty())
+            .map(|(_, amp_names)| {
+                AmpTree::Sum(amp_names.iter().map(|name| trees[name].clone()).collect())
+                    .build(&amplitudes)
+            })
+            .collect::<Result<Vec<_>, RustitudeError>>()?;
+        let mut model = Model::new(&cohsums);
+
+        // Constrained coefficients take the initial value of the first amplitude in the file
+        for (name, amp) in &amps {
+            for (parameter, value) in amp
+                .coefficient
+                .parameter_names()
+                .into_iter()
+                .zip(amp.coefficient.initial_values())
+            {
+                model.set_initial(name, parameter, convert!(value, F))?;
+            }
+        }
+
+        // Constrain shared parameters and production coefficients
+        let mut anchors: HashMap<&str, &(String, String)> = HashMap::default();
+        for (reference, target) in &references {
+            match anchors.get(reference.as_str()) {
+                Some(anchor) => {
+                    constrain_pair(&mut model, (&anchor.0, &anchor.1), (&target.0, &target.1))?
+                }
+                None => {
+                    anchors.insert(reference, target);
+                }
+            }
+        }
+        for (line, names) in &constraints {
+            let coefficient = |name: &String| {
+                amps.iter()
+                    .find(|(amp_name, _)| amp_name == name)
+                    .map(|(_, amp)| &amp.coefficient)
+                    .ok_or_else(|| parse_error(*line, format!("unknown amplitude \"{name}\"")))
+            };
+            let first = coefficient(&names[0])?;
+            for name in &names[1..] {
+                let other = coefficient(name)?;
+                if first.parameter_names() != other.parameter_names() {
+                    return Err(parse_error(
+                        *line,
+                        format!(
+                            "cannot constrain \"{}\" and \"{name}\" with different coordinates",
+                            names[0]
+                        ),
+                    ));
+                }
+                for parameter in first.parameter_names() {
+                    constrain_pair(&mut model, (&names[0], parameter), (name, parameter))?;
+                }
+            }
+        }
+
+        // Set initial values of shared parameters, then fix and bound parameters
+        for (reference, (amplitude, parameter)) in &anchors {
+            let def = &parameters[*reference];
+            model.set_initial(amplitude, parameter, convert!(def.value, F))?;
+            match def.mode {
+                ParameterMode::Free => {}
+                ParameterMode::Fixed => model.fix(amplitude, parameter, convert!(def.value, F))?,
+                ParameterMode::Bounded(lower, upper) => model.set_bounds(
+                    amplitude,
+                    parameter,
+                    (convert!(lower, F), convert!(upper, F)),
+                )?,
+                ParameterMode::Gaussian(mean, sigma) => model.add_gaussian_constraint(
+                    amplitude,
+                    parameter,
+                    convert!(mean, F),
+                    convert!(sigma, F),
+                )?,
+            }
+        }
+        for (amplitude, parameter, value) in &fixed_values {
+            model.fix(amplitude, parameter, convert!(*value, F))?;
+        }
+        let mut fixed_coefficients: Vec<(usize, &String)> = amps
+            .iter()
+            .filter(|(_, amp)| amp.coefficient.fixed)
+            .map(|(name, _)| (0, name))
+            .collect();
+        fixed_coefficients.extend(fixes.iter().map(|(line, name)| (*line, name)));
+        for (line, name) in fixed_coefficients {
+            let amp = &amps
+                .iter()
+                .find(|(amp_name, _)| amp_name == name)
+                .ok_or_else(|| parse_error(line, format!("unknown amplitude \"{name}\"")))?
+                .1;
+            for (parameter, value) in amp
+                .coefficient
+                .parameter_names()
+                .into_iter()
+                .zip(amp.coefficient.initial_values())
+            {
+                if model.get_parameter(name, parameter)?.is_free() {
+                    model.fix(name, parameter, convert!(value, F))?;
+                }
+            }
+        }
+
+        for reaction in &mut reactions {
+            let prefix = format!("{}::", reaction.name);
+            let mut names: Vec<String> = amplitudes
+                .keys()
+                .filter(|name| name.starts_with(&prefix))
+                .cloned()
+                .collect();
+            names.sort();
+            reaction.amplitudes = names;
+        }
+        Ok(Self { model, reactions })
+    }
+
+    /// Reads and parses an AmpTools configuration file. See [`AmpToolsConfig::parse`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::IOError`] if the file cannot be read, or any
+    /// error from [`AmpToolsConfig::parse`].
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, RustitudeError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Returns the reaction with the given name, if it exists.
+    pub fn reaction(&self, name: &str) -> Option<&Reaction> {
+        self.reactions.iter().find(|reaction| reaction.name == name)
+    }
+
+    /// Returns a copy of the [`Model`] in which every [`Amplitude`] that does not belong to the
+    /// given reaction is deactivated. The parameters are shared with [`AmpToolsConfig::model`],
+    /// so the same parameter vector can be used to evaluate every reaction.
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::ParseError`] if the reaction does not exist.
+    pub fn reaction_model(&self, name: &str) -> Result<Model<F>, RustitudeError> {
+        let reaction = self
+            .reaction(name)
+            .ok_or_else(|| RustitudeError::ParseError(format!("unknown reaction \"{name}\"")))?;
+        let mut model = self.model.deep_clone();
+        let others: Vec<String> = model
+            .amplitudes
+            .read()
+            .iter()
+            .filter(|amp| !reaction.amplitudes.contains(&amp.name))
+            .map(|amp| amp.name.clone())
+            .collect();
+        for amp in others {
+            model.deactivate(&amp)?;
+        }
+        Ok(model)
+    }
+}
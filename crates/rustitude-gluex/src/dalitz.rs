@@ -97,4 +97,11 @@ impl<F: Field> Node<F> for OmegaDalitz<F> {
             "delta".to_string(),
         ]
     }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "OmegaDalitz",
+            serde_json::json!({ "decay": self.decay }),
+        ))
+    }
 }
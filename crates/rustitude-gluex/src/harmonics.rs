@@ -1,5 +1,5 @@
 use rayon::prelude::*;
-use rustitude_core::{convert, prelude::*};
+use rustitude_core::{convert, frames::polarization_angle, prelude::*};
 use sphrs::{ComplexSH, SHEval};
 
 use crate::utils::{Decay, Frame, Sign, Wave};
@@ -23,15 +23,23 @@ impl<F: Field> Ylm<F> {
 }
 impl<F: Field> Node<F> for Ylm<F> {
     fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        let shared = SharedData::compute(&self.requirements(), dataset)?;
+        self.precalculate_shared(dataset, &shared)
+    }
+    fn requirements(&self) -> Vec<SharedQuantity<F>> {
+        vec![self.decay.shared_coordinates(self.frame, 0)]
+    }
+    fn precalculate_shared(
+        &mut self,
+        dataset: &Dataset<F>,
+        shared: &SharedData,
+    ) -> Result<(), RustitudeError> {
+        let coordinates = self.decay.get_shared_coordinates(self.frame, 0, shared)?;
         self.data = dataset
             .events
             .par_iter()
-            .map(|event| {
-                let (_, _, _, p) =
-                    self.frame
-                        .coordinates(self.decay, self.decay.primary_p4(event), event);
-                ComplexSH::Spherical.eval(self.wave.l(), self.wave.m(), &p)
-            })
+            .zip(coordinates)
+            .map(|(_, (_, p))| ComplexSH::Spherical.eval(self.wave.l(), self.wave.m(), p))
             .collect();
         Ok(())
     }
@@ -39,6 +47,23 @@ impl<F: Field> Node<F> for Ylm<F> {
     fn calculate(&self, _parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
         Ok(self.data[event.index])
     }
+
+    fn calculate_batch(
+        &self,
+        _parameters: &[F],
+        events: &[Event<F>],
+        out: &mut [Complex<F>],
+    ) -> Result<(), RustitudeError> {
+        gather_batch(&self.data, events, out);
+        Ok(())
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "Ylm",
+            serde_json::json!({ "wave": self.wave, "decay": self.decay, "frame": self.frame }),
+        ))
+    }
 }
 
 #[derive(Clone)]
@@ -62,16 +87,25 @@ impl<F: Field> Zlm<F> {
 }
 impl<F: Field + num::Float> Node<F> for Zlm<F> {
     fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        let shared = SharedData::compute(&self.requirements(), dataset)?;
+        self.precalculate_shared(dataset, &shared)
+    }
+    fn requirements(&self) -> Vec<SharedQuantity<F>> {
+        vec![self.decay.shared_coordinates(self.frame, 0)]
+    }
+    fn precalculate_shared(
+        &mut self,
+        dataset: &Dataset<F>,
+        shared: &SharedData,
+    ) -> Result<(), RustitudeError> {
+        let coordinates = self.decay.get_shared_coordinates(self.frame, 0, shared)?;
         self.data = dataset
             .events
             .par_iter()
-            .map(|event| {
-                let (_, y, _, p) = self.decay.coordinates(self.frame, 0, event);
-                let ylm = ComplexSH::Spherical.eval(self.wave.l(), self.wave.m(), &p);
-                let big_phi = F::atan2(
-                    y.dot(&event.eps),
-                    event.beam_p4.direction().dot(&event.eps.cross(&y)),
-                );
+            .zip(coordinates)
+            .map(|(event, (y, p))| {
+                let ylm = ComplexSH::Spherical.eval(self.wave.l(), self.wave.m(), p);
+                let big_phi = polarization_angle(event, y);
                 let pgamma = event.eps_mag();
                 let phase = Complex::cis(-big_phi);
                 let zlm = ylm * phase;
@@ -92,6 +126,132 @@ impl<F: Field + num::Float> Node<F> for Zlm<F> {
     fn calculate(&self, _parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
         Ok(self.data[event.index])
     }
+
+    fn calculate_batch(
+        &self,
+        _parameters: &[F],
+        events: &[Event<F>],
+        out: &mut [Complex<F>],
+    ) -> Result<(), RustitudeError> {
+        gather_batch(&self.data, events, out);
+        Ok(())
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "Zlm",
+            serde_json::json!({
+                "wave": self.wave,
+                "reflectivity": self.reflectivity,
+                "decay": self.decay,
+                "frame": self.frame,
+            }),
+        ))
+    }
+}
+
+/// A [`Zlm`] variant whose beam polarization magnitude is a free parameter rather than the
+/// per-event value stored in [`Event::eps`]. Only the direction of the polarization vector is
+/// used at precalculation time. This allows the polarization magnitude of each orientation
+/// dataset to be fit as a nuisance parameter, typically constrained to a TPOL measurement with
+/// [`Model::add_gaussian_constraint`].
+#[derive(Clone)]
+pub struct PolarizedZlm<F: Field> {
+    wave: Wave,
+    reflectivity: Sign,
+    decay: Decay,
+    frame: Frame,
+    data: Vec<Complex<F>>,
+}
+impl<F: Field> PolarizedZlm<F> {
+    pub fn new(wave: Wave, reflectivity: Sign, decay: Decay, frame: Frame) -> Self {
+        Self {
+            wave,
+            reflectivity,
+            decay,
+            frame,
+            data: Vec::default(),
+        }
+    }
+}
+impl<F: Field + num::Float> Node<F> for PolarizedZlm<F> {
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        let shared = SharedData::compute(&self.requirements(), dataset)?;
+        self.precalculate_shared(dataset, &shared)
+    }
+    fn requirements(&self) -> Vec<SharedQuantity<F>> {
+        vec![self.decay.shared_coordinates(self.frame, 0)]
+    }
+    fn precalculate_shared(
+        &mut self,
+        dataset: &Dataset<F>,
+        shared: &SharedData,
+    ) -> Result<(), RustitudeError> {
+        let coordinates = self.decay.get_shared_coordinates(self.frame, 0, shared)?;
+        self.data = dataset
+            .events
+            .par_iter()
+            .zip(coordinates)
+            .map(|(event, (y, p))| {
+                let ylm = ComplexSH::Spherical.eval(self.wave.l(), self.wave.m(), p);
+                let big_phi = polarization_angle(event, y);
+                ylm * Complex::cis(-big_phi)
+            })
+            .collect();
+        Ok(())
+    }
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let zlm = self.data[event.index];
+        let pgamma = parameters[0];
+        Ok(match self.reflectivity {
+            Sign::Positive => Complex::new(
+                F::sqrt(F::one() + pgamma) * zlm.re,
+                F::sqrt(F::one() - pgamma) * zlm.im,
+            ),
+            Sign::Negative => Complex::new(
+                F::sqrt(F::one() - pgamma) * zlm.re,
+                F::sqrt(F::one() + pgamma) * zlm.im,
+            ),
+        })
+    }
+
+    fn calculate_batch(
+        &self,
+        parameters: &[F],
+        events: &[Event<F>],
+        out: &mut [Complex<F>],
+    ) -> Result<(), RustitudeError> {
+        gather_batch(&self.data, events, out);
+        let pgamma = parameters[0];
+        let (re_scale, im_scale) = match self.reflectivity {
+            Sign::Positive => (F::sqrt(F::one() + pgamma), F::sqrt(F::one() - pgamma)),
+            Sign::Negative => (F::sqrt(F::one() - pgamma), F::sqrt(F::one() + pgamma)),
+        };
+        for value in out.iter_mut() {
+            *value = Complex::new(re_scale * value.re, im_scale * value.im);
+        }
+        Ok(())
+    }
+
+    fn parameters(&self) -> Vec<String> {
+        vec!["polarization magnitude".to_string()]
+    }
+
+    fn parameter_bounds(&self) -> Vec<(F, F)> {
+        vec![(F::zero(), F::one())]
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "PolarizedZlm",
+            serde_json::json!({
+                "wave": self.wave,
+                "reflectivity": self.reflectivity,
+                "decay": self.decay,
+                "frame": self.frame,
+            }),
+        ))
+    }
 }
 
 #[derive(Clone)]
@@ -113,16 +273,25 @@ impl<F: Field> OnePS<F> {
 }
 impl<F: Field> Node<F> for OnePS<F> {
     fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        let shared = SharedData::compute(&self.requirements(), dataset)?;
+        self.precalculate_shared(dataset, &shared)
+    }
+    fn requirements(&self) -> Vec<SharedQuantity<F>> {
+        vec![self.decay.shared_coordinates(self.frame, 0)]
+    }
+    fn precalculate_shared(
+        &mut self,
+        dataset: &Dataset<F>,
+        shared: &SharedData,
+    ) -> Result<(), RustitudeError> {
+        let coordinates = self.decay.get_shared_coordinates(self.frame, 0, shared)?;
         self.data = dataset
             .events
             .par_iter()
-            .map(|event| {
-                let (_, y, _, _) = self.decay.coordinates(self.frame, 0, event);
+            .zip(coordinates)
+            .map(|(event, (y, _))| {
                 let pol_angle = F::acos(event.eps[0]);
-                let big_phi = F::atan2(
-                    y.dot(&event.eps),
-                    event.beam_p4.direction().dot(&event.eps.cross(&y)),
-                );
+                let big_phi = polarization_angle(event, y);
                 let pgamma = event.eps_mag();
                 let phase = Complex::cis(-(pol_angle + big_phi));
                 match self.reflectivity {
@@ -143,6 +312,27 @@ impl<F: Field> Node<F> for OnePS<F> {
     fn calculate(&self, _parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
         Ok(self.data[event.index])
     }
+
+    fn calculate_batch(
+        &self,
+        _parameters: &[F],
+        events: &[Event<F>],
+        out: &mut [Complex<F>],
+    ) -> Result<(), RustitudeError> {
+        gather_batch(&self.data, events, out);
+        Ok(())
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "OnePS",
+            serde_json::json!({
+                "reflectivity": self.reflectivity,
+                "decay": self.decay,
+                "frame": self.frame,
+            }),
+        ))
+    }
 }
 
 #[derive(Clone)]
@@ -166,16 +356,28 @@ impl<F: Field> TwoPS<F> {
 }
 impl<F: Field> Node<F> for TwoPS<F> {
     fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        let shared = SharedData::compute(&self.requirements(), dataset)?;
+        self.precalculate_shared(dataset, &shared)
+    }
+    fn requirements(&self) -> Vec<SharedQuantity<F>> {
+        vec![self.decay.shared_coordinates(self.frame, 0)]
+    }
+    fn precalculate_shared(
+        &mut self,
+        dataset: &Dataset<F>,
+        shared: &SharedData,
+    ) -> Result<(), RustitudeError> {
+        let coordinates = self.decay.get_shared_coordinates(self.frame, 0, shared)?;
         self.data = dataset
             .events
             .par_iter()
-            .map(|event| {
-                let (_, _, _, p) = self.decay.coordinates(self.frame, 0, event);
+            .zip(coordinates)
+            .map(|(_, (_, p))| {
                 let ylm_p = ComplexSH::Spherical
-                    .eval(self.wave.l(), self.wave.m(), &p)
+                    .eval(self.wave.l(), self.wave.m(), p)
                     .conj();
                 let ylm_m = ComplexSH::Spherical
-                    .eval(self.wave.l(), -self.wave.m(), &p)
+                    .eval(self.wave.l(), -self.wave.m(), p)
                     .conj();
                 let m_refl = convert!(
                     if self.wave.m() % 2 == 0 {
@@ -203,4 +405,26 @@ impl<F: Field> Node<F> for TwoPS<F> {
     fn calculate(&self, _parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
         Ok(self.data[event.index])
     }
+
+    fn calculate_batch(
+        &self,
+        _parameters: &[F],
+        events: &[Event<F>],
+        out: &mut [Complex<F>],
+    ) -> Result<(), RustitudeError> {
+        gather_batch(&self.data, events, out);
+        Ok(())
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "TwoPS",
+            serde_json::json!({
+                "wave": self.wave,
+                "reflectivity": self.reflectivity,
+                "decay": self.decay,
+                "frame": self.frame,
+            }),
+        ))
+    }
 }
@@ -1,6 +1,193 @@
+use nalgebra::RealField;
+use rustitude_core::prelude::*;
+
+pub mod amptools;
 pub mod dalitz;
 pub mod harmonics;
+pub mod moments;
 pub mod polarization;
 pub mod resonances;
 pub mod sdmes;
 pub mod utils;
+pub mod vecps;
+
+/// Reads the non-resonant production channels of a K-matrix [`NodeSpec`], which are absent from
+/// specs written before they were introduced.
+fn non_resonant_channels(spec: &NodeSpec) -> Result<Vec<usize>, RustitudeError> {
+    if spec.args.get("non_resonant").is_some() {
+        spec.arg("non_resonant")
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+/// Registers every [`Node`] in this crate with the given [`NodeRegistry`] so that [`Model`]s
+/// which use them can be loaded with [`Model::from_json`] or [`Model::from_yaml`].
+pub fn register_nodes<F: Field + RealField + 'static>(registry: &mut NodeRegistry<F>) {
+    registry.register("OmegaDalitz", |spec| {
+        Ok(Box::new(dalitz::OmegaDalitz::new(spec.arg("decay")?)))
+    });
+    registry.register("Ylm", |spec| {
+        Ok(Box::new(harmonics::Ylm::new(
+            spec.arg("wave")?,
+            spec.arg("decay")?,
+            spec.arg("frame")?,
+        )))
+    });
+    registry.register("Zlm", |spec| {
+        Ok(Box::new(harmonics::Zlm::new(
+            spec.arg("wave")?,
+            spec.arg("reflectivity")?,
+            spec.arg("decay")?,
+            spec.arg("frame")?,
+        )))
+    });
+    registry.register("PolarizedZlm", |spec| {
+        Ok(Box::new(harmonics::PolarizedZlm::new(
+            spec.arg("wave")?,
+            spec.arg("reflectivity")?,
+            spec.arg("decay")?,
+            spec.arg("frame")?,
+        )))
+    });
+    registry.register("VecPS", |spec| {
+        Ok(Box::new(vecps::VecPS::new(
+            spec.arg("j")?,
+            spec.arg("m")?,
+            spec.arg("l")?,
+            spec.arg("reflectivity")?,
+            spec.arg("decay_helicity")?,
+            spec.arg("decay")?,
+            spec.arg("frame")?,
+        )?))
+    });
+    registry.register("OnePS", |spec| {
+        Ok(Box::new(harmonics::OnePS::new(
+            spec.arg("reflectivity")?,
+            spec.arg("decay")?,
+            spec.arg("frame")?,
+        )))
+    });
+    registry.register("TwoPS", |spec| {
+        Ok(Box::new(harmonics::TwoPS::new(
+            spec.arg("wave")?,
+            spec.arg("reflectivity")?,
+            spec.arg("decay")?,
+            spec.arg("frame")?,
+        )))
+    });
+    registry.register("MomentIntensity", |spec| {
+        Ok(Box::new(moments::MomentIntensity::new(
+            spec.arg("l_max")?,
+            spec.arg("polarized")?,
+            spec.arg("positivity")?,
+            spec.arg("decay")?,
+            spec.arg("frame")?,
+        )))
+    });
+    registry.register("ThreePiPolFrac", |spec| {
+        Ok(Box::new(polarization::ThreePiPolFrac::new(
+            spec.arg("beam_pol")?,
+            spec.arg("j_resonance")?,
+            spec.arg("p_resonance")?,
+            spec.arg("i_resonance")?,
+            spec.arg("l_resonance")?,
+            spec.arg("j_isobar")?,
+            spec.arg("i_isobar")?,
+            spec.arg("iz_daughters")?,
+            spec.arg("decay_resonance")?,
+            spec.arg("decay_isobar")?,
+        )))
+    });
+    registry.register("BreitWigner", |spec| {
+        let mut bw = resonances::BreitWigner::new(spec.arg("l")?, spec.arg("decay")?);
+        if spec.args.get("barrier_factor").is_some() {
+            bw = bw.with_barrier_factor(spec.arg("barrier_factor")?);
+        }
+        if spec.args.get("width_channels").is_some() {
+            let width_channels: Vec<resonances::WidthChannel<F>> = spec.arg("width_channels")?;
+            bw = bw.with_width_channels(&width_channels);
+        }
+        if spec.args.get("mass_convention").is_some() {
+            bw = bw.with_mass_convention(spec.arg("mass_convention")?);
+        }
+        Ok(Box::new(bw))
+    });
+    registry.register("Flatte", |spec| {
+        Ok(Box::new(resonances::Flatte::new(
+            spec.arg("channel")?,
+            spec.arg("m1s")?,
+            spec.arg("m2s")?,
+            spec.arg("decay")?,
+        )))
+    });
+    registry.register("Deck", |spec| {
+        Ok(Box::new(resonances::Deck::new(
+            spec.arg("isobar")?,
+            spec.arg("bachelor")?,
+            spec.arg("exchange_mass")?,
+            spec.arg("form_factor")?,
+            spec.arg("slope")?,
+        )))
+    });
+    registry.register("KMatrixF0", |spec| {
+        Ok(Box::new(
+            resonances::KMatrixF0::new(spec.arg("channel")?, spec.arg("decay")?)
+                .with_non_resonant(&non_resonant_channels(spec)?),
+        ))
+    });
+    registry.register("KMatrixF2", |spec| {
+        Ok(Box::new(
+            resonances::KMatrixF2::new(spec.arg("channel")?, spec.arg("decay")?)
+                .with_non_resonant(&non_resonant_channels(spec)?),
+        ))
+    });
+    registry.register("KMatrixA0", |spec| {
+        Ok(Box::new(
+            resonances::KMatrixA0::new(spec.arg("channel")?, spec.arg("decay")?)
+                .with_non_resonant(&non_resonant_channels(spec)?),
+        ))
+    });
+    registry.register("KMatrixA2", |spec| {
+        Ok(Box::new(
+            resonances::KMatrixA2::new(spec.arg("channel")?, spec.arg("decay")?)
+                .with_non_resonant(&non_resonant_channels(spec)?),
+        ))
+    });
+    registry.register("KMatrixRho", |spec| {
+        Ok(Box::new(
+            resonances::KMatrixRho::new(spec.arg("channel")?, spec.arg("decay")?)
+                .with_non_resonant(&non_resonant_channels(spec)?),
+        ))
+    });
+    registry.register("KMatrixPi1", |spec| {
+        Ok(Box::new(
+            resonances::KMatrixPi1::new(spec.arg("channel")?, spec.arg("decay")?)
+                .with_non_resonant(&non_resonant_channels(spec)?),
+        ))
+    });
+    registry.register("TwoPiSDME", |spec| {
+        Ok(Box::new(sdmes::TwoPiSDME::new(
+            spec.arg("decay")?,
+            spec.arg("frame")?,
+        )))
+    });
+    registry.register("ThreePiSDME", |spec| {
+        Ok(Box::new(sdmes::ThreePiSDME::new(
+            spec.arg("decay")?,
+            spec.arg("frame")?,
+        )))
+    });
+    registry.register("VectorSDME", |spec| {
+        Ok(Box::new(sdmes::VectorSDME::new(
+            spec.arg("decay")?,
+            spec.arg("frame")?,
+        )))
+    });
+    registry.register("VecRadiativeSDME", |spec| {
+        Ok(Box::new(sdmes::VecRadiativeSDME::new(
+            spec.arg("decay")?,
+            spec.arg("frame")?,
+        )))
+    });
+}
@@ -0,0 +1,450 @@
+//! Analytic projection of two-pseudoscalar partial waves onto spherical-harmonic moments.
+//!
+//! For a coherent sum of partial waves $`\sum_{\ell m} T_{\ell m} Y_\ell^m(\Omega)`$ (see
+//! [`Ylm`](crate::harmonics::Ylm)), the moments
+//! $`H(L, M) = \int |\sum_{\ell m} T_{\ell m} Y_\ell^m(\Omega)|^2 Y_L^{M*}(\Omega)\,d\Omega`$ can be
+//! computed directly from the production coefficients $`T_{\ell m}`$ with
+//!
+//! ```math
+//! H(L, M) = \sum_{\ell m \ell' m'} T_{\ell m} T^*_{\ell' m'} \sqrt{\frac{(2\ell' + 1)(2L + 1)}{4\pi(2\ell + 1)}} \langle \ell' 0; L 0 | \ell 0 \rangle \langle \ell' m'; L M | \ell m \rangle
+//! ```
+//!
+//! rather than by integrating the fitted intensity over Monte-Carlo. The Legendre moments are the
+//! $`M = 0`$ moments. Incoherent sums contribute additively (see
+//! [`project_incoherent_moments`]).
+//!
+//! The coefficients can be read from a fitted [`Model`] with [`production_coefficient`]. If
+//! the coefficients depend on mass (for instance through a
+//! [`BreitWigner`](crate::resonances::BreitWigner)), [`moments_by_event`] evaluates them at every
+//! [`Event`] in a [`Manager`]'s [`Dataset`], which gives smooth moment curves when plotted
+//! against the mass of each event.
+//!
+//! The moments of a measured angular distribution are estimated from a [`Dataset`] with
+//! [`dataset_moments`], and the moments predicted by a fitted [`Model`] over (accepted)
+//! Monte-Carlo are given by [`predicted_moments`]. Comparing the two is a standard cross-check of
+//! a fit.
+//!
+//! Alternatively, a [`MomentIntensity`] fits the moments themselves rather than partial waves,
+//! which gives a model-independent description of the angular distribution.
+use std::str::FromStr;
+
+use rayon::prelude::*;
+use rustitude_core::{convert, frames::polarization_angle, prelude::*};
+use serde::{Deserialize, Serialize};
+use sphrs::{ComplexSH, SHEval};
+
+use crate::utils::{Decay, Frame, Wave};
+
+/// A single spherical-harmonic moment $`H(L, M)`$.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Moment<F: Field> {
+    /// The total angular momentum $`L`$ of the moment.
+    pub l: usize,
+    /// The projection $`M`$ of the moment.
+    pub m: isize,
+    /// The value of the moment.
+    pub value: Complex<F>,
+}
+
+/// Computes $`\int Y_\ell^m Y_{\ell'}^{m'*} Y_L^{M*}\,d\Omega`$, which is real.
+fn gaunt<F: Field>(l: i64, m: i64, lp: i64, mp: i64, big_l: i64, big_m: i64) -> F {
+    if m != mp + big_m || mp.abs() > lp || big_m.abs() > big_l || m.abs() > l {
+        return F::zero();
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let cg = |j1: i64, m1: i64, j2: i64, m2: i64, j3: i64, m3: i64| {
+        wigners::clebsch_gordan(
+            j1 as u32, m1 as i32, j2 as u32, m2 as i32, j3 as u32, m3 as i32,
+        )
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let norm = f64::sqrt(
+        ((2 * lp + 1) * (2 * big_l + 1)) as f64 / (4.0 * std::f64::consts::PI * (2 * l + 1) as f64),
+    );
+    convert!(
+        norm * cg(lp, 0, big_l, 0, l, 0) * cg(lp, mp, big_l, big_m, l, m),
+        F
+    )
+}
+
+/// Projects a single coherent sum of partial waves onto the moments $`H(L, M)`$ for
+/// $`0 \le L \le`$ `l_max` and $`-L \le M \le L`$.
+///
+/// Each entry of `waves` pairs a [`Wave`] with its complex production coefficient. Waves without
+/// an explicit projection ([`Wave::S`], [`Wave::P`], [`Wave::D`], and [`Wave::F`]) are treated as
+/// $`m = 0`$.
+pub fn project_moments<F: Field>(waves: &[(Wave, Complex<F>)], l_max: usize) -> Vec<Moment<F>> {
+    let mut moments = Vec::with_capacity((l_max + 1).pow(2));
+    for big_l in 0..=l_max {
+        #[allow(clippy::cast_possible_wrap)]
+        let big_l_signed = big_l as isize;
+        for big_m in -big_l_signed..=big_l_signed {
+            let mut value = Complex::default();
+            for (wave, t) in waves {
+                for (wave_p, t_p) in waves {
+                    let coefficient: F = gaunt(
+                        wave.l(),
+                        wave.m(),
+                        wave_p.l(),
+                        wave_p.m(),
+                        big_l as i64,
+                        big_m as i64,
+                    );
+                    if coefficient != F::zero() {
+                        value += t * t_p.conj() * coefficient;
+                    }
+                }
+            }
+            moments.push(Moment {
+                l: big_l,
+                m: big_m,
+                value,
+            });
+        }
+    }
+    moments
+}
+
+/// Projects an incoherent sum of coherent sums of partial waves onto moments by adding the
+/// result of [`project_moments`] for each coherent sum.
+pub fn project_incoherent_moments<F: Field>(
+    sums: &[Vec<(Wave, Complex<F>)>],
+    l_max: usize,
+) -> Vec<Moment<F>> {
+    let mut moments = project_moments::<F>(&[], l_max);
+    for sum in sums {
+        for (total, moment) in moments.iter_mut().zip(project_moments(sum, l_max)) {
+            total.value += moment.value;
+        }
+    }
+    moments
+}
+
+/// Evaluates the product of the named [`Amplitude`]s in a [`Model`] for the given free
+/// parameters and [`Event`].
+///
+/// This is intended to extract the production coefficient of a partial wave, such as a
+/// [`cscalar`] (which does not depend on the [`Event`]), or a [`cscalar`] multiplied by a
+/// mass-dependent lineshape. In the latter case the [`Model`] must be loaded with a [`Dataset`]
+/// containing the [`Event`], as it is inside a [`Manager`].
+///
+/// # Errors
+///
+/// This function will return a [`RustitudeError::AmplitudeNotFoundError`] if any of the
+/// amplitudes do not exist, or any error produced while calculating them.
+pub fn production_coefficient<F: Field>(
+    model: &Model<F>,
+    parameters: &[F],
+    amplitudes: &[&str],
+    event: &Event<F>,
+) -> Result<Complex<F>, RustitudeError> {
+    let pars: Vec<F> = model
+        .parameters
+        .iter()
+        .map(|p| p.index.map_or(p.initial, |i| parameters[i]))
+        .collect();
+    let model_amplitudes = model.amplitudes.read();
+    let mut coefficient = Complex::new(F::one(), F::zero());
+    for name in amplitudes {
+        let amp = model_amplitudes
+            .iter()
+            .find(|amp| amp.name == *name)
+            .ok_or_else(|| RustitudeError::AmplitudeNotFoundError(name.to_string()))?;
+        let start = model
+            .parameters
+            .iter()
+            .position(|p| p.amplitude == *name)
+            .unwrap_or_default();
+        coefficient *= amp
+            .node
+            .calculate(&pars[start..start + amp.parameters.len()], event)?;
+    }
+    Ok(coefficient)
+}
+
+/// Computes the moments of an incoherent sum of coherent sums of partial waves at every
+/// [`Event`] in a [`Manager`]'s [`Dataset`].
+///
+/// Each partial wave is given as a [`Wave`] and the names of the [`Amplitude`]s whose product is
+/// its production coefficient (see [`production_coefficient`]).
+///
+/// # Errors
+///
+/// This function will return a [`RustitudeError`] if any production coefficient cannot be
+/// computed.
+pub fn moments_by_event<F: Field>(
+    manager: &Manager<F>,
+    parameters: &[F],
+    sums: &[Vec<(Wave, Vec<&str>)>],
+    l_max: usize,
+) -> Result<Vec<Vec<Moment<F>>>, RustitudeError> {
+    manager
+        .dataset
+        .events
+        .par_iter()
+        .map(|event| {
+            let coefficients = sums
+                .iter()
+                .map(|sum| {
+                    sum.iter()
+                        .map(|(wave, amplitudes)| {
+                            Ok((
+                                *wave,
+                                production_coefficient(
+                                    &manager.model,
+                                    parameters,
+                                    amplitudes,
+                                    event,
+                                )?,
+                            ))
+                        })
+                        .collect::<Result<Vec<_>, RustitudeError>>()
+                })
+                .collect::<Result<Vec<_>, RustitudeError>>()?;
+            Ok(project_incoherent_moments(&coefficients, l_max))
+        })
+        .collect()
+}
+
+/// Computes the weighted moments $`H(L, M) = \sum_i w_i Y_L^{M*}(\Omega_i)`$ of the angular
+/// distribution of a [`Dataset`] for $`0 \le L \le`$ `l_max` and $`-L \le M \le L`$.
+///
+/// The angles $`\Omega_i`$ are those of the primary daughter of the given [`Decay`] in the given
+/// [`Frame`]. The weights are the [`Event`] weights unless `weights` is given. These are the
+/// Monte-Carlo estimates of the moments defined in [`project_moments`].
+///
+/// # Errors
+///
+/// This function will return a [`RustitudeError::InvalidParameterValue`] if the number of weights
+/// does not match the number of events.
+pub fn dataset_moments<F: Field>(
+    dataset: &Dataset<F>,
+    weights: Option<&[F]>,
+    l_max: usize,
+    decay: Decay,
+    frame: Frame,
+) -> Result<Vec<Moment<F>>, RustitudeError> {
+    let weights = weights.map_or_else(|| dataset.weights(), <[F]>::to_vec);
+    if weights.len() != dataset.len() {
+        return Err(RustitudeError::InvalidParameterValue(format!(
+            "expected {} weights, got {}",
+            dataset.len(),
+            weights.len()
+        )));
+    }
+    let lm: Vec<(usize, isize)> = (0..=l_max)
+        .flat_map(|l| {
+            #[allow(clippy::cast_possible_wrap)]
+            let l_signed = l as isize;
+            (-l_signed..=l_signed).map(move |m| (l, m))
+        })
+        .collect();
+    let values = dataset
+        .events
+        .par_iter()
+        .zip(weights.par_iter())
+        .map(|(event, weight)| {
+            let (_, _, _, p) = decay.coordinates_of(frame, decay.primary_p4(event), event);
+            lm.iter()
+                .map(|(l, m)| ComplexSH::Spherical.eval(*l as i64, *m as i64, &p).conj() * *weight)
+                .collect::<Vec<Complex<F>>>()
+        })
+        .reduce(
+            || vec![Complex::default(); lm.len()],
+            |a, b| a.into_iter().zip(b).map(|(x, y)| x + y).collect(),
+        );
+    Ok(lm
+        .into_iter()
+        .zip(values)
+        .map(|((l, m), value)| Moment { l, m, value })
+        .collect())
+}
+
+/// Computes the moments predicted by the [`Model`] of an [`ExtendedLogLikelihood`] with the
+/// given free parameters by weighting each [`Event`] of a Monte-Carlo [`Dataset`] by its
+/// normalized intensity (see [`ExtendedLogLikelihood::intensity`]).
+///
+/// With accepted Monte-Carlo, the result can be compared directly to the [`dataset_moments`] of
+/// the data.
+///
+/// # Errors
+///
+/// This function will return any error produced while calculating the intensity.
+pub fn predicted_moments<F: Field>(
+    ell: &ExtendedLogLikelihood<F>,
+    parameters: &[F],
+    dataset_mc: &Dataset<F>,
+    l_max: usize,
+    decay: Decay,
+    frame: Frame,
+) -> Result<Vec<Moment<F>>, RustitudeError> {
+    let intensity = if ell.data_manager.model.contains_python_amplitudes {
+        ell.intensity(parameters, dataset_mc)?
+    } else {
+        ell.par_intensity(parameters, dataset_mc)?
+    };
+    dataset_moments(dataset_mc, Some(&intensity), l_max, decay, frame)
+}
+
+/// How a [`MomentIntensity`] handles a negative intensity, which is allowed by an arbitrary set
+/// of moments.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Positivity {
+    /// Use the absolute value of the intensity.
+    #[default]
+    Absolute,
+    /// Set negative intensities to zero.
+    Clamp,
+}
+
+impl FromStr for Positivity {
+    type Err = RustitudeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "absolute" | "abs" => Ok(Self::Absolute),
+            "clamp" => Ok(Self::Clamp),
+            _ => Err(RustitudeError::ParseError(format!(
+                "Unknown positivity option: {s}"
+            ))),
+        }
+    }
+}
+
+/// A [`Node`] which parameterizes an intensity directly by its moments rather than by partial
+/// waves.
+///
+/// With the angles $`\Omega`$ of the primary daughter of a [`Decay`] in a [`Frame`], the
+/// intensity is
+///
+/// ```math
+/// I(\Omega, \Phi) = I^0(\Omega) - P_\gamma \cos(2\Phi) I^1(\Omega) - P_\gamma \sin(2\Phi) I^2(\Omega)
+/// ```
+///
+/// where $`\Phi`$ is the angle between the beam polarization and the production plane,
+///
+/// ```math
+/// I^\alpha(\Omega) = \sum_{L=0}^{L_{\max}} \sum_{M=0}^{L} \tau_M H^\alpha(L, M) \,\text{Re}\,Y_L^M(\Omega)
+/// ```
+///
+/// for $`\alpha = 0, 1`$, $`I^2`$ is the same sum over $`M > 0`$ with $`\text{Im}\,Y_L^M`$, and
+/// $`\tau_0 = 1`$ and $`\tau_M = 2`$ otherwise. The moments $`H^0(L, M)`$ follow the same
+/// convention as [`project_moments`] (for real moments), so a fit with this [`Node`] can be
+/// compared directly to a partial-wave projection.
+///
+/// The parameters are named `H0(L,M)` for $`0 \le M \le L \le L_{\max}`$ and, if `polarized` is
+/// `true`, `H1(L,M)` for the same range and `H2(L,M)` for $`M > 0`$. The [`Node`] returns
+/// $`\sqrt{I}`$, where negative intensities are handled according to the given [`Positivity`].
+#[derive(Clone)]
+pub struct MomentIntensity<F: Field> {
+    l_max: usize,
+    polarized: bool,
+    positivity: Positivity,
+    decay: Decay,
+    frame: Frame,
+    data: Vec<Vec<F>>,
+}
+
+impl<F: Field> MomentIntensity<F> {
+    pub fn new(
+        l_max: usize,
+        polarized: bool,
+        positivity: Positivity,
+        decay: Decay,
+        frame: Frame,
+    ) -> Self {
+        Self {
+            l_max,
+            polarized,
+            positivity,
+            decay,
+            frame,
+            data: Vec::default(),
+        }
+    }
+
+    /// Returns $`(L, M)`$ for each moment of one of the sums $`I^\alpha`$.
+    fn lm(&self, alpha: usize) -> Vec<(usize, usize)> {
+        let m_min = usize::from(alpha == 2);
+        (0..=self.l_max)
+            .flat_map(|l| (m_min..=l).map(move |m| (l, m)))
+            .collect()
+    }
+
+    fn alphas(&self) -> Vec<usize> {
+        if self.polarized {
+            vec![0, 1, 2]
+        } else {
+            vec![0]
+        }
+    }
+}
+
+impl<F: Field> Node<F> for MomentIntensity<F> {
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        let terms: Vec<(usize, usize, usize)> = self
+            .alphas()
+            .into_iter()
+            .flat_map(|alpha| self.lm(alpha).into_iter().map(move |(l, m)| (alpha, l, m)))
+            .collect();
+        // The intensity is linear in the moments, so only the coefficient of each is stored
+        self.data = dataset
+            .events
+            .par_iter()
+            .map(|event| {
+                let (_, y, _, p) = self.decay.coordinates(self.frame, 0, event);
+                let big_phi = polarization_angle(event, &y);
+                let pgamma = event.eps_mag();
+                terms
+                    .iter()
+                    .map(|&(alpha, l, m)| {
+                        let ylm = ComplexSH::Spherical.eval(l as i64, m as i64, &p);
+                        let tau = if m == 0 { F::one() } else { convert!(2, F) };
+                        match alpha {
+                            0 => tau * ylm.re,
+                            1 => -pgamma * F::cos(convert!(2, F) * big_phi) * tau * ylm.re,
+                            _ => -pgamma * F::sin(convert!(2, F) * big_phi) * tau * ylm.im,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let intensity: F = self.data[event.index]
+            .iter()
+            .zip(parameters)
+            .map(|(&coefficient, &moment)| coefficient * moment)
+            .sum();
+        Ok(Complex::from(F::sqrt(match self.positivity {
+            Positivity::Absolute => F::abs(intensity),
+            Positivity::Clamp => F::max(intensity, F::zero()),
+        })))
+    }
+
+    fn parameters(&self) -> Vec<String> {
+        self.alphas()
+            .into_iter()
+            .flat_map(|alpha| {
+                self.lm(alpha)
+                    .into_iter()
+                    .map(move |(l, m)| format!("H{alpha}({l},{m})"))
+            })
+            .collect()
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "MomentIntensity",
+            serde_json::json!({
+                "l_max": self.l_max,
+                "polarized": self.polarized,
+                "positivity": self.positivity,
+                "decay": self.decay,
+                "frame": self.frame,
+            }),
+        ))
+    }
+}
@@ -74,7 +74,8 @@ impl<F: Field> Node<F> for ThreePiPolFrac<F> {
                     self.decay_resonance.coordinates(Frame::Helicity, 2, event);
                 let p1_iso_p4 = self.decay_isobar.primary_p4(event).boost_along(&isobar_p4);
                 let (_, _, _, p1_iso_coords) =
-                    Frame::Helicity.coordinates(self.decay_resonance, &p1_iso_p4, event);
+                    self.decay_resonance
+                        .coordinates_of(Frame::Helicity, &p1_iso_p4, event);
                 let k = utils::breakup_momentum(
                     resonance_p4.m(),
                     isobar_p4.m(),
@@ -160,4 +161,29 @@ impl<F: Field> Node<F> for ThreePiPolFrac<F> {
     fn parameters(&self) -> Vec<String> {
         std::vec!["polarization fraction".to_string()]
     }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        let sign = |value: F| {
+            if value.is_sign_positive() {
+                Sign::Positive
+            } else {
+                Sign::Negative
+            }
+        };
+        Some(NodeSpec::new(
+            "ThreePiPolFrac",
+            serde_json::json!({
+                "beam_pol": sign(self.beam_pol),
+                "j_resonance": self.j_resonance,
+                "p_resonance": sign(self.p_resonance),
+                "i_resonance": self.i_resonance,
+                "l_resonance": self.l_resonance,
+                "j_isobar": self.j_isobar,
+                "i_isobar": self.i_isobar,
+                "iz_daughters": self.iz_daughters,
+                "decay_resonance": self.decay_resonance,
+                "decay_isobar": self.decay_isobar,
+            }),
+        ))
+    }
 }
@@ -1,19 +1,141 @@
+use std::str::FromStr;
+
 use crate::utils;
 use crate::utils::Decay;
 
-use nalgebra::{RealField, SMatrix, SVector};
 use rayon::prelude::*;
-use rustitude_core::{convert, convert_array, convert_vec, prelude::*};
+use rustitude_core::{convert, math, prelude::*};
+use serde::{Deserialize, Serialize};
+
+pub use rustitude_core::amplitude::AdlerZero;
+
+/// The convention for the barrier factor $`B_\ell(q)`$ of a [`BreitWigner`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BarrierFactor {
+    /// The Blatt-Weisskopf barrier factor [`math::blatt_weisskopf`], where the amplitude is
+    /// multiplied by $`B_\ell(q)`$.
+    #[default]
+    BlattWeisskopf,
+    /// The Blatt-Weisskopf barrier factor [`math::blatt_weisskopf`], where the amplitude is
+    /// multiplied by $`B_\ell(q) / B_\ell(q_0)`$.
+    NormalizedBlattWeisskopf,
+    /// $`B_\ell(q) = q^\ell`$, where the amplitude is multiplied by $`(q / q_0)^\ell`$.
+    MomentumPower,
+    /// $`B_\ell(q) = 1`$.
+    None,
+}
+
+impl BarrierFactor {
+    /// Computes $`B_\ell(q)`$.
+    pub fn value<F: Field>(&self, q: F, l: usize) -> F {
+        match self {
+            Self::BlattWeisskopf | Self::NormalizedBlattWeisskopf => math::blatt_weisskopf(q, l),
+            Self::MomentumPower => q.powi(l as i32),
+            Self::None => F::one(),
+        }
+    }
+
+    /// Computes the barrier factor which multiplies the amplitude, given $`B_\ell(q)`$ and
+    /// $`B_\ell(q_0)`$.
+    pub fn numerator<F: Field>(&self, b: F, b0: F) -> F {
+        match self {
+            Self::BlattWeisskopf => b,
+            Self::NormalizedBlattWeisskopf | Self::MomentumPower => b / b0,
+            Self::None => F::one(),
+        }
+    }
+}
+
+impl FromStr for BarrierFactor {
+    type Err = RustitudeError;
 
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "blatt-weisskopf" | "bw" => Ok(Self::BlattWeisskopf),
+            "normalized-blatt-weisskopf" | "normalized-bw" => Ok(Self::NormalizedBlattWeisskopf),
+            "momentum-power" | "q^l" => Ok(Self::MomentumPower),
+            "none" => Ok(Self::None),
+            _ => Err(RustitudeError::ParseError(format!(
+                "Unknown barrier factor: {s}"
+            ))),
+        }
+    }
+}
+
+/// The mass which multiplies the width in a [`BreitWigner`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MassConvention {
+    /// The nominal mass $`m_0`$ of the resonance.
+    #[default]
+    Nominal,
+    /// The reconstructed mass $`m`$ of the resonance.
+    Reconstructed,
+}
+
+impl FromStr for MassConvention {
+    type Err = RustitudeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "nominal" => Ok(Self::Nominal),
+            "reconstructed" => Ok(Self::Reconstructed),
+            _ => Err(RustitudeError::ParseError(format!(
+                "Unknown mass convention: {s}"
+            ))),
+        }
+    }
+}
+
+/// A decay channel which contributes to the mass-dependent width of a [`BreitWigner`], with
+/// branching fraction `fraction` into two particles with masses `m1` and `m2` and orbital
+/// angular momentum `l`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct WidthChannel<F: Field> {
+    pub fraction: F,
+    pub m1: F,
+    pub m2: F,
+    pub l: usize,
+}
+
+#[derive(Default, Clone)]
+struct BreitWignerData<F: Field> {
+    m: F,
+    m1: F,
+    m2: F,
+    q: F,
+    b: F,
+    channel_q: Vec<F>,
+    channel_b: Vec<F>,
+}
+
+/// A [`Node`] for a relativistic Breit-Wigner with a mass-dependent width,
+///
+/// ```math
+/// \frac{B_\ell(q)}{B_\ell(q_0)} \frac{m_0 \Gamma_0 / \pi}{m_0^2 - m^2 - i \mu \Gamma(m)}
+/// ```
+///
+/// where $`m`$ is the reconstructed mass of the decay with breakup momentum $`q`$, $`m_0`$ and
+/// $`\Gamma_0`$ are the `mass` and `width` parameters, $`q_0`$ is the breakup momentum at
+/// $`m_0`$, and $`\mu`$ is set by the [`MassConvention`]. The barrier factor ratio is replaced
+/// according to the [`BarrierFactor`] convention (by default, the amplitude is multiplied by
+/// $`B_\ell(q)`$ alone). The width is
+///
+/// ```math
+/// \Gamma(m) = \Gamma_0 \sum_i b_i \frac{m_0}{m} \frac{q_i}{q_{i,0}} \left(\frac{B_{\ell_i}(q_i)}{B_{\ell_i}(q_{i,0})}\right)^2
+/// ```
+///
+/// summed over the [`WidthChannel`]s with branching fractions $`b_i`$. If no channels are given,
+/// the width is given by the decay itself with $`b = 1`$, using the reconstructed masses of the
+/// daughters.
 #[derive(Default, Clone)]
 pub struct BreitWigner<F: Field> {
     l: usize,
     decay: Decay,
-    m: Vec<F>,
-    m1: Vec<F>,
-    m2: Vec<F>,
-    q: Vec<F>,
-    f: Vec<F>,
+    barrier_factor: BarrierFactor,
+    width_channels: Vec<WidthChannel<F>>,
+    mass_convention: MassConvention,
+    data: Vec<BreitWignerData<F>>,
 }
 impl<F: Field> BreitWigner<F> {
     pub fn new(l: usize, decay: Decay) -> Self {
@@ -23,10 +145,28 @@ impl<F: Field> BreitWigner<F> {
             ..Default::default()
         }
     }
+
+    /// Sets the [`BarrierFactor`] convention.
+    pub fn with_barrier_factor(mut self, barrier_factor: BarrierFactor) -> Self {
+        self.barrier_factor = barrier_factor;
+        self
+    }
+
+    /// Sets the [`WidthChannel`]s which contribute to the mass-dependent width.
+    pub fn with_width_channels(mut self, width_channels: &[WidthChannel<F>]) -> Self {
+        self.width_channels = width_channels.to_vec();
+        self
+    }
+
+    /// Sets the [`MassConvention`] of the mass which multiplies the width.
+    pub fn with_mass_convention(mut self, mass_convention: MassConvention) -> Self {
+        self.mass_convention = mass_convention;
+        self
+    }
 }
 impl<F: Field> Node<F> for BreitWigner<F> {
     fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
-        (self.m, (self.m1, (self.m2, (self.q, self.f)))) = dataset
+        self.data = dataset
             .events
             .par_iter()
             .map(|event| {
@@ -36,31 +176,88 @@ impl<F: Field> Node<F> for BreitWigner<F> {
                 let m1 = p1.m();
                 let m2 = p2.m();
                 let q = utils::breakup_momentum(m, m1, m2);
-                let f = utils::blatt_weisskopf(m, m1, m2, self.l);
-                (m, (m1, (m2, (q, f))))
+                let b = self.barrier_factor.value(q, self.l);
+                let channel_q: Vec<F> = self
+                    .width_channels
+                    .iter()
+                    .map(|channel| utils::breakup_momentum(m, channel.m1, channel.m2))
+                    .collect();
+                let channel_b = self
+                    .width_channels
+                    .iter()
+                    .zip(&channel_q)
+                    .map(|(channel, &q)| self.barrier_factor.value(q, channel.l))
+                    .collect();
+                BreitWignerData {
+                    m,
+                    m1,
+                    m2,
+                    q,
+                    b,
+                    channel_q,
+                    channel_b,
+                }
             })
-            .unzip();
+            .collect();
         Ok(())
     }
 
     fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        let m = self.m[event.index];
-        let m1 = self.m1[event.index];
-        let m2 = self.m2[event.index];
-        let q = self.q[event.index];
-        let f = self.f[event.index];
+        let BreitWignerData {
+            m,
+            m1,
+            m2,
+            q,
+            b,
+            ref channel_q,
+            ref channel_b,
+        } = self.data[event.index];
         let m0 = parameters[0];
         let g0 = parameters[1];
-        let f0 = utils::blatt_weisskopf(m0, m1, m2, self.l);
         let q0 = utils::breakup_momentum(m0, m1, m2);
-        let g = g0 * (m0 / m) * (q / q0) * (f.powi(2) / f0.powi(2));
-        Ok(Complex::new(f * (m0 * g0 / F::PI()), F::zero())
-            / Complex::new(m0.powi(2) - m.powi(2), -F::one() * m0 * g))
+        let b0 = self.barrier_factor.value(q0, self.l);
+        let g = if self.width_channels.is_empty() {
+            g0 * (m0 / m) * (q / q0) * (b.powi(2) / b0.powi(2))
+        } else {
+            self.width_channels
+                .iter()
+                .zip(channel_q.iter().zip(channel_b))
+                .map(|(channel, (&q_i, &b_i))| {
+                    let q_i0 = utils::breakup_momentum(m0, channel.m1, channel.m2);
+                    let b_i0 = self.barrier_factor.value(q_i0, channel.l);
+                    channel.fraction * g0 * (m0 / m) * (q_i / q_i0) * (b_i.powi(2) / b_i0.powi(2))
+                })
+                .sum()
+        };
+        let mu = match self.mass_convention {
+            MassConvention::Nominal => m0,
+            MassConvention::Reconstructed => m,
+        };
+        let f = self.barrier_factor.numerator(b, b0);
+        Ok(Complex::new(f * (mu * g0 / F::PI()), F::zero())
+            / Complex::new(m0.powi(2) - m.powi(2), -F::one() * mu * g))
     }
 
     fn parameters(&self) -> Vec<String> {
         vec!["mass".to_string(), "width".to_string()]
     }
+
+    fn parameter_bounds(&self) -> Vec<(F, F)> {
+        vec![(F::zero(), F::infinity()); 2]
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "BreitWigner",
+            serde_json::json!({
+                "l": self.l,
+                "decay": self.decay,
+                "barrier_factor": self.barrier_factor,
+                "width_channels": self.width_channels,
+                "mass_convention": self.mass_convention,
+            }),
+        ))
+    }
 }
 
 #[derive(Clone)]
@@ -111,6 +308,10 @@ impl<F: Field> Node<F> for Flatte<F> {
         vec!["mass".to_string(), "g1".to_string(), "g2".to_string()]
     }
 
+    fn parameter_bounds(&self) -> Vec<(F, F)> {
+        vec![(F::zero(), F::infinity()); 3]
+    }
+
     fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
         let (res_mass, br_momenta) = self.data[event.index];
         let gammas = [
@@ -124,569 +325,301 @@ impl<F: Field> Node<F> for Flatte<F> {
             / (mass.powi(2) - Complex::from(res_mass.powi(2)))
             - Complex::<F>::i() * mass * (gammas[0] * gammas[1]))
     }
-}
-
-#[derive(Clone, Copy)]
-pub struct AdlerZero<F: Field> {
-    pub s_0: F,
-    pub s_norm: F,
-}
-#[derive(Clone)]
-pub struct KMatrixConstants<F: Field, const C: usize, const R: usize> {
-    g: SMatrix<F, C, R>,
-    c: SMatrix<F, C, C>,
-    m1s: [F; C],
-    m2s: [F; C],
-    mrs: [F; R],
-    adler_zero: Option<AdlerZero<F>>,
-    l: usize,
-}
-
-impl<F: Field + 'static, const C: usize, const R: usize> KMatrixConstants<F, C, R> {
-    fn c_matrix(&self, s: F) -> SMatrix<Complex<F>, C, C> {
-        SMatrix::from_diagonal(&SVector::from_fn(|i, _| {
-            utils::rho(s, self.m1s[i], self.m2s[i]) / F::PI()
-                * ((utils::chi_plus(s, self.m1s[i], self.m2s[i])
-                    + utils::rho(s, self.m1s[i], self.m2s[i]))
-                    / (utils::chi_plus(s, self.m1s[i], self.m2s[i])
-                        - utils::rho(s, self.m1s[i], self.m2s[i])))
-                .ln()
-                - utils::chi_plus(s, self.m1s[i], self.m2s[i]) / F::PI()
-                    * ((self.m2s[i] - self.m1s[i]) / (self.m1s[i] + self.m2s[i]))
-                    * F::ln(self.m2s[i] / self.m1s[i])
-        }))
-    }
-    fn barrier_factor(s: F, m1: F, m2: F, mr: F, l: usize) -> F {
-        utils::blatt_weisskopf(F::sqrt(s), m1, m2, l) / utils::blatt_weisskopf(mr, m1, m2, l)
-    }
-    fn barrier_matrix(&self, s: F) -> SMatrix<F, C, R> {
-        SMatrix::from_fn(|i, a| {
-            Self::barrier_factor(s, self.m1s[i], self.m2s[i], self.mrs[a], self.l)
-        })
-    }
-
-    fn k_matrix(&self, s: F) -> SMatrix<Complex<F>, C, C> {
-        let bf = self.barrier_matrix(s);
-        SMatrix::from_fn(|i, j| {
-            (0..R)
-                .map(|a| {
-                    Complex::from(
-                        bf[(i, a)]
-                            * bf[(j, a)]
-                            * (self.g[(i, a)] * self.g[(j, a)]
-                                + (self.c[(i, j)]) * (self.mrs[a].powi(2) - s)),
-                    ) * self.pole_product_remainder(s, a)
-                })
-                .sum::<Complex<F>>()
-                * self
-                    .adler_zero
-                    .map_or(F::one(), |az| (s - az.s_0) / az.s_norm)
-        })
-    }
-
-    fn pole_product_remainder(&self, s: F, a_i: usize) -> F {
-        (0..R)
-            .filter_map(|a| {
-                if a != a_i {
-                    Some(self.mrs[a].powi(2) - s)
-                } else {
-                    None
-                }
-            })
-            .product()
-    }
-    fn pole_product(&self, s: F) -> F {
-        (0..R).map(|a| (self.mrs[a].powi(2) - s)).product()
-    }
-
-    fn p_vector(
-        betas: &SVector<Complex<F>, R>,
-        pvector_constants: &SMatrix<Complex<F>, C, R>,
-    ) -> SVector<Complex<F>, C> {
-        SVector::<Complex<F>, C>::from_fn(|j, _| {
-            (0..R).map(|a| betas[a] * pvector_constants[(j, a)]).sum()
-        })
-    }
 
-    pub fn calculate_k_matrix(
-        betas: &SVector<Complex<F>, R>,
-        ikc_inv_vec: &SVector<Complex<F>, C>,
-        pvector_constants_mat: &SMatrix<Complex<F>, C, R>,
-    ) -> Complex<F> {
-        ikc_inv_vec.dot(&Self::p_vector(betas, pvector_constants_mat))
-    }
-}
-impl<F: Field + RealField + 'static, const C: usize, const R: usize> KMatrixConstants<F, C, R> {
-    fn ikc_inv(&self, s: F, channel: usize) -> SVector<Complex<F>, C> {
-        let i_mat = SMatrix::<Complex<F>, C, C>::identity().scale(self.pole_product(s));
-        let k_mat = self.k_matrix(s);
-        let c_mat = self.c_matrix(s);
-        let ikc_mat = i_mat + k_mat * c_mat;
-        let ikc_inv_mat = ikc_mat.try_inverse().unwrap();
-        ikc_inv_mat.row(channel).transpose()
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "Flatte",
+            serde_json::json!({
+                "channel": self.channel,
+                "m1s": self.m1s,
+                "m2s": self.m2s,
+                "decay": self.decay,
+            }),
+        ))
     }
 }
 
+/// A [`KMatrix`] with a fixed [`KMatrixConfig`], where the invariant mass of the system is that
+/// of a [`Decay`]. The [`KMatrix`] itself is built when the node is precalculated, so invalid
+/// channels are reported then.
 #[derive(Clone)]
-#[allow(clippy::type_complexity)]
-pub struct KMatrixF0<F: Field> {
+struct KMatrixPreset<F: Field> {
+    kind: &'static str,
     channel: usize,
     decay: Decay,
-    constants: KMatrixConstants<F, 5, 5>,
-    data: Vec<(SVector<Complex<F>, 5>, SMatrix<Complex<F>, 5, 5>)>,
+    non_resonant: Vec<usize>,
+    config: KMatrixConfig<F>,
+    kmatrix: Option<KMatrix<F>>,
 }
-#[rustfmt::skip]
-impl<F: Field + 'static> KMatrixF0<F> {
-    pub fn new(channel: usize, decay: Decay) -> Self {
+impl<F: Field + 'static> KMatrixPreset<F> {
+    const fn new(
+        kind: &'static str,
+        channel: usize,
+        decay: Decay,
+        config: KMatrixConfig<F>,
+    ) -> Self {
         Self {
+            kind,
             channel,
             decay,
-            constants: KMatrixConstants {
-                g: SMatrix::<F, 5, 5>::from_vec(convert_vec!(vec![
-                     0.74987, -0.01257,  0.27536, -0.15102,  0.36103,
-                     0.06401,  0.00204,  0.77413,  0.50999,  0.13112,
-                    -0.23417, -0.01032,  0.72283,  0.11934,  0.36792, 
-                     0.01270,  0.26700,  0.09214,  0.02742, -0.04025,
-                    -0.14242,  0.22780,  0.15981,  0.16272, -0.17397,  
-                ], F)),
-                c: SMatrix::<F, 5, 5>::from_vec(convert_vec!(vec![
-                     0.03728,  0.00000, -0.01398, -0.02203,  0.01397,
-                     0.00000,  0.00000,  0.00000,  0.00000,  0.00000,
-                    -0.01398,  0.00000,  0.02349,  0.03101, -0.04003,
-                    -0.02203,  0.00000,  0.03101, -0.13769, -0.06722,
-                     0.01397,  0.00000, -0.04003, -0.06722, -0.28401,
-                ], F)),
-                m1s: convert_array!([0.1349768, 2.0 * 0.1349768, 0.493677, 0.547862, 0.547862], F),
-                m2s: convert_array!([0.1349768, 2.0 * 0.1349768, 0.497611, 0.547862, 0.95778], F),
-                mrs: convert_array!([0.51461, 0.90630, 1.23089, 1.46104, 1.69611], F),
-                adler_zero: Some(AdlerZero {
-                    s_0: convert!(0.0091125, F),
-                    s_norm: F::one(),
-                }),
-                l: 0,
-            },
-            data: Vec::default(),
+            non_resonant: Vec::new(),
+            config,
+            kmatrix: None,
         }
     }
-}
 
-impl<F: Field + RealField> Node<F> for KMatrixF0<F> {
-    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
-        self.data = dataset
-            .events
-            .par_iter()
-            .map(|event| {
-                let s = self.decay.resonance_p4(event).m2();
-                let barrier_mat = self.constants.barrier_matrix(s);
-                let pvector_constants = SMatrix::<Complex<F>, 5, 5>::from_fn(|i, a| {
-                    Complex::from(barrier_mat[(i, a)])
-                        * self.constants.g[(i, a)]
-                        * self.constants.pole_product_remainder(s, a)
-                });
-                (self.constants.ikc_inv(s, self.channel), pvector_constants)
-            })
-            .collect();
-        Ok(())
-    }
-    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        let betas = SVector::<Complex<F>, 5>::new(
-            Complex::new(parameters[0], parameters[1]),
-            Complex::new(parameters[2], parameters[3]),
-            Complex::new(parameters[4], parameters[5]),
-            Complex::new(parameters[6], parameters[7]),
-            Complex::new(parameters[8], parameters[9]),
-        );
-        let (ikc_inv_vec, pvector_constants_mat) = self.data[event.index];
-        Ok(KMatrixConstants::calculate_k_matrix(
-            &betas,
-            &ikc_inv_vec,
-            &pvector_constants_mat,
-        ))
-    }
-    fn parameters(&self) -> Vec<String> {
-        vec![
-            "f0_500 re".to_string(),
-            "f0_500 im".to_string(),
-            "f0_980 re".to_string(),
-            "f0_980 im".to_string(),
-            "f0_1370 re".to_string(),
-            "f0_1370 im".to_string(),
-            "f0_1500 re".to_string(),
-            "f0_1500 im".to_string(),
-            "f0_1710 re".to_string(),
-            "f0_1710 im".to_string(),
-        ]
+    fn build(&self) -> Result<KMatrix<F>, RustitudeError> {
+        let (daughters_1, daughters_2) = match self.decay {
+            Decay::TwoBodyDecay([a, b]) => (vec![a], vec![b]),
+            Decay::ThreeBodyDecay([a, b, c]) => (vec![a], vec![b, c]),
+        };
+        KMatrix::new(
+            &daughters_1,
+            &daughters_2,
+            self.channel,
+            self.config.clone(),
+        )?
+        .with_non_resonant(&self.non_resonant)
     }
-}
-#[derive(Clone)]
-#[allow(clippy::type_complexity)]
-pub struct KMatrixF2<F: Field> {
-    channel: usize,
-    decay: Decay,
-    constants: KMatrixConstants<F, 4, 4>,
-    data: Vec<(SVector<Complex<F>, 4>, SMatrix<Complex<F>, 4, 4>)>,
-}
-#[rustfmt::skip]
-impl<F: Field + 'static> KMatrixF2<F> {
-    pub fn new(channel: usize, decay: Decay) -> Self {
-        Self {
-            channel,
-            decay,
-            constants: KMatrixConstants {
-                g: SMatrix::<F, 4, 4>::from_vec(convert_vec!(vec![
-                     0.40033,  0.15479, -0.08900, -0.00113,
-                     0.01820,  0.17300,  0.32393,  0.15256,
-                    -0.06709,  0.22941, -0.43133,  0.23721,
-                    -0.49924,  0.19295,  0.27975, -0.03987,
-                ], F)),
-                c: SMatrix::<F, 4, 4>::from_vec(convert_vec!(vec![
-                    -0.04319,  0.00000,  0.00984,  0.01028,
-                     0.00000,  0.00000,  0.00000,  0.00000,
-                     0.00984,  0.00000, -0.07344,  0.05533,
-                     0.01028,  0.00000,  0.05533, -0.05183,
-                ], F)),
-                m1s: convert_array!([0.1349768, 2.0 * 0.1349768, 0.493677, 0.547862], F),
-                m2s: convert_array!([0.1349768, 2.0 * 0.1349768, 0.497611, 0.547862], F),
-                mrs: convert_array!([1.15299, 1.48359, 1.72923, 1.96700], F),
-                adler_zero: None,
-                l: 2,
-            },
-            data: Vec::default()
-        }
+
+    fn kmatrix(&self) -> Result<&KMatrix<F>, RustitudeError> {
+        self.kmatrix.as_ref().ok_or_else(|| {
+            RustitudeError::EvaluationError(format!("{} has not been precalculated", self.kind))
+        })
     }
 }
 
-impl<F: Field + RealField> Node<F> for KMatrixF2<F> {
+impl<F: Field + 'static> Node<F> for KMatrixPreset<F> {
     fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
-        self.data = dataset
-            .events
-            .par_iter()
-            .map(|event| {
-                let s = self.decay.resonance_p4(event).m2();
-                let barrier_mat = self.constants.barrier_matrix(s);
-                let pvector_constants = SMatrix::<Complex<F>, 4, 4>::from_fn(|i, a| {
-                    Complex::from(barrier_mat[(i, a)])
-                        * self.constants.g[(i, a)]
-                        * self.constants.pole_product_remainder(s, a)
-                });
-                (self.constants.ikc_inv(s, self.channel), pvector_constants)
-            })
-            .collect();
+        let mut kmatrix = self.build()?;
+        kmatrix.precalculate(dataset)?;
+        self.kmatrix = Some(kmatrix);
         Ok(())
     }
     fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        let betas = SVector::<Complex<F>, 4>::new(
-            Complex::new(parameters[0], parameters[1]),
-            Complex::new(parameters[2], parameters[3]),
-            Complex::new(parameters[4], parameters[5]),
-            Complex::new(parameters[6], parameters[7]),
-        );
-        let (ikc_inv_vec, pvector_constants_mat) = self.data[event.index];
-        Ok(KMatrixConstants::calculate_k_matrix(
-            &betas,
-            &ikc_inv_vec,
-            &pvector_constants_mat,
-        ))
+        self.kmatrix()?.calculate(parameters, event)
+    }
+    fn calculate_gradient(
+        &self,
+        parameters: &[F],
+        event: &Event<F>,
+    ) -> Result<Vec<Complex<F>>, RustitudeError> {
+        self.kmatrix()?.calculate_gradient(parameters, event)
     }
     fn parameters(&self) -> Vec<String> {
-        vec![
-            "f2_1270 re".to_string(),
-            "f2_1270 im".to_string(),
-            "f2_1525 re".to_string(),
-            "f2_1525 im".to_string(),
-            "f2_1810 re".to_string(),
-            "f2_1810 im".to_string(),
-            "f2_1950 re".to_string(),
-            "f2_1950 im".to_string(),
-        ]
+        let poles = self.config.poles.iter().map(|pole| pole.name.clone());
+        let non_resonant = self.non_resonant.iter().map(|channel| {
+            // Invalid channels are reported when the node is precalculated
+            let label = self
+                .config
+                .channels
+                .get(*channel)
+                .map_or_else(|| format!("channel {channel}"), |c| c.name.clone());
+            format!("{label} non-resonant")
+        });
+        poles
+            .chain(non_resonant)
+            .flat_map(|label| [format!("{label} re"), format!("{label} im")])
+            .collect()
+    }
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            self.kind,
+            serde_json::json!({
+                "channel": self.channel,
+                "decay": self.decay,
+                "non_resonant": self.non_resonant,
+            }),
+        ))
     }
 }
 
-#[derive(Clone)]
-#[allow(clippy::type_complexity)]
-pub struct KMatrixA0<F: Field> {
-    channel: usize,
-    decay: Decay,
-    constants: KMatrixConstants<F, 2, 2>,
-    data: Vec<(SVector<Complex<F>, 2>, SMatrix<Complex<F>, 2, 2>)>,
-}
-#[rustfmt::skip]
-impl<F: Field + 'static> KMatrixA0<F> {
-    pub fn new(channel: usize, decay: Decay) -> Self {
-        Self {
-            channel,
-            decay,
-            constants: KMatrixConstants {
-                g: SMatrix::<F, 2, 2>::from_vec(convert_vec!(vec![
-                    0.43215, -0.28825, 
-                    0.19000,  0.43372
-                ], F)),
-                c: SMatrix::<F, 2, 2>::from_vec(convert_vec!(vec![
-                    0.00000,  0.00000,
-                    0.00000,  0.00000
-                ], F)),
-                m1s: convert_array!([0.1349768, 0.493677], F),
-                m2s: convert_array!([0.547862, 0.497611], F),
-                mrs: convert_array!([0.95395, 1.26767], F),
-                adler_zero: None,
-                l: 0,
-            },
-            data: Vec::default()
+macro_rules! impl_kmatrix_preset {
+    ($(#[$meta:meta])* $name:ident, $config:ident) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        pub struct $name<F: Field>(KMatrixPreset<F>);
+        impl<F: Field + 'static> $name<F> {
+            pub fn new(channel: usize, decay: Decay) -> Self {
+                Self(KMatrixPreset::new(
+                    stringify!($name),
+                    channel,
+                    decay,
+                    KMatrixConfig::$config(),
+                ))
+            }
+            pub fn with_non_resonant(mut self, channels: &[usize]) -> Self {
+                self.0.non_resonant = channels.to_vec();
+                self
+            }
         }
-    }
+        impl<F: Field + 'static> Node<F> for $name<F> {
+            fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+                self.0.precalculate(dataset)
+            }
+            fn calculate(
+                &self,
+                parameters: &[F],
+                event: &Event<F>,
+            ) -> Result<Complex<F>, RustitudeError> {
+                self.0.calculate(parameters, event)
+            }
+            fn calculate_gradient(
+                &self,
+                parameters: &[F],
+                event: &Event<F>,
+            ) -> Result<Vec<Complex<F>>, RustitudeError> {
+                self.0.calculate_gradient(parameters, event)
+            }
+            fn parameters(&self) -> Vec<String> {
+                self.0.parameters()
+            }
+            fn spec(&self) -> Option<NodeSpec> {
+                self.0.spec()
+            }
+        }
+    };
 }
 
-impl<F: Field + RealField> Node<F> for KMatrixA0<F> {
-    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
-        self.data = dataset
-            .events
-            .par_iter()
-            .map(|event| {
-                let s = self.decay.resonance_p4(event).m2();
-                let barrier_mat = self.constants.barrier_matrix(s);
-                let pvector_constants = SMatrix::<Complex<F>, 2, 2>::from_fn(|i, a| {
-                    Complex::from(barrier_mat[(i, a)])
-                        * self.constants.g[(i, a)]
-                        * self.constants.pole_product_remainder(s, a)
-                });
-                (self.constants.ikc_inv(s, self.channel), pvector_constants)
-            })
-            .collect();
-        Ok(())
-    }
-    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        let betas = SVector::<Complex<F>, 2>::new(
-            Complex::new(parameters[0], parameters[1]),
-            Complex::new(parameters[2], parameters[3]),
-        );
-        let (ikc_inv_vec, pvector_constants_mat) = self.data[event.index];
-        Ok(KMatrixConstants::calculate_k_matrix(
-            &betas,
-            &ikc_inv_vec,
-            &pvector_constants_mat,
-        ))
-    }
-    fn parameters(&self) -> Vec<String> {
-        vec![
-            "a0_980 re".to_string(),
-            "a0_980 im".to_string(),
-            "a0_1450 re".to_string(),
-            "a0_1450 im".to_string(),
-        ]
-    }
-}
+impl_kmatrix_preset!(
+    /// A [`KMatrix`] with the [`KMatrixConfig::f0`] constants.
+    KMatrixF0,
+    f0
+);
+impl_kmatrix_preset!(
+    /// A [`KMatrix`] with the [`KMatrixConfig::f2`] constants.
+    KMatrixF2,
+    f2
+);
+impl_kmatrix_preset!(
+    /// A [`KMatrix`] with the [`KMatrixConfig::a0`] constants.
+    KMatrixA0,
+    a0
+);
+impl_kmatrix_preset!(
+    /// A [`KMatrix`] with the [`KMatrixConfig::a2`] constants.
+    KMatrixA2,
+    a2
+);
+impl_kmatrix_preset!(
+    /// A [`KMatrix`] with the [`KMatrixConfig::rho`] constants.
+    KMatrixRho,
+    rho
+);
+impl_kmatrix_preset!(
+    /// A [`KMatrix`] with the [`KMatrixConfig::pi1`] constants.
+    KMatrixPi1,
+    pi1
+);
 
-#[derive(Clone)]
-#[allow(clippy::type_complexity)]
-pub struct KMatrixA2<F: Field> {
-    channel: usize,
-    decay: Decay,
-    constants: KMatrixConstants<F, 3, 2>,
-    data: Vec<(SVector<Complex<F>, 3>, SMatrix<Complex<F>, 3, 2>)>,
-}
-#[rustfmt::skip]
-impl<F: Field + 'static> KMatrixA2<F> {
-    pub fn new(channel: usize, decay: Decay) -> Self {
-        Self {
-            channel,
-            decay,
-            constants: KMatrixConstants {
-                g: SMatrix::<F, 3, 2>::from_vec(convert_vec!(vec![
-                     0.30073,  0.21426, -0.09162,
-                     0.68567,  0.12543,  0.00184 
-                ], F)),
-                c: SMatrix::<F, 3, 3>::from_vec(convert_vec!(vec![
-                    -0.40184,  0.00033, -0.08707,
-                     0.00033, -0.21416, -0.06193,
-                    -0.08707, -0.06193, -0.17435,
-                ], F)),
-                m1s: convert_array!([0.1349768, 0.493677, 0.1349768], F),
-                m2s: convert_array!([0.547862, 0.497611, 0.95778], F),
-                mrs: convert_array!([1.30080, 1.75351], F),
-                adler_zero: None,
-                l: 2,
-            },
-            data: Vec::default()
-        }
-    }
+/// A form factor $`F(t)`$ at the vertex of an exchanged particle with mass $`m`$ and squared
+/// four-momentum transfer $`t`$, normalized so that $`F(m^2) = 1`$.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum FormFactor<F: Field> {
+    /// $`F(t) = 1`$.
+    #[default]
+    None,
+    /// $`F(t) = e^{b(t - m^2)}`$ with the given slope $`b`$.
+    Exponential(F),
+    /// $`F(t) = (\Lambda^2 - m^2) / (\Lambda^2 - t)`$ with the given cutoff $`\Lambda`$.
+    Monopole(F),
+    /// $`F(t) = \left[(\Lambda^2 - m^2) / (\Lambda^2 - t)\right]^2`$ with the given cutoff
+    /// $`\Lambda`$.
+    Dipole(F),
 }
 
-impl<F: Field + RealField> Node<F> for KMatrixA2<F> {
-    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
-        self.data = dataset
-            .events
-            .par_iter()
-            .map(|event| {
-                let s = self.decay.resonance_p4(event).m2();
-                let barrier_mat = self.constants.barrier_matrix(s);
-                let pvector_constants = SMatrix::<Complex<F>, 3, 2>::from_fn(|i, a| {
-                    Complex::from(barrier_mat[(i, a)])
-                        * self.constants.g[(i, a)]
-                        * self.constants.pole_product_remainder(s, a)
-                });
-                (self.constants.ikc_inv(s, self.channel), pvector_constants)
-            })
-            .collect();
-        Ok(())
-    }
-    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        let betas = SVector::<Complex<F>, 2>::new(
-            Complex::new(parameters[0], parameters[1]),
-            Complex::new(parameters[2], parameters[3]),
-        );
-        let (ikc_inv_vec, pvector_constants_mat) = self.data[event.index];
-        Ok(KMatrixConstants::calculate_k_matrix(
-            &betas,
-            &ikc_inv_vec,
-            &pvector_constants_mat,
-        ))
-    }
-    fn parameters(&self) -> Vec<String> {
-        vec![
-            "a2_1320 re".to_string(),
-            "a2_1320 im".to_string(),
-            "a2_1700 re".to_string(),
-            "a2_1700 im".to_string(),
-        ]
+impl<F: Field> FormFactor<F> {
+    pub fn value(&self, t: F, m: F) -> F {
+        match *self {
+            Self::None => F::one(),
+            Self::Exponential(b) => F::exp(b * (t - m.powi(2))),
+            Self::Monopole(cutoff) => (cutoff.powi(2) - m.powi(2)) / (cutoff.powi(2) - t),
+            Self::Dipole(cutoff) => ((cutoff.powi(2) - m.powi(2)) / (cutoff.powi(2) - t)).powi(2),
+        }
     }
 }
 
+/// A [`Node`] for the non-resonant Deck amplitude of a three-body final state, in which the
+/// beam dissociates into an isobar and a virtual exchanged particle (a pion by default) which
+/// then scatters elastically off of the target into the bachelor and the recoil.
+///
+/// With $`t_{\text{ex}} = (p_{\text{beam}} - p_{\text{isobar}})^2`$, the subenergy
+/// $`s = (p_{\text{bachelor}} + p_{\text{recoil}})^2`$ of the elastic scattering, and the
+/// momentum transfer $`t = (p_{\text{beam}} - p_{\text{isobar}} - p_{\text{bachelor}})^2`$ to the
+/// target, this struct implements [`Node`] to calculate
+///
+/// ```math
+/// \frac{F(t_{\text{ex}})}{m_{\text{ex}}^2 - t_{\text{ex}}}\, s\, e^{b t / 2}
+/// ```
+///
+/// where $`m_{\text{ex}}`$ is the mass of the exchanged particle, $`F`$ is a [`FormFactor`] at
+/// the exchange vertex, and $`b`$ is the slope of the elastic scattering. The amplitude is real
+/// and has no free parameters, so it is typically multiplied by a complex scalar and combined
+/// coherently with the resonant waves.
 #[derive(Clone)]
-#[allow(clippy::type_complexity)]
-pub struct KMatrixRho<F: Field> {
-    channel: usize,
-    decay: Decay,
-    constants: KMatrixConstants<F, 3, 2>,
-    data: Vec<(SVector<Complex<F>, 3>, SMatrix<Complex<F>, 3, 2>)>,
+pub struct Deck<F: Field> {
+    isobar: Decay,
+    bachelor: usize,
+    exchange_mass: F,
+    form_factor: FormFactor<F>,
+    slope: F,
+    data: Vec<F>,
 }
-#[rustfmt::skip]
-impl<F: Field + 'static> KMatrixRho<F> {
-    pub fn new(channel: usize, decay: Decay) -> Self {
+
+impl<F: Field> Deck<F> {
+    pub fn new(
+        isobar: Decay,
+        bachelor: usize,
+        exchange_mass: F,
+        form_factor: FormFactor<F>,
+        slope: F,
+    ) -> Self {
         Self {
-            channel,
-            decay,
-            constants: KMatrixConstants {
-                g: SMatrix::<F, 3, 2>::from_vec(convert_vec!(vec![
-                     0.28023,  0.01806,  0.06501,
-                     0.16318,  0.53879,  0.00495,
-                ], F)),
-                c: SMatrix::<F, 3, 3>::from_vec(convert_vec!(vec![
-                    -0.06948,  0.00000,  0.07958,
-                     0.00000,  0.00000,  0.00000,
-                     0.07958,  0.00000, -0.60000,
-                ], F)),
-                m1s: convert_array!([0.1349768, 2.0 * 0.1349768, 0.493677], F),
-                m2s: convert_array!([0.1349768, 2.0 * 0.1349768, 0.497611], F),
-                mrs: convert_array!([0.71093, 1.58660], F),
-                adler_zero: None,
-                l: 1,
-            },
+            isobar,
+            bachelor,
+            exchange_mass,
+            form_factor,
+            slope,
             data: Vec::default(),
         }
     }
 }
 
-impl<F: Field + RealField> Node<F> for KMatrixRho<F> {
+impl<F: Field> Node<F> for Deck<F> {
     fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
         self.data = dataset
             .events
             .par_iter()
             .map(|event| {
-                let s = self.decay.resonance_p4(event).m2();
-                let barrier_mat = self.constants.barrier_matrix(s);
-                let pvector_constants = SMatrix::<Complex<F>, 3, 2>::from_fn(|i, a| {
-                    Complex::from(barrier_mat[(i, a)])
-                        * self.constants.g[(i, a)]
-                        * self.constants.pole_product_remainder(s, a)
-                });
-                (self.constants.ikc_inv(s, self.channel), pvector_constants)
+                let isobar = self.isobar.resonance_p4(event);
+                let bachelor = event.daughter_p4s[self.bachelor];
+                let t_ex = (event.beam_p4 - isobar).m2();
+                let s = (bachelor + event.recoil_p4).m2();
+                let t = (event.beam_p4 - isobar - bachelor).m2();
+                self.form_factor.value(t_ex, self.exchange_mass)
+                    / (self.exchange_mass.powi(2) - t_ex)
+                    * s
+                    * F::exp(self.slope * t / convert!(2, F))
             })
             .collect();
         Ok(())
     }
-    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        let betas = SVector::<Complex<F>, 2>::new(
-            Complex::new(parameters[0], parameters[1]),
-            Complex::new(parameters[2], parameters[3]),
-        );
-        let (ikc_inv_vec, pvector_constants_mat) = self.data[event.index];
-        Ok(KMatrixConstants::calculate_k_matrix(
-            &betas,
-            &ikc_inv_vec,
-            &pvector_constants_mat,
-        ))
-    }
-    fn parameters(&self) -> Vec<String> {
-        vec![
-            "rho_770 re".to_string(),
-            "rho_770 im".to_string(),
-            "rho_1700 re".to_string(),
-            "rho_1700 im".to_string(),
-        ]
-    }
-}
 
-#[derive(Clone)]
-#[allow(clippy::type_complexity)]
-pub struct KMatrixPi1<F: Field> {
-    channel: usize,
-    decay: Decay,
-    constants: KMatrixConstants<F, 2, 1>,
-    data: Vec<(SVector<Complex<F>, 2>, SMatrix<Complex<F>, 2, 1>)>,
-}
-#[rustfmt::skip]
-impl<F: Field + 'static> KMatrixPi1<F> {
-    pub fn new(channel: usize, decay: Decay) -> Self {
-        Self {
-            channel,
-            decay,
-            constants: KMatrixConstants {
-                g: SMatrix::<F, 2, 1>::from_vec(convert_vec!(vec![
-                    0.80564,  1.04595
-                ], F)),
-                c: SMatrix::<F, 2, 2>::from_vec(convert_vec!(vec![
-                    1.05000,  0.15163,
-                    0.15163, -0.24611,
-                ], F)),
-                m1s: convert_array!([0.1349768, 0.1349768], F),
-                m2s: convert_array!([0.547862, 0.95778], F),
-                mrs: convert_array!([1.38552], F),
-                adler_zero: None,
-                l: 1,
-            },
-            data: Vec::default()
-        }
+    fn calculate(&self, _parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        Ok(Complex::from(self.data[event.index]))
     }
-}
 
-impl<F: Field + RealField> Node<F> for KMatrixPi1<F> {
-    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
-        self.data = dataset
-            .events
-            .par_iter()
-            .map(|event| {
-                let s = self.decay.resonance_p4(event).m2();
-                let barrier_mat = self.constants.barrier_matrix(s);
-                let pvector_constants = SMatrix::<Complex<F>, 2, 1>::from_fn(|i, a| {
-                    Complex::from(barrier_mat[(i, a)])
-                        * self.constants.g[(i, a)]
-                        * self.constants.pole_product_remainder(s, a)
-                });
-                (self.constants.ikc_inv(s, self.channel), pvector_constants)
-            })
-            .collect();
-        Ok(())
-    }
-    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
-        let betas = SVector::<Complex<F>, 1>::new(Complex::new(parameters[0], parameters[1]));
-        let (ikc_inv_vec, pvector_constants_mat) = self.data[event.index];
-        Ok(KMatrixConstants::calculate_k_matrix(
-            &betas,
-            &ikc_inv_vec,
-            &pvector_constants_mat,
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "Deck",
+            serde_json::json!({
+                "isobar": self.isobar,
+                "bachelor": self.bachelor,
+                "exchange_mass": self.exchange_mass,
+                "form_factor": self.form_factor,
+                "slope": self.slope,
+            }),
         ))
     }
-    fn parameters(&self) -> Vec<String> {
-        vec!["pi1_1600 re".to_string(), "pi1_1600 im".to_string()]
-    }
 }
@@ -1,5 +1,5 @@
 use rayon::prelude::*;
-use rustitude_core::prelude::*;
+use rustitude_core::{frames::polarization_angle, prelude::*};
 use sphrs::SHCoordinates;
 
 use crate::utils::{Decay, Frame};
@@ -32,10 +32,7 @@ impl<F: Field> Node<F> for TwoPiSDME<F> {
             .par_iter()
             .map(|event| {
                 let (_, y, _, p) = self.decay.coordinates(self.frame, 0, event);
-                let big_phi = F::atan2(
-                    y.dot(&event.eps),
-                    event.beam_p4.direction().dot(&event.eps.cross(&y)),
-                );
+                let big_phi = polarization_angle(event, &y);
                 let pgamma = event.eps_mag();
                 (
                     p.theta_cos().powi(2),
@@ -93,6 +90,13 @@ impl<F: Field> Node<F> for TwoPiSDME<F> {
             "rho_1n12".to_string(),
         ]
     }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "TwoPiSDME",
+            serde_json::json!({ "decay": self.decay, "frame": self.frame }),
+        ))
+    }
 }
 
 #[derive(Clone)]
@@ -124,15 +128,12 @@ impl<F: Field> Node<F> for ThreePiSDME<F> {
             .map(|event| {
                 let res_p4 = self.decay.resonance_p4(event);
                 let p1_res_p4 = self.decay.primary_p4(event).boost_along(&res_p4);
-                let p2_res_p4 = self.decay.primary_p4(event).boost_along(&res_p4);
+                let p2_res_p4 = self.decay.secondary_p4(event).boost_along(&res_p4);
                 let norm = p1_res_p4.momentum().cross(&p2_res_p4.momentum()).unit();
                 let (_, y, _, p) = self
-                    .frame
-                    .coordinates_from_boosted_vec(self.decay, &norm, event);
-                let big_phi = F::atan2(
-                    y.dot(&event.eps),
-                    event.beam_p4.direction().dot(&event.eps.cross(&y)),
-                );
+                    .decay
+                    .coordinates_from_boosted_vec(self.frame, &norm, event);
+                let big_phi = polarization_angle(event, &y);
                 let pgamma = event.eps_mag();
                 (
                     p.theta_cos().powi(2),
@@ -190,6 +191,13 @@ impl<F: Field> Node<F> for ThreePiSDME<F> {
             "rho_1n12".to_string(),
         ]
     }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "ThreePiSDME",
+            serde_json::json!({ "decay": self.decay, "frame": self.frame }),
+        ))
+    }
 }
 
 #[derive(Clone)]
@@ -220,10 +228,7 @@ impl<F: Field> Node<F> for VecRadiativeSDME<F> {
             .par_iter()
             .map(|event| {
                 let (_, y, _, p) = self.decay.coordinates(self.frame, 0, event);
-                let big_phi = F::atan2(
-                    y.dot(&event.eps),
-                    event.beam_p4.direction().dot(&event.eps.cross(&y)),
-                );
+                let big_phi = polarization_angle(event, &y);
                 let pgamma = event.eps_mag();
                 (
                     p.theta_cos().powi(2),
@@ -283,4 +288,130 @@ impl<F: Field> Node<F> for VecRadiativeSDME<F> {
             "rho_1n12".to_string(),
         ]
     }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "VecRadiativeSDME",
+            serde_json::json!({ "decay": self.decay, "frame": self.frame }),
+        ))
+    }
+}
+
+/// A [`Node`] for the full Schilling-Seyboth-Wolf decay angular distribution of a vector meson
+/// ($`\rho`$, $`\omega`$, $`\phi`$) photoproduced by a linearly polarized beam,
+///
+/// ```math
+/// W(\cos\theta, \phi, \Phi) = W^0(\cos\theta, \phi) - P_\gamma \cos(2\Phi) W^1(\cos\theta, \phi) - P_\gamma \sin(2\Phi) W^2(\cos\theta, \phi)
+/// ```
+///
+/// where
+///
+/// ```math
+/// \begin{aligned}
+/// W^0 &= \frac{3}{4\pi}\left[\frac{1}{2}(1 - \rho^0_{00}) + \frac{1}{2}(3\rho^0_{00} - 1)\cos^2\theta - \sqrt{2}\,\text{Re}\,\rho^0_{10}\sin 2\theta\cos\phi - \rho^0_{1-1}\sin^2\theta\cos 2\phi\right] \\
+/// W^1 &= \frac{3}{4\pi}\left[\rho^1_{11}\sin^2\theta + \rho^1_{00}\cos^2\theta - \sqrt{2}\,\text{Re}\,\rho^1_{10}\sin 2\theta\cos\phi - \rho^1_{1-1}\sin^2\theta\cos 2\phi\right] \\
+/// W^2 &= \frac{3}{4\pi}\left[\sqrt{2}\,\text{Im}\,\rho^2_{10}\sin 2\theta\sin\phi + \text{Im}\,\rho^2_{1-1}\sin^2\theta\sin 2\phi\right]
+/// \end{aligned}
+/// ```
+///
+/// and $`\Phi`$ is the angle between the beam polarization and the production plane. The decay
+/// angles $`(\theta, \phi)`$ are measured in the given [`Frame`] from the primary daughter of a
+/// two-body decay ($`\rho \to \pi\pi`$, $`\phi \to K\bar{K}`$) or from the normal to the decay
+/// plane of a three-body decay ($`\omega \to \pi^+\pi^-\pi^0`$), using its primary and secondary
+/// daughters. The [`Node`] returns $`\sqrt{|W|}`$ so that the intensity of an [`Amplitude`] built
+/// from it is $`W`$.
+///
+/// The parameters are the nine independent matrix elements `rho_000`, `rho_100`, `rho_1n10`
+/// ($`\rho^0_{00}`$, $`\text{Re}\,\rho^0_{10}`$, $`\rho^0_{1-1}`$), `rho_111`, `rho_001`,
+/// `rho_101`, `rho_1n11` ($`\rho^1_{11}`$, $`\rho^1_{00}`$, $`\text{Re}\,\rho^1_{10}`$,
+/// $`\rho^1_{1-1}`$), and `rho_102`, `rho_1n12` ($`\text{Im}\,\rho^2_{10}`$,
+/// $`\text{Im}\,\rho^2_{1-1}`$).
+#[derive(Clone)]
+pub struct VectorSDME<F: Field> {
+    decay: Decay,
+    frame: Frame,
+    data: Vec<(F, F, F, F, F, F)>,
+}
+
+impl<F: Field> VectorSDME<F> {
+    pub fn new(decay: Decay, frame: Frame) -> Self {
+        Self {
+            decay,
+            frame,
+            data: Vec::default(),
+        }
+    }
+}
+
+impl<F: Field> Node<F> for VectorSDME<F> {
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        self.data = dataset
+            .events
+            .par_iter()
+            .map(|event| {
+                let (_, y, _, p) = match self.decay {
+                    Decay::TwoBodyDecay(_) => self.decay.coordinates(self.frame, 0, event),
+                    Decay::ThreeBodyDecay(_) => {
+                        let res_p4 = self.decay.resonance_p4(event);
+                        let p1_res_p4 = self.decay.primary_p4(event).boost_along(&res_p4);
+                        let p2_res_p4 = self.decay.secondary_p4(event).boost_along(&res_p4);
+                        let norm = p1_res_p4.momentum().cross(&p2_res_p4.momentum()).unit();
+                        self.decay
+                            .coordinates_from_boosted_vec(self.frame, &norm, event)
+                    }
+                };
+                (
+                    p.theta_cos().powi(2),
+                    F::sin(p.theta()).powi(2),
+                    F::sin(convert!(2, F) * p.theta()),
+                    p.phi(),
+                    polarization_angle(event, &y),
+                    event.eps_mag(),
+                )
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn calculate(&self, parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        let (cossqtheta, sinsqtheta, sin2theta, phi, big_phi, pgamma) = self.data[event.index];
+        let [rho_000, rho_100, rho_1n10, rho_111, rho_001, rho_101, rho_1n11, rho_102, rho_1n12] =
+            [0, 1, 2, 3, 4, 5, 6, 7, 8].map(|i| parameters[i]);
+        let w0 = convert!(0.5, F) * (F::one() - rho_000)
+            + convert!(0.5, F) * (convert!(3, F) * rho_000 - F::one()) * cossqtheta
+            - F::SQRT_2() * rho_100 * sin2theta * F::cos(phi)
+            - rho_1n10 * sinsqtheta * F::cos(convert!(2, F) * phi);
+        let w1 = rho_111 * sinsqtheta + rho_001 * cossqtheta
+            - F::SQRT_2() * rho_101 * sin2theta * F::cos(phi)
+            - rho_1n11 * sinsqtheta * F::cos(convert!(2, F) * phi);
+        let w2 = F::SQRT_2() * rho_102 * sin2theta * F::sin(phi)
+            + rho_1n12 * sinsqtheta * F::sin(convert!(2, F) * phi);
+        Ok(Complex::from(F::sqrt(F::abs(
+            (convert!(3, F) / (convert!(4, F) * F::PI()))
+                * (w0
+                    - pgamma * F::cos(convert!(2, F) * big_phi) * w1
+                    - pgamma * F::sin(convert!(2, F) * big_phi) * w2),
+        ))))
+    }
+
+    fn parameters(&self) -> Vec<String> {
+        vec![
+            "rho_000".to_string(),
+            "rho_100".to_string(),
+            "rho_1n10".to_string(),
+            "rho_111".to_string(),
+            "rho_001".to_string(),
+            "rho_101".to_string(),
+            "rho_1n11".to_string(),
+            "rho_102".to_string(),
+            "rho_1n12".to_string(),
+        ]
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "VectorSDME",
+            serde_json::json!({ "decay": self.decay, "frame": self.frame }),
+        ))
+    }
 }
@@ -1,57 +1,16 @@
 use std::{fmt::Display, num::ParseIntError, str::FromStr};
 
-use factorial::Factorial;
-use rustitude_core::{convert, prelude::*};
+use rayon::prelude::*;
+pub use rustitude_core::frames::{Frame, ParseFrameError};
+use rustitude_core::{frames::Axes, math, prelude::*};
+use serde::{Deserialize, Serialize};
 use sphrs::Coordinates;
 use thiserror::Error;
 
-pub fn breakup_momentum<F: Field>(m0: F, m1: F, m2: F) -> F {
-    F::sqrt(F::abs(
-        m0.powi(4) + m1.powi(4) + m2.powi(4)
-            - convert!(2, F)
-                * (m0.powi(2) * m1.powi(2) + m0.powi(2) * m2.powi(2) + m1.powi(2) * m2.powi(2)),
-    )) / (convert!(2, F) * m0)
-}
-
-/// Computes the ([`Complex<F>`]) breakup momentum of a particle with mass `m0` decaying into two particles
-/// with masses `m1` and `m2`.
-pub fn breakup_momentum_c<F: Field>(m0: F, m1: F, m2: F) -> Complex<F> {
-    rho(m0.powi(2), m1, m2) * m0 / convert!(2, F)
-}
-
-pub fn chi_plus<F: Field>(s: F, m1: F, m2: F) -> Complex<F> {
-    Complex::from(F::one() - ((m1 + m2) * (m1 + m2)) / s)
-}
-
-pub fn chi_minus<F: Field>(s: F, m1: F, m2: F) -> Complex<F> {
-    Complex::from(F::one() - ((m1 - m2) * (m1 - m2)) / s)
-}
-
-pub fn rho<F: Field>(s: F, m1: F, m2: F) -> Complex<F> {
-    Complex::sqrt(chi_plus(s, m1, m2) * chi_minus(s, m1, m2))
-}
+pub use rustitude_core::math::{breakup_momentum, breakup_momentum_c, chi_minus, chi_plus, rho};
 
 pub fn blatt_weisskopf<F: Field>(m0: F, m1: F, m2: F, l: usize) -> F {
-    let q = breakup_momentum(m0, m1, m2);
-    let z = q.powi(2) / convert!(0.1973, F).powi(2);
-    match l {
-        0 => F::one(),
-        1 => F::sqrt((convert!(2, F) * z) / (z + F::one())),
-        2 => F::sqrt(
-            (convert!(13.0, F) * z.powi(2)) / ((z - convert!(3, F)).powi(2) + convert!(9, F) * z),
-        ),
-        3 => F::sqrt(
-            (convert!(277.0, F) * z.powi(3))
-                / (z * (z - convert!(15.0, F)).powi(2)
-                    + convert!(9, F) * (convert!(2, F) * z - convert!(5, F)).powi(2)),
-        ),
-        4 => F::sqrt(
-            (convert!(12746.0, F) * z.powi(4))
-                / (z.powi(2) - convert!(45.0, F) * z + convert!(105.0, F)).powi(2)
-                + convert!(25.0, F) * z * (convert!(2, F) * z - convert!(21.0, F)).powi(2),
-        ),
-        l => panic!("L = {l} is not yet implemented"),
-    }
+    math::blatt_weisskopf(breakup_momentum(m0, m1, m2), l)
 }
 
 /// Computes the ([`Complex<F>`]) Blatt-Weisskopf barrier factor representing the energy required for a particle
@@ -60,56 +19,11 @@ pub fn blatt_weisskopf<F: Field>(m0: F, m1: F, m2: F, l: usize) -> F {
 /// In applications where `m0` is expected to be above the mass threshold to produce `m1` and
 /// `m2`, the absolute value of this function can be safely assumed to be equal to its value.
 pub fn blatt_weisskopf_c<F: Field>(m0: F, m1: F, m2: F, l: usize) -> Complex<F> {
-    let q = breakup_momentum_c(m0, m1, m2);
-    let z = q.powi(2) / convert!(0.1973, F).powi(2);
-    match l {
-        0 => Complex::from(F::one()),
-        1 => Complex::sqrt((Complex::from(convert!(2, F)) * z) / (z + F::one())),
-        2 => Complex::sqrt(
-            (z.powi(2) * convert!(13.0, F)) / ((z - convert!(3, F)).powi(2) + z * convert!(9, F)),
-        ),
-        3 => Complex::sqrt(
-            (z.powi(3) * convert!(277.0, F))
-                / (z * (z - convert!(15.0, F)).powi(2)
-                    + (z * convert!(2, F) - convert!(5, F)).powi(2))
-                * convert!(9, F),
-        ),
-        4 => Complex::sqrt(
-            (z.powi(4) * convert!(12746.0, F))
-                / (z.powi(2) - z * convert!(45.0, F) + convert!(105.0, F)).powi(2)
-                + z * convert!(25.0, F) * (z * convert!(2, F) - convert!(21.0, F)).powi(2),
-        ),
-        l => panic!("L = {l} is not yet implemented"),
-    }
+    math::blatt_weisskopf_c(breakup_momentum_c(m0, m1, m2), l)
 }
 
 pub fn small_wigner_d_matrix<F: Field>(beta: F, j: usize, m: isize, n: isize) -> F {
-    let jpm = (j as i32 + m as i32) as u32;
-    let jmm = (j as i32 - m as i32) as u32;
-    let jpn = (j as i32 + n as i32) as u32;
-    let jmn = (j as i32 - n as i32) as u32;
-    let prefactor = F::sqrt(convert!(
-        jpm.factorial() * jmm.factorial() * jpn.factorial() * jmn.factorial(),
-        F
-    ));
-    let s_min = isize::max(0, n - m) as usize;
-    let s_max = isize::min(jpn as isize, jmm as isize) as usize;
-    let sum: F = (s_min..=s_max)
-        .map(|s| {
-            (F::powi(-F::one(), m as i32 - n as i32 + s as i32)
-                * (F::cos(beta / convert!(2, F))
-                    .powi(2 * (j as i32) + n as i32 - m as i32 - 2 * (s as i32)))
-                * (F::sin(beta / convert!(2, F)).powi(m as i32 - n as i32 + 2 * s as i32)))
-                / convert!(
-                    (jpm - s as u32).factorial()
-                        * (s as u32).factorial()
-                        * ((m - n + s as isize) as u32).factorial()
-                        * (jmm - s as u32).factorial(),
-                    F
-                )
-        })
-        .sum();
-    prefactor * sum
+    math::wigner_small_d(beta, 2 * j as u32, 2 * m as i32, 2 * n as i32)
 }
 
 pub fn wigner_d_matrix<F: Field>(
@@ -120,12 +34,10 @@ pub fn wigner_d_matrix<F: Field>(
     m: isize,
     n: isize,
 ) -> Complex<F> {
-    Complex::cis(convert!(-m, F) * alpha)
-        * small_wigner_d_matrix(beta, j, m, n)
-        * Complex::cis(convert!(-n, F) * gamma)
+    math::wigner_d(alpha, beta, gamma, 2 * j as u32, 2 * m as i32, 2 * n as i32)
 }
 
-#[derive(Clone, Copy, Default, PartialEq)]
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 #[rustfmt::skip]
 pub enum Wave {
     #[default]
@@ -203,34 +115,6 @@ impl Display for Wave {
     }
 }
 
-#[derive(Copy, Clone, PartialEq)]
-pub enum Frame {
-    Helicity,
-    GottfriedJackson,
-}
-
-#[derive(Debug, PartialEq, Eq, Error)]
-#[error("Unknown frame: {0}")]
-pub struct ParseFrameError(String);
-
-impl From<ParseFrameError> for RustitudeError {
-    fn from(value: ParseFrameError) -> Self {
-        RustitudeError::ParseError(value.to_string())
-    }
-}
-
-impl FromStr for Frame {
-    type Err = ParseFrameError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_ref() {
-            "helicity" | "hx" => Ok(Frame::Helicity),
-            "gottfried-jackson" | "gj" => Ok(Frame::GottfriedJackson),
-            _ => Err(ParseFrameError(s.to_string())),
-        }
-    }
-}
-
 pub fn coordinates<F: Field + 'static>(
     x: &Vector3<F>,
     y: &Vector3<F>,
@@ -240,61 +124,48 @@ pub fn coordinates<F: Field + 'static>(
     Coordinates::cartesian(p.dot(x), p.dot(y), p.dot(z))
 }
 
-impl Frame {
-    pub fn coordinates<F: Field>(
+/// The $`\hat{y}`$ axis of a [`Frame`] and the [`Coordinates`] of a daughter in it, as stored by
+/// [`Decay::shared_coordinates`].
+pub type FrameCoordinates<F> = (Vector3<F>, Coordinates<F>);
+
+/// The coordinate methods of [`Frame`] from before it was moved to [`rustitude_core::frames`].
+pub trait FrameExt {
+    #[deprecated(note = "use `Decay::coordinates_of` instead")]
+    fn coordinates<F: Field>(
+        &self,
+        decay: Decay,
+        other_p4: &FourMomentum<F>,
+        event: &Event<F>,
+    ) -> (Vector3<F>, Vector3<F>, Vector3<F>, Coordinates<F>);
+    #[deprecated(note = "use `Decay::coordinates_from_boosted_vec` instead")]
+    fn coordinates_from_boosted_vec<F: Field>(
+        &self,
+        decay: Decay,
+        other_res_vec: &Vector3<F>,
+        event: &Event<F>,
+    ) -> (Vector3<F>, Vector3<F>, Vector3<F>, Coordinates<F>);
+}
+
+impl FrameExt for Frame {
+    fn coordinates<F: Field>(
         &self,
         decay: Decay,
         other_p4: &FourMomentum<F>,
         event: &Event<F>,
     ) -> (Vector3<F>, Vector3<F>, Vector3<F>, Coordinates<F>) {
-        let resonance_p4 = decay.resonance_p4(event);
-        let beam_res_vec = event.beam_p4.boost_along(&resonance_p4).momentum();
-        let recoil_res_vec = event.recoil_p4.boost_along(&resonance_p4).momentum();
-        let other_res_vec = other_p4.boost_along(&resonance_p4).momentum();
-        let (x, y, z) = match self {
-            Frame::Helicity => {
-                let z = -recoil_res_vec.unit();
-                let y = beam_res_vec.cross(&z).unit();
-                let x = y.cross(&z);
-                (x, y, z)
-            }
-            Frame::GottfriedJackson => {
-                let z = beam_res_vec.unit();
-                let y = event.beam_p4.momentum().cross(&(-recoil_res_vec)).unit();
-                let x = y.cross(&z);
-                (x, y, z)
-            }
-        };
-        (x, y, z, coordinates(&x, &y, &z, &other_res_vec))
+        decay.coordinates_of(*self, other_p4, event)
     }
-    pub fn coordinates_from_boosted_vec<F: Field>(
+    fn coordinates_from_boosted_vec<F: Field>(
         &self,
         decay: Decay,
         other_res_vec: &Vector3<F>,
         event: &Event<F>,
     ) -> (Vector3<F>, Vector3<F>, Vector3<F>, Coordinates<F>) {
-        let resonance_p4 = decay.resonance_p4(event);
-        let beam_res_vec = event.beam_p4.boost_along(&resonance_p4).momentum();
-        let recoil_res_vec = event.recoil_p4.boost_along(&resonance_p4).momentum();
-        let (x, y, z) = match self {
-            Frame::Helicity => {
-                let z = -recoil_res_vec.unit();
-                let y = beam_res_vec.cross(&z).unit();
-                let x = y.cross(&z);
-                (x, y, z)
-            }
-            Frame::GottfriedJackson => {
-                let z = beam_res_vec.unit();
-                let y = event.beam_p4.momentum().cross(&(-recoil_res_vec)).unit();
-                let x = y.cross(&z);
-                (x, y, z)
-            }
-        };
-        (x, y, z, coordinates(&x, &y, &z, other_res_vec))
+        decay.coordinates_from_boosted_vec(*self, other_res_vec, event)
     }
 }
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Sign {
     Positive = 1,
     Negative = -1,
@@ -331,7 +202,7 @@ impl Display for Sign {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum Decay {
     TwoBodyDecay([usize; 2]),
     ThreeBodyDecay([usize; 3]),
@@ -420,12 +291,80 @@ impl Decay {
             Decay::ThreeBodyDecay(inds) => &event.daughter_p4s[inds[2]],
         }
     }
+    /// The key of [`Decay::shared_coordinates`].
+    pub fn coordinates_key(&self, frame: Frame, index: usize) -> String {
+        let decay = match self {
+            Decay::TwoBodyDecay(inds) => format!("{inds:?}"),
+            Decay::ThreeBodyDecay(inds) => format!("{inds:?}"),
+        };
+        format!("gluex.coordinates{decay}/{frame}/{index}")
+    }
+    /// A [`SharedQuantity`] which holds the $`\hat{y}`$ axis of the [`Frame`] and the
+    /// [`Coordinates`] of the daughter at `index` for each event (see [`Decay::coordinates`]).
+    ///
+    /// Every [`Node`] which requests the same [`Decay`], [`Frame`], and daughter shares a single
+    /// computation when its [`Model`] is loaded. The values can be read with
+    /// [`Decay::get_shared_coordinates`].
+    pub fn shared_coordinates<F: Field + 'static>(
+        &self,
+        frame: Frame,
+        index: usize,
+    ) -> SharedQuantity<F> {
+        let decay = *self;
+        SharedQuantity::new(
+            &self.coordinates_key(frame, index),
+            move |dataset: &Dataset<F>, _: &SharedData| {
+                Ok(dataset
+                    .events
+                    .par_iter()
+                    .map(|event| {
+                        let (_, y, _, p) = decay.coordinates(frame, index, event);
+                        (y, p)
+                    })
+                    .collect::<Vec<FrameCoordinates<F>>>())
+            },
+        )
+    }
+    /// Reads the values of [`Decay::shared_coordinates`] from a [`SharedData`].
+    ///
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::EvaluationError`] if the coordinates were not
+    /// computed.
+    pub fn get_shared_coordinates<'a, F: Field + 'static>(
+        &self,
+        frame: Frame,
+        index: usize,
+        shared: &'a SharedData,
+    ) -> Result<&'a [FrameCoordinates<F>], RustitudeError> {
+        shared
+            .require::<Vec<FrameCoordinates<F>>>(&self.coordinates_key(frame, index))
+            .map(Vec::as_slice)
+    }
     pub fn coordinates<F: Field>(
         &self,
         frame: Frame,
         index: usize,
         event: &Event<F>,
     ) -> (Vector3<F>, Vector3<F>, Vector3<F>, Coordinates<F>) {
-        frame.coordinates(*self, self.daughter_p4(index, event), event)
+        self.coordinates_of(frame, self.daughter_p4(index, event), event)
+    }
+    pub fn coordinates_of<F: Field>(
+        &self,
+        frame: Frame,
+        other_p4: &FourMomentum<F>,
+        event: &Event<F>,
+    ) -> (Vector3<F>, Vector3<F>, Vector3<F>, Coordinates<F>) {
+        let other_res_vec = other_p4.boost_along(&self.resonance_p4(event)).momentum();
+        self.coordinates_from_boosted_vec(frame, &other_res_vec, event)
+    }
+    pub fn coordinates_from_boosted_vec<F: Field>(
+        &self,
+        frame: Frame,
+        other_res_vec: &Vector3<F>,
+        event: &Event<F>,
+    ) -> (Vector3<F>, Vector3<F>, Vector3<F>, Coordinates<F>) {
+        let Axes { x, y, z } = Axes::new(frame, event, &self.resonance_p4(event));
+        (x, y, z, coordinates(&x, &y, &z, other_res_vec))
     }
 }
@@ -0,0 +1,219 @@
+use rayon::prelude::*;
+use rustitude_core::{
+    frames::{polarization_angle, Axes},
+    math,
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{Decay, Frame, Sign};
+
+/// The decay of a resonance $`X`$ into a vector meson $`V`$ and a bachelor pseudoscalar.
+///
+/// The bachelor is the daughter at index `bachelor` and the vector decays into the daughters
+/// of `vector`. The spin of a vector which decays into two particles ($`\rho \to \pi\pi`$,
+/// $`\omega \to \pi^0\gamma`$) is analyzed by the momentum of its primary daughter, while the
+/// spin of a vector which decays into three particles ($`\omega \to \pi^+\pi^-\pi^0`$) is
+/// analyzed by the normal to its decay plane, $`\vec{p}_1 \times \vec{p}_2`$, using its primary
+/// and secondary daughters.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct VectorDecay {
+    pub bachelor: usize,
+    pub vector: Decay,
+}
+
+impl Default for VectorDecay {
+    fn default() -> Self {
+        Self {
+            bachelor: 0,
+            vector: Decay::ThreeBodyDecay([1, 2, 3]),
+        }
+    }
+}
+
+impl VectorDecay {
+    pub fn vector_p4<F: Field>(&self, event: &Event<F>) -> FourMomentum<F> {
+        self.vector.resonance_p4(event)
+    }
+    pub fn bachelor_p4<'a, F: Field>(&self, event: &'a Event<F>) -> &'a FourMomentum<F> {
+        &event.daughter_p4s[self.bachelor]
+    }
+    pub fn resonance_p4<F: Field>(&self, event: &Event<F>) -> FourMomentum<F> {
+        self.vector_p4(event) + *self.bachelor_p4(event)
+    }
+
+    /// Computes $`(\cos\theta, \phi)`$ of the vector in a [`Frame`] in the rest frame of the
+    /// resonance and $`(\cos\theta_H, \phi_H)`$ of the vector's spin analyzer in the helicity
+    /// frame of the vector, along with the $`\hat{y}`$ axis of the resonance [`Frame`].
+    ///
+    /// The helicity frame of the vector is reached by boosting to the rest frame of the resonance
+    /// and then to the rest frame of the vector, where $`\hat{z}_H`$ is along the momentum of the
+    /// vector in the rest frame of the resonance and $`\hat{y}_H \propto \hat{z} \times
+    /// \hat{z}_H`$.
+    pub fn angles<F: Field>(&self, frame: Frame, event: &Event<F>) -> ((F, F), (F, F), Vector3<F>) {
+        let resonance = self.resonance_p4(event);
+        let axes = Axes::new(frame, event, &resonance);
+        let vector = self.vector_p4(event).boost_along(&resonance);
+        let z = vector.momentum().unit();
+        let y = axes.z.cross(&z).unit();
+        let helicity_axes = Axes {
+            x: y.cross(&z),
+            y,
+            z,
+        };
+        let boost = |p: &FourMomentum<F>| p.boost_along(&resonance).boost_along(&vector);
+        let analyzer = match self.vector {
+            Decay::TwoBodyDecay(_) => boost(self.vector.primary_p4(event)).momentum(),
+            Decay::ThreeBodyDecay(_) => boost(self.vector.primary_p4(event))
+                .momentum()
+                .cross(&boost(self.vector.secondary_p4(event)).momentum()),
+        };
+        (
+            axes.angles(&vector.momentum()),
+            helicity_axes.angles(&analyzer),
+            axes.y,
+        )
+    }
+}
+
+/// A [`Node`] for the angular distribution of a resonance $`X`$ with spin $`J`$ and spin
+/// projection $`m`$ which decays into a vector meson and a bachelor pseudoscalar with orbital
+/// angular momentum $`\ell`$ (see [`VectorDecay`]), as used in $`\omega\pi`$ and $`b_1(1235)`$
+/// analyses.
+///
+/// In the helicity formalism, the amplitude is
+///
+/// ```math
+/// A = \sum_{\lambda} D^{J*}_{m\lambda}(\phi, \theta, 0) \langle \ell 0; 1 \lambda | J \lambda \rangle D^{1*}_{\lambda\nu}(\phi_H, \theta_H, 0)
+/// ```
+///
+/// where $`\lambda`$ is the helicity of the vector, $`(\theta, \phi)`$ are the angles of the
+/// vector in the rest frame of $`X`$, and $`(\theta_H, \phi_H)`$ are the angles of the spin
+/// analyzer of the vector in its helicity frame (see [`VectorDecay::angles`]). The helicity
+/// $`\nu`$ of the vector decay is zero for strong decays, and is the helicity $`\pm 1`$ of the
+/// photon for radiative decays such as $`\omega \to \pi^0\gamma`$, where each photon helicity
+/// should be summed incoherently. The amplitude is combined with the angle $`\Phi`$ between the
+/// beam polarization and the production plane in the same way as a
+/// [`Zlm`](crate::harmonics::Zlm) for the given reflectivity.
+#[derive(Clone)]
+pub struct VecPS<F: Field> {
+    j: usize,
+    m: isize,
+    l: usize,
+    reflectivity: Sign,
+    decay_helicity: isize,
+    decay: VectorDecay,
+    frame: Frame,
+    data: Vec<Complex<F>>,
+}
+
+impl<F: Field> VecPS<F> {
+    /// # Errors
+    ///
+    /// This method will return a [`RustitudeError::InvalidParameterValue`] if $`|m| > J`$, if
+    /// the vector decay helicity is not $`-1`$, $`0`$, or $`1`$, or if $`J`$ cannot be formed
+    /// from $`\ell`$ and the spin of the vector.
+    pub fn new(
+        j: usize,
+        m: isize,
+        l: usize,
+        reflectivity: Sign,
+        decay_helicity: isize,
+        decay: VectorDecay,
+        frame: Frame,
+    ) -> Result<Self, RustitudeError> {
+        if m.unsigned_abs() > j {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "|m = {m}| > (J = {j})"
+            )));
+        }
+        if decay_helicity.abs() > 1 {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "the helicity of a vector decay must be -1, 0, or 1, got {decay_helicity}"
+            )));
+        }
+        if j > l + 1 || j + 1 < l {
+            return Err(RustitudeError::InvalidParameterValue(format!(
+                "J = {j} cannot be formed from a vector and L = {l}"
+            )));
+        }
+        Ok(Self {
+            j,
+            m,
+            l,
+            reflectivity,
+            decay_helicity,
+            decay,
+            frame,
+            data: Vec::default(),
+        })
+    }
+}
+
+impl<F: Field> Node<F> for VecPS<F> {
+    fn precalculate(&mut self, dataset: &Dataset<F>) -> Result<(), RustitudeError> {
+        let (two_j, two_m, two_l) = (2 * self.j as u32, 2 * self.m as i32, 2 * self.l as u32);
+        let two_nu = 2 * self.decay_helicity as i32;
+        self.data = dataset
+            .events
+            .par_iter()
+            .map(|event| {
+                let ((costheta, phi), (costheta_h, phi_h), y) =
+                    self.decay.angles(self.frame, event);
+                let (theta, theta_h) = (F::acos(costheta), F::acos(costheta_h));
+                let amplitude: Complex<F> = [-2, 0, 2]
+                    .into_iter()
+                    .map(|two_lambda| {
+                        math::wigner_d(phi, theta, F::zero(), two_j, two_m, two_lambda).conj()
+                            * math::clebsch_gordan::<F>(two_l, 0, 2, two_lambda, two_j, two_lambda)
+                            * math::wigner_d(phi_h, theta_h, F::zero(), 2, two_lambda, two_nu)
+                                .conj()
+                    })
+                    .sum();
+                let big_phi = polarization_angle(event, &y);
+                let pgamma = event.eps_mag();
+                let zlm = amplitude * Complex::cis(-big_phi);
+                match self.reflectivity {
+                    Sign::Positive => Complex::new(
+                        F::sqrt(F::one() + pgamma) * zlm.re,
+                        F::sqrt(F::one() - pgamma) * zlm.im,
+                    ),
+                    Sign::Negative => Complex::new(
+                        F::sqrt(F::one() - pgamma) * zlm.re,
+                        F::sqrt(F::one() + pgamma) * zlm.im,
+                    ),
+                }
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn calculate(&self, _parameters: &[F], event: &Event<F>) -> Result<Complex<F>, RustitudeError> {
+        Ok(self.data[event.index])
+    }
+
+    fn calculate_batch(
+        &self,
+        _parameters: &[F],
+        events: &[Event<F>],
+        out: &mut [Complex<F>],
+    ) -> Result<(), RustitudeError> {
+        gather_batch(&self.data, events, out);
+        Ok(())
+    }
+
+    fn spec(&self) -> Option<NodeSpec> {
+        Some(NodeSpec::new(
+            "VecPS",
+            serde_json::json!({
+                "j": self.j,
+                "m": self.m,
+                "l": self.l,
+                "reflectivity": self.reflectivity,
+                "decay_helicity": self.decay_helicity,
+                "decay": self.decay,
+                "frame": self.frame,
+            }),
+        ))
+    }
+}
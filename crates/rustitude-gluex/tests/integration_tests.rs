@@ -1,12 +1,23 @@
 mod f64_tests {
     use rustitude_core::assert_is_close;
+    use rustitude_core::frames::polarization_angle;
     use rustitude_core::prelude::*;
     use rustitude_core::utils::*;
     use rustitude_gluex::utils::Decay;
-    use rustitude_gluex::utils::{Frame, Sign, Wave};
+    use rustitude_gluex::utils::{blatt_weisskopf, breakup_momentum, Frame, Sign, Wave};
     use rustitude_gluex::{
-        harmonics::{Ylm, Zlm},
-        resonances::{KMatrixA0, KMatrixA2, KMatrixF0, KMatrixF2, KMatrixPi1, KMatrixRho},
+        amptools::AmpToolsConfig,
+        harmonics::{PolarizedZlm, Ylm, Zlm},
+        moments::{
+            dataset_moments, moments_by_event, predicted_moments, production_coefficient,
+            project_moments, MomentIntensity, Positivity,
+        },
+        resonances::{
+            BarrierFactor, BreitWigner, Deck, FormFactor, KMatrixA0, KMatrixA2, KMatrixF0,
+            KMatrixF2, KMatrixPi1, KMatrixRho, MassConvention, WidthChannel,
+        },
+        sdmes::VectorSDME,
+        vecps::{VecPS, VectorDecay},
     };
     #[test]
     fn test_ylm() -> Result<(), RustitudeError> {
@@ -297,6 +308,689 @@ mod f64_tests {
         assert_is_close!(manager.evaluate(&[0.0, 0.0])?[0], 0.0, f64);
         Ok(())
     }
+
+    #[test]
+    fn test_kmatrix_non_resonant() -> Result<(), RustitudeError> {
+        let dataset = Dataset::new(vec![generate_test_event_f64()]);
+        let f0 = KMatrixF0::new(2, Decay::default())
+            .with_non_resonant(&[0, 2])
+            .named("F0(2)");
+        let model = model!(f0.real());
+        assert_eq!(model.get_n_free(), 14);
+        assert_eq!(model.parameters[10].name, "pipi non-resonant re");
+        assert_eq!(model.parameters[13].name, "kkbar non-resonant im");
+        let manager = Manager::new(&model, &dataset)?;
+        let mut parameters = [0.0; 14];
+        parameters[0] = 1.0;
+        assert_is_close!(manager.evaluate(&parameters)?[0], 0.030680506, f64);
+        parameters[0] = 0.0;
+        assert_is_close!(manager.evaluate(&parameters)?[0], 0.0, f64);
+        parameters[10] = 1.0;
+        assert!(manager.evaluate(&parameters)?[0] > 0.0);
+
+        let mut registry = NodeRegistry::new();
+        rustitude_gluex::register_nodes(&mut registry);
+        let reloaded = Model::from_json(&model.to_json()?, &registry)?;
+        assert_is_close!(
+            Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?[0],
+            manager.evaluate(&parameters)?[0],
+            f64
+        );
+
+        let invalid = KMatrixA0::new(1, Decay::default())
+            .with_non_resonant(&[2])
+            .named("A0(1)");
+        assert!(Manager::new(&model!(invalid.real()), &dataset).is_err());
+        let duplicate = KMatrixA0::new(1, Decay::default())
+            .with_non_resonant(&[1, 1])
+            .named("A0(1)");
+        assert!(Manager::new(&model!(duplicate.real()), &dataset).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialization() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let z22p =
+            Zlm::new(Wave::D2, Sign::Positive, Decay::default(), Frame::Helicity).named("z22+");
+        let f0 = KMatrixF0::new(2, Decay::default()).named("f0");
+        let model = model!(z22p.real() * &f0, z22p.imag() * &f0);
+        let mut registry = NodeRegistry::new();
+        rustitude_gluex::register_nodes(&mut registry);
+        let reloaded = Model::from_yaml(&model.to_yaml()?, &registry)?;
+        let parameters: Vec<f64> = (0..model.get_n_free()).map(|i| i as f64 * 0.1).collect();
+        let expected = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        let result = Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?;
+        for (r, e) in result.iter().zip(&expected) {
+            assert_is_close!(*r, *e, f64);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_polarized_zlm() -> Result<(), RustitudeError> {
+        let event = generate_test_event_f64();
+        let pgamma = event.eps_mag();
+        let dataset = Dataset::new(vec![event]);
+        for sign in [Sign::Positive, Sign::Negative] {
+            let z = Zlm::new(Wave::D2, sign, Decay::default(), Frame::Helicity).named("z");
+            let pz =
+                PolarizedZlm::new(Wave::D2, sign, Decay::default(), Frame::Helicity).named("pz");
+            let expected = Manager::new(&model!(z.real(), z.imag()), &dataset)?.evaluate(&[])?;
+            let mut model = model!(pz.real(), pz.imag());
+            model.add_gaussian_constraint("pz", "polarization magnitude", pgamma, 0.01)?;
+            let result = Manager::new(&model, &dataset)?.evaluate(&[pgamma])?;
+            assert_is_close!(result[0], expected[0], f64);
+            assert_is_close!(model.constraint_penalty(&[pgamma + 0.02])?, 4.0, f64);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_amptools_config() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let config = r#"
+            # A single D-wave with a shared Breit-Wigner mass
+            reaction EtaPi Beam Proton Eta Pi0
+            data EtaPi ROOTDataReader data.root
+            accmc EtaPi ROOTDataReader acc.root
+            define a2width 0.107
+            parameter a2mass 1.318 bounded 1.2 1.4
+            sum EtaPi PositiveRe PositiveIm
+            amplitude EtaPi::PositiveRe::D2+ Zlm 2 2 +1 +1 LOOPPOLANG LOOPPOLVAL
+            amplitude EtaPi::PositiveIm::D2+ Zlm 2 2 -1 -1 LOOPPOLANG LOOPPOLVAL
+            amplitude EtaPi::PositiveRe::D2+ BreitWigner [a2mass] a2width 2 2 3
+            amplitude EtaPi::PositiveIm::D2+ BreitWigner [a2mass] a2width 2 2 3
+            initialize EtaPi::PositiveRe::D2+ cartesian 1.0 0.5
+            constrain EtaPi::PositiveRe::D2+ EtaPi::PositiveIm::D2+
+        "#;
+        let config = AmpToolsConfig::<f64>::parse(config)?;
+        let reaction = config.reaction("EtaPi").unwrap();
+        assert_eq!(reaction.data.as_ref().unwrap().args, vec!["data.root"]);
+        assert!(reaction.genmc.is_none());
+        assert_eq!(config.model.get_n_free(), 3);
+        assert_eq!(config.model.get_initial(), vec![1.0, 0.5, 1.318]);
+        assert_eq!(config.model.get_bounds()[2], (1.2, 1.4),);
+
+        let c = cscalar("c");
+        let z = Zlm::new(Wave::D2, Sign::Positive, Decay::default(), Frame::Helicity).named("z");
+        let bw = BreitWigner::new(2, Decay::default()).named("bw");
+        let mut expected_model = model!(&c * z.real() * &bw, &c * z.imag() * &bw);
+        expected_model.fix("bw", "width", 0.107)?;
+        let parameters = [1.0, 0.5, 1.318];
+        let expected = Manager::new(&expected_model, &dataset)?.evaluate(&parameters)?;
+        for model in [config.model.clone(), config.reaction_model("EtaPi")?] {
+            let result = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+            for (r, e) in result.iter().zip(&expected) {
+                assert_is_close!(*r, *e, f64);
+            }
+        }
+        assert!(AmpToolsConfig::<f64>::parse("reaction R a b\nloop x 1 2").is_err());
+        Ok(())
+    }
+    #[test]
+    fn test_moments() -> Result<(), RustitudeError> {
+        use sphrs::{ComplexSH, Coordinates, SHEval};
+        let waves = [
+            (Wave::S0, Complex::new(1.0, 0.0)),
+            (Wave::P1, Complex::new(0.3, -0.4)),
+            (Wave::D0, Complex::new(-0.5, 0.2)),
+            (Wave::Dn1, Complex::new(0.1, 0.7)),
+        ];
+        let moments = project_moments(&waves, 4);
+        assert_eq!(moments.len(), 25);
+        let total: f64 = waves.iter().map(|(_, t)| t.norm_sqr()).sum();
+        assert_is_close!(
+            moments[0].value.re * f64::sqrt(4.0 * std::f64::consts::PI),
+            total,
+            f64
+        );
+        let (n_theta, n_phi) = (200, 200);
+        let d_theta = std::f64::consts::PI / n_theta as f64;
+        let d_phi = 2.0 * std::f64::consts::PI / n_phi as f64;
+        for moment in &moments {
+            let mut numeric = Complex::default();
+            for i in 0..n_theta {
+                let theta = (i as f64 + 0.5) * d_theta;
+                for j in 0..n_phi {
+                    let phi = (j as f64 + 0.5) * d_phi;
+                    let p = Coordinates::spherical(1.0, theta, phi);
+                    let amp: Complex<f64> = waves
+                        .iter()
+                        .map(|(w, t)| t * ComplexSH::Spherical.eval(w.l(), w.m(), &p))
+                        .sum();
+                    let y = ComplexSH::Spherical.eval(moment.l as i64, moment.m as i64, &p);
+                    numeric += y.conj() * amp.norm_sqr() * theta.sin() * d_theta * d_phi;
+                }
+            }
+            assert!((moment.value - numeric).norm() < 1e-4);
+        }
+
+        let dataset = generate_test_dataset_f64();
+        let s0 = Ylm::new(Wave::S0, Decay::default(), Frame::Helicity).named("s0");
+        let d0 = Ylm::new(Wave::D0, Decay::default(), Frame::Helicity).named("d0");
+        let model = model!(cscalar("s") * &s0 + pcscalar("d") * &d0);
+        let manager = Manager::new(&model, &dataset)?;
+        let parameters = [1.0, 0.5, 0.8, 0.3];
+        let event = Event::default();
+        let s = production_coefficient(&model, &parameters, &["s"], &event)?;
+        let d = production_coefficient(&model, &parameters, &["d"], &event)?;
+        assert_eq!(s, Complex::new(1.0, 0.5));
+        assert_is_close!(d.norm(), 0.8, f64);
+        assert!(production_coefficient(&model, &parameters, &["missing"], &event).is_err());
+        let expected = project_moments(&[(Wave::S0, s), (Wave::D0, d)], 4);
+        let sums = vec![vec![(Wave::S0, vec!["s"]), (Wave::D0, vec!["d"])]];
+        let by_event = moments_by_event(&manager, &parameters, &sums, 4)?;
+        assert_eq!(by_event.len(), dataset.len());
+        for moments in &by_event {
+            for (m, e) in moments.iter().zip(&expected) {
+                assert!((m.value - e.value).norm() < 1e-12);
+            }
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_dataset_moments() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let y10 = Ylm::new(Wave::P0, Decay::default(), Frame::Helicity).named("y10");
+        let y21 = Ylm::new(Wave::D1, Decay::default(), Frame::Helicity).named("y21");
+        let ylm_model = model!(&y10 + &y21);
+        let manager = Manager::new(&ylm_model, &dataset)?;
+        let moments = dataset_moments(&dataset, None, 2, Decay::default(), Frame::Helicity)?;
+        assert_eq!(moments.len(), 9);
+        let w: f64 = dataset.weights().iter().sum();
+        assert_is_close!(
+            moments[0].value.re,
+            w / f64::sqrt(4.0 * std::f64::consts::PI),
+            f64
+        );
+        for (name, index) in [("y10", 2), ("y21", 7)] {
+            let expected: Complex<f64> = dataset
+                .events
+                .iter()
+                .map(|event| {
+                    production_coefficient(&manager.model, &[], &[name], event)
+                        .unwrap()
+                        .conj()
+                        * event.weight
+                })
+                .sum();
+            assert!((moments[index].value - expected).norm() < 1e-12);
+        }
+        assert!(
+            dataset_moments(&dataset, Some(&[1.0]), 2, Decay::default(), Frame::Helicity).is_err()
+        );
+
+        let model = model!(scalar("a"));
+        let manager = Manager::new(&model, &dataset)?;
+        let ell = ExtendedLogLikelihood::new(manager.clone(), manager);
+        let predicted =
+            predicted_moments(&ell, &[1.5], &dataset, 2, Decay::default(), Frame::Helicity)?;
+        for (p, m) in predicted.iter().zip(&moments) {
+            assert!((p.value - m.value * 2.25).norm() < 1e-12);
+        }
+        Ok(())
+    }
+    #[test]
+    fn test_barrier_factor_references() {
+        use rustitude_gluex::utils::{blatt_weisskopf, blatt_weisskopf_c};
+        // Tabulated von Hippel-Quigg factors for K pi at 1.5 GeV, where z = 11.212_884_5
+        let (m0, m1, m2) = (1.5, 0.13957, 0.49368);
+        let expected = [
+            1.0,
+            1.355_078_788_8,
+            3.115_731_182_0,
+            11.616_800_962_2,
+            51.637_301_035_4,
+        ];
+        for (l, value) in expected.into_iter().enumerate() {
+            assert_is_close!(blatt_weisskopf(m0, m1, m2, l), value, 1e-8, f64);
+            let complex = blatt_weisskopf_c(m0, m1, m2, l);
+            assert_is_close!(complex.re, value, 1e-8, f64);
+            assert_is_close!(complex.im, 0.0, 1e-8, f64);
+        }
+    }
+    #[test]
+    fn test_wigner_d_references() {
+        use rustitude_gluex::utils::{small_wigner_d_matrix, wigner_d_matrix};
+        // Tabulated small-d functions at beta = 0.7, including m < n and m = -n
+        let beta = 0.7;
+        let expected = [
+            ((1, 1, 1), 0.882_421_093_6),
+            ((1, 1, 0), -0.455_530_695_2),
+            ((1, 0, 1), 0.455_530_695_2),
+            ((1, 1, -1), 0.117_578_906_4),
+            ((2, 2, 1), -0.568_471_276_1),
+            ((2, 2, 0), 0.254_144_621_2),
+            ((2, 1, 0), -0.603_462_251_4),
+            ((2, 0, 1), 0.603_462_251_4),
+            ((2, 1, -1), 0.297_437_522_2),
+            ((2, 0, 0), 0.377_475_357_2),
+        ];
+        for ((j, m, n), value) in expected {
+            assert_is_close!(small_wigner_d_matrix(beta, j, m, n), value, 1e-8, f64);
+            let d = wigner_d_matrix(0.3, beta, -0.4, j, m, n);
+            let phase = 0.4f64.mul_add(n as f64, -0.3 * m as f64);
+            assert_is_close!(d.re, value * phase.cos(), 1e-8, f64);
+            assert_is_close!(d.im, value * phase.sin(), 1e-8, f64);
+        }
+    }
+    fn generate_three_body_event_f64() -> Event<f64> {
+        // The second kaon of the test event is replaced by its decay into two pions
+        let mut event = generate_test_event_f64();
+        event.daughter_p4s = vec![
+            event.daughter_p4s[0],
+            FourMomentum::new(3.616_314_5, 0.135_676_4, -0.311_182_4, 3.597_639_2),
+            FourMomentum::new(1.892_728_5, -0.143_012, -0.356_191_1, 1.848_138_8),
+        ];
+        event
+    }
+    #[test]
+    fn test_vecps() -> Result<(), RustitudeError> {
+        let dataset = Dataset::new(vec![generate_three_body_event_f64()]);
+        let decay = VectorDecay {
+            bachelor: 0,
+            vector: Decay::TwoBodyDecay([1, 2]),
+        };
+        let s10p = VecPS::new(1, 0, 0, Sign::Positive, 0, decay, Frame::Helicity)?.named("s10+");
+        let d11n = VecPS::new(1, 1, 2, Sign::Negative, 0, decay, Frame::Helicity)?.named("d11-");
+        let p2n1p = VecPS::new(2, -1, 1, Sign::Positive, 0, decay, Frame::Helicity)?.named("p2n1+");
+        let s11p_radiative =
+            VecPS::new(1, 1, 0, Sign::Positive, 1, decay, Frame::Helicity)?.named("s11+ (nu=1)");
+        let manager = Manager::new(&model!(s10p.real()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.055_770_54, f64);
+        let manager = Manager::new(&model!(s10p.imag()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.168_333_62, f64);
+        let manager = Manager::new(&model!(d11n.real()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.017_064_067, f64);
+        let manager = Manager::new(&model!(d11n.imag()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.016_162_194, f64);
+        let manager = Manager::new(&model!(p2n1p.real()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.100_898_6, f64);
+        let manager = Manager::new(&model!(p2n1p.imag()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.000_061_812_88, f64);
+        let manager = Manager::new(&model!(s11p_radiative.real()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.020_088_081, f64);
+        let manager = Manager::new(&model!(s11p_radiative.imag()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.365_102_16, f64);
+        assert!(VecPS::<f64>::new(1, 2, 0, Sign::Positive, 0, decay, Frame::Helicity).is_err());
+        assert!(VecPS::<f64>::new(1, 0, 0, Sign::Positive, 2, decay, Frame::Helicity).is_err());
+        assert!(VecPS::<f64>::new(3, 0, 0, Sign::Positive, 0, decay, Frame::Helicity).is_err());
+
+        let mut registry = NodeRegistry::new();
+        rustitude_gluex::register_nodes(&mut registry);
+        let model = model!(&s10p + &d11n, p2n1p, s11p_radiative);
+        let reloaded = Model::from_yaml(&model.to_yaml()?, &registry)?;
+        let expected = Manager::new(&model, &dataset)?.evaluate(&[])?;
+        let result = Manager::new(&reloaded, &dataset)?.evaluate(&[])?;
+        assert_is_close!(result[0], expected[0], f64);
+        Ok(())
+    }
+    #[test]
+    fn test_vector_sdme() -> Result<(), RustitudeError> {
+        use sphrs::SHCoordinates;
+        let dataset = generate_test_dataset_f64();
+        let sdme = VectorSDME::new(Decay::default(), Frame::Helicity).named("sdme");
+        let model = model!(sdme.clone());
+        let manager = Manager::new(&model, &dataset)?;
+        let norm = 3.0 / (4.0 * std::f64::consts::PI);
+        // A purely longitudinal vector, rho^0_00 = 1, decays as cos^2(theta)
+        let longitudinal = manager.evaluate(&[1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0])?;
+        // A purely transverse vector, rho^0_00 = 0, decays as sin^2(theta) / 2
+        let transverse = manager.evaluate(&[0.0; 9])?;
+        // rho^1_11 adds a sin^2(theta) term modulated by the beam polarization
+        let polarized = manager.evaluate(&[0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0])?;
+        for (i, event) in dataset.events.iter().enumerate() {
+            let (_, y, _, p) = Decay::default().coordinates(Frame::Helicity, 0, event);
+            let cossqtheta = p.theta_cos().powi(2);
+            let sinsqtheta = 1.0 - cossqtheta;
+            let big_phi = polarization_angle(event, &y);
+            assert_is_close!(longitudinal[i], norm * cossqtheta, f64);
+            assert_is_close!(transverse[i], norm * 0.5 * sinsqtheta, f64);
+            assert_is_close!(
+                polarized[i],
+                norm * 0.5 * sinsqtheta * (1.0 - event.eps_mag() * f64::cos(2.0 * big_phi)),
+                f64
+            );
+        }
+
+        let mut registry = NodeRegistry::new();
+        rustitude_gluex::register_nodes(&mut registry);
+        let three_body = VectorSDME::new(Decay::ThreeBodyDecay([0, 1, 2]), Frame::GottfriedJackson)
+            .named("three_body");
+        let dataset = Dataset::new(vec![generate_three_body_event_f64()]);
+        let model = model!(sdme, three_body);
+        let reloaded = Model::from_yaml(&model.to_yaml()?, &registry)?;
+        let parameters: Vec<f64> = (0..model.get_n_free()).map(|i| i as f64 * 0.05).collect();
+        let expected = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        let result = Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?;
+        assert_is_close!(result[0], expected[0], f64);
+        Ok(())
+    }
+    #[test]
+    fn test_deck() -> Result<(), RustitudeError> {
+        let event = generate_three_body_event_f64();
+        let dataset = Dataset::new(vec![event.clone()]);
+        let isobar = Decay::TwoBodyDecay([1, 2]);
+        let pion = Deck::new(isobar, 0, 0.139_570, FormFactor::None, 0.0).named("pion");
+        let manager = Manager::new(&model!(pion), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 48.172_2, f64);
+
+        let isobar_p4 = isobar.resonance_p4(&event);
+        let bachelor_p4 = event.daughter_p4s[0];
+        let t_ex = (event.beam_p4 - isobar_p4).m2();
+        let s = (bachelor_p4 + event.recoil_p4).m2();
+        let t = (event.beam_p4 - isobar_p4 - bachelor_p4).m2();
+        let mut amplitudes: Vec<Box<dyn AmpLike<f64>>> = Vec::new();
+        for (name, exchange_mass, form_factor, slope, f) in [
+            ("pion", 0.139_570, FormFactor::None, 0.0, 1.0),
+            ("rho", 0.775_26, FormFactor::None, 0.0, 1.0),
+            (
+                "exponential",
+                0.139_570,
+                FormFactor::Exponential(2.0),
+                0.0,
+                f64::exp(2.0 * (t_ex - 0.139_570f64.powi(2))),
+            ),
+            (
+                "monopole",
+                0.139_570,
+                FormFactor::Monopole(1.2),
+                0.0,
+                (1.44 - 0.139_570f64.powi(2)) / (1.44 - t_ex),
+            ),
+            (
+                "dipole",
+                0.139_570,
+                FormFactor::Dipole(1.2),
+                0.0,
+                ((1.44 - 0.139_570f64.powi(2)) / (1.44 - t_ex)).powi(2),
+            ),
+            ("sloped", 0.139_570, FormFactor::None, 4.0, 1.0),
+        ] {
+            let deck = Deck::new(isobar, 0, exchange_mass, form_factor, slope).named(name);
+            let manager = Manager::new(&model!(deck.clone()), &dataset)?;
+            let expected = f / (exchange_mass.powi(2) - t_ex) * s * f64::exp(slope * t / 2.0);
+            assert_is_close!(manager.evaluate(&[])?[0], expected.powi(2), f64);
+            amplitudes.push(Box::new(deck));
+        }
+
+        let mut registry = NodeRegistry::new();
+        rustitude_gluex::register_nodes(&mut registry);
+        let model = Model::new(&amplitudes);
+        let reloaded = Model::from_yaml(&model.to_yaml()?, &registry)?;
+        let expected = Manager::new(&model, &dataset)?.evaluate(&[])?;
+        let result = Manager::new(&reloaded, &dataset)?.evaluate(&[])?;
+        assert_is_close!(result[0], expected[0], f64);
+        Ok(())
+    }
+    #[test]
+    fn test_moment_intensity() -> Result<(), RustitudeError> {
+        let dataset = generate_test_dataset_f64();
+        let s0 = Ylm::new(Wave::S0, Decay::default(), Frame::Helicity).named("s0");
+        let d0 = Ylm::new(Wave::D0, Decay::default(), Frame::Helicity).named("d0");
+        let parameters = [1.0, 0.5, 0.8, 0.3];
+        let event = Event::default();
+
+        // A single S-wave is described by the single moment H(0, 0)
+        let model = model!(cscalar("s") * &s0);
+        let s = production_coefficient(&model, &parameters[..2], &["s"], &event)?;
+        let moments = project_moments(&[(Wave::S0, s)], 0);
+        assert_eq!(moments.len(), 1);
+        let expected = Manager::new(&model, &dataset)?.evaluate(&parameters[..2])?;
+        let h = MomentIntensity::new(
+            0,
+            false,
+            Positivity::Absolute,
+            Decay::default(),
+            Frame::Helicity,
+        )
+        .named("h");
+        assert_eq!(h.parameters(), vec!["H0(0,0)"]);
+        let result = Manager::new(&model!(h), &dataset)?.evaluate(&[moments[0].value.re])?;
+        for (r, e) in result.iter().zip(&expected) {
+            assert_is_close!(*r, *e, f64);
+        }
+
+        // The S- and D-wave model of test_moments is described by its M = 0 moments
+        let model = model!(cscalar("s") * &s0 + pcscalar("d") * &d0);
+        let s = production_coefficient(&model, &parameters, &["s"], &event)?;
+        let d = production_coefficient(&model, &parameters, &["d"], &event)?;
+        let moments = project_moments(&[(Wave::S0, s), (Wave::D0, d)], 4);
+        let h = MomentIntensity::new(
+            4,
+            false,
+            Positivity::Absolute,
+            Decay::default(),
+            Frame::Helicity,
+        )
+        .named("h");
+        let values: Vec<f64> = moments
+            .iter()
+            .filter(|moment| moment.m >= 0)
+            .map(|moment| {
+                assert!(moment.m == 0 || moment.value.norm() < 1e-12);
+                moment.value.re
+            })
+            .collect();
+        assert_eq!(values.len(), h.parameters().len());
+        let expected = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        let result = Manager::new(&model!(h), &dataset)?.evaluate(&values)?;
+        for (r, e) in result.iter().zip(&expected) {
+            assert_is_close!(*r, *e, f64);
+        }
+        Ok(())
+    }
+    /// Returns the reconstructed mass, the daughter masses, and the breakup momentum of the
+    /// default [`Decay`] of the test event.
+    fn breit_wigner_kinematics() -> (f64, f64, f64, f64) {
+        let event = generate_test_event_f64();
+        let (p1, p2) = (event.daughter_p4s[0], event.daughter_p4s[1]);
+        let (m, m1, m2) = ((p1 + p2).m(), p1.m(), p2.m());
+        (m, m1, m2, breakup_momentum(m, m1, m2))
+    }
+    /// Computes the intensity of a [`BreitWigner`] with barrier factor `f`, width `g`, and the
+    /// mass `mu` which multiplies the width.
+    fn breit_wigner_intensity(m0: f64, g0: f64, mu: f64, f: f64, g: f64) -> f64 {
+        let (m, _, _, _) = breit_wigner_kinematics();
+        (Complex::new(f * mu * g0 / std::f64::consts::PI, 0.0)
+            / Complex::new(m0.powi(2) - m.powi(2), -mu * g))
+        .norm_sqr()
+    }
+    #[test]
+    fn test_breit_wigner() -> Result<(), RustitudeError> {
+        let dataset = Dataset::new(vec![generate_test_event_f64()]);
+        let bw = BreitWigner::new(2, Decay::default()).named("bw");
+        let manager = Manager::new(&model!(bw), &dataset)?;
+        assert_is_close!(manager.evaluate(&[1.5, 0.2])?[0], 0.358_151_25, f64);
+        assert_is_close!(manager.evaluate(&[1.3, 0.1])?[0], 0.186_921_22, f64);
+        let (m, m1, m2, q) = breit_wigner_kinematics();
+        let (m0, g0) = (1.5, 0.2);
+        let q0 = breakup_momentum(m0, m1, m2);
+        let (b, b0) = (
+            blatt_weisskopf(m, m1, m2, 2),
+            blatt_weisskopf(m0, m1, m2, 2),
+        );
+        let g = g0 * (m0 / m) * (q / q0) * (b / b0).powi(2);
+        assert_is_close!(
+            manager.evaluate(&[m0, g0])?[0],
+            breit_wigner_intensity(m0, g0, m0, b, g),
+            f64
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_breit_wigner_normalized_barrier() -> Result<(), RustitudeError> {
+        let dataset = Dataset::new(vec![generate_test_event_f64()]);
+        let bw = BreitWigner::new(2, Decay::default())
+            .with_barrier_factor(BarrierFactor::NormalizedBlattWeisskopf)
+            .named("bw");
+        let manager = Manager::new(&model!(bw), &dataset)?;
+        let (m, m1, m2, q) = breit_wigner_kinematics();
+        let (m0, g0) = (1.5, 0.2);
+        let q0 = breakup_momentum(m0, m1, m2);
+        let (b, b0) = (
+            blatt_weisskopf(m, m1, m2, 2),
+            blatt_weisskopf(m0, m1, m2, 2),
+        );
+        let g = g0 * (m0 / m) * (q / q0) * (b / b0).powi(2);
+        assert_is_close!(
+            manager.evaluate(&[m0, g0])?[0],
+            breit_wigner_intensity(m0, g0, m0, b / b0, g),
+            f64
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_breit_wigner_momentum_power() -> Result<(), RustitudeError> {
+        let dataset = Dataset::new(vec![generate_test_event_f64()]);
+        let bw = BreitWigner::new(2, Decay::default())
+            .with_barrier_factor(BarrierFactor::MomentumPower)
+            .named("bw");
+        let manager = Manager::new(&model!(bw), &dataset)?;
+        let (m, m1, m2, q) = breit_wigner_kinematics();
+        let (m0, g0) = (1.5, 0.2);
+        let q0 = breakup_momentum(m0, m1, m2);
+        let g = g0 * (m0 / m) * (q / q0).powi(5);
+        assert_is_close!(
+            manager.evaluate(&[m0, g0])?[0],
+            breit_wigner_intensity(m0, g0, m0, (q / q0).powi(2), g),
+            f64
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_breit_wigner_no_barrier() -> Result<(), RustitudeError> {
+        let dataset = Dataset::new(vec![generate_test_event_f64()]);
+        let bw = BreitWigner::new(2, Decay::default())
+            .with_barrier_factor(BarrierFactor::None)
+            .named("bw");
+        let manager = Manager::new(&model!(bw), &dataset)?;
+        let (m, m1, m2, q) = breit_wigner_kinematics();
+        let (m0, g0) = (1.5, 0.2);
+        let q0 = breakup_momentum(m0, m1, m2);
+        let g = g0 * (m0 / m) * (q / q0);
+        assert_is_close!(
+            manager.evaluate(&[m0, g0])?[0],
+            breit_wigner_intensity(m0, g0, m0, 1.0, g),
+            f64
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_breit_wigner_width_channels() -> Result<(), RustitudeError> {
+        let dataset = Dataset::new(vec![generate_test_event_f64()]);
+        let channels = [
+            WidthChannel {
+                fraction: 0.7,
+                m1: 0.139_570,
+                m2: 0.139_570,
+                l: 2,
+            },
+            WidthChannel {
+                fraction: 0.3,
+                m1: 0.497_611,
+                m2: 0.497_611,
+                l: 0,
+            },
+        ];
+        let bw = BreitWigner::new(2, Decay::default())
+            .with_width_channels(&channels)
+            .named("bw");
+        let manager = Manager::new(&model!(bw), &dataset)?;
+        let (m, m1, m2, _) = breit_wigner_kinematics();
+        let (m0, g0) = (1.5, 0.2);
+        let b = blatt_weisskopf(m, m1, m2, 2);
+        let g: f64 = channels
+            .iter()
+            .map(|channel| {
+                let (q_i, q_i0) = (
+                    breakup_momentum(m, channel.m1, channel.m2),
+                    breakup_momentum(m0, channel.m1, channel.m2),
+                );
+                let (b_i, b_i0) = (
+                    blatt_weisskopf(m, channel.m1, channel.m2, channel.l),
+                    blatt_weisskopf(m0, channel.m1, channel.m2, channel.l),
+                );
+                channel.fraction * g0 * (m0 / m) * (q_i / q_i0) * (b_i / b_i0).powi(2)
+            })
+            .sum();
+        assert_is_close!(
+            manager.evaluate(&[m0, g0])?[0],
+            breit_wigner_intensity(m0, g0, m0, b, g),
+            f64
+        );
+        Ok(())
+    }
+    #[test]
+    fn test_breit_wigner_reconstructed_mass() -> Result<(), RustitudeError> {
+        let dataset = Dataset::new(vec![generate_test_event_f64()]);
+        let bw = BreitWigner::new(2, Decay::default())
+            .with_mass_convention(MassConvention::Reconstructed)
+            .named("bw");
+        let manager = Manager::new(&model!(bw.clone()), &dataset)?;
+        let (m, m1, m2, q) = breit_wigner_kinematics();
+        let (m0, g0) = (1.5, 0.2);
+        let q0 = breakup_momentum(m0, m1, m2);
+        let (b, b0) = (
+            blatt_weisskopf(m, m1, m2, 2),
+            blatt_weisskopf(m0, m1, m2, 2),
+        );
+        let g = g0 * (m0 / m) * (q / q0) * (b / b0).powi(2);
+        assert_is_close!(
+            manager.evaluate(&[m0, g0])?[0],
+            breit_wigner_intensity(m0, g0, m, b, g),
+            f64
+        );
+
+        // Every option is kept when the model is serialized
+        let mut registry = NodeRegistry::new();
+        rustitude_gluex::register_nodes(&mut registry);
+        let options = BreitWigner::new(1, Decay::default())
+            .with_barrier_factor(BarrierFactor::MomentumPower)
+            .with_width_channels(&[WidthChannel {
+                fraction: 1.0,
+                m1: 0.497_611,
+                m2: 0.497_611,
+                l: 1,
+            }])
+            .with_mass_convention(MassConvention::Reconstructed)
+            .named("options");
+        let model = model!(bw, options);
+        let reloaded = Model::from_yaml(&model.to_yaml()?, &registry)?;
+        let parameters = [m0, g0, 1.3, 0.1];
+        let expected = Manager::new(&model, &dataset)?.evaluate(&parameters)?;
+        let result = Manager::new(&reloaded, &dataset)?.evaluate(&parameters)?;
+        assert_is_close!(result[0], expected[0], f64);
+        Ok(())
+    }
+    #[test]
+    #[allow(deprecated)]
+    fn test_frame_shims() {
+        use rustitude_gluex::utils::{FrameExt, ParseFrameError};
+        use sphrs::SHCoordinates;
+        let event = generate_test_event_f64();
+        let decay = Decay::default();
+        let boosted = event.daughter_p4s[0]
+            .boost_along(&decay.resonance_p4(&event))
+            .momentum();
+        for frame in [Frame::Helicity, Frame::GottfriedJackson] {
+            let (x, y, z, p) = frame.coordinates(decay, &event.daughter_p4s[0], &event);
+            let (ex, ey, ez, ep) = decay.coordinates(frame, 0, &event);
+            assert_eq!((x, y, z), (ex, ey, ez));
+            assert_eq!((p.theta_cos(), p.phi()), (ep.theta_cos(), ep.phi()));
+            let (x, y, z, p) = frame.coordinates_from_boosted_vec(decay, &boosted, &event);
+            let (ex, ey, ez, ep) = decay.coordinates_from_boosted_vec(frame, &boosted, &event);
+            assert_eq!((x, y, z), (ex, ey, ez));
+            assert_eq!((p.theta_cos(), p.phi()), (ep.theta_cos(), ep.phi()));
+        }
+        let error: ParseFrameError = "lab".parse::<Frame>().unwrap_err();
+        assert_eq!(error.to_string(), "Unknown frame: lab");
+        assert!(matches!(
+            RustitudeError::from(error),
+            RustitudeError::ParseError(_)
+        ));
+    }
 }
 mod f32_tests {
     use rustitude_core::assert_is_close;
@@ -306,7 +1000,10 @@ mod f32_tests {
     use rustitude_gluex::utils::{Frame, Sign, Wave};
     use rustitude_gluex::{
         harmonics::{Ylm, Zlm},
-        resonances::{KMatrixA0, KMatrixA2, KMatrixF0, KMatrixF2, KMatrixPi1, KMatrixRho},
+        resonances::{
+            BreitWigner, KMatrixA0, KMatrixA2, KMatrixF0, KMatrixF2, KMatrixPi1, KMatrixRho,
+        },
+        vecps::{VecPS, VectorDecay},
     };
 
     #[test]
@@ -602,4 +1299,56 @@ mod f32_tests {
         assert_is_close!(manager.evaluate(&[0.0, 0.0])?[0], 0.0, f32);
         Ok(())
     }
+
+    fn generate_three_body_event_f32() -> Event<f32> {
+        // The second kaon of the test event is replaced by its decay into two pions
+        let mut event = generate_test_event_f32();
+        event.daughter_p4s = vec![
+            event.daughter_p4s[0],
+            FourMomentum::new(3.616_314_5, 0.135_676_4, -0.311_182_4, 3.597_639),
+            FourMomentum::new(1.892_728_5, -0.143_012, -0.356_191_1, 1.848_138_8),
+        ];
+        event
+    }
+
+    #[test]
+    fn test_vecps() -> Result<(), RustitudeError> {
+        let dataset = Dataset::new(vec![generate_three_body_event_f32()]);
+        let decay = VectorDecay {
+            bachelor: 0,
+            vector: Decay::TwoBodyDecay([1, 2]),
+        };
+        let s10p = VecPS::new(1, 0, 0, Sign::Positive, 0, decay, Frame::Helicity)?.named("s10+");
+        let d11n = VecPS::new(1, 1, 2, Sign::Negative, 0, decay, Frame::Helicity)?.named("d11-");
+        let p2n1p = VecPS::new(2, -1, 1, Sign::Positive, 0, decay, Frame::Helicity)?.named("p2n1+");
+        let s11p_radiative =
+            VecPS::new(1, 1, 0, Sign::Positive, 1, decay, Frame::Helicity)?.named("s11+ (nu=1)");
+        let manager = Manager::new(&model!(s10p.real()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.055_770_904, f32);
+        let manager = Manager::new(&model!(s10p.imag()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.168_334_63, f32);
+        let manager = Manager::new(&model!(d11n.real()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.017_063_882, f32);
+        let manager = Manager::new(&model!(d11n.imag()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.016_163_617, f32);
+        let manager = Manager::new(&model!(p2n1p.real()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.100_899_41, f32);
+        let manager = Manager::new(&model!(p2n1p.imag()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.000_061_873_37, f32);
+        let manager = Manager::new(&model!(s11p_radiative.real()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.020_090_526, f32);
+        let manager = Manager::new(&model!(s11p_radiative.imag()), &dataset)?;
+        assert_is_close!(manager.evaluate(&[])?[0], 0.365_101_87, f32);
+        Ok(())
+    }
+
+    #[test]
+    fn test_breit_wigner() -> Result<(), RustitudeError> {
+        let dataset = Dataset::new(vec![generate_test_event_f32()]);
+        let bw = BreitWigner::new(2, Decay::default()).named("bw");
+        let manager = Manager::new(&model!(bw), &dataset)?;
+        assert_is_close!(manager.evaluate(&[1.5, 0.2])?[0], 0.358_150_96, f32);
+        assert_is_close!(manager.evaluate(&[1.3, 0.1])?[0], 0.186_917_4, f32);
+        Ok(())
+    }
 }
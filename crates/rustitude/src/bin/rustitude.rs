@@ -0,0 +1,261 @@
+//! Command-line utilities for `rustitude`.
+//!
+//! Currently the only subcommand is `bench`, which measures model evaluation throughput on the
+//! user's machine:
+//!
+//! ```text
+//! rustitude bench <dataset> [--mc <dataset>] [--iterations N] [--f32]
+//! ```
+//!
+//! The dataset may be a `.parquet` or `.root` file. The benchmark model is the same K-matrix +
+//! [`Zlm`] model used in the `kmatrix` Criterion benchmark. When built with the `cuda` feature,
+//! the benchmark also times a `GpuLikelihood` on the first CUDA device. The GPU backend requires
+//! a model which is linear in its production coefficients, so these rows use the same [`Zlm`]
+//! waves with complex couplings in place of the K-matrices, timed on the host through a
+//! [`LinearLikelihood`] (`linear`) and on the device (`gpu`).
+use std::{process::ExitCode, time::Instant};
+
+use nalgebra::RealField;
+#[cfg(feature = "cuda")]
+use rustitude::fitting::prelude::{GpuField, GpuLikelihood};
+use rustitude::gluex::harmonics::Zlm;
+use rustitude::gluex::resonances::{KMatrixA0, KMatrixA2, KMatrixF0, KMatrixF2};
+use rustitude::gluex::utils::{Decay, Frame, Sign, Wave};
+use rustitude::prelude::*;
+
+const USAGE: &str = "\
+Usage: rustitude <command> [options]
+
+Commands:
+    bench <dataset>     Report model evaluation throughput (events/sec)
+
+Options for bench:
+    --mc <dataset>      Monte-Carlo dataset for the likelihood (defaults to <dataset>)
+    --iterations <N>    Number of timed evaluations per backend (default: 20)
+    --f32               Use single precision instead of double precision
+    -h, --help          Print this message";
+
+struct BenchArgs {
+    data: String,
+    mc: Option<String>,
+    iterations: usize,
+    single_precision: bool,
+}
+
+impl BenchArgs {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut data = None;
+        let mut mc = None;
+        let mut iterations = 20;
+        let mut single_precision = false;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--mc" => mc = Some(args.next().ok_or("--mc requires a value")?.clone()),
+                "--iterations" => {
+                    iterations = args
+                        .next()
+                        .ok_or("--iterations requires a value")?
+                        .parse()
+                        .map_err(|err| format!("invalid value for --iterations: {err}"))?;
+                    if iterations == 0 {
+                        return Err("--iterations must be at least 1".to_string());
+                    }
+                }
+                "--f32" => single_precision = true,
+                other if other.starts_with('-') => return Err(format!("unknown option: {other}")),
+                other => {
+                    if data.replace(other.to_string()).is_some() {
+                        return Err(format!("unexpected argument: {other}"));
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            data: data.ok_or("bench requires a dataset path")?,
+            mc,
+            iterations,
+            single_precision,
+        })
+    }
+}
+
+/// The floating-point types the benchmark can run with.
+#[cfg(feature = "cuda")]
+trait BenchField: Field + RealField + GpuField {}
+#[cfg(feature = "cuda")]
+impl<F: Field + RealField + GpuField> BenchField for F {}
+/// The floating-point types the benchmark can run with.
+#[cfg(not(feature = "cuda"))]
+trait BenchField: Field + RealField {}
+#[cfg(not(feature = "cuda"))]
+impl<F: Field + RealField> BenchField for F {}
+
+fn load_dataset<F: Field + 'static>(path: &str) -> Result<Dataset<F>, RustitudeError> {
+    if path.ends_with(".root") {
+        Dataset::from_root(path, ReadMethod::Standard)
+    } else {
+        Dataset::from_parquet(path, ReadMethod::Standard)
+    }
+}
+
+fn waves<F: BenchField + 'static>() -> (Amplitude<F>, Amplitude<F>, Amplitude<F>) {
+    let s0p = Amplitude::new(
+        "s0+",
+        Zlm::new(Wave::S0, Sign::Positive, Decay::default(), Frame::Helicity),
+    );
+    let s0n = Amplitude::new(
+        "s0-",
+        Zlm::new(Wave::S0, Sign::Negative, Decay::default(), Frame::Helicity),
+    );
+    let d2 = Amplitude::new(
+        "d2",
+        Zlm::new(Wave::D2, Sign::Positive, Decay::default(), Frame::Helicity),
+    );
+    (s0p, s0n, d2)
+}
+
+fn benchmark_model<F: BenchField + 'static>() -> Result<Model<F>, RustitudeError> {
+    let f0p = Amplitude::new("f0+", KMatrixF0::new(2, Decay::default()));
+    let f0n = Amplitude::new("f0-", KMatrixF0::new(2, Decay::default()));
+    let f2 = Amplitude::new("f2", KMatrixF2::new(2, Decay::default()));
+    let a0p = Amplitude::new("a0+", KMatrixA0::new(1, Decay::default()));
+    let a0n = Amplitude::new("a0-", KMatrixA0::new(1, Decay::default()));
+    let a2 = Amplitude::new("a2", KMatrixA2::new(1, Decay::default()));
+    let (s0p, s0n, d2) = waves();
+    let pos_real = (&f0p + &a0p) * s0p.real() + (&f2 + &a2) * d2.real();
+    let pos_imag = (&f0p + &a0p) * s0p.imag() + (&f2 + &a2) * d2.imag();
+    let neg_real = (&f0n + &a0n) * s0n.real();
+    let neg_imag = (&f0n + &a0n) * s0n.imag();
+    let mut model = model!(pos_real, pos_imag, neg_real, neg_imag);
+    for amp in ["f0+", "f0-"] {
+        model.fix(amp, "f0_500 re", F::zero())?;
+        model.fix(amp, "f0_500 im", F::zero())?;
+        model.fix(amp, "f0_980 im", F::zero())?;
+    }
+    Ok(model)
+}
+
+#[cfg(feature = "cuda")]
+fn linear_model<F: BenchField + 'static>() -> Model<F> {
+    let (s0p, s0n, d2) = waves();
+    let (s_pos, s_neg, d_pos) = (cscalar("S+"), cscalar("S-"), cscalar("D+"));
+    let pos_real = &s_pos * s0p.real() + &d_pos * d2.real();
+    let pos_imag = &s_pos * s0p.imag() + &d_pos * d2.imag();
+    let neg_real = &s_neg * s0n.real();
+    let neg_imag = &s_neg * s0n.imag();
+    model!(pos_real, pos_imag, neg_real, neg_imag)
+}
+
+fn report(backend: &str, n_events: usize, iterations: usize, seconds: f64) {
+    #[allow(clippy::cast_precision_loss)]
+    let events_per_sec = (n_events * iterations) as f64 / seconds;
+    println!(
+        "{backend:<10} {:>12.3} ms/eval {:>16.0} events/sec",
+        1000.0 * seconds / iterations as f64,
+        events_per_sec
+    );
+}
+
+fn time<T>(
+    iterations: usize,
+    mut f: impl FnMut() -> Result<T, RustitudeError>,
+) -> Result<f64, RustitudeError> {
+    // One untimed evaluation to warm caches and the rayon thread pool
+    f()?;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        std::hint::black_box(f()?);
+    }
+    Ok(start.elapsed().as_secs_f64())
+}
+
+fn bench<F: BenchField + 'static>(args: &BenchArgs) -> Result<(), RustitudeError> {
+    let start = Instant::now();
+    let data = load_dataset::<F>(&args.data)?;
+    let mc = match &args.mc {
+        Some(path) => load_dataset::<F>(path)?,
+        None => data.clone(),
+    };
+    println!(
+        "Loaded {} data and {} Monte-Carlo events in {:.3} s",
+        data.len(),
+        mc.len(),
+        start.elapsed().as_secs_f64()
+    );
+    let model = benchmark_model::<F>()?;
+    let start = Instant::now();
+    let ell = ExtendedLogLikelihood::new(Manager::new(&model, &data)?, Manager::new(&model, &mc)?);
+    println!(
+        "Precalculated {} amplitudes in {:.3} s",
+        model.amplitudes.read().len(),
+        start.elapsed().as_secs_f64()
+    );
+    println!(
+        "Precision: {}, free parameters: {}, threads: {}, iterations: {}",
+        if args.single_precision { "f32" } else { "f64" },
+        model.get_n_free(),
+        std::thread::available_parallelism().map_or(1, std::num::NonZero::get),
+        args.iterations
+    );
+    let parameters = ell.get_initial();
+    let n_events = data.len() + mc.len();
+    let serial = time(args.iterations, || ell.evaluate(&parameters))?;
+    report("serial", n_events, args.iterations, serial);
+    let parallel = time(args.iterations, || ell.par_evaluate(&parameters))?;
+    report("rayon", n_events, args.iterations, parallel);
+    #[cfg(feature = "cuda")]
+    bench_gpu(&data, &mc, args)?;
+    #[cfg(not(feature = "cuda"))]
+    println!("{:<10} not available in this build", "gpu");
+    Ok(())
+}
+
+#[cfg(feature = "cuda")]
+fn bench_gpu<F: BenchField + 'static>(
+    data: &Dataset<F>,
+    mc: &Dataset<F>,
+    args: &BenchArgs,
+) -> Result<(), RustitudeError> {
+    let model = linear_model::<F>();
+    let ell = ExtendedLogLikelihood::new(Manager::new(&model, data)?, Manager::new(&model, mc)?);
+    let linear = LinearLikelihood::new(&ell)?;
+    let parameters = linear.get_initial();
+    let n_events = data.len() + mc.len();
+    let host = time(args.iterations, || linear.par_evaluate(&parameters))?;
+    report("linear", n_events, args.iterations, host);
+    match GpuLikelihood::new(linear, 0) {
+        Ok(gpu) => {
+            let device = time(args.iterations, || gpu.evaluate(&parameters))?;
+            report("gpu", n_events, args.iterations, device);
+        }
+        Err(err) => println!("{:<10} not available: {err}", "gpu"),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    let result = match args[0].as_str() {
+        "bench" => BenchArgs::parse(&args[1..]).and_then(|bench_args| {
+            if bench_args.single_precision {
+                bench::<f32>(&bench_args)
+            } else {
+                bench::<f64>(&bench_args)
+            }
+            .map_err(|err| err.to_string())
+        }),
+        other => Err(format!("unknown command: {other}\n\n{USAGE}")),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
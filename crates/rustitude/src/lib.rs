@@ -10,6 +10,14 @@ pub mod prelude {
     pub use rustitude_core::prelude::*;
 }
 
+pub mod fitting {
+    //! Re-exports the fitting and study types of [`rustitude-core`](`rustitude_core`) (see
+    //! [`rustitude_core::fitting`]).
+    pub mod prelude {
+        pub use rustitude_core::fitting::prelude::*;
+    }
+}
+
 #[cfg(feature = "gluex")]
 pub mod gluex {
     pub use rustitude_gluex::*;
@@ -28,6 +28,24 @@ from .amplitude import (
     PCScalar_32,
     PiecewiseM_64,
     PiecewiseM_32,
+    PiecewiseT_64,
+    PiecewiseT_32,
+    PiecewiseMT_64,
+    PiecewiseMT_32,
+    PiecewiseCosTheta_64,
+    PiecewiseCosTheta_32,
+    PiecewiseBeamEnergy_64,
+    PiecewiseBeamEnergy_32,
+    Piecewise_64,
+    Piecewise_32,
+    PolynomialM_64,
+    PolynomialM_32,
+    SplineM_64,
+    SplineM_32,
+    TemplateHistM_64,
+    TemplateHistM_32,
+    TemplateKDEM_64,
+    TemplateKDEM_32,
     Parameter_64,
     Parameter_32,
     Model_64,
@@ -44,6 +62,7 @@ from .amplitude import (
     Sum_32,
     NormSqr_64,
     NormSqr_32,
+    ActivationSnapshot,
     Node_64,
     Node_32,
 )
@@ -55,14 +74,42 @@ from .manager import (
     Manager_32,
     NelderMead_64,
     NelderMead_32,
+    FitResult_64,
+    FitResult_32,
+    Ensemble_64,
+    Ensemble_32,
+    ToyStudyResult_64,
+    ToyStudyResult_32,
+    SimultaneousLikelihood_64,
+    SimultaneousLikelihood_32,
+    LinearLikelihood_64,
+    LinearLikelihood_32,
+    BinnedChi2_64,
+    BinnedChi2_32,
+    set_precalculation_cache,
+    clear_precalculation_cache,
+    set_profiling,
+    reset_profiling,
+    profiling_report,
+    histogram,
 )
 
+from . import fitting
 from abc import ABCMeta, abstractmethod
 
 Scalar = Scalar_64
 CScalar = CScalar_64
 PCScalar = PCScalar_64
 PiecewiseM = PiecewiseM_64
+PiecewiseT = PiecewiseT_64
+PiecewiseMT = PiecewiseMT_64
+PiecewiseCosTheta = PiecewiseCosTheta_64
+PiecewiseBeamEnergy = PiecewiseBeamEnergy_64
+Piecewise = Piecewise_64
+PolynomialM = PolynomialM_64
+SplineM = SplineM_64
+TemplateHistM = TemplateHistM_64
+TemplateKDEM = TemplateKDEM_64
 Parameter = Parameter_64
 Model = Model_64
 Amplitude = Amplitude_64
@@ -77,6 +124,12 @@ Dataset = Dataset_64
 ExtendedLogLikelihood = ExtendedLogLikelihood_64
 Manager = Manager_64
 NelderMead = NelderMead_64
+FitResult = FitResult_64
+Ensemble = Ensemble_64
+ToyStudyResult = ToyStudyResult_64
+SimultaneousLikelihood = SimultaneousLikelihood_64
+LinearLikelihood = LinearLikelihood_64
+BinnedChi2 = BinnedChi2_64
 
 __version__: str = __version__
 
@@ -86,6 +139,7 @@ __all__ = [
     'manager',
     'amplitude',
     'four_momentum',
+    'fitting',
     'Event',
     'Event_64',
     'Event_32',
@@ -116,6 +170,7 @@ __all__ = [
     'NormSqr',
     'NormSqr_64',
     'NormSqr_32',
+    'ActivationSnapshot',
     'Scalar',
     'Scalar_64',
     'Scalar_32',
@@ -128,6 +183,33 @@ __all__ = [
     'PiecewiseM',
     'PiecewiseM_64',
     'PiecewiseM_32',
+    'PiecewiseT',
+    'PiecewiseT_64',
+    'PiecewiseT_32',
+    'PiecewiseMT',
+    'PiecewiseMT_64',
+    'PiecewiseMT_32',
+    'PiecewiseCosTheta',
+    'PiecewiseCosTheta_64',
+    'PiecewiseCosTheta_32',
+    'PiecewiseBeamEnergy',
+    'PiecewiseBeamEnergy_64',
+    'PiecewiseBeamEnergy_32',
+    'Piecewise',
+    'Piecewise_64',
+    'Piecewise_32',
+    'PolynomialM',
+    'PolynomialM_64',
+    'PolynomialM_32',
+    'SplineM',
+    'SplineM_64',
+    'SplineM_32',
+    'TemplateHistM',
+    'TemplateHistM_64',
+    'TemplateHistM_32',
+    'TemplateKDEM',
+    'TemplateKDEM_64',
+    'TemplateKDEM_32',
     'Parameter',
     'Parameter_64',
     'Parameter_32',
@@ -137,6 +219,30 @@ __all__ = [
     'NelderMead',
     'NelderMead_64',
     'NelderMead_32',
+    'FitResult',
+    'FitResult_64',
+    'FitResult_32',
+    'Ensemble',
+    'Ensemble_64',
+    'Ensemble_32',
+    'ToyStudyResult',
+    'ToyStudyResult_64',
+    'ToyStudyResult_32',
+    'SimultaneousLikelihood',
+    'SimultaneousLikelihood_64',
+    'SimultaneousLikelihood_32',
+    'LinearLikelihood',
+    'LinearLikelihood_64',
+    'LinearLikelihood_32',
+    'BinnedChi2',
+    'BinnedChi2_64',
+    'BinnedChi2_32',
+    'set_precalculation_cache',
+    'clear_precalculation_cache',
+    'set_profiling',
+    'reset_profiling',
+    'profiling_report',
+    'histogram',
     'Node',
     'Node_64',
     'Node_32',
@@ -163,8 +269,12 @@ def open(
 ) -> Dataset_64 | Dataset_32:  # noqa: A001
     filepath = (file_name if isinstance(file_name, Path) else Path(file_name)).resolve()
     tfile = uproot.open(filepath)
-    ttree = tfile[tree_name] if tree_name else tfile.get(tfile.keys()[0])
-    if not isinstance(ttree, HasBranches):
+    key = tree_name if tree_name else tfile.keys()[0]
+    ttree = tfile[key]
+    # RNTuples do not have branches, but expose their fields through the same interface. They are
+    # identified by the class of their key ("ROOT::RNTuple" or "ROOT::Experimental::RNTuple")
+    is_rntuple = tfile.classname_of(key).endswith('RNTuple')
+    if not isinstance(ttree, HasBranches) and not is_rntuple:
         raise Exception('TTree has no branches!')
     requested_branches = [
         'E_Beam',
@@ -178,7 +288,7 @@ def open(
         'Py_FinalState',
         'Pz_FinalState',
     ]
-    available_branches = [b for b in requested_branches if b in ttree]
+    available_branches = [b for b in requested_branches if b in ttree.keys()]
     tree_arrays = ttree.arrays(available_branches, library='np')
     if pol_in_beam:
         eps_x = tree_arrays['Px_Beam']
@@ -211,6 +321,12 @@ class PyNode_64(metaclass=ABCMeta):
     def parameters(self) -> list[str]:
         pass
 
+    def initial_values(self) -> list[float]:
+        return []
+
+    def parameter_bounds(self) -> list[tuple[float, float]]:
+        return []
+
 
 PyNode = PyNode_64
 
@@ -228,6 +344,12 @@ class PyNode_32(metaclass=ABCMeta):
     def parameters(self) -> list[str]:
         pass
 
+    def initial_values(self) -> list[float]:
+        return []
+
+    def parameter_bounds(self) -> list[tuple[float, float]]:
+        return []
+
 
 ScipyOptMethods = Literal[
     'py-Nelder-Mead',
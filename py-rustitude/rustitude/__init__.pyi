@@ -17,6 +17,7 @@ class Parameter_64:
     fixed: bool
     initial: float
     bounds: tuple[float, float]
+    transform: str | None
 
     def __init__(self, amplitude: str, name: str, index: int) -> None: ...
 
@@ -29,6 +30,7 @@ class Parameter_32:
     fixed: bool
     initial: float
     bounds: tuple[float, float]
+    transform: str | None
 
     def __init__(self, amplitude: str, name: str, index: int) -> None: ...
 
@@ -41,6 +43,8 @@ class PyNode_64(metaclass=ABCMeta):
     def calculate(self, parameters: list[float], event: Event) -> complex: ...
     @abstractmethod
     def parameters(self) -> list[str]: ...
+    def initial_values(self) -> list[float]: ...
+    def parameter_bounds(self) -> list[tuple[float, float]]: ...
 
 class PyNode_32(metaclass=ABCMeta):
     @abstractmethod
@@ -49,6 +53,8 @@ class PyNode_32(metaclass=ABCMeta):
     def calculate(self, parameters: list[float], event: Event) -> complex: ...
     @abstractmethod
     def parameters(self) -> list[str]: ...
+    def initial_values(self) -> list[float]: ...
+    def parameter_bounds(self) -> list[tuple[float, float]]: ...
 
 PyNode = PyNode_64
 
@@ -73,8 +79,10 @@ class Amplitude_64:
     active: bool
     cache_position: int
     parameter_index_start: int
+    scale: float
 
     def __init__(self, name: str, node: Node_64) -> None: ...
+    def scaled(self, scale: float) -> Amplitude_64: ...
     def real(self) -> Real_64: ...
     def imag(self) -> Imag_64: ...
     def __add__(self, other: Self | Real_64 | Imag_64 | Product_64 | Sum_64) -> Sum_64: ...
@@ -88,8 +96,10 @@ class Amplitude_32:
     active: bool
     cache_position: int
     parameter_index_start: int
+    scale: float
 
     def __init__(self, name: str, node: Node_32) -> None: ...
+    def scaled(self, scale: float) -> Amplitude_32: ...
     def real(self) -> Real_32: ...
     def imag(self) -> Imag_32: ...
     def __add__(self, other: Self | Real_32 | Imag_32 | Product_32 | Sum_32) -> Sum_32: ...
@@ -201,14 +211,101 @@ def PiecewiseM_32(name: str, bins: int, range: tuple[float, float]) -> Amplitude
 
 PiecewiseM = PiecewiseM_64
 
+def PiecewiseT_64(name: str, bins: int, range: tuple[float, float]) -> Amplitude_64: ...
+def PiecewiseT_32(name: str, bins: int, range: tuple[float, float]) -> Amplitude_32: ...
+
+PiecewiseT = PiecewiseT_64
+
+def PiecewiseMT_64(
+    name: str,
+    bins: tuple[int, int],
+    ranges: tuple[tuple[float, float], tuple[float, float]],
+) -> Amplitude_64: ...
+def PiecewiseMT_32(
+    name: str,
+    bins: tuple[int, int],
+    ranges: tuple[tuple[float, float], tuple[float, float]],
+) -> Amplitude_32: ...
+
+PiecewiseMT = PiecewiseMT_64
+
+def PiecewiseCosTheta_64(name: str, bins: int, range: tuple[float, float]) -> Amplitude_64: ...
+def PiecewiseCosTheta_32(name: str, bins: int, range: tuple[float, float]) -> Amplitude_32: ...
+
+PiecewiseCosTheta = PiecewiseCosTheta_64
+
+def PiecewiseBeamEnergy_64(name: str, bins: int, range: tuple[float, float]) -> Amplitude_64: ...
+def PiecewiseBeamEnergy_32(name: str, bins: int, range: tuple[float, float]) -> Amplitude_32: ...
+
+PiecewiseBeamEnergy = PiecewiseBeamEnergy_64
+
+def Piecewise_64(
+    name: str, bins: int, range: tuple[float, float], variable: Callable[[Event_64], float]
+) -> Amplitude_64: ...
+def Piecewise_32(
+    name: str, bins: int, range: tuple[float, float], variable: Callable[[Event_32], float]
+) -> Amplitude_32: ...
+
+Piecewise = Piecewise_64
+
+def PolynomialM_64(
+    name: str, order: int, range: tuple[float, float] | None = None
+) -> Amplitude_64: ...
+def PolynomialM_32(
+    name: str, order: int, range: tuple[float, float] | None = None
+) -> Amplitude_32: ...
+
+PolynomialM = PolynomialM_64
+
+def SplineM_64(
+    name: str,
+    knots: list[float],
+    kind: Literal['cubic', 'monotone'] = 'cubic',
+    form: Literal['cartesian', 'polar'] = 'cartesian',
+) -> Amplitude_64: ...
+def SplineM_32(
+    name: str,
+    knots: list[float],
+    kind: Literal['cubic', 'monotone'] = 'cubic',
+    form: Literal['cartesian', 'polar'] = 'cartesian',
+) -> Amplitude_32: ...
+
+SplineM = SplineM_64
+
+def TemplateHistM_64(name: str, edges: list[float], contents: list[float]) -> Amplitude_64: ...
+def TemplateHistM_32(name: str, edges: list[float], contents: list[float]) -> Amplitude_32: ...
+
+TemplateHistM = TemplateHistM_64
+
+def TemplateKDEM_64(
+    name: str,
+    points: list[float],
+    bandwidth: float | None = None,
+    weights: list[float] | None = None,
+) -> Amplitude_64: ...
+def TemplateKDEM_32(
+    name: str,
+    points: list[float],
+    bandwidth: float | None = None,
+    weights: list[float] | None = None,
+) -> Amplitude_32: ...
+
+TemplateKDEM = TemplateKDEM_64
+
 class NormSqr_64:
-    pass
+    name: str | None
+    active: bool
 
 class NormSqr_32:
-    pass
+    name: str | None
+    active: bool
 
 NormSqr = NormSqr_64
 
+class ActivationSnapshot:
+    amplitudes: list[tuple[str, bool]]
+    sums: list[bool]
+
 class Model_64:
     cohsums: list[NormSqr_64]
     amplitudes: list[Amplitude_64]
@@ -218,26 +315,85 @@ class Model_64:
     bounds: list[tuple[float, float]]
     initial: list[float]
     n_free: int
+    history: list[str]
+    aliases: list[tuple[str, str, str]]
 
     def __init__(
-        self, terms: list[Amplitude_64 | Real_64 | Imag_64 | Product_64 | Sum_64]
+        self,
+        terms: list[Amplitude_64 | Real_64 | Imag_64 | Product_64 | Sum_64],
+        names: list[str] | None = None,
     ) -> None: ...
+    def to_json(self) -> str: ...
+    def to_yaml(self) -> str: ...
+    def to_f32(self) -> Model_32: ...
+    @staticmethod
+    def from_json(json: str) -> Model_64: ...
+    @staticmethod
+    def from_yaml(yaml: str) -> Model_64: ...
+    @staticmethod
+    def from_config(config: str) -> Model_64: ...
+    @staticmethod
+    def from_config_file(path: str) -> Model_64: ...
+    def rename_amplitude(self, old: str, new: str) -> None: ...
+    def alias(self, alias: str, amplitude: str, parameter: str) -> None: ...
+    def remove_alias(self, alias: str) -> None: ...
     def get_parameter(self, amplitude_name: str, parameter_name: str) -> Parameter_64 | None: ...
     def print_parameters(self) -> None: ...
+    def to_latex(self) -> str: ...
+    def to_dot(self) -> str: ...
+    def to_mermaid(self) -> str: ...
+    def optimize(self) -> None: ...
     def constrain(
         self, amplitude_1: str, parameter_1: str, amplitude_2: str, parameter_2: str
     ) -> None: ...
     def fix(self, amplitude_1: str, parameter_1: str, value: float) -> None: ...
     def free(self, amplitude_1: str, parameter_1: str) -> None: ...
+    def add_gaussian_constraint(
+        self, amplitude: str, parameter: str, mean: float, sigma: float
+    ) -> None: ...
+    def add_prior(self, amplitude: str, parameter: str, mean: float, sigma: float) -> None: ...
+    def add_penalty(
+        self, parameters: list[tuple[str, str]], function: Callable[[list[float]], float]
+    ) -> None: ...
     def set_bounds(
         self, amplitude_1: str, parameter_1: str, bounds: tuple[float, float]
     ) -> None: ...
+    def set_transform(
+        self,
+        amplitude: str,
+        parameter: str,
+        transform: Literal['log', 'sqrt', 'bounded'] | None,
+        bounds: tuple[float, float] | None = None,
+    ) -> None: ...
     def set_initial(self, amplitude_1: str, parameter_1: str, initial: float) -> None: ...
+    def matching_parameters(self, pattern: str) -> list[Parameter_64]: ...
+    def fix_matching(self, pattern: str, value: float) -> None: ...
+    def free_matching(self, pattern: str) -> None: ...
+    def set_bounds_matching(self, pattern: str, bounds: tuple[float, float]) -> None: ...
+    def set_initial_matching(self, pattern: str, initial: float) -> None: ...
+    def constrain_matching(self, pattern: str) -> None: ...
     def activate(self, amplitude: str) -> None: ...
     def activate_all(self) -> None: ...
     def isolate(self, amplitudes: list[str]) -> None: ...
     def deactivate(self, amplitude: str) -> None: ...
     def deactivate_all(self) -> None: ...
+    def get_sum(self, name: str) -> NormSqr_64: ...
+    def activate_sum(self, name: str) -> None: ...
+    def deactivate_sum(self, name: str) -> None: ...
+    def activation_state(self) -> ActivationSnapshot: ...
+    def restore(self, snapshot: ActivationSnapshot) -> None: ...
+    def add_incoherent_sum(
+        self, name: str, terms: list[Amplitude_64 | Real_64 | Imag_64 | Product_64 | Sum_64]
+    ) -> None: ...
+    def add_sum(
+        self,
+        term: Amplitude_64 | Real_64 | Imag_64 | Product_64 | Sum_64,
+        name: str | None = None,
+    ) -> None: ...
+    def remove_sum(self, index: int) -> NormSqr_64: ...
+    def undo(self) -> str | None: ...
+    def replay(self, model: Model_64) -> None: ...
+    def clear_history(self) -> None: ...
 
 class Model_32:
     cohsums: list[NormSqr_32]
@@ -248,26 +404,85 @@ class Model_32:
     bounds: list[tuple[float, float]]
     initial: list[float]
     n_free: int
+    history: list[str]
+    aliases: list[tuple[str, str, str]]
 
     def __init__(
-        self, terms: list[Amplitude_32 | Real_32 | Imag_32 | Product_32 | Sum_32]
+        self,
+        terms: list[Amplitude_32 | Real_32 | Imag_32 | Product_32 | Sum_32],
+        names: list[str] | None = None,
     ) -> None: ...
+    def to_json(self) -> str: ...
+    def to_yaml(self) -> str: ...
+    def to_f64(self) -> Model_64: ...
+    @staticmethod
+    def from_json(json: str) -> Model_32: ...
+    @staticmethod
+    def from_yaml(yaml: str) -> Model_32: ...
+    @staticmethod
+    def from_config(config: str) -> Model_32: ...
+    @staticmethod
+    def from_config_file(path: str) -> Model_32: ...
+    def rename_amplitude(self, old: str, new: str) -> None: ...
+    def alias(self, alias: str, amplitude: str, parameter: str) -> None: ...
+    def remove_alias(self, alias: str) -> None: ...
     def get_parameter(self, amplitude_name: str, parameter_name: str) -> Parameter_32 | None: ...
     def print_parameters(self) -> None: ...
+    def to_latex(self) -> str: ...
+    def to_dot(self) -> str: ...
+    def to_mermaid(self) -> str: ...
+    def optimize(self) -> None: ...
     def constrain(
         self, amplitude_1: str, parameter_1: str, amplitude_2: str, parameter_2: str
     ) -> None: ...
     def fix(self, amplitude_1: str, parameter_1: str, value: float) -> None: ...
     def free(self, amplitude_1: str, parameter_1: str) -> None: ...
+    def add_gaussian_constraint(
+        self, amplitude: str, parameter: str, mean: float, sigma: float
+    ) -> None: ...
+    def add_prior(self, amplitude: str, parameter: str, mean: float, sigma: float) -> None: ...
+    def add_penalty(
+        self, parameters: list[tuple[str, str]], function: Callable[[list[float]], float]
+    ) -> None: ...
     def set_bounds(
         self, amplitude_1: str, parameter_1: str, bounds: tuple[float, float]
     ) -> None: ...
+    def set_transform(
+        self,
+        amplitude: str,
+        parameter: str,
+        transform: Literal['log', 'sqrt', 'bounded'] | None,
+        bounds: tuple[float, float] | None = None,
+    ) -> None: ...
     def set_initial(self, amplitude_1: str, parameter_1: str, initial: float) -> None: ...
+    def matching_parameters(self, pattern: str) -> list[Parameter_32]: ...
+    def fix_matching(self, pattern: str, value: float) -> None: ...
+    def free_matching(self, pattern: str) -> None: ...
+    def set_bounds_matching(self, pattern: str, bounds: tuple[float, float]) -> None: ...
+    def set_initial_matching(self, pattern: str, initial: float) -> None: ...
+    def constrain_matching(self, pattern: str) -> None: ...
     def activate(self, amplitude: str) -> None: ...
     def activate_all(self) -> None: ...
     def isolate(self, amplitudes: list[str]) -> None: ...
     def deactivate(self, amplitude: str) -> None: ...
     def deactivate_all(self) -> None: ...
+    def get_sum(self, name: str) -> NormSqr_32: ...
+    def activate_sum(self, name: str) -> None: ...
+    def deactivate_sum(self, name: str) -> None: ...
+    def activation_state(self) -> ActivationSnapshot: ...
+    def restore(self, snapshot: ActivationSnapshot) -> None: ...
+    def add_incoherent_sum(
+        self, name: str, terms: list[Amplitude_32 | Real_32 | Imag_32 | Product_32 | Sum_32]
+    ) -> None: ...
+    def add_sum(
+        self,
+        term: Amplitude_32 | Real_32 | Imag_32 | Product_32 | Sum_32,
+        name: str | None = None,
+    ) -> None: ...
+    def remove_sum(self, index: int) -> NormSqr_32: ...
+    def undo(self) -> str | None: ...
+    def replay(self, model: Model_32) -> None: ...
+    def clear_history(self) -> None: ...
 
 Model = Model_64
 
@@ -284,7 +499,17 @@ class FourMomentum_64:
     def set_px(self, value: float) -> None: ...
     def set_py(self, value: float) -> None: ...
     def set_pz(self, value: float) -> None: ...
-    def boost_along(self, other: FourMomentum_64) -> float: ...
+    def boost_along(self, other: FourMomentum_64) -> FourMomentum_64: ...
+    def boost_to_rest_frame_of(self, other: FourMomentum_64) -> FourMomentum_64: ...
+    def boost(self, beta: tuple[float, float, float]) -> FourMomentum_64: ...
+    def rotate_axis_angle(
+        self, axis: tuple[float, float, float], angle: float
+    ) -> FourMomentum_64: ...
+    def rotate_euler(self, alpha: float, beta: float, gamma: float) -> FourMomentum_64: ...
+    def rotate_frame_axis_angle(
+        self, axis: tuple[float, float, float], angle: float
+    ) -> FourMomentum_64: ...
+    def rotate_frame_euler(self, alpha: float, beta: float, gamma: float) -> FourMomentum_64: ...
     def __add__(self, other: FourMomentum_64) -> FourMomentum_64: ...
     def __sub__(self, other: FourMomentum_64) -> FourMomentum_64: ...
 
@@ -301,7 +526,17 @@ class FourMomentum_32:
     def set_px(self, value: float) -> None: ...
     def set_py(self, value: float) -> None: ...
     def set_pz(self, value: float) -> None: ...
-    def boost_along(self, other: FourMomentum_32) -> float: ...
+    def boost_along(self, other: FourMomentum_32) -> FourMomentum_32: ...
+    def boost_to_rest_frame_of(self, other: FourMomentum_32) -> FourMomentum_32: ...
+    def boost(self, beta: tuple[float, float, float]) -> FourMomentum_32: ...
+    def rotate_axis_angle(
+        self, axis: tuple[float, float, float], angle: float
+    ) -> FourMomentum_32: ...
+    def rotate_euler(self, alpha: float, beta: float, gamma: float) -> FourMomentum_32: ...
+    def rotate_frame_axis_angle(
+        self, axis: tuple[float, float, float], angle: float
+    ) -> FourMomentum_32: ...
+    def rotate_frame_euler(self, alpha: float, beta: float, gamma: float) -> FourMomentum_32: ...
     def __add__(self, other: FourMomentum_32) -> FourMomentum_32: ...
     def __sub__(self, other: FourMomentum_32) -> FourMomentum_32: ...
 
@@ -314,6 +549,7 @@ class Event_64:
     recoil_p4: FourMomentum_64
     daughter_p4s: list[FourMomentum_64]
     eps: list[float]
+    circular_polarization: float
 
 class Event_32:
     index: int
@@ -322,16 +558,20 @@ class Event_32:
     recoil_p4: FourMomentum_32
     daughter_p4s: list[FourMomentum_32]
     eps: list[float]
+    circular_polarization: float
 
 Event = Event_64
 
 class Dataset_64:
     events: list[Event_64]
     weights: list[float]
+    layout: Literal['events', 'columns']
 
     def __getitem__(self, index: int) -> Event_64: ...
     def __len__(self) -> int: ...
     def __add__(self, other: Dataset_64) -> Dataset_64: ...
+    def append(self, other: Dataset_64) -> None: ...
+    def to_f32(self) -> Dataset_32: ...
     def split_m(
         self,
         range: tuple[float, float],  # noqa: A002
@@ -340,9 +580,37 @@ class Dataset_64:
         p2_indices: list[float] | None = None,
     ) -> tuple[list[list[int]], list[int], list[int]]: ...
     def get_bootstrap_indices(self, seed: int) -> list[int]: ...
+    def standard_kinematics(
+        self,
+        name: Literal[
+            'resonance_mass', 'helicity_costheta', 'helicity_phi', 'gj_costheta', 'gj_phi'
+        ],
+    ) -> list[float]: ...
+    def kinematics_keys(self) -> list[str]: ...
+    def clear_kinematics(self) -> None: ...
+    def with_layout(self, layout: Literal['events', 'columns']) -> Dataset_64: ...
+    def with_weight_errors(self, errors: list[float]) -> Dataset_64: ...
+    def boost_to_com(self) -> Dataset_64: ...
+    def rotate_euler(self, alpha: float, beta: float, gamma: float) -> Dataset_64: ...
+    def reorder_daughters(self, order: list[int]) -> Dataset_64: ...
+    def smear(self, covariance: list[list[float]], seed: int = 0) -> Dataset_64: ...
+    def with_weight_covariance(
+        self, blocks: list[tuple[list[int], list[list[float]]]]
+    ) -> Dataset_64: ...
     @staticmethod
     def from_events(events: list[Event_64]) -> Dataset_64: ...
     @staticmethod
+    def from_phase_space(
+        beam_energy: float,
+        target_mass: float,
+        recoil_mass: float,
+        daughter_masses: list[float],
+        n_events: int,
+        *,
+        offset: int = 0,
+        eps: tuple[float, float, float] | None = None,
+    ) -> Dataset_64: ...
+    @staticmethod
     def from_dict(data: dict[str, list[float | list[float]]]) -> Dataset_64: ...
     @staticmethod
     def from_parquet(path: str) -> Dataset_64: ...
@@ -353,15 +621,28 @@ class Dataset_64:
     @staticmethod
     def from_parquet_unpolarized(path: str) -> Dataset_64: ...
     @staticmethod
+    def from_parquet_circular(path: str, degree: float) -> Dataset_64: ...
+    @staticmethod
     def from_root(path: str) -> Dataset_64: ...
+    @staticmethod
+    def from_parquet_with_polarization_table(
+        path: str, table: list[tuple[int, int, float, float]]
+    ) -> Dataset_64: ...
+    @staticmethod
+    def from_root_with_polarization_table(
+        path: str, table: list[tuple[int, int, float, float]]
+    ) -> Dataset_64: ...
 
 class Dataset_32:
     events: list[Event_32]
     weights: list[float]
+    layout: Literal['events', 'columns']
 
     def __getitem__(self, index: int) -> Event_32: ...
     def __len__(self) -> int: ...
     def __add__(self, other: Dataset_32) -> Dataset_32: ...
+    def append(self, other: Dataset_32) -> None: ...
+    def to_f64(self) -> Dataset_64: ...
     def split_m(
         self,
         range: tuple[float, float],  # noqa: A002
@@ -370,9 +651,37 @@ class Dataset_32:
         p2_indices: list[float] | None = None,
     ) -> tuple[list[list[int]], list[int], list[int]]: ...
     def get_bootstrap_indices(self, seed: int) -> list[int]: ...
+    def standard_kinematics(
+        self,
+        name: Literal[
+            'resonance_mass', 'helicity_costheta', 'helicity_phi', 'gj_costheta', 'gj_phi'
+        ],
+    ) -> list[float]: ...
+    def kinematics_keys(self) -> list[str]: ...
+    def clear_kinematics(self) -> None: ...
+    def with_layout(self, layout: Literal['events', 'columns']) -> Dataset_32: ...
+    def with_weight_errors(self, errors: list[float]) -> Dataset_32: ...
+    def boost_to_com(self) -> Dataset_32: ...
+    def rotate_euler(self, alpha: float, beta: float, gamma: float) -> Dataset_32: ...
+    def reorder_daughters(self, order: list[int]) -> Dataset_32: ...
+    def smear(self, covariance: list[list[float]], seed: int = 0) -> Dataset_32: ...
+    def with_weight_covariance(
+        self, blocks: list[tuple[list[int], list[list[float]]]]
+    ) -> Dataset_32: ...
     @staticmethod
     def from_events(events: list[Event_32]) -> Dataset_32: ...
     @staticmethod
+    def from_phase_space(
+        beam_energy: float,
+        target_mass: float,
+        recoil_mass: float,
+        daughter_masses: list[float],
+        n_events: int,
+        *,
+        offset: int = 0,
+        eps: tuple[float, float, float] | None = None,
+    ) -> Dataset_32: ...
+    @staticmethod
     def from_dict(data: dict[str, list[float | list[float]]]) -> Dataset_32: ...
     @staticmethod
     def from_parquet(path: str) -> Dataset_32: ...
@@ -383,13 +692,25 @@ class Dataset_32:
     @staticmethod
     def from_parquet_unpolarized(path: str) -> Dataset_32: ...
     @staticmethod
+    def from_parquet_circular(path: str, degree: float) -> Dataset_32: ...
+    @staticmethod
     def from_root(path: str) -> Dataset_32: ...
+    @staticmethod
+    def from_parquet_with_polarization_table(
+        path: str, table: list[tuple[int, int, float, float]]
+    ) -> Dataset_32: ...
+    @staticmethod
+    def from_root_with_polarization_table(
+        path: str, table: list[tuple[int, int, float, float]]
+    ) -> Dataset_32: ...
 
 Dataset = Dataset_64
 
 class Manager_64:
     model: Model_64
     dataset: Dataset_64
+    incremental: bool
+    threads: int
     root: Amplitude_64
     amplitudes: list[Amplitude_64]
     parameters: list[Parameter_64]
@@ -400,27 +721,66 @@ class Manager_64:
     n_free: int
 
     def __init__(self, model: Model_64, dataset: Dataset_64) -> None: ...
+    def set_intensity_policy(
+        self, policy: Literal['error', 'skip', 'clamp'] | None = None, *, clamp: float | None = None
+    ) -> None: ...
+    def invalid_intensities(
+        self, parameters: list[float]
+    ) -> list[tuple[int, float, list[tuple[str, complex]]]]: ...
+    def debug_event(self, parameters: list[float], index: int) -> dict[str, Any]: ...
     def __call__(
         self, parameters: list[float], *, indices: list[int] | None = None, parallel: bool = True
     ) -> list[float]: ...
     def evaluate(
         self, parameters: list[float], *, indices: list[int] | None = None, parallel: bool = True
     ) -> list[float]: ...
+    def evaluate_cohsum(
+        self, parameters: list[float], sum_index: int, *, parallel: bool = True
+    ) -> list[float]: ...
+    def evaluate_many(
+        self, parameter_sets: list[list[float]], *, parallel: bool = True
+    ) -> list[list[float]]: ...
     def fix(self, amplitude_1: str, parameter_1: str, value: float) -> None: ...
     def free(self, amplitude_1: str, parameter_1: str) -> None: ...
+    def add_gaussian_constraint(
+        self, amplitude: str, parameter: str, mean: float, sigma: float
+    ) -> None: ...
+    def add_prior(self, amplitude: str, parameter: str, mean: float, sigma: float) -> None: ...
+    def add_penalty(
+        self, parameters: list[tuple[str, str]], function: Callable[[list[float]], float]
+    ) -> None: ...
     def set_bounds(
         self, amplitude_1: str, parameter_1: str, bounds: tuple[float, float]
     ) -> None: ...
+    def set_transform(
+        self,
+        amplitude: str,
+        parameter: str,
+        transform: Literal['log', 'sqrt', 'bounded'] | None,
+        bounds: tuple[float, float] | None = None,
+    ) -> None: ...
     def set_initial(self, amplitude_1: str, parameter_1: str, initial: float) -> None: ...
+    def fix_matching(self, pattern: str, value: float) -> None: ...
+    def free_matching(self, pattern: str) -> None: ...
+    def set_bounds_matching(self, pattern: str, bounds: tuple[float, float]) -> None: ...
+    def set_initial_matching(self, pattern: str, initial: float) -> None: ...
+    def constrain_matching(self, pattern: str) -> None: ...
     def activate(self, amplitude: str) -> None: ...
     def activate_all(self) -> None: ...
     def isolate(self, amplitudes: list[str]) -> None: ...
     def deactivate(self, amplitude: str) -> None: ...
     def deactivate_all(self) -> None: ...
+    def get_sum(self, name: str) -> NormSqr_64: ...
+    def activate_sum(self, name: str) -> None: ...
+    def deactivate_sum(self, name: str) -> None: ...
+    def activation_state(self) -> ActivationSnapshot: ...
+    def restore(self, snapshot: ActivationSnapshot) -> None: ...
 
 class Manager_32:
     model: Model_32
     dataset: Dataset_32
+    incremental: bool
+    threads: int
     root: Amplitude_32
     amplitudes: list[Amplitude_32]
     parameters: list[Parameter_32]
@@ -431,23 +791,60 @@ class Manager_32:
     n_free: int
 
     def __init__(self, model: Model_32, dataset: Dataset_32) -> None: ...
+    def set_intensity_policy(
+        self, policy: Literal['error', 'skip', 'clamp'] | None = None, *, clamp: float | None = None
+    ) -> None: ...
+    def invalid_intensities(
+        self, parameters: list[float]
+    ) -> list[tuple[int, float, list[tuple[str, complex]]]]: ...
+    def debug_event(self, parameters: list[float], index: int) -> dict[str, Any]: ...
     def __call__(
         self, parameters: list[float], *, indices: list[int] | None = None, parallel: bool = True
     ) -> list[float]: ...
     def evaluate(
         self, parameters: list[float], *, indices: list[int] | None = None, parallel: bool = True
     ) -> list[float]: ...
+    def evaluate_cohsum(
+        self, parameters: list[float], sum_index: int, *, parallel: bool = True
+    ) -> list[float]: ...
+    def evaluate_many(
+        self, parameter_sets: list[list[float]], *, parallel: bool = True
+    ) -> list[list[float]]: ...
     def fix(self, amplitude_1: str, parameter_1: str, value: float) -> None: ...
     def free(self, amplitude_1: str, parameter_1: str) -> None: ...
+    def add_gaussian_constraint(
+        self, amplitude: str, parameter: str, mean: float, sigma: float
+    ) -> None: ...
+    def add_prior(self, amplitude: str, parameter: str, mean: float, sigma: float) -> None: ...
+    def add_penalty(
+        self, parameters: list[tuple[str, str]], function: Callable[[list[float]], float]
+    ) -> None: ...
     def set_bounds(
         self, amplitude_1: str, parameter_1: str, bounds: tuple[float, float]
     ) -> None: ...
+    def set_transform(
+        self,
+        amplitude: str,
+        parameter: str,
+        transform: Literal['log', 'sqrt', 'bounded'] | None,
+        bounds: tuple[float, float] | None = None,
+    ) -> None: ...
     def set_initial(self, amplitude_1: str, parameter_1: str, initial: float) -> None: ...
+    def fix_matching(self, pattern: str, value: float) -> None: ...
+    def free_matching(self, pattern: str) -> None: ...
+    def set_bounds_matching(self, pattern: str, bounds: tuple[float, float]) -> None: ...
+    def set_initial_matching(self, pattern: str, initial: float) -> None: ...
+    def constrain_matching(self, pattern: str) -> None: ...
     def activate(self, amplitude: str) -> None: ...
     def activate_all(self) -> None: ...
     def isolate(self, amplitudes: list[str]) -> None: ...
     def deactivate(self, amplitude: str) -> None: ...
     def deactivate_all(self) -> None: ...
+    def get_sum(self, name: str) -> NormSqr_32: ...
+    def activate_sum(self, name: str) -> None: ...
+    def deactivate_sum(self, name: str) -> None: ...
+    def activation_state(self) -> ActivationSnapshot: ...
+    def restore(self, snapshot: ActivationSnapshot) -> None: ...
 
 Manager = Manager_64
 
@@ -462,10 +859,35 @@ class ExtendedLogLikelihood_64:
     bounds: list[tuple[float, float]]
     initial: list[float]
     n_free: int
+    wide_mc_accumulation: bool
+    deterministic_reduction: bool
+    yields: list[Parameter_64]
 
     def __init__(
         self, data_manager: Manager | Manager_64, mc_manager: Manager | Manager_64
     ) -> None: ...
+    @staticmethod
+    def from_phase_space(
+        data_manager: Manager | Manager_64,
+        beam_energy: float,
+        target_mass: float,
+        recoil_mass: float,
+        daughter_masses: list[float],
+        parameters: list[float],
+        *,
+        precision: float = 1e-3,
+        max_events: int = 1048576,
+        eps: tuple[float, float, float] | None = None,
+    ) -> ExtendedLogLikelihood_64: ...
+    def set_intensity_floor(self, floor: float | None = None, *, drop: bool = False) -> None: ...
+    def floored_events(self, parameters: list[float]) -> list[int]: ...
+    def set_robust_loss(self, threshold: float | None = None, *, tukey: bool = False) -> None: ...
+    @property
+    def likelihood_kind(self) -> Literal['extended', 'unextended', 'binned']: ...
+    @likelihood_kind.setter
+    def likelihood_kind(self, value: Literal['extended', 'unextended']) -> None: ...
+    def set_binned(self, edges: list[float], variable: Callable[[Event_64], float]) -> None: ...
+    def evaluate_standard(self, parameters: list[float]) -> float: ...
     def __call__(
         self,
         parameters: list[float],
@@ -481,6 +903,95 @@ class ExtendedLogLikelihood_64:
         indices_mc: list[int] | None = None,
         parallel: bool = True,
     ) -> float: ...
+    def gradient(self, parameters: list[float], *, parallel: bool = True) -> list[float]: ...
+    def evaluate_with_gradient(
+        self, parameters: list[float], *, parallel: bool = True
+    ) -> tuple[float, list[float]]: ...
+    def hessian(
+        self, parameters: list[float], *, parallel: bool = True
+    ) -> list[list[float]]: ...
+    def covariance(
+        self, parameters: list[float], *, parallel: bool = True
+    ) -> list[list[float]]: ...
+    def weighted_covariance(
+        self, parameters: list[float], *, parallel: bool = True
+    ) -> list[list[float]]: ...
+    def profile(
+        self,
+        amplitude: str,
+        parameter: str,
+        values: list[float],
+        *,
+        max_steps: int = 1000,
+        parallel: bool = True,
+    ) -> list[float]: ...
+    def multistart(
+        self,
+        n_starts: int,
+        *,
+        seed: int = 0,
+        max_steps: int = 1000,
+        parallel: bool = True,
+    ) -> list[FitResult_64]: ...
+    def toy_study(
+        self,
+        truth: list[float],
+        n_toys: int,
+        *,
+        seed: int = 0,
+        source: Literal['accept-reject', 'bootstrap'] = 'accept-reject',
+        pool: Dataset_64 | None = None,
+        n_events: int | None = None,
+        truth_covariance: list[list[float]] | None = None,
+        max_steps: int = 1000,
+        parallel: bool = True,
+    ) -> ToyStudyResult_64: ...
+    def minos(
+        self,
+        best: list[float],
+        fx: float,
+        index: int,
+        *,
+        delta: float = 1.0,
+        step: float | None = None,
+        max_steps: int = 1000,
+    ) -> tuple[float | None, float | None]: ...
+    def project(
+        self,
+        parameters: list[float],
+        dataset_mc: Dataset_64,
+        amplitudes: list[str],
+        *,
+        parallel: bool = True,
+    ) -> list[float]: ...
+    def binned_chi2(
+        self,
+        parameters: list[float],
+        dataset_mc: Dataset_64,
+        edges: list[float],
+        data_values: list[float],
+        mc_values: list[float],
+        *,
+        parallel: bool = True,
+    ) -> tuple[float, int, float]: ...
+    def interference(
+        self,
+        parameters: list[float],
+        dataset_mc: Dataset_64,
+        amplitudes_a: list[str],
+        amplitudes_b: list[str],
+        *,
+        parallel: bool = True,
+    ) -> list[float]: ...
+    def fit_fractions(
+        self,
+        parameters: list[float],
+        dataset_mc: Dataset_64,
+        groups: list[tuple[str, list[str]]] | None = None,
+        covariance: list[list[float]] | None = None,
+        *,
+        parallel: bool = True,
+    ) -> list[tuple[str, float, float | None]]: ...
     def intensity(
         self,
         parameters: list[float],
@@ -492,15 +1003,63 @@ class ExtendedLogLikelihood_64:
     ) -> list[float]: ...
     def fix(self, amplitude_1: str, parameter_1: str, value: float) -> None: ...
     def free(self, amplitude_1: str, parameter_1: str) -> None: ...
+    def add_gaussian_constraint(
+        self, amplitude: str, parameter: str, mean: float, sigma: float
+    ) -> None: ...
+    def add_prior(self, amplitude: str, parameter: str, mean: float, sigma: float) -> None: ...
+    def add_penalty(
+        self, parameters: list[tuple[str, str]], function: Callable[[list[float]], float]
+    ) -> None: ...
+    def add_signal_yield(self) -> None: ...
+    def add_background_template(
+        self, data_template: list[float], mc_template: list[float]
+    ) -> None: ...
+    def set_acceptance(self, acceptance: list[float] | None = None) -> None: ...
+    @property
+    def acceptance(self) -> list[float] | None: ...
+    def average_acceptance(self, parameters: list[float]) -> float: ...
+    def set_generated(self, dataset: Dataset_64 | None = None) -> None: ...
+    def efficiency(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def wave_efficiencies(
+        self,
+        parameters: list[float],
+        groups: list[tuple[str, list[str]]] | None = None,
+        *,
+        parallel: bool = True,
+    ) -> list[tuple[str, float]]: ...
+    def corrected_yield(self, parameters: list[float]) -> float: ...
+    def cache_normalization(self) -> None: ...
+    def clear_normalization_cache(self) -> None: ...
+    @property
+    def threads(self) -> int: ...
+    @threads.setter
+    def threads(self, threads: int) -> None: ...
     def set_bounds(
         self, amplitude_1: str, parameter_1: str, bounds: tuple[float, float]
     ) -> None: ...
+    def set_transform(
+        self,
+        amplitude: str,
+        parameter: str,
+        transform: Literal['log', 'sqrt', 'bounded'] | None,
+        bounds: tuple[float, float] | None = None,
+    ) -> None: ...
     def set_initial(self, amplitude_1: str, parameter_1: str, initial: float) -> None: ...
+    def fix_matching(self, pattern: str, value: float) -> None: ...
+    def free_matching(self, pattern: str) -> None: ...
+    def set_bounds_matching(self, pattern: str, bounds: tuple[float, float]) -> None: ...
+    def set_initial_matching(self, pattern: str, initial: float) -> None: ...
+    def constrain_matching(self, pattern: str) -> None: ...
     def activate(self, amplitude: str) -> None: ...
     def activate_all(self) -> None: ...
     def isolate(self, amplitudes: list[str]) -> None: ...
     def deactivate(self, amplitude: str) -> None: ...
     def deactivate_all(self) -> None: ...
+    def get_sum(self, name: str) -> NormSqr_64: ...
+    def activate_sum(self, name: str) -> None: ...
+    def deactivate_sum(self, name: str) -> None: ...
+    def activation_state(self) -> ActivationSnapshot: ...
+    def restore(self, snapshot: ActivationSnapshot) -> None: ...
 
 class ExtendedLogLikelihood_32:
     data_manager: Manager_32
@@ -513,8 +1072,33 @@ class ExtendedLogLikelihood_32:
     bounds: list[tuple[float, float]]
     initial: list[float]
     n_free: int
+    wide_mc_accumulation: bool
+    deterministic_reduction: bool
+    yields: list[Parameter_32]
 
     def __init__(self, data_manager: Manager_32, mc_manager: Manager_32) -> None: ...
+    @staticmethod
+    def from_phase_space(
+        data_manager: Manager_32,
+        beam_energy: float,
+        target_mass: float,
+        recoil_mass: float,
+        daughter_masses: list[float],
+        parameters: list[float],
+        *,
+        precision: float = 1e-3,
+        max_events: int = 1048576,
+        eps: tuple[float, float, float] | None = None,
+    ) -> ExtendedLogLikelihood_32: ...
+    def set_intensity_floor(self, floor: float | None = None, *, drop: bool = False) -> None: ...
+    def floored_events(self, parameters: list[float]) -> list[int]: ...
+    def set_robust_loss(self, threshold: float | None = None, *, tukey: bool = False) -> None: ...
+    @property
+    def likelihood_kind(self) -> Literal['extended', 'unextended', 'binned']: ...
+    @likelihood_kind.setter
+    def likelihood_kind(self, value: Literal['extended', 'unextended']) -> None: ...
+    def set_binned(self, edges: list[float], variable: Callable[[Event_32], float]) -> None: ...
+    def evaluate_standard(self, parameters: list[float]) -> float: ...
     def __call__(
         self,
         parameters: list[float],
@@ -530,6 +1114,95 @@ class ExtendedLogLikelihood_32:
         indices_mc: list[int] | None = None,
         parallel: bool = True,
     ) -> float: ...
+    def gradient(self, parameters: list[float], *, parallel: bool = True) -> list[float]: ...
+    def evaluate_with_gradient(
+        self, parameters: list[float], *, parallel: bool = True
+    ) -> tuple[float, list[float]]: ...
+    def hessian(
+        self, parameters: list[float], *, parallel: bool = True
+    ) -> list[list[float]]: ...
+    def covariance(
+        self, parameters: list[float], *, parallel: bool = True
+    ) -> list[list[float]]: ...
+    def weighted_covariance(
+        self, parameters: list[float], *, parallel: bool = True
+    ) -> list[list[float]]: ...
+    def profile(
+        self,
+        amplitude: str,
+        parameter: str,
+        values: list[float],
+        *,
+        max_steps: int = 1000,
+        parallel: bool = True,
+    ) -> list[float]: ...
+    def multistart(
+        self,
+        n_starts: int,
+        *,
+        seed: int = 0,
+        max_steps: int = 1000,
+        parallel: bool = True,
+    ) -> list[FitResult_32]: ...
+    def toy_study(
+        self,
+        truth: list[float],
+        n_toys: int,
+        *,
+        seed: int = 0,
+        source: Literal['accept-reject', 'bootstrap'] = 'accept-reject',
+        pool: Dataset_32 | None = None,
+        n_events: int | None = None,
+        truth_covariance: list[list[float]] | None = None,
+        max_steps: int = 1000,
+        parallel: bool = True,
+    ) -> ToyStudyResult_32: ...
+    def minos(
+        self,
+        best: list[float],
+        fx: float,
+        index: int,
+        *,
+        delta: float = 1.0,
+        step: float | None = None,
+        max_steps: int = 1000,
+    ) -> tuple[float | None, float | None]: ...
+    def project(
+        self,
+        parameters: list[float],
+        dataset_mc: Dataset_32,
+        amplitudes: list[str],
+        *,
+        parallel: bool = True,
+    ) -> list[float]: ...
+    def binned_chi2(
+        self,
+        parameters: list[float],
+        dataset_mc: Dataset_32,
+        edges: list[float],
+        data_values: list[float],
+        mc_values: list[float],
+        *,
+        parallel: bool = True,
+    ) -> tuple[float, int, float]: ...
+    def interference(
+        self,
+        parameters: list[float],
+        dataset_mc: Dataset_32,
+        amplitudes_a: list[str],
+        amplitudes_b: list[str],
+        *,
+        parallel: bool = True,
+    ) -> list[float]: ...
+    def fit_fractions(
+        self,
+        parameters: list[float],
+        dataset_mc: Dataset_32,
+        groups: list[tuple[str, list[str]]] | None = None,
+        covariance: list[list[float]] | None = None,
+        *,
+        parallel: bool = True,
+    ) -> list[tuple[str, float, float | None]]: ...
     def intensity(
         self,
         parameters: list[float],
@@ -541,15 +1214,63 @@ class ExtendedLogLikelihood_32:
     ) -> list[float]: ...
     def fix(self, amplitude_1: str, parameter_1: str, value: float) -> None: ...
     def free(self, amplitude_1: str, parameter_1: str) -> None: ...
+    def add_gaussian_constraint(
+        self, amplitude: str, parameter: str, mean: float, sigma: float
+    ) -> None: ...
+    def add_prior(self, amplitude: str, parameter: str, mean: float, sigma: float) -> None: ...
+    def add_penalty(
+        self, parameters: list[tuple[str, str]], function: Callable[[list[float]], float]
+    ) -> None: ...
+    def add_signal_yield(self) -> None: ...
+    def add_background_template(
+        self, data_template: list[float], mc_template: list[float]
+    ) -> None: ...
+    def set_acceptance(self, acceptance: list[float] | None = None) -> None: ...
+    @property
+    def acceptance(self) -> list[float] | None: ...
+    def average_acceptance(self, parameters: list[float]) -> float: ...
+    def set_generated(self, dataset: Dataset_32 | None = None) -> None: ...
+    def efficiency(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def wave_efficiencies(
+        self,
+        parameters: list[float],
+        groups: list[tuple[str, list[str]]] | None = None,
+        *,
+        parallel: bool = True,
+    ) -> list[tuple[str, float]]: ...
+    def corrected_yield(self, parameters: list[float]) -> float: ...
+    def cache_normalization(self) -> None: ...
+    def clear_normalization_cache(self) -> None: ...
+    @property
+    def threads(self) -> int: ...
+    @threads.setter
+    def threads(self, threads: int) -> None: ...
     def set_bounds(
         self, amplitude_1: str, parameter_1: str, bounds: tuple[float, float]
     ) -> None: ...
+    def set_transform(
+        self,
+        amplitude: str,
+        parameter: str,
+        transform: Literal['log', 'sqrt', 'bounded'] | None,
+        bounds: tuple[float, float] | None = None,
+    ) -> None: ...
     def set_initial(self, amplitude_1: str, parameter_1: str, initial: float) -> None: ...
+    def fix_matching(self, pattern: str, value: float) -> None: ...
+    def free_matching(self, pattern: str) -> None: ...
+    def set_bounds_matching(self, pattern: str, bounds: tuple[float, float]) -> None: ...
+    def set_initial_matching(self, pattern: str, initial: float) -> None: ...
+    def constrain_matching(self, pattern: str) -> None: ...
     def activate(self, amplitude: str) -> None: ...
     def activate_all(self) -> None: ...
     def isolate(self, amplitudes: list[str]) -> None: ...
     def deactivate(self, amplitude: str) -> None: ...
     def deactivate_all(self) -> None: ...
+    def get_sum(self, name: str) -> NormSqr_32: ...
+    def activate_sum(self, name: str) -> None: ...
+    def deactivate_sum(self, name: str) -> None: ...
+    def activation_state(self) -> ActivationSnapshot: ...
+    def restore(self, snapshot: ActivationSnapshot) -> None: ...
 
 ExtendedLogLikelihood = ExtendedLogLikelihood_64
 
@@ -573,7 +1294,9 @@ class NelderMead_64:
     def initialize(self) -> None: ...
     def step(self) -> None: ...
     def check_for_termination(self) -> bool: ...
-    def minimize(self, steps: int) -> None: ...
+    def minimize(
+        self, steps: int, *, callback: Callable[[int, float, list[float]], None] | None = None
+    ) -> None: ...
     def best(self) -> tuple[list[float], float]: ...
 
 class NelderMead_32:
@@ -596,11 +1319,323 @@ class NelderMead_32:
     def initialize(self) -> None: ...
     def step(self) -> None: ...
     def check_for_termination(self) -> bool: ...
-    def minimize(self, steps: int) -> None: ...
+    def minimize(
+        self, steps: int, *, callback: Callable[[int, float, list[float]], None] | None = None
+    ) -> None: ...
     def best(self) -> tuple[list[float], float]: ...
 
 NelderMead = NelderMead_64
 
+class FitResult_64:
+    version: str
+    timestamp: int
+    fx: float
+    standard_fx: float | None
+    best: list[float]
+    indices: list[tuple[str, str, int]]
+    covariance: list[list[float]] | None
+    errors: list[float] | None
+    correlation: list[list[float]] | None
+    minos_errors: list[tuple[float | None, float | None]] | None
+    parameters: list[Parameter_64]
+    free_parameters: list[Parameter_64]
+
+    def __init__(
+        self,
+        ell: ExtendedLogLikelihood_64,
+        best: list[float],
+        fx: float,
+        covariance: list[list[float]] | None = None,
+    ) -> None: ...
+    def value(self, amplitude: str, parameter: str) -> float: ...
+    def fit_fractions(
+        self,
+        ell: ExtendedLogLikelihood_64,
+        dataset_mc: Dataset_64,
+        groups: list[tuple[str, list[str]]] | None = None,
+    ) -> list[tuple[str, float, float | None]]: ...
+    def aic(self) -> float: ...
+    def bic(self) -> float: ...
+    def warm_start(
+        self, ell: ExtendedLogLikelihood_64 | ExtendedLogLikelihood_32
+    ) -> list[float]: ...
+    def restore(
+        self, ell: ExtendedLogLikelihood_64 | ExtendedLogLikelihood_32
+    ) -> list[float]: ...
+    def likelihood_ratio_test(self, alternative: FitResult_64) -> tuple[float, int, float]: ...
+    def error(self, amplitude: str, parameter: str) -> float | None: ...
+    def minos_error(
+        self, amplitude: str, parameter: str
+    ) -> tuple[float | None, float | None] | None: ...
+    def compute_minos_errors(
+        self, ell: ExtendedLogLikelihood_64, *, delta: float = 1.0, max_steps: int = 1000
+    ) -> None: ...
+    def to_json(self) -> str: ...
+    @staticmethod
+    def from_json(json: str) -> FitResult_64: ...
+
+class FitResult_32:
+    version: str
+    timestamp: int
+    fx: float
+    standard_fx: float | None
+    best: list[float]
+    indices: list[tuple[str, str, int]]
+    covariance: list[list[float]] | None
+    errors: list[float] | None
+    correlation: list[list[float]] | None
+    minos_errors: list[tuple[float | None, float | None]] | None
+    parameters: list[Parameter_32]
+    free_parameters: list[Parameter_32]
+
+    def __init__(
+        self,
+        ell: ExtendedLogLikelihood_32,
+        best: list[float],
+        fx: float,
+        covariance: list[list[float]] | None = None,
+    ) -> None: ...
+    def value(self, amplitude: str, parameter: str) -> float: ...
+    def fit_fractions(
+        self,
+        ell: ExtendedLogLikelihood_32,
+        dataset_mc: Dataset_32,
+        groups: list[tuple[str, list[str]]] | None = None,
+    ) -> list[tuple[str, float, float | None]]: ...
+    def aic(self) -> float: ...
+    def bic(self) -> float: ...
+    def warm_start(
+        self, ell: ExtendedLogLikelihood_64 | ExtendedLogLikelihood_32
+    ) -> list[float]: ...
+    def restore(
+        self, ell: ExtendedLogLikelihood_64 | ExtendedLogLikelihood_32
+    ) -> list[float]: ...
+    def likelihood_ratio_test(self, alternative: FitResult_32) -> tuple[float, int, float]: ...
+    def error(self, amplitude: str, parameter: str) -> float | None: ...
+    def minos_error(
+        self, amplitude: str, parameter: str
+    ) -> tuple[float | None, float | None] | None: ...
+    def compute_minos_errors(
+        self, ell: ExtendedLogLikelihood_32, *, delta: float = 1.0, max_steps: int = 1000
+    ) -> None: ...
+    def to_json(self) -> str: ...
+    @staticmethod
+    def from_json(json: str) -> FitResult_32: ...
+
+FitResult = FitResult_64
+
+class Ensemble_64:
+    names: list[str]
+    samples: list[list[float]]
+    weights: list[float]
+    chains: list[int]
+    effective_size: float
+    mean: list[float]
+    std: list[float]
+    covariance: list[list[float]]
+    correlation: list[list[float]]
+
+    def __init__(
+        self,
+        names: list[str],
+        samples: list[list[float]],
+        weights: list[float] | None = None,
+        chains: list[int] | None = None,
+    ) -> None: ...
+    @staticmethod
+    def from_fit_results(results: list[FitResult_64]) -> Ensemble_64: ...
+    def __len__(self) -> int: ...
+    def index(self, name: str) -> int: ...
+    def quantile(self, name: str, q: float) -> float: ...
+    def median(self, name: str) -> float: ...
+    def hpd(self, name: str, probability: float) -> tuple[float, float]: ...
+    def to_arviz_dict(self) -> dict[str, list[list[float]]]: ...
+    def to_json(self) -> str: ...
+    @staticmethod
+    def from_json(json: str) -> Ensemble_64: ...
+
+class Ensemble_32:
+    names: list[str]
+    samples: list[list[float]]
+    weights: list[float]
+    chains: list[int]
+    effective_size: float
+    mean: list[float]
+    std: list[float]
+    covariance: list[list[float]]
+    correlation: list[list[float]]
+
+    def __init__(
+        self,
+        names: list[str],
+        samples: list[list[float]],
+        weights: list[float] | None = None,
+        chains: list[int] | None = None,
+    ) -> None: ...
+    @staticmethod
+    def from_fit_results(results: list[FitResult_32]) -> Ensemble_32: ...
+    def __len__(self) -> int: ...
+    def index(self, name: str) -> int: ...
+    def quantile(self, name: str, q: float) -> float: ...
+    def median(self, name: str) -> float: ...
+    def hpd(self, name: str, probability: float) -> tuple[float, float]: ...
+    def to_arviz_dict(self) -> dict[str, list[list[float]]]: ...
+    def to_json(self) -> str: ...
+    @staticmethod
+    def from_json(json: str) -> Ensemble_32: ...
+
+Ensemble = Ensemble_64
+
+class ToyStudyResult_64:
+    names: list[str]
+    truths: list[list[float]]
+    n_events: list[int]
+    errors: list[list[float]]
+    fits: list[FitResult_64]
+
+    def __len__(self) -> int: ...
+    def biases(self, name: str) -> list[float]: ...
+    def pulls(self, name: str) -> list[float]: ...
+    def summary(self) -> list[dict[str, Any]]: ...
+    def to_json(self) -> str: ...
+    @staticmethod
+    def from_json(json: str) -> ToyStudyResult_64: ...
+
+class ToyStudyResult_32:
+    names: list[str]
+    truths: list[list[float]]
+    n_events: list[int]
+    errors: list[list[float]]
+    fits: list[FitResult_32]
+
+    def __len__(self) -> int: ...
+    def biases(self, name: str) -> list[float]: ...
+    def pulls(self, name: str) -> list[float]: ...
+    def summary(self) -> list[dict[str, Any]]: ...
+    def to_json(self) -> str: ...
+    @staticmethod
+    def from_json(json: str) -> ToyStudyResult_32: ...
+
+ToyStudyResult = ToyStudyResult_64
+
+class SimultaneousLikelihood_64:
+    labels: list[str]
+    terms: list[tuple[str, ExtendedLogLikelihood_64]]
+    parameter_names: list[str]
+    bounds: list[tuple[float, float]]
+    initial: list[float]
+    n_free: int
+
+    def __init__(self, terms: list[tuple[str, ExtendedLogLikelihood_64]]) -> None: ...
+    def scope(self, amplitude: str, parameter: str) -> None: ...
+    def share(self, amplitude: str, parameter: str) -> None: ...
+    def is_scoped(self, amplitude: str, parameter: str) -> bool: ...
+    def add_normalizations(self, reference: str, sigma: float | None = None) -> None: ...
+    def remove_normalizations(self) -> None: ...
+    def term_parameters(self, label: str, parameters: list[float]) -> list[float]: ...
+    def __call__(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def evaluate(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def gradient(self, parameters: list[float], *, parallel: bool = True) -> list[float]: ...
+
+class SimultaneousLikelihood_32:
+    labels: list[str]
+    terms: list[tuple[str, ExtendedLogLikelihood_32]]
+    parameter_names: list[str]
+    bounds: list[tuple[float, float]]
+    initial: list[float]
+    n_free: int
+
+    def __init__(self, terms: list[tuple[str, ExtendedLogLikelihood_32]]) -> None: ...
+    def scope(self, amplitude: str, parameter: str) -> None: ...
+    def share(self, amplitude: str, parameter: str) -> None: ...
+    def is_scoped(self, amplitude: str, parameter: str) -> bool: ...
+    def add_normalizations(self, reference: str, sigma: float | None = None) -> None: ...
+    def remove_normalizations(self) -> None: ...
+    def term_parameters(self, label: str, parameters: list[float]) -> list[float]: ...
+    def __call__(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def evaluate(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def gradient(self, parameters: list[float], *, parallel: bool = True) -> list[float]: ...
+
+SimultaneousLikelihood = SimultaneousLikelihood_64
+
+class LinearLikelihood_64:
+    n_terms: int
+    bounds: list[tuple[float, float]]
+    initial: list[float]
+    n_free: int
+    storage: str
+
+    def __init__(self, ell: ExtendedLogLikelihood_64, *, storage: Literal['full', 'single', 'half'] = 'full') -> None: ...
+    def __call__(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def evaluate(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def gradient(self, parameters: list[float], *, parallel: bool = True) -> list[float]: ...
+
+class LinearLikelihood_32:
+    n_terms: int
+    bounds: list[tuple[float, float]]
+    initial: list[float]
+    n_free: int
+    storage: str
+
+    def __init__(self, ell: ExtendedLogLikelihood_32, *, storage: Literal['full', 'single', 'half'] = 'full') -> None: ...
+    def __call__(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def evaluate(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def gradient(self, parameters: list[float], *, parallel: bool = True) -> list[float]: ...
+
+class BinnedChi2_64:
+    edges: list[list[float]]
+    n_bins: int
+    n_filled: int
+    counts: list[float]
+    bounds: list[tuple[float, float]]
+    initial: list[float]
+    n_free: int
+
+    def __init__(
+        self,
+        data_manager: Manager_64,
+        mc_manager: Manager_64,
+        edges: list[list[float]],
+        variables: Callable[[Event_64], list[float]],
+    ) -> None: ...
+    def predicted(self, parameters: list[float]) -> list[float]: ...
+    def __call__(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def evaluate(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def gradient(self, parameters: list[float], *, parallel: bool = True) -> list[float]: ...
+
+class BinnedChi2_32:
+    edges: list[list[float]]
+    n_bins: int
+    n_filled: int
+    counts: list[float]
+    bounds: list[tuple[float, float]]
+    initial: list[float]
+    n_free: int
+
+    def __init__(
+        self,
+        data_manager: Manager_32,
+        mc_manager: Manager_32,
+        edges: list[list[float]],
+        variables: Callable[[Event_32], list[float]],
+    ) -> None: ...
+    def predicted(self, parameters: list[float]) -> list[float]: ...
+    def __call__(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def evaluate(self, parameters: list[float], *, parallel: bool = True) -> float: ...
+    def gradient(self, parameters: list[float], *, parallel: bool = True) -> list[float]: ...
+
+LinearLikelihood = LinearLikelihood_64
+BinnedChi2 = BinnedChi2_64
+
+def set_precalculation_cache(enabled: bool = True) -> None: ...
+def clear_precalculation_cache() -> None: ...
+def set_profiling(enabled: bool = True) -> None: ...
+def reset_profiling() -> None: ...
+def profiling_report() -> list[tuple[str, str, int, float]]: ...
+def histogram(
+    values: list[float], edges: list[float], weights: list[float] | None = None
+) -> tuple[list[float], list[float]]: ...
+
 @overload
 def open(
     file_name: str | Path,
@@ -0,0 +1,52 @@
+"""The likelihoods, fit results, and study tools of ``rustitude`` in one namespace."""
+
+from __future__ import annotations
+
+from .manager import (
+    ExtendedLogLikelihood_64,
+    ExtendedLogLikelihood_32,
+    NelderMead_64,
+    NelderMead_32,
+    FitResult_64,
+    FitResult_32,
+    Ensemble_64,
+    Ensemble_32,
+    ToyStudyResult_64,
+    ToyStudyResult_32,
+    SimultaneousLikelihood_64,
+    SimultaneousLikelihood_32,
+    LinearLikelihood_64,
+    LinearLikelihood_32,
+)
+
+ExtendedLogLikelihood = ExtendedLogLikelihood_64
+NelderMead = NelderMead_64
+FitResult = FitResult_64
+Ensemble = Ensemble_64
+ToyStudyResult = ToyStudyResult_64
+SimultaneousLikelihood = SimultaneousLikelihood_64
+LinearLikelihood = LinearLikelihood_64
+
+__all__ = [
+    'ExtendedLogLikelihood',
+    'ExtendedLogLikelihood_64',
+    'ExtendedLogLikelihood_32',
+    'NelderMead',
+    'NelderMead_64',
+    'NelderMead_32',
+    'FitResult',
+    'FitResult_64',
+    'FitResult_32',
+    'Ensemble',
+    'Ensemble_64',
+    'Ensemble_32',
+    'ToyStudyResult',
+    'ToyStudyResult_64',
+    'ToyStudyResult_32',
+    'SimultaneousLikelihood',
+    'SimultaneousLikelihood_64',
+    'SimultaneousLikelihood_32',
+    'LinearLikelihood',
+    'LinearLikelihood_64',
+    'LinearLikelihood_32',
+]
@@ -1 +1 @@
-from . import resonances, sdmes, harmonics, dalitz, polarization
+from . import resonances, sdmes, harmonics, moments, dalitz, polarization, vecps
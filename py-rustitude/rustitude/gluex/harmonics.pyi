@@ -207,6 +207,115 @@ def Zlm_32(
     decay: str = '[0, 1]',
     frame: str = 'helicity',
 ) -> Amplitude_32: ...
+@overload
+def PolarizedZlm(
+    name: str,
+    l: Literal[0],  # noqa: E741
+    m: Literal[0],
+    reflectivity: str = '+',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude: ...
+@overload
+def PolarizedZlm(
+    name: str,
+    l: Literal[1],  # noqa: E741
+    m: Literal[-1, 0, 1],
+    reflectivity: str = '+',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude: ...
+@overload
+def PolarizedZlm(
+    name: str,
+    l: Literal[2],  # noqa: E741
+    m: Literal[-2, -1, 0, 1, 2],
+    reflectivity: str = '+',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude: ...
+@overload
+def PolarizedZlm(
+    name: str,
+    l: Literal[3],  # noqa: E741
+    m: Literal[-3, -2, -1, 0, 1, 2, 3],
+    reflectivity: Literal[
+        'positive', 'pos', 'p', '+', 'plus', 'negative', 'neg', 'n', '-', 'minus', 'm'
+    ] = 'positive',
+    frame: Literal['helicity', 'hx', 'gottfried-jackson', 'gj'] = 'helicity',
+) -> Amplitude: ...
+@overload
+def PolarizedZlm_64(
+    name: str,
+    l: Literal[0],  # noqa: E741
+    m: Literal[0],
+    reflectivity: str = '+',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude_64: ...
+@overload
+def PolarizedZlm_64(
+    name: str,
+    l: Literal[1],  # noqa: E741
+    m: Literal[-1, 0, 1],
+    reflectivity: str = '+',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude_64: ...
+@overload
+def PolarizedZlm_64(
+    name: str,
+    l: Literal[2],  # noqa: E741
+    m: Literal[-2, -1, 0, 1, 2],
+    reflectivity: str = '+',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude_64: ...
+@overload
+def PolarizedZlm_64(
+    name: str,
+    l: Literal[3],  # noqa: E741
+    m: Literal[-3, -2, -1, 0, 1, 2, 3],
+    reflectivity: str = '+',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude_64: ...
+@overload
+def PolarizedZlm_32(
+    name: str,
+    l: Literal[0],  # noqa: E741
+    m: Literal[0],
+    reflectivity: str = '+',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude_32: ...
+@overload
+def PolarizedZlm_32(
+    name: str,
+    l: Literal[1],  # noqa: E741
+    m: Literal[-1, 0, 1],
+    reflectivity: str = '+',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude_32: ...
+@overload
+def PolarizedZlm_32(
+    name: str,
+    l: Literal[2],  # noqa: E741
+    m: Literal[-2, -1, 0, 1, 2],
+    reflectivity: str = '+',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude_32: ...
+@overload
+def PolarizedZlm_32(
+    name: str,
+    l: Literal[3],  # noqa: E741
+    m: Literal[-3, -2, -1, 0, 1, 2, 3],
+    reflectivity: str = '+',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude_32: ...
 def OnePS(  # noqa: N802
     name: str,
     reflectivity: str = '+',
@@ -0,0 +1,55 @@
+from typing import Literal
+
+from rustitude import (
+    Amplitude,
+    Amplitude_32,
+    Amplitude_64,
+    Dataset_64,
+    ExtendedLogLikelihood_64,
+)
+
+def project_moments(
+    waves: list[tuple[int, int, complex]], l_max: int
+) -> list[tuple[int, int, complex]]: ...
+def project_incoherent_moments(
+    sums: list[list[tuple[int, int, complex]]], l_max: int
+) -> list[tuple[int, int, complex]]: ...
+def dataset_moments(
+    dataset: Dataset_64,
+    l_max: int,
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+    weights: list[float] | None = None,
+) -> list[tuple[int, int, complex]]: ...
+def predicted_moments(
+    ell: ExtendedLogLikelihood_64,
+    parameters: list[float],
+    dataset_mc: Dataset_64,
+    l_max: int,
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> list[tuple[int, int, complex]]: ...
+def MomentIntensity(
+    name: str,
+    l_max: int,
+    polarized: bool = True,
+    positivity: Literal['absolute', 'clamp'] = 'absolute',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude: ...
+def MomentIntensity_64(
+    name: str,
+    l_max: int,
+    polarized: bool = True,
+    positivity: Literal['absolute', 'clamp'] = 'absolute',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude_64: ...
+def MomentIntensity_32(
+    name: str,
+    l_max: int,
+    polarized: bool = True,
+    positivity: Literal['absolute', 'clamp'] = 'absolute',
+    decay: str = '[0, 1]',
+    frame: str = 'helicity',
+) -> Amplitude_32: ...
@@ -1,19 +1,36 @@
+from typing import Literal
+
 from rustitude import Amplitude, Amplitude_64, Amplitude_32
 
 def BreitWigner(
     name: str,
     l: int,  # noqa: E741
     decay: str = '[0, 1]',
+    barrier_factor: Literal[
+        'blatt-weisskopf', 'normalized-blatt-weisskopf', 'momentum-power', 'none'
+    ] = 'blatt-weisskopf',
+    width_channels: list[tuple[float, float, float, int]] | None = None,
+    mass_convention: Literal['nominal', 'reconstructed'] = 'nominal',
 ) -> Amplitude: ...  # noqa: N802
 def BreitWigner_64(
     name: str,
     l: int,  # noqa: E741
     decay: str = '[0, 1]',
+    barrier_factor: Literal[
+        'blatt-weisskopf', 'normalized-blatt-weisskopf', 'momentum-power', 'none'
+    ] = 'blatt-weisskopf',
+    width_channels: list[tuple[float, float, float, int]] | None = None,
+    mass_convention: Literal['nominal', 'reconstructed'] = 'nominal',
 ) -> Amplitude_64: ...  # noqa: N802
 def BreitWigner_32(
     name: str,
     l: int,  # noqa: E741
     decay: str = '[0, 1]',
+    barrier_factor: Literal[
+        'blatt-weisskopf', 'normalized-blatt-weisskopf', 'momentum-power', 'none'
+    ] = 'blatt-weisskopf',
+    width_channels: list[tuple[float, float, float, int]] | None = None,
+    mass_convention: Literal['nominal', 'reconstructed'] = 'nominal',
 ) -> Amplitude_32: ...  # noqa: N802
 def Flatte(
     name: str,
@@ -36,21 +53,156 @@ def Flatte_32(
     m2s: tuple[float, float],
     decay: str = '[0, 1]',
 ) -> Amplitude_32: ...  # noqa: N802
-def KMatrixA0(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude: ...  # noqa: N802
-def KMatrixA0_64(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_64: ...  # noqa: N802
-def KMatrixA0_32(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_32: ...  # noqa: N802
-def KMatrixA2(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude: ...  # noqa: N802
-def KMatrixA2_64(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_64: ...  # noqa: N802
-def KMatrixA2_32(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_32: ...  # noqa: N802
-def KMatrixF0(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude: ...  # noqa: N802
-def KMatrixF0_64(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_64: ...  # noqa: N802
-def KMatrixF0_32(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_32: ...  # noqa: N802
-def KMatrixF2(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude: ...  # noqa: N802
-def KMatrixF2_64(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_64: ...  # noqa: N802
-def KMatrixF2_32(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_32: ...  # noqa: N802
-def KMatrixPi1(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude: ...  # noqa: N802
-def KMatrixPi1_64(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_64: ...  # noqa: N802
-def KMatrixPi1_32(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_32: ...  # noqa: N802
-def KMatrixRho(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude: ...  # noqa: N802
-def KMatrixRho_64(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_64: ...  # noqa: N802
-def KMatrixRho_32(name: str, channel: int, decay: str = '[0, 1]') -> Amplitude_32: ...  # noqa: N802
+def KMatrixA0(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude: ...  # noqa: N802
+def KMatrixA0_64(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_64: ...  # noqa: N802
+def KMatrixA0_32(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_32: ...  # noqa: N802
+def KMatrixA2(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude: ...  # noqa: N802
+def KMatrixA2_64(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_64: ...  # noqa: N802
+def KMatrixA2_32(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_32: ...  # noqa: N802
+def KMatrixF0(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude: ...  # noqa: N802
+def KMatrixF0_64(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_64: ...  # noqa: N802
+def KMatrixF0_32(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_32: ...  # noqa: N802
+def KMatrixF2(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude: ...  # noqa: N802
+def KMatrixF2_64(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_64: ...  # noqa: N802
+def KMatrixF2_32(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_32: ...  # noqa: N802
+def KMatrixPi1(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude: ...  # noqa: N802
+def KMatrixPi1_64(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_64: ...  # noqa: N802
+def KMatrixPi1_32(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_32: ...  # noqa: N802
+def KMatrixRho(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude: ...  # noqa: N802
+def KMatrixRho_64(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_64: ...  # noqa: N802
+def KMatrixRho_32(
+    name: str,
+    channel: int,
+    decay: str = '[0, 1]',
+    *,
+    non_resonant: list[int] | None = None,
+) -> Amplitude_32: ...  # noqa: N802
+def Deck(  # noqa: N802
+    name: str,
+    isobar: str = '[0, 1]',
+    bachelor: int = 2,
+    exchange_mass: float = 0.13957,
+    form_factor: Literal['none', 'exponential', 'monopole', 'dipole'] = 'none',
+    form_factor_parameter: float = 1.0,
+    slope: float = 0.0,
+) -> Amplitude: ...
+def Deck_64(  # noqa: N802
+    name: str,
+    isobar: str = '[0, 1]',
+    bachelor: int = 2,
+    exchange_mass: float = 0.13957,
+    form_factor: Literal['none', 'exponential', 'monopole', 'dipole'] = 'none',
+    form_factor_parameter: float = 1.0,
+    slope: float = 0.0,
+) -> Amplitude_64: ...
+def Deck_32(  # noqa: N802
+    name: str,
+    isobar: str = '[0, 1]',
+    bachelor: int = 2,
+    exchange_mass: float = 0.13957,
+    form_factor: Literal['none', 'exponential', 'monopole', 'dipole'] = 'none',
+    form_factor_parameter: float = 1.0,
+    slope: float = 0.0,
+) -> Amplitude_32: ...
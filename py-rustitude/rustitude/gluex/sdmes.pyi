@@ -27,3 +27,12 @@ def VecRadiativeSDME_64(  # noqa: N802
 def VecRadiativeSDME_32(  # noqa: N802
     name: str, decay: str = '[0, 1]', frame: str = 'helicity'
 ) -> Amplitude_32: ...
+def VectorSDME(  # noqa: N802
+    name: str, decay: str = '[0, 1]', frame: str = 'helicity'
+) -> Amplitude: ...
+def VectorSDME_64(  # noqa: N802
+    name: str, decay: str = '[0, 1]', frame: str = 'helicity'
+) -> Amplitude_64: ...
+def VectorSDME_32(  # noqa: N802
+    name: str, decay: str = '[0, 1]', frame: str = 'helicity'
+) -> Amplitude_32: ...
@@ -0,0 +1,35 @@
+from rustitude import Amplitude, Amplitude_64, Amplitude_32
+
+def VecPS(
+    name: str,
+    j: int,
+    m: int,
+    l: int,  # noqa: E741
+    reflectivity: str = '+',
+    decay_helicity: int = 0,
+    bachelor: int = 0,
+    vector: str = '[1, 2, 3]',
+    frame: str = 'helicity',
+) -> Amplitude: ...
+def VecPS_64(
+    name: str,
+    j: int,
+    m: int,
+    l: int,  # noqa: E741
+    reflectivity: str = '+',
+    decay_helicity: int = 0,
+    bachelor: int = 0,
+    vector: str = '[1, 2, 3]',
+    frame: str = 'helicity',
+) -> Amplitude_64: ...
+def VecPS_32(
+    name: str,
+    j: int,
+    m: int,
+    l: int,  # noqa: E741
+    reflectivity: str = '+',
+    decay_helicity: int = 0,
+    bachelor: int = 0,
+    vector: str = '[1, 2, 3]',
+    frame: str = 'helicity',
+) -> Amplitude_32: ...
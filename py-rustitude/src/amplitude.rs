@@ -3,6 +3,58 @@ use pyo3::{prelude::*, types::PyList};
 use rustitude_core::{self as rust, amplitude::AmpLike as RustAmpLike};
 use std::ops::{Add, Mul};
 
+/// Parses the name of a [`Transform`](rust::amplitude::Transform) and optional bounds from
+/// Python.
+pub(crate) fn parse_transform<F: rust::Field>(
+    transform: Option<&str>,
+    bounds: Option<(F, F)>,
+) -> PyResult<Option<rust::amplitude::Transform<F>>> {
+    match (transform, bounds) {
+        (None, None) => Ok(None),
+        (Some("log"), None) => Ok(Some(rust::amplitude::Transform::Log)),
+        (Some("sqrt"), None) => Ok(Some(rust::amplitude::Transform::Sqrt)),
+        (Some("bounded"), Some((lower, upper))) => {
+            Ok(Some(rust::amplitude::Transform::Bounded(lower, upper)))
+        }
+        (transform, _) => Err(PyErr::from(
+            rust::errors::RustitudeError::InvalidParameterValue(format!(
+                "expected \"log\", \"sqrt\", \"bounded\" with bounds, or None, got {transform:?}"
+            )),
+        )),
+    }
+}
+
+/// Parses the interpolation and knot parameterization of a
+/// [`Spline`](rust::amplitude::Spline) from Python.
+pub(crate) fn parse_spline(
+    kind: &str,
+    form: &str,
+) -> PyResult<(rust::amplitude::SplineKind, rust::amplitude::KnotForm)> {
+    let kind = match kind {
+        "cubic" => rust::amplitude::SplineKind::Cubic,
+        "monotone" => rust::amplitude::SplineKind::Monotone,
+        kind => {
+            return Err(PyErr::from(
+                rust::errors::RustitudeError::InvalidParameterValue(format!(
+                    "expected \"cubic\" or \"monotone\", got \"{kind}\""
+                )),
+            ))
+        }
+    };
+    let form = match form {
+        "cartesian" => rust::amplitude::KnotForm::Cartesian,
+        "polar" => rust::amplitude::KnotForm::Polar,
+        form => {
+            return Err(PyErr::from(
+                rust::errors::RustitudeError::InvalidParameterValue(format!(
+                    "expected \"cartesian\" or \"polar\", got \"{form}\""
+                )),
+            ))
+        }
+    };
+    Ok((kind, form))
+}
+
 #[pyclass]
 #[derive(Clone)]
 pub struct Parameter_64(rust::amplitude::Parameter<f64>);
@@ -48,6 +100,10 @@ impl Parameter_64 {
     fn bounds(&self) -> (f64, f64) {
         self.0.bounds
     }
+    #[getter]
+    fn transform(&self) -> Option<String> {
+        self.0.transform.map(|transform| format!("{transform:?}"))
+    }
     fn __str__(&self) -> String {
         format!("{}", self.0)
     }
@@ -101,6 +157,10 @@ impl Parameter_32 {
     fn bounds(&self) -> (f32, f32) {
         self.0.bounds
     }
+    #[getter]
+    fn transform(&self) -> Option<String> {
+        self.0.transform.map(|transform| format!("{transform:?}"))
+    }
     fn __str__(&self) -> String {
         format!("{}", self.0)
     }
@@ -190,6 +250,34 @@ impl rust::amplitude::Node<f64> for PyNode_64 {
         })
     }
 
+    fn initial_values(&self) -> Vec<f64> {
+        Python::with_gil(|py| {
+            let node = self.node.bind(py);
+            if node.hasattr("initial_values").unwrap_or(false) {
+                node.call_method0("initial_values")
+                    .unwrap()
+                    .extract()
+                    .unwrap()
+            } else {
+                vec![]
+            }
+        })
+    }
+
+    fn parameter_bounds(&self) -> Vec<(f64, f64)> {
+        Python::with_gil(|py| {
+            let node = self.node.bind(py);
+            if node.hasattr("parameter_bounds").unwrap_or(false) {
+                node.call_method0("parameter_bounds")
+                    .unwrap()
+                    .extract()
+                    .unwrap()
+            } else {
+                vec![]
+            }
+        })
+    }
+
     fn is_python_node(&self) -> bool {
         true
     }
@@ -276,6 +364,34 @@ impl rust::amplitude::Node<f32> for PyNode_32 {
         })
     }
 
+    fn initial_values(&self) -> Vec<f32> {
+        Python::with_gil(|py| {
+            let node = self.node.bind(py);
+            if node.hasattr("initial_values").unwrap_or(false) {
+                node.call_method0("initial_values")
+                    .unwrap()
+                    .extract()
+                    .unwrap()
+            } else {
+                vec![]
+            }
+        })
+    }
+
+    fn parameter_bounds(&self) -> Vec<(f32, f32)> {
+        Python::with_gil(|py| {
+            let node = self.node.bind(py);
+            if node.hasattr("parameter_bounds").unwrap_or(false) {
+                node.call_method0("parameter_bounds")
+                    .unwrap()
+                    .extract()
+                    .unwrap()
+            } else {
+                vec![]
+            }
+        })
+    }
+
     fn is_python_node(&self) -> bool {
         true
     }
@@ -299,6 +415,148 @@ enum AmpLike_32 {
     Sum(Sum_32),
 }
 
+/// A [`Piecewise`](rust::amplitude::Piecewise) function binned in a variable computed by a
+/// Python callable, which is evaluated once per [`Event`](rust::dataset::Event) during
+/// precalculation.
+#[derive(Clone)]
+struct PyPiecewise_64 {
+    edges: Vec<(f64, f64)>,
+    variable: Py<PyAny>,
+    calculated_variable: Vec<f64>,
+}
+
+impl PyPiecewise_64 {
+    fn new(bins: usize, range: (f64, f64), variable: Py<PyAny>) -> Self {
+        let diff = (range.1 - range.0) / bins as f64;
+        let edges = (0..bins)
+            .map(|i| {
+                (
+                    f64::mul_add(i as f64, diff, range.0),
+                    f64::mul_add((i + 1) as f64, diff, range.0),
+                )
+            })
+            .collect();
+        Self {
+            edges,
+            variable,
+            calculated_variable: Vec::default(),
+        }
+    }
+
+    fn bin(&self, event: &rust::dataset::Event<f64>) -> Option<usize> {
+        let val = self.calculated_variable[event.index];
+        self.edges.iter().position(|&(l, r)| val >= l && val <= r)
+    }
+}
+
+impl rust::amplitude::Node<f64> for PyPiecewise_64 {
+    fn precalculate(
+        &mut self,
+        dataset: &rust::dataset::Dataset<f64>,
+    ) -> Result<(), rust::errors::RustitudeError> {
+        self.calculated_variable = Python::with_gil(|py| {
+            dataset
+                .events
+                .iter()
+                .map(|event| {
+                    let py_event = crate::dataset::Event_64::from(event.clone());
+                    self.variable.call1(py, (py_event,))?.extract::<f64>(py)
+                })
+                .collect::<PyResult<Vec<f64>>>()
+        })?;
+        Ok(())
+    }
+
+    fn calculate(
+        &self,
+        parameters: &[f64],
+        event: &rust::dataset::Event<f64>,
+    ) -> Result<rust::prelude::Complex<f64>, rust::errors::RustitudeError> {
+        Ok(self
+            .bin(event)
+            .map_or_else(rust::prelude::Complex::default, |i_bin| {
+                rust::prelude::Complex::new(parameters[i_bin * 2], parameters[(i_bin * 2) + 1])
+            }))
+    }
+
+    fn parameters(&self) -> Vec<String> {
+        (0..self.edges.len())
+            .flat_map(|i| vec![format!("bin {} re", i), format!("bin {} im", i)])
+            .collect()
+    }
+}
+
+/// A [`Piecewise`](rust::amplitude::Piecewise) function binned in a variable computed by a
+/// Python callable, which is evaluated once per [`Event`](rust::dataset::Event) during
+/// precalculation.
+#[derive(Clone)]
+struct PyPiecewise_32 {
+    edges: Vec<(f32, f32)>,
+    variable: Py<PyAny>,
+    calculated_variable: Vec<f32>,
+}
+
+impl PyPiecewise_32 {
+    fn new(bins: usize, range: (f32, f32), variable: Py<PyAny>) -> Self {
+        let diff = (range.1 - range.0) / bins as f32;
+        let edges = (0..bins)
+            .map(|i| {
+                (
+                    f32::mul_add(i as f32, diff, range.0),
+                    f32::mul_add((i + 1) as f32, diff, range.0),
+                )
+            })
+            .collect();
+        Self {
+            edges,
+            variable,
+            calculated_variable: Vec::default(),
+        }
+    }
+
+    fn bin(&self, event: &rust::dataset::Event<f32>) -> Option<usize> {
+        let val = self.calculated_variable[event.index];
+        self.edges.iter().position(|&(l, r)| val >= l && val <= r)
+    }
+}
+
+impl rust::amplitude::Node<f32> for PyPiecewise_32 {
+    fn precalculate(
+        &mut self,
+        dataset: &rust::dataset::Dataset<f32>,
+    ) -> Result<(), rust::errors::RustitudeError> {
+        self.calculated_variable = Python::with_gil(|py| {
+            dataset
+                .events
+                .iter()
+                .map(|event| {
+                    let py_event = crate::dataset::Event_32::from(event.clone());
+                    self.variable.call1(py, (py_event,))?.extract::<f32>(py)
+                })
+                .collect::<PyResult<Vec<f32>>>()
+        })?;
+        Ok(())
+    }
+
+    fn calculate(
+        &self,
+        parameters: &[f32],
+        event: &rust::dataset::Event<f32>,
+    ) -> Result<rust::prelude::Complex<f32>, rust::errors::RustitudeError> {
+        Ok(self
+            .bin(event)
+            .map_or_else(rust::prelude::Complex::default, |i_bin| {
+                rust::prelude::Complex::new(parameters[i_bin * 2], parameters[(i_bin * 2) + 1])
+            }))
+    }
+
+    fn parameters(&self) -> Vec<String> {
+        (0..self.edges.len())
+            .flat_map(|i| vec![format!("bin {} re", i), format!("bin {} im", i)])
+            .collect()
+    }
+}
+
 #[pyclass]
 #[derive(Clone)]
 pub struct Amplitude_64(rust::amplitude::Amplitude<f64>);
@@ -330,6 +588,13 @@ impl Amplitude_64 {
     fn parameter_index_start(&self) -> usize {
         self.0.parameter_index_start
     }
+    #[getter]
+    fn scale(&self) -> f64 {
+        self.0.scale
+    }
+    fn scaled(&self, scale: f64) -> Self {
+        Self(self.0.clone().scaled(scale))
+    }
     fn __str__(&self) -> String {
         format!("{}", self.0)
     }
@@ -403,6 +668,13 @@ impl Amplitude_32 {
     fn parameter_index_start(&self) -> usize {
         self.0.parameter_index_start
     }
+    #[getter]
+    fn scale(&self) -> f32 {
+        self.0.scale
+    }
+    fn scaled(&self, scale: f32) -> Self {
+        Self(self.0.clone().scaled(scale))
+    }
     fn __str__(&self) -> String {
         format!("{}", self.0)
     }
@@ -840,6 +1112,14 @@ impl_convert!(NormSqr_64, rust::amplitude::NormSqr<f64>);
 
 #[pymethods]
 impl NormSqr_64 {
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.0.name.clone()
+    }
+    #[getter]
+    fn active(&self) -> bool {
+        self.0.active
+    }
     fn __str__(&self) -> String {
         format!("{}", self.0)
     }
@@ -855,6 +1135,14 @@ impl_convert!(NormSqr_32, rust::amplitude::NormSqr<f32>);
 
 #[pymethods]
 impl NormSqr_32 {
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.0.name.clone()
+    }
+    #[getter]
+    fn active(&self) -> bool {
+        self.0.active
+    }
     fn __str__(&self) -> String {
         format!("{}", self.0)
     }
@@ -863,6 +1151,57 @@ impl NormSqr_32 {
     }
 }
 
+#[pyclass]
+#[derive(Clone)]
+pub struct ActivationSnapshot(rust::amplitude::ActivationSnapshot);
+impl_convert!(ActivationSnapshot, rust::amplitude::ActivationSnapshot);
+
+#[pymethods]
+impl ActivationSnapshot {
+    #[getter]
+    fn amplitudes(&self) -> Vec<(String, bool)> {
+        self.0.amplitudes.clone()
+    }
+    #[getter]
+    fn sums(&self) -> Vec<bool> {
+        self.0.sums.clone()
+    }
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// Converts Python [`AmpLike`](rust::amplitude::AmpLike) objects into boxed Rust ones.
+fn extract_amplikes_64(
+    py: Python<'_>,
+    amps: Vec<PyObject>,
+) -> PyResult<Vec<Box<dyn rust::amplitude::AmpLike<f64>>>> {
+    amps.into_iter()
+        .map(|obj| {
+            if let Ok(amp) = obj.extract::<Amplitude_64>(py) {
+                Ok(Box::new(rust::amplitude::Amplitude::<f64>::from(amp))
+                    as Box<dyn rust::amplitude::AmpLike<f64>>)
+            } else if let Ok(amp) = obj.extract::<Real_64>(py) {
+                Ok(Box::new(rust::amplitude::Real::<f64>::from(amp))
+                    as Box<dyn rust::amplitude::AmpLike<f64>>)
+            } else if let Ok(amp) = obj.extract::<Imag_64>(py) {
+                Ok(Box::new(rust::amplitude::Imag::<f64>::from(amp))
+                    as Box<dyn rust::amplitude::AmpLike<f64>>)
+            } else if let Ok(amp) = obj.extract::<Product_64>(py) {
+                Ok(Box::new(rust::amplitude::Product::<f64>::from(amp))
+                    as Box<dyn rust::amplitude::AmpLike<f64>>)
+            } else if let Ok(amp) = obj.extract::<Sum_64>(py) {
+                Ok(Box::new(rust::amplitude::Sum::<f64>::from(amp))
+                    as Box<dyn rust::amplitude::AmpLike<f64>>)
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                    "Unsupported AmpLike type",
+                ))
+            }
+        })
+        .collect()
+}
+
 #[pyclass]
 #[derive(Clone)]
 pub struct Model_64(rust::amplitude::Model<f64>);
@@ -932,45 +1271,97 @@ impl Model_64 {
     fn n_free(&self) -> usize {
         self.0.get_n_free()
     }
+    #[getter]
+    fn aliases(&self) -> Vec<(String, String, String)> {
+        self.0.aliases().to_vec()
+    }
+    #[getter]
+    fn history(&self) -> Vec<String> {
+        self.0.history().iter().map(|op| op.to_string()).collect()
+    }
+    fn undo(&mut self) -> Option<String> {
+        self.0.undo().map(|op| op.to_string())
+    }
+    fn replay(&self, model: &mut Model_64) -> PyResult<()> {
+        self.0.replay(&mut model.0).map_err(PyErr::from)
+    }
+    fn clear_history(&mut self) {
+        self.0.clear_history()
+    }
     #[new]
-    fn new(amps: Vec<PyObject>) -> PyResult<Self> {
+    #[pyo3(signature = (amps, names = None))]
+    fn new(amps: Vec<PyObject>, names: Option<Vec<String>>) -> PyResult<Self> {
         Python::with_gil(|py| {
-            let rust_amps: PyResult<Vec<Box<dyn rust::amplitude::AmpLike<f64>>>> = amps
-                .into_iter()
-                .map(|obj| {
-                    if let Ok(amp) = obj.extract::<Amplitude_64>(py) {
-                        Ok(Box::new(rust::amplitude::Amplitude::<f64>::from(amp))
-                            as Box<dyn rust::amplitude::AmpLike<f64>>)
-                    } else if let Ok(amp) = obj.extract::<Real_64>(py) {
-                        Ok(Box::new(rust::amplitude::Real::<f64>::from(amp))
-                            as Box<dyn rust::amplitude::AmpLike<f64>>)
-                    } else if let Ok(amp) = obj.extract::<Imag_64>(py) {
-                        Ok(Box::new(rust::amplitude::Imag::<f64>::from(amp))
-                            as Box<dyn rust::amplitude::AmpLike<f64>>)
-                    } else if let Ok(amp) = obj.extract::<Product_64>(py) {
-                        Ok(Box::new(rust::amplitude::Product::<f64>::from(amp))
-                            as Box<dyn rust::amplitude::AmpLike<f64>>)
-                    } else if let Ok(amp) = obj.extract::<Sum_64>(py) {
-                        Ok(Box::new(rust::amplitude::Sum::<f64>::from(amp))
-                            as Box<dyn rust::amplitude::AmpLike<f64>>)
-                    } else {
-                        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                            "Unsupported AmpLike type",
-                        ))
+            let rust_amps = extract_amplikes_64(py, amps)?;
+            let model = match names {
+                Some(names) => {
+                    if names.len() != rust_amps.len() {
+                        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                            "expected one name per coherent sum",
+                        ));
                     }
-                })
-                .collect();
-            let rust_amps = rust_amps?;
-            let model = rust::amplitude::Model::new(&rust_amps);
+                    let sums: Vec<(&str, Box<dyn rust::amplitude::AmpLike<f64>>)> =
+                        names.iter().map(String::as_str).zip(rust_amps).collect();
+                    rust::amplitude::Model::new_named(&sums)
+                }
+                None => rust::amplitude::Model::new(&rust_amps),
+            };
             Ok(Self(model))
         })
     }
+    fn to_json(&self) -> PyResult<String> {
+        self.0.to_json().map_err(PyErr::from)
+    }
+    fn to_yaml(&self) -> PyResult<String> {
+        self.0.to_yaml().map_err(PyErr::from)
+    }
+    fn to_f32(&self) -> PyResult<Model_32> {
+        self.0
+            .to_f32(&node_registry())
+            .map(Model_32)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        rust::amplitude::Model::from_json(json, &node_registry())
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_yaml(yaml: &str) -> PyResult<Self> {
+        rust::amplitude::Model::from_yaml(yaml, &node_registry())
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_config(config: &str) -> PyResult<Self> {
+        rust::amplitude::Model::from_config(config, &node_registry())
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_config_file(path: &str) -> PyResult<Self> {
+        rust::amplitude::Model::from_config_file(path, &node_registry())
+            .map(Self)
+            .map_err(PyErr::from)
+    }
     fn get_amplitude(&self, amplitude_name: &str) -> PyResult<Amplitude_64> {
         self.0
             .get_amplitude(amplitude_name)
             .map(Amplitude_64::from)
             .map_err(PyErr::from)
     }
+    fn rename_amplitude(&mut self, old: &str, new: &str) -> PyResult<()> {
+        self.0.rename_amplitude(old, new).map_err(PyErr::from)
+    }
+    fn alias(&mut self, alias: &str, amplitude: &str, parameter: &str) -> PyResult<()> {
+        self.0
+            .alias(alias, amplitude, parameter)
+            .map_err(PyErr::from)
+    }
+    fn remove_alias(&mut self, alias: &str) -> PyResult<()> {
+        self.0.remove_alias(alias).map_err(PyErr::from)
+    }
     fn get_parameter(&self, amplitude_name: &str, parameter_name: &str) -> PyResult<Parameter_64> {
         self.0
             .get_parameter(amplitude_name, parameter_name)
@@ -980,6 +1371,18 @@ impl Model_64 {
     fn print_parameters(&self) {
         self.0.print_parameters()
     }
+    fn to_latex(&self) -> String {
+        self.0.to_latex()
+    }
+    fn to_dot(&self) -> String {
+        self.0.to_dot()
+    }
+    fn to_mermaid(&self) -> String {
+        self.0.to_mermaid()
+    }
+    fn optimize(&mut self) -> PyResult<()> {
+        self.0.optimize().map_err(PyErr::from)
+    }
     fn constrain(
         &mut self,
         amplitude_1: &str,
@@ -997,16 +1400,92 @@ impl Model_64 {
     fn free(&mut self, amplitude: &str, parameter: &str) -> PyResult<()> {
         self.0.free(amplitude, parameter).map_err(PyErr::from)
     }
+    fn add_gaussian_constraint(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f64,
+        sigma: f64,
+    ) -> PyResult<()> {
+        self.0
+            .add_gaussian_constraint(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_prior(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f64,
+        sigma: f64,
+    ) -> PyResult<()> {
+        self.0
+            .add_prior(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_penalty(
+        &mut self,
+        parameters: Vec<(String, String)>,
+        function: PyObject,
+    ) -> PyResult<()> {
+        let names: Vec<(&str, &str)> = parameters
+            .iter()
+            .map(|(amplitude, parameter)| (amplitude.as_str(), parameter.as_str()))
+            .collect();
+        self.0
+            .add_penalty(&names, move |values: &[f64]| {
+                Python::with_gil(|py| function.call1(py, (values.to_vec(),))?.extract::<f64>(py))
+                    .map_err(rust::errors::RustitudeError::from)
+            })
+            .map_err(PyErr::from)
+    }
     fn set_bounds(&mut self, amplitude: &str, parameter: &str, bounds: (f64, f64)) -> PyResult<()> {
         self.0
             .set_bounds(amplitude, parameter, bounds)
             .map_err(PyErr::from)
     }
+    #[pyo3(signature = (amplitude, parameter, transform, bounds = None))]
+    fn set_transform(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        transform: Option<&str>,
+        bounds: Option<(f64, f64)>,
+    ) -> PyResult<()> {
+        self.0
+            .set_transform(amplitude, parameter, parse_transform(transform, bounds)?)
+            .map_err(PyErr::from)
+    }
     fn set_initial(&mut self, amplitude: &str, parameter: &str, value: f64) -> PyResult<()> {
         self.0
             .set_initial(amplitude, parameter, value)
             .map_err(PyErr::from)
     }
+    fn matching_parameters(&self, pattern: &str) -> Vec<Parameter_64> {
+        self.0
+            .matching_parameters(pattern)
+            .into_iter()
+            .map(Parameter_64::from)
+            .collect()
+    }
+    fn fix_matching(&mut self, pattern: &str, value: f64) -> PyResult<()> {
+        self.0.fix_matching(pattern, value).map_err(PyErr::from)
+    }
+    fn free_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.free_matching(pattern).map_err(PyErr::from)
+    }
+    fn set_bounds_matching(&mut self, pattern: &str, bounds: (f64, f64)) -> PyResult<()> {
+        self.0
+            .set_bounds_matching(pattern, bounds)
+            .map_err(PyErr::from)
+    }
+    fn set_initial_matching(&mut self, pattern: &str, value: f64) -> PyResult<()> {
+        self.0
+            .set_initial_matching(pattern, value)
+            .map_err(PyErr::from)
+    }
+    fn constrain_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.constrain_matching(pattern).map_err(PyErr::from)
+    }
     fn activate(&mut self, amplitude: &str) -> PyResult<()> {
         self.0.activate(amplitude).map_err(PyErr::from)
     }
@@ -1024,6 +1503,74 @@ impl Model_64 {
     fn deactivate_all(&mut self) {
         self.0.deactivate_all()
     }
+    fn get_sum(&self, name: &str) -> PyResult<NormSqr_64> {
+        self.0
+            .get_sum(name)
+            .map(NormSqr_64::from)
+            .map_err(PyErr::from)
+    }
+    fn activate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.activate_sum(name).map_err(PyErr::from)
+    }
+    fn deactivate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.deactivate_sum(name).map_err(PyErr::from)
+    }
+    fn activation_state(&self) -> ActivationSnapshot {
+        self.0.activation_state().into()
+    }
+    fn restore(&mut self, snapshot: ActivationSnapshot) -> PyResult<()> {
+        self.0.restore(&snapshot.into()).map_err(PyErr::from)
+    }
+    fn add_incoherent_sum(&mut self, name: &str, terms: Vec<PyObject>) -> PyResult<()> {
+        Python::with_gil(|py| {
+            let terms = extract_amplikes_64(py, terms)?;
+            self.0.add_incoherent_sum(name, &terms).map_err(PyErr::from)
+        })
+    }
+    #[pyo3(signature = (term, name = None))]
+    fn add_sum(&mut self, term: PyObject, name: Option<&str>) -> PyResult<()> {
+        Python::with_gil(|py| {
+            let term = extract_amplikes_64(py, vec![term])?.remove(0);
+            self.0.add_sum(name, term).map_err(PyErr::from)
+        })
+    }
+    fn remove_sum(&mut self, index: usize) -> PyResult<NormSqr_64> {
+        self.0
+            .remove_sum(index)
+            .map(NormSqr_64::from)
+            .map_err(PyErr::from)
+    }
+}
+
+/// Converts Python [`AmpLike`](rust::amplitude::AmpLike) objects into boxed Rust ones.
+fn extract_amplikes_32(
+    py: Python<'_>,
+    amps: Vec<PyObject>,
+) -> PyResult<Vec<Box<dyn rust::amplitude::AmpLike<f32>>>> {
+    amps.into_iter()
+        .map(|obj| {
+            if let Ok(amp) = obj.extract::<Amplitude_32>(py) {
+                Ok(Box::new(rust::amplitude::Amplitude::<f32>::from(amp))
+                    as Box<dyn rust::amplitude::AmpLike<f32>>)
+            } else if let Ok(amp) = obj.extract::<Real_32>(py) {
+                Ok(Box::new(rust::amplitude::Real::<f32>::from(amp))
+                    as Box<dyn rust::amplitude::AmpLike<f32>>)
+            } else if let Ok(amp) = obj.extract::<Imag_32>(py) {
+                Ok(Box::new(rust::amplitude::Imag::<f32>::from(amp))
+                    as Box<dyn rust::amplitude::AmpLike<f32>>)
+            } else if let Ok(amp) = obj.extract::<Product_32>(py) {
+                Ok(Box::new(rust::amplitude::Product::<f32>::from(amp))
+                    as Box<dyn rust::amplitude::AmpLike<f32>>)
+            } else if let Ok(amp) = obj.extract::<Sum_32>(py) {
+                Ok(Box::new(rust::amplitude::Sum::<f32>::from(amp))
+                    as Box<dyn rust::amplitude::AmpLike<f32>>)
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                    "Unsupported AmpLike type",
+                ))
+            }
+        })
+        .collect()
 }
 
 #[pyclass]
@@ -1095,45 +1642,97 @@ impl Model_32 {
     fn n_free(&self) -> usize {
         self.0.get_n_free()
     }
+    #[getter]
+    fn aliases(&self) -> Vec<(String, String, String)> {
+        self.0.aliases().to_vec()
+    }
+    #[getter]
+    fn history(&self) -> Vec<String> {
+        self.0.history().iter().map(|op| op.to_string()).collect()
+    }
+    fn undo(&mut self) -> Option<String> {
+        self.0.undo().map(|op| op.to_string())
+    }
+    fn replay(&self, model: &mut Model_32) -> PyResult<()> {
+        self.0.replay(&mut model.0).map_err(PyErr::from)
+    }
+    fn clear_history(&mut self) {
+        self.0.clear_history()
+    }
     #[new]
-    fn new(amps: Vec<PyObject>) -> PyResult<Self> {
+    #[pyo3(signature = (amps, names = None))]
+    fn new(amps: Vec<PyObject>, names: Option<Vec<String>>) -> PyResult<Self> {
         Python::with_gil(|py| {
-            let rust_amps: PyResult<Vec<Box<dyn rust::amplitude::AmpLike<f32>>>> = amps
-                .into_iter()
-                .map(|obj| {
-                    if let Ok(amp) = obj.extract::<Amplitude_32>(py) {
-                        Ok(Box::new(rust::amplitude::Amplitude::<f32>::from(amp))
-                            as Box<dyn rust::amplitude::AmpLike<f32>>)
-                    } else if let Ok(amp) = obj.extract::<Real_32>(py) {
-                        Ok(Box::new(rust::amplitude::Real::<f32>::from(amp))
-                            as Box<dyn rust::amplitude::AmpLike<f32>>)
-                    } else if let Ok(amp) = obj.extract::<Imag_32>(py) {
-                        Ok(Box::new(rust::amplitude::Imag::<f32>::from(amp))
-                            as Box<dyn rust::amplitude::AmpLike<f32>>)
-                    } else if let Ok(amp) = obj.extract::<Product_32>(py) {
-                        Ok(Box::new(rust::amplitude::Product::<f32>::from(amp))
-                            as Box<dyn rust::amplitude::AmpLike<f32>>)
-                    } else if let Ok(amp) = obj.extract::<Sum_32>(py) {
-                        Ok(Box::new(rust::amplitude::Sum::<f32>::from(amp))
-                            as Box<dyn rust::amplitude::AmpLike<f32>>)
-                    } else {
-                        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                            "Unsupported AmpLike type",
-                        ))
+            let rust_amps = extract_amplikes_32(py, amps)?;
+            let model = match names {
+                Some(names) => {
+                    if names.len() != rust_amps.len() {
+                        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                            "expected one name per coherent sum",
+                        ));
                     }
-                })
-                .collect();
-            let rust_amps = rust_amps?;
-            let model = rust::amplitude::Model::new(&rust_amps);
+                    let sums: Vec<(&str, Box<dyn rust::amplitude::AmpLike<f32>>)> =
+                        names.iter().map(String::as_str).zip(rust_amps).collect();
+                    rust::amplitude::Model::new_named(&sums)
+                }
+                None => rust::amplitude::Model::new(&rust_amps),
+            };
             Ok(Self(model))
         })
     }
+    fn to_json(&self) -> PyResult<String> {
+        self.0.to_json().map_err(PyErr::from)
+    }
+    fn to_yaml(&self) -> PyResult<String> {
+        self.0.to_yaml().map_err(PyErr::from)
+    }
+    fn to_f64(&self) -> PyResult<Model_64> {
+        self.0
+            .to_f64(&node_registry())
+            .map(Model_64)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        rust::amplitude::Model::from_json(json, &node_registry())
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_yaml(yaml: &str) -> PyResult<Self> {
+        rust::amplitude::Model::from_yaml(yaml, &node_registry())
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_config(config: &str) -> PyResult<Self> {
+        rust::amplitude::Model::from_config(config, &node_registry())
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_config_file(path: &str) -> PyResult<Self> {
+        rust::amplitude::Model::from_config_file(path, &node_registry())
+            .map(Self)
+            .map_err(PyErr::from)
+    }
     fn get_amplitude(&self, amplitude_name: &str) -> PyResult<Amplitude_32> {
         self.0
             .get_amplitude(amplitude_name)
             .map(Amplitude_32::from)
             .map_err(PyErr::from)
     }
+    fn rename_amplitude(&mut self, old: &str, new: &str) -> PyResult<()> {
+        self.0.rename_amplitude(old, new).map_err(PyErr::from)
+    }
+    fn alias(&mut self, alias: &str, amplitude: &str, parameter: &str) -> PyResult<()> {
+        self.0
+            .alias(alias, amplitude, parameter)
+            .map_err(PyErr::from)
+    }
+    fn remove_alias(&mut self, alias: &str) -> PyResult<()> {
+        self.0.remove_alias(alias).map_err(PyErr::from)
+    }
     fn get_parameter(&self, amplitude_name: &str, parameter_name: &str) -> PyResult<Parameter_32> {
         self.0
             .get_parameter(amplitude_name, parameter_name)
@@ -1143,6 +1742,18 @@ impl Model_32 {
     fn print_parameters(&self) {
         self.0.print_parameters()
     }
+    fn to_latex(&self) -> String {
+        self.0.to_latex()
+    }
+    fn to_dot(&self) -> String {
+        self.0.to_dot()
+    }
+    fn to_mermaid(&self) -> String {
+        self.0.to_mermaid()
+    }
+    fn optimize(&mut self) -> PyResult<()> {
+        self.0.optimize().map_err(PyErr::from)
+    }
     fn constrain(
         &mut self,
         amplitude_1: &str,
@@ -1160,16 +1771,92 @@ impl Model_32 {
     fn free(&mut self, amplitude: &str, parameter: &str) -> PyResult<()> {
         self.0.free(amplitude, parameter).map_err(PyErr::from)
     }
+    fn add_gaussian_constraint(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f32,
+        sigma: f32,
+    ) -> PyResult<()> {
+        self.0
+            .add_gaussian_constraint(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_prior(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f32,
+        sigma: f32,
+    ) -> PyResult<()> {
+        self.0
+            .add_prior(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_penalty(
+        &mut self,
+        parameters: Vec<(String, String)>,
+        function: PyObject,
+    ) -> PyResult<()> {
+        let names: Vec<(&str, &str)> = parameters
+            .iter()
+            .map(|(amplitude, parameter)| (amplitude.as_str(), parameter.as_str()))
+            .collect();
+        self.0
+            .add_penalty(&names, move |values: &[f32]| {
+                Python::with_gil(|py| function.call1(py, (values.to_vec(),))?.extract::<f32>(py))
+                    .map_err(rust::errors::RustitudeError::from)
+            })
+            .map_err(PyErr::from)
+    }
     fn set_bounds(&mut self, amplitude: &str, parameter: &str, bounds: (f32, f32)) -> PyResult<()> {
         self.0
             .set_bounds(amplitude, parameter, bounds)
             .map_err(PyErr::from)
     }
+    #[pyo3(signature = (amplitude, parameter, transform, bounds = None))]
+    fn set_transform(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        transform: Option<&str>,
+        bounds: Option<(f32, f32)>,
+    ) -> PyResult<()> {
+        self.0
+            .set_transform(amplitude, parameter, parse_transform(transform, bounds)?)
+            .map_err(PyErr::from)
+    }
     fn set_initial(&mut self, amplitude: &str, parameter: &str, value: f32) -> PyResult<()> {
         self.0
             .set_initial(amplitude, parameter, value)
             .map_err(PyErr::from)
     }
+    fn matching_parameters(&self, pattern: &str) -> Vec<Parameter_32> {
+        self.0
+            .matching_parameters(pattern)
+            .into_iter()
+            .map(Parameter_32::from)
+            .collect()
+    }
+    fn fix_matching(&mut self, pattern: &str, value: f32) -> PyResult<()> {
+        self.0.fix_matching(pattern, value).map_err(PyErr::from)
+    }
+    fn free_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.free_matching(pattern).map_err(PyErr::from)
+    }
+    fn set_bounds_matching(&mut self, pattern: &str, bounds: (f32, f32)) -> PyResult<()> {
+        self.0
+            .set_bounds_matching(pattern, bounds)
+            .map_err(PyErr::from)
+    }
+    fn set_initial_matching(&mut self, pattern: &str, value: f32) -> PyResult<()> {
+        self.0
+            .set_initial_matching(pattern, value)
+            .map_err(PyErr::from)
+    }
+    fn constrain_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.constrain_matching(pattern).map_err(PyErr::from)
+    }
     fn activate(&mut self, amplitude: &str) -> PyResult<()> {
         self.0.activate(amplitude).map_err(PyErr::from)
     }
@@ -1187,6 +1874,50 @@ impl Model_32 {
     fn deactivate_all(&mut self) {
         self.0.deactivate_all()
     }
+    fn get_sum(&self, name: &str) -> PyResult<NormSqr_32> {
+        self.0
+            .get_sum(name)
+            .map(NormSqr_32::from)
+            .map_err(PyErr::from)
+    }
+    fn activate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.activate_sum(name).map_err(PyErr::from)
+    }
+    fn deactivate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.deactivate_sum(name).map_err(PyErr::from)
+    }
+    fn activation_state(&self) -> ActivationSnapshot {
+        self.0.activation_state().into()
+    }
+    fn restore(&mut self, snapshot: ActivationSnapshot) -> PyResult<()> {
+        self.0.restore(&snapshot.into()).map_err(PyErr::from)
+    }
+    fn add_incoherent_sum(&mut self, name: &str, terms: Vec<PyObject>) -> PyResult<()> {
+        Python::with_gil(|py| {
+            let terms = extract_amplikes_32(py, terms)?;
+            self.0.add_incoherent_sum(name, &terms).map_err(PyErr::from)
+        })
+    }
+    #[pyo3(signature = (term, name = None))]
+    fn add_sum(&mut self, term: PyObject, name: Option<&str>) -> PyResult<()> {
+        Python::with_gil(|py| {
+            let term = extract_amplikes_32(py, vec![term])?.remove(0);
+            self.0.add_sum(name, term).map_err(PyErr::from)
+        })
+    }
+    fn remove_sum(&mut self, index: usize) -> PyResult<NormSqr_32> {
+        self.0
+            .remove_sum(index)
+            .map(NormSqr_32::from)
+            .map_err(PyErr::from)
+    }
+}
+
+fn node_registry<F: rust::Field + nalgebra::RealField + 'static>(
+) -> rust::serialization::NodeRegistry<F> {
+    let mut registry = rust::serialization::NodeRegistry::new();
+    rustitude_gluex::register_nodes(&mut registry);
+    registry
 }
 
 #[pyfunction]
@@ -1206,6 +1937,78 @@ pub fn PiecewiseM_64(name: &str, bins: usize, range: (f64, f64)) -> Amplitude_64
     rust::amplitude::piecewise_m(name, bins, range).into()
 }
 #[pyfunction]
+pub fn PiecewiseT_64(name: &str, bins: usize, range: (f64, f64)) -> Amplitude_64 {
+    rust::amplitude::piecewise_t(name, bins, range).into()
+}
+#[pyfunction]
+pub fn PiecewiseMT_64(
+    name: &str,
+    bins: (usize, usize),
+    ranges: ((f64, f64), (f64, f64)),
+) -> Amplitude_64 {
+    rust::amplitude::piecewise_m_t(name, bins, ranges).into()
+}
+#[pyfunction]
+pub fn PiecewiseCosTheta_64(name: &str, bins: usize, range: (f64, f64)) -> Amplitude_64 {
+    rust::amplitude::piecewise_costheta(name, bins, range).into()
+}
+#[pyfunction]
+pub fn PiecewiseBeamEnergy_64(name: &str, bins: usize, range: (f64, f64)) -> Amplitude_64 {
+    rust::amplitude::piecewise_beam_energy(name, bins, range).into()
+}
+#[pyfunction]
+pub fn Piecewise_64(
+    name: &str,
+    bins: usize,
+    range: (f64, f64),
+    variable: Py<PyAny>,
+) -> Amplitude_64 {
+    Amplitude_64(rust::amplitude::Amplitude::new(
+        name,
+        PyPiecewise_64::new(bins, range, variable),
+    ))
+}
+#[pyfunction]
+#[pyo3(signature = (name, order, range = None))]
+pub fn PolynomialM_64(
+    name: &str,
+    order: usize,
+    range: Option<(f64, f64)>,
+) -> PyResult<Amplitude_64> {
+    let basis = range.map_or(rust::amplitude::PolynomialBasis::Power, |(a, b)| {
+        rust::amplitude::PolynomialBasis::Chebyshev(a, b)
+    });
+    Ok(rust::amplitude::polynomial_m(name, order, basis)?.into())
+}
+#[pyfunction]
+#[pyo3(signature = (name, knots, kind = "cubic", form = "cartesian"))]
+pub fn SplineM_64(name: &str, knots: Vec<f64>, kind: &str, form: &str) -> PyResult<Amplitude_64> {
+    let (kind, form) = parse_spline(kind, form)?;
+    Ok(rust::amplitude::spline_m(name, knots, kind, form)?.into())
+}
+#[pyfunction]
+pub fn TemplateHistM_64(name: &str, edges: Vec<f64>, contents: Vec<f64>) -> PyResult<Amplitude_64> {
+    Ok(rust::amplitude::template_hist_m(name, edges, contents)?.into())
+}
+#[pyfunction]
+#[pyo3(signature = (name, points, bandwidth = None, weights = None))]
+pub fn TemplateKDEM_64(
+    name: &str,
+    points: Vec<f64>,
+    bandwidth: Option<f64>,
+    weights: Option<Vec<f64>>,
+) -> PyResult<Amplitude_64> {
+    let bandwidth = match bandwidth {
+        Some(bandwidth) => bandwidth,
+        None => {
+            let samples: Vec<Vec<f64>> = points.iter().map(|p| vec![*p]).collect();
+            let sample_weights = weights.clone().unwrap_or_else(|| vec![1.0; points.len()]);
+            rust::kde::Bandwidth::Scott.select(&samples, &sample_weights)?[0]
+        }
+    };
+    Ok(rust::amplitude::template_kde_m(name, points, weights, bandwidth)?.into())
+}
+#[pyfunction]
 fn Scalar_32(name: &str) -> Amplitude_32 {
     rust::amplitude::scalar(name).into()
 }
@@ -1221,6 +2024,78 @@ fn PCScalar_32(name: &str) -> Amplitude_32 {
 pub fn PiecewiseM_32(name: &str, bins: usize, range: (f32, f32)) -> Amplitude_32 {
     rust::amplitude::piecewise_m(name, bins, range).into()
 }
+#[pyfunction]
+pub fn PiecewiseT_32(name: &str, bins: usize, range: (f32, f32)) -> Amplitude_32 {
+    rust::amplitude::piecewise_t(name, bins, range).into()
+}
+#[pyfunction]
+pub fn PiecewiseMT_32(
+    name: &str,
+    bins: (usize, usize),
+    ranges: ((f32, f32), (f32, f32)),
+) -> Amplitude_32 {
+    rust::amplitude::piecewise_m_t(name, bins, ranges).into()
+}
+#[pyfunction]
+pub fn PiecewiseCosTheta_32(name: &str, bins: usize, range: (f32, f32)) -> Amplitude_32 {
+    rust::amplitude::piecewise_costheta(name, bins, range).into()
+}
+#[pyfunction]
+pub fn PiecewiseBeamEnergy_32(name: &str, bins: usize, range: (f32, f32)) -> Amplitude_32 {
+    rust::amplitude::piecewise_beam_energy(name, bins, range).into()
+}
+#[pyfunction]
+pub fn Piecewise_32(
+    name: &str,
+    bins: usize,
+    range: (f32, f32),
+    variable: Py<PyAny>,
+) -> Amplitude_32 {
+    Amplitude_32(rust::amplitude::Amplitude::new(
+        name,
+        PyPiecewise_32::new(bins, range, variable),
+    ))
+}
+#[pyfunction]
+#[pyo3(signature = (name, order, range = None))]
+pub fn PolynomialM_32(
+    name: &str,
+    order: usize,
+    range: Option<(f32, f32)>,
+) -> PyResult<Amplitude_32> {
+    let basis = range.map_or(rust::amplitude::PolynomialBasis::Power, |(a, b)| {
+        rust::amplitude::PolynomialBasis::Chebyshev(a, b)
+    });
+    Ok(rust::amplitude::polynomial_m(name, order, basis)?.into())
+}
+#[pyfunction]
+#[pyo3(signature = (name, knots, kind = "cubic", form = "cartesian"))]
+pub fn SplineM_32(name: &str, knots: Vec<f32>, kind: &str, form: &str) -> PyResult<Amplitude_32> {
+    let (kind, form) = parse_spline(kind, form)?;
+    Ok(rust::amplitude::spline_m(name, knots, kind, form)?.into())
+}
+#[pyfunction]
+pub fn TemplateHistM_32(name: &str, edges: Vec<f32>, contents: Vec<f32>) -> PyResult<Amplitude_32> {
+    Ok(rust::amplitude::template_hist_m(name, edges, contents)?.into())
+}
+#[pyfunction]
+#[pyo3(signature = (name, points, bandwidth = None, weights = None))]
+pub fn TemplateKDEM_32(
+    name: &str,
+    points: Vec<f32>,
+    bandwidth: Option<f32>,
+    weights: Option<Vec<f32>>,
+) -> PyResult<Amplitude_32> {
+    let bandwidth = match bandwidth {
+        Some(bandwidth) => bandwidth,
+        None => {
+            let samples: Vec<Vec<f32>> = points.iter().map(|p| vec![*p]).collect();
+            let sample_weights = weights.clone().unwrap_or_else(|| vec![1.0; points.len()]);
+            rust::kde::Bandwidth::Scott.select(&samples, &sample_weights)?[0]
+        }
+    };
+    Ok(rust::amplitude::template_kde_m(name, points, weights, bandwidth)?.into())
+}
 
 pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Amplitude_64>()?;
@@ -1237,6 +2112,7 @@ pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Sum_32>()?;
     m.add_class::<NormSqr_64>()?;
     m.add_class::<NormSqr_32>()?;
+    m.add_class::<ActivationSnapshot>()?;
     m.add_class::<Model_64>()?;
     m.add_class::<Model_32>()?;
     m.add_class::<PyNode_64>()?;
@@ -1249,5 +2125,23 @@ pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(PCScalar_32, m)?)?;
     m.add_function(wrap_pyfunction!(PiecewiseM_64, m)?)?;
     m.add_function(wrap_pyfunction!(PiecewiseM_32, m)?)?;
+    m.add_function(wrap_pyfunction!(PiecewiseT_64, m)?)?;
+    m.add_function(wrap_pyfunction!(PiecewiseMT_64, m)?)?;
+    m.add_function(wrap_pyfunction!(PiecewiseT_32, m)?)?;
+    m.add_function(wrap_pyfunction!(PiecewiseMT_32, m)?)?;
+    m.add_function(wrap_pyfunction!(PiecewiseCosTheta_64, m)?)?;
+    m.add_function(wrap_pyfunction!(PiecewiseCosTheta_32, m)?)?;
+    m.add_function(wrap_pyfunction!(PiecewiseBeamEnergy_64, m)?)?;
+    m.add_function(wrap_pyfunction!(PiecewiseBeamEnergy_32, m)?)?;
+    m.add_function(wrap_pyfunction!(Piecewise_64, m)?)?;
+    m.add_function(wrap_pyfunction!(Piecewise_32, m)?)?;
+    m.add_function(wrap_pyfunction!(PolynomialM_64, m)?)?;
+    m.add_function(wrap_pyfunction!(PolynomialM_32, m)?)?;
+    m.add_function(wrap_pyfunction!(SplineM_64, m)?)?;
+    m.add_function(wrap_pyfunction!(SplineM_32, m)?)?;
+    m.add_function(wrap_pyfunction!(TemplateHistM_64, m)?)?;
+    m.add_function(wrap_pyfunction!(TemplateHistM_32, m)?)?;
+    m.add_function(wrap_pyfunction!(TemplateKDEM_64, m)?)?;
+    m.add_function(wrap_pyfunction!(TemplateKDEM_32, m)?)?;
     Ok(())
 }
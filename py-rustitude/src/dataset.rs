@@ -1,12 +1,38 @@
 use crate::four_momentum::{FourMomentum_32, FourMomentum_64};
 use crate::impl_convert;
-use nalgebra::Vector3;
+use nalgebra::{Matrix4, Vector3};
 use pyo3::prelude::*;
 use rayon::prelude::*;
 use rustitude_core::dataset as rust;
 use rustitude_core::four_momentum as rust_fm;
 use std::collections::HashMap;
 
+fn parse_layout(layout: &str) -> PyResult<rust::DatasetLayout> {
+    match layout {
+        "events" => Ok(rust::DatasetLayout::Events),
+        "columns" => Ok(rust::DatasetLayout::Columns),
+        layout => Err(PyErr::from(
+            rustitude_core::errors::RustitudeError::InvalidParameterValue(format!(
+                "expected \"events\" or \"columns\", got \"{layout}\""
+            )),
+        )),
+    }
+}
+
+fn polarization_table<F: rustitude_core::Field>(
+    table: Vec<(u32, u32, F, F)>,
+) -> PyResult<rust::PolarizationTable<F>> {
+    rust::PolarizationTable::new(
+        table
+            .into_iter()
+            .map(|(first_run, last_run, angle, magnitude)| {
+                rust::RunPeriod::new(first_run, last_run, angle, magnitude)
+            })
+            .collect(),
+    )
+    .map_err(PyErr::from)
+}
+
 #[pyclass]
 #[derive(Debug, Default, Clone)]
 pub struct Event_64(rust::Event<f64>);
@@ -43,6 +69,10 @@ impl Event_64 {
     fn eps(&self) -> [f64; 3] {
         [self.0.eps[0], self.0.eps[1], self.0.eps[2]]
     }
+    #[getter]
+    fn circular_polarization(&self) -> f64 {
+        self.0.circular_polarization
+    }
     fn __str__(&self) -> String {
         format!("{}", self.0)
     }
@@ -87,6 +117,10 @@ impl Event_32 {
     fn eps(&self) -> [f32; 3] {
         [self.0.eps[0], self.0.eps[1], self.0.eps[2]]
     }
+    #[getter]
+    fn circular_polarization(&self) -> f32 {
+        self.0.circular_polarization
+    }
     fn __str__(&self) -> String {
         format!("{}", self.0)
     }
@@ -121,13 +155,22 @@ impl Dataset_64 {
     }
 
     fn __getitem__(&self, idx: isize) -> PyResult<Py<Event_64>> {
-        Ok(Python::with_gil(|py| Py::new(py, self.events()[idx as usize].clone())).unwrap())
+        let event = self.0.events[idx as usize].clone();
+        Ok(Python::with_gil(|py| Py::new(py, Event_64::from(event))).unwrap())
     }
 
     fn __add__(&self, other: Dataset_64) -> Dataset_64 {
         (self.0.clone() + other.0).into()
     }
 
+    fn append(&mut self, other: Dataset_64) {
+        self.0.append(other.0);
+    }
+
+    fn to_f32(&self) -> Dataset_32 {
+        self.0.to_f32().into()
+    }
+
     #[pyo3(signature = (range, bins, daughter_indices=None))]
     fn split_m(
         &self,
@@ -142,11 +185,117 @@ impl Dataset_64 {
         self.0.get_bootstrap_indices(seed)
     }
 
+    fn standard_kinematics(&self, name: &str) -> PyResult<Vec<f64>> {
+        self.0
+            .standard_kinematics(name)
+            .map(|array| array.to_vec())
+            .map_err(PyErr::from)
+    }
+
+    fn kinematics_keys(&self) -> Vec<String> {
+        self.0.kinematics_keys()
+    }
+
+    fn clear_kinematics(&self) {
+        self.0.clear_kinematics();
+    }
+
+    #[getter]
+    fn layout(&self) -> &'static str {
+        match self.0.layout() {
+            rust::DatasetLayout::Events => "events",
+            rust::DatasetLayout::Columns => "columns",
+        }
+    }
+
+    fn with_layout(&self, layout: &str) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_layout(parse_layout(layout)?)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
+    fn with_weight_errors(&self, errors: Vec<f64>) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_weight_covariance(rust::WeightCovariance::from_errors(&errors))
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
+    fn boost_to_com(&self) -> PyResult<Self> {
+        self.0.clone().boost_to_com().map(Self).map_err(PyErr::from)
+    }
+
+    fn rotate_euler(&self, alpha: f64, beta: f64, gamma: f64) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_transforms(&[rust::DatasetTransform::Rotate(
+                rust_fm::euler_rotation_matrix(alpha, beta, gamma),
+            )])
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
+    fn reorder_daughters(&self, order: Vec<usize>) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_transforms(&[rust::DatasetTransform::ReorderDaughters(order)])
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
+    #[pyo3(signature = (covariance, seed=0))]
+    fn smear(&self, covariance: [[f64; 4]; 4], seed: u64) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_transforms(&[rust::DatasetTransform::smear(
+                Matrix4::from_fn(|i, j| covariance[i][j]),
+                seed,
+            )?])
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn with_weight_covariance(&self, blocks: Vec<(Vec<usize>, Vec<Vec<f64>>)>) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_weight_covariance(rust::WeightCovariance::from_blocks(blocks)?)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
     #[staticmethod]
     fn from_events(events: Vec<Event_64>) -> Self {
         rust::Dataset::new(events.into_iter().map(rust::Event::from).collect()).into()
     }
 
+    #[staticmethod]
+    #[pyo3(signature = (beam_energy, target_mass, recoil_mass, daughter_masses, n_events, *, offset = 0, eps = None))]
+    fn from_phase_space(
+        beam_energy: f64,
+        target_mass: f64,
+        recoil_mass: f64,
+        daughter_masses: Vec<f64>,
+        n_events: usize,
+        offset: usize,
+        eps: Option<[f64; 3]>,
+    ) -> PyResult<Self> {
+        let phase_space = rustitude_core::phase_space::PhaseSpace::new(
+            beam_energy,
+            target_mass,
+            recoil_mass,
+            daughter_masses,
+        )?
+        .with_polarization(eps.map_or_else(Vector3::zeros, Vector3::from));
+        phase_space
+            .generate(n_events, offset)
+            .map(Self::from)
+            .map_err(PyErr::from)
+    }
+
     #[staticmethod]
     fn from_dict(py: Python, data: HashMap<String, PyObject>) -> PyResult<Self> {
         let e_beam_vec: Vec<f64> = data["E_Beam"].extract(py)?;
@@ -221,6 +370,7 @@ impl Dataset_64 {
                                 })
                                 .collect(),
                             eps,
+                            circular_polarization: 0.0,
                         }
                     },
                 )
@@ -248,7 +398,13 @@ impl Dataset_64 {
     }
     #[staticmethod]
     fn from_parquet_unpolarized(path: &str) -> PyResult<Self> {
-        rust::Dataset::from_parquet(path, rust::ReadMethod::EPS(0.0, 0.0, 0.0))
+        rust::Dataset::from_parquet(path, rust::ReadMethod::Unpolarized)
+            .map(Dataset_64::from)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_parquet_circular(path: &str, degree: f64) -> PyResult<Self> {
+        rust::Dataset::from_parquet(path, rust::ReadMethod::Circular(degree))
             .map(Dataset_64::from)
             .map_err(PyErr::from)
     }
@@ -258,6 +414,24 @@ impl Dataset_64 {
             .map(Dataset_64::from)
             .map_err(PyErr::from)
     }
+    #[staticmethod]
+    fn from_parquet_with_polarization_table(
+        path: &str,
+        table: Vec<(u32, u32, f64, f64)>,
+    ) -> PyResult<Self> {
+        rust::Dataset::from_parquet_with_polarization_table(path, &polarization_table(table)?)
+            .map(Dataset_64::from)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_root_with_polarization_table(
+        path: &str,
+        table: Vec<(u32, u32, f64, f64)>,
+    ) -> PyResult<Self> {
+        rust::Dataset::from_root_with_polarization_table(path, &polarization_table(table)?)
+            .map(Dataset_64::from)
+            .map_err(PyErr::from)
+    }
 }
 
 #[pyclass]
@@ -286,13 +460,22 @@ impl Dataset_32 {
     }
 
     fn __getitem__(&self, idx: isize) -> PyResult<Py<Event_32>> {
-        Ok(Python::with_gil(|py| Py::new(py, self.events()[idx as usize].clone())).unwrap())
+        let event = self.0.events[idx as usize].clone();
+        Ok(Python::with_gil(|py| Py::new(py, Event_32::from(event))).unwrap())
     }
 
     fn __add__(&self, other: Dataset_32) -> Dataset_32 {
         (self.0.clone() + other.0).into()
     }
 
+    fn append(&mut self, other: Dataset_32) {
+        self.0.append(other.0);
+    }
+
+    fn to_f64(&self) -> Dataset_64 {
+        self.0.to_f64().into()
+    }
+
     #[pyo3(signature = (range, bins, daughter_indices=None))]
     fn split_m(
         &self,
@@ -307,11 +490,117 @@ impl Dataset_32 {
         self.0.get_bootstrap_indices(seed)
     }
 
+    fn standard_kinematics(&self, name: &str) -> PyResult<Vec<f32>> {
+        self.0
+            .standard_kinematics(name)
+            .map(|array| array.to_vec())
+            .map_err(PyErr::from)
+    }
+
+    fn kinematics_keys(&self) -> Vec<String> {
+        self.0.kinematics_keys()
+    }
+
+    fn clear_kinematics(&self) {
+        self.0.clear_kinematics();
+    }
+
+    #[getter]
+    fn layout(&self) -> &'static str {
+        match self.0.layout() {
+            rust::DatasetLayout::Events => "events",
+            rust::DatasetLayout::Columns => "columns",
+        }
+    }
+
+    fn with_layout(&self, layout: &str) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_layout(parse_layout(layout)?)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
+    fn with_weight_errors(&self, errors: Vec<f32>) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_weight_covariance(rust::WeightCovariance::from_errors(&errors))
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
+    fn boost_to_com(&self) -> PyResult<Self> {
+        self.0.clone().boost_to_com().map(Self).map_err(PyErr::from)
+    }
+
+    fn rotate_euler(&self, alpha: f32, beta: f32, gamma: f32) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_transforms(&[rust::DatasetTransform::Rotate(
+                rust_fm::euler_rotation_matrix(alpha, beta, gamma),
+            )])
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
+    fn reorder_daughters(&self, order: Vec<usize>) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_transforms(&[rust::DatasetTransform::ReorderDaughters(order)])
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
+    #[pyo3(signature = (covariance, seed=0))]
+    fn smear(&self, covariance: [[f32; 4]; 4], seed: u64) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_transforms(&[rust::DatasetTransform::smear(
+                Matrix4::from_fn(|i, j| covariance[i][j]),
+                seed,
+            )?])
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn with_weight_covariance(&self, blocks: Vec<(Vec<usize>, Vec<Vec<f32>>)>) -> PyResult<Self> {
+        self.0
+            .clone()
+            .with_weight_covariance(rust::WeightCovariance::from_blocks(blocks)?)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+
     #[staticmethod]
     fn from_events(events: Vec<Event_32>) -> Self {
         rust::Dataset::new(events.into_iter().map(rust::Event::from).collect()).into()
     }
 
+    #[staticmethod]
+    #[pyo3(signature = (beam_energy, target_mass, recoil_mass, daughter_masses, n_events, *, offset = 0, eps = None))]
+    fn from_phase_space(
+        beam_energy: f32,
+        target_mass: f32,
+        recoil_mass: f32,
+        daughter_masses: Vec<f32>,
+        n_events: usize,
+        offset: usize,
+        eps: Option<[f32; 3]>,
+    ) -> PyResult<Self> {
+        let phase_space = rustitude_core::phase_space::PhaseSpace::new(
+            beam_energy,
+            target_mass,
+            recoil_mass,
+            daughter_masses,
+        )?
+        .with_polarization(eps.map_or_else(Vector3::zeros, Vector3::from));
+        phase_space
+            .generate(n_events, offset)
+            .map(Self::from)
+            .map_err(PyErr::from)
+    }
+
     #[staticmethod]
     fn from_dict(py: Python, data: HashMap<String, PyObject>) -> PyResult<Self> {
         let e_beam_vec: Vec<f32> = data["E_Beam"].extract(py)?;
@@ -386,6 +675,7 @@ impl Dataset_32 {
                                 })
                                 .collect(),
                             eps,
+                            circular_polarization: 0.0,
                         }
                     },
                 )
@@ -412,7 +702,13 @@ impl Dataset_32 {
     }
     #[staticmethod]
     fn from_parquet_unpolarized(path: &str) -> PyResult<Self> {
-        rust::Dataset::from_parquet(path, rust::ReadMethod::EPS(0.0, 0.0, 0.0))
+        rust::Dataset::from_parquet(path, rust::ReadMethod::Unpolarized)
+            .map(Dataset_32::from)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_parquet_circular(path: &str, degree: f32) -> PyResult<Self> {
+        rust::Dataset::from_parquet(path, rust::ReadMethod::Circular(degree))
             .map(Dataset_32::from)
             .map_err(PyErr::from)
     }
@@ -422,6 +718,24 @@ impl Dataset_32 {
             .map(Dataset_32::from)
             .map_err(PyErr::from)
     }
+    #[staticmethod]
+    fn from_parquet_with_polarization_table(
+        path: &str,
+        table: Vec<(u32, u32, f32, f32)>,
+    ) -> PyResult<Self> {
+        rust::Dataset::from_parquet_with_polarization_table(path, &polarization_table(table)?)
+            .map(Dataset_32::from)
+            .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_root_with_polarization_table(
+        path: &str,
+        table: Vec<(u32, u32, f32, f32)>,
+    ) -> PyResult<Self> {
+        rust::Dataset::from_root_with_polarization_table(path, &polarization_table(table)?)
+            .map(Dataset_32::from)
+            .map_err(PyErr::from)
+    }
 }
 
 pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
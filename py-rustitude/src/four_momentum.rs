@@ -1,6 +1,7 @@
 use crate::impl_convert;
 use pyo3::prelude::*;
 use rustitude_core::four_momentum as rust;
+use rustitude_core::prelude::Vector3;
 
 #[pyclass]
 #[derive(Debug, Clone, PartialEq, Copy, Default)]
@@ -47,6 +48,26 @@ impl FourMomentum_64 {
     fn boost_along(&self, other: Self) -> Self {
         self.0.boost_along(&other.into()).into()
     }
+    fn boost_to_rest_frame_of(&self, other: Self) -> Self {
+        self.0.boost_to_rest_frame_of(&other.into()).into()
+    }
+    fn boost(&self, beta: [f64; 3]) -> Self {
+        self.0.boost(&Vector3::from(beta)).into()
+    }
+    fn rotate_axis_angle(&self, axis: [f64; 3], angle: f64) -> Self {
+        self.0.rotate_axis_angle(&Vector3::from(axis), angle).into()
+    }
+    fn rotate_euler(&self, alpha: f64, beta: f64, gamma: f64) -> Self {
+        self.0.rotate_euler(alpha, beta, gamma).into()
+    }
+    fn rotate_frame_axis_angle(&self, axis: [f64; 3], angle: f64) -> Self {
+        self.0
+            .rotate_frame_axis_angle(&Vector3::from(axis), angle)
+            .into()
+    }
+    fn rotate_frame_euler(&self, alpha: f64, beta: f64, gamma: f64) -> Self {
+        self.0.rotate_frame_euler(alpha, beta, gamma).into()
+    }
     fn __add__(&self, other: Self) -> Self {
         (self.0 + other.0).into()
     }
@@ -100,6 +121,26 @@ impl FourMomentum_32 {
     fn boost_along(&self, other: Self) -> Self {
         self.0.boost_along(&other.into()).into()
     }
+    fn boost_to_rest_frame_of(&self, other: Self) -> Self {
+        self.0.boost_to_rest_frame_of(&other.into()).into()
+    }
+    fn boost(&self, beta: [f32; 3]) -> Self {
+        self.0.boost(&Vector3::from(beta)).into()
+    }
+    fn rotate_axis_angle(&self, axis: [f32; 3], angle: f32) -> Self {
+        self.0.rotate_axis_angle(&Vector3::from(axis), angle).into()
+    }
+    fn rotate_euler(&self, alpha: f32, beta: f32, gamma: f32) -> Self {
+        self.0.rotate_euler(alpha, beta, gamma).into()
+    }
+    fn rotate_frame_axis_angle(&self, axis: [f32; 3], angle: f32) -> Self {
+        self.0
+            .rotate_frame_axis_angle(&Vector3::from(axis), angle)
+            .into()
+    }
+    fn rotate_frame_euler(&self, alpha: f32, beta: f32, gamma: f32) -> Self {
+        self.0.rotate_frame_euler(alpha, beta, gamma).into()
+    }
     fn __add__(&self, other: Self) -> Self {
         (self.0 + other.0).into()
     }
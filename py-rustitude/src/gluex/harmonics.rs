@@ -137,6 +137,87 @@ fn Zlm_32(
     ))
 }
 
+#[pyfunction]
+#[pyo3(signature = (name, l, m, reflectivity="+", decay="[0, 1]", frame="helicity"))]
+fn PolarizedZlm(
+    name: &str,
+    l: usize,
+    m: isize,
+    reflectivity: &str,
+    decay: &str,
+    frame: &str,
+) -> PyResult<Amplitude_64> {
+    Ok(Amplitude_64::new(
+        name,
+        rust::PolarizedZlm::new(
+            Wave::new(l, m),
+            Sign::from_str(reflectivity)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            Decay::from_str(decay)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            Frame::from_str(frame)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+        ),
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (name, l, m, reflectivity="+", decay="[0, 1]", frame="helicity"))]
+fn PolarizedZlm_64(
+    name: &str,
+    l: usize,
+    m: isize,
+    reflectivity: &str,
+    decay: &str,
+    frame: &str,
+) -> PyResult<Amplitude_64> {
+    Ok(Amplitude_64::new(
+        name,
+        rust::PolarizedZlm::new(
+            Wave::new(l, m),
+            Sign::from_str(reflectivity)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            Decay::from_str(decay)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            Frame::from_str(frame)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+        ),
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (name, l, m, reflectivity="+", decay="[0, 1]", frame="helicity"))]
+fn PolarizedZlm_32(
+    name: &str,
+    l: usize,
+    m: isize,
+    reflectivity: &str,
+    decay: &str,
+    frame: &str,
+) -> PyResult<Amplitude_32> {
+    Ok(Amplitude_32::new(
+        name,
+        rust::PolarizedZlm::new(
+            Wave::new(l, m),
+            Sign::from_str(reflectivity)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            Decay::from_str(decay)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            Frame::from_str(frame)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+        ),
+    ))
+}
+
 #[pyfunction]
 #[pyo3(signature = (name, reflectivity="+", decay="[0, 1]", frame="helicity"))]
 fn OnePS(name: &str, reflectivity: &str, decay: &str, frame: &str) -> PyResult<Amplitude_64> {
@@ -282,6 +363,9 @@ pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(Zlm, m)?)?;
     m.add_function(wrap_pyfunction!(Zlm_64, m)?)?;
     m.add_function(wrap_pyfunction!(Zlm_32, m)?)?;
+    m.add_function(wrap_pyfunction!(PolarizedZlm, m)?)?;
+    m.add_function(wrap_pyfunction!(PolarizedZlm_64, m)?)?;
+    m.add_function(wrap_pyfunction!(PolarizedZlm_32, m)?)?;
     m.add_function(wrap_pyfunction!(OnePS, m)?)?;
     m.add_function(wrap_pyfunction!(OnePS_64, m)?)?;
     m.add_function(wrap_pyfunction!(OnePS_32, m)?)?;
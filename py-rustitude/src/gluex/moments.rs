@@ -0,0 +1,176 @@
+use std::str::FromStr;
+
+use pyo3::prelude::*;
+use rustitude::prelude::{Complex, RustitudeError};
+use rustitude_gluex::{
+    moments as rust,
+    utils::{Decay, Frame, Wave},
+};
+
+use crate::{
+    amplitude::{Amplitude_32, Amplitude_64},
+    dataset::Dataset_64,
+    manager::ExtendedLogLikelihood_64,
+};
+
+fn to_waves(waves: Vec<(usize, isize, Complex<f64>)>) -> Vec<(Wave, Complex<f64>)> {
+    waves
+        .into_iter()
+        .map(|(l, m, t)| (Wave::new(l, m), t))
+        .collect()
+}
+
+fn from_moments(moments: Vec<rust::Moment<f64>>) -> Vec<(usize, isize, Complex<f64>)> {
+    moments
+        .into_iter()
+        .map(|moment| (moment.l, moment.m, moment.value))
+        .collect()
+}
+
+#[pyfunction]
+fn project_moments(
+    waves: Vec<(usize, isize, Complex<f64>)>,
+    l_max: usize,
+) -> Vec<(usize, isize, Complex<f64>)> {
+    from_moments(rust::project_moments(&to_waves(waves), l_max))
+}
+
+#[pyfunction]
+fn project_incoherent_moments(
+    sums: Vec<Vec<(usize, isize, Complex<f64>)>>,
+    l_max: usize,
+) -> Vec<(usize, isize, Complex<f64>)> {
+    let sums: Vec<Vec<(Wave, Complex<f64>)>> = sums.into_iter().map(to_waves).collect();
+    from_moments(rust::project_incoherent_moments(&sums, l_max))
+}
+
+fn parse_angles(decay: &str, frame: &str) -> PyResult<(Decay, Frame)> {
+    Ok((
+        Decay::from_str(decay)
+            .map_err(RustitudeError::from)
+            .map_err(PyErr::from)?,
+        Frame::from_str(frame)
+            .map_err(RustitudeError::from)
+            .map_err(PyErr::from)?,
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (dataset, l_max, decay="[0, 1]", frame="helicity", weights=None))]
+fn dataset_moments(
+    dataset: Dataset_64,
+    l_max: usize,
+    decay: &str,
+    frame: &str,
+    weights: Option<Vec<f64>>,
+) -> PyResult<Vec<(usize, isize, Complex<f64>)>> {
+    let (decay, frame) = parse_angles(decay, frame)?;
+    Ok(from_moments(rust::dataset_moments(
+        &dataset.into(),
+        weights.as_deref(),
+        l_max,
+        decay,
+        frame,
+    )?))
+}
+
+#[pyfunction]
+#[pyo3(signature = (ell, parameters, dataset_mc, l_max, decay="[0, 1]", frame="helicity"))]
+fn predicted_moments(
+    ell: &ExtendedLogLikelihood_64,
+    parameters: Vec<f64>,
+    dataset_mc: Dataset_64,
+    l_max: usize,
+    decay: &str,
+    frame: &str,
+) -> PyResult<Vec<(usize, isize, Complex<f64>)>> {
+    let (decay, frame) = parse_angles(decay, frame)?;
+    Ok(from_moments(rust::predicted_moments(
+        &ell.0,
+        &parameters,
+        &dataset_mc.into(),
+        l_max,
+        decay,
+        frame,
+    )?))
+}
+
+#[pyfunction]
+#[pyo3(signature = (name, l_max, polarized=true, positivity="absolute", decay="[0, 1]", frame="helicity"))]
+fn MomentIntensity(
+    name: &str,
+    l_max: usize,
+    polarized: bool,
+    positivity: &str,
+    decay: &str,
+    frame: &str,
+) -> PyResult<Amplitude_64> {
+    let (decay, frame) = parse_angles(decay, frame)?;
+    Ok(Amplitude_64::new(
+        name,
+        rust::MomentIntensity::<f64>::new(
+            l_max,
+            polarized,
+            rust::Positivity::from_str(positivity).map_err(PyErr::from)?,
+            decay,
+            frame,
+        ),
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (name, l_max, polarized=true, positivity="absolute", decay="[0, 1]", frame="helicity"))]
+fn MomentIntensity_64(
+    name: &str,
+    l_max: usize,
+    polarized: bool,
+    positivity: &str,
+    decay: &str,
+    frame: &str,
+) -> PyResult<Amplitude_64> {
+    let (decay, frame) = parse_angles(decay, frame)?;
+    Ok(Amplitude_64::new(
+        name,
+        rust::MomentIntensity::<f64>::new(
+            l_max,
+            polarized,
+            rust::Positivity::from_str(positivity).map_err(PyErr::from)?,
+            decay,
+            frame,
+        ),
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (name, l_max, polarized=true, positivity="absolute", decay="[0, 1]", frame="helicity"))]
+fn MomentIntensity_32(
+    name: &str,
+    l_max: usize,
+    polarized: bool,
+    positivity: &str,
+    decay: &str,
+    frame: &str,
+) -> PyResult<Amplitude_32> {
+    let (decay, frame) = parse_angles(decay, frame)?;
+    Ok(Amplitude_32::new(
+        name,
+        rust::MomentIntensity::<f32>::new(
+            l_max,
+            polarized,
+            rust::Positivity::from_str(positivity).map_err(PyErr::from)?,
+            decay,
+            frame,
+        ),
+    ))
+}
+
+pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(project_moments, m)?)?;
+    m.add_function(wrap_pyfunction!(project_incoherent_moments, m)?)?;
+    m.add_function(wrap_pyfunction!(dataset_moments, m)?)?;
+    m.add_function(wrap_pyfunction!(predicted_moments, m)?)?;
+    m.add_function(wrap_pyfunction!(MomentIntensity, m)?)?;
+    m.add_function(wrap_pyfunction!(MomentIntensity_64, m)?)?;
+    m.add_function(wrap_pyfunction!(MomentIntensity_32, m)?)?;
+    Ok(())
+}
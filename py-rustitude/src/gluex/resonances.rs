@@ -2,46 +2,79 @@ use std::str::FromStr;
 
 use crate::amplitude::{Amplitude_32, Amplitude_64};
 use pyo3::prelude::*;
-use rustitude::prelude::RustitudeError;
+use rustitude::prelude::{Field, RustitudeError};
 use rustitude_gluex::{resonances as rust, utils::Decay};
 
+fn breit_wigner<F: Field>(
+    l: usize,
+    decay: &str,
+    barrier_factor: &str,
+    width_channels: Option<Vec<(F, F, F, usize)>>,
+    mass_convention: &str,
+) -> PyResult<rust::BreitWigner<F>> {
+    let width_channels: Vec<rust::WidthChannel<F>> = width_channels
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(fraction, m1, m2, l)| rust::WidthChannel {
+            fraction,
+            m1,
+            m2,
+            l,
+        })
+        .collect();
+    Ok(rust::BreitWigner::new(
+        l,
+        Decay::from_str(decay)
+            .map_err(RustitudeError::from)
+            .map_err(PyErr::from)?,
+    )
+    .with_barrier_factor(rust::BarrierFactor::from_str(barrier_factor).map_err(PyErr::from)?)
+    .with_width_channels(&width_channels)
+    .with_mass_convention(rust::MassConvention::from_str(mass_convention).map_err(PyErr::from)?))
+}
 #[pyfunction]
-#[pyo3(signature = (name, l, decay="[0, 1]"))]
-fn BreitWigner(name: &str, l: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, l, decay="[0, 1]", barrier_factor="blatt-weisskopf", width_channels=None, mass_convention="nominal"))]
+fn BreitWigner(
+    name: &str,
+    l: usize,
+    decay: &str,
+    barrier_factor: &str,
+    width_channels: Option<Vec<(f64, f64, f64, usize)>>,
+    mass_convention: &str,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
-        rust::BreitWigner::new(
-            l,
-            Decay::from_str(decay)
-                .map_err(RustitudeError::from)
-                .map_err(PyErr::from)?,
-        ),
+        breit_wigner(l, decay, barrier_factor, width_channels, mass_convention)?,
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, l, decay="[0, 1]"))]
-fn BreitWigner_64(name: &str, l: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, l, decay="[0, 1]", barrier_factor="blatt-weisskopf", width_channels=None, mass_convention="nominal"))]
+fn BreitWigner_64(
+    name: &str,
+    l: usize,
+    decay: &str,
+    barrier_factor: &str,
+    width_channels: Option<Vec<(f64, f64, f64, usize)>>,
+    mass_convention: &str,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
-        rust::BreitWigner::new(
-            l,
-            Decay::from_str(decay)
-                .map_err(RustitudeError::from)
-                .map_err(PyErr::from)?,
-        ),
+        breit_wigner(l, decay, barrier_factor, width_channels, mass_convention)?,
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, l, decay="[0, 1]"))]
-fn BreitWigner_32(name: &str, l: usize, decay: &str) -> PyResult<Amplitude_32> {
+#[pyo3(signature = (name, l, decay="[0, 1]", barrier_factor="blatt-weisskopf", width_channels=None, mass_convention="nominal"))]
+fn BreitWigner_32(
+    name: &str,
+    l: usize,
+    decay: &str,
+    barrier_factor: &str,
+    width_channels: Option<Vec<(f32, f32, f32, usize)>>,
+    mass_convention: &str,
+) -> PyResult<Amplitude_32> {
     Ok(Amplitude_32::new(
         name,
-        rust::BreitWigner::new(
-            l,
-            Decay::from_str(decay)
-                .map_err(RustitudeError::from)
-                .map_err(PyErr::from)?,
-        ),
+        breit_wigner(l, decay, barrier_factor, width_channels, mass_convention)?,
     ))
 }
 #[pyfunction]
@@ -108,8 +141,13 @@ fn Flatte_32(
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixA0(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixA0(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixA0::new(
@@ -117,12 +155,18 @@ fn KMatrixA0(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64>
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixA0_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixA0_64(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixA0::new(
@@ -130,12 +174,18 @@ fn KMatrixA0_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_6
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixA0_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_32> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixA0_32(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_32> {
     Ok(Amplitude_32::new(
         name,
         rust::KMatrixA0::new(
@@ -143,12 +193,18 @@ fn KMatrixA0_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_3
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixA2(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixA2(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixA2::new(
@@ -156,12 +212,18 @@ fn KMatrixA2(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64>
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixA2_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixA2_64(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixA2::new(
@@ -169,12 +231,18 @@ fn KMatrixA2_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_6
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixA2_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_32> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixA2_32(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_32> {
     Ok(Amplitude_32::new(
         name,
         rust::KMatrixA2::new(
@@ -182,12 +250,18 @@ fn KMatrixA2_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_3
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixF0(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixF0(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixF0::new(
@@ -195,12 +269,18 @@ fn KMatrixF0(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64>
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixF0_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixF0_64(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixF0::new(
@@ -208,12 +288,18 @@ fn KMatrixF0_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_6
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixF0_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_32> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixF0_32(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_32> {
     Ok(Amplitude_32::new(
         name,
         rust::KMatrixF0::new(
@@ -221,12 +307,18 @@ fn KMatrixF0_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_3
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixF2(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixF2(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixF2::new(
@@ -234,12 +326,18 @@ fn KMatrixF2(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64>
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixF2_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixF2_64(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixF2::new(
@@ -247,12 +345,18 @@ fn KMatrixF2_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_6
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixF2_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_32> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixF2_32(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_32> {
     Ok(Amplitude_32::new(
         name,
         rust::KMatrixF2::new(
@@ -260,12 +364,18 @@ fn KMatrixF2_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_3
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixPi1(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixPi1(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixPi1::new(
@@ -273,12 +383,18 @@ fn KMatrixPi1(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64>
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixPi1_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixPi1_64(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixPi1::new(
@@ -286,12 +402,18 @@ fn KMatrixPi1_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixPi1_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_32> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixPi1_32(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_32> {
     Ok(Amplitude_32::new(
         name,
         rust::KMatrixPi1::new(
@@ -299,12 +421,18 @@ fn KMatrixPi1_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixRho(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixRho(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixRho::new(
@@ -312,12 +440,18 @@ fn KMatrixRho(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64>
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixRho_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_64> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixRho_64(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_64> {
     Ok(Amplitude_64::new(
         name,
         rust::KMatrixRho::new(
@@ -325,12 +459,18 @@ fn KMatrixRho_64(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
-        ),
+        )
+        .with_non_resonant(&non_resonant),
     ))
 }
 #[pyfunction]
-#[pyo3(signature = (name, channel, decay="[0, 1]"))]
-fn KMatrixRho_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_32> {
+#[pyo3(signature = (name, channel, decay="[0, 1]", *, non_resonant=Vec::new()))]
+fn KMatrixRho_32(
+    name: &str,
+    channel: usize,
+    decay: &str,
+    non_resonant: Vec<usize>,
+) -> PyResult<Amplitude_32> {
     Ok(Amplitude_32::new(
         name,
         rust::KMatrixRho::new(
@@ -338,11 +478,101 @@ fn KMatrixRho_32(name: &str, channel: usize, decay: &str) -> PyResult<Amplitude_
             Decay::from_str(decay)
                 .map_err(RustitudeError::from)
                 .map_err(PyErr::from)?,
+        )
+        .with_non_resonant(&non_resonant),
+    ))
+}
+
+/// Parses the name of a [`FormFactor`](rust::FormFactor) and its parameter from Python.
+fn parse_form_factor<F: Field>(kind: &str, parameter: F) -> PyResult<rust::FormFactor<F>> {
+    match kind.to_lowercase().as_ref() {
+        "none" => Ok(rust::FormFactor::None),
+        "exponential" => Ok(rust::FormFactor::Exponential(parameter)),
+        "monopole" => Ok(rust::FormFactor::Monopole(parameter)),
+        "dipole" => Ok(rust::FormFactor::Dipole(parameter)),
+        _ => Err(RustitudeError::ParseError(format!("Unknown form factor: {kind}")).into()),
+    }
+}
+#[pyfunction]
+#[pyo3(signature = (name, isobar="[0, 1]", bachelor=2, exchange_mass=0.13957, form_factor="none", form_factor_parameter=1.0, slope=0.0))]
+#[allow(clippy::too_many_arguments)]
+fn Deck(
+    name: &str,
+    isobar: &str,
+    bachelor: usize,
+    exchange_mass: f64,
+    form_factor: &str,
+    form_factor_parameter: f64,
+    slope: f64,
+) -> PyResult<Amplitude_64> {
+    Ok(Amplitude_64::new(
+        name,
+        rust::Deck::new(
+            Decay::from_str(isobar)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            bachelor,
+            exchange_mass,
+            parse_form_factor(form_factor, form_factor_parameter)?,
+            slope,
+        ),
+    ))
+}
+#[pyfunction]
+#[pyo3(signature = (name, isobar="[0, 1]", bachelor=2, exchange_mass=0.13957, form_factor="none", form_factor_parameter=1.0, slope=0.0))]
+#[allow(clippy::too_many_arguments)]
+fn Deck_64(
+    name: &str,
+    isobar: &str,
+    bachelor: usize,
+    exchange_mass: f64,
+    form_factor: &str,
+    form_factor_parameter: f64,
+    slope: f64,
+) -> PyResult<Amplitude_64> {
+    Ok(Amplitude_64::new(
+        name,
+        rust::Deck::new(
+            Decay::from_str(isobar)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            bachelor,
+            exchange_mass,
+            parse_form_factor(form_factor, form_factor_parameter)?,
+            slope,
+        ),
+    ))
+}
+#[pyfunction]
+#[pyo3(signature = (name, isobar="[0, 1]", bachelor=2, exchange_mass=0.13957, form_factor="none", form_factor_parameter=1.0, slope=0.0))]
+#[allow(clippy::too_many_arguments)]
+fn Deck_32(
+    name: &str,
+    isobar: &str,
+    bachelor: usize,
+    exchange_mass: f32,
+    form_factor: &str,
+    form_factor_parameter: f32,
+    slope: f32,
+) -> PyResult<Amplitude_32> {
+    Ok(Amplitude_32::new(
+        name,
+        rust::Deck::new(
+            Decay::from_str(isobar)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            bachelor,
+            exchange_mass,
+            parse_form_factor(form_factor, form_factor_parameter)?,
+            slope,
         ),
     ))
 }
 
 pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(Deck, m)?)?;
+    m.add_function(wrap_pyfunction!(Deck_64, m)?)?;
+    m.add_function(wrap_pyfunction!(Deck_32, m)?)?;
     m.add_function(wrap_pyfunction!(BreitWigner, m)?)?;
     m.add_function(wrap_pyfunction!(BreitWigner_64, m)?)?;
     m.add_function(wrap_pyfunction!(BreitWigner_32, m)?)?;
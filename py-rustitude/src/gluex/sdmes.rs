@@ -142,6 +142,52 @@ fn VecRadiativeSDME_32(name: &str, decay: &str, frame: &str) -> PyResult<Amplitu
     ))
 }
 
+#[pyfunction]
+#[pyo3(signature = (name, decay="[0, 1]", frame="helicity"))]
+fn VectorSDME(name: &str, decay: &str, frame: &str) -> PyResult<Amplitude_64> {
+    Ok(Amplitude_64::new(
+        name,
+        rust::VectorSDME::new(
+            Decay::from_str(decay)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            Frame::from_str(frame)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+        ),
+    ))
+}
+#[pyfunction]
+#[pyo3(signature = (name, decay="[0, 1]", frame="helicity"))]
+fn VectorSDME_64(name: &str, decay: &str, frame: &str) -> PyResult<Amplitude_64> {
+    Ok(Amplitude_64::new(
+        name,
+        rust::VectorSDME::new(
+            Decay::from_str(decay)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            Frame::from_str(frame)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+        ),
+    ))
+}
+#[pyfunction]
+#[pyo3(signature = (name, decay="[0, 1]", frame="helicity"))]
+fn VectorSDME_32(name: &str, decay: &str, frame: &str) -> PyResult<Amplitude_32> {
+    Ok(Amplitude_32::new(
+        name,
+        rust::VectorSDME::new(
+            Decay::from_str(decay)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            Frame::from_str(frame)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+        ),
+    ))
+}
+
 pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(TwoPiSDME, m)?)?;
     m.add_function(wrap_pyfunction!(TwoPiSDME_64, m)?)?;
@@ -152,5 +198,8 @@ pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(VecRadiativeSDME, m)?)?;
     m.add_function(wrap_pyfunction!(VecRadiativeSDME_64, m)?)?;
     m.add_function(wrap_pyfunction!(VecRadiativeSDME_32, m)?)?;
+    m.add_function(wrap_pyfunction!(VectorSDME, m)?)?;
+    m.add_function(wrap_pyfunction!(VectorSDME_64, m)?)?;
+    m.add_function(wrap_pyfunction!(VectorSDME_32, m)?)?;
     Ok(())
 }
@@ -0,0 +1,123 @@
+use std::str::FromStr;
+
+use crate::amplitude::{Amplitude_32, Amplitude_64};
+use pyo3::prelude::*;
+use rustitude::prelude::RustitudeError;
+use rustitude_gluex::utils::{Decay, Frame, Sign};
+use rustitude_gluex::vecps as rust;
+
+#[pyfunction]
+#[pyo3(signature = (name, j, m, l, reflectivity="+", decay_helicity=0, bachelor=0, vector="[1, 2, 3]", frame="helicity"))]
+#[allow(clippy::too_many_arguments)]
+fn VecPS(
+    name: &str,
+    j: usize,
+    m: isize,
+    l: usize,
+    reflectivity: &str,
+    decay_helicity: isize,
+    bachelor: usize,
+    vector: &str,
+    frame: &str,
+) -> PyResult<Amplitude_64> {
+    Ok(Amplitude_64::new(
+        name,
+        rust::VecPS::new(
+            j,
+            m,
+            l,
+            Sign::from_str(reflectivity)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            decay_helicity,
+            rust::VectorDecay {
+                bachelor,
+                vector: Decay::from_str(vector)
+                    .map_err(RustitudeError::from)
+                    .map_err(PyErr::from)?,
+            },
+            Frame::from_str(frame)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+        )?,
+    ))
+}
+#[pyfunction]
+#[pyo3(signature = (name, j, m, l, reflectivity="+", decay_helicity=0, bachelor=0, vector="[1, 2, 3]", frame="helicity"))]
+#[allow(clippy::too_many_arguments)]
+fn VecPS_64(
+    name: &str,
+    j: usize,
+    m: isize,
+    l: usize,
+    reflectivity: &str,
+    decay_helicity: isize,
+    bachelor: usize,
+    vector: &str,
+    frame: &str,
+) -> PyResult<Amplitude_64> {
+    Ok(Amplitude_64::new(
+        name,
+        rust::VecPS::new(
+            j,
+            m,
+            l,
+            Sign::from_str(reflectivity)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            decay_helicity,
+            rust::VectorDecay {
+                bachelor,
+                vector: Decay::from_str(vector)
+                    .map_err(RustitudeError::from)
+                    .map_err(PyErr::from)?,
+            },
+            Frame::from_str(frame)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+        )?,
+    ))
+}
+#[pyfunction]
+#[pyo3(signature = (name, j, m, l, reflectivity="+", decay_helicity=0, bachelor=0, vector="[1, 2, 3]", frame="helicity"))]
+#[allow(clippy::too_many_arguments)]
+fn VecPS_32(
+    name: &str,
+    j: usize,
+    m: isize,
+    l: usize,
+    reflectivity: &str,
+    decay_helicity: isize,
+    bachelor: usize,
+    vector: &str,
+    frame: &str,
+) -> PyResult<Amplitude_32> {
+    Ok(Amplitude_32::new(
+        name,
+        rust::VecPS::new(
+            j,
+            m,
+            l,
+            Sign::from_str(reflectivity)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+            decay_helicity,
+            rust::VectorDecay {
+                bachelor,
+                vector: Decay::from_str(vector)
+                    .map_err(RustitudeError::from)
+                    .map_err(PyErr::from)?,
+            },
+            Frame::from_str(frame)
+                .map_err(RustitudeError::from)
+                .map_err(PyErr::from)?,
+        )?,
+    ))
+}
+
+pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(VecPS, m)?)?;
+    m.add_function(wrap_pyfunction!(VecPS_64, m)?)?;
+    m.add_function(wrap_pyfunction!(VecPS_32, m)?)?;
+    Ok(())
+}
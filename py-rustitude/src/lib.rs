@@ -1,5 +1,6 @@
 #![allow(non_snake_case)]
 #![allow(non_camel_case_types)]
+#![allow(clippy::useless_conversion)]
 use pyo3::prelude::*;
 mod amplitude;
 mod dataset;
@@ -29,7 +30,7 @@ where
 {
     let child_module = PyModule::new_bound(parent.py(), name)?;
     mod_init(&child_module)?;
-    parent.add(name.split('.').last().unwrap(), &child_module)?;
+    parent.add(name.split('.').next_back().unwrap(), &child_module)?;
     parent
         .py()
         .import_bound("sys")?
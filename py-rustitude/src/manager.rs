@@ -1,14 +1,17 @@
+use std::collections::HashMap;
+
 use ganesh::algorithms::nelder_mead;
 use ganesh::core::Minimizer;
-use pyo3::{exceptions::PyRuntimeError, prelude::*};
+use nalgebra::Vector3;
+use pyo3::{exceptions::PyRuntimeError, prelude::*, types::PyDict};
 use rustitude_core as rust;
 
 use crate::{
     amplitude::{
-        Amplitude_32, Amplitude_64, Model_32, Model_64, NormSqr_32, NormSqr_64, Parameter_32,
-        Parameter_64,
+        parse_transform, ActivationSnapshot, Amplitude_32, Amplitude_64, Model_32, Model_64,
+        NormSqr_32, NormSqr_64, Parameter_32, Parameter_64,
     },
-    dataset::{Dataset_32, Dataset_64},
+    dataset::{Dataset_32, Dataset_64, Event_32, Event_64},
     impl_convert,
 };
 
@@ -44,6 +47,77 @@ impl Manager_64 {
         self.0.dataset.clone().into()
     }
     #[getter]
+    fn incremental(&self) -> bool {
+        self.0.incremental.is_some()
+    }
+    #[setter]
+    fn set_incremental(&mut self, incremental: bool) {
+        self.0 = self.0.clone().with_incremental(incremental);
+    }
+    #[getter]
+    fn threads(&self) -> usize {
+        self.0.num_threads()
+    }
+    #[setter]
+    fn set_threads(&mut self, threads: usize) -> PyResult<()> {
+        self.0 = self.0.clone().with_threads(threads)?;
+        Ok(())
+    }
+    #[pyo3(signature = (policy = None, *, clamp = None))]
+    fn set_intensity_policy(&mut self, policy: Option<&str>, clamp: Option<f64>) -> PyResult<()> {
+        self.0.intensity_policy = match (policy, clamp) {
+            (None, _) => None,
+            (Some("error"), None) => Some(rust::manager::IntensityPolicy::Error),
+            (Some("skip"), None) => Some(rust::manager::IntensityPolicy::Skip),
+            (Some("clamp"), Some(value)) => Some(rust::manager::IntensityPolicy::Clamp(value)),
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "the policy must be 'error', 'skip', or 'clamp' (with a clamp value)",
+                ))
+            }
+        };
+        Ok(())
+    }
+    fn debug_event(
+        &self,
+        py: Python<'_>,
+        parameters: Vec<f64>,
+        index: usize,
+    ) -> PyResult<PyObject> {
+        let report = self.0.debug_event(&parameters, index)?;
+        let cohsums = report
+            .cohsums
+            .into_iter()
+            .map(|cohsum| {
+                let dict = PyDict::new_bound(py);
+                dict.set_item("name", cohsum.name)?;
+                dict.set_item("active", cohsum.active)?;
+                dict.set_item("terms", cohsum.terms)?;
+                dict.set_item("value", cohsum.value)?;
+                dict.set_item("intensity", cohsum.intensity)?;
+                Ok(dict)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let dict = PyDict::new_bound(py);
+        dict.set_item("index", report.index)?;
+        dict.set_item("intensity", report.intensity)?;
+        dict.set_item("amplitudes", report.amplitudes)?;
+        dict.set_item("cohsums", cohsums)?;
+        Ok(dict.into())
+    }
+    #[allow(clippy::type_complexity)]
+    fn invalid_intensities(
+        &self,
+        parameters: Vec<f64>,
+    ) -> PyResult<Vec<(usize, f64, Vec<(String, rust::prelude::Complex<f64>)>)>> {
+        Ok(self
+            .0
+            .invalid_intensities(&parameters)?
+            .into_iter()
+            .map(|invalid| (invalid.index, invalid.intensity, invalid.amplitudes))
+            .collect())
+    }
+    #[getter]
     fn amplitudes(&self) -> Vec<Amplitude_64> {
         self.0
             .model
@@ -140,6 +214,33 @@ impl Manager_64 {
             self.0.evaluate(&parameters).map_err(PyErr::from)
         }
     }
+    #[pyo3(signature = (parameters, sum_index, *, parallel = true))]
+    fn evaluate_cohsum(
+        &self,
+        parameters: Vec<f64>,
+        sum_index: usize,
+        parallel: bool,
+    ) -> PyResult<Vec<f64>> {
+        if parallel {
+            self.0.par_evaluate_cohsum(&parameters, sum_index)
+        } else {
+            self.0.evaluate_cohsum(&parameters, sum_index)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameter_sets, *, parallel = true))]
+    fn evaluate_many(
+        &self,
+        parameter_sets: Vec<Vec<f64>>,
+        parallel: bool,
+    ) -> PyResult<Vec<Vec<f64>>> {
+        if parallel {
+            self.0.par_evaluate_many(&parameter_sets)
+        } else {
+            self.0.evaluate_many(&parameter_sets)
+        }
+        .map_err(PyErr::from)
+    }
     fn get_amplitude(&self, amplitude_name: &str) -> PyResult<Amplitude_64> {
         self.0
             .get_amplitude(amplitude_name)
@@ -172,16 +273,85 @@ impl Manager_64 {
     fn free(&mut self, amplitude: &str, parameter: &str) -> PyResult<()> {
         self.0.free(amplitude, parameter).map_err(PyErr::from)
     }
+    fn add_gaussian_constraint(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f64,
+        sigma: f64,
+    ) -> PyResult<()> {
+        self.0
+            .add_gaussian_constraint(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_prior(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f64,
+        sigma: f64,
+    ) -> PyResult<()> {
+        self.0
+            .add_prior(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_penalty(
+        &mut self,
+        parameters: Vec<(String, String)>,
+        function: PyObject,
+    ) -> PyResult<()> {
+        let names: Vec<(&str, &str)> = parameters
+            .iter()
+            .map(|(amplitude, parameter)| (amplitude.as_str(), parameter.as_str()))
+            .collect();
+        self.0
+            .add_penalty(&names, move |values: &[f64]| {
+                Python::with_gil(|py| function.call1(py, (values.to_vec(),))?.extract::<f64>(py))
+                    .map_err(rust::errors::RustitudeError::from)
+            })
+            .map_err(PyErr::from)
+    }
     fn set_bounds(&mut self, amplitude: &str, parameter: &str, bounds: (f64, f64)) -> PyResult<()> {
         self.0
             .set_bounds(amplitude, parameter, bounds)
             .map_err(PyErr::from)
     }
+    #[pyo3(signature = (amplitude, parameter, transform, bounds = None))]
+    fn set_transform(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        transform: Option<&str>,
+        bounds: Option<(f64, f64)>,
+    ) -> PyResult<()> {
+        self.0
+            .set_transform(amplitude, parameter, parse_transform(transform, bounds)?)
+            .map_err(PyErr::from)
+    }
     fn set_initial(&mut self, amplitude: &str, parameter: &str, value: f64) -> PyResult<()> {
         self.0
             .set_initial(amplitude, parameter, value)
             .map_err(PyErr::from)
     }
+    fn fix_matching(&mut self, pattern: &str, value: f64) -> PyResult<()> {
+        self.0.fix_matching(pattern, value).map_err(PyErr::from)
+    }
+    fn free_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.free_matching(pattern).map_err(PyErr::from)
+    }
+    fn set_bounds_matching(&mut self, pattern: &str, bounds: (f64, f64)) -> PyResult<()> {
+        self.0
+            .set_bounds_matching(pattern, bounds)
+            .map_err(PyErr::from)
+    }
+    fn set_initial_matching(&mut self, pattern: &str, value: f64) -> PyResult<()> {
+        self.0
+            .set_initial_matching(pattern, value)
+            .map_err(PyErr::from)
+    }
+    fn constrain_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.constrain_matching(pattern).map_err(PyErr::from)
+    }
     fn activate(&mut self, amplitude: &str) -> PyResult<()> {
         self.0.activate(amplitude).map_err(PyErr::from)
     }
@@ -199,6 +369,24 @@ impl Manager_64 {
     fn deactivate_all(&mut self) {
         self.0.deactivate_all()
     }
+    fn get_sum(&self, name: &str) -> PyResult<NormSqr_64> {
+        self.0
+            .get_sum(name)
+            .map(NormSqr_64::from)
+            .map_err(PyErr::from)
+    }
+    fn activate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.activate_sum(name).map_err(PyErr::from)
+    }
+    fn deactivate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.deactivate_sum(name).map_err(PyErr::from)
+    }
+    fn activation_state(&self) -> ActivationSnapshot {
+        self.0.activation_state().into()
+    }
+    fn restore(&mut self, snapshot: ActivationSnapshot) -> PyResult<()> {
+        self.0.restore(&snapshot.into()).map_err(PyErr::from)
+    }
 }
 
 #[pyclass]
@@ -233,6 +421,77 @@ impl Manager_32 {
         self.0.dataset.clone().into()
     }
     #[getter]
+    fn incremental(&self) -> bool {
+        self.0.incremental.is_some()
+    }
+    #[setter]
+    fn set_incremental(&mut self, incremental: bool) {
+        self.0 = self.0.clone().with_incremental(incremental);
+    }
+    #[getter]
+    fn threads(&self) -> usize {
+        self.0.num_threads()
+    }
+    #[setter]
+    fn set_threads(&mut self, threads: usize) -> PyResult<()> {
+        self.0 = self.0.clone().with_threads(threads)?;
+        Ok(())
+    }
+    #[pyo3(signature = (policy = None, *, clamp = None))]
+    fn set_intensity_policy(&mut self, policy: Option<&str>, clamp: Option<f32>) -> PyResult<()> {
+        self.0.intensity_policy = match (policy, clamp) {
+            (None, _) => None,
+            (Some("error"), None) => Some(rust::manager::IntensityPolicy::Error),
+            (Some("skip"), None) => Some(rust::manager::IntensityPolicy::Skip),
+            (Some("clamp"), Some(value)) => Some(rust::manager::IntensityPolicy::Clamp(value)),
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "the policy must be 'error', 'skip', or 'clamp' (with a clamp value)",
+                ))
+            }
+        };
+        Ok(())
+    }
+    fn debug_event(
+        &self,
+        py: Python<'_>,
+        parameters: Vec<f32>,
+        index: usize,
+    ) -> PyResult<PyObject> {
+        let report = self.0.debug_event(&parameters, index)?;
+        let cohsums = report
+            .cohsums
+            .into_iter()
+            .map(|cohsum| {
+                let dict = PyDict::new_bound(py);
+                dict.set_item("name", cohsum.name)?;
+                dict.set_item("active", cohsum.active)?;
+                dict.set_item("terms", cohsum.terms)?;
+                dict.set_item("value", cohsum.value)?;
+                dict.set_item("intensity", cohsum.intensity)?;
+                Ok(dict)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let dict = PyDict::new_bound(py);
+        dict.set_item("index", report.index)?;
+        dict.set_item("intensity", report.intensity)?;
+        dict.set_item("amplitudes", report.amplitudes)?;
+        dict.set_item("cohsums", cohsums)?;
+        Ok(dict.into())
+    }
+    #[allow(clippy::type_complexity)]
+    fn invalid_intensities(
+        &self,
+        parameters: Vec<f32>,
+    ) -> PyResult<Vec<(usize, f32, Vec<(String, rust::prelude::Complex<f32>)>)>> {
+        Ok(self
+            .0
+            .invalid_intensities(&parameters)?
+            .into_iter()
+            .map(|invalid| (invalid.index, invalid.intensity, invalid.amplitudes))
+            .collect())
+    }
+    #[getter]
     fn amplitudes(&self) -> Vec<Amplitude_32> {
         self.0
             .model
@@ -329,6 +588,33 @@ impl Manager_32 {
             self.0.evaluate(&parameters).map_err(PyErr::from)
         }
     }
+    #[pyo3(signature = (parameters, sum_index, *, parallel = true))]
+    fn evaluate_cohsum(
+        &self,
+        parameters: Vec<f32>,
+        sum_index: usize,
+        parallel: bool,
+    ) -> PyResult<Vec<f32>> {
+        if parallel {
+            self.0.par_evaluate_cohsum(&parameters, sum_index)
+        } else {
+            self.0.evaluate_cohsum(&parameters, sum_index)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameter_sets, *, parallel = true))]
+    fn evaluate_many(
+        &self,
+        parameter_sets: Vec<Vec<f32>>,
+        parallel: bool,
+    ) -> PyResult<Vec<Vec<f32>>> {
+        if parallel {
+            self.0.par_evaluate_many(&parameter_sets)
+        } else {
+            self.0.evaluate_many(&parameter_sets)
+        }
+        .map_err(PyErr::from)
+    }
     fn get_amplitude(&self, amplitude_name: &str) -> PyResult<Amplitude_32> {
         self.0
             .get_amplitude(amplitude_name)
@@ -361,16 +647,85 @@ impl Manager_32 {
     fn free(&mut self, amplitude: &str, parameter: &str) -> PyResult<()> {
         self.0.free(amplitude, parameter).map_err(PyErr::from)
     }
+    fn add_gaussian_constraint(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f32,
+        sigma: f32,
+    ) -> PyResult<()> {
+        self.0
+            .add_gaussian_constraint(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_prior(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f32,
+        sigma: f32,
+    ) -> PyResult<()> {
+        self.0
+            .add_prior(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_penalty(
+        &mut self,
+        parameters: Vec<(String, String)>,
+        function: PyObject,
+    ) -> PyResult<()> {
+        let names: Vec<(&str, &str)> = parameters
+            .iter()
+            .map(|(amplitude, parameter)| (amplitude.as_str(), parameter.as_str()))
+            .collect();
+        self.0
+            .add_penalty(&names, move |values: &[f32]| {
+                Python::with_gil(|py| function.call1(py, (values.to_vec(),))?.extract::<f32>(py))
+                    .map_err(rust::errors::RustitudeError::from)
+            })
+            .map_err(PyErr::from)
+    }
     fn set_bounds(&mut self, amplitude: &str, parameter: &str, bounds: (f32, f32)) -> PyResult<()> {
         self.0
             .set_bounds(amplitude, parameter, bounds)
             .map_err(PyErr::from)
     }
+    #[pyo3(signature = (amplitude, parameter, transform, bounds = None))]
+    fn set_transform(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        transform: Option<&str>,
+        bounds: Option<(f32, f32)>,
+    ) -> PyResult<()> {
+        self.0
+            .set_transform(amplitude, parameter, parse_transform(transform, bounds)?)
+            .map_err(PyErr::from)
+    }
     fn set_initial(&mut self, amplitude: &str, parameter: &str, value: f32) -> PyResult<()> {
         self.0
             .set_initial(amplitude, parameter, value)
             .map_err(PyErr::from)
     }
+    fn fix_matching(&mut self, pattern: &str, value: f32) -> PyResult<()> {
+        self.0.fix_matching(pattern, value).map_err(PyErr::from)
+    }
+    fn free_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.free_matching(pattern).map_err(PyErr::from)
+    }
+    fn set_bounds_matching(&mut self, pattern: &str, bounds: (f32, f32)) -> PyResult<()> {
+        self.0
+            .set_bounds_matching(pattern, bounds)
+            .map_err(PyErr::from)
+    }
+    fn set_initial_matching(&mut self, pattern: &str, value: f32) -> PyResult<()> {
+        self.0
+            .set_initial_matching(pattern, value)
+            .map_err(PyErr::from)
+    }
+    fn constrain_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.constrain_matching(pattern).map_err(PyErr::from)
+    }
     fn activate(&mut self, amplitude: &str) -> PyResult<()> {
         self.0.activate(amplitude).map_err(PyErr::from)
     }
@@ -388,10 +743,28 @@ impl Manager_32 {
     fn deactivate_all(&mut self) {
         self.0.deactivate_all()
     }
+    fn get_sum(&self, name: &str) -> PyResult<NormSqr_32> {
+        self.0
+            .get_sum(name)
+            .map(NormSqr_32::from)
+            .map_err(PyErr::from)
+    }
+    fn activate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.activate_sum(name).map_err(PyErr::from)
+    }
+    fn deactivate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.deactivate_sum(name).map_err(PyErr::from)
+    }
+    fn activation_state(&self) -> ActivationSnapshot {
+        self.0.activation_state().into()
+    }
+    fn restore(&mut self, snapshot: ActivationSnapshot) -> PyResult<()> {
+        self.0.restore(&snapshot.into()).map_err(PyErr::from)
+    }
 }
 
 #[pyclass]
-pub struct ExtendedLogLikelihood_64(rust::manager::ExtendedLogLikelihood<f64>);
+pub struct ExtendedLogLikelihood_64(pub(crate) rust::manager::ExtendedLogLikelihood<f64>);
 impl_convert!(
     ExtendedLogLikelihood_64,
     rust::manager::ExtendedLogLikelihood<f64>
@@ -478,10 +851,132 @@ impl ExtendedLogLikelihood_64 {
     fn n_free(&self) -> usize {
         self.0.get_n_free()
     }
+    #[getter]
+    fn get_wide_mc_accumulation(&self) -> bool {
+        self.0.wide_mc_accumulation
+    }
+    #[setter]
+    fn set_wide_mc_accumulation(&mut self, value: bool) {
+        self.0.wide_mc_accumulation = value;
+    }
+    #[getter]
+    fn get_deterministic_reduction(&self) -> bool {
+        self.0.deterministic_reduction
+    }
+    #[setter]
+    fn set_deterministic_reduction(&mut self, value: bool) {
+        self.0.deterministic_reduction = value;
+    }
+    #[pyo3(signature = (floor = None, *, drop = false))]
+    fn set_intensity_floor(&mut self, floor: Option<f64>, drop: bool) {
+        self.0.intensity_floor = floor.map(|floor| {
+            if drop {
+                rust::manager::IntensityFloor::Drop(floor)
+            } else {
+                rust::manager::IntensityFloor::Clamp(floor)
+            }
+        });
+    }
+    fn floored_events(&self, parameters: Vec<f64>) -> PyResult<Vec<usize>> {
+        self.0.floored_events(&parameters).map_err(PyErr::from)
+    }
+    #[pyo3(signature = (threshold = None, *, tukey = false))]
+    fn set_robust_loss(&mut self, threshold: Option<f64>, tukey: bool) {
+        self.0.robust_loss = threshold.map(|threshold| {
+            if tukey {
+                rust::manager::RobustLoss::Tukey(threshold)
+            } else {
+                rust::manager::RobustLoss::Cap(threshold)
+            }
+        });
+    }
+    #[getter]
+    fn get_likelihood_kind(&self) -> &'static str {
+        match self.0.kind {
+            rust::manager::LikelihoodKind::Extended => "extended",
+            rust::manager::LikelihoodKind::Unextended => "unextended",
+            rust::manager::LikelihoodKind::Binned(_) => "binned",
+        }
+    }
+    #[setter]
+    fn set_likelihood_kind(&mut self, kind: &str) -> PyResult<()> {
+        self.0.kind = match kind {
+            "extended" => rust::manager::LikelihoodKind::Extended,
+            "unextended" => rust::manager::LikelihoodKind::Unextended,
+            _ => {
+                return Err(PyErr::from(
+                    rust::errors::RustitudeError::InvalidParameterValue(format!(
+                        "expected \"extended\" or \"unextended\" (use set_binned for a binned likelihood), got {kind:?}"
+                    )),
+                ))
+            }
+        };
+        Ok(())
+    }
+    fn set_binned(&mut self, edges: Vec<f64>, variable: Bound<'_, PyAny>) -> PyResult<()> {
+        let values = |dataset: &rust::dataset::Dataset<f64>| -> PyResult<Vec<f64>> {
+            dataset
+                .events
+                .iter()
+                .map(|event| variable.call1((Event_64::from(event.clone()),))?.extract())
+                .collect()
+        };
+        let data_values = values(&self.0.data_manager.dataset)?;
+        let mc_values = values(&self.0.mc_manager.dataset)?;
+        self.0.kind = rust::manager::LikelihoodKind::Binned(rust::manager::Binning::from_values(
+            edges,
+            &data_values,
+            &mc_values,
+        )?);
+        Ok(())
+    }
+    fn evaluate_standard(&self, parameters: Vec<f64>) -> PyResult<f64> {
+        self.0.evaluate_standard(&parameters).map_err(PyErr::from)
+    }
+    #[getter]
+    fn yields(&self) -> Vec<Parameter_64> {
+        self.0
+            .free_parameters()
+            .into_iter()
+            .filter(|p| p.amplitude == rust::manager::YIELD_AMPLITUDE)
+            .map(Parameter_64::from)
+            .collect()
+    }
     #[new]
     fn new(data_manager: Manager_64, mc_manager: Manager_64) -> Self {
         rust::manager::ExtendedLogLikelihood::new(data_manager.into(), mc_manager.into()).into()
     }
+    #[staticmethod]
+    #[pyo3(signature = (data_manager, beam_energy, target_mass, recoil_mass, daughter_masses, parameters, *, precision = 1e-3, max_events = 1 << 20, eps = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn from_phase_space(
+        data_manager: Manager_64,
+        beam_energy: f64,
+        target_mass: f64,
+        recoil_mass: f64,
+        daughter_masses: Vec<f64>,
+        parameters: Vec<f64>,
+        precision: f64,
+        max_events: usize,
+        eps: Option<[f64; 3]>,
+    ) -> PyResult<Self> {
+        let phase_space = rust::phase_space::PhaseSpace::new(
+            beam_energy,
+            target_mass,
+            recoil_mass,
+            daughter_masses,
+        )?
+        .with_polarization(eps.map_or_else(Vector3::zeros, Vector3::from));
+        rust::manager::ExtendedLogLikelihood::from_phase_space(
+            data_manager.into(),
+            &phase_space,
+            &parameters,
+            precision,
+            max_events,
+        )
+        .map(Self)
+        .map_err(PyErr::from)
+    }
     #[pyo3(signature = (parameters, *, indices_data = None, indices_mc = None, parallel = true))]
     fn evaluate(
         &self,
@@ -533,86 +1028,340 @@ impl ExtendedLogLikelihood_64 {
             .map_err(PyErr::from)
         }
     }
-    #[pyo3(signature = (parameters, dataset, *, indices_data = None, indices_mc = None, parallel = true))]
-    fn intensity(
-        &self,
-        parameters: Vec<f64>,
-        dataset: Dataset_64,
-        indices_data: Option<Vec<usize>>,
-        indices_mc: Option<Vec<usize>>,
-        parallel: bool,
-    ) -> PyResult<Vec<f64>> {
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn gradient(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<Vec<f64>> {
         if parallel {
-            if self.0.data_manager.model.contains_python_amplitudes
-                || self.0.mc_manager.model.contains_python_amplitudes
-            {
-                return Err(PyRuntimeError::new_err(
-                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!",
-                ));
-            }
-            match (indices_data, indices_mc) {
-                (None, None) => self.0.par_intensity(&parameters, &dataset.into()),
-                (None, Some(i_mc)) => self.0.par_intensity_indexed(
-                    &parameters,
-                    &dataset.into(),
-                    &((0..self.0.data_manager.dataset.len()).collect::<Vec<usize>>()),
-                    &i_mc,
-                ),
-                (Some(i_data), None) => self.0.par_intensity_indexed(
-                    &parameters,
-                    &dataset.into(),
-                    &i_data,
-                    &((0..self.0.mc_manager.dataset.len()).collect::<Vec<usize>>()),
-                ),
-                (Some(i_data), Some(i_mc)) => {
-                    self.0
-                        .par_intensity_indexed(&parameters, &dataset.into(), &i_data, &i_mc)
-                }
-            }
-            .map_err(PyErr::from)
+            self.0.par_gradient(&parameters)
         } else {
-            match (indices_data, indices_mc) {
-                (None, None) => self.0.intensity(&parameters, &dataset.into()),
-                (None, Some(i_mc)) => self.0.intensity_indexed(
-                    &parameters,
-                    &dataset.into(),
-                    &((0..self.0.data_manager.dataset.len()).collect::<Vec<usize>>()),
-                    &i_mc,
-                ),
-                (Some(i_data), None) => self.0.intensity_indexed(
-                    &parameters,
-                    &dataset.into(),
-                    &i_data,
-                    &((0..self.0.mc_manager.dataset.len()).collect::<Vec<usize>>()),
-                ),
-                (Some(i_data), Some(i_mc)) => {
-                    self.0
-                        .intensity_indexed(&parameters, &dataset.into(), &i_data, &i_mc)
-                }
-            }
-            .map_err(PyErr::from)
+            self.0.gradient(&parameters)
         }
+        .map_err(PyErr::from)
     }
-    #[pyo3(name = "__call__", signature = (parameters, *, indices_data = None, indices_mc = None, parallel = true))]
-    fn call(
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn evaluate_with_gradient(
         &self,
         parameters: Vec<f64>,
-        indices_data: Option<Vec<usize>>,
-        indices_mc: Option<Vec<usize>>,
         parallel: bool,
-    ) -> PyResult<f64> {
-        self.evaluate(parameters, indices_data, indices_mc, parallel)
+    ) -> PyResult<(f64, Vec<f64>)> {
+        if parallel {
+            self.0.par_evaluate_with_gradient(&parameters)
+        } else {
+            self.0.evaluate_with_gradient(&parameters)
+        }
+        .map_err(PyErr::from)
     }
-    fn get_amplitude(&self, amplitude_name: &str) -> PyResult<Amplitude_64> {
-        self.0
-            .get_amplitude(amplitude_name)
-            .map(Amplitude_64::from)
-            .map_err(PyErr::from)
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn hessian(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<Vec<Vec<f64>>> {
+        if parallel {
+            self.0.par_hessian(&parameters)
+        } else {
+            self.0.hessian(&parameters)
+        }
+        .map_err(PyErr::from)
     }
-    fn get_parameter(&self, amplitude_name: &str, parameter_name: &str) -> PyResult<Parameter_64> {
-        self.0
-            .get_parameter(amplitude_name, parameter_name)
-            .map(Parameter_64::from)
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn weighted_covariance(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<Vec<Vec<f64>>> {
+        if parallel {
+            self.0.par_weighted_covariance(&parameters)
+        } else {
+            self.0.weighted_covariance(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn covariance(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<Vec<Vec<f64>>> {
+        if parallel {
+            self.0.par_covariance(&parameters)
+        } else {
+            self.0.covariance(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (amplitude, parameter, values, *, max_steps = 1000, parallel = true))]
+    fn profile(
+        &self,
+        amplitude: &str,
+        parameter: &str,
+        values: Vec<f64>,
+        max_steps: usize,
+        parallel: bool,
+    ) -> PyResult<Vec<f64>> {
+        if parallel {
+            self.0.par_profile(amplitude, parameter, &values, max_steps)
+        } else {
+            self.0.profile(amplitude, parameter, &values, max_steps)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (n_starts, *, seed = 0, max_steps = 1000, parallel = true))]
+    fn multistart(
+        &self,
+        n_starts: usize,
+        seed: u64,
+        max_steps: usize,
+        parallel: bool,
+    ) -> PyResult<Vec<FitResult_64>> {
+        if parallel {
+            self.0.par_multistart(n_starts, seed, max_steps)
+        } else {
+            self.0.multistart(n_starts, seed, max_steps)
+        }
+        .map(|results| results.into_iter().map(FitResult_64).collect())
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (truth, n_toys, *, seed = 0, source = "accept-reject", pool = None, n_events = None, truth_covariance = None, max_steps = 1000, parallel = true))]
+    #[allow(clippy::too_many_arguments)]
+    fn toy_study(
+        &self,
+        truth: Vec<f64>,
+        n_toys: usize,
+        seed: u64,
+        source: &str,
+        pool: Option<Dataset_64>,
+        n_events: Option<usize>,
+        truth_covariance: Option<Vec<Vec<f64>>>,
+        max_steps: usize,
+        parallel: bool,
+    ) -> PyResult<ToyStudyResult_64> {
+        let source = match source {
+            "accept-reject" => rust::studies::ToySource::AcceptReject,
+            "bootstrap" => rust::studies::ToySource::Bootstrap,
+            _ => {
+                return Err(PyErr::from(
+                    rust::errors::RustitudeError::InvalidParameterValue(format!(
+                        "expected \"accept-reject\" or \"bootstrap\", got {source:?}"
+                    )),
+                ))
+            }
+        };
+        let mut study = rust::studies::ToyStudy::new(self.0.clone(), &truth)?
+            .with_source(source)
+            .with_max_steps(max_steps);
+        if let Some(pool) = pool {
+            study = study.with_pool(pool.into());
+        }
+        if let Some(n_events) = n_events {
+            study = study.with_events(n_events);
+        }
+        if let Some(covariance) = truth_covariance {
+            study = study.with_truth_covariance(&covariance)?;
+        }
+        if parallel {
+            study.par_run(n_toys, seed)
+        } else {
+            study.run(n_toys, seed)
+        }
+        .map(ToyStudyResult_64)
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (best, fx, index, *, delta = 1.0, step = None, max_steps = 1000))]
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    fn minos(
+        &self,
+        best: Vec<f64>,
+        fx: f64,
+        index: usize,
+        delta: f64,
+        step: Option<f64>,
+        max_steps: usize,
+    ) -> PyResult<(Option<f64>, Option<f64>)> {
+        self.0
+            .minos(&best, fx, index, delta, step.unwrap_or(f64::NAN), max_steps)
+            .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, dataset_mc, amplitudes, *, parallel = true))]
+    fn project(
+        &self,
+        parameters: Vec<f64>,
+        dataset_mc: Dataset_64,
+        amplitudes: Vec<String>,
+        parallel: bool,
+    ) -> PyResult<Vec<f64>> {
+        let amplitudes: Vec<&str> = amplitudes.iter().map(String::as_str).collect();
+        if parallel {
+            self.0
+                .par_project(&parameters, &dataset_mc.into(), &amplitudes)
+        } else {
+            self.0.project(&parameters, &dataset_mc.into(), &amplitudes)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, dataset_mc, edges, data_values, mc_values, *, parallel = true))]
+    fn binned_chi2(
+        &self,
+        parameters: Vec<f64>,
+        dataset_mc: Dataset_64,
+        edges: Vec<f64>,
+        data_values: Vec<f64>,
+        mc_values: Vec<f64>,
+        parallel: bool,
+    ) -> PyResult<(f64, usize, f64)> {
+        let dataset_mc = dataset_mc.into();
+        let intensity = if parallel {
+            self.0.par_intensity(&parameters, &dataset_mc)
+        } else {
+            self.0.intensity(&parameters, &dataset_mc)
+        }?;
+        let chi2 = rust::diagnostics::BinnedChiSquare::new(
+            edges,
+            &data_values,
+            Some(&self.0.data_manager.dataset.weights()),
+            &mc_values,
+            Some(&intensity),
+        )?;
+        Ok((chi2.chi2, chi2.ndf, chi2.p_value()))
+    }
+    #[pyo3(signature = (parameters, dataset_mc, amplitudes_a, amplitudes_b, *, parallel = true))]
+    fn interference(
+        &self,
+        parameters: Vec<f64>,
+        dataset_mc: Dataset_64,
+        amplitudes_a: Vec<String>,
+        amplitudes_b: Vec<String>,
+        parallel: bool,
+    ) -> PyResult<Vec<f64>> {
+        let amplitudes_a: Vec<&str> = amplitudes_a.iter().map(String::as_str).collect();
+        let amplitudes_b: Vec<&str> = amplitudes_b.iter().map(String::as_str).collect();
+        if parallel {
+            self.0.par_interference(
+                &parameters,
+                &dataset_mc.into(),
+                &amplitudes_a,
+                &amplitudes_b,
+            )
+        } else {
+            self.0.interference(
+                &parameters,
+                &dataset_mc.into(),
+                &amplitudes_a,
+                &amplitudes_b,
+            )
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, dataset_mc, groups = None, covariance = None, *, parallel = true))]
+    #[allow(clippy::type_complexity)]
+    fn fit_fractions(
+        &self,
+        parameters: Vec<f64>,
+        dataset_mc: Dataset_64,
+        groups: Option<Vec<(String, Vec<String>)>>,
+        covariance: Option<Vec<Vec<f64>>>,
+        parallel: bool,
+    ) -> PyResult<Vec<(String, f64, Option<f64>)>> {
+        let groups = groups.unwrap_or_default();
+        let groups: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|(name, amplitudes)| {
+                (
+                    name.as_str(),
+                    amplitudes.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        if parallel {
+            self.0.par_fit_fractions(
+                &parameters,
+                &dataset_mc.into(),
+                &groups,
+                covariance.as_deref(),
+            )
+        } else {
+            self.0.fit_fractions(
+                &parameters,
+                &dataset_mc.into(),
+                &groups,
+                covariance.as_deref(),
+            )
+        }
+        .map(|fractions| {
+            fractions
+                .into_iter()
+                .map(|fraction| (fraction.name, fraction.value, fraction.error))
+                .collect()
+        })
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, dataset, *, indices_data = None, indices_mc = None, parallel = true))]
+    fn intensity(
+        &self,
+        parameters: Vec<f64>,
+        dataset: Dataset_64,
+        indices_data: Option<Vec<usize>>,
+        indices_mc: Option<Vec<usize>>,
+        parallel: bool,
+    ) -> PyResult<Vec<f64>> {
+        if parallel {
+            if self.0.data_manager.model.contains_python_amplitudes
+                || self.0.mc_manager.model.contains_python_amplitudes
+            {
+                return Err(PyRuntimeError::new_err(
+                    "Python amplitudes cannot be evaluated with Rust parallelism due to the GIL!",
+                ));
+            }
+            match (indices_data, indices_mc) {
+                (None, None) => self.0.par_intensity(&parameters, &dataset.into()),
+                (None, Some(i_mc)) => self.0.par_intensity_indexed(
+                    &parameters,
+                    &dataset.into(),
+                    &((0..self.0.data_manager.dataset.len()).collect::<Vec<usize>>()),
+                    &i_mc,
+                ),
+                (Some(i_data), None) => self.0.par_intensity_indexed(
+                    &parameters,
+                    &dataset.into(),
+                    &i_data,
+                    &((0..self.0.mc_manager.dataset.len()).collect::<Vec<usize>>()),
+                ),
+                (Some(i_data), Some(i_mc)) => {
+                    self.0
+                        .par_intensity_indexed(&parameters, &dataset.into(), &i_data, &i_mc)
+                }
+            }
+            .map_err(PyErr::from)
+        } else {
+            match (indices_data, indices_mc) {
+                (None, None) => self.0.intensity(&parameters, &dataset.into()),
+                (None, Some(i_mc)) => self.0.intensity_indexed(
+                    &parameters,
+                    &dataset.into(),
+                    &((0..self.0.data_manager.dataset.len()).collect::<Vec<usize>>()),
+                    &i_mc,
+                ),
+                (Some(i_data), None) => self.0.intensity_indexed(
+                    &parameters,
+                    &dataset.into(),
+                    &i_data,
+                    &((0..self.0.mc_manager.dataset.len()).collect::<Vec<usize>>()),
+                ),
+                (Some(i_data), Some(i_mc)) => {
+                    self.0
+                        .intensity_indexed(&parameters, &dataset.into(), &i_data, &i_mc)
+                }
+            }
+            .map_err(PyErr::from)
+        }
+    }
+    #[pyo3(name = "__call__", signature = (parameters, *, indices_data = None, indices_mc = None, parallel = true))]
+    fn call(
+        &self,
+        parameters: Vec<f64>,
+        indices_data: Option<Vec<usize>>,
+        indices_mc: Option<Vec<usize>>,
+        parallel: bool,
+    ) -> PyResult<f64> {
+        self.evaluate(parameters, indices_data, indices_mc, parallel)
+    }
+    fn get_amplitude(&self, amplitude_name: &str) -> PyResult<Amplitude_64> {
+        self.0
+            .get_amplitude(amplitude_name)
+            .map(Amplitude_64::from)
+            .map_err(PyErr::from)
+    }
+    fn get_parameter(&self, amplitude_name: &str, parameter_name: &str) -> PyResult<Parameter_64> {
+        self.0
+            .get_parameter(amplitude_name, parameter_name)
+            .map(Parameter_64::from)
             .map_err(PyErr::from)
     }
     fn print_parameters(&self) {
@@ -635,16 +1384,164 @@ impl ExtendedLogLikelihood_64 {
     fn free(&mut self, amplitude: &str, parameter: &str) -> PyResult<()> {
         self.0.free(amplitude, parameter).map_err(PyErr::from)
     }
+    fn add_gaussian_constraint(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f64,
+        sigma: f64,
+    ) -> PyResult<()> {
+        self.0
+            .add_gaussian_constraint(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_prior(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f64,
+        sigma: f64,
+    ) -> PyResult<()> {
+        self.0
+            .add_prior(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_penalty(
+        &mut self,
+        parameters: Vec<(String, String)>,
+        function: PyObject,
+    ) -> PyResult<()> {
+        let names: Vec<(&str, &str)> = parameters
+            .iter()
+            .map(|(amplitude, parameter)| (amplitude.as_str(), parameter.as_str()))
+            .collect();
+        self.0
+            .add_penalty(&names, move |values: &[f64]| {
+                Python::with_gil(|py| function.call1(py, (values.to_vec(),))?.extract::<f64>(py))
+                    .map_err(rust::errors::RustitudeError::from)
+            })
+            .map_err(PyErr::from)
+    }
+    fn add_signal_yield(&mut self) {
+        self.0.add_signal_yield()
+    }
+    fn add_background_template(
+        &mut self,
+        data_template: Vec<f64>,
+        mc_template: Vec<f64>,
+    ) -> PyResult<()> {
+        self.0
+            .add_background_template(data_template, mc_template)
+            .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (acceptance = None))]
+    fn set_acceptance(&mut self, acceptance: Option<Vec<f64>>) -> PyResult<()> {
+        self.0.set_acceptance(acceptance).map_err(PyErr::from)
+    }
+    #[getter]
+    fn acceptance(&self) -> Option<Vec<f64>> {
+        self.0.acceptance.clone()
+    }
+    fn average_acceptance(&self, parameters: Vec<f64>) -> PyResult<f64> {
+        self.0.average_acceptance(&parameters).map_err(PyErr::from)
+    }
+    #[pyo3(signature = (dataset = None))]
+    fn set_generated(&mut self, dataset: Option<Dataset_64>) -> PyResult<()> {
+        let dataset: Option<rust::dataset::Dataset<f64>> = dataset.map(Into::into);
+        self.0.set_generated(dataset.as_ref()).map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn efficiency(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<f64> {
+        if parallel {
+            self.0.par_efficiency(&parameters)
+        } else {
+            self.0.efficiency(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, groups = None, *, parallel = true))]
+    fn wave_efficiencies(
+        &self,
+        parameters: Vec<f64>,
+        groups: Option<Vec<(String, Vec<String>)>>,
+        parallel: bool,
+    ) -> PyResult<Vec<(String, f64)>> {
+        let groups = groups.unwrap_or_default();
+        let groups: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|(name, amplitudes)| {
+                (
+                    name.as_str(),
+                    amplitudes.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        if parallel {
+            self.0.par_wave_efficiencies(&parameters, &groups)
+        } else {
+            self.0.wave_efficiencies(&parameters, &groups)
+        }
+        .map_err(PyErr::from)
+    }
+    fn corrected_yield(&self, parameters: Vec<f64>) -> PyResult<f64> {
+        self.0.corrected_yield(&parameters).map_err(PyErr::from)
+    }
+    fn cache_normalization(&mut self) -> PyResult<()> {
+        self.0.cache_normalization().map_err(PyErr::from)
+    }
+    fn clear_normalization_cache(&mut self) {
+        self.0.clear_normalization_cache()
+    }
+    #[getter]
+    fn threads(&self) -> usize {
+        self.0.num_threads()
+    }
+    #[setter]
+    fn set_threads(&mut self, threads: usize) -> PyResult<()> {
+        self.0 = self.0.clone().with_threads(threads)?;
+        Ok(())
+    }
     fn set_bounds(&mut self, amplitude: &str, parameter: &str, bounds: (f64, f64)) -> PyResult<()> {
         self.0
             .set_bounds(amplitude, parameter, bounds)
             .map_err(PyErr::from)
     }
+    #[pyo3(signature = (amplitude, parameter, transform, bounds = None))]
+    fn set_transform(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        transform: Option<&str>,
+        bounds: Option<(f64, f64)>,
+    ) -> PyResult<()> {
+        self.0
+            .set_transform(amplitude, parameter, parse_transform(transform, bounds)?)
+            .map_err(PyErr::from)
+    }
     fn set_initial(&mut self, amplitude: &str, parameter: &str, value: f64) -> PyResult<()> {
         self.0
             .set_initial(amplitude, parameter, value)
             .map_err(PyErr::from)
     }
+    fn fix_matching(&mut self, pattern: &str, value: f64) -> PyResult<()> {
+        self.0.fix_matching(pattern, value).map_err(PyErr::from)
+    }
+    fn free_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.free_matching(pattern).map_err(PyErr::from)
+    }
+    fn set_bounds_matching(&mut self, pattern: &str, bounds: (f64, f64)) -> PyResult<()> {
+        self.0
+            .set_bounds_matching(pattern, bounds)
+            .map_err(PyErr::from)
+    }
+    fn set_initial_matching(&mut self, pattern: &str, value: f64) -> PyResult<()> {
+        self.0
+            .set_initial_matching(pattern, value)
+            .map_err(PyErr::from)
+    }
+    fn constrain_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.constrain_matching(pattern).map_err(PyErr::from)
+    }
     fn activate(&mut self, amplitude: &str) -> PyResult<()> {
         self.0.activate(amplitude).map_err(PyErr::from)
     }
@@ -662,6 +1559,24 @@ impl ExtendedLogLikelihood_64 {
     fn deactivate_all(&mut self) {
         self.0.deactivate_all()
     }
+    fn get_sum(&self, name: &str) -> PyResult<NormSqr_64> {
+        self.0
+            .get_sum(name)
+            .map(NormSqr_64::from)
+            .map_err(PyErr::from)
+    }
+    fn activate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.activate_sum(name).map_err(PyErr::from)
+    }
+    fn deactivate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.deactivate_sum(name).map_err(PyErr::from)
+    }
+    fn activation_state(&self) -> ActivationSnapshot {
+        self.0.activation_state().into()
+    }
+    fn restore(&mut self, snapshot: ActivationSnapshot) -> PyResult<()> {
+        self.0.restore(&snapshot.into()).map_err(PyErr::from)
+    }
 }
 
 #[pyclass]
@@ -752,10 +1667,132 @@ impl ExtendedLogLikelihood_32 {
     fn n_free(&self) -> usize {
         self.0.get_n_free()
     }
+    #[getter]
+    fn get_wide_mc_accumulation(&self) -> bool {
+        self.0.wide_mc_accumulation
+    }
+    #[setter]
+    fn set_wide_mc_accumulation(&mut self, value: bool) {
+        self.0.wide_mc_accumulation = value;
+    }
+    #[getter]
+    fn get_deterministic_reduction(&self) -> bool {
+        self.0.deterministic_reduction
+    }
+    #[setter]
+    fn set_deterministic_reduction(&mut self, value: bool) {
+        self.0.deterministic_reduction = value;
+    }
+    #[pyo3(signature = (floor = None, *, drop = false))]
+    fn set_intensity_floor(&mut self, floor: Option<f32>, drop: bool) {
+        self.0.intensity_floor = floor.map(|floor| {
+            if drop {
+                rust::manager::IntensityFloor::Drop(floor)
+            } else {
+                rust::manager::IntensityFloor::Clamp(floor)
+            }
+        });
+    }
+    fn floored_events(&self, parameters: Vec<f32>) -> PyResult<Vec<usize>> {
+        self.0.floored_events(&parameters).map_err(PyErr::from)
+    }
+    #[pyo3(signature = (threshold = None, *, tukey = false))]
+    fn set_robust_loss(&mut self, threshold: Option<f32>, tukey: bool) {
+        self.0.robust_loss = threshold.map(|threshold| {
+            if tukey {
+                rust::manager::RobustLoss::Tukey(threshold)
+            } else {
+                rust::manager::RobustLoss::Cap(threshold)
+            }
+        });
+    }
+    #[getter]
+    fn get_likelihood_kind(&self) -> &'static str {
+        match self.0.kind {
+            rust::manager::LikelihoodKind::Extended => "extended",
+            rust::manager::LikelihoodKind::Unextended => "unextended",
+            rust::manager::LikelihoodKind::Binned(_) => "binned",
+        }
+    }
+    #[setter]
+    fn set_likelihood_kind(&mut self, kind: &str) -> PyResult<()> {
+        self.0.kind = match kind {
+            "extended" => rust::manager::LikelihoodKind::Extended,
+            "unextended" => rust::manager::LikelihoodKind::Unextended,
+            _ => {
+                return Err(PyErr::from(
+                    rust::errors::RustitudeError::InvalidParameterValue(format!(
+                        "expected \"extended\" or \"unextended\" (use set_binned for a binned likelihood), got {kind:?}"
+                    )),
+                ))
+            }
+        };
+        Ok(())
+    }
+    fn set_binned(&mut self, edges: Vec<f32>, variable: Bound<'_, PyAny>) -> PyResult<()> {
+        let values = |dataset: &rust::dataset::Dataset<f32>| -> PyResult<Vec<f32>> {
+            dataset
+                .events
+                .iter()
+                .map(|event| variable.call1((Event_32::from(event.clone()),))?.extract())
+                .collect()
+        };
+        let data_values = values(&self.0.data_manager.dataset)?;
+        let mc_values = values(&self.0.mc_manager.dataset)?;
+        self.0.kind = rust::manager::LikelihoodKind::Binned(rust::manager::Binning::from_values(
+            edges,
+            &data_values,
+            &mc_values,
+        )?);
+        Ok(())
+    }
+    fn evaluate_standard(&self, parameters: Vec<f32>) -> PyResult<f32> {
+        self.0.evaluate_standard(&parameters).map_err(PyErr::from)
+    }
+    #[getter]
+    fn yields(&self) -> Vec<Parameter_32> {
+        self.0
+            .free_parameters()
+            .into_iter()
+            .filter(|p| p.amplitude == rust::manager::YIELD_AMPLITUDE)
+            .map(Parameter_32::from)
+            .collect()
+    }
     #[new]
     fn new(data_manager: Manager_32, mc_manager: Manager_32) -> Self {
         rust::manager::ExtendedLogLikelihood::new(data_manager.into(), mc_manager.into()).into()
     }
+    #[staticmethod]
+    #[pyo3(signature = (data_manager, beam_energy, target_mass, recoil_mass, daughter_masses, parameters, *, precision = 1e-3, max_events = 1 << 20, eps = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn from_phase_space(
+        data_manager: Manager_32,
+        beam_energy: f32,
+        target_mass: f32,
+        recoil_mass: f32,
+        daughter_masses: Vec<f32>,
+        parameters: Vec<f32>,
+        precision: f32,
+        max_events: usize,
+        eps: Option<[f32; 3]>,
+    ) -> PyResult<Self> {
+        let phase_space = rust::phase_space::PhaseSpace::new(
+            beam_energy,
+            target_mass,
+            recoil_mass,
+            daughter_masses,
+        )?
+        .with_polarization(eps.map_or_else(Vector3::zeros, Vector3::from));
+        rust::manager::ExtendedLogLikelihood::from_phase_space(
+            data_manager.into(),
+            &phase_space,
+            &parameters,
+            precision,
+            max_events,
+        )
+        .map(Self)
+        .map_err(PyErr::from)
+    }
     #[pyo3(signature = (parameters, *, indices_data = None, indices_mc = None, parallel = true))]
     fn evaluate(
         &self,
@@ -807,9 +1844,263 @@ impl ExtendedLogLikelihood_32 {
             .map_err(PyErr::from)
         }
     }
-    #[pyo3(signature = (parameters, dataset, *, indices_data = None, indices_mc = None, parallel = true))]
-    fn intensity(
-        &self,
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn gradient(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<Vec<f32>> {
+        if parallel {
+            self.0.par_gradient(&parameters)
+        } else {
+            self.0.gradient(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn evaluate_with_gradient(
+        &self,
+        parameters: Vec<f32>,
+        parallel: bool,
+    ) -> PyResult<(f32, Vec<f32>)> {
+        if parallel {
+            self.0.par_evaluate_with_gradient(&parameters)
+        } else {
+            self.0.evaluate_with_gradient(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn hessian(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<Vec<Vec<f32>>> {
+        if parallel {
+            self.0.par_hessian(&parameters)
+        } else {
+            self.0.hessian(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn weighted_covariance(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<Vec<Vec<f32>>> {
+        if parallel {
+            self.0.par_weighted_covariance(&parameters)
+        } else {
+            self.0.weighted_covariance(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn covariance(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<Vec<Vec<f32>>> {
+        if parallel {
+            self.0.par_covariance(&parameters)
+        } else {
+            self.0.covariance(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (amplitude, parameter, values, *, max_steps = 1000, parallel = true))]
+    fn profile(
+        &self,
+        amplitude: &str,
+        parameter: &str,
+        values: Vec<f32>,
+        max_steps: usize,
+        parallel: bool,
+    ) -> PyResult<Vec<f32>> {
+        if parallel {
+            self.0.par_profile(amplitude, parameter, &values, max_steps)
+        } else {
+            self.0.profile(amplitude, parameter, &values, max_steps)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (n_starts, *, seed = 0, max_steps = 1000, parallel = true))]
+    fn multistart(
+        &self,
+        n_starts: usize,
+        seed: u64,
+        max_steps: usize,
+        parallel: bool,
+    ) -> PyResult<Vec<FitResult_32>> {
+        if parallel {
+            self.0.par_multistart(n_starts, seed, max_steps)
+        } else {
+            self.0.multistart(n_starts, seed, max_steps)
+        }
+        .map(|results| results.into_iter().map(FitResult_32).collect())
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (truth, n_toys, *, seed = 0, source = "accept-reject", pool = None, n_events = None, truth_covariance = None, max_steps = 1000, parallel = true))]
+    #[allow(clippy::too_many_arguments)]
+    fn toy_study(
+        &self,
+        truth: Vec<f32>,
+        n_toys: usize,
+        seed: u64,
+        source: &str,
+        pool: Option<Dataset_32>,
+        n_events: Option<usize>,
+        truth_covariance: Option<Vec<Vec<f32>>>,
+        max_steps: usize,
+        parallel: bool,
+    ) -> PyResult<ToyStudyResult_32> {
+        let source = match source {
+            "accept-reject" => rust::studies::ToySource::AcceptReject,
+            "bootstrap" => rust::studies::ToySource::Bootstrap,
+            _ => {
+                return Err(PyErr::from(
+                    rust::errors::RustitudeError::InvalidParameterValue(format!(
+                        "expected \"accept-reject\" or \"bootstrap\", got {source:?}"
+                    )),
+                ))
+            }
+        };
+        let mut study = rust::studies::ToyStudy::new(self.0.clone(), &truth)?
+            .with_source(source)
+            .with_max_steps(max_steps);
+        if let Some(pool) = pool {
+            study = study.with_pool(pool.into());
+        }
+        if let Some(n_events) = n_events {
+            study = study.with_events(n_events);
+        }
+        if let Some(covariance) = truth_covariance {
+            study = study.with_truth_covariance(&covariance)?;
+        }
+        if parallel {
+            study.par_run(n_toys, seed)
+        } else {
+            study.run(n_toys, seed)
+        }
+        .map(ToyStudyResult_32)
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (best, fx, index, *, delta = 1.0, step = None, max_steps = 1000))]
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    fn minos(
+        &self,
+        best: Vec<f32>,
+        fx: f32,
+        index: usize,
+        delta: f32,
+        step: Option<f32>,
+        max_steps: usize,
+    ) -> PyResult<(Option<f32>, Option<f32>)> {
+        self.0
+            .minos(&best, fx, index, delta, step.unwrap_or(f32::NAN), max_steps)
+            .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, dataset_mc, amplitudes, *, parallel = true))]
+    fn project(
+        &self,
+        parameters: Vec<f32>,
+        dataset_mc: Dataset_32,
+        amplitudes: Vec<String>,
+        parallel: bool,
+    ) -> PyResult<Vec<f32>> {
+        let amplitudes: Vec<&str> = amplitudes.iter().map(String::as_str).collect();
+        if parallel {
+            self.0
+                .par_project(&parameters, &dataset_mc.into(), &amplitudes)
+        } else {
+            self.0.project(&parameters, &dataset_mc.into(), &amplitudes)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, dataset_mc, edges, data_values, mc_values, *, parallel = true))]
+    fn binned_chi2(
+        &self,
+        parameters: Vec<f32>,
+        dataset_mc: Dataset_32,
+        edges: Vec<f32>,
+        data_values: Vec<f32>,
+        mc_values: Vec<f32>,
+        parallel: bool,
+    ) -> PyResult<(f32, usize, f32)> {
+        let dataset_mc = dataset_mc.into();
+        let intensity = if parallel {
+            self.0.par_intensity(&parameters, &dataset_mc)
+        } else {
+            self.0.intensity(&parameters, &dataset_mc)
+        }?;
+        let chi2 = rust::diagnostics::BinnedChiSquare::new(
+            edges,
+            &data_values,
+            Some(&self.0.data_manager.dataset.weights()),
+            &mc_values,
+            Some(&intensity),
+        )?;
+        Ok((chi2.chi2, chi2.ndf, chi2.p_value()))
+    }
+    #[pyo3(signature = (parameters, dataset_mc, amplitudes_a, amplitudes_b, *, parallel = true))]
+    fn interference(
+        &self,
+        parameters: Vec<f32>,
+        dataset_mc: Dataset_32,
+        amplitudes_a: Vec<String>,
+        amplitudes_b: Vec<String>,
+        parallel: bool,
+    ) -> PyResult<Vec<f32>> {
+        let amplitudes_a: Vec<&str> = amplitudes_a.iter().map(String::as_str).collect();
+        let amplitudes_b: Vec<&str> = amplitudes_b.iter().map(String::as_str).collect();
+        if parallel {
+            self.0.par_interference(
+                &parameters,
+                &dataset_mc.into(),
+                &amplitudes_a,
+                &amplitudes_b,
+            )
+        } else {
+            self.0.interference(
+                &parameters,
+                &dataset_mc.into(),
+                &amplitudes_a,
+                &amplitudes_b,
+            )
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, dataset_mc, groups = None, covariance = None, *, parallel = true))]
+    #[allow(clippy::type_complexity)]
+    fn fit_fractions(
+        &self,
+        parameters: Vec<f32>,
+        dataset_mc: Dataset_32,
+        groups: Option<Vec<(String, Vec<String>)>>,
+        covariance: Option<Vec<Vec<f32>>>,
+        parallel: bool,
+    ) -> PyResult<Vec<(String, f32, Option<f32>)>> {
+        let groups = groups.unwrap_or_default();
+        let groups: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|(name, amplitudes)| {
+                (
+                    name.as_str(),
+                    amplitudes.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        if parallel {
+            self.0.par_fit_fractions(
+                &parameters,
+                &dataset_mc.into(),
+                &groups,
+                covariance.as_deref(),
+            )
+        } else {
+            self.0.fit_fractions(
+                &parameters,
+                &dataset_mc.into(),
+                &groups,
+                covariance.as_deref(),
+            )
+        }
+        .map(|fractions| {
+            fractions
+                .into_iter()
+                .map(|fraction| (fraction.name, fraction.value, fraction.error))
+                .collect()
+        })
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, dataset, *, indices_data = None, indices_mc = None, parallel = true))]
+    fn intensity(
+        &self,
         parameters: Vec<f32>,
         dataset: Dataset_32,
         indices_data: Option<Vec<usize>>,
@@ -909,16 +2200,164 @@ impl ExtendedLogLikelihood_32 {
     fn free(&mut self, amplitude: &str, parameter: &str) -> PyResult<()> {
         self.0.free(amplitude, parameter).map_err(PyErr::from)
     }
+    fn add_gaussian_constraint(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f32,
+        sigma: f32,
+    ) -> PyResult<()> {
+        self.0
+            .add_gaussian_constraint(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_prior(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        mean: f32,
+        sigma: f32,
+    ) -> PyResult<()> {
+        self.0
+            .add_prior(amplitude, parameter, mean, sigma)
+            .map_err(PyErr::from)
+    }
+    fn add_penalty(
+        &mut self,
+        parameters: Vec<(String, String)>,
+        function: PyObject,
+    ) -> PyResult<()> {
+        let names: Vec<(&str, &str)> = parameters
+            .iter()
+            .map(|(amplitude, parameter)| (amplitude.as_str(), parameter.as_str()))
+            .collect();
+        self.0
+            .add_penalty(&names, move |values: &[f32]| {
+                Python::with_gil(|py| function.call1(py, (values.to_vec(),))?.extract::<f32>(py))
+                    .map_err(rust::errors::RustitudeError::from)
+            })
+            .map_err(PyErr::from)
+    }
+    fn add_signal_yield(&mut self) {
+        self.0.add_signal_yield()
+    }
+    fn add_background_template(
+        &mut self,
+        data_template: Vec<f32>,
+        mc_template: Vec<f32>,
+    ) -> PyResult<()> {
+        self.0
+            .add_background_template(data_template, mc_template)
+            .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (acceptance = None))]
+    fn set_acceptance(&mut self, acceptance: Option<Vec<f32>>) -> PyResult<()> {
+        self.0.set_acceptance(acceptance).map_err(PyErr::from)
+    }
+    #[getter]
+    fn acceptance(&self) -> Option<Vec<f32>> {
+        self.0.acceptance.clone()
+    }
+    fn average_acceptance(&self, parameters: Vec<f32>) -> PyResult<f32> {
+        self.0.average_acceptance(&parameters).map_err(PyErr::from)
+    }
+    #[pyo3(signature = (dataset = None))]
+    fn set_generated(&mut self, dataset: Option<Dataset_32>) -> PyResult<()> {
+        let dataset: Option<rust::dataset::Dataset<f32>> = dataset.map(Into::into);
+        self.0.set_generated(dataset.as_ref()).map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn efficiency(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<f32> {
+        if parallel {
+            self.0.par_efficiency(&parameters)
+        } else {
+            self.0.efficiency(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, groups = None, *, parallel = true))]
+    fn wave_efficiencies(
+        &self,
+        parameters: Vec<f32>,
+        groups: Option<Vec<(String, Vec<String>)>>,
+        parallel: bool,
+    ) -> PyResult<Vec<(String, f32)>> {
+        let groups = groups.unwrap_or_default();
+        let groups: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|(name, amplitudes)| {
+                (
+                    name.as_str(),
+                    amplitudes.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        if parallel {
+            self.0.par_wave_efficiencies(&parameters, &groups)
+        } else {
+            self.0.wave_efficiencies(&parameters, &groups)
+        }
+        .map_err(PyErr::from)
+    }
+    fn corrected_yield(&self, parameters: Vec<f32>) -> PyResult<f32> {
+        self.0.corrected_yield(&parameters).map_err(PyErr::from)
+    }
+    fn cache_normalization(&mut self) -> PyResult<()> {
+        self.0.cache_normalization().map_err(PyErr::from)
+    }
+    fn clear_normalization_cache(&mut self) {
+        self.0.clear_normalization_cache()
+    }
+    #[getter]
+    fn threads(&self) -> usize {
+        self.0.num_threads()
+    }
+    #[setter]
+    fn set_threads(&mut self, threads: usize) -> PyResult<()> {
+        self.0 = self.0.clone().with_threads(threads)?;
+        Ok(())
+    }
     fn set_bounds(&mut self, amplitude: &str, parameter: &str, bounds: (f32, f32)) -> PyResult<()> {
         self.0
             .set_bounds(amplitude, parameter, bounds)
             .map_err(PyErr::from)
     }
+    #[pyo3(signature = (amplitude, parameter, transform, bounds = None))]
+    fn set_transform(
+        &mut self,
+        amplitude: &str,
+        parameter: &str,
+        transform: Option<&str>,
+        bounds: Option<(f32, f32)>,
+    ) -> PyResult<()> {
+        self.0
+            .set_transform(amplitude, parameter, parse_transform(transform, bounds)?)
+            .map_err(PyErr::from)
+    }
     fn set_initial(&mut self, amplitude: &str, parameter: &str, value: f32) -> PyResult<()> {
         self.0
             .set_initial(amplitude, parameter, value)
             .map_err(PyErr::from)
     }
+    fn fix_matching(&mut self, pattern: &str, value: f32) -> PyResult<()> {
+        self.0.fix_matching(pattern, value).map_err(PyErr::from)
+    }
+    fn free_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.free_matching(pattern).map_err(PyErr::from)
+    }
+    fn set_bounds_matching(&mut self, pattern: &str, bounds: (f32, f32)) -> PyResult<()> {
+        self.0
+            .set_bounds_matching(pattern, bounds)
+            .map_err(PyErr::from)
+    }
+    fn set_initial_matching(&mut self, pattern: &str, value: f32) -> PyResult<()> {
+        self.0
+            .set_initial_matching(pattern, value)
+            .map_err(PyErr::from)
+    }
+    fn constrain_matching(&mut self, pattern: &str) -> PyResult<()> {
+        self.0.constrain_matching(pattern).map_err(PyErr::from)
+    }
     fn activate(&mut self, amplitude: &str) -> PyResult<()> {
         self.0.activate(amplitude).map_err(PyErr::from)
     }
@@ -936,6 +2375,24 @@ impl ExtendedLogLikelihood_32 {
     fn deactivate_all(&mut self) {
         self.0.deactivate_all()
     }
+    fn get_sum(&self, name: &str) -> PyResult<NormSqr_32> {
+        self.0
+            .get_sum(name)
+            .map(NormSqr_32::from)
+            .map_err(PyErr::from)
+    }
+    fn activate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.activate_sum(name).map_err(PyErr::from)
+    }
+    fn deactivate_sum(&mut self, name: &str) -> PyResult<()> {
+        self.0.deactivate_sum(name).map_err(PyErr::from)
+    }
+    fn activation_state(&self) -> ActivationSnapshot {
+        self.0.activation_state().into()
+    }
+    fn restore(&mut self, snapshot: ActivationSnapshot) -> PyResult<()> {
+        self.0.restore(&snapshot.into()).map_err(PyErr::from)
+    }
 }
 
 #[pyclass]
@@ -1006,8 +2463,23 @@ impl NelderMead_64 {
     fn check_for_termination(&self) -> bool {
         self.0.check_for_termination()
     }
-    fn minimize(&mut self, steps: usize) -> PyResult<()> {
-        self.0.minimize(None, steps, |_| {}).map_err(PyErr::from)
+    #[pyo3(signature = (steps, *, callback = None))]
+    fn minimize(&mut self, steps: usize, callback: Option<PyObject>) -> PyResult<()> {
+        let Some(callback) = callback else {
+            return self.0.minimize(None, steps, |_| {}).map_err(PyErr::from);
+        };
+        // the loop from `Minimizer::minimize`, which lets errors raised by the callback stop it
+        self.0.initialize(None)?;
+        for step in 1..=steps {
+            self.0.step(None)?;
+            self.0.update_best();
+            let (x_best, fx_best) = self.best();
+            Python::with_gil(|py| callback.call1(py, (step, fx_best, x_best)))?;
+            if self.0.check_for_termination() {
+                break;
+            }
+        }
+        Ok(())
     }
     fn best(&self) -> (Vec<f64>, f64) {
         let (x_best, fx_best) = self.0.best();
@@ -1083,8 +2555,23 @@ impl NelderMead_32 {
     fn check_for_termination(&self) -> bool {
         self.0.check_for_termination()
     }
-    fn minimize(&mut self, steps: usize) -> PyResult<()> {
-        self.0.minimize(None, steps, |_| {}).map_err(PyErr::from)
+    #[pyo3(signature = (steps, *, callback = None))]
+    fn minimize(&mut self, steps: usize, callback: Option<PyObject>) -> PyResult<()> {
+        let Some(callback) = callback else {
+            return self.0.minimize(None, steps, |_| {}).map_err(PyErr::from);
+        };
+        // the loop from `Minimizer::minimize`, which lets errors raised by the callback stop it
+        self.0.initialize(None)?;
+        for step in 1..=steps {
+            self.0.step(None)?;
+            self.0.update_best();
+            let (x_best, fx_best) = self.best();
+            Python::with_gil(|py| callback.call1(py, (step, fx_best, x_best)))?;
+            if self.0.check_for_termination() {
+                break;
+            }
+        }
+        Ok(())
     }
     fn best(&self) -> (Vec<f32>, f32) {
         let (x_best, fx_best) = self.0.best();
@@ -1092,6 +2579,1236 @@ impl NelderMead_32 {
     }
 }
 
+#[pyclass]
+#[derive(Clone)]
+pub struct FitResult_64(rust::fit::FitResult<f64>);
+impl_convert!(FitResult_64, rust::fit::FitResult<f64>);
+
+#[pymethods]
+impl FitResult_64 {
+    #[new]
+    #[pyo3(signature = (ell, best, fx, covariance = None))]
+    fn new(
+        ell: &ExtendedLogLikelihood_64,
+        best: Vec<f64>,
+        fx: f64,
+        covariance: Option<Vec<Vec<f64>>>,
+    ) -> PyResult<Self> {
+        rust::fit::FitResult::new(&ell.0, &best, fx, covariance)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    #[getter]
+    fn version(&self) -> String {
+        self.0.version.clone()
+    }
+    #[getter]
+    fn timestamp(&self) -> u64 {
+        self.0.timestamp
+    }
+    #[getter]
+    fn fx(&self) -> f64 {
+        self.0.fx
+    }
+    #[getter]
+    fn standard_fx(&self) -> Option<f64> {
+        self.0.standard_fx
+    }
+    #[getter]
+    fn best(&self) -> Vec<f64> {
+        self.0.best.clone()
+    }
+    #[getter]
+    fn covariance(&self) -> Option<Vec<Vec<f64>>> {
+        self.0.covariance.clone()
+    }
+    #[getter]
+    fn errors(&self) -> Option<Vec<f64>> {
+        self.0.errors()
+    }
+    #[getter]
+    fn correlation(&self) -> Option<Vec<Vec<f64>>> {
+        self.0.correlation()
+    }
+    #[getter]
+    #[allow(clippy::type_complexity)]
+    fn minos_errors(&self) -> Option<Vec<(Option<f64>, Option<f64>)>> {
+        self.0.minos_errors.clone()
+    }
+    #[getter]
+    fn parameters(&self) -> Vec<Parameter_64> {
+        self.0
+            .parameters
+            .iter()
+            .cloned()
+            .map(Parameter_64::from)
+            .collect()
+    }
+    #[getter]
+    fn free_parameters(&self) -> Vec<Parameter_64> {
+        self.0
+            .free_parameters()
+            .into_iter()
+            .cloned()
+            .map(Parameter_64::from)
+            .collect()
+    }
+    fn value(&self, amplitude: &str, parameter: &str) -> PyResult<f64> {
+        self.0.value(amplitude, parameter).map_err(PyErr::from)
+    }
+    #[pyo3(signature = (ell, dataset_mc, groups = None))]
+    #[allow(clippy::type_complexity)]
+    fn fit_fractions(
+        &self,
+        ell: &ExtendedLogLikelihood_64,
+        dataset_mc: Dataset_64,
+        groups: Option<Vec<(String, Vec<String>)>>,
+    ) -> PyResult<Vec<(String, f64, Option<f64>)>> {
+        ell.fit_fractions(
+            self.0.best.clone(),
+            dataset_mc,
+            groups,
+            self.0.covariance.clone(),
+            true,
+        )
+    }
+    fn aic(&self) -> f64 {
+        rust::diagnostics::aic(&self.0)
+    }
+    fn bic(&self) -> f64 {
+        rust::diagnostics::bic(&self.0)
+    }
+    fn warm_start(&self, ell: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+        if let Ok(ell) = ell.downcast::<ExtendedLogLikelihood_64>() {
+            Ok(self.0.warm_start(&mut ell.borrow_mut().0)?)
+        } else {
+            let ell = ell.downcast::<ExtendedLogLikelihood_32>()?;
+            Ok(self
+                .0
+                .warm_start(&mut ell.borrow_mut().0)?
+                .into_iter()
+                .map(f64::from)
+                .collect())
+        }
+    }
+    fn restore(&self, ell: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+        if let Ok(ell) = ell.downcast::<ExtendedLogLikelihood_64>() {
+            Ok(self.0.restore(&mut ell.borrow_mut().0)?)
+        } else {
+            let ell = ell.downcast::<ExtendedLogLikelihood_32>()?;
+            Ok(self
+                .0
+                .restore(&mut ell.borrow_mut().0)?
+                .into_iter()
+                .map(f64::from)
+                .collect())
+        }
+    }
+    #[getter]
+    fn indices(&self) -> Vec<(String, String, usize)> {
+        self.0.indices.clone()
+    }
+    fn likelihood_ratio_test(&self, alternative: &Self) -> PyResult<(f64, usize, f64)> {
+        let test = rust::diagnostics::likelihood_ratio_test(&self.0, &alternative.0)?;
+        Ok((test.statistic, test.ndf, test.p_value))
+    }
+    fn error(&self, amplitude: &str, parameter: &str) -> PyResult<Option<f64>> {
+        self.0.error(amplitude, parameter).map_err(PyErr::from)
+    }
+    #[allow(clippy::type_complexity)]
+    fn minos_error(
+        &self,
+        amplitude: &str,
+        parameter: &str,
+    ) -> PyResult<Option<(Option<f64>, Option<f64>)>> {
+        self.0
+            .minos_error(amplitude, parameter)
+            .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (ell, *, delta = 1.0, max_steps = 1000))]
+    fn compute_minos_errors(
+        &mut self,
+        ell: &ExtendedLogLikelihood_64,
+        delta: f64,
+        max_steps: usize,
+    ) -> PyResult<()> {
+        self.0
+            .compute_minos_errors(&ell.0, delta, max_steps)
+            .map_err(PyErr::from)
+    }
+    fn to_json(&self) -> PyResult<String> {
+        self.0.to_json().map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        rust::fit::FitResult::from_json(json)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct FitResult_32(rust::fit::FitResult<f32>);
+impl_convert!(FitResult_32, rust::fit::FitResult<f32>);
+
+#[pymethods]
+impl FitResult_32 {
+    #[new]
+    #[pyo3(signature = (ell, best, fx, covariance = None))]
+    fn new(
+        ell: &ExtendedLogLikelihood_32,
+        best: Vec<f32>,
+        fx: f32,
+        covariance: Option<Vec<Vec<f32>>>,
+    ) -> PyResult<Self> {
+        rust::fit::FitResult::new(&ell.0, &best, fx, covariance)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    #[getter]
+    fn version(&self) -> String {
+        self.0.version.clone()
+    }
+    #[getter]
+    fn timestamp(&self) -> u64 {
+        self.0.timestamp
+    }
+    #[getter]
+    fn fx(&self) -> f32 {
+        self.0.fx
+    }
+    #[getter]
+    fn standard_fx(&self) -> Option<f32> {
+        self.0.standard_fx
+    }
+    #[getter]
+    fn best(&self) -> Vec<f32> {
+        self.0.best.clone()
+    }
+    #[getter]
+    fn covariance(&self) -> Option<Vec<Vec<f32>>> {
+        self.0.covariance.clone()
+    }
+    #[getter]
+    fn errors(&self) -> Option<Vec<f32>> {
+        self.0.errors()
+    }
+    #[getter]
+    fn correlation(&self) -> Option<Vec<Vec<f32>>> {
+        self.0.correlation()
+    }
+    #[getter]
+    #[allow(clippy::type_complexity)]
+    fn minos_errors(&self) -> Option<Vec<(Option<f32>, Option<f32>)>> {
+        self.0.minos_errors.clone()
+    }
+    #[getter]
+    fn parameters(&self) -> Vec<Parameter_32> {
+        self.0
+            .parameters
+            .iter()
+            .cloned()
+            .map(Parameter_32::from)
+            .collect()
+    }
+    #[getter]
+    fn free_parameters(&self) -> Vec<Parameter_32> {
+        self.0
+            .free_parameters()
+            .into_iter()
+            .cloned()
+            .map(Parameter_32::from)
+            .collect()
+    }
+    fn value(&self, amplitude: &str, parameter: &str) -> PyResult<f32> {
+        self.0.value(amplitude, parameter).map_err(PyErr::from)
+    }
+    #[pyo3(signature = (ell, dataset_mc, groups = None))]
+    #[allow(clippy::type_complexity)]
+    fn fit_fractions(
+        &self,
+        ell: &ExtendedLogLikelihood_32,
+        dataset_mc: Dataset_32,
+        groups: Option<Vec<(String, Vec<String>)>>,
+    ) -> PyResult<Vec<(String, f32, Option<f32>)>> {
+        ell.fit_fractions(
+            self.0.best.clone(),
+            dataset_mc,
+            groups,
+            self.0.covariance.clone(),
+            true,
+        )
+    }
+    fn aic(&self) -> f32 {
+        rust::diagnostics::aic(&self.0)
+    }
+    fn bic(&self) -> f32 {
+        rust::diagnostics::bic(&self.0)
+    }
+    fn warm_start(&self, ell: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+        if let Ok(ell) = ell.downcast::<ExtendedLogLikelihood_64>() {
+            Ok(self.0.warm_start(&mut ell.borrow_mut().0)?)
+        } else {
+            let ell = ell.downcast::<ExtendedLogLikelihood_32>()?;
+            Ok(self
+                .0
+                .warm_start(&mut ell.borrow_mut().0)?
+                .into_iter()
+                .map(f64::from)
+                .collect())
+        }
+    }
+    fn restore(&self, ell: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+        if let Ok(ell) = ell.downcast::<ExtendedLogLikelihood_64>() {
+            Ok(self.0.restore(&mut ell.borrow_mut().0)?)
+        } else {
+            let ell = ell.downcast::<ExtendedLogLikelihood_32>()?;
+            Ok(self
+                .0
+                .restore(&mut ell.borrow_mut().0)?
+                .into_iter()
+                .map(f64::from)
+                .collect())
+        }
+    }
+    #[getter]
+    fn indices(&self) -> Vec<(String, String, usize)> {
+        self.0.indices.clone()
+    }
+    fn likelihood_ratio_test(&self, alternative: &Self) -> PyResult<(f32, usize, f32)> {
+        let test = rust::diagnostics::likelihood_ratio_test(&self.0, &alternative.0)?;
+        Ok((test.statistic, test.ndf, test.p_value))
+    }
+    fn error(&self, amplitude: &str, parameter: &str) -> PyResult<Option<f32>> {
+        self.0.error(amplitude, parameter).map_err(PyErr::from)
+    }
+    #[allow(clippy::type_complexity)]
+    fn minos_error(
+        &self,
+        amplitude: &str,
+        parameter: &str,
+    ) -> PyResult<Option<(Option<f32>, Option<f32>)>> {
+        self.0
+            .minos_error(amplitude, parameter)
+            .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (ell, *, delta = 1.0, max_steps = 1000))]
+    fn compute_minos_errors(
+        &mut self,
+        ell: &ExtendedLogLikelihood_32,
+        delta: f32,
+        max_steps: usize,
+    ) -> PyResult<()> {
+        self.0
+            .compute_minos_errors(&ell.0, delta, max_steps)
+            .map_err(PyErr::from)
+    }
+    fn to_json(&self) -> PyResult<String> {
+        self.0.to_json().map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        rust::fit::FitResult::from_json(json)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct Ensemble_64(rust::ensemble::Ensemble<f64>);
+impl_convert!(Ensemble_64, rust::ensemble::Ensemble<f64>);
+
+#[pymethods]
+impl Ensemble_64 {
+    #[new]
+    #[pyo3(signature = (names, samples, weights = None, chains = None))]
+    fn new(
+        names: Vec<String>,
+        samples: Vec<Vec<f64>>,
+        weights: Option<Vec<f64>>,
+        chains: Option<Vec<usize>>,
+    ) -> PyResult<Self> {
+        let ensemble = rust::ensemble::Ensemble::new(names, samples, weights)?;
+        match chains {
+            Some(chains) => ensemble.with_chains(chains),
+            None => Ok(ensemble),
+        }
+        .map(Self)
+        .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_fit_results(results: Vec<FitResult_64>) -> PyResult<Self> {
+        let results: Vec<rust::fit::FitResult<f64>> = results.into_iter().map(|r| r.0).collect();
+        rust::ensemble::Ensemble::from_fit_results(&results)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+    #[getter]
+    fn names(&self) -> Vec<String> {
+        self.0.names.clone()
+    }
+    #[getter]
+    fn samples(&self) -> Vec<Vec<f64>> {
+        self.0.samples.clone()
+    }
+    #[getter]
+    fn weights(&self) -> Vec<f64> {
+        self.0.weights.clone()
+    }
+    #[getter]
+    fn chains(&self) -> Vec<usize> {
+        self.0.chains.clone()
+    }
+    #[getter]
+    fn effective_size(&self) -> f64 {
+        self.0.effective_size()
+    }
+    #[getter]
+    fn mean(&self) -> Vec<f64> {
+        self.0.mean()
+    }
+    #[getter]
+    fn std(&self) -> Vec<f64> {
+        self.0.std()
+    }
+    #[getter]
+    fn covariance(&self) -> Vec<Vec<f64>> {
+        self.0.covariance()
+    }
+    #[getter]
+    fn correlation(&self) -> Vec<Vec<f64>> {
+        self.0.correlation()
+    }
+    fn index(&self, name: &str) -> PyResult<usize> {
+        self.0.index(name).map_err(PyErr::from)
+    }
+    fn quantile(&self, name: &str, q: f64) -> PyResult<f64> {
+        self.0.quantile(self.0.index(name)?, q).map_err(PyErr::from)
+    }
+    fn median(&self, name: &str) -> PyResult<f64> {
+        self.0.median(self.0.index(name)?).map_err(PyErr::from)
+    }
+    fn hpd(&self, name: &str, probability: f64) -> PyResult<(f64, f64)> {
+        self.0
+            .hpd(self.0.index(name)?, probability)
+            .map_err(PyErr::from)
+    }
+    fn to_arviz_dict(&self) -> HashMap<String, Vec<Vec<f64>>> {
+        self.0
+            .names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), self.0.chain_draws(i)))
+            .collect()
+    }
+    fn to_json(&self) -> PyResult<String> {
+        self.0.to_json().map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        rust::ensemble::Ensemble::from_json(json)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct Ensemble_32(rust::ensemble::Ensemble<f32>);
+impl_convert!(Ensemble_32, rust::ensemble::Ensemble<f32>);
+
+#[pymethods]
+impl Ensemble_32 {
+    #[new]
+    #[pyo3(signature = (names, samples, weights = None, chains = None))]
+    fn new(
+        names: Vec<String>,
+        samples: Vec<Vec<f32>>,
+        weights: Option<Vec<f32>>,
+        chains: Option<Vec<usize>>,
+    ) -> PyResult<Self> {
+        let ensemble = rust::ensemble::Ensemble::new(names, samples, weights)?;
+        match chains {
+            Some(chains) => ensemble.with_chains(chains),
+            None => Ok(ensemble),
+        }
+        .map(Self)
+        .map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_fit_results(results: Vec<FitResult_32>) -> PyResult<Self> {
+        let results: Vec<rust::fit::FitResult<f32>> = results.into_iter().map(|r| r.0).collect();
+        rust::ensemble::Ensemble::from_fit_results(&results)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+    #[getter]
+    fn names(&self) -> Vec<String> {
+        self.0.names.clone()
+    }
+    #[getter]
+    fn samples(&self) -> Vec<Vec<f32>> {
+        self.0.samples.clone()
+    }
+    #[getter]
+    fn weights(&self) -> Vec<f32> {
+        self.0.weights.clone()
+    }
+    #[getter]
+    fn chains(&self) -> Vec<usize> {
+        self.0.chains.clone()
+    }
+    #[getter]
+    fn effective_size(&self) -> f32 {
+        self.0.effective_size()
+    }
+    #[getter]
+    fn mean(&self) -> Vec<f32> {
+        self.0.mean()
+    }
+    #[getter]
+    fn std(&self) -> Vec<f32> {
+        self.0.std()
+    }
+    #[getter]
+    fn covariance(&self) -> Vec<Vec<f32>> {
+        self.0.covariance()
+    }
+    #[getter]
+    fn correlation(&self) -> Vec<Vec<f32>> {
+        self.0.correlation()
+    }
+    fn index(&self, name: &str) -> PyResult<usize> {
+        self.0.index(name).map_err(PyErr::from)
+    }
+    fn quantile(&self, name: &str, q: f32) -> PyResult<f32> {
+        self.0.quantile(self.0.index(name)?, q).map_err(PyErr::from)
+    }
+    fn median(&self, name: &str) -> PyResult<f32> {
+        self.0.median(self.0.index(name)?).map_err(PyErr::from)
+    }
+    fn hpd(&self, name: &str, probability: f32) -> PyResult<(f32, f32)> {
+        self.0
+            .hpd(self.0.index(name)?, probability)
+            .map_err(PyErr::from)
+    }
+    fn to_arviz_dict(&self) -> HashMap<String, Vec<Vec<f32>>> {
+        self.0
+            .names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), self.0.chain_draws(i)))
+            .collect()
+    }
+    fn to_json(&self) -> PyResult<String> {
+        self.0.to_json().map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        rust::ensemble::Ensemble::from_json(json)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct ToyStudyResult_64(rust::studies::ToyStudyResult<f64>);
+impl_convert!(ToyStudyResult_64, rust::studies::ToyStudyResult<f64>);
+
+#[pymethods]
+impl ToyStudyResult_64 {
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+    #[getter]
+    fn names(&self) -> Vec<String> {
+        self.0.names.clone()
+    }
+    #[getter]
+    fn truths(&self) -> Vec<Vec<f64>> {
+        self.0.toys.iter().map(|toy| toy.truth.clone()).collect()
+    }
+    #[getter]
+    fn n_events(&self) -> Vec<usize> {
+        self.0.toys.iter().map(|toy| toy.n_events).collect()
+    }
+    #[getter]
+    fn errors(&self) -> Vec<Vec<f64>> {
+        self.0.toys.iter().map(|toy| toy.errors.clone()).collect()
+    }
+    #[getter]
+    fn fits(&self) -> Vec<FitResult_64> {
+        self.0
+            .toys
+            .iter()
+            .map(|toy| FitResult_64(toy.fit.clone()))
+            .collect()
+    }
+    fn biases(&self, name: &str) -> PyResult<Vec<f64>> {
+        Ok(self.0.biases(self.0.index(name)?))
+    }
+    fn pulls(&self, name: &str) -> PyResult<Vec<f64>> {
+        Ok(self.0.pulls(self.0.index(name)?))
+    }
+    fn summary<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.0
+            .summary()
+            .into_iter()
+            .map(|summary| {
+                let dict = PyDict::new_bound(py);
+                dict.set_item("name", summary.name)?;
+                dict.set_item("n_toys", summary.n_toys)?;
+                dict.set_item("bias_mean", summary.bias_mean)?;
+                dict.set_item("bias_mean_error", summary.bias_mean_error)?;
+                dict.set_item("bias_std", summary.bias_std)?;
+                dict.set_item("pull_mean", summary.pull_mean)?;
+                dict.set_item("pull_mean_error", summary.pull_mean_error)?;
+                dict.set_item("pull_std", summary.pull_std)?;
+                dict.set_item("pull_std_error", summary.pull_std_error)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+    fn to_json(&self) -> PyResult<String> {
+        self.0.to_json().map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        rust::studies::ToyStudyResult::from_json(json)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct ToyStudyResult_32(rust::studies::ToyStudyResult<f32>);
+impl_convert!(ToyStudyResult_32, rust::studies::ToyStudyResult<f32>);
+
+#[pymethods]
+impl ToyStudyResult_32 {
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+    #[getter]
+    fn names(&self) -> Vec<String> {
+        self.0.names.clone()
+    }
+    #[getter]
+    fn truths(&self) -> Vec<Vec<f32>> {
+        self.0.toys.iter().map(|toy| toy.truth.clone()).collect()
+    }
+    #[getter]
+    fn n_events(&self) -> Vec<usize> {
+        self.0.toys.iter().map(|toy| toy.n_events).collect()
+    }
+    #[getter]
+    fn errors(&self) -> Vec<Vec<f32>> {
+        self.0.toys.iter().map(|toy| toy.errors.clone()).collect()
+    }
+    #[getter]
+    fn fits(&self) -> Vec<FitResult_32> {
+        self.0
+            .toys
+            .iter()
+            .map(|toy| FitResult_32(toy.fit.clone()))
+            .collect()
+    }
+    fn biases(&self, name: &str) -> PyResult<Vec<f32>> {
+        Ok(self.0.biases(self.0.index(name)?))
+    }
+    fn pulls(&self, name: &str) -> PyResult<Vec<f32>> {
+        Ok(self.0.pulls(self.0.index(name)?))
+    }
+    fn summary<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.0
+            .summary()
+            .into_iter()
+            .map(|summary| {
+                let dict = PyDict::new_bound(py);
+                dict.set_item("name", summary.name)?;
+                dict.set_item("n_toys", summary.n_toys)?;
+                dict.set_item("bias_mean", summary.bias_mean)?;
+                dict.set_item("bias_mean_error", summary.bias_mean_error)?;
+                dict.set_item("bias_std", summary.bias_std)?;
+                dict.set_item("pull_mean", summary.pull_mean)?;
+                dict.set_item("pull_mean_error", summary.pull_mean_error)?;
+                dict.set_item("pull_std", summary.pull_std)?;
+                dict.set_item("pull_std_error", summary.pull_std_error)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+    fn to_json(&self) -> PyResult<String> {
+        self.0.to_json().map_err(PyErr::from)
+    }
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        rust::studies::ToyStudyResult::from_json(json)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (enabled = true))]
+fn set_precalculation_cache(enabled: bool) {
+    rust::cache::PrecalculationCache::<f64>::global().set_enabled(enabled);
+    rust::cache::PrecalculationCache::<f32>::global().set_enabled(enabled);
+}
+
+#[pyfunction]
+fn clear_precalculation_cache() {
+    rust::cache::PrecalculationCache::<f64>::global().clear();
+    rust::cache::PrecalculationCache::<f32>::global().clear();
+}
+
+#[pyfunction]
+#[pyo3(signature = (enabled = true))]
+fn set_profiling(enabled: bool) {
+    if enabled {
+        rust::profiling::enable();
+    } else {
+        rust::profiling::disable();
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (values, edges, weights = None))]
+fn histogram(
+    values: Vec<f64>,
+    edges: Vec<f64>,
+    weights: Option<Vec<f64>>,
+) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let hist = rust::histogram::Histogram::new(edges, &values, weights.as_deref())?;
+    let errors = hist.errors();
+    Ok((hist.contents, errors))
+}
+
+#[pyfunction]
+fn reset_profiling() {
+    rust::profiling::reset();
+}
+
+#[pyfunction]
+fn profiling_report() -> Vec<(String, String, u64, f64)> {
+    rust::profiling::report()
+        .entries
+        .into_iter()
+        .map(|entry| {
+            (
+                entry.stage.to_string(),
+                entry.name,
+                entry.calls,
+                entry.total.as_secs_f64(),
+            )
+        })
+        .collect()
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct SimultaneousLikelihood_64(rust::simultaneous::SimultaneousLikelihood<f64>);
+impl_convert!(
+    SimultaneousLikelihood_64,
+    rust::simultaneous::SimultaneousLikelihood<f64>
+);
+
+#[pymethods]
+impl SimultaneousLikelihood_64 {
+    #[new]
+    fn new(terms: Vec<(String, PyRef<ExtendedLogLikelihood_64>)>) -> PyResult<Self> {
+        rust::simultaneous::SimultaneousLikelihood::new(
+            terms
+                .iter()
+                .map(|(label, ell)| (label.as_str(), ell.0.clone()))
+                .collect(),
+        )
+        .map(Self)
+        .map_err(PyErr::from)
+    }
+    #[getter]
+    fn labels(&self) -> Vec<String> {
+        self.0.labels().into_iter().map(String::from).collect()
+    }
+    #[getter]
+    fn terms(&self) -> Vec<(String, ExtendedLogLikelihood_64)> {
+        self.0
+            .terms()
+            .iter()
+            .map(|(label, ell)| (label.clone(), ell.clone().into()))
+            .collect()
+    }
+    #[getter]
+    fn parameter_names(&self) -> Vec<String> {
+        self.0.parameter_names()
+    }
+    #[getter]
+    fn bounds(&self) -> Vec<(f64, f64)> {
+        self.0.get_bounds()
+    }
+    #[getter]
+    fn initial(&self) -> Vec<f64> {
+        self.0.get_initial()
+    }
+    #[getter]
+    fn n_free(&self) -> usize {
+        self.0.get_n_free()
+    }
+    fn scope(&mut self, amplitude: &str, parameter: &str) -> PyResult<()> {
+        self.0.scope(amplitude, parameter).map_err(PyErr::from)
+    }
+    fn share(&mut self, amplitude: &str, parameter: &str) -> PyResult<()> {
+        self.0.share(amplitude, parameter).map_err(PyErr::from)
+    }
+    fn is_scoped(&self, amplitude: &str, parameter: &str) -> bool {
+        self.0.is_scoped(amplitude, parameter)
+    }
+    #[pyo3(signature = (reference, sigma = None))]
+    fn add_normalizations(&mut self, reference: &str, sigma: Option<f64>) -> PyResult<()> {
+        self.0
+            .add_normalizations(reference, sigma)
+            .map_err(PyErr::from)
+    }
+    fn remove_normalizations(&mut self) {
+        self.0.remove_normalizations();
+    }
+    fn term_parameters(&self, label: &str, parameters: Vec<f64>) -> PyResult<Vec<f64>> {
+        self.0
+            .term_parameters(label, &parameters)
+            .map_err(PyErr::from)
+    }
+    #[pyo3(name = "__call__", signature = (parameters, *, parallel = true))]
+    fn call(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<f64> {
+        self.evaluate(parameters, parallel)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn evaluate(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<f64> {
+        if parallel {
+            self.0.par_evaluate(&parameters)
+        } else {
+            self.0.evaluate(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn gradient(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<Vec<f64>> {
+        if parallel {
+            self.0.par_gradient(&parameters)
+        } else {
+            self.0.gradient(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct SimultaneousLikelihood_32(rust::simultaneous::SimultaneousLikelihood<f32>);
+impl_convert!(
+    SimultaneousLikelihood_32,
+    rust::simultaneous::SimultaneousLikelihood<f32>
+);
+
+#[pymethods]
+impl SimultaneousLikelihood_32 {
+    #[new]
+    fn new(terms: Vec<(String, PyRef<ExtendedLogLikelihood_32>)>) -> PyResult<Self> {
+        rust::simultaneous::SimultaneousLikelihood::new(
+            terms
+                .iter()
+                .map(|(label, ell)| (label.as_str(), ell.0.clone()))
+                .collect(),
+        )
+        .map(Self)
+        .map_err(PyErr::from)
+    }
+    #[getter]
+    fn labels(&self) -> Vec<String> {
+        self.0.labels().into_iter().map(String::from).collect()
+    }
+    #[getter]
+    fn terms(&self) -> Vec<(String, ExtendedLogLikelihood_32)> {
+        self.0
+            .terms()
+            .iter()
+            .map(|(label, ell)| (label.clone(), ell.clone().into()))
+            .collect()
+    }
+    #[getter]
+    fn parameter_names(&self) -> Vec<String> {
+        self.0.parameter_names()
+    }
+    #[getter]
+    fn bounds(&self) -> Vec<(f32, f32)> {
+        self.0.get_bounds()
+    }
+    #[getter]
+    fn initial(&self) -> Vec<f32> {
+        self.0.get_initial()
+    }
+    #[getter]
+    fn n_free(&self) -> usize {
+        self.0.get_n_free()
+    }
+    fn scope(&mut self, amplitude: &str, parameter: &str) -> PyResult<()> {
+        self.0.scope(amplitude, parameter).map_err(PyErr::from)
+    }
+    fn share(&mut self, amplitude: &str, parameter: &str) -> PyResult<()> {
+        self.0.share(amplitude, parameter).map_err(PyErr::from)
+    }
+    fn is_scoped(&self, amplitude: &str, parameter: &str) -> bool {
+        self.0.is_scoped(amplitude, parameter)
+    }
+    #[pyo3(signature = (reference, sigma = None))]
+    fn add_normalizations(&mut self, reference: &str, sigma: Option<f32>) -> PyResult<()> {
+        self.0
+            .add_normalizations(reference, sigma)
+            .map_err(PyErr::from)
+    }
+    fn remove_normalizations(&mut self) {
+        self.0.remove_normalizations();
+    }
+    fn term_parameters(&self, label: &str, parameters: Vec<f32>) -> PyResult<Vec<f32>> {
+        self.0
+            .term_parameters(label, &parameters)
+            .map_err(PyErr::from)
+    }
+    #[pyo3(name = "__call__", signature = (parameters, *, parallel = true))]
+    fn call(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<f32> {
+        self.evaluate(parameters, parallel)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn evaluate(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<f32> {
+        if parallel {
+            self.0.par_evaluate(&parameters)
+        } else {
+            self.0.evaluate(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn gradient(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<Vec<f32>> {
+        if parallel {
+            self.0.par_gradient(&parameters)
+        } else {
+            self.0.gradient(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct LinearLikelihood_64(rust::linear::LinearLikelihood<f64>);
+impl_convert!(LinearLikelihood_64, rust::linear::LinearLikelihood<f64>);
+
+#[pymethods]
+impl LinearLikelihood_64 {
+    #[new]
+    #[pyo3(signature = (ell, *, storage = "full"))]
+    fn new(ell: PyRef<ExtendedLogLikelihood_64>, storage: &str) -> PyResult<Self> {
+        let storage = storage
+            .parse::<rust::linear::StoragePrecision>()
+            .map_err(PyErr::from)?;
+        rust::linear::LinearLikelihood::new_with_storage(&ell.0, storage)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    #[getter]
+    fn storage(&self) -> String {
+        self.0.storage().to_string()
+    }
+    #[getter]
+    fn n_terms(&self) -> usize {
+        self.0.n_terms()
+    }
+    #[getter]
+    fn bounds(&self) -> Vec<(f64, f64)> {
+        self.0.get_bounds()
+    }
+    #[getter]
+    fn initial(&self) -> Vec<f64> {
+        self.0.get_initial()
+    }
+    #[getter]
+    fn n_free(&self) -> usize {
+        self.0.get_n_free()
+    }
+    #[pyo3(name = "__call__", signature = (parameters, *, parallel = true))]
+    fn call(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<f64> {
+        self.evaluate(parameters, parallel)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn evaluate(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<f64> {
+        if parallel {
+            self.0.par_evaluate(&parameters)
+        } else {
+            self.0.evaluate(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn gradient(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<Vec<f64>> {
+        if parallel {
+            self.0.par_gradient(&parameters)
+        } else {
+            self.0.gradient(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct LinearLikelihood_32(rust::linear::LinearLikelihood<f32>);
+impl_convert!(LinearLikelihood_32, rust::linear::LinearLikelihood<f32>);
+
+#[pymethods]
+impl LinearLikelihood_32 {
+    #[new]
+    #[pyo3(signature = (ell, *, storage = "full"))]
+    fn new(ell: PyRef<ExtendedLogLikelihood_32>, storage: &str) -> PyResult<Self> {
+        let storage = storage
+            .parse::<rust::linear::StoragePrecision>()
+            .map_err(PyErr::from)?;
+        rust::linear::LinearLikelihood::new_with_storage(&ell.0, storage)
+            .map(Self)
+            .map_err(PyErr::from)
+    }
+    #[getter]
+    fn storage(&self) -> String {
+        self.0.storage().to_string()
+    }
+    #[getter]
+    fn n_terms(&self) -> usize {
+        self.0.n_terms()
+    }
+    #[getter]
+    fn bounds(&self) -> Vec<(f32, f32)> {
+        self.0.get_bounds()
+    }
+    #[getter]
+    fn initial(&self) -> Vec<f32> {
+        self.0.get_initial()
+    }
+    #[getter]
+    fn n_free(&self) -> usize {
+        self.0.get_n_free()
+    }
+    #[pyo3(name = "__call__", signature = (parameters, *, parallel = true))]
+    fn call(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<f32> {
+        self.evaluate(parameters, parallel)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn evaluate(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<f32> {
+        if parallel {
+            self.0.par_evaluate(&parameters)
+        } else {
+            self.0.evaluate(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn gradient(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<Vec<f32>> {
+        if parallel {
+            self.0.par_gradient(&parameters)
+        } else {
+            self.0.gradient(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct BinnedChi2_64(rust::binned::BinnedChi2<f64>);
+impl_convert!(BinnedChi2_64, rust::binned::BinnedChi2<f64>);
+
+#[pymethods]
+impl BinnedChi2_64 {
+    #[new]
+    fn new(
+        data_manager: Manager_64,
+        mc_manager: Manager_64,
+        edges: Vec<Vec<f64>>,
+        variables: Bound<'_, PyAny>,
+    ) -> PyResult<Self> {
+        let values = |manager: &Manager_64| -> PyResult<Vec<Vec<f64>>> {
+            manager
+                .0
+                .dataset
+                .events
+                .iter()
+                .map(|event| variables.call1((Event_64::from(event.clone()),))?.extract())
+                .collect()
+        };
+        let data_values = values(&data_manager)?;
+        let mc_values = values(&mc_manager)?;
+        rust::binned::BinnedChi2::from_values(
+            data_manager.0,
+            mc_manager.0,
+            edges,
+            &data_values,
+            &mc_values,
+        )
+        .map(Self)
+        .map_err(PyErr::from)
+    }
+    #[getter]
+    fn edges(&self) -> Vec<Vec<f64>> {
+        self.0.edges().to_vec()
+    }
+    #[getter]
+    fn n_bins(&self) -> usize {
+        self.0.n_bins()
+    }
+    #[getter]
+    fn n_filled(&self) -> usize {
+        self.0.n_filled()
+    }
+    #[getter]
+    fn counts(&self) -> Vec<f64> {
+        self.0.counts().to_vec()
+    }
+    #[getter]
+    fn bounds(&self) -> Vec<(f64, f64)> {
+        self.0.get_bounds()
+    }
+    #[getter]
+    fn initial(&self) -> Vec<f64> {
+        self.0.get_initial()
+    }
+    #[getter]
+    fn n_free(&self) -> usize {
+        self.0.get_n_free()
+    }
+    fn predicted(&self, parameters: Vec<f64>) -> PyResult<Vec<f64>> {
+        self.0.predicted(&parameters).map_err(PyErr::from)
+    }
+    #[pyo3(name = "__call__", signature = (parameters, *, parallel = true))]
+    fn call(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<f64> {
+        self.evaluate(parameters, parallel)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn evaluate(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<f64> {
+        if parallel {
+            self.0.par_evaluate(&parameters)
+        } else {
+            self.0.evaluate(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn gradient(&self, parameters: Vec<f64>, parallel: bool) -> PyResult<Vec<f64>> {
+        if parallel {
+            self.0.par_gradient(&parameters)
+        } else {
+            self.0.gradient(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct BinnedChi2_32(rust::binned::BinnedChi2<f32>);
+impl_convert!(BinnedChi2_32, rust::binned::BinnedChi2<f32>);
+
+#[pymethods]
+impl BinnedChi2_32 {
+    #[new]
+    fn new(
+        data_manager: Manager_32,
+        mc_manager: Manager_32,
+        edges: Vec<Vec<f32>>,
+        variables: Bound<'_, PyAny>,
+    ) -> PyResult<Self> {
+        let values = |manager: &Manager_32| -> PyResult<Vec<Vec<f32>>> {
+            manager
+                .0
+                .dataset
+                .events
+                .iter()
+                .map(|event| variables.call1((Event_32::from(event.clone()),))?.extract())
+                .collect()
+        };
+        let data_values = values(&data_manager)?;
+        let mc_values = values(&mc_manager)?;
+        rust::binned::BinnedChi2::from_values(
+            data_manager.0,
+            mc_manager.0,
+            edges,
+            &data_values,
+            &mc_values,
+        )
+        .map(Self)
+        .map_err(PyErr::from)
+    }
+    #[getter]
+    fn edges(&self) -> Vec<Vec<f32>> {
+        self.0.edges().to_vec()
+    }
+    #[getter]
+    fn n_bins(&self) -> usize {
+        self.0.n_bins()
+    }
+    #[getter]
+    fn n_filled(&self) -> usize {
+        self.0.n_filled()
+    }
+    #[getter]
+    fn counts(&self) -> Vec<f32> {
+        self.0.counts().to_vec()
+    }
+    #[getter]
+    fn bounds(&self) -> Vec<(f32, f32)> {
+        self.0.get_bounds()
+    }
+    #[getter]
+    fn initial(&self) -> Vec<f32> {
+        self.0.get_initial()
+    }
+    #[getter]
+    fn n_free(&self) -> usize {
+        self.0.get_n_free()
+    }
+    fn predicted(&self, parameters: Vec<f32>) -> PyResult<Vec<f32>> {
+        self.0.predicted(&parameters).map_err(PyErr::from)
+    }
+    #[pyo3(name = "__call__", signature = (parameters, *, parallel = true))]
+    fn call(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<f32> {
+        self.evaluate(parameters, parallel)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn evaluate(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<f32> {
+        if parallel {
+            self.0.par_evaluate(&parameters)
+        } else {
+            self.0.evaluate(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+    #[pyo3(signature = (parameters, *, parallel = true))]
+    fn gradient(&self, parameters: Vec<f32>, parallel: bool) -> PyResult<Vec<f32>> {
+        if parallel {
+            self.0.par_gradient(&parameters)
+        } else {
+            self.0.gradient(&parameters)
+        }
+        .map_err(PyErr::from)
+    }
+}
+
 pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Manager_64>()?;
     m.add_class::<Manager_32>()?;
@@ -1099,5 +3816,23 @@ pub fn pyo3_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<ExtendedLogLikelihood_32>()?;
     m.add_class::<NelderMead_64>()?;
     m.add_class::<NelderMead_32>()?;
+    m.add_class::<FitResult_64>()?;
+    m.add_class::<FitResult_32>()?;
+    m.add_class::<Ensemble_64>()?;
+    m.add_class::<Ensemble_32>()?;
+    m.add_class::<ToyStudyResult_64>()?;
+    m.add_class::<ToyStudyResult_32>()?;
+    m.add_class::<SimultaneousLikelihood_64>()?;
+    m.add_class::<SimultaneousLikelihood_32>()?;
+    m.add_class::<LinearLikelihood_64>()?;
+    m.add_class::<LinearLikelihood_32>()?;
+    m.add_class::<BinnedChi2_64>()?;
+    m.add_class::<BinnedChi2_32>()?;
+    m.add_function(wrap_pyfunction!(set_precalculation_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_precalculation_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(set_profiling, m)?)?;
+    m.add_function(wrap_pyfunction!(histogram, m)?)?;
+    m.add_function(wrap_pyfunction!(reset_profiling, m)?)?;
+    m.add_function(wrap_pyfunction!(profiling_report, m)?)?;
     Ok(())
 }
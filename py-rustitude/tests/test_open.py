@@ -0,0 +1,51 @@
+import pytest
+import uproot
+
+import rustitude
+
+ak = pytest.importorskip('awkward')
+
+DATA = {
+    'E_Beam': [8.7, 8.5, 8.9],
+    'Px_Beam': [0.0, 0.0, 0.0],
+    'Py_Beam': [0.0, 0.0, 0.0],
+    'Pz_Beam': [8.7, 8.5, 8.9],
+    'Weight': [1.0, 0.5, -0.25],
+    'EPS': [[0.38, 0.0, 0.0], [0.0, 0.38, 0.0], [0.27, 0.27, 0.0]],
+    'E_FinalState': [[1.10, 3.88, 4.65], [1.21, 3.43, 4.79], [1.05, 4.16, 4.62]],
+    'Px_FinalState': [[0.29, 0.21, -0.50], [-0.17, 0.44, -0.27], [0.08, -0.30, 0.22]],
+    'Py_FinalState': [[0.12, 0.15, -0.27], [0.31, -0.12, -0.19], [-0.20, 0.09, 0.11]],
+    'Pz_FinalState': [[0.25, 3.81, 4.64], [0.59, 3.36, 4.75], [0.12, 4.10, 4.68]],
+}
+
+
+def assert_same_events(dataset):
+    assert len(dataset) == len(DATA['Weight'])
+    for i, event in enumerate(dataset.events):
+        assert event.weight == pytest.approx(DATA['Weight'][i])
+        assert event.beam_p4.e == pytest.approx(DATA['E_Beam'][i])
+        assert list(event.eps) == pytest.approx(DATA['EPS'][i])
+        assert event.recoil_p4.e == pytest.approx(DATA['E_FinalState'][i][0])
+        assert [p4.pz for p4 in event.daughter_p4s] == pytest.approx(
+            DATA['Pz_FinalState'][i][1:]
+        )
+
+
+def test_open_ttree(tmp_path):
+    path = tmp_path / 'ttree.root'
+    with uproot.recreate(path) as f:
+        f['kin'] = ak.Array(DATA)
+    assert_same_events(rustitude.open(path))
+
+
+def test_open_rntuple(tmp_path):
+    if not hasattr(uproot.WritableDirectory, 'mkrntuple'):
+        pytest.skip('this version of uproot cannot write RNTuples')
+    path = tmp_path / 'rntuple.root'
+    with uproot.recreate(path) as f:
+        f.mkrntuple('kin', ak.Array(DATA))
+    assert_same_events(rustitude.open(path))
+    assert_same_events(rustitude.open(path, 'kin'))
+    # The Rust reader cannot read RNTuples, and says so
+    with pytest.raises(Exception, match='RNTuple'):
+        rustitude.Dataset_64.from_root(str(path))